@@ -0,0 +1,668 @@
+//! Hash chain and signed checkpoints for tamper evidence
+//!
+//! Every entry of a tenant gets a sequence number and a SHA-256 hash over
+//! its content and the hash of the previous entry, so changing, removing
+//! or reordering an entry breaks every later link. Rewriting the whole
+//! tail of the chain is caught by checkpoints: at a fixed interval the
+//! head hash is signed (HMAC-SHA256) with an organisation key that is not
+//! stored in the database. Checkpoints name their predecessor, so a
+//! removed checkpoint is detected as well; only the newest checkpoints can
+//! be dropped unnoticed, which is why they should also be exported.
+
+use chrono::{DateTime, SubsecRound, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt;
+use uuid::Uuid;
+
+use super::models::AuditEntry;
+
+/// Previous hash of the first entry of a chain
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Entries between two signed checkpoints
+pub const DEFAULT_CHECKPOINT_INTERVAL: u64 = 1000;
+
+/// Audit entry with its position in the tenant's hash chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainedAuditEntry {
+    #[serde(flatten)]
+    pub entry: AuditEntry,
+
+    /// Position in the chain, starting at 1
+    pub sequence: u64,
+
+    /// Hash of the previous entry, [`GENESIS_HASH`] for the first
+    pub previous_hash: String,
+
+    /// Hex SHA-256 over the entry content, sequence and previous hash
+    pub entry_hash: String,
+}
+
+impl ChainedAuditEntry {
+    /// Recompute the hash from the stored content
+    pub fn compute_hash(&self) -> String {
+        entry_hash(&self.entry, self.sequence, &self.previous_hash)
+    }
+}
+
+/// Hash of an entry at a position in the chain
+///
+/// The entry is serialized as a JSON object with sorted keys; the
+/// timestamp counts in whole microseconds, the precision PostgreSQL keeps.
+pub fn entry_hash(entry: &AuditEntry, sequence: u64, previous_hash: &str) -> String {
+    let canonical = serde_json::json!({
+        "id": entry.id,
+        "sequence": sequence,
+        "previous_hash": previous_hash,
+        "timestamp": entry.timestamp.timestamp_micros(),
+        "tenant_id": entry.tenant_id,
+        "user_did": entry.user_did,
+        "action": entry.action.name(),
+        "resource_type": entry.resource_type,
+        "resource_id": entry.resource_id,
+        "outcome": entry.outcome.as_str(),
+        "ip_address": entry.ip_address,
+        "user_agent": entry.user_agent,
+        "context": entry.context,
+        "session_id": entry.session_id,
+        "parent_id": entry.parent_id,
+    });
+    hex::encode(Sha256::digest(canonical.to_string().as_bytes()))
+}
+
+/// Link left behind when an entry is archived and purged
+///
+/// The content is gone from the database, but the hashes keep the chain
+/// intact; the archived entry must hash to `entry_hash`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedLink {
+    pub sequence: u64,
+    pub entry_id: Uuid,
+    pub previous_hash: String,
+    pub entry_hash: String,
+    /// Archive holding the entry
+    pub archive_id: Uuid,
+}
+
+/// Entry or archived link, in chain order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChainLink {
+    Entry(Box<ChainedAuditEntry>),
+    Archived(ArchivedLink),
+}
+
+impl ChainLink {
+    pub fn sequence(&self) -> u64 {
+        match self {
+            Self::Entry(e) => e.sequence,
+            Self::Archived(a) => a.sequence,
+        }
+    }
+}
+
+impl From<&ChainedAuditEntry> for ArchivedLink {
+    fn from(chained: &ChainedAuditEntry) -> Self {
+        Self {
+            sequence: chained.sequence,
+            entry_id: chained.entry.id,
+            previous_hash: chained.previous_hash.clone(),
+            entry_hash: chained.entry_hash.clone(),
+            archive_id: Uuid::nil(),
+        }
+    }
+}
+
+/// Last link of a tenant's chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainHead {
+    /// Sequence of the last entry, 0 for an empty chain
+    pub sequence: u64,
+    pub entry_hash: String,
+}
+
+impl ChainHead {
+    /// Head of an empty chain
+    pub fn genesis() -> Self {
+        Self { sequence: 0, entry_hash: GENESIS_HASH.to_string() }
+    }
+
+    /// Link an entry to the chain and advance the head
+    ///
+    /// The timestamp is truncated to microseconds so the stored entry
+    /// hashes to the same value.
+    pub fn append(&mut self, mut entry: AuditEntry) -> ChainedAuditEntry {
+        entry.timestamp = entry.timestamp.trunc_subsecs(6);
+        let sequence = self.sequence + 1;
+        let hash = entry_hash(&entry, sequence, &self.entry_hash);
+        let chained = ChainedAuditEntry {
+            entry,
+            sequence,
+            previous_hash: std::mem::replace(&mut self.entry_hash, hash.clone()),
+            entry_hash: hash,
+        };
+        self.sequence = sequence;
+        chained
+    }
+}
+
+impl Default for ChainHead {
+    fn default() -> Self {
+        Self::genesis()
+    }
+}
+
+/// Organisation key for signing checkpoints
+#[derive(Clone)]
+pub struct CheckpointKey {
+    /// Identifies the key, so old checkpoints verify after rotation
+    pub key_id: String,
+    secret: Vec<u8>,
+}
+
+impl CheckpointKey {
+    pub fn new(key_id: impl Into<String>, secret: impl Into<Vec<u8>>) -> Self {
+        Self { key_id: key_id.into(), secret: secret.into() }
+    }
+}
+
+impl fmt::Debug for CheckpointKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CheckpointKey").field("key_id", &self.key_id).finish_non_exhaustive()
+    }
+}
+
+/// Signed statement of the chain head at a sequence
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditCheckpoint {
+    pub id: Uuid,
+    pub tenant_id: String,
+    pub sequence: u64,
+    /// Hash of the entry at `sequence`
+    pub entry_hash: String,
+    /// Sequence of the preceding checkpoint, 0 for the first
+    pub previous_checkpoint: u64,
+    pub created_at: DateTime<Utc>,
+    pub key_id: String,
+    /// Hex HMAC-SHA256
+    pub signature: String,
+}
+
+impl AuditCheckpoint {
+    /// Sign the head of a chain
+    pub fn sign(
+        tenant_id: &str,
+        head: &ChainHead,
+        previous_checkpoint: u64,
+        key: &CheckpointKey,
+    ) -> Self {
+        let mut checkpoint = Self {
+            id: Uuid::new_v4(),
+            tenant_id: tenant_id.to_string(),
+            sequence: head.sequence,
+            entry_hash: head.entry_hash.clone(),
+            previous_checkpoint,
+            created_at: Utc::now().trunc_subsecs(6),
+            key_id: key.key_id.clone(),
+            signature: String::new(),
+        };
+        checkpoint.signature = hex::encode(checkpoint.mac(&key.secret).finalize().into_bytes());
+        checkpoint
+    }
+
+    /// Check the signature with `key`
+    pub fn verify(&self, key: &CheckpointKey) -> bool {
+        let Ok(signature) = hex::decode(&self.signature) else {
+            return false;
+        };
+        key.key_id == self.key_id && self.mac(&key.secret).verify_slice(&signature).is_ok()
+    }
+
+    fn mac(&self, secret: &[u8]) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+        let message = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            self.tenant_id,
+            self.sequence,
+            self.entry_hash,
+            self.previous_checkpoint,
+            self.created_at.timestamp_micros(),
+            self.key_id
+        );
+        mac.update(message.as_bytes());
+        mac
+    }
+}
+
+/// Why the chain is broken
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChainBreakKind {
+    /// Entries are missing before this one
+    SequenceGap { expected: u64, found: u64 },
+    /// The entry does not link to its predecessor
+    PreviousHashMismatch { expected: String, found: String },
+    /// The entry content was changed after it was written
+    ContentHashMismatch { stored: String, computed: String },
+    /// The chain was rewritten up to a signed checkpoint
+    CheckpointMismatch { signed: String, found: String },
+    /// The checkpoint was not signed with the named key
+    InvalidSignature { key_id: String },
+    /// The checkpoint was signed with a key that is not configured
+    UnknownKey { key_id: String },
+    /// The checkpoint before this one was removed
+    CheckpointMissing { expected_previous: u64, found_previous: u64 },
+    /// A checkpoint covers entries that no longer exist
+    EntriesMissing { last_sequence: u64 },
+}
+
+/// First broken link found by verification
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainBreak {
+    pub sequence: u64,
+    /// Entry at the break, if it exists
+    pub entry_id: Option<Uuid>,
+    pub kind: ChainBreakKind,
+}
+
+impl fmt::Display for ChainBreak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ChainBreakKind::SequenceGap { expected, found } => {
+                write!(f, "entries {} to {} are missing", expected, found - 1)
+            }
+            ChainBreakKind::PreviousHashMismatch { .. } => {
+                write!(f, "entry {} does not link to its predecessor", self.sequence)
+            }
+            ChainBreakKind::ContentHashMismatch { .. } => {
+                write!(f, "entry {} was modified", self.sequence)
+            }
+            ChainBreakKind::CheckpointMismatch { .. } => {
+                write!(f, "chain up to entry {} differs from the signed checkpoint", self.sequence)
+            }
+            ChainBreakKind::InvalidSignature { key_id } => {
+                write!(f, "checkpoint at entry {} has an invalid signature for key '{}'", self.sequence, key_id)
+            }
+            ChainBreakKind::UnknownKey { key_id } => {
+                write!(f, "checkpoint at entry {} is signed with unknown key '{}'", self.sequence, key_id)
+            }
+            ChainBreakKind::CheckpointMissing { expected_previous, .. } => {
+                write!(f, "checkpoint at entry {} is missing", expected_previous)
+            }
+            ChainBreakKind::EntriesMissing { last_sequence } => write!(
+                f,
+                "checkpoint at entry {} covers entries after the last entry {}",
+                self.sequence, last_sequence
+            ),
+        }
+    }
+}
+
+/// Result of walking a chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainVerification {
+    pub tenant_id: String,
+    pub entries_checked: u64,
+    /// Links of archived entries, checked for linkage only
+    #[serde(default)]
+    pub archived_links: u64,
+    pub checkpoints_checked: u64,
+    /// Head of the chain as far as it was intact
+    pub head: ChainHead,
+    pub first_break: Option<ChainBreak>,
+}
+
+impl ChainVerification {
+    pub fn is_intact(&self) -> bool {
+        self.first_break.is_none()
+    }
+}
+
+/// Walks a chain entry by entry, for chains too long to load at once
+///
+/// Entries must be pushed in sequence order. Verification stops at the
+/// first break; later entries are ignored.
+pub struct ChainVerifier<'a> {
+    tenant_id: String,
+    keys: &'a [CheckpointKey],
+    checkpoints: BTreeMap<u64, AuditCheckpoint>,
+    head: ChainHead,
+    last_checkpoint: u64,
+    entries_checked: u64,
+    archived_links: u64,
+    checkpoints_checked: u64,
+    first_break: Option<ChainBreak>,
+}
+
+impl<'a> ChainVerifier<'a> {
+    pub fn new(tenant_id: &str, checkpoints: Vec<AuditCheckpoint>, keys: &'a [CheckpointKey]) -> Self {
+        Self {
+            tenant_id: tenant_id.to_string(),
+            keys,
+            checkpoints: checkpoints.into_iter().map(|c| (c.sequence, c)).collect(),
+            head: ChainHead::genesis(),
+            last_checkpoint: 0,
+            entries_checked: 0,
+            archived_links: 0,
+            checkpoints_checked: 0,
+            first_break: None,
+        }
+    }
+
+    /// Check the next entry; false once the chain is broken
+    pub fn push(&mut self, chained: &ChainedAuditEntry) -> bool {
+        let computed = chained.compute_hash();
+        let content = (computed != chained.entry_hash).then(|| ChainBreakKind::ContentHashMismatch {
+            stored: chained.entry_hash.clone(),
+            computed,
+        });
+        let linked = self.link(
+            chained.sequence,
+            chained.entry.id,
+            &chained.previous_hash,
+            &chained.entry_hash,
+            content,
+        );
+        if linked {
+            self.entries_checked += 1;
+        }
+        linked
+    }
+
+    /// Check the link of an archived entry; its content is not available
+    pub fn push_archived(&mut self, link: &ArchivedLink) -> bool {
+        let linked = self.link(link.sequence, link.entry_id, &link.previous_hash, &link.entry_hash, None);
+        if linked {
+            self.archived_links += 1;
+        }
+        linked
+    }
+
+    pub fn push_link(&mut self, link: &ChainLink) -> bool {
+        match link {
+            ChainLink::Entry(entry) => self.push(entry),
+            ChainLink::Archived(archived) => self.push_archived(archived),
+        }
+    }
+
+    fn link(
+        &mut self,
+        sequence: u64,
+        entry_id: Uuid,
+        previous_hash: &str,
+        entry_hash: &str,
+        content: Option<ChainBreakKind>,
+    ) -> bool {
+        if self.first_break.is_some() {
+            return false;
+        }
+        let expected = self.head.sequence + 1;
+        let kind = if sequence != expected {
+            Some(ChainBreakKind::SequenceGap { expected, found: sequence })
+        } else if previous_hash != self.head.entry_hash {
+            Some(ChainBreakKind::PreviousHashMismatch {
+                expected: self.head.entry_hash.clone(),
+                found: previous_hash.to_string(),
+            })
+        } else {
+            content
+        };
+        if let Some(kind) = kind {
+            return self.broken(expected, Some(entry_id), kind);
+        }
+
+        self.head = ChainHead { sequence, entry_hash: entry_hash.to_string() };
+
+        if let Some(checkpoint) = self.checkpoints.remove(&sequence) {
+            if let Some(kind) = self.check_checkpoint(&checkpoint) {
+                return self.broken(sequence, Some(entry_id), kind);
+            }
+            if checkpoint.entry_hash != entry_hash {
+                let kind = ChainBreakKind::CheckpointMismatch {
+                    signed: checkpoint.entry_hash,
+                    found: entry_hash.to_string(),
+                };
+                return self.broken(sequence, Some(entry_id), kind);
+            }
+            self.last_checkpoint = checkpoint.sequence;
+            self.checkpoints_checked += 1;
+        }
+        true
+    }
+
+    /// Finish the walk; checkpoints past the last entry mean the tail was cut
+    pub fn finish(mut self) -> ChainVerification {
+        if self.first_break.is_none()
+            && let Some((sequence, checkpoint)) = self.checkpoints.pop_first()
+        {
+            let kind = self.check_checkpoint(&checkpoint).unwrap_or(ChainBreakKind::EntriesMissing {
+                last_sequence: self.head.sequence,
+            });
+            self.first_break = Some(ChainBreak { sequence, entry_id: None, kind });
+        }
+        ChainVerification {
+            tenant_id: self.tenant_id,
+            entries_checked: self.entries_checked,
+            archived_links: self.archived_links,
+            checkpoints_checked: self.checkpoints_checked,
+            head: self.head,
+            first_break: self.first_break,
+        }
+    }
+
+    fn check_checkpoint(&self, checkpoint: &AuditCheckpoint) -> Option<ChainBreakKind> {
+        let Some(key) = self.keys.iter().find(|k| k.key_id == checkpoint.key_id) else {
+            return Some(ChainBreakKind::UnknownKey { key_id: checkpoint.key_id.clone() });
+        };
+        if checkpoint.tenant_id != self.tenant_id || !checkpoint.verify(key) {
+            return Some(ChainBreakKind::InvalidSignature { key_id: checkpoint.key_id.clone() });
+        }
+        if checkpoint.previous_checkpoint != self.last_checkpoint {
+            return Some(ChainBreakKind::CheckpointMissing {
+                expected_previous: checkpoint.previous_checkpoint,
+                found_previous: self.last_checkpoint,
+            });
+        }
+        None
+    }
+
+    fn broken(&mut self, sequence: u64, entry_id: Option<Uuid>, kind: ChainBreakKind) -> bool {
+        self.first_break = Some(ChainBreak { sequence, entry_id, kind });
+        false
+    }
+}
+
+/// Verify a complete chain held in memory
+pub fn verify_chain(
+    tenant_id: &str,
+    entries: &[ChainedAuditEntry],
+    checkpoints: Vec<AuditCheckpoint>,
+    keys: &[CheckpointKey],
+) -> ChainVerification {
+    let mut verifier = ChainVerifier::new(tenant_id, checkpoints, keys);
+    for entry in entries {
+        if !verifier.push(entry) {
+            break;
+        }
+    }
+    verifier.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::models::AuditAction;
+
+    const TENANT: &str = "utrecht";
+
+    fn key() -> CheckpointKey {
+        CheckpointKey::new("org-2026", b"organisatiesleutel".to_vec())
+    }
+
+    /// Chain of `n` entries with a checkpoint every `interval` entries
+    fn chain(n: usize, interval: u64) -> (Vec<ChainedAuditEntry>, Vec<AuditCheckpoint>) {
+        let mut head = ChainHead::genesis();
+        let mut entries = Vec::new();
+        let mut checkpoints: Vec<AuditCheckpoint> = Vec::new();
+        for i in 0..n {
+            let entry = AuditEntry::new(
+                TENANT,
+                "did:example:user",
+                AuditAction::DocumentViewed,
+                "document",
+                &format!("doc-{}", i),
+            );
+            entries.push(head.append(entry));
+            if head.sequence.is_multiple_of(interval) {
+                let previous = checkpoints.last().map_or(0, |c| c.sequence);
+                checkpoints.push(AuditCheckpoint::sign(TENANT, &head, previous, &key()));
+            }
+        }
+        (entries, checkpoints)
+    }
+
+    #[test]
+    fn test_intact_chain_verifies() {
+        let (entries, checkpoints) = chain(10, 4);
+        let result = verify_chain(TENANT, &entries, checkpoints, &[key()]);
+        assert!(result.is_intact(), "{:?}", result.first_break);
+        assert_eq!(result.entries_checked, 10);
+        assert_eq!(result.checkpoints_checked, 2);
+        assert_eq!(result.head.sequence, 10);
+        assert_eq!(entries[0].previous_hash, GENESIS_HASH);
+    }
+
+    #[test]
+    fn test_modified_entry_is_reported() {
+        let (mut entries, checkpoints) = chain(10, 4);
+        entries[5].entry.user_did = "did:example:someone-else".to_string();
+        let result = verify_chain(TENANT, &entries, checkpoints, &[key()]);
+        let broken = result.first_break.unwrap();
+        assert_eq!(broken.sequence, 6);
+        assert_eq!(broken.entry_id, Some(entries[5].entry.id));
+        assert!(matches!(broken.kind, ChainBreakKind::ContentHashMismatch { .. }));
+        assert_eq!(result.entries_checked, 5);
+    }
+
+    #[test]
+    fn test_deleted_entry_is_reported() {
+        let (mut entries, checkpoints) = chain(10, 4);
+        entries.remove(2);
+        let broken = verify_chain(TENANT, &entries, checkpoints, &[key()]).first_break.unwrap();
+        assert_eq!(broken.sequence, 3);
+        assert_eq!(broken.kind, ChainBreakKind::SequenceGap { expected: 3, found: 4 });
+        assert_eq!(broken.to_string(), "entries 3 to 3 are missing");
+    }
+
+    #[test]
+    fn test_renumbered_chain_is_reported() {
+        // Delete an entry and renumber the rest without rehashing
+        let (mut entries, checkpoints) = chain(10, 4);
+        entries.remove(2);
+        for (i, e) in entries.iter_mut().enumerate() {
+            e.sequence = i as u64 + 1;
+        }
+        let broken = verify_chain(TENANT, &entries, checkpoints, &[key()]).first_break.unwrap();
+        assert_eq!(broken.sequence, 3);
+        assert!(matches!(broken.kind, ChainBreakKind::PreviousHashMismatch { .. }));
+    }
+
+    #[test]
+    fn test_rewritten_tail_is_caught_by_checkpoint() {
+        let (entries, checkpoints) = chain(10, 4);
+        // Rebuild a valid-looking chain with entry 3 left out
+        let mut head = ChainHead::genesis();
+        let forged: Vec<ChainedAuditEntry> = entries
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != 2)
+            .map(|(_, e)| head.append(e.entry.clone()))
+            .collect();
+        let broken = verify_chain(TENANT, &forged, checkpoints, &[key()]).first_break.unwrap();
+        assert_eq!(broken.sequence, 4);
+        assert!(matches!(broken.kind, ChainBreakKind::CheckpointMismatch { .. }));
+    }
+
+    #[test]
+    fn test_forged_checkpoint_is_reported() {
+        let (entries, mut checkpoints) = chain(10, 4);
+        let forger = CheckpointKey::new("org-2026", b"geraden".to_vec());
+        let head = ChainHead { sequence: 4, entry_hash: entries[3].entry_hash.clone() };
+        checkpoints[0] = AuditCheckpoint::sign(TENANT, &head, 0, &forger);
+        let broken = verify_chain(TENANT, &entries, checkpoints, &[key()]).first_break.unwrap();
+        assert_eq!(broken.kind, ChainBreakKind::InvalidSignature { key_id: "org-2026".to_string() });
+
+        let (entries, checkpoints) = chain(10, 4);
+        let other = CheckpointKey::new("org-2027", b"organisatiesleutel".to_vec());
+        let broken = verify_chain(TENANT, &entries, checkpoints, &[other]).first_break.unwrap();
+        assert_eq!(broken.kind, ChainBreakKind::UnknownKey { key_id: "org-2026".to_string() });
+    }
+
+    #[test]
+    fn test_removed_checkpoint_is_reported() {
+        let (entries, mut checkpoints) = chain(12, 4);
+        checkpoints.remove(1);
+        let broken = verify_chain(TENANT, &entries, checkpoints, &[key()]).first_break.unwrap();
+        assert_eq!(broken.sequence, 12);
+        assert_eq!(
+            broken.kind,
+            ChainBreakKind::CheckpointMissing { expected_previous: 8, found_previous: 4 }
+        );
+    }
+
+    #[test]
+    fn test_truncated_chain_is_reported() {
+        let (mut entries, checkpoints) = chain(10, 4);
+        entries.truncate(6);
+        let broken = verify_chain(TENANT, &entries, checkpoints, &[key()]).first_break.unwrap();
+        assert_eq!(broken.sequence, 8);
+        assert_eq!(broken.entry_id, None);
+        assert_eq!(broken.kind, ChainBreakKind::EntriesMissing { last_sequence: 6 });
+    }
+
+    #[test]
+    fn test_archived_links_keep_chain_intact() {
+        let (entries, checkpoints) = chain(10, 4);
+        let keys = [key()];
+        let walk = |links: &[ChainLink]| {
+            let mut verifier = ChainVerifier::new(TENANT, checkpoints.clone(), &keys);
+            for link in links {
+                if !verifier.push_link(link) {
+                    break;
+                }
+            }
+            verifier.finish()
+        };
+
+        let mut links: Vec<ChainLink> = entries
+            .iter()
+            .map(|e| match e.sequence {
+                1..=5 => ChainLink::Archived(ArchivedLink::from(e)),
+                _ => ChainLink::Entry(Box::new(e.clone())),
+            })
+            .collect();
+        let result = walk(&links);
+        assert!(result.is_intact(), "{:?}", result.first_break);
+        assert_eq!((result.archived_links, result.entries_checked), (5, 5));
+
+        // A forged hash on an archived link breaks the link after it
+        if let ChainLink::Archived(a) = &mut links[2] {
+            a.entry_hash = "f".repeat(64);
+        }
+        let broken = walk(&links).first_break.unwrap();
+        assert_eq!(broken.sequence, 4);
+        assert!(matches!(broken.kind, ChainBreakKind::PreviousHashMismatch { .. }));
+    }
+
+    #[test]
+    fn test_hash_ignores_sub_microsecond_precision() {
+        let mut head = ChainHead::genesis();
+        let entry = AuditEntry::new(TENANT, "did:example:user", AuditAction::UserLogin, "session", "s-1");
+        let chained = head.append(entry);
+        assert_eq!(chained.entry.timestamp.timestamp_subsec_nanos() % 1000, 0);
+        assert_eq!(chained.compute_hash(), chained.entry_hash);
+    }
+}
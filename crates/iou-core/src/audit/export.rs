@@ -0,0 +1,197 @@
+//! Export of audit ranges to JSON Lines and CSV
+//!
+//! Formats are line based, so exports can be streamed page by page: write
+//! [`AuditExportFormat::header`] once and then one line per entry.
+
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+
+use super::models::AuditEntry;
+
+/// Columns of the CSV export
+pub const CSV_COLUMNS: [&str; 13] = [
+    "id",
+    "timestamp",
+    "tenant_id",
+    "user_did",
+    "action",
+    "resource_type",
+    "resource_id",
+    "outcome",
+    "ip_address",
+    "user_agent",
+    "session_id",
+    "parent_id",
+    "context",
+];
+
+/// File format of an export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditExportFormat {
+    /// One JSON object per line
+    #[default]
+    #[serde(alias = "jsonl", alias = "ndjson")]
+    JsonLines,
+    /// RFC 4180, with the context as a JSON string
+    Csv,
+}
+
+impl AuditExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::JsonLines => "application/x-ndjson",
+            Self::Csv => "text/csv; charset=utf-8",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::JsonLines => "jsonl",
+            Self::Csv => "csv",
+        }
+    }
+
+    /// Text before the first entry
+    pub fn header(&self) -> String {
+        match self {
+            Self::JsonLines => String::new(),
+            Self::Csv => format!("{}\r\n", CSV_COLUMNS.join(",")),
+        }
+    }
+
+    /// One entry, including the line terminator
+    pub fn line(&self, entry: &AuditEntry) -> String {
+        match self {
+            Self::JsonLines => {
+                let mut line = serde_json::json!({
+                    "id": entry.id,
+                    "timestamp": entry.timestamp,
+                    "tenant_id": entry.tenant_id,
+                    "user_did": entry.user_did,
+                    "action": entry.action.name(),
+                    "resource_type": entry.resource_type,
+                    "resource_id": entry.resource_id,
+                    "outcome": entry.outcome.as_str(),
+                    "ip_address": entry.ip_address,
+                    "user_agent": entry.user_agent,
+                    "session_id": entry.session_id,
+                    "parent_id": entry.parent_id,
+                    "context": entry.context,
+                })
+                .to_string();
+                line.push('\n');
+                line
+            }
+            Self::Csv => {
+                let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+                let fields = [
+                    entry.id.to_string(),
+                    entry.timestamp.to_rfc3339(),
+                    entry.tenant_id.clone(),
+                    entry.user_did.clone(),
+                    entry.action.name(),
+                    entry.resource_type.clone(),
+                    entry.resource_id.clone(),
+                    entry.outcome.as_str().to_string(),
+                    optional(&entry.ip_address),
+                    optional(&entry.user_agent),
+                    optional(&entry.session_id),
+                    entry.parent_id.map(|id| id.to_string()).unwrap_or_default(),
+                    entry.context.as_ref().map(|c| c.to_string()).unwrap_or_default(),
+                ];
+                let mut line = fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(",");
+                line.push_str("\r\n");
+                line
+            }
+        }
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Writes an export to any [`Write`]
+pub struct AuditExportWriter<W: Write> {
+    writer: W,
+    format: AuditExportFormat,
+    entries: u64,
+}
+
+impl<W: Write> AuditExportWriter<W> {
+    /// Start an export; writes the header
+    pub fn new(mut writer: W, format: AuditExportFormat) -> io::Result<Self> {
+        writer.write_all(format.header().as_bytes())?;
+        Ok(Self { writer, format, entries: 0 })
+    }
+
+    pub fn write_entry(&mut self, entry: &AuditEntry) -> io::Result<()> {
+        self.writer.write_all(self.format.line(entry).as_bytes())?;
+        self.entries += 1;
+        Ok(())
+    }
+
+    /// Entries written so far
+    pub fn entries(&self) -> u64 {
+        self.entries
+    }
+
+    /// Flush and return the underlying writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::models::AuditAction;
+
+    fn entry() -> AuditEntry {
+        AuditEntry::new("utrecht", "did:example:user", AuditAction::DocumentViewed, "document", "doc-1")
+            .with_metadata(Some("10.0.0.1".to_string()), Some("Mozilla/5.0 (X11, Linux)".to_string()))
+            .with_context(serde_json::json!({"reden": "inzage \"dossier\""}))
+    }
+
+    #[test]
+    fn test_json_lines_export() {
+        let mut writer = AuditExportWriter::new(Vec::new(), AuditExportFormat::JsonLines).unwrap();
+        writer.write_entry(&entry()).unwrap();
+        writer.write_entry(&entry()).unwrap();
+        assert_eq!(writer.entries(), 2);
+
+        let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let parsed: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(parsed["action"], "document_viewed");
+        assert_eq!(parsed["context"]["reden"], "inzage \"dossier\"");
+    }
+
+    #[test]
+    fn test_csv_export_quotes_fields() {
+        let mut writer = AuditExportWriter::new(Vec::new(), AuditExportFormat::Csv).unwrap();
+        writer.write_entry(&entry()).unwrap();
+
+        let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+        let lines: Vec<&str> = output.split("\r\n").collect();
+        assert_eq!(lines[0], CSV_COLUMNS.join(","));
+        assert!(lines[1].contains(",document_viewed,document,doc-1,success,10.0.0.1,"));
+        assert!(lines[1].contains("\"Mozilla/5.0 (X11, Linux)\""));
+        assert!(lines[1].ends_with("\"{\"\"reden\"\":\"\"inzage \\\"\"dossier\\\"\"\"\"}\""));
+    }
+
+    #[test]
+    fn test_format_names() {
+        let format: AuditExportFormat = serde_json::from_str("\"jsonl\"").unwrap();
+        assert_eq!(format, AuditExportFormat::JsonLines);
+        let format: AuditExportFormat = serde_json::from_str("\"csv\"").unwrap();
+        assert_eq!(format.extension(), "csv");
+    }
+}
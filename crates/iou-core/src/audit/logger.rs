@@ -1,7 +1,19 @@
 //! Audit logger with write-ahead semantics
 
-use super::models::{AuditEntry, AuditAction, AuditOutcome, AuditQuery, AuditQueryResult};
-use sqlx::{PgPool, Row};
+use super::chain::{
+    ArchivedLink, AuditCheckpoint, ChainHead, ChainLink, ChainVerification, ChainVerifier,
+    ChainedAuditEntry, CheckpointKey, DEFAULT_CHECKPOINT_INTERVAL,
+};
+use super::export::{AuditExportFormat, AuditExportWriter};
+use super::models::{AuditEntry, AuditAction, AuditCursor, AuditOutcome, AuditQuery, AuditQueryResult};
+use super::retention::{
+    archive_checksum, encode_archive, ArchivedEntry, AuditArchive, MonthPartition, RetentionPolicy,
+    RetentionReport,
+};
+use crate::storage::StorageBackend;
+use chrono::{DateTime, Months, NaiveDate, Utc};
+use sqlx::{PgPool, Postgres, QueryBuilder, Row, postgres::PgRow};
+use std::io::Write;
 use std::sync::Arc;
 use thiserror::Error;
 use uuid::Uuid;
@@ -25,26 +37,94 @@ pub trait AuditBackend: Send + Sync {
 }
 
 /// PostgreSQL audit backend with BIO compliance
+///
+/// Entries are hash-chained per tenant (see [`super::chain`]). With a
+/// checkpoint key, every `checkpoint_interval` entries the chain head is
+/// signed and stored in `audit_checkpoints`.
 pub struct PostgresAuditBackend {
     pool: PgPool,
+    checkpoint_key: Option<CheckpointKey>,
+    verification_keys: Vec<CheckpointKey>,
+    checkpoint_interval: u64,
 }
 
+/// Entries fetched per round trip when walking a chain
+const CHAIN_BATCH_SIZE: i64 = 1000;
+
+/// Most entries in one retention archive
+const ARCHIVE_BATCH_SIZE: usize = 10_000;
+
 impl PostgresAuditBackend {
     /// Create a new PostgreSQL audit backend
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            checkpoint_key: None,
+            verification_keys: Vec::new(),
+            checkpoint_interval: DEFAULT_CHECKPOINT_INTERVAL,
+        }
+    }
+
+    /// Sign checkpoints with this organisation key
+    pub fn with_checkpoint_key(mut self, key: CheckpointKey) -> Self {
+        self.verification_keys.push(key.clone());
+        self.checkpoint_key = Some(key);
+        self
+    }
+
+    /// Accept checkpoints signed with a previous (rotated) key
+    pub fn with_verification_key(mut self, key: CheckpointKey) -> Self {
+        self.verification_keys.push(key);
+        self
+    }
+
+    /// Entries between two checkpoints (default 1000)
+    pub fn with_checkpoint_interval(mut self, interval: u64) -> Self {
+        self.checkpoint_interval = interval.max(1);
+        self
     }
 
     /// Initialize the audit table with proper constraints
+    ///
+    /// The table is partitioned by month on `timestamp`, with a default
+    /// partition for anything outside the created months. A table created
+    /// before partitioning is converted in place.
     pub async fn init(&self) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let kind: Option<String> = sqlx::query_scalar(
+            "SELECT relkind::text FROM pg_class WHERE oid = to_regclass('audit_log')"
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        // Plain table from before partitioning: move it aside, free its
+        // constraint and index names, copy the rows back below
+        let legacy = kind.as_deref() == Some("r");
+        if legacy {
+            sqlx::query(
+                r#"
+                ALTER TABLE audit_log RENAME TO audit_log_unpartitioned;
+                ALTER TABLE audit_log_unpartitioned DROP CONSTRAINT IF EXISTS audit_tenant_timestamp;
+                ALTER TABLE audit_log_unpartitioned DROP CONSTRAINT IF EXISTS audit_log_pkey CASCADE;
+                ALTER TABLE audit_log_unpartitioned ADD COLUMN IF NOT EXISTS sequence BIGINT;
+                ALTER TABLE audit_log_unpartitioned ADD COLUMN IF NOT EXISTS previous_hash VARCHAR(64);
+                DROP INDEX IF EXISTS idx_audit_tenant_timestamp, idx_audit_user_did, idx_audit_resource,
+                    idx_audit_parent, idx_audit_context, idx_audit_tenant_sequence;
+                "#
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
         // Create audit_log table
         sqlx::query(
             r#"
             CREATE TABLE IF NOT EXISTS audit_log (
-                -- Primary key
-                id UUID PRIMARY KEY,
+                -- Primary key (includes the partition key)
+                id UUID NOT NULL,
 
-                -- Timestamp (indexed for queries)
+                -- Timestamp (partition key, indexed for queries)
                 timestamp TIMESTAMPTZ NOT NULL DEFAULT NOW(),
 
                 -- Tenant isolation (required for multi-tenancy)
@@ -73,16 +153,61 @@ impl PostgresAuditBackend {
                 -- Session correlation
                 session_id VARCHAR(100),
 
-                -- Chained operations
-                parent_id UUID REFERENCES audit_log(id),
+                -- Chained operations (parents may be purged earlier)
+                parent_id UUID,
 
-                -- Tamper evidence (hash of entry)
+                -- Tamper evidence (hash chain, see audit::chain)
                 entry_hash VARCHAR(64),
+                sequence BIGINT,
+                previous_hash VARCHAR(64),
+
+                PRIMARY KEY (id, timestamp),
 
                 -- Index for tenant queries
                 CONSTRAINT audit_tenant_timestamp UNIQUE (tenant_id, timestamp, id)
-            );
+            ) PARTITION BY RANGE (timestamp);
+
+            CREATE TABLE IF NOT EXISTS audit_log_default PARTITION OF audit_log DEFAULT;
+            "#
+        )
+        .execute(&mut *tx)
+        .await?;
 
+        let today = Utc::now().date_naive();
+        let oldest: Option<DateTime<Utc>> = if legacy {
+            sqlx::query_scalar("SELECT MIN(timestamp) FROM audit_log_unpartitioned")
+                .fetch_one(&mut *tx)
+                .await?
+        } else {
+            None
+        };
+        let from = oldest.map_or(today, |t| t.date_naive().min(today));
+        create_partitions(&mut tx, from, today + Months::new(PARTITION_MONTHS_AHEAD)).await?;
+
+        if legacy {
+            sqlx::query(
+                r#"
+                INSERT INTO audit_log (
+                    id, timestamp, tenant_id, user_did, action,
+                    resource_type, resource_id, outcome,
+                    ip_address, user_agent, context, session_id, parent_id,
+                    entry_hash, sequence, previous_hash
+                )
+                SELECT id, timestamp, tenant_id, user_did, action,
+                       resource_type, resource_id, outcome,
+                       ip_address, user_agent, context, session_id, parent_id,
+                       entry_hash, sequence, previous_hash
+                FROM audit_log_unpartitioned;
+
+                DROP TABLE audit_log_unpartitioned;
+                "#
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query(
+            r#"
             -- Indexes for efficient querying
             CREATE INDEX IF NOT EXISTS idx_audit_tenant_timestamp
                 ON audit_log(tenant_id, timestamp DESC);
@@ -99,86 +224,214 @@ impl PostgresAuditBackend {
             CREATE INDEX IF NOT EXISTS idx_audit_context
                 ON audit_log USING GIN (context);
 
+            -- Unique per tenant by construction (appends lock the chain head)
+            CREATE INDEX IF NOT EXISTS idx_audit_tenant_sequence
+                ON audit_log(tenant_id, sequence) WHERE sequence IS NOT NULL;
+
             -- Comment for documentation
             COMMENT ON TABLE audit_log IS
-                'Tamper-evident audit log with 7-year retention (BIO compliant)';
-            "#
-        )
-        .execute(&self.pool)
-        .await?;
+                'Tamper-evident audit log, partitioned by month, retention per action (BIO compliant)';
 
-        // Create audit function for trigger (if needed)
-        sqlx::query(
-            r#"
-            CREATE OR REPLACE FUNCTION audit_insert_trigger()
-            RETURNS TRIGGER AS $$
-            BEGIN
-                -- Compute entry hash for tamper evidence
-                NEW.entry_hash = encode(
-                    digest(
-                        NEW.id::TEXT ||
-                        NEW.timestamp::TEXT ||
-                        NEW.tenant_id ||
-                        NEW.user_did ||
-                        NEW.action ||
-                        NEW.resource_type ||
-                        NEW.resource_id ||
-                        NEW.outcome::TEXT,
-                        'sha256'
-                    ),
-                    'hex'
-                );
-                RETURN NEW;
-            END;
-            $$ LANGUAGE plpgsql;
+            -- Last link per tenant; locked while appending
+            CREATE TABLE IF NOT EXISTS audit_chain_heads (
+                tenant_id VARCHAR(50) PRIMARY KEY,
+                sequence BIGINT NOT NULL,
+                entry_hash VARCHAR(64) NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS audit_checkpoints (
+                id UUID PRIMARY KEY,
+                tenant_id VARCHAR(50) NOT NULL,
+                sequence BIGINT NOT NULL,
+                entry_hash VARCHAR(64) NOT NULL,
+                previous_checkpoint BIGINT NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL,
+                key_id VARCHAR(100) NOT NULL,
+                signature VARCHAR(64) NOT NULL,  -- Hex HMAC-SHA256
+                CONSTRAINT audit_checkpoint_tenant_sequence UNIQUE (tenant_id, sequence)
+            );
+
+            -- Archives written to object storage before a purge
+            CREATE TABLE IF NOT EXISTS audit_archives (
+                id UUID PRIMARY KEY,
+                tenant_id VARCHAR(50) NOT NULL,
+                key TEXT NOT NULL,
+                sha256 VARCHAR(64) NOT NULL,
+                entries BIGINT NOT NULL,
+                first_timestamp TIMESTAMPTZ NOT NULL,
+                last_timestamp TIMESTAMPTZ NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL
+            );
+
+            -- Chain links of purged entries
+            CREATE TABLE IF NOT EXISTS audit_archived_links (
+                tenant_id VARCHAR(50) NOT NULL,
+                sequence BIGINT NOT NULL,
+                entry_id UUID NOT NULL,
+                previous_hash VARCHAR(64) NOT NULL,
+                entry_hash VARCHAR(64) NOT NULL,
+                archive_id UUID NOT NULL REFERENCES audit_archives(id),
+                PRIMARY KEY (tenant_id, sequence)
+            );
+
+            -- Entry hashes are computed by the application (audit::chain)
+            DROP FUNCTION IF EXISTS audit_insert_trigger();
             "#
         )
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
 
-        Ok(())
+        tx.commit().await
+    }
+
+    /// Create monthly partitions from this month up to `through`
+    pub async fn ensure_partitions(&self, through: NaiveDate) -> Result<Vec<String>, AuditError> {
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        let created = create_partitions(&mut tx, Utc::now().date_naive(), through)
+            .await
+            .map_err(db_error)?;
+        tx.commit().await.map_err(db_error)?;
+        Ok(created)
+    }
+}
+
+/// Months of partitions created ahead of time
+const PARTITION_MONTHS_AHEAD: u32 = 3;
+
+fn db_error(e: sqlx::Error) -> AuditError {
+    AuditError::Database(e.to_string())
+}
+
+/// Create missing partitions, returning their names
+///
+/// A month whose rows already went to the default partition cannot be
+/// created; partitions are therefore made well ahead of time.
+async fn create_partitions(
+    conn: &mut sqlx::PgConnection,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<String>, sqlx::Error> {
+    let mut created = Vec::new();
+    for partition in MonthPartition::between(from, to) {
+        let exists: bool = sqlx::query_scalar("SELECT to_regclass($1) IS NOT NULL")
+            .bind(&partition.name)
+            .fetch_one(&mut *conn)
+            .await?;
+        if !exists {
+            sqlx::query(&partition.create_sql()).execute(&mut *conn).await?;
+            created.push(partition.name);
+        }
     }
+    Ok(created)
 }
 
 #[async_trait::async_trait]
 impl AuditBackend for PostgresAuditBackend {
     async fn write(&self, entry: &AuditEntry) -> Result<(), AuditError> {
-        let action = match &entry.action {
-            AuditAction::Custom(s) => s.clone(),
-            a => serde_json::to_string(a).unwrap_or_else(|_| "custom".to_string()),
+        let db = |e: sqlx::Error| AuditError::Database(e.to_string());
+        let mut tx = self.pool.begin().await.map_err(db)?;
+
+        // Lock the tenant's chain head so appends are serialized
+        sqlx::query(
+            r#"
+            INSERT INTO audit_chain_heads (tenant_id, sequence, entry_hash)
+            VALUES ($1, 0, $2)
+            ON CONFLICT (tenant_id) DO NOTHING
+            "#
+        )
+        .bind(&entry.tenant_id)
+        .bind(ChainHead::genesis().entry_hash)
+        .execute(&mut *tx)
+        .await
+        .map_err(db)?;
+
+        let row = sqlx::query(
+            "SELECT sequence, entry_hash FROM audit_chain_heads WHERE tenant_id = $1 FOR UPDATE"
+        )
+        .bind(&entry.tenant_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(db)?;
+
+        let mut head = ChainHead {
+            sequence: row.get::<i64, _>("sequence") as u64,
+            entry_hash: row.get("entry_hash"),
         };
+        let chained = head.append(entry.clone());
+        let entry = &chained.entry;
 
         sqlx::query(
             r#"
             INSERT INTO audit_log (
                 id, timestamp, tenant_id, user_did, action,
                 resource_type, resource_id, outcome,
-                ip_address, user_agent, context, session_id, parent_id
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+                ip_address, user_agent, context, session_id, parent_id,
+                sequence, previous_hash, entry_hash
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
             "#
         )
         .bind(entry.id)
         .bind(entry.timestamp)
         .bind(&entry.tenant_id)
         .bind(&entry.user_did)
-        .bind(&action)
+        .bind(entry.action.name())
         .bind(&entry.resource_type)
         .bind(&entry.resource_id)
-        .bind(match entry.outcome {
-            AuditOutcome::Success => "success",
-            AuditOutcome::Failed => "failed",
-            AuditOutcome::Denied => "denied",
-            AuditOutcome::Error => "error",
-        })
+        .bind(entry.outcome.as_str())
         .bind(&entry.ip_address)
         .bind(&entry.user_agent)
         .bind(&entry.context)
         .bind(&entry.session_id)
         .bind(entry.parent_id)
-        .execute(&self.pool)
+        .bind(chained.sequence as i64)
+        .bind(&chained.previous_hash)
+        .bind(&chained.entry_hash)
+        .execute(&mut *tx)
         .await
-        .map_err(|e| AuditError::Database(e.to_string()))?;
+        .map_err(db)?;
+
+        sqlx::query("UPDATE audit_chain_heads SET sequence = $1, entry_hash = $2 WHERE tenant_id = $3")
+            .bind(head.sequence as i64)
+            .bind(&head.entry_hash)
+            .bind(&entry.tenant_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db)?;
+
+        if let Some(key) = &self.checkpoint_key
+            && head.sequence.is_multiple_of(self.checkpoint_interval)
+        {
+            let previous: Option<i64> = sqlx::query_scalar(
+                "SELECT MAX(sequence) FROM audit_checkpoints WHERE tenant_id = $1"
+            )
+            .bind(&entry.tenant_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(db)?;
+
+            let checkpoint = AuditCheckpoint::sign(&entry.tenant_id, &head, previous.unwrap_or(0) as u64, key);
+            sqlx::query(
+                r#"
+                INSERT INTO audit_checkpoints (
+                    id, tenant_id, sequence, entry_hash, previous_checkpoint,
+                    created_at, key_id, signature
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                "#
+            )
+            .bind(checkpoint.id)
+            .bind(&checkpoint.tenant_id)
+            .bind(checkpoint.sequence as i64)
+            .bind(&checkpoint.entry_hash)
+            .bind(checkpoint.previous_checkpoint as i64)
+            .bind(checkpoint.created_at)
+            .bind(&checkpoint.key_id)
+            .bind(&checkpoint.signature)
+            .execute(&mut *tx)
+            .await
+            .map_err(db)?;
+        }
 
+        tx.commit().await.map_err(db)?;
         Ok(())
     }
 
@@ -187,71 +440,60 @@ impl AuditBackend for PostgresAuditBackend {
         tenant_id: &str,
         query: &AuditQuery,
     ) -> Result<AuditQueryResult, AuditError> {
-        let limit = i64::from(query.limit.min(1000)); // Max 1000 per query
-
-        // Build dynamic query (simplified - in production use query builder)
-        let sql = r#"
-            SELECT id, timestamp, tenant_id, user_did, action,
-                   resource_type, resource_id, outcome,
-                   ip_address, user_agent, context, session_id, parent_id
-            FROM audit_log
-            WHERE tenant_id = $1
-            ORDER BY timestamp DESC
-            LIMIT $2
-        "#;
-
-        // Count total
-        let count_sql = r#"
-            SELECT COUNT(*) as total FROM audit_log WHERE tenant_id = $1
-        "#;
+        let limit = i64::from(query.limit.clamp(1, 1000)); // Max 1000 per query
+        let cursor = query
+            .cursor
+            .as_deref()
+            .map(|c| AuditCursor::decode(c).ok_or_else(|| AuditError::InvalidCursor(c.to_string())))
+            .transpose()?;
 
-        let total_count = sqlx::query_scalar::<_, i64>(count_sql)
-            .bind(tenant_id)
+        // Count total (all pages)
+        let mut count = QueryBuilder::new("SELECT COUNT(*) FROM audit_log");
+        push_filters(&mut count, tenant_id, query);
+        let total_count: i64 = count
+            .build_query_scalar()
             .fetch_one(&self.pool)
             .await
-            .unwrap_or(0);
+            .map_err(db_error)?;
 
-        // Fetch entries
-        let rows = sqlx::query(sql)
-            .bind(tenant_id)
-            .bind(limit)
-            .fetch_all(&self.pool)
-            .await
-            .map_err(|e| AuditError::Database(e.to_string()))?;
-
-        let entries: Vec<AuditEntry> = rows.into_iter().map(|row| {
-            let action_str: String = row.get("action");
-            let outcome_str: String = row.get("outcome");
+        // Fetch one extra entry to know whether another page follows
+        let mut select = QueryBuilder::new(
+            r#"
+            SELECT id, timestamp, tenant_id, user_did, action,
+                   resource_type, resource_id, outcome,
+                   ip_address, user_agent, context, session_id, parent_id
+            FROM audit_log"#,
+        );
+        push_filters(&mut select, tenant_id, query);
+        let (after, order) = if query.oldest_first { (">", "ASC") } else { ("<", "DESC") };
+        if let Some(cursor) = cursor {
+            select
+                .push(format!(" AND (timestamp, id) {after} ("))
+                .push_bind(cursor.timestamp)
+                .push(", ")
+                .push_bind(cursor.id)
+                .push(")");
+        }
+        select
+            .push(format!(" ORDER BY timestamp {order}, id {order} LIMIT "))
+            .push_bind(limit + 1);
 
-            AuditEntry {
-                id: row.get("id"),
-                timestamp: row.get("timestamp"),
-                tenant_id: row.get("tenant_id"),
-                user_did: row.get("user_did"),
-                action: AuditAction::from(action_str),
-                resource_type: row.get("resource_type"),
-                resource_id: row.get("resource_id"),
-                outcome: match outcome_str.as_str() {
-                    "success" => AuditOutcome::Success,
-                    "failed" => AuditOutcome::Failed,
-                    "denied" => AuditOutcome::Denied,
-                    _ => AuditOutcome::Error,
-                },
-                ip_address: row.get("ip_address"),
-                user_agent: row.get("user_agent"),
-                context: row.get("context"),
-                session_id: row.get("session_id"),
-                parent_id: row.get("parent_id"),
-            }
-        }).collect();
+        let rows = select.build().fetch_all(&self.pool).await.map_err(db_error)?;
 
-        let entries_len = entries.len();
-        let total_count_u64 = total_count as u64;
+        let mut entries: Vec<AuditEntry> = rows.iter().map(entry_from_row).collect();
+        let has_more = entries.len() as i64 > limit;
+        entries.truncate(limit as usize);
+        let next_cursor = if has_more {
+            entries.last().map(|entry| AuditCursor::of(entry).encode())
+        } else {
+            None
+        };
 
         Ok(AuditQueryResult {
             entries,
-            total_count: total_count_u64,
-            has_more: (entries_len as u64) < total_count_u64,
+            total_count: total_count as u64,
+            has_more,
+            next_cursor,
         })
     }
 
@@ -269,31 +511,452 @@ impl AuditBackend for PostgresAuditBackend {
         .await
         .map_err(|e| AuditError::Database(e.to_string()))?;
 
-        Ok(row.map(|row| {
-            let action_str: String = row.get("action");
-            let outcome_str: String = row.get("outcome");
+        Ok(row.as_ref().map(entry_from_row))
+    }
+}
+
+/// `WHERE` clause of [`AuditQuery`], without the cursor
+fn push_filters(builder: &mut QueryBuilder<'_, Postgres>, tenant_id: &str, query: &AuditQuery) {
+    builder.push(" WHERE tenant_id = ").push_bind(tenant_id.to_string());
+    if let Some(start) = query.start_timestamp {
+        builder.push(" AND timestamp >= ").push_bind(start);
+    }
+    if let Some(end) = query.end_timestamp {
+        builder.push(" AND timestamp < ").push_bind(end);
+    }
+    if let Some(user_did) = &query.user_did {
+        builder.push(" AND user_did = ").push_bind(user_did.clone());
+    }
+    if let Some(action) = &query.action {
+        builder.push(" AND action = ").push_bind(action.name());
+    }
+    if let Some(resource_type) = &query.resource_type {
+        builder.push(" AND resource_type = ").push_bind(resource_type.clone());
+    }
+}
+
+fn entry_from_row(row: &PgRow) -> AuditEntry {
+    let action_str: String = row.get("action");
+    let outcome_str: String = row.get("outcome");
+
+    AuditEntry {
+        id: row.get("id"),
+        timestamp: row.get("timestamp"),
+        tenant_id: row.get("tenant_id"),
+        user_did: row.get("user_did"),
+        action: AuditAction::from(action_str),
+        resource_type: row.get("resource_type"),
+        resource_id: row.get("resource_id"),
+        outcome: AuditOutcome::from(outcome_str.as_str()),
+        ip_address: row.get("ip_address"),
+        user_agent: row.get("user_agent"),
+        context: row.get("context"),
+        session_id: row.get("session_id"),
+        parent_id: row.get("parent_id"),
+    }
+}
+
+impl PostgresAuditBackend {
+    /// Chained entries of a tenant after `after_sequence`, in chain order
+    ///
+    /// Entries written before chaining was introduced have no sequence and
+    /// are not returned.
+    pub async fn chain_entries(
+        &self,
+        tenant_id: &str,
+        after_sequence: u64,
+        limit: i64,
+    ) -> Result<Vec<ChainedAuditEntry>, AuditError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, timestamp, tenant_id, user_did, action,
+                   resource_type, resource_id, outcome,
+                   ip_address, user_agent, context, session_id, parent_id,
+                   sequence, previous_hash, entry_hash
+            FROM audit_log
+            WHERE tenant_id = $1 AND sequence > $2
+            ORDER BY sequence ASC
+            LIMIT $3
+            "#
+        )
+        .bind(tenant_id)
+        .bind(after_sequence as i64)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AuditError::Database(e.to_string()))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| ChainedAuditEntry {
+                entry: entry_from_row(row),
+                sequence: row.get::<i64, _>("sequence") as u64,
+                previous_hash: row.get("previous_hash"),
+                entry_hash: row.get("entry_hash"),
+            })
+            .collect())
+    }
+
+    /// Links of purged entries of a tenant after `after_sequence`
+    pub async fn archived_links(
+        &self,
+        tenant_id: &str,
+        after_sequence: u64,
+        limit: i64,
+    ) -> Result<Vec<ArchivedLink>, AuditError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT sequence, entry_id, previous_hash, entry_hash, archive_id
+            FROM audit_archived_links
+            WHERE tenant_id = $1 AND sequence > $2
+            ORDER BY sequence ASC
+            LIMIT $3
+            "#
+        )
+        .bind(tenant_id)
+        .bind(after_sequence as i64)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(rows
+            .iter()
+            .map(|row| ArchivedLink {
+                sequence: row.get::<i64, _>("sequence") as u64,
+                entry_id: row.get("entry_id"),
+                previous_hash: row.get("previous_hash"),
+                entry_hash: row.get("entry_hash"),
+                archive_id: row.get("archive_id"),
+            })
+            .collect())
+    }
+
+    /// Stored entries and archived links after `after_sequence`, in chain order
+    pub async fn chain_links(
+        &self,
+        tenant_id: &str,
+        after_sequence: u64,
+        limit: i64,
+    ) -> Result<Vec<ChainLink>, AuditError> {
+        let entries = self.chain_entries(tenant_id, after_sequence, limit).await?;
+        let archived = self.archived_links(tenant_id, after_sequence, limit).await?;
+
+        // A full batch may continue past its last link: only merge up to
+        // the point where both batches are complete
+        let full = |last: Option<u64>, len: usize| last.filter(|_| len as i64 == limit);
+        let bound = [
+            full(entries.last().map(|e| e.sequence), entries.len()),
+            full(archived.last().map(|a| a.sequence), archived.len()),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+        .unwrap_or(u64::MAX);
+
+        let mut links: Vec<ChainLink> = entries
+            .into_iter()
+            .map(|entry| ChainLink::Entry(Box::new(entry)))
+            .chain(archived.into_iter().map(ChainLink::Archived))
+            .filter(|link| link.sequence() <= bound)
+            .collect();
+        links.sort_by_key(ChainLink::sequence);
+        Ok(links)
+    }
+
+    /// Signed checkpoints of a tenant in sequence order
+    pub async fn checkpoints(&self, tenant_id: &str) -> Result<Vec<AuditCheckpoint>, AuditError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, tenant_id, sequence, entry_hash, previous_checkpoint,
+                   created_at, key_id, signature
+            FROM audit_checkpoints
+            WHERE tenant_id = $1
+            ORDER BY sequence ASC
+            "#
+        )
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| AuditError::Database(e.to_string()))?;
 
-            AuditEntry {
+        Ok(rows
+            .iter()
+            .map(|row| AuditCheckpoint {
                 id: row.get("id"),
-                timestamp: row.get("timestamp"),
                 tenant_id: row.get("tenant_id"),
-                user_did: row.get("user_did"),
-                action: AuditAction::from(action_str),
-                resource_type: row.get("resource_type"),
-                resource_id: row.get("resource_id"),
-                outcome: match outcome_str.as_str() {
-                    "success" => AuditOutcome::Success,
-                    "failed" => AuditOutcome::Failed,
-                    "denied" => AuditOutcome::Denied,
-                    _ => AuditOutcome::Error,
-                },
-                ip_address: row.get("ip_address"),
-                user_agent: row.get("user_agent"),
-                context: row.get("context"),
-                session_id: row.get("session_id"),
-                parent_id: row.get("parent_id"),
+                sequence: row.get::<i64, _>("sequence") as u64,
+                entry_hash: row.get("entry_hash"),
+                previous_checkpoint: row.get::<i64, _>("previous_checkpoint") as u64,
+                created_at: row.get("created_at"),
+                key_id: row.get("key_id"),
+                signature: row.get("signature"),
+            })
+            .collect())
+    }
+
+    /// Walk a tenant's chain and report the first broken link
+    pub async fn verify_chain(&self, tenant_id: &str) -> Result<ChainVerification, AuditError> {
+        let checkpoints = self.checkpoints(tenant_id).await?;
+        let mut verifier = ChainVerifier::new(tenant_id, checkpoints, &self.verification_keys);
+
+        let mut after = 0;
+        loop {
+            let batch = self.chain_links(tenant_id, after, CHAIN_BATCH_SIZE).await?;
+            let Some(last) = batch.last() else {
+                break;
+            };
+            after = last.sequence();
+            if !batch.iter().all(|link| verifier.push_link(link)) {
+                break;
+            }
+        }
+
+        Ok(verifier.finish())
+    }
+
+    /// Archive and purge a tenant's expired entries
+    ///
+    /// Expired entries are archived per month to `storage`, read back and
+    /// checked, and only then deleted; chained entries leave their link in
+    /// `audit_archived_links`. Afterwards partitions are created for the
+    /// coming months and empty partitions past every retention period are
+    /// dropped.
+    pub async fn apply_retention(
+        &self,
+        tenant_id: &str,
+        policy: &RetentionPolicy,
+        storage: &dyn StorageBackend,
+        now: DateTime<Utc>,
+    ) -> Result<RetentionReport, AuditError> {
+        let mut report = RetentionReport {
+            tenant_id: tenant_id.to_string(),
+            ..Default::default()
+        };
+
+        let cutoff = policy.earliest_cutoff(now);
+        let mut cursor: Option<AuditCursor> = None;
+        let mut batch: Option<(MonthPartition, Vec<ArchivedEntry>)> = None;
+        loop {
+            let rows = sqlx::query(
+                r#"
+                SELECT id, timestamp, tenant_id, user_did, action,
+                       resource_type, resource_id, outcome,
+                       ip_address, user_agent, context, session_id, parent_id,
+                       sequence, previous_hash, entry_hash
+                FROM audit_log
+                WHERE tenant_id = $1 AND timestamp < $2
+                  AND ($3::timestamptz IS NULL OR (timestamp, id) > ($3, $4))
+                ORDER BY timestamp ASC, id ASC
+                LIMIT $5
+                "#
+            )
+            .bind(tenant_id)
+            .bind(cutoff)
+            .bind(cursor.map(|c| c.timestamp))
+            .bind(cursor.map(|c| c.id))
+            .bind(CHAIN_BATCH_SIZE)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+            let Some(last) = rows.last() else {
+                break;
+            };
+            cursor = Some(AuditCursor::of(&entry_from_row(last)));
+
+            for row in &rows {
+                let archived = ArchivedEntry {
+                    entry: entry_from_row(row),
+                    sequence: row.get::<Option<i64>, _>("sequence").map(|s| s as u64),
+                    previous_hash: row.get("previous_hash"),
+                    entry_hash: row.get("entry_hash"),
+                };
+                if !policy.is_expired(&archived.entry, now) {
+                    continue;
+                }
+
+                let month = MonthPartition::containing(archived.entry.timestamp.date_naive());
+                let full = batch.as_ref().is_some_and(|(current, entries)| {
+                    current != &month || entries.len() >= ARCHIVE_BATCH_SIZE
+                });
+                if full && let Some((current, entries)) = batch.take() {
+                    self.archive_and_purge(tenant_id, &current, &entries, storage, &mut report).await?;
+                }
+                batch.get_or_insert_with(|| (month, Vec::new())).1.push(archived);
+            }
+        }
+        if let Some((month, entries)) = batch {
+            self.archive_and_purge(tenant_id, &month, &entries, storage, &mut report).await?;
+        }
+
+        let ahead = now.date_naive() + Months::new(PARTITION_MONTHS_AHEAD);
+        report.partitions_created = self.ensure_partitions(ahead).await?;
+        report.partitions_dropped = self.drop_expired_partitions(policy, now).await?;
+        Ok(report)
+    }
+
+    /// Write one archive, verify it and delete its entries
+    async fn archive_and_purge(
+        &self,
+        tenant_id: &str,
+        month: &MonthPartition,
+        entries: &[ArchivedEntry],
+        storage: &dyn StorageBackend,
+        report: &mut RetentionReport,
+    ) -> Result<(), AuditError> {
+        let (Some(first), Some(last)) = (entries.first(), entries.last()) else {
+            return Ok(());
+        };
+
+        let id = Uuid::new_v4();
+        let key = AuditArchive::key_for(tenant_id, month, id);
+        let data = encode_archive(entries).map_err(|e| AuditError::Serialization(e.to_string()))?;
+        let sha256 = archive_checksum(&data);
+
+        storage
+            .upload(&key, data, "application/gzip")
+            .await
+            .map_err(|e| AuditError::Archive(e.to_string()))?;
+
+        // Never purge what cannot be read back
+        let stored = storage
+            .download(&key)
+            .await
+            .map_err(|e| AuditError::Archive(e.to_string()))?;
+        if archive_checksum(&stored) != sha256 {
+            return Err(AuditError::Archive(format!("checksum mismatch for {key}")));
+        }
+
+        let archive = AuditArchive {
+            id,
+            tenant_id: tenant_id.to_string(),
+            key,
+            sha256,
+            entries: entries.len() as u64,
+            first_timestamp: first.entry.timestamp,
+            last_timestamp: last.entry.timestamp,
+            created_at: Utc::now(),
+        };
+        let links: Vec<ChainedAuditEntry> = entries.iter().filter_map(ArchivedEntry::chained).collect();
+        let ids: Vec<Uuid> = entries.iter().map(|e| e.entry.id).collect();
+
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO audit_archives (
+                id, tenant_id, key, sha256, entries,
+                first_timestamp, last_timestamp, created_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#
+        )
+        .bind(archive.id)
+        .bind(&archive.tenant_id)
+        .bind(&archive.key)
+        .bind(&archive.sha256)
+        .bind(archive.entries as i64)
+        .bind(archive.first_timestamp)
+        .bind(archive.last_timestamp)
+        .bind(archive.created_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO audit_archived_links (
+                tenant_id, sequence, entry_id, previous_hash, entry_hash, archive_id
+            )
+            SELECT $1, sequence, entry_id, previous_hash, entry_hash, $6
+            FROM UNNEST($2::bigint[], $3::uuid[], $4::varchar[], $5::varchar[])
+                AS link(sequence, entry_id, previous_hash, entry_hash)
+            "#
+        )
+        .bind(tenant_id)
+        .bind(links.iter().map(|l| l.sequence as i64).collect::<Vec<_>>())
+        .bind(links.iter().map(|l| l.entry.id).collect::<Vec<_>>())
+        .bind(links.iter().map(|l| l.previous_hash.clone()).collect::<Vec<_>>())
+        .bind(links.iter().map(|l| l.entry_hash.clone()).collect::<Vec<_>>())
+        .bind(archive.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+        let purged = sqlx::query(
+            r#"
+            DELETE FROM audit_log
+            WHERE tenant_id = $1 AND id = ANY($2)
+              AND timestamp >= $3 AND timestamp <= $4
+            "#
+        )
+        .bind(tenant_id)
+        .bind(&ids)
+        .bind(archive.first_timestamp)
+        .bind(archive.last_timestamp)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?
+        .rows_affected();
+
+        tx.commit().await.map_err(db_error)?;
+
+        tracing::info!(
+            "Archived {} audit entries of tenant {} to {}",
+            archive.entries,
+            tenant_id,
+            archive.key
+        );
+        report.archived += archive.entries;
+        report.purged += purged;
+        report.archives.push(archive.key);
+        Ok(())
+    }
+
+    /// Drop empty monthly partitions past every retention period
+    ///
+    /// Partitions are shared by all tenants, so one is only dropped once
+    /// every tenant's entries in it have been purged.
+    pub async fn drop_expired_partitions(
+        &self,
+        policy: &RetentionPolicy,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<String>, AuditError> {
+        let names: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT c.relname::text
+            FROM pg_inherits i JOIN pg_class c ON c.oid = i.inhrelid
+            WHERE i.inhparent = 'audit_log'::regclass
+            ORDER BY 1
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        let cutoff = policy.latest_cutoff(now).date_naive();
+        let mut dropped = Vec::new();
+        // Only names parsed as month partitions end up in the SQL below
+        for partition in names.iter().filter_map(|name| MonthPartition::from_name(name)) {
+            if partition.end > cutoff {
+                continue;
+            }
+            let empty: bool = sqlx::query_scalar(&format!(
+                "SELECT NOT EXISTS (SELECT 1 FROM {})",
+                partition.name
+            ))
+            .fetch_one(&self.pool)
+            .await
+            .map_err(db_error)?;
+            if empty {
+                sqlx::query(&format!("DROP TABLE IF EXISTS {}", partition.name))
+                    .execute(&self.pool)
+                    .await
+                    .map_err(db_error)?;
+                dropped.push(partition.name);
             }
-        }))
+        }
+        Ok(dropped)
     }
 }
 
@@ -329,6 +992,35 @@ impl<B: AuditBackend> AuditLogger<B> {
     pub async fn get(&self, id: Uuid) -> Result<Option<AuditEntry>, AuditError> {
         self.backend.get(id).await
     }
+
+    /// Export every entry matching `query` to `writer`, page by page
+    ///
+    /// Use [`AuditQuery::range`] for a whole period, e.g. six months of
+    /// access logs for an auditor.
+    pub async fn export<W: Write + Send>(
+        &self,
+        tenant_id: &str,
+        query: &AuditQuery,
+        format: AuditExportFormat,
+        writer: W,
+    ) -> Result<W, AuditError> {
+        let io = |e: std::io::Error| AuditError::Serialization(e.to_string());
+        let mut export = AuditExportWriter::new(writer, format).map_err(io)?;
+
+        let mut page = query.clone();
+        loop {
+            let result = self.backend.query(tenant_id, &page).await?;
+            for entry in &result.entries {
+                export.write_entry(entry).map_err(io)?;
+            }
+            match result.next_cursor {
+                Some(cursor) => page = query.after(cursor),
+                None => break,
+            }
+        }
+
+        export.finish().map_err(io)
+    }
 }
 
 /// Shared audit logger that can be used across async tasks
@@ -365,6 +1057,12 @@ pub enum AuditError {
 
     #[error("Immutable: audit entries cannot be modified")]
     Immutable,
+
+    #[error("Invalid cursor: {0}")]
+    InvalidCursor(String),
+
+    #[error("Archive error: {0}")]
+    Archive(String),
 }
 
 #[cfg(test)]
@@ -7,9 +7,32 @@
 //!
 //! Audit entries are written BEFORE the action is executed.
 //! If audit logging fails, the action MUST NOT proceed.
+//!
+//! # Tamper Evidence
+//!
+//! Entries are hash-chained per tenant and the chain head is periodically
+//! signed with an organisation key; `PostgresAuditBackend::verify_chain`
+//! walks the chain and reports the first broken link.
+//!
+//! # Export and Retention
+//!
+//! Query results are paged with cursors and can be exported as JSON Lines
+//! or CSV. Entries past their retention period are archived to object
+//! storage before they are purged; the table is partitioned by month.
 
+pub mod chain;
+pub mod export;
 pub mod logger;
 pub mod models;
+pub mod retention;
 
-pub use logger::{AuditLogger, AuditBackend, PostgresAuditBackend, SharedAuditLogger, shared_logger, log_shared};
-pub use models::{AuditEntry, AuditAction, AuditOutcome, AuditFilter, AuditQuery};
+pub use logger::{AuditLogger, AuditBackend, AuditError, PostgresAuditBackend, SharedAuditLogger, shared_logger, log_shared};
+pub use chain::{
+    ArchivedLink, AuditCheckpoint, ChainBreak, ChainBreakKind, ChainHead, ChainLink, ChainVerification,
+    ChainVerifier, ChainedAuditEntry, CheckpointKey, verify_chain, GENESIS_HASH, DEFAULT_CHECKPOINT_INTERVAL,
+};
+pub use export::{AuditExportFormat, AuditExportWriter};
+pub use models::{AuditEntry, AuditAction, AuditOutcome, AuditFilter, AuditQuery, AuditQueryResult, AuditCursor};
+pub use retention::{
+    ArchivedEntry, AuditArchive, MonthPartition, RetentionPolicy, RetentionReport, DEFAULT_RETENTION_MONTHS,
+};
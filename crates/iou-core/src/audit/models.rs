@@ -1,5 +1,7 @@
 //! Audit log models
 
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -144,6 +146,36 @@ pub enum AuditAction {
     Custom(String),
 }
 
+impl AuditAction {
+    /// Stored name, e.g. "document_created"; custom actions keep their own
+    pub fn name(&self) -> String {
+        let name = match self {
+            AuditAction::DocumentCreated => "document_created",
+            AuditAction::DocumentViewed => "document_viewed",
+            AuditAction::DocumentUpdated => "document_updated",
+            AuditAction::DocumentDeleted => "document_deleted",
+            AuditAction::DocumentApproved => "document_approved",
+            AuditAction::DocumentRejected => "document_rejected",
+            AuditAction::ProcessStarted => "process_started",
+            AuditAction::ProcessCompleted => "process_completed",
+            AuditAction::ProcessFailed => "process_failed",
+            AuditAction::ProcessCancelled => "process_cancelled",
+            AuditAction::RuleEvaluated => "rule_evaluated",
+            AuditAction::UserLogin => "user_login",
+            AuditAction::UserLogout => "user_logout",
+            AuditAction::VCPresented => "vc_presented",
+            AuditAction::TenantCreated => "tenant_created",
+            AuditAction::TenantUpdated => "tenant_updated",
+            AuditAction::UserInvited => "user_invited",
+            AuditAction::UserRemoved => "user_removed",
+            AuditAction::CalculationStarted => "calculation_started",
+            AuditAction::CalculationCompleted => "calculation_completed",
+            AuditAction::Custom(s) => s,
+        };
+        name.to_string()
+    }
+}
+
 impl From<String> for AuditAction {
     fn from(s: String) -> Self {
         match s.as_str() {
@@ -161,6 +193,10 @@ impl From<String> for AuditAction {
             "user_login" => AuditAction::UserLogin,
             "user_logout" => AuditAction::UserLogout,
             "vc_presented" => AuditAction::VCPresented,
+            "tenant_created" => AuditAction::TenantCreated,
+            "tenant_updated" => AuditAction::TenantUpdated,
+            "user_invited" => AuditAction::UserInvited,
+            "user_removed" => AuditAction::UserRemoved,
             "calculation_started" => AuditAction::CalculationStarted,
             "calculation_completed" => AuditAction::CalculationCompleted,
             _ => AuditAction::Custom(s),
@@ -185,6 +221,29 @@ pub enum AuditOutcome {
     Error,
 }
 
+impl AuditOutcome {
+    /// Stored name, e.g. "success"
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditOutcome::Success => "success",
+            AuditOutcome::Failed => "failed",
+            AuditOutcome::Denied => "denied",
+            AuditOutcome::Error => "error",
+        }
+    }
+}
+
+impl From<&str> for AuditOutcome {
+    fn from(s: &str) -> Self {
+        match s {
+            "success" => AuditOutcome::Success,
+            "failed" => AuditOutcome::Failed,
+            "denied" => AuditOutcome::Denied,
+            _ => AuditOutcome::Error,
+        }
+    }
+}
+
 /// Filter for querying audit logs
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditQuery {
@@ -194,6 +253,14 @@ pub struct AuditQuery {
     pub action: Option<AuditAction>,
     pub resource_type: Option<String>,
     pub limit: u32,
+
+    /// Continue after the last entry of a previous page
+    #[serde(default)]
+    pub cursor: Option<String>,
+
+    /// Return the oldest entries first (default: newest first)
+    #[serde(default)]
+    pub oldest_first: bool,
 }
 
 impl Default for AuditQuery {
@@ -206,8 +273,59 @@ impl Default for AuditQuery {
             action: None,
             resource_type: None,
             limit: 100,
+            cursor: None,
+            oldest_first: false,
+        }
+    }
+}
+
+impl AuditQuery {
+    /// All entries in `[start, end)`, oldest first, for exports
+    pub fn range(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self {
+            start_timestamp: Some(start),
+            end_timestamp: Some(end),
+            limit: 1000,
+            oldest_first: true,
+            ..Self::default()
         }
     }
+
+    /// The same query for the page after `cursor`
+    pub fn after(&self, cursor: String) -> Self {
+        Self { cursor: Some(cursor), ..self.clone() }
+    }
+}
+
+/// Position in a result set: the (timestamp, id) of the last entry returned
+///
+/// Entries are ordered by timestamp and then id, so the cursor stays valid
+/// while new entries are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuditCursor {
+    pub timestamp: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl AuditCursor {
+    pub fn of(entry: &AuditEntry) -> Self {
+        Self { timestamp: entry.timestamp, id: entry.id }
+    }
+
+    /// Opaque, URL-safe form
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}.{}", self.timestamp.timestamp_micros(), self.id))
+    }
+
+    /// Parse an encoded cursor, `None` if it is malformed
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let decoded = String::from_utf8(URL_SAFE_NO_PAD.decode(cursor).ok()?).ok()?;
+        let (micros, id) = decoded.split_once('.')?;
+        Some(Self {
+            timestamp: DateTime::from_timestamp_micros(micros.parse().ok()?)?,
+            id: id.parse().ok()?,
+        })
+    }
 }
 
 /// Result type for audit queries
@@ -216,6 +334,10 @@ pub struct AuditQueryResult {
     pub entries: Vec<AuditEntry>,
     pub total_count: u64,
     pub has_more: bool,
+
+    /// Cursor for the next page, if there is one
+    #[serde(default)]
+    pub next_cursor: Option<String>,
 }
 
 /// Type alias for AuditFilter (for backward compatibility)
@@ -240,6 +362,21 @@ mod tests {
         assert_eq!(entry.outcome, AuditOutcome::Success);
     }
 
+    #[test]
+    fn test_audit_action_name_round_trip() {
+        for action in [
+            AuditAction::DocumentCreated,
+            AuditAction::VCPresented,
+            AuditAction::TenantUpdated,
+            AuditAction::Custom("export".into()),
+        ] {
+            let name = action.name();
+            assert_eq!(AuditAction::from(name.clone()).name(), name);
+        }
+        assert_eq!(AuditAction::DocumentCreated.name(), "document_created");
+        assert_eq!(AuditAction::VCPresented.name(), "vc_presented");
+    }
+
     #[test]
     fn test_audit_entry_builder() {
         let entry = AuditEntry::new(
@@ -258,6 +395,16 @@ mod tests {
         assert!(entry.context.is_some());
     }
 
+    #[test]
+    fn test_audit_cursor_round_trip() {
+        let entry = AuditEntry::new("utrecht", "did:example:u", AuditAction::UserLogin, "session", "s");
+        let cursor = AuditCursor::of(&entry);
+        let decoded = AuditCursor::decode(&cursor.encode()).unwrap();
+        assert_eq!(decoded.id, entry.id);
+        assert_eq!(decoded.timestamp.timestamp_micros(), entry.timestamp.timestamp_micros());
+        assert!(AuditCursor::decode("not-a-cursor").is_none());
+    }
+
     #[test]
     fn test_audit_query_default() {
         let query = AuditQuery::default();
@@ -0,0 +1,339 @@
+//! Retention of audit entries
+//!
+//! Each action has a retention period in months; entries past it are
+//! written to object storage as gzipped JSON Lines archives and only then
+//! purged from the database. Purged chained entries leave their link
+//! (sequence and hashes) behind, so the hash chain stays verifiable and
+//! the archived content can still be checked against it.
+//!
+//! The audit table is partitioned by month so old months can be dropped
+//! once all their entries are purged.
+
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, Write};
+use uuid::Uuid;
+
+use super::chain::ChainedAuditEntry;
+use super::models::{AuditAction, AuditEntry};
+
+/// BIO default: seven years
+pub const DEFAULT_RETENTION_MONTHS: u32 = 84;
+
+/// Retention period per action, in months
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// Months for actions without their own rule
+    #[serde(default = "default_retention_months")]
+    pub default_months: u32,
+
+    /// Months per action name, e.g. `document_viewed: 24`
+    #[serde(default)]
+    pub actions: BTreeMap<String, u32>,
+}
+
+fn default_retention_months() -> u32 {
+    DEFAULT_RETENTION_MONTHS
+}
+
+impl RetentionPolicy {
+    /// Seven years for everything, two years for access and session logs
+    pub fn bio() -> Self {
+        let access = ["document_viewed", "user_login", "user_logout", "vc_presented"];
+        Self {
+            default_months: DEFAULT_RETENTION_MONTHS,
+            actions: access.iter().map(|a| (a.to_string(), 24)).collect(),
+        }
+    }
+
+    /// Set the retention of one action
+    pub fn with_action(mut self, action: &AuditAction, months: u32) -> Self {
+        self.actions.insert(action.name(), months);
+        self
+    }
+
+    pub fn months_for(&self, action: &AuditAction) -> u32 {
+        self.actions.get(&action.name()).copied().unwrap_or(self.default_months)
+    }
+
+    /// Moment the entry may be purged
+    pub fn expires_at(&self, entry: &AuditEntry) -> DateTime<Utc> {
+        entry
+            .timestamp
+            .checked_add_months(Months::new(self.months_for(&entry.action)))
+            .unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+
+    pub fn is_expired(&self, entry: &AuditEntry, now: DateTime<Utc>) -> bool {
+        self.expires_at(entry) <= now
+    }
+
+    /// Entries older than this may be expired under some rule
+    pub fn earliest_cutoff(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        self.cutoff(self.actions.values().copied().chain([self.default_months]).min(), now)
+    }
+
+    /// Entries older than this are expired under every rule
+    pub fn latest_cutoff(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        self.cutoff(self.actions.values().copied().chain([self.default_months]).max(), now)
+    }
+
+    fn cutoff(&self, months: Option<u32>, now: DateTime<Utc>) -> DateTime<Utc> {
+        now.checked_sub_months(Months::new(months.unwrap_or(self.default_months)))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC)
+    }
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self::bio()
+    }
+}
+
+/// One monthly partition of the audit table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MonthPartition {
+    /// Table name, e.g. `audit_log_y2026m10`
+    pub name: String,
+    /// First day of the month
+    pub start: NaiveDate,
+    /// First day of the next month
+    pub end: NaiveDate,
+}
+
+impl MonthPartition {
+    /// Partition holding `date`
+    pub fn containing(date: NaiveDate) -> Self {
+        let start = date.with_day(1).expect("every month has a first day");
+        let end = start + Months::new(1);
+        Self {
+            name: format!("audit_log_y{:04}m{:02}", start.year(), start.month()),
+            start,
+            end,
+        }
+    }
+
+    /// Partition named like `audit_log_y2026m10`
+    pub fn from_name(name: &str) -> Option<Self> {
+        let (year, month) = name.strip_prefix("audit_log_y")?.split_once('m')?;
+        let start = NaiveDate::from_ymd_opt(year.parse().ok()?, month.parse().ok()?, 1)?;
+        let partition = Self::containing(start);
+        (partition.name == name).then_some(partition)
+    }
+
+    pub fn next(&self) -> Self {
+        Self::containing(self.end)
+    }
+
+    /// Partitions from the month of `from` up to and including that of `to`
+    pub fn between(from: NaiveDate, to: NaiveDate) -> Vec<Self> {
+        let mut partitions = Vec::new();
+        let mut partition = Self::containing(from);
+        while partition.start <= to {
+            let next = partition.next();
+            partitions.push(partition);
+            partition = next;
+        }
+        partitions
+    }
+
+    /// `CREATE TABLE` statement attaching the partition to `audit_log`
+    pub fn create_sql(&self) -> String {
+        format!(
+            "CREATE TABLE IF NOT EXISTS {} PARTITION OF audit_log \
+             FOR VALUES FROM ('{} 00:00:00+00') TO ('{} 00:00:00+00')",
+            self.name, self.start, self.end
+        )
+    }
+}
+
+/// An archived entry; chain fields are absent for entries written before
+/// the log was hash-chained
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedEntry {
+    #[serde(flatten)]
+    pub entry: AuditEntry,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry_hash: Option<String>,
+}
+
+impl ArchivedEntry {
+    /// The entry with its chain link, if it was chained
+    pub fn chained(&self) -> Option<ChainedAuditEntry> {
+        Some(ChainedAuditEntry {
+            entry: self.entry.clone(),
+            sequence: self.sequence?,
+            previous_hash: self.previous_hash.clone()?,
+            entry_hash: self.entry_hash.clone()?,
+        })
+    }
+}
+
+impl From<ChainedAuditEntry> for ArchivedEntry {
+    fn from(chained: ChainedAuditEntry) -> Self {
+        Self {
+            entry: chained.entry,
+            sequence: Some(chained.sequence),
+            previous_hash: Some(chained.previous_hash),
+            entry_hash: Some(chained.entry_hash),
+        }
+    }
+}
+
+impl From<AuditEntry> for ArchivedEntry {
+    fn from(entry: AuditEntry) -> Self {
+        Self { entry, sequence: None, previous_hash: None, entry_hash: None }
+    }
+}
+
+/// Archive object written to storage before a purge
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditArchive {
+    pub id: Uuid,
+    pub tenant_id: String,
+    /// Object storage key
+    pub key: String,
+    /// Hex SHA-256 of the stored object
+    pub sha256: String,
+    pub entries: u64,
+    pub first_timestamp: DateTime<Utc>,
+    pub last_timestamp: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AuditArchive {
+    /// Storage key for a batch of entries of one tenant and month
+    pub fn key_for(tenant_id: &str, month: &MonthPartition, id: Uuid) -> String {
+        format!(
+            "audit-archive/{}/{:04}/{:02}/{}.jsonl.gz",
+            tenant_id,
+            month.start.year(),
+            month.start.month(),
+            id
+        )
+    }
+}
+
+/// Gzipped JSON Lines of archived entries
+pub fn encode_archive(entries: &[ArchivedEntry]) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    for entry in entries {
+        serde_json::to_writer(&mut encoder, entry)?;
+        encoder.write_all(b"\n")?;
+    }
+    encoder.finish()
+}
+
+/// Read back an archive written by [`encode_archive`]
+pub fn decode_archive(data: &[u8]) -> io::Result<Vec<ArchivedEntry>> {
+    BufReader::new(GzDecoder::new(data))
+        .lines()
+        .filter(|line| !matches!(line, Ok(l) if l.is_empty()))
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+        .collect()
+}
+
+/// Hex SHA-256 of archive bytes
+pub fn archive_checksum(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// Outcome of a retention run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionReport {
+    pub tenant_id: String,
+    pub archived: u64,
+    pub purged: u64,
+    /// Keys of the archives written
+    pub archives: Vec<String>,
+    pub partitions_created: Vec<String>,
+    pub partitions_dropped: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::chain::ChainHead;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn entry(action: AuditAction, timestamp: &str) -> AuditEntry {
+        let mut entry = AuditEntry::new("utrecht", "did:example:user", action, "document", "doc-1");
+        entry.timestamp = at(timestamp);
+        entry
+    }
+
+    #[test]
+    fn test_retention_per_action() {
+        let policy = RetentionPolicy::bio().with_action(&AuditAction::Custom("export".into()), 6);
+        let now = at("2026-10-16T12:00:00Z");
+
+        let viewed = entry(AuditAction::DocumentViewed, "2024-10-16T11:00:00Z");
+        assert_eq!(policy.months_for(&viewed.action), 24);
+        assert!(policy.is_expired(&viewed, now));
+
+        let deleted = entry(AuditAction::DocumentDeleted, "2024-10-16T11:00:00Z");
+        assert!(!policy.is_expired(&deleted, now));
+        assert_eq!(policy.expires_at(&deleted), at("2031-10-16T11:00:00Z"));
+
+        let export = entry(AuditAction::Custom("export".into()), "2026-04-16T12:00:00Z");
+        assert!(policy.is_expired(&export, now));
+
+        assert_eq!(policy.earliest_cutoff(now), at("2026-04-16T12:00:00Z"));
+        assert_eq!(policy.latest_cutoff(now), at("2019-10-16T12:00:00Z"));
+    }
+
+    #[test]
+    fn test_policy_from_yaml() {
+        let policy: RetentionPolicy = serde_yaml::from_str("actions:\n  user_login: 6\n").unwrap();
+        assert_eq!(policy.default_months, DEFAULT_RETENTION_MONTHS);
+        assert_eq!(policy.months_for(&AuditAction::UserLogin), 6);
+        assert_eq!(policy.months_for(&AuditAction::DocumentViewed), DEFAULT_RETENTION_MONTHS);
+    }
+
+    #[test]
+    fn test_month_partitions() {
+        let partitions = MonthPartition::between(
+            NaiveDate::from_ymd_opt(2026, 11, 20).unwrap(),
+            NaiveDate::from_ymd_opt(2027, 1, 1).unwrap(),
+        );
+        let names: Vec<&str> = partitions.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["audit_log_y2026m11", "audit_log_y2026m12", "audit_log_y2027m01"]);
+        assert_eq!(partitions[1].end, NaiveDate::from_ymd_opt(2027, 1, 1).unwrap());
+        assert_eq!(MonthPartition::from_name("audit_log_y2026m12"), Some(partitions[1].clone()));
+        assert_eq!(MonthPartition::from_name("audit_log_default"), None);
+        assert_eq!(MonthPartition::from_name("audit_log_y2026m1"), None);
+        assert_eq!(
+            partitions[1].create_sql(),
+            "CREATE TABLE IF NOT EXISTS audit_log_y2026m12 PARTITION OF audit_log \
+             FOR VALUES FROM ('2026-12-01 00:00:00+00') TO ('2027-01-01 00:00:00+00')"
+        );
+    }
+
+    #[test]
+    fn test_archive_round_trip_keeps_chain() {
+        let mut head = ChainHead::genesis();
+        let chained = head.append(entry(AuditAction::DocumentViewed, "2024-01-05T10:00:00Z"));
+        let legacy = entry(AuditAction::UserLogin, "2024-01-06T10:00:00Z");
+        let data = encode_archive(&[chained.clone().into(), legacy.into()]).unwrap();
+
+        let entries = decode_archive(&data).unwrap();
+        assert_eq!(entries.len(), 2);
+        let restored = entries[0].chained().unwrap();
+        assert_eq!(restored.entry_hash, chained.entry_hash);
+        assert_eq!(restored.compute_hash(), chained.entry_hash);
+        assert!(entries[1].chained().is_none());
+        assert_eq!(archive_checksum(&data).len(), 64);
+    }
+}
@@ -93,6 +93,8 @@ pub enum WooRefusalGround {
     Bedrijfsgegevens,
     /// Art 5.1.1.d: Bijzondere persoonsgegevens
     BijzonderePersoonsgegevens,
+    /// Art 5.1.1.e: Identificatienummers
+    Identificatienummers,
     /// Art 5.1.2.a: Internationale betrekkingen
     InternationaleBetrekkingen,
     /// Art 5.1.2.b: Economische belangen
@@ -103,18 +105,48 @@ pub enum WooRefusalGround {
     InspectieToezicht,
     /// Art 5.1.2.e: Persoonlijke levenssfeer
     PersoonlijkeLevenssfeer,
-    /// Art 5.1.2.f: Onevenredige benadeling
-    OnevenredigeBenadeling,
-    /// Art 5.1.2.g: Functioneren bestuursorgaan
-    FunctionerenBestuursorgaan,
-    /// Art 5.1.2.h: Concurrentiepositie
+    /// Art 5.1.2.f: Concurrentiegevoelige bedrijfsgegevens
     Concurrentiepositie,
-    /// Art 5.1.2.i: Misbruik van wettelijke bevoegdheden
+    /// Art 5.1.2.g: Bescherming van het milieu
+    Milieu,
+    /// Art 5.1.2.h: Beveiliging van personen en bedrijven
+    BeveiligingPersonen,
+    /// Art 5.1.2.i: Functioneren bestuursorgaan
+    FunctionerenBestuursorgaan,
+    /// Art 5.1.5: Onevenredige benadeling
+    OnevenredigeBenadeling,
+    /// Geen weigeringsgrond van de Woo; alleen voor bestaande gegevens
     MisbruikBevoegdheden,
     /// Art 5.2: Persoonlijke beleidsopvattingen
     PersoonlijkeBeleidsopvattingen,
 }
 
+impl WooRefusalGround {
+    /// Wetsartikel van de weigeringsgrond (bijv. "5.1.2.e"), `None` als de
+    /// Woo de grond niet kent
+    pub fn artikel(&self) -> Option<&'static str> {
+        Some(match self {
+            Self::EenheidKroon => "5.1.1.a",
+            Self::VeiligheidStaat => "5.1.1.b",
+            Self::Bedrijfsgegevens => "5.1.1.c",
+            Self::BijzonderePersoonsgegevens => "5.1.1.d",
+            Self::Identificatienummers => "5.1.1.e",
+            Self::InternationaleBetrekkingen => "5.1.2.a",
+            Self::EconomischeBelangen => "5.1.2.b",
+            Self::OpsporingVervolging => "5.1.2.c",
+            Self::InspectieToezicht => "5.1.2.d",
+            Self::PersoonlijkeLevenssfeer => "5.1.2.e",
+            Self::Concurrentiepositie => "5.1.2.f",
+            Self::Milieu => "5.1.2.g",
+            Self::BeveiligingPersonen => "5.1.2.h",
+            Self::FunctionerenBestuursorgaan => "5.1.2.i",
+            Self::OnevenredigeBenadeling => "5.1.5",
+            Self::MisbruikBevoegdheden => return None,
+            Self::PersoonlijkeBeleidsopvattingen => "5.2",
+        })
+    }
+}
+
 /// AVG metadata voor privacy compliance
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AvgMetadata {
@@ -257,6 +289,33 @@ mod tests {
         assert_eq!(json, "\"persoonlijke_levenssfeer\"");
     }
 
+    #[test]
+    fn test_woo_refusal_ground_artikel() {
+        use WooRefusalGround::*;
+        let cases = [
+            (EenheidKroon, Some("5.1.1.a")),
+            (VeiligheidStaat, Some("5.1.1.b")),
+            (Bedrijfsgegevens, Some("5.1.1.c")),
+            (BijzonderePersoonsgegevens, Some("5.1.1.d")),
+            (Identificatienummers, Some("5.1.1.e")),
+            (InternationaleBetrekkingen, Some("5.1.2.a")),
+            (EconomischeBelangen, Some("5.1.2.b")),
+            (OpsporingVervolging, Some("5.1.2.c")),
+            (InspectieToezicht, Some("5.1.2.d")),
+            (PersoonlijkeLevenssfeer, Some("5.1.2.e")),
+            (Concurrentiepositie, Some("5.1.2.f")),
+            (Milieu, Some("5.1.2.g")),
+            (BeveiligingPersonen, Some("5.1.2.h")),
+            (FunctionerenBestuursorgaan, Some("5.1.2.i")),
+            (OnevenredigeBenadeling, Some("5.1.5")),
+            (MisbruikBevoegdheden, None),
+            (PersoonlijkeBeleidsopvattingen, Some("5.2")),
+        ];
+        for (ground, artikel) in cases {
+            assert_eq!(ground.artikel(), artikel, "{:?}", ground);
+        }
+    }
+
     #[test]
     fn test_avg_metadata() {
         let mut avg = AvgMetadata::default();
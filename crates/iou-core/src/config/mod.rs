@@ -8,6 +8,6 @@ pub mod watcher;
 
 pub use workflow::{
     WorkflowConfig, StageConfig, ApproverConfig, ApprovalTypeConfig,
-    VersionStorageConfig, SlaConfig, DomainConfig,
+    VersionStorageConfig, SlaConfig, SlaCalendarConfig, DomainConfig,
 };
 pub use watcher::{ConfigWatcher, ConfigChangeEvent, ConfigError};
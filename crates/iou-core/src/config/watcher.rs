@@ -258,6 +258,7 @@ mod tests {
             sla: SlaConfig {
                 weekend_days: vec![],
                 escalation_hours: vec![],
+                calendar: Default::default(),
             },
         };
 
@@ -3,8 +3,11 @@
 //! Defines the structure for YAML-based workflow configuration,
 //! including approval stages, version storage settings, and SLA policies.
 
+use chrono::{NaiveDate, Weekday};
 use serde::{Deserialize, Serialize};
 
+use crate::sla::{LiberationDay, SlaTimeZone, WorkingHours};
+
 /// Root workflow configuration structure
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct WorkflowConfig {
@@ -115,17 +118,25 @@ pub struct VersionStorageConfig {
     pub full_versions_keep: i32,
     #[serde(rename = "compress_after_days")]
     pub compress_after_days: i32,
+    /// Every n-th version stays a full keyframe in the delta chain
+    #[serde(default = "default_keyframe_interval")]
+    pub keyframe_interval: i32,
 }
 
 fn default_full_versions_keep() -> i32 {
     5
 }
 
+fn default_keyframe_interval() -> i32 {
+    10
+}
+
 impl Default for VersionStorageConfig {
     fn default() -> Self {
         Self {
             full_versions_keep: default_full_versions_keep(),
             compress_after_days: 30,
+            keyframe_interval: default_keyframe_interval(),
         }
     }
 }
@@ -137,6 +148,30 @@ pub struct SlaConfig {
     pub weekend_days: Vec<String>,
     #[serde(rename = "escalation_hours")]
     pub escalation_hours: Vec<i32>,
+    /// Working hours, time zone and closure days
+    #[serde(default)]
+    pub calendar: SlaCalendarConfig,
+}
+
+/// Working calendar of an organisation
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct SlaCalendarConfig {
+    /// "UTC" (default) or "Europe/Amsterdam"
+    #[serde(default)]
+    pub timezone: SlaTimeZone,
+    /// Working window per weekday, e.g. `monday: "09:00-17:00"`; when the
+    /// block is absent every day counts around the clock
+    #[serde(default)]
+    pub working_hours: WorkingHours,
+    /// Skip the Dutch public holidays
+    #[serde(default)]
+    pub dutch_holidays: bool,
+    /// "lustrum" (default), "yearly" or "never"
+    #[serde(default)]
+    pub liberation_day: LiberationDay,
+    /// Extra organisation closure days (YYYY-MM-DD)
+    #[serde(default)]
+    pub closure_days: Vec<NaiveDate>,
 }
 
 impl SlaConfig {
@@ -170,6 +205,30 @@ impl SlaConfig {
 
         Ok(())
     }
+
+    /// Calculator configuration for this policy
+    ///
+    /// Weekend day names are expected to be valid (see [`validate`](Self::validate));
+    /// unknown names are ignored.
+    pub fn calculator_config(&self) -> crate::sla::SlaConfig {
+        crate::sla::SlaConfig {
+            weekend_days: self
+                .weekend_days
+                .iter()
+                .filter_map(|day| day.parse::<Weekday>().ok())
+                .collect(),
+            holidays: self
+                .calendar
+                .closure_days
+                .iter()
+                .map(|date| date.format("%Y-%m-%d").to_string())
+                .collect(),
+            working_hours: self.calendar.working_hours.clone(),
+            time_zone: self.calendar.timezone,
+            dutch_holidays: self.calendar.dutch_holidays,
+            liberation_day: self.calendar.liberation_day,
+        }
+    }
 }
 
 impl WorkflowConfig {
@@ -206,6 +265,9 @@ impl WorkflowConfig {
         if self.version_storage.compress_after_days < 0 {
             return Err("compress_after_days cannot be negative".to_string());
         }
+        if self.version_storage.keyframe_interval < 1 {
+            return Err("keyframe_interval must be at least 1".to_string());
+        }
 
         Ok(())
     }
@@ -334,12 +396,14 @@ mod tests {
         let valid = SlaConfig {
             weekend_days: vec!["Saturday".to_string(), "Sunday".to_string()],
             escalation_hours: vec![24, 8, 1],
+            calendar: SlaCalendarConfig::default(),
         };
         assert!(valid.validate().is_ok());
 
         let invalid = SlaConfig {
             weekend_days: vec!["Funday".to_string()],
             escalation_hours: vec![24],
+            calendar: SlaCalendarConfig::default(),
         };
         assert!(invalid.validate().is_err());
     }
@@ -349,6 +413,7 @@ mod tests {
         let config = SlaConfig {
             weekend_days: vec!["saturday".to_string(), "SUNDAY".to_string()],
             escalation_hours: vec![24],
+            calendar: SlaCalendarConfig::default(),
         };
         assert!(config.validate().is_ok());
     }
@@ -387,6 +452,7 @@ mod tests {
             sla: SlaConfig {
                 weekend_days: vec![],
                 escalation_hours: vec![],
+                calendar: SlaCalendarConfig::default(),
             },
         };
         assert!(config.validate().is_err());
@@ -434,10 +500,12 @@ mod tests {
             version_storage: VersionStorageConfig {
                 full_versions_keep: 5,
                 compress_after_days: 30,
+                keyframe_interval: 10,
             },
             sla: SlaConfig {
                 weekend_days: vec!["Saturday".to_string()],
                 escalation_hours: vec![24],
+                calendar: SlaCalendarConfig::default(),
             },
         };
 
@@ -446,6 +514,7 @@ mod tests {
             version_storage: Some(VersionStorageConfig {
                 full_versions_keep: 10,
                 compress_after_days: 60,
+                keyframe_interval: 10,
             }),
             sla: None,
         };
@@ -455,4 +524,32 @@ mod tests {
         assert_eq!(merged.version_storage.full_versions_keep, 10); // From override
         assert_eq!(merged.sla.weekend_days, defaults.sla.weekend_days); // From defaults
     }
+
+    #[test]
+    fn test_sla_calendar_from_yaml() {
+        let yaml = r#"
+weekend_days: [Saturday, Sunday]
+escalation_hours: [24]
+calendar:
+  timezone: Europe/Amsterdam
+  dutch_holidays: true
+  working_hours:
+    monday: "08:30-17:00"
+    friday: "08:30-12:00"
+  closure_days: [2026-05-15]
+"#;
+        let config: SlaConfig = serde_yaml::from_str(yaml).unwrap();
+        let calculator = config.calculator_config();
+        assert_eq!(calculator.weekend_days, vec![Weekday::Sat, Weekday::Sun]);
+        assert_eq!(calculator.time_zone, SlaTimeZone::EuropeAmsterdam);
+        assert!(calculator.dutch_holidays);
+        assert!(calculator.holidays.contains("2026-05-15"));
+        assert_eq!(calculator.working_hours.monday.unwrap().to_string(), "08:30-17:00");
+        assert_eq!(calculator.working_hours.tuesday, None);
+
+        let without: SlaConfig = serde_yaml::from_str("weekend_days: []\nescalation_hours: [1]").unwrap();
+        assert_eq!(without.calendar, SlaCalendarConfig::default());
+        assert_eq!(without.calculator_config().working_hours, WorkingHours::all_day());
+    }
 }
+
@@ -1,7 +1,9 @@
-use similar::{ChangeTag, TextDiff};
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, DiffOp, TextDiff};
 
 /// Format for diff output
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DiffFormat {
     /// Unified diff format (git-style, with + and - prefixes)
     Unified,
@@ -12,7 +14,8 @@ pub enum DiffFormat {
 }
 
 /// A single change in the diff
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "text", rename_all = "snake_case")]
 pub enum DiffChange {
     /// Text unchanged between versions
     Unchanged(String),
@@ -24,8 +27,24 @@ pub enum DiffChange {
     Replaced { old: String, new: String },
 }
 
+/// One row of a side-by-side diff
+///
+/// Unchanged lines fill both columns; a replaced block fills both columns
+/// pair by pair, with any surplus lines on one side only.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SideBySideRow {
+    /// Line number in the old version (1-based), if the left column is filled
+    pub old_line: Option<usize>,
+    /// Line number in the new version (1-based), if the right column is filled
+    pub new_line: Option<usize>,
+    pub old_text: Option<String>,
+    pub new_text: Option<String>,
+    /// Word-level changes when both columns hold different text
+    pub words: Vec<DiffChange>,
+}
+
 /// Complete diff between two document versions
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DocumentDiff {
     /// Source version identifier
     pub from_version: String,
@@ -35,6 +54,8 @@ pub struct DocumentDiff {
     pub format: DiffFormat,
     /// List of changes
     pub changes: Vec<DiffChange>,
+    /// Aligned rows, filled for [`DiffFormat::SideBySide`] only
+    pub rows: Vec<SideBySideRow>,
 }
 
 /// Generates diffs between document versions
@@ -43,6 +64,9 @@ pub struct DiffGenerator;
 impl DiffGenerator {
     /// Generate a diff between two text contents
     ///
+    /// The versions are labelled "old" and "new"; use
+    /// [`generate_version_diff`](Self::generate_version_diff) to label them.
+    ///
     /// # Arguments
     /// * `old_content` - The original/older version content
     /// * `new_content` - The new/current version content
@@ -56,155 +80,135 @@ impl DiffGenerator {
         new_content: &str,
         format: DiffFormat,
     ) -> DocumentDiff {
-        match format {
-            DiffFormat::Unified => self.unified_diff(old_content, new_content),
+        self.generate_version_diff("old", old_content, "new", new_content, format)
+    }
+
+    /// Generate a diff between two identified versions
+    pub fn generate_version_diff(
+        &self,
+        from_version: &str,
+        old_content: &str,
+        to_version: &str,
+        new_content: &str,
+        format: DiffFormat,
+    ) -> DocumentDiff {
+        let (changes, rows) = match format {
+            DiffFormat::Unified => (self.unified_diff(old_content, new_content), Vec::new()),
             DiffFormat::SideBySide => self.side_by_side_diff(old_content, new_content),
-            DiffFormat::Inline => self.inline_diff(old_content, new_content),
+            DiffFormat::Inline => (self.word_diff(old_content, new_content), Vec::new()),
+        };
+
+        DocumentDiff {
+            from_version: from_version.to_string(),
+            to_version: to_version.to_string(),
+            format,
+            changes,
+            rows,
         }
     }
 
-    /// Generate unified diff format (git-style)
+    /// Word-level changes between two texts
     ///
-    /// Output uses `+` prefix for additions and `-` prefix for deletions.
-    fn unified_diff(&self, old: &str, new: &str) -> DocumentDiff {
-        let diff = TextDiff::from_lines(old, new);
+    /// Consecutive deletions and insertions are grouped into
+    /// [`DiffChange::Replaced`], suitable for `<del>`/`<ins>` rendering.
+    /// Changes separated only by whitespace are joined into one.
+    pub fn word_diff(&self, old: &str, new: &str) -> Vec<DiffChange> {
+        let diff = TextDiff::from_words(old, new);
         let mut changes = Vec::new();
+        let mut equal = String::new();
+        let mut deleted = String::new();
+        let mut inserted = String::new();
 
-        for op in diff.ops() {
-            for change in diff.iter_changes(op) {
-                let text = change.value().to_string();
-                match change.tag() {
-                    ChangeTag::Equal => {
-                        changes.push(DiffChange::Unchanged(
-                            text.strip_suffix('\n').unwrap_or(&text).to_string()
-                        ));
-                    }
-                    ChangeTag::Insert => {
-                        changes.push(DiffChange::Inserted(
-                            text.strip_suffix('\n').unwrap_or(&text).to_string()
-                        ));
+        for change in diff.iter_all_changes() {
+            match change.tag() {
+                ChangeTag::Equal => {
+                    flush_pending(&mut changes, &mut deleted, &mut inserted);
+                    equal.push_str(change.value());
+                }
+                ChangeTag::Delete | ChangeTag::Insert => {
+                    if !equal.is_empty() {
+                        changes.push(DiffChange::Unchanged(std::mem::take(&mut equal)));
                     }
-                    ChangeTag::Delete => {
-                        changes.push(DiffChange::Deleted(
-                            text.strip_suffix('\n').unwrap_or(&text).to_string()
-                        ));
+                    if change.tag() == ChangeTag::Delete {
+                        deleted.push_str(change.value());
+                    } else {
+                        inserted.push_str(change.value());
                     }
                 }
             }
         }
 
-        DocumentDiff {
-            from_version: "old".to_string(),
-            to_version: "new".to_string(),
-            format: DiffFormat::Unified,
-            changes,
+        flush_pending(&mut changes, &mut deleted, &mut inserted);
+        if !equal.is_empty() {
+            changes.push(DiffChange::Unchanged(equal));
         }
+        join_across_whitespace(changes)
     }
 
-    /// Generate side-by-side diff format
+    /// Generate unified diff format (git-style)
     ///
-    /// Produces aligned changes suitable for parallel column display.
-    fn side_by_side_diff(&self, old: &str, new: &str) -> DocumentDiff {
+    /// Output uses `+` prefix for additions and `-` prefix for deletions.
+    fn unified_diff(&self, old: &str, new: &str) -> Vec<DiffChange> {
         let diff = TextDiff::from_lines(old, new);
         let mut changes = Vec::new();
 
-        for op in diff.ops() {
-            for change in diff.iter_changes(op) {
-                let text = change.value().to_string();
-                match change.tag() {
-                    ChangeTag::Equal => {
-                        changes.push(DiffChange::Unchanged(
-                            text.strip_suffix('\n').unwrap_or(&text).to_string()
-                        ));
-                    }
-                    ChangeTag::Insert => {
-                        changes.push(DiffChange::Inserted(
-                            text.strip_suffix('\n').unwrap_or(&text).to_string()
-                        ));
-                    }
-                    ChangeTag::Delete => {
-                        changes.push(DiffChange::Deleted(
-                            text.strip_suffix('\n').unwrap_or(&text).to_string()
-                        ));
-                    }
-                }
-            }
+        for change in diff.iter_all_changes() {
+            let text = strip_newline(change.value());
+            changes.push(match change.tag() {
+                ChangeTag::Equal => DiffChange::Unchanged(text),
+                ChangeTag::Insert => DiffChange::Inserted(text),
+                ChangeTag::Delete => DiffChange::Deleted(text),
+            });
         }
 
-        DocumentDiff {
-            from_version: "old".to_string(),
-            to_version: "new".to_string(),
-            format: DiffFormat::SideBySide,
-            changes,
-        }
+        changes
     }
 
-    /// Generate inline diff format
+    /// Generate side-by-side diff format
     ///
-    /// Produces inline changes with highlight markers suitable for
-    /// HTML rendering (e.g., `<ins>` and `<del>` tags).
-    fn inline_diff(&self, old: &str, new: &str) -> DocumentDiff {
-        let diff = TextDiff::from_chars(old, new);
+    /// Lines of a replaced block are paired row by row; paired lines become
+    /// [`DiffChange::Replaced`] and get word-level changes in their row.
+    fn side_by_side_diff(&self, old: &str, new: &str) -> (Vec<DiffChange>, Vec<SideBySideRow>) {
+        let diff = TextDiff::from_lines(old, new);
+        let old_lines: Vec<String> = diff.old_slices().iter().map(|l| strip_newline(l)).collect();
+        let new_lines: Vec<String> = diff.new_slices().iter().map(|l| strip_newline(l)).collect();
         let mut changes = Vec::new();
-
-        // Group consecutive changes for better readability
-        let mut pending_delete = String::new();
-        let mut pending_insert = String::new();
+        let mut rows = Vec::new();
 
         for op in diff.ops() {
-            for change in diff.iter_changes(op) {
-                match change.tag() {
-                    ChangeTag::Equal => {
-                        // Flush any pending changes
-                        if !pending_delete.is_empty() || !pending_insert.is_empty() {
-                            if !pending_delete.is_empty() && !pending_insert.is_empty() {
-                                changes.push(DiffChange::Replaced {
-                                    old: pending_delete.clone(),
-                                    new: pending_insert.clone(),
-                                });
-                            } else if !pending_delete.is_empty() {
-                                changes.push(DiffChange::Deleted(pending_delete.clone()));
-                            } else if !pending_insert.is_empty() {
-                                changes.push(DiffChange::Inserted(pending_insert.clone()));
-                            }
-                            pending_delete.clear();
-                            pending_insert.clear();
-                        }
-                        // Strip trailing newlines for consistency with line-based diffs
-                        let value = change.value();
-                        let text = value.strip_suffix('\n').unwrap_or(value).to_string();
-                        changes.push(DiffChange::Unchanged(text));
-                    }
-                    ChangeTag::Delete => {
-                        pending_delete.push_str(change.value());
-                    }
-                    ChangeTag::Insert => {
-                        pending_insert.push_str(change.value());
+            let (old_range, new_range) = (op.old_range(), op.new_range());
+            let paired = old_range.len().max(new_range.len());
+            for k in 0..paired {
+                let o = old_range.clone().nth(k);
+                let n = new_range.clone().nth(k);
+                let old_text = o.map(|i| old_lines[i].clone());
+                let new_text = n.map(|i| new_lines[i].clone());
+
+                let (change, words) = match (&old_text, &new_text) {
+                    (Some(text), Some(_)) if matches!(op, DiffOp::Equal { .. }) => {
+                        (DiffChange::Unchanged(text.clone()), Vec::new())
                     }
-                }
-            }
-        }
+                    (Some(old), Some(new)) => (
+                        DiffChange::Replaced { old: old.clone(), new: new.clone() },
+                        self.word_diff(old, new),
+                    ),
+                    (Some(old), None) => (DiffChange::Deleted(old.clone()), Vec::new()),
+                    (None, Some(new)) => (DiffChange::Inserted(new.clone()), Vec::new()),
+                    (None, None) => unreachable!("row beyond both ranges"),
+                };
 
-        // Flush remaining changes
-        if !pending_delete.is_empty() || !pending_insert.is_empty() {
-            if !pending_delete.is_empty() && !pending_insert.is_empty() {
-                changes.push(DiffChange::Replaced {
-                    old: pending_delete,
-                    new: pending_insert,
+                changes.push(change);
+                rows.push(SideBySideRow {
+                    old_line: o.map(|i| i + 1),
+                    new_line: n.map(|i| i + 1),
+                    old_text,
+                    new_text,
+                    words,
                 });
-            } else if !pending_delete.is_empty() {
-                changes.push(DiffChange::Deleted(pending_delete));
-            } else if !pending_insert.is_empty() {
-                changes.push(DiffChange::Inserted(pending_insert));
             }
         }
 
-        DocumentDiff {
-            from_version: "old".to_string(),
-            to_version: "new".to_string(),
-            format: DiffFormat::Inline,
-            changes,
-        }
+        (changes, rows)
     }
 }
 
@@ -213,3 +217,58 @@ impl Default for DiffGenerator {
         Self
     }
 }
+
+fn strip_newline(text: &str) -> String {
+    text.strip_suffix('\n').unwrap_or(text).to_string()
+}
+
+/// Push pending deletions/insertions as one change
+fn flush_pending(changes: &mut Vec<DiffChange>, deleted: &mut String, inserted: &mut String) {
+    match (deleted.is_empty(), inserted.is_empty()) {
+        (true, true) => {}
+        (false, false) => changes.push(DiffChange::Replaced {
+            old: std::mem::take(deleted),
+            new: std::mem::take(inserted),
+        }),
+        (false, true) => changes.push(DiffChange::Deleted(std::mem::take(deleted))),
+        (true, false) => changes.push(DiffChange::Inserted(std::mem::take(inserted))),
+    }
+}
+
+/// Join `change, whitespace, change` runs into a single replacement
+fn join_across_whitespace(changes: Vec<DiffChange>) -> Vec<DiffChange> {
+    fn sides(change: &DiffChange) -> Option<(&str, &str)> {
+        match change {
+            DiffChange::Inserted(new) => Some(("", new)),
+            DiffChange::Deleted(old) => Some((old, "")),
+            DiffChange::Replaced { old, new } => Some((old, new)),
+            DiffChange::Unchanged(_) => None,
+        }
+    }
+
+    let mut joined: Vec<DiffChange> = Vec::with_capacity(changes.len());
+    let mut iter = changes.into_iter().peekable();
+    while let Some(change) = iter.next() {
+        if let DiffChange::Unchanged(space) = &change
+            && space.trim().is_empty()
+            && let Some((prev_old, prev_new)) = joined.last().and_then(sides)
+            && let Some((next_old, next_new)) = iter.peek().and_then(sides)
+        {
+            let old = format!("{}{}{}", prev_old, space, next_old);
+            let new = format!("{}{}{}", prev_new, space, next_new);
+            let merged = if prev_old.is_empty() && next_old.is_empty() {
+                DiffChange::Inserted(new)
+            } else if prev_new.is_empty() && next_new.is_empty() {
+                DiffChange::Deleted(old)
+            } else {
+                DiffChange::Replaced { old, new }
+            };
+            iter.next();
+            joined.pop();
+            joined.push(merged);
+            continue;
+        }
+        joined.push(change);
+    }
+    joined
+}
@@ -0,0 +1,554 @@
+//! Structure-aware diff of Markdown documents
+//!
+//! Documents are split into heading sections and blocks (paragraphs, list
+//! items, tables, code blocks, quotes). Sections are matched by title, so
+//! renumbering a heading is not a change; blocks within a matched section
+//! are diffed as units. Changed paragraphs carry word-level changes,
+//! changed tables carry cell-level changes, and blocks that disappear in
+//! one place and reappear unchanged elsewhere are reported as moved.
+
+use serde::{Deserialize, Serialize};
+use similar::{Algorithm, DiffOp, TextDiff, capture_diff_slices};
+use std::collections::{HashMap, VecDeque};
+
+use super::generator::{DiffChange, DiffGenerator};
+
+/// Sections whose text is less similar than this are reported as rewritten
+pub const REWRITE_THRESHOLD: f32 = 0.5;
+
+/// Kind of a Markdown block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockKind {
+    Paragraph,
+    ListItem,
+    Table,
+    Code,
+    Quote,
+    Rule,
+}
+
+/// How a section changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionStatus {
+    Added,
+    Removed,
+    Modified,
+    /// Modified so much that a block-by-block view is not useful
+    Rewritten,
+}
+
+/// Change to a single block within a section
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BlockChange {
+    Added { kind: BlockKind, text: String },
+    Removed { kind: BlockKind, text: String },
+    /// Block edited in place, with word-level changes
+    Modified { kind: BlockKind, old: String, new: String, words: Vec<DiffChange> },
+    /// Table edited in place
+    TableChanged {
+        cells: Vec<CellChange>,
+        rows_added: Vec<Vec<String>>,
+        rows_removed: Vec<Vec<String>>,
+    },
+}
+
+/// Edited table cell; `row` 0 is the header row
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CellChange {
+    /// Row in the new table
+    pub row: usize,
+    pub column: usize,
+    pub old: String,
+    pub new: String,
+}
+
+/// Changes within one heading section
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SectionDiff {
+    /// Section number, e.g. "3.2"; empty for text before the first heading
+    pub number: String,
+    pub title: String,
+    pub status: SectionStatus,
+    /// Word similarity between the old and new section text (0.0 - 1.0)
+    pub similarity: f32,
+    pub changes: Vec<BlockChange>,
+}
+
+impl SectionDiff {
+    /// Number and title, e.g. "3.2 Overwegingen"
+    pub fn label(&self) -> String {
+        section_label(&self.number, &self.title)
+    }
+}
+
+/// Block found unchanged in another place
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MovedBlock {
+    pub kind: BlockKind,
+    pub text: String,
+    /// Label of the section it was in
+    pub from_section: String,
+    /// Label of the section it is in now
+    pub to_section: String,
+}
+
+/// Section-level diff between two Markdown documents
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarkdownDiff {
+    pub from_version: String,
+    pub to_version: String,
+    /// Changed sections in the order of the new document, removed
+    /// sections after the section that preceded them
+    pub sections: Vec<SectionDiff>,
+    pub moved: Vec<MovedBlock>,
+}
+
+impl MarkdownDiff {
+    /// True when the documents have the same structure and text
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty() && self.moved.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Block {
+    kind: BlockKind,
+    text: String,
+}
+
+#[derive(Debug)]
+struct Section {
+    number: String,
+    title: String,
+    blocks: Vec<Block>,
+}
+
+impl Section {
+    fn key(&self) -> String {
+        self.title.to_lowercase()
+    }
+
+    fn body(&self) -> String {
+        self.blocks.iter().map(|b| b.text.as_str()).collect::<Vec<_>>().join("\n\n")
+    }
+}
+
+impl DiffGenerator {
+    /// Diff two Markdown documents by section and block
+    pub fn markdown_diff(
+        &self,
+        from_version: &str,
+        old_content: &str,
+        to_version: &str,
+        new_content: &str,
+    ) -> MarkdownDiff {
+        let old_sections = parse_sections(old_content);
+        let new_sections = parse_sections(new_content);
+
+        // Match sections by title, in document order for duplicate titles
+        let mut unmatched: HashMap<String, VecDeque<usize>> = HashMap::new();
+        for (i, section) in old_sections.iter().enumerate() {
+            unmatched.entry(section.key()).or_default().push_back(i);
+        }
+        let matches: Vec<Option<usize>> = new_sections
+            .iter()
+            .map(|section| unmatched.get_mut(&section.key()).and_then(|q| q.pop_front()))
+            .collect();
+        let mut old_matched = vec![false; old_sections.len()];
+        for i in matches.iter().flatten() {
+            old_matched[*i] = true;
+        }
+
+        let mut sections = Vec::new();
+        let removed_after = |sections: &mut Vec<SectionDiff>, from: usize, to: usize| {
+            for (i, old) in old_sections.iter().enumerate().take(to).skip(from) {
+                if !old_matched[i] && (!old.blocks.is_empty() || !old.title.is_empty()) {
+                    sections.push(whole_section(old, SectionStatus::Removed));
+                }
+            }
+        };
+
+        let mut old_pos = 0;
+        for (new, matched) in new_sections.iter().zip(&matches) {
+            match matched {
+                Some(i) => {
+                    if *i >= old_pos {
+                        removed_after(&mut sections, old_pos, *i);
+                        old_pos = i + 1;
+                    }
+                    if let Some(diff) = self.section_diff(&old_sections[*i], new) {
+                        sections.push(diff);
+                    }
+                }
+                None if new.blocks.is_empty() && new.title.is_empty() => {}
+                None => sections.push(whole_section(new, SectionStatus::Added)),
+            }
+        }
+        removed_after(&mut sections, old_pos, old_sections.len());
+
+        let moved = extract_moves(&mut sections);
+
+        MarkdownDiff {
+            from_version: from_version.to_string(),
+            to_version: to_version.to_string(),
+            sections,
+            moved,
+        }
+    }
+
+    /// Block changes between two versions of a section, `None` if unchanged
+    fn section_diff(&self, old: &Section, new: &Section) -> Option<SectionDiff> {
+        if old.blocks == new.blocks {
+            return None;
+        }
+
+        let old_texts: Vec<&str> = old.blocks.iter().map(|b| b.text.as_str()).collect();
+        let new_texts: Vec<&str> = new.blocks.iter().map(|b| b.text.as_str()).collect();
+        let mut changes = Vec::new();
+
+        for op in capture_diff_slices(Algorithm::Myers, &old_texts, &new_texts) {
+            let (old_range, new_range) = (op.old_range(), op.new_range());
+            if matches!(op, DiffOp::Equal { .. }) {
+                continue;
+            }
+            let paired = old_range.len().max(new_range.len());
+            for k in 0..paired {
+                let old_block = old_range.clone().nth(k).map(|i| &old.blocks[i]);
+                let new_block = new_range.clone().nth(k).map(|i| &new.blocks[i]);
+                match (old_block, new_block) {
+                    (Some(o), Some(n)) if o.kind == n.kind => changes.push(self.block_change(o, n)),
+                    (o, n) => {
+                        if let Some(o) = o {
+                            changes.push(BlockChange::Removed { kind: o.kind, text: o.text.clone() });
+                        }
+                        if let Some(n) = n {
+                            changes.push(BlockChange::Added { kind: n.kind, text: n.text.clone() });
+                        }
+                    }
+                }
+            }
+        }
+
+        let similarity = TextDiff::from_words(&old.body(), &new.body()).ratio();
+        let status = if similarity < REWRITE_THRESHOLD {
+            SectionStatus::Rewritten
+        } else {
+            SectionStatus::Modified
+        };
+
+        Some(SectionDiff {
+            number: new.number.clone(),
+            title: new.title.clone(),
+            status,
+            similarity,
+            changes,
+        })
+    }
+
+    fn block_change(&self, old: &Block, new: &Block) -> BlockChange {
+        if old.kind == BlockKind::Table {
+            let (cells, rows_added, rows_removed) = table_changes(&old.text, &new.text);
+            return BlockChange::TableChanged { cells, rows_added, rows_removed };
+        }
+        BlockChange::Modified {
+            kind: new.kind,
+            old: old.text.clone(),
+            new: new.text.clone(),
+            words: self.word_diff(&old.text, &new.text),
+        }
+    }
+}
+
+fn section_label(number: &str, title: &str) -> String {
+    match (number.is_empty(), title.is_empty()) {
+        (true, _) => title.to_string(),
+        (false, true) => number.to_string(),
+        (false, false) => format!("{} {}", number, title),
+    }
+}
+
+fn whole_section(section: &Section, status: SectionStatus) -> SectionDiff {
+    let changes = section
+        .blocks
+        .iter()
+        .map(|b| match status {
+            SectionStatus::Removed => BlockChange::Removed { kind: b.kind, text: b.text.clone() },
+            _ => BlockChange::Added { kind: b.kind, text: b.text.clone() },
+        })
+        .collect();
+    SectionDiff {
+        number: section.number.clone(),
+        title: section.title.clone(),
+        status,
+        similarity: 0.0,
+        changes,
+    }
+}
+
+/// Turn removed/added pairs with the same text into moves
+///
+/// Sections left without changes after this (a paragraph moved within the
+/// section) are dropped, unless the section itself was added or removed.
+fn extract_moves(sections: &mut Vec<SectionDiff>) -> Vec<MovedBlock> {
+    let normalize = |text: &str| text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let mut removed: HashMap<(BlockKind, String), VecDeque<(usize, usize)>> = HashMap::new();
+    for (s, section) in sections.iter().enumerate() {
+        for (c, change) in section.changes.iter().enumerate() {
+            if let BlockChange::Removed { kind, text } = change
+                && *kind != BlockKind::Rule
+            {
+                removed.entry((*kind, normalize(text))).or_default().push_back((s, c));
+            }
+        }
+    }
+
+    let mut moved = Vec::new();
+    let mut taken: Vec<(usize, usize)> = Vec::new();
+    for (s, section) in sections.iter().enumerate() {
+        for (c, change) in section.changes.iter().enumerate() {
+            if let BlockChange::Added { kind, text } = change
+                && let Some((from_s, from_c)) =
+                    removed.get_mut(&(*kind, normalize(text))).and_then(|q| q.pop_front())
+            {
+                moved.push(MovedBlock {
+                    kind: *kind,
+                    text: text.clone(),
+                    from_section: sections[from_s].label(),
+                    to_section: section.label(),
+                });
+                taken.push((s, c));
+                taken.push((from_s, from_c));
+            }
+        }
+    }
+
+    for (s, section) in sections.iter_mut().enumerate() {
+        let mut c = 0;
+        section.changes.retain(|_| {
+            let keep = !taken.contains(&(s, c));
+            c += 1;
+            keep
+        });
+    }
+    sections.retain(|s| {
+        !s.changes.is_empty() || matches!(s.status, SectionStatus::Added | SectionStatus::Removed)
+    });
+    moved
+}
+
+/// Cell edits, added rows and removed rows between two pipe tables
+fn table_changes(old: &str, new: &str) -> (Vec<CellChange>, Vec<Vec<String>>, Vec<Vec<String>>) {
+    let old_rows = table_rows(old);
+    let new_rows = table_rows(new);
+    let mut cells = Vec::new();
+    let mut added = Vec::new();
+    let mut removed = Vec::new();
+
+    for op in capture_diff_slices(Algorithm::Myers, &old_rows, &new_rows) {
+        if matches!(op, DiffOp::Equal { .. }) {
+            continue;
+        }
+        let (old_range, new_range) = (op.old_range(), op.new_range());
+        for k in 0..old_range.len().max(new_range.len()) {
+            match (old_range.clone().nth(k), new_range.clone().nth(k)) {
+                (Some(o), Some(n)) => {
+                    let (old_row, new_row) = (&old_rows[o], &new_rows[n]);
+                    for column in 0..old_row.len().max(new_row.len()) {
+                        let old_cell = old_row.get(column).cloned().unwrap_or_default();
+                        let new_cell = new_row.get(column).cloned().unwrap_or_default();
+                        if old_cell != new_cell {
+                            cells.push(CellChange { row: n, column, old: old_cell, new: new_cell });
+                        }
+                    }
+                }
+                (Some(o), None) => removed.push(old_rows[o].clone()),
+                (None, Some(n)) => added.push(new_rows[n].clone()),
+                (None, None) => {}
+            }
+        }
+    }
+    (cells, added, removed)
+}
+
+/// Cells of a pipe table, without the delimiter row
+fn table_rows(table: &str) -> Vec<Vec<String>> {
+    table
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            let line = line.strip_prefix('|').unwrap_or(line);
+            let line = line.strip_suffix('|').unwrap_or(line);
+            line.split('|').map(|cell| cell.trim().to_string()).collect::<Vec<_>>()
+        })
+        .filter(|cells| {
+            !cells.iter().all(|c| !c.is_empty() && c.chars().all(|ch| matches!(ch, '-' | ':' | ' ')))
+        })
+        .collect()
+}
+
+/// Split a document into sections of blocks
+///
+/// The first section holds the text before the first heading. Sections
+/// are numbered from the heading levels, or from a number the heading
+/// starts with ("3.2 Overwegingen"). A single top-level heading at the
+/// start is treated as the document title and not counted.
+fn parse_sections(markdown: &str) -> Vec<Section> {
+    let blocks = parse_blocks(markdown);
+    let levels: Vec<usize> = blocks
+        .iter()
+        .filter_map(|b| match b {
+            Parsed::Heading(level, _) => Some(*level),
+            Parsed::Block(_) => None,
+        })
+        .collect();
+    let top = levels.iter().copied().min().unwrap_or(1);
+    let titled = levels.first() == Some(&top) && levels.iter().filter(|l| **l == top).count() == 1;
+    let base = if titled { top + 1 } else { top };
+
+    let mut sections = vec![Section { number: String::new(), title: String::new(), blocks: Vec::new() }];
+    let mut counters = [0usize; 6];
+
+    for parsed in blocks {
+        match parsed {
+            Parsed::Heading(level, text) => {
+                let (explicit, title) = split_number(&text);
+                let number = if level < base {
+                    String::new()
+                } else {
+                    counters[level - 1] += 1;
+                    counters[level..].iter_mut().for_each(|c| *c = 0);
+                    counters[base - 1..level].iter().map(|c| c.to_string()).collect::<Vec<_>>().join(".")
+                };
+                sections.push(Section {
+                    number: explicit.unwrap_or(number),
+                    title,
+                    blocks: Vec::new(),
+                });
+            }
+            Parsed::Block(block) => sections.last_mut().expect("preamble section").blocks.push(block),
+        }
+    }
+    sections
+}
+
+/// Leading section number of a heading, e.g. "3.2." in "3.2. Overwegingen"
+fn split_number(heading: &str) -> (Option<String>, String) {
+    if let Some((first, rest)) = heading.split_once(char::is_whitespace) {
+        let number = first.trim_end_matches('.');
+        if !number.is_empty()
+            && number.split('.').all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+        {
+            return (Some(number.to_string()), rest.trim().to_string());
+        }
+    }
+    (None, heading.trim().to_string())
+}
+
+enum Parsed {
+    Heading(usize, String),
+    Block(Block),
+}
+
+fn heading(line: &str) -> Option<(usize, String)> {
+    let trimmed = line.trim_start();
+    let hashes = trimmed.chars().take_while(|c| *c == '#').count();
+    let rest = &trimmed[hashes..];
+    if (1..=6).contains(&hashes) && (rest.is_empty() || rest.starts_with(char::is_whitespace)) {
+        Some((hashes, rest.trim().trim_end_matches('#').trim().to_string()))
+    } else {
+        None
+    }
+}
+
+fn is_list_item(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("- ") || trimmed.starts_with("* ") || trimmed.starts_with("+ ") {
+        return true;
+    }
+    let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+    digits > 0 && (trimmed[digits..].starts_with(". ") || trimmed[digits..].starts_with(") "))
+}
+
+fn is_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.len() >= 3
+        && ['-', '*', '_'].iter().any(|marker| compact.chars().all(|c| c == *marker))
+}
+
+fn fence(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    ["```", "~~~"].into_iter().find(|f| trimmed.starts_with(f))
+}
+
+fn block(kind: BlockKind, lines: &[&str]) -> Parsed {
+    Parsed::Block(Block { kind, text: lines.join("\n").trim_end().to_string() })
+}
+
+/// Headings and blocks in document order
+fn parse_blocks(markdown: &str) -> Vec<Parsed> {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut parsed = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        let start = i;
+
+        if line.trim().is_empty() {
+            i += 1;
+        } else if let Some(marker) = fence(line) {
+            i += 1;
+            while i < lines.len() && !lines[i].trim_start().starts_with(marker) {
+                i += 1;
+            }
+            i = (i + 1).min(lines.len());
+            parsed.push(block(BlockKind::Code, &lines[start..i]));
+        } else if let Some((level, text)) = heading(line) {
+            i += 1;
+            parsed.push(Parsed::Heading(level, text));
+        } else if is_rule(line) {
+            i += 1;
+            parsed.push(block(BlockKind::Rule, &lines[start..i]));
+        } else if line.trim_start().starts_with('|') {
+            while i < lines.len() && lines[i].trim_start().starts_with('|') {
+                i += 1;
+            }
+            parsed.push(block(BlockKind::Table, &lines[start..i]));
+        } else if line.trim_start().starts_with('>') {
+            while i < lines.len() && lines[i].trim_start().starts_with('>') {
+                i += 1;
+            }
+            parsed.push(block(BlockKind::Quote, &lines[start..i]));
+        } else if is_list_item(line) {
+            // One block per item, including indented continuation lines
+            i += 1;
+            while i < lines.len()
+                && !lines[i].trim().is_empty()
+                && !is_list_item(lines[i])
+                && lines[i].starts_with(char::is_whitespace)
+            {
+                i += 1;
+            }
+            parsed.push(block(BlockKind::ListItem, &lines[start..i]));
+        } else {
+            i += 1;
+            while i < lines.len()
+                && !lines[i].trim().is_empty()
+                && fence(lines[i]).is_none()
+                && heading(lines[i]).is_none()
+                && !is_list_item(lines[i])
+                && !lines[i].trim_start().starts_with('|')
+                && !lines[i].trim_start().starts_with('>')
+            {
+                i += 1;
+            }
+            parsed.push(block(BlockKind::Paragraph, &lines[start..i]));
+        }
+    }
+
+    parsed
+}
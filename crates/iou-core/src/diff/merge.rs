@@ -0,0 +1,269 @@
+//! Three-way merge of document versions
+//!
+//! Both sides are diffed line by line against their common ancestor.
+//! Changes that touch different parts of the ancestor are combined;
+//! changes to the same or adjacent lines are a conflict unless both sides
+//! made the identical change. Conflicts are written into the merged text
+//! with git-style markers and returned separately for the UI.
+
+use serde::{Deserialize, Serialize};
+use similar::{DiffOp, TextDiff};
+
+use super::generator::DiffGenerator;
+
+/// Start of the "ours" side of a conflict
+pub const MARKER_OURS: &str = "<<<<<<<";
+/// Separator between the two sides of a conflict
+pub const MARKER_SEPARATOR: &str = "=======";
+/// End of the "theirs" side of a conflict
+pub const MARKER_THEIRS: &str = ">>>>>>>";
+
+/// A region both sides changed differently
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergeConflict {
+    /// First line of the region in the ancestor (1-based)
+    pub base_line: usize,
+    /// Line of the opening marker in the merged text (1-based)
+    pub merged_line: usize,
+    pub base: String,
+    pub ours: String,
+    pub theirs: String,
+}
+
+/// Outcome of a three-way merge
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergeResult {
+    /// Merged text, with conflict markers where `conflicts` is not empty
+    pub merged: String,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl MergeResult {
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Lines `start..end` of the ancestor replaced by `lines`
+#[derive(Debug)]
+struct Hunk<'a> {
+    start: usize,
+    end: usize,
+    lines: Vec<&'a str>,
+}
+
+impl DiffGenerator {
+    /// Merge `ours` and `theirs`, both derived from `base`
+    ///
+    /// `ours_label` and `theirs_label` appear after the conflict markers.
+    pub fn three_way_merge(
+        &self,
+        base: &str,
+        ours: &str,
+        theirs: &str,
+        ours_label: &str,
+        theirs_label: &str,
+    ) -> MergeResult {
+        let base_lines: Vec<&str> = base.split_inclusive('\n').collect();
+        let ours_hunks = hunks(base, ours);
+        let theirs_hunks = hunks(base, theirs);
+
+        let mut merged = String::with_capacity(base.len().max(ours.len()).max(theirs.len()));
+        let mut merged_lines = 0;
+        let mut conflicts = Vec::new();
+        let (mut i, mut j, mut pos) = (0, 0, 0);
+
+        loop {
+            let next_ours = ours_hunks.get(i).map(|h| h.start);
+            let next_theirs = theirs_hunks.get(j).map(|h| h.start);
+            let start = match (next_ours, next_theirs) {
+                (None, None) => break,
+                (Some(a), Some(b)) => a.min(b),
+                (Some(a), None) => a,
+                (None, Some(b)) => b,
+            };
+
+            // Grow the cluster while hunks of either side touch it
+            let mut end = start;
+            let (first_ours, first_theirs) = (i, j);
+            loop {
+                let mut grown = false;
+                while let Some(h) = ours_hunks.get(i).filter(|h| h.start <= end) {
+                    end = end.max(h.end);
+                    i += 1;
+                    grown = true;
+                }
+                while let Some(h) = theirs_hunks.get(j).filter(|h| h.start <= end) {
+                    end = end.max(h.end);
+                    j += 1;
+                    grown = true;
+                }
+                if !grown {
+                    break;
+                }
+            }
+
+            for line in &base_lines[pos..start] {
+                push(&mut merged, &mut merged_lines, line);
+            }
+            pos = end;
+
+            let ours_cluster = &ours_hunks[first_ours..i];
+            let theirs_cluster = &theirs_hunks[first_theirs..j];
+            let ours_text = apply(&base_lines, start, end, ours_cluster);
+            let theirs_text = apply(&base_lines, start, end, theirs_cluster);
+
+            if theirs_cluster.is_empty() || ours_text == theirs_text {
+                push(&mut merged, &mut merged_lines, &ours_text);
+            } else if ours_cluster.is_empty() {
+                push(&mut merged, &mut merged_lines, &theirs_text);
+            } else {
+                conflicts.push(MergeConflict {
+                    base_line: start + 1,
+                    merged_line: merged_lines + 1,
+                    base: base_lines[start..end].concat(),
+                    ours: ours_text.clone(),
+                    theirs: theirs_text.clone(),
+                });
+                push(&mut merged, &mut merged_lines, &format!("{} {}\n", MARKER_OURS, ours_label));
+                push_terminated(&mut merged, &mut merged_lines, &ours_text);
+                push(&mut merged, &mut merged_lines, &format!("{}\n", MARKER_SEPARATOR));
+                push_terminated(&mut merged, &mut merged_lines, &theirs_text);
+                push(&mut merged, &mut merged_lines, &format!("{} {}\n", MARKER_THEIRS, theirs_label));
+            }
+        }
+
+        for line in &base_lines[pos..] {
+            push(&mut merged, &mut merged_lines, line);
+        }
+
+        MergeResult { merged, conflicts }
+    }
+}
+
+/// Changes from `base` to `changed`, adjacent edits combined
+fn hunks<'a>(base: &str, changed: &'a str) -> Vec<Hunk<'a>> {
+    let diff = TextDiff::from_lines(base, changed);
+    let new_lines = diff.new_slices();
+    let mut found: Vec<Hunk<'a>> = Vec::new();
+
+    for op in diff.ops() {
+        let (start, end, lines) = match *op {
+            DiffOp::Equal { .. } => continue,
+            DiffOp::Delete { old_index, old_len, .. } => (old_index, old_index + old_len, Vec::new()),
+            DiffOp::Insert { old_index, new_index, new_len } => {
+                (old_index, old_index, new_lines[new_index..new_index + new_len].to_vec())
+            }
+            DiffOp::Replace { old_index, old_len, new_index, new_len } => (
+                old_index,
+                old_index + old_len,
+                new_lines[new_index..new_index + new_len].to_vec(),
+            ),
+        };
+        match found.last_mut() {
+            Some(last) if last.end == start => {
+                last.end = end;
+                last.lines.extend(lines);
+            }
+            _ => found.push(Hunk { start, end, lines }),
+        }
+    }
+    found
+}
+
+/// Ancestor lines `start..end` with one side's hunks applied
+fn apply(base_lines: &[&str], start: usize, end: usize, hunks: &[Hunk<'_>]) -> String {
+    let mut out = String::new();
+    let mut pos = start;
+    for hunk in hunks {
+        base_lines[pos..hunk.start].iter().for_each(|line| out.push_str(line));
+        hunk.lines.iter().for_each(|line| out.push_str(line));
+        pos = hunk.end;
+    }
+    base_lines[pos..end].iter().for_each(|line| out.push_str(line));
+    out
+}
+
+fn push(out: &mut String, lines: &mut usize, text: &str) {
+    out.push_str(text);
+    *lines += text.matches('\n').count();
+}
+
+/// Like `push`, but ends with a newline so a marker starts on its own line
+fn push_terminated(out: &mut String, lines: &mut usize, text: &str) {
+    push(out, lines, text);
+    if !text.is_empty() && !text.ends_with('\n') {
+        push(out, lines, "\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merge(base: &str, ours: &str, theirs: &str) -> MergeResult {
+        DiffGenerator.three_way_merge(base, ours, theirs, "ours", "theirs")
+    }
+
+    #[test]
+    fn test_non_overlapping_changes_merge() {
+        let base = "# Nota\n\nAanleiding.\n\nOverwegingen.\n\nBesluit.\n";
+        let ours = "# Nota\n\nAanleiding, aangevuld.\n\nOverwegingen.\n\nBesluit.\n";
+        let theirs = "# Nota\n\nAanleiding.\n\nOverwegingen.\n\nBesluit van het college.\n";
+
+        let result = merge(base, ours, theirs);
+        assert!(result.is_clean());
+        assert_eq!(
+            result.merged,
+            "# Nota\n\nAanleiding, aangevuld.\n\nOverwegingen.\n\nBesluit van het college.\n"
+        );
+    }
+
+    #[test]
+    fn test_identical_changes_merge_once() {
+        let result = merge("a\nb\nc\n", "a\nB\nc\n", "a\nB\nc\n");
+        assert!(result.is_clean());
+        assert_eq!(result.merged, "a\nB\nc\n");
+    }
+
+    #[test]
+    fn test_insertions_at_different_places() {
+        let result = merge("a\nb\nc\nd\n", "x\na\nb\nc\nd\n", "a\nb\nc\nd\ny\n");
+        assert!(result.is_clean());
+        assert_eq!(result.merged, "x\na\nb\nc\nd\ny\n");
+    }
+
+    #[test]
+    fn test_conflict_with_markers() {
+        let base = "titel\nbedrag: 100\nslot\n";
+        let ours = "titel\nbedrag: 150\nslot\n";
+        let theirs = "titel\nbedrag: 200\nslot\n";
+
+        let result = merge(base, ours, theirs);
+        assert_eq!(result.conflicts.len(), 1);
+        let conflict = &result.conflicts[0];
+        assert_eq!(conflict.base_line, 2);
+        assert_eq!(conflict.merged_line, 2);
+        assert_eq!(conflict.base, "bedrag: 100\n");
+        assert_eq!(conflict.ours, "bedrag: 150\n");
+        assert_eq!(conflict.theirs, "bedrag: 200\n");
+        assert_eq!(
+            result.merged,
+            "titel\n<<<<<<< ours\nbedrag: 150\n=======\nbedrag: 200\n>>>>>>> theirs\nslot\n"
+        );
+    }
+
+    #[test]
+    fn test_delete_versus_edit_conflicts() {
+        let result = merge("a\nb\nc\n", "a\nc\n", "a\nB\nc\n");
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].ours, "");
+        assert_eq!(result.conflicts[0].theirs, "B\n");
+    }
+
+    #[test]
+    fn test_missing_final_newline() {
+        let result = merge("a\nb", "a\nx", "a\ny");
+        assert_eq!(result.merged, "a\n<<<<<<< ours\nx\n=======\ny\n>>>>>>> theirs\n");
+    }
+}
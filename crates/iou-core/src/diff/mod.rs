@@ -1,8 +1,16 @@
 pub mod generator;
+pub mod markdown;
+pub mod merge;
 
 pub use generator::{
     DiffGenerator,
     DiffFormat,
     DocumentDiff,
     DiffChange,
+    SideBySideRow,
+};
+
+pub use merge::{MergeConflict, MergeResult};
+pub use markdown::{
+    BlockChange, BlockKind, CellChange, MarkdownDiff, MovedBlock, SectionDiff, SectionStatus,
 };
@@ -168,7 +168,7 @@ pub use setting::{
 pub use ssi::{
     VerifiableCredential, VerifiablePresentation, VCValidationError,
     Claims, ClaimValue, DIDResolver, DidMethod, DidDocument, DidKey,
-    parse_did, PresentationValidator, UniversalDidResolver,
+    parse_did, PresentationValidator, UniversalDidResolver, PresentationRequirements,
 };
 
 // Audit logging (use AuditEntry from document module, other types from audit)
@@ -179,7 +179,7 @@ pub use audit::{AuditAction, AuditOutcome, AuditFilter, AuditQuery};
 
 // Storage
 #[cfg(feature = "server")]
-pub use storage::{S3Client, S3Config, S3Error};
+pub use storage::{S3Client, S3Config, S3Error, S3Object};
 
 // Version storage
 #[cfg(feature = "server")]
@@ -2,20 +2,44 @@
 //!
 //! Calculates deadlines based on business hours, skipping weekends and
 //! configured holidays. Supports configurable weekend days for international
-//! use cases (e.g., Friday-Saturday weekend in Middle Eastern countries).
+//! use cases (e.g., Friday-Saturday weekend in Middle Eastern countries),
+//! working hours per weekday in a configured time zone, the Dutch public
+//! holidays, and calendar-day terms as used in Awb and Woo deadlines.
 
-use chrono::{DateTime, Datelike, Duration, Utc, Weekday};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc, Weekday};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
+use super::calendar::{SlaTimeZone, WorkingDay, WorkingHours};
+use super::holidays::{dutch_public_holiday, LiberationDay};
+
+/// Days searched for working time before giving up (ten years)
+const MAX_SEARCH_DAYS: i64 = 3660;
+
 /// Configuration for SLA calculation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SlaConfig {
     /// Days considered weekends (default: Saturday, Sunday)
     pub weekend_days: Vec<Weekday>,
 
-    /// Holiday dates (YYYY-MM-DD format)
+    /// Holiday and closure dates (YYYY-MM-DD format)
     pub holidays: HashSet<String>,
+
+    /// Working window per weekday (default: around the clock)
+    #[serde(default)]
+    pub working_hours: WorkingHours,
+
+    /// Time zone of working hours and dates (default: UTC)
+    #[serde(default)]
+    pub time_zone: SlaTimeZone,
+
+    /// Also skip the Dutch public holidays
+    #[serde(default)]
+    pub dutch_holidays: bool,
+
+    /// Whether 5 May counts when `dutch_holidays` is set
+    #[serde(default)]
+    pub liberation_day: LiberationDay,
 }
 
 impl Default for SlaConfig {
@@ -23,10 +47,63 @@ impl Default for SlaConfig {
         Self {
             weekend_days: vec![Weekday::Sat, Weekday::Sun],
             holidays: HashSet::new(),
+            working_hours: WorkingHours::default(),
+            time_zone: SlaTimeZone::default(),
+            dutch_holidays: false,
+            liberation_day: LiberationDay::default(),
         }
     }
 }
 
+impl SlaConfig {
+    /// Dutch government office: Monday to Friday 09:00-17:00 in
+    /// Europe/Amsterdam, skipping the Dutch public holidays
+    pub fn netherlands() -> Self {
+        Self {
+            working_hours: WorkingHours::weekdays(WorkingDay::hours(9, 17)),
+            time_zone: SlaTimeZone::EuropeAmsterdam,
+            dutch_holidays: true,
+            ..Self::default()
+        }
+    }
+}
+
+/// How a deadline is computed from its start
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DeadlineRule {
+    /// A number of working hours within the configured working hours
+    BusinessHours { hours: i32 },
+    /// A term in calendar days, ending at the end of the last day
+    ///
+    /// The term starts the day after the start (Awb art. 6:8 style). With
+    /// `next_working_day`, a term ending on a weekend day or holiday is
+    /// extended to the next working day (Algemene termijnenwet art. 1).
+    CalendarDays {
+        days: u32,
+        #[serde(default)]
+        extension_days: u32,
+        #[serde(default = "default_next_working_day")]
+        next_working_day: bool,
+    },
+}
+
+fn default_next_working_day() -> bool {
+    true
+}
+
+impl DeadlineRule {
+    /// A term of whole weeks, extended per the Algemene termijnenwet
+    pub fn weeks(weeks: u32) -> Self {
+        Self::CalendarDays { days: weeks * 7, extension_days: 0, next_working_day: true }
+    }
+
+    /// A term of whole weeks plus an extension of whole weeks
+    pub fn weeks_with_extension(weeks: u32, extension_weeks: u32) -> Self {
+        Self::CalendarDays { days: weeks * 7, extension_days: extension_weeks * 7, next_working_day: true }
+    }
+}
+
 /// SLA calculator for deadline computation
 pub struct SlaCalculator {
     config: SlaConfig,
@@ -47,10 +124,9 @@ impl SlaCalculator {
 
     /// Calculate deadline by adding business hours to start time
     ///
-    /// This implementation uses a simplified approach:
-    /// - Adds hours one at a time, skipping weekends
-    /// - Does NOT handle partial days or business hours within a day
-    /// - Holidays are checked but not fully implemented in initial version
+    /// Only time within the working hours of working days counts. Working
+    /// hours are local wall-clock time in the configured time zone, so a
+    /// 09:00-17:00 day is eight hours on DST transition days as well.
     ///
     /// # Arguments
     /// * `start` - The starting timestamp
@@ -63,30 +139,115 @@ impl SlaCalculator {
         start: DateTime<Utc>,
         business_hours: i32,
     ) -> DateTime<Utc> {
-        let mut current = start;
-        let mut hours_remaining = business_hours;
+        let tz = self.config.time_zone;
+        let mut remaining = Duration::hours(business_hours as i64);
+        let mut cursor = tz.to_local(start);
 
-        while hours_remaining > 0 {
-            current = current + Duration::hours(1);
+        if remaining <= Duration::zero() {
+            return start;
+        }
 
-            // Skip weekends and holidays
-            if !self.is_weekend(current) && !self.is_holiday(current) {
-                hours_remaining -= 1;
+        for _ in 0..MAX_SEARCH_DAYS {
+            let date = cursor.date();
+            if let Some(window) = self.working_window(date) {
+                let window_start = window.start_on(date);
+                let window_end = window.end_on(date);
+                if cursor < window_start {
+                    cursor = window_start;
+                }
+                if cursor < window_end {
+                    let available = window_end - cursor;
+                    if remaining <= available {
+                        return tz.from_local(cursor + remaining);
+                    }
+                    remaining -= available;
+                }
             }
+            cursor = (date + Duration::days(1)).and_time(NaiveTime::MIN);
         }
 
-        current
+        tz.from_local(cursor)
+    }
+
+    /// Deadline for a rule, see [`DeadlineRule`]
+    pub fn deadline_for(&self, start: DateTime<Utc>, rule: &DeadlineRule) -> DateTime<Utc> {
+        match *rule {
+            DeadlineRule::BusinessHours { hours } => self.calculate_deadline(start, hours),
+            DeadlineRule::CalendarDays { days, extension_days, next_working_day } => {
+                let tz = self.config.time_zone;
+                let mut last_day = tz.to_local(start).date() + Duration::days((days + extension_days) as i64);
+                if next_working_day {
+                    last_day = self.next_working_day(last_day);
+                }
+                let end_of_day = (last_day + Duration::days(1)).and_time(NaiveTime::MIN);
+                tz.from_local(end_of_day) - Duration::seconds(1)
+            }
+        }
+    }
+
+    /// First date on or after `date` that is not a weekend day or holiday
+    pub fn next_working_day(&self, date: NaiveDate) -> NaiveDate {
+        let mut date = date;
+        for _ in 0..MAX_SEARCH_DAYS {
+            if !self.is_weekend_date(date) && self.holiday_name(date).is_none() {
+                break;
+            }
+            date += Duration::days(1);
+        }
+        date
     }
 
     /// Check if a given date falls on a weekend
     pub fn is_weekend(&self, date: DateTime<Utc>) -> bool {
-        self.config.weekend_days.contains(&date.weekday())
+        self.is_weekend_date(self.config.time_zone.to_local(date).date())
     }
 
     /// Check if a given date is a holiday
     pub fn is_holiday(&self, date: DateTime<Utc>) -> bool {
-        let date_str = date.format("%Y-%m-%d").to_string();
-        self.config.holidays.contains(&date_str)
+        self.holiday_name(self.config.time_zone.to_local(date).date()).is_some()
+    }
+
+    /// Name of the holiday on a local date; configured dates are "Sluitingsdag"
+    pub fn holiday_name(&self, date: NaiveDate) -> Option<&'static str> {
+        if self.config.holidays.contains(&date.format("%Y-%m-%d").to_string()) {
+            return Some("Sluitingsdag");
+        }
+        if self.config.dutch_holidays {
+            return dutch_public_holiday(date, self.config.liberation_day);
+        }
+        None
+    }
+
+    fn is_weekend_date(&self, date: NaiveDate) -> bool {
+        self.config.weekend_days.contains(&date.weekday())
+    }
+
+    /// Working window of a local date, if it is a working day
+    fn working_window(&self, date: NaiveDate) -> Option<WorkingDay> {
+        if self.is_weekend_date(date) || self.holiday_name(date).is_some() {
+            return None;
+        }
+        self.config.working_hours.on(date.weekday())
+    }
+
+    /// Working time between two instants (zero if `end` is before `start`)
+    pub fn business_time_between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Duration {
+        let tz = self.config.time_zone;
+        let (start, end) = (tz.to_local(start), tz.to_local(end));
+        let mut total = Duration::zero();
+        let mut date = start.date();
+
+        while date <= end.date() {
+            if let Some(window) = self.working_window(date) {
+                let from = window.start_on(date).max(start);
+                let to = window.end_on(date).min(end);
+                if to > from {
+                    total += to - from;
+                }
+            }
+            date += Duration::days(1);
+        }
+        total
     }
 
     /// Check if deadline has passed
@@ -96,34 +257,19 @@ impl SlaCalculator {
 
     /// Calculate business hours until deadline
     ///
-    /// Counts only business hours (excluding weekends) between
-    /// current time and deadline. Returns negative if deadline is past.
+    /// Counts only working time between current time and deadline, rounded
+    /// up to whole hours. Returns negative if deadline is past.
     pub fn hours_until_deadline(&self, deadline: DateTime<Utc>) -> i32 {
-        let now = Utc::now();
-        let is_past = now > deadline;
-
-        let (start, end) = if is_past {
-            (deadline, now)
-        } else {
-            (now, deadline)
-        };
-
-        let mut current = start;
-        let mut business_hours = 0;
-
-        while current < end {
-            current = current + Duration::hours(1);
-
-            // Count only business hours (skip weekends and holidays)
-            if !self.is_weekend(current) && !self.is_holiday(current) {
-                business_hours += 1;
-            }
-        }
+        self.hours_until_deadline_at(deadline, Utc::now())
+    }
 
-        if is_past {
-            -business_hours
+    /// Business hours between `now` and the deadline, see [`Self::hours_until_deadline`]
+    pub fn hours_until_deadline_at(&self, deadline: DateTime<Utc>, now: DateTime<Utc>) -> i32 {
+        let whole_hours = |time: Duration| ((time.num_seconds() + 3599) / 3600) as i32;
+        if now > deadline {
+            -whole_hours(self.business_time_between(deadline, now))
         } else {
-            business_hours
+            whole_hours(self.business_time_between(now, deadline))
         }
     }
 
@@ -194,27 +340,6 @@ mod tests {
         assert!(!calculator.is_overdue(future));
     }
 
-    #[test]
-    fn test_hours_until_deadline_future() {
-        let calculator = create_calculator();
-
-        let deadline = Utc::now() + Duration::hours(24);
-        let hours = calculator.hours_until_deadline(deadline);
-
-        assert!(hours > 0);
-        assert!(hours <= 24); // May be less due to weekends
-    }
-
-    #[test]
-    fn test_hours_until_deadline_past() {
-        let calculator = create_calculator();
-
-        let deadline = Utc::now() - Duration::hours(24);
-        let hours = calculator.hours_until_deadline(deadline);
-
-        assert!(hours < 0);
-    }
-
     #[test]
     fn test_is_weekend() {
         let calculator = create_calculator();
@@ -280,4 +405,98 @@ mod tests {
             .with_timezone(&Utc);
         assert!(!calculator.is_holiday(regular_day));
     }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_office_hours_carry_over_to_next_day() {
+        let calculator = SlaCalculator::with_config(SlaConfig::netherlands());
+        // Tuesday 15:00 Amsterdam (CET) + 4 working hours = Wednesday 11:00
+        let deadline = calculator.calculate_deadline(utc("2026-01-13T14:00:00Z"), 4);
+        assert_eq!(deadline, utc("2026-01-14T10:00:00Z"));
+
+        // Before opening time counting starts at 09:00
+        let deadline = calculator.calculate_deadline(utc("2026-01-13T05:00:00Z"), 8);
+        assert_eq!(deadline, utc("2026-01-13T16:00:00Z"));
+    }
+
+    #[test]
+    fn test_office_hours_across_dst_and_holidays() {
+        let calculator = SlaCalculator::with_config(SlaConfig::netherlands());
+        // Thursday 2 April 2026 16:00 CEST; Good Friday is a working day,
+        // Easter Monday 6 April is not. 1 + 8 + 7 hours => Tuesday 16:00 CEST
+        let deadline = calculator.calculate_deadline(utc("2026-04-02T14:00:00Z"), 16);
+        assert_eq!(deadline, utc("2026-04-07T14:00:00Z"));
+
+        // Friday 27 March 16:00 CET + 2 hours, summer time starts on Sunday
+        let deadline = calculator.calculate_deadline(utc("2026-03-27T15:00:00Z"), 2);
+        assert_eq!(deadline, utc("2026-03-30T08:00:00Z"));
+    }
+
+    #[test]
+    fn test_dutch_holidays_are_holidays() {
+        let calculator = SlaCalculator::with_config(SlaConfig::netherlands());
+        assert!(calculator.is_holiday(utc("2026-04-27T10:00:00Z")));
+        assert!(calculator.is_holiday(utc("2026-05-14T10:00:00Z")));
+        assert!(!calculator.is_holiday(utc("2026-05-05T10:00:00Z")));
+        // 31 December 23:30 UTC is already New Year's Day in Amsterdam
+        assert!(calculator.is_holiday(utc("2025-12-31T23:30:00Z")));
+        assert!(!SlaCalculator::new().is_holiday(utc("2026-04-27T10:00:00Z")));
+    }
+
+    #[test]
+    fn test_calendar_rule_with_extension() {
+        let calculator = SlaCalculator::with_config(SlaConfig::netherlands());
+        // Received Monday 2 March 2026: 4 weeks ends Monday 30 March
+        let start = utc("2026-03-02T10:00:00Z");
+        let deadline = calculator.deadline_for(start, &DeadlineRule::weeks(4));
+        assert_eq!(deadline, utc("2026-03-30T21:59:59Z"));
+
+        // Plus 2 weeks extension ends Monday 13 April
+        let deadline = calculator.deadline_for(start, &DeadlineRule::weeks_with_extension(4, 2));
+        assert_eq!(deadline, utc("2026-04-13T21:59:59Z"));
+    }
+
+    #[test]
+    fn test_calendar_rule_moves_past_weekend_and_holiday() {
+        let calculator = SlaCalculator::with_config(SlaConfig::netherlands());
+        // Six weeks from Saturday 21 Feb 2026 ends on Easter Saturday 4 April;
+        // Sunday and Easter Monday follow, so the term ends Tuesday 7 April
+        let deadline = calculator.deadline_for(utc("2026-02-21T10:00:00Z"), &DeadlineRule::weeks(6));
+        assert_eq!(deadline, utc("2026-04-07T21:59:59Z"));
+
+        let rule = DeadlineRule::CalendarDays { days: 42, extension_days: 0, next_working_day: false };
+        let deadline = calculator.deadline_for(utc("2026-02-21T10:00:00Z"), &rule);
+        assert_eq!(deadline, utc("2026-04-04T21:59:59Z"));
+    }
+
+    #[test]
+    fn test_hours_until_deadline_future() {
+        let calculator = SlaCalculator::with_config(SlaConfig::netherlands());
+        // Friday 16:00 CET to Monday 10:30: 2.5 working hours, rounded up
+        let now = utc("2026-01-09T15:00:00Z");
+        assert_eq!(calculator.hours_until_deadline_at(utc("2026-01-12T09:30:00Z"), now), 3);
+
+        // Nothing to count over the weekend itself
+        let saturday = utc("2026-01-10T10:00:00Z");
+        assert_eq!(calculator.hours_until_deadline_at(utc("2026-01-11T10:00:00Z"), saturday), 0);
+    }
+
+    #[test]
+    fn test_hours_until_deadline_past() {
+        let calculator = SlaCalculator::with_config(SlaConfig::netherlands());
+        let now = utc("2026-01-12T09:30:00Z");
+        assert_eq!(calculator.hours_until_deadline_at(utc("2026-01-09T15:00:00Z"), now), -3);
+    }
+
+    #[test]
+    fn test_business_time_between() {
+        let calculator = SlaCalculator::with_config(SlaConfig::netherlands());
+        let worked = calculator.business_time_between(utc("2026-01-09T15:00:00Z"), utc("2026-01-12T09:30:00Z"));
+        // Friday 16:00-17:00 and Monday 09:00-10:30
+        assert_eq!(worked, Duration::minutes(150));
+    }
 }
+
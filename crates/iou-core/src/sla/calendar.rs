@@ -0,0 +1,263 @@
+//! Working hours and time zones for SLA calculation
+//!
+//! Working time is measured in local wall-clock time. Only UTC and
+//! Europe/Amsterdam are supported; Amsterdam follows the EU summer time
+//! rule (last Sunday of March to last Sunday of October, switching at
+//! 01:00 UTC), which is exact from 1996 onwards.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Time zone in which working hours and calendar days are interpreted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SlaTimeZone {
+    #[default]
+    #[serde(rename = "UTC")]
+    Utc,
+    #[serde(rename = "Europe/Amsterdam")]
+    EuropeAmsterdam,
+}
+
+impl SlaTimeZone {
+    /// UTC offset in effect at `utc`
+    pub fn offset_at(&self, utc: DateTime<Utc>) -> Duration {
+        match self {
+            Self::Utc => Duration::zero(),
+            Self::EuropeAmsterdam => {
+                let year = utc.year();
+                let start = Utc.from_utc_datetime(&last_sunday(year, 3).and_hms_opt(1, 0, 0).expect("valid time"));
+                let end = Utc.from_utc_datetime(&last_sunday(year, 10).and_hms_opt(1, 0, 0).expect("valid time"));
+                if utc >= start && utc < end {
+                    Duration::hours(2)
+                } else {
+                    Duration::hours(1)
+                }
+            }
+        }
+    }
+
+    /// Local wall-clock time of an instant
+    pub fn to_local(&self, utc: DateTime<Utc>) -> NaiveDateTime {
+        utc.naive_utc() + self.offset_at(utc)
+    }
+
+    /// Instant of a local wall-clock time
+    ///
+    /// Times skipped by the spring transition resolve to the same
+    /// wall-clock time under the old offset (i.e. one hour later in summer
+    /// time); repeated times in autumn resolve to the first occurrence.
+    pub fn from_local(&self, local: NaiveDateTime) -> DateTime<Utc> {
+        // Try the larger offset first so the earliest instant wins
+        let candidates = match self {
+            Self::Utc => vec![Duration::zero()],
+            Self::EuropeAmsterdam => vec![Duration::hours(2), Duration::hours(1)],
+        };
+        for offset in &candidates {
+            let utc = Utc.from_utc_datetime(&(local - *offset));
+            if self.offset_at(utc) == *offset {
+                return utc;
+            }
+        }
+        Utc.from_utc_datetime(&(local - candidates[candidates.len() - 1]))
+    }
+}
+
+fn last_sunday(year: i32, month: u32) -> NaiveDate {
+    let next_month = if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1)
+    }
+    .expect("valid date");
+    let last = next_month - Duration::days(1);
+    last - Duration::days(last.weekday().num_days_from_sunday() as i64)
+}
+
+/// Working window of a single day, in minutes from local midnight
+///
+/// Written as `"HH:MM-HH:MM"`; the end may be `24:00`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct WorkingDay {
+    pub start_minute: u32,
+    pub end_minute: u32,
+}
+
+impl WorkingDay {
+    /// The whole day, 00:00-24:00
+    pub const ALL_DAY: Self = Self { start_minute: 0, end_minute: 24 * 60 };
+
+    /// Window from `start` to `end` hours, e.g. `WorkingDay::hours(9, 17)`
+    pub fn hours(start: u32, end: u32) -> Self {
+        Self { start_minute: start * 60, end_minute: end * 60 }
+    }
+
+    /// Working minutes in the window
+    pub fn minutes(&self) -> u32 {
+        self.end_minute - self.start_minute
+    }
+
+    pub(crate) fn start_on(&self, date: NaiveDate) -> NaiveDateTime {
+        date.and_time(NaiveTime::MIN) + Duration::minutes(self.start_minute as i64)
+    }
+
+    pub(crate) fn end_on(&self, date: NaiveDate) -> NaiveDateTime {
+        date.and_time(NaiveTime::MIN) + Duration::minutes(self.end_minute as i64)
+    }
+}
+
+impl TryFrom<String> for WorkingDay {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let parse = |time: &str| -> Result<u32, String> {
+            let (h, m) = time
+                .trim()
+                .split_once(':')
+                .ok_or_else(|| format!("Invalid time '{}', expected HH:MM", time))?;
+            let h: u32 = h.parse().map_err(|_| format!("Invalid hour in '{}'", time))?;
+            let m: u32 = m.parse().map_err(|_| format!("Invalid minute in '{}'", time))?;
+            if m >= 60 || h * 60 + m > 24 * 60 {
+                return Err(format!("Time '{}' out of range", time));
+            }
+            Ok(h * 60 + m)
+        };
+
+        let (start, end) = value
+            .split_once('-')
+            .ok_or_else(|| format!("Invalid working hours '{}', expected HH:MM-HH:MM", value))?;
+        let (start_minute, end_minute) = (parse(start)?, parse(end)?);
+        if start_minute >= end_minute {
+            return Err(format!("Working hours '{}' end before they start", value));
+        }
+        Ok(Self { start_minute, end_minute })
+    }
+}
+
+impl From<WorkingDay> for String {
+    fn from(day: WorkingDay) -> Self {
+        day.to_string()
+    }
+}
+
+impl fmt::Display for WorkingDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02}:{:02}-{:02}:{:02}",
+            self.start_minute / 60,
+            self.start_minute % 60,
+            self.end_minute / 60,
+            self.end_minute % 60
+        )
+    }
+}
+
+/// Working window per weekday; a missing day has no working hours
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkingHours {
+    #[serde(default)]
+    pub monday: Option<WorkingDay>,
+    #[serde(default)]
+    pub tuesday: Option<WorkingDay>,
+    #[serde(default)]
+    pub wednesday: Option<WorkingDay>,
+    #[serde(default)]
+    pub thursday: Option<WorkingDay>,
+    #[serde(default)]
+    pub friday: Option<WorkingDay>,
+    #[serde(default)]
+    pub saturday: Option<WorkingDay>,
+    #[serde(default)]
+    pub sunday: Option<WorkingDay>,
+}
+
+impl WorkingHours {
+    /// Every day around the clock; weekends are excluded separately
+    pub fn all_day() -> Self {
+        Self::uniform(Some(WorkingDay::ALL_DAY), Some(WorkingDay::ALL_DAY))
+    }
+
+    /// The same window Monday to Friday, nothing in the weekend
+    pub fn weekdays(day: WorkingDay) -> Self {
+        Self::uniform(Some(day), None)
+    }
+
+    fn uniform(weekday: Option<WorkingDay>, weekend: Option<WorkingDay>) -> Self {
+        Self {
+            monday: weekday,
+            tuesday: weekday,
+            wednesday: weekday,
+            thursday: weekday,
+            friday: weekday,
+            saturday: weekend,
+            sunday: weekend,
+        }
+    }
+
+    /// Working window on a weekday
+    pub fn on(&self, weekday: Weekday) -> Option<WorkingDay> {
+        match weekday {
+            Weekday::Mon => self.monday,
+            Weekday::Tue => self.tuesday,
+            Weekday::Wed => self.wednesday,
+            Weekday::Thu => self.thursday,
+            Weekday::Fri => self.friday,
+            Weekday::Sat => self.saturday,
+            Weekday::Sun => self.sunday,
+        }
+    }
+}
+
+impl Default for WorkingHours {
+    fn default() -> Self {
+        Self::all_day()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn local(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_amsterdam_offsets() {
+        let tz = SlaTimeZone::EuropeAmsterdam;
+        assert_eq!(tz.to_local(utc("2026-01-15T08:00:00Z")), local("2026-01-15 09:00"));
+        assert_eq!(tz.to_local(utc("2026-07-15T08:00:00Z")), local("2026-07-15 10:00"));
+        // Summer time 2026: 29 March 01:00 UTC until 25 October 01:00 UTC
+        assert_eq!(tz.to_local(utc("2026-03-29T00:59:00Z")), local("2026-03-29 01:59"));
+        assert_eq!(tz.to_local(utc("2026-03-29T01:00:00Z")), local("2026-03-29 03:00"));
+        assert_eq!(tz.to_local(utc("2026-10-25T00:59:00Z")), local("2026-10-25 02:59"));
+        assert_eq!(tz.to_local(utc("2026-10-25T01:00:00Z")), local("2026-10-25 02:00"));
+    }
+
+    #[test]
+    fn test_amsterdam_from_local() {
+        let tz = SlaTimeZone::EuropeAmsterdam;
+        assert_eq!(tz.from_local(local("2026-07-15 10:00")), utc("2026-07-15T08:00:00Z"));
+        assert_eq!(tz.from_local(local("2026-12-15 17:00")), utc("2026-12-15T16:00:00Z"));
+        // Skipped hour and repeated hour
+        assert_eq!(tz.from_local(local("2026-03-29 02:30")), utc("2026-03-29T01:30:00Z"));
+        assert_eq!(tz.from_local(local("2026-10-25 02:30")), utc("2026-10-25T00:30:00Z"));
+    }
+
+    #[test]
+    fn test_working_day_parsing() {
+        let day = WorkingDay::try_from("08:30-17:00".to_string()).unwrap();
+        assert_eq!(day, WorkingDay { start_minute: 510, end_minute: 1020 });
+        assert_eq!(day.to_string(), "08:30-17:00");
+        assert_eq!(WorkingDay::try_from("00:00-24:00".to_string()).unwrap(), WorkingDay::ALL_DAY);
+        assert!(WorkingDay::try_from("17:00-09:00".to_string()).is_err());
+        assert!(WorkingDay::try_from("9-17".to_string()).is_err());
+        assert!(WorkingDay::try_from("09:00-24:30".to_string()).is_err());
+    }
+}
@@ -0,0 +1,155 @@
+//! Dutch public holidays
+//!
+//! Generates the generally recognised holidays of the Algemene
+//! termijnenwet plus Easter and Whit Sunday. Moveable feasts are derived
+//! from Easter (Gregorian computus). Koningsdag is 27 April, or the
+//! Saturday before when that is a Sunday; before 2014 Koninginnedag on
+//! 30 April with the same rule. Bevrijdingsdag (5 May) is by default only
+//! a day off in lustrum years, as in the government collective agreement.
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// Whether Bevrijdingsdag (5 May) counts as a holiday
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LiberationDay {
+    /// Only in years divisible by five (2025, 2030, ...)
+    #[default]
+    Lustrum,
+    /// Every year
+    Yearly,
+    /// Never
+    Never,
+}
+
+/// A named public holiday
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub name: &'static str,
+}
+
+/// Easter Sunday of a Gregorian year (anonymous Gregorian algorithm)
+pub fn easter_sunday(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32).expect("computus yields a valid date")
+}
+
+/// King's (or Queen's) Day of a year
+pub fn koningsdag(year: i32) -> NaiveDate {
+    let day = if year >= 2014 { 27 } else { 30 };
+    let date = NaiveDate::from_ymd_opt(year, 4, day).expect("valid date");
+    if date.weekday() == Weekday::Sun {
+        date - Duration::days(1)
+    } else {
+        date
+    }
+}
+
+/// Public holidays of a year in date order
+pub fn dutch_public_holidays(year: i32, liberation_day: LiberationDay) -> Vec<Holiday> {
+    let fixed = |month, day| NaiveDate::from_ymd_opt(year, month, day).expect("valid date");
+    let easter = easter_sunday(year);
+
+    let mut holidays = vec![
+        Holiday { date: fixed(1, 1), name: "Nieuwjaarsdag" },
+        Holiday { date: easter, name: "Eerste Paasdag" },
+        Holiday { date: easter + Duration::days(1), name: "Tweede Paasdag" },
+        Holiday {
+            date: koningsdag(year),
+            name: if year >= 2014 { "Koningsdag" } else { "Koninginnedag" },
+        },
+        Holiday { date: easter + Duration::days(39), name: "Hemelvaartsdag" },
+        Holiday { date: easter + Duration::days(49), name: "Eerste Pinksterdag" },
+        Holiday { date: easter + Duration::days(50), name: "Tweede Pinksterdag" },
+        Holiday { date: fixed(12, 25), name: "Eerste Kerstdag" },
+        Holiday { date: fixed(12, 26), name: "Tweede Kerstdag" },
+    ];
+
+    let liberation = match liberation_day {
+        LiberationDay::Lustrum => year % 5 == 0,
+        LiberationDay::Yearly => true,
+        LiberationDay::Never => false,
+    };
+    if liberation {
+        holidays.push(Holiday { date: fixed(5, 5), name: "Bevrijdingsdag" });
+    }
+
+    holidays.sort_by_key(|h| h.date);
+    holidays
+}
+
+/// Name of the public holiday on `date`, if any
+pub fn dutch_public_holiday(date: NaiveDate, liberation_day: LiberationDay) -> Option<&'static str> {
+    dutch_public_holidays(date.year(), liberation_day)
+        .into_iter()
+        .find(|h| h.date == date)
+        .map(|h| h.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_easter_dates() {
+        assert_eq!(easter_sunday(2024), date(2024, 3, 31));
+        assert_eq!(easter_sunday(2025), date(2025, 4, 20));
+        assert_eq!(easter_sunday(2026), date(2026, 4, 5));
+        assert_eq!(easter_sunday(2038), date(2038, 4, 25));
+    }
+
+    #[test]
+    fn test_koningsdag_on_sunday_moves_to_saturday() {
+        assert_eq!(koningsdag(2025), date(2025, 4, 26));
+        assert_eq!(koningsdag(2026), date(2026, 4, 27));
+        assert_eq!(koningsdag(2006), date(2006, 4, 29));
+    }
+
+    #[test]
+    fn test_liberation_day_in_lustrum_years() {
+        assert_eq!(dutch_public_holiday(date(2025, 5, 5), LiberationDay::Lustrum), Some("Bevrijdingsdag"));
+        assert_eq!(dutch_public_holiday(date(2026, 5, 5), LiberationDay::Lustrum), None);
+        assert_eq!(dutch_public_holiday(date(2026, 5, 5), LiberationDay::Yearly), Some("Bevrijdingsdag"));
+    }
+
+    #[test]
+    fn test_holidays_2026() {
+        let dates: Vec<NaiveDate> = dutch_public_holidays(2026, LiberationDay::Lustrum)
+            .into_iter()
+            .map(|h| h.date)
+            .collect();
+        assert_eq!(
+            dates,
+            vec![
+                date(2026, 1, 1),
+                date(2026, 4, 5),
+                date(2026, 4, 6),
+                date(2026, 4, 27),
+                date(2026, 5, 14),
+                date(2026, 5, 24),
+                date(2026, 5, 25),
+                date(2026, 12, 25),
+                date(2026, 12, 26),
+            ]
+        );
+    }
+}
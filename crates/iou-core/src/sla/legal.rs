@@ -0,0 +1,519 @@
+//! Legal decision terms for Woo requests and Awb objections
+//!
+//! Replays the events of a case (extension, third-party consultation,
+//! suspension, postponement, notice of default, decision) against the
+//! statutory term and records every change of the due date with its legal
+//! basis. Terms are in calendar days: a term of N weeks starting on day D
+//! ends at the end of D + N weeks, extended to the next working day when
+//! that is a weekend day or public holiday (Algemene termijnenwet).
+//! Suspensions add the suspended days to the term.
+//!
+//! After the due date has passed, a written notice of default
+//! (ingebrekestelling) starts the penalty timeline of Awb art. 4:17: two
+//! weeks after receipt a penalty accrues per day, €23 for the first 14 days,
+//! €35 for the next 14 and €45 after that, for at most 42 days (€1,442).
+
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::calculator::{SlaCalculator, SlaConfig};
+
+/// Maximum number of days a penalty accrues (Awb art. 4:17 lid 3)
+pub const DWANGSOM_MAX_DAYS: i64 = 42;
+
+/// Penalty per day in euros, per 14-day band (Awb art. 4:17 lid 2)
+pub const DWANGSOM_DAILY_RATES: [u32; 3] = [23, 35, 45];
+
+/// Kind of proceeding and its statutory term
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Procedure {
+    /// Request under the Wet open overheid: 4 weeks, extendable by 2
+    WooVerzoek,
+    /// Objection under the Awb: 6 weeks, or 12 with an advisory
+    /// committee, extendable by 6
+    Bezwaar { advisory_committee: bool },
+}
+
+impl Procedure {
+    fn term_weeks(&self) -> i64 {
+        match self {
+            Self::WooVerzoek => 4,
+            Self::Bezwaar { advisory_committee: false } => 6,
+            Self::Bezwaar { advisory_committee: true } => 12,
+        }
+    }
+
+    fn term_basis(&self) -> &'static str {
+        match self {
+            Self::WooVerzoek => "art. 4.4 lid 1 Woo",
+            Self::Bezwaar { .. } => "art. 7:10 lid 1 Awb",
+        }
+    }
+
+    fn extension_weeks(&self) -> i64 {
+        match self {
+            Self::WooVerzoek => 2,
+            Self::Bezwaar { .. } => 6,
+        }
+    }
+
+    fn extension_basis(&self) -> &'static str {
+        match self {
+            Self::WooVerzoek => "art. 4.4 lid 2 Woo",
+            Self::Bezwaar { .. } => "art. 7:10 lid 3 Awb",
+        }
+    }
+
+    fn postponement_basis(&self) -> &'static str {
+        match self {
+            Self::WooVerzoek => "art. 4:15 lid 2 onder a Awb",
+            Self::Bezwaar { .. } => "art. 7:10 lid 4 Awb",
+        }
+    }
+}
+
+/// Something that happened in a case, on a date
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CaseEvent {
+    /// The statutory extension (verdaging) was announced
+    Extended { date: NaiveDate },
+    /// A third party was asked for its view (zienswijze)
+    ConsultationStarted { date: NaiveDate },
+    /// The view was received or its term expired
+    ConsultationEnded { date: NaiveDate },
+    /// The term was suspended (opschorting), e.g. pending missing information
+    Suspended { date: NaiveDate, reason: String },
+    /// The suspension ended
+    Resumed { date: NaiveDate },
+    /// Further postponement with the requester's consent
+    Postponed { date: NaiveDate, weeks: u32 },
+    /// A written notice of default was received
+    NoticeOfDefault { date: NaiveDate },
+    /// The decision was made
+    Decided { date: NaiveDate },
+}
+
+impl CaseEvent {
+    pub fn date(&self) -> NaiveDate {
+        match self {
+            Self::Extended { date }
+            | Self::ConsultationStarted { date }
+            | Self::ConsultationEnded { date }
+            | Self::Suspended { date, .. }
+            | Self::Resumed { date }
+            | Self::Postponed { date, .. }
+            | Self::NoticeOfDefault { date }
+            | Self::Decided { date } => *date,
+        }
+    }
+}
+
+/// A case to evaluate
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LegalCase {
+    pub procedure: Procedure,
+    /// Day the term starts from: receipt of the Woo request, or the last
+    /// day of the objection period (see [`objection_period_end`])
+    pub start: NaiveDate,
+    /// Events in any order; they are replayed by date
+    pub events: Vec<CaseEvent>,
+}
+
+/// One change of the due date
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeadlineChange {
+    pub date: NaiveDate,
+    pub description: String,
+    pub legal_basis: String,
+    /// Due date after this change; `None` while the term is suspended
+    pub due_date: Option<NaiveDate>,
+}
+
+/// Penalty for not deciding in time
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Dwangsom {
+    pub notice_received: NaiveDate,
+    /// First day the penalty accrues
+    pub accrues_from: NaiveDate,
+    /// Days accrued so far (at most [`DWANGSOM_MAX_DAYS`])
+    pub days: i64,
+    /// Amount accrued so far in euros
+    pub amount_eur: u32,
+    /// Amount when the maximum is reached
+    pub max_amount_eur: u32,
+}
+
+/// Evaluation of a case on a reference date
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeadlineStatus {
+    /// Legal due date (end of that day); `None` while suspended
+    pub due_date: Option<NaiveDate>,
+    pub suspended: bool,
+    pub decided_on: Option<NaiveDate>,
+    /// Days past the due date on the reference date or decision date
+    pub days_overdue: i64,
+    pub history: Vec<DeadlineChange>,
+    pub dwangsom: Option<Dwangsom>,
+}
+
+/// Invalid event sequences
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum LegalDeadlineError {
+    #[error("Event on {0} is before the start of the term")]
+    BeforeStart(NaiveDate),
+
+    #[error("Extension on {date} is too late: the term ended on {due_date}")]
+    LateExtension { date: NaiveDate, due_date: NaiveDate },
+
+    #[error("The term was already extended")]
+    AlreadyExtended,
+
+    #[error("{0} without a matching start")]
+    UnmatchedEnd(&'static str),
+
+    #[error("{0} started twice")]
+    AlreadyStarted(&'static str),
+
+    #[error("Event on {0} after the decision")]
+    AfterDecision(NaiveDate),
+}
+
+/// Last day of the six-week objection period for a decision sent on `sent`
+/// (Awb art. 6:7 and 6:8), extended per the Algemene termijnenwet
+pub fn objection_period_end(calculator: &SlaCalculator, sent: NaiveDate) -> NaiveDate {
+    calculator.next_working_day(sent + Duration::weeks(6))
+}
+
+/// Penalty in euros for `days` days of default
+pub fn dwangsom_amount(days: i64) -> u32 {
+    let days = days.clamp(0, DWANGSOM_MAX_DAYS);
+    DWANGSOM_DAILY_RATES
+        .iter()
+        .enumerate()
+        .map(|(band, rate)| (days - band as i64 * 14).clamp(0, 14) as u32 * rate)
+        .sum()
+}
+
+/// Computes legal due dates on top of an [`SlaCalculator`] calendar
+pub struct LegalDeadlineEngine {
+    calculator: SlaCalculator,
+}
+
+impl LegalDeadlineEngine {
+    pub fn new(calculator: SlaCalculator) -> Self {
+        Self { calculator }
+    }
+
+    /// Engine on the Dutch calendar, see [`SlaConfig::netherlands`]
+    pub fn netherlands() -> Self {
+        Self::new(SlaCalculator::with_config(SlaConfig::netherlands()))
+    }
+
+    pub fn calculator(&self) -> &SlaCalculator {
+        &self.calculator
+    }
+
+    /// Due date of a new case without events
+    pub fn initial_due_date(&self, procedure: Procedure, start: NaiveDate) -> NaiveDate {
+        self.calculator.next_working_day(start + Duration::weeks(procedure.term_weeks()))
+    }
+
+    /// Replay the events of a case up to `as_of`
+    pub fn evaluate(&self, case: &LegalCase, as_of: NaiveDate) -> Result<DeadlineStatus, LegalDeadlineError> {
+        let procedure = case.procedure;
+        let mut events: Vec<&CaseEvent> = case.events.iter().collect();
+        events.sort_by_key(|e| e.date());
+
+        // Raw end of the term before the termijnenwet, plus days suspended
+        let mut term_end = case.start + Duration::weeks(procedure.term_weeks());
+        let mut due = self.calculator.next_working_day(term_end);
+        let mut history = vec![DeadlineChange {
+            date: case.start,
+            description: format!("Beslistermijn van {} weken gaat lopen", procedure.term_weeks()),
+            legal_basis: procedure.term_basis().to_string(),
+            due_date: Some(due),
+        }];
+
+        let mut extended = false;
+        let mut consultation_since: Option<NaiveDate> = None;
+        let mut suspension_since: Option<NaiveDate> = None;
+        let mut suspended_since: Option<NaiveDate> = None;
+        let mut decided_on: Option<NaiveDate> = None;
+        let mut notice: Option<NaiveDate> = None;
+
+        for event in events {
+            let date = event.date();
+            if date < case.start {
+                return Err(LegalDeadlineError::BeforeStart(date));
+            }
+            if decided_on.is_some() {
+                return Err(LegalDeadlineError::AfterDecision(date));
+            }
+
+            let (description, basis) = match event {
+                CaseEvent::Extended { .. } => {
+                    if extended {
+                        return Err(LegalDeadlineError::AlreadyExtended);
+                    }
+                    if suspended_since.is_none() && date > due {
+                        return Err(LegalDeadlineError::LateExtension { date, due_date: due });
+                    }
+                    extended = true;
+                    term_end += Duration::weeks(procedure.extension_weeks());
+                    (
+                        format!("Beslistermijn verdaagd met {} weken", procedure.extension_weeks()),
+                        procedure.extension_basis().to_string(),
+                    )
+                }
+                CaseEvent::ConsultationStarted { .. } => {
+                    if consultation_since.is_some() {
+                        return Err(LegalDeadlineError::AlreadyStarted("Zienswijzeprocedure"));
+                    }
+                    consultation_since = Some(date);
+                    suspended_since.get_or_insert(date);
+                    (
+                        "Derde-belanghebbende gevraagd om zienswijze; termijn opgeschort".to_string(),
+                        "art. 4.4 lid 3 Woo".to_string(),
+                    )
+                }
+                CaseEvent::ConsultationEnded { .. } => {
+                    consultation_since
+                        .take()
+                        .ok_or(LegalDeadlineError::UnmatchedEnd("Einde zienswijzeprocedure"))?;
+                    if suspension_since.is_none() {
+                        term_end += date - suspended_since.take().expect("suspended during consultation");
+                    }
+                    (
+                        "Zienswijze ontvangen of termijn daarvoor verstreken".to_string(),
+                        "art. 4.4 lid 3 Woo".to_string(),
+                    )
+                }
+                CaseEvent::Suspended { reason, .. } => {
+                    if suspension_since.is_some() {
+                        return Err(LegalDeadlineError::AlreadyStarted("Opschorting"));
+                    }
+                    suspension_since = Some(date);
+                    suspended_since.get_or_insert(date);
+                    (format!("Termijn opgeschort: {}", reason), "art. 4:15 lid 1 Awb".to_string())
+                }
+                CaseEvent::Resumed { .. } => {
+                    suspension_since.take().ok_or(LegalDeadlineError::UnmatchedEnd("Einde opschorting"))?;
+                    if consultation_since.is_none() {
+                        term_end += date - suspended_since.take().expect("suspended during suspension");
+                    }
+                    ("Opschorting beëindigd".to_string(), "art. 4:15 lid 1 Awb".to_string())
+                }
+                CaseEvent::Postponed { weeks, .. } => {
+                    term_end += Duration::weeks(*weeks as i64);
+                    (
+                        format!("Beslistermijn met instemming verlengd met {} weken", weeks),
+                        procedure.postponement_basis().to_string(),
+                    )
+                }
+                CaseEvent::NoticeOfDefault { .. } => {
+                    if suspended_since.is_none() && date > due && notice.is_none() {
+                        notice = Some(date);
+                        (
+                            "Ingebrekestelling ontvangen; dwangsom gaat na twee weken lopen".to_string(),
+                            "art. 4:17 lid 1 Awb".to_string(),
+                        )
+                    } else {
+                        (
+                            "Ingebrekestelling ontvangen vóór afloop van de termijn; zonder gevolg".to_string(),
+                            "art. 4:17 lid 1 Awb".to_string(),
+                        )
+                    }
+                }
+                CaseEvent::Decided { .. } => {
+                    decided_on = Some(date);
+                    let timely = suspended_since.is_some() || date <= due;
+                    let description = if timely {
+                        "Besluit tijdig genomen".to_string()
+                    } else {
+                        format!("Besluit {} dagen te laat genomen", (date - due).num_days())
+                    };
+                    (description, procedure.term_basis().to_string())
+                }
+            };
+
+            if suspended_since.is_none() {
+                due = self.calculator.next_working_day(term_end);
+            }
+            history.push(DeadlineChange {
+                date,
+                description,
+                legal_basis: basis,
+                due_date: suspended_since.is_none().then_some(due),
+            });
+        }
+
+        let suspended = suspended_since.is_some();
+        let until = decided_on.unwrap_or(as_of);
+        let days_overdue = if suspended { 0 } else { (until - due).num_days().max(0) };
+
+        let dwangsom = notice.map(|received| {
+            let accrues_from = received + Duration::days(15);
+            let days = ((until - accrues_from).num_days() + 1).clamp(0, DWANGSOM_MAX_DAYS);
+            let days = if decided_on.is_some() && until >= accrues_from {
+                // The decision day itself no longer counts
+                (days - 1).max(0)
+            } else {
+                days
+            };
+            Dwangsom {
+                notice_received: received,
+                accrues_from,
+                days,
+                amount_eur: dwangsom_amount(days),
+                max_amount_eur: dwangsom_amount(DWANGSOM_MAX_DAYS),
+            }
+        });
+
+        Ok(DeadlineStatus {
+            due_date: (!suspended).then_some(due),
+            suspended,
+            decided_on,
+            days_overdue,
+            history,
+            dwangsom,
+        })
+    }
+}
+
+impl Default for LegalDeadlineEngine {
+    fn default() -> Self {
+        Self::netherlands()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn woo(start: NaiveDate, events: Vec<CaseEvent>) -> LegalCase {
+        LegalCase { procedure: Procedure::WooVerzoek, start, events }
+    }
+
+    #[test]
+    fn test_woo_term_with_extension() {
+        let engine = LegalDeadlineEngine::netherlands();
+        // Received Monday 2 March 2026
+        let case = woo(date(2026, 3, 2), vec![CaseEvent::Extended { date: date(2026, 3, 25) }]);
+        let status = engine.evaluate(&case, date(2026, 3, 26)).unwrap();
+
+        assert_eq!(status.history[0].due_date, Some(date(2026, 3, 30)));
+        assert_eq!(status.due_date, Some(date(2026, 4, 13)));
+        assert_eq!(status.history[1].legal_basis, "art. 4.4 lid 2 Woo");
+        assert_eq!(status.days_overdue, 0);
+    }
+
+    #[test]
+    fn test_extension_after_due_date_rejected() {
+        let engine = LegalDeadlineEngine::netherlands();
+        let case = woo(date(2026, 3, 2), vec![CaseEvent::Extended { date: date(2026, 3, 31) }]);
+        assert_eq!(
+            engine.evaluate(&case, date(2026, 4, 1)),
+            Err(LegalDeadlineError::LateExtension { date: date(2026, 3, 31), due_date: date(2026, 3, 30) })
+        );
+    }
+
+    #[test]
+    fn test_overlapping_suspensions_count_once() {
+        let engine = LegalDeadlineEngine::netherlands();
+        let case = woo(
+            date(2026, 1, 5),
+            vec![
+                CaseEvent::ConsultationStarted { date: date(2026, 1, 12) },
+                CaseEvent::Suspended { date: date(2026, 1, 14), reason: "aanvulling".to_string() },
+                CaseEvent::ConsultationEnded { date: date(2026, 1, 19) },
+                CaseEvent::Resumed { date: date(2026, 1, 22) },
+            ],
+        );
+        let status = engine.evaluate(&case, date(2026, 1, 20)).unwrap();
+        // Suspended from 12 to 22 January: 10 days on top of 2 February
+        assert_eq!(status.due_date, Some(date(2026, 2, 12)));
+        assert_eq!(status.history[1].due_date, None);
+
+        let open = woo(date(2026, 1, 5), vec![CaseEvent::ConsultationStarted { date: date(2026, 1, 12) }]);
+        let status = engine.evaluate(&open, date(2026, 3, 1)).unwrap();
+        assert!(status.suspended);
+        assert_eq!(status.due_date, None);
+        assert_eq!(status.days_overdue, 0);
+    }
+
+    #[test]
+    fn test_unmatched_end_rejected() {
+        let engine = LegalDeadlineEngine::netherlands();
+        let case = woo(date(2026, 1, 5), vec![CaseEvent::Resumed { date: date(2026, 1, 12) }]);
+        assert!(matches!(engine.evaluate(&case, date(2026, 1, 12)), Err(LegalDeadlineError::UnmatchedEnd(_))));
+    }
+
+    #[test]
+    fn test_dwangsom_timeline() {
+        let engine = LegalDeadlineEngine::netherlands();
+        // Due 2 February 2026, notice received 9 February
+        let events = vec![CaseEvent::NoticeOfDefault { date: date(2026, 2, 9) }];
+        let status = engine.evaluate(&woo(date(2026, 1, 5), events.clone()), date(2026, 3, 1)).unwrap();
+        let dwangsom = status.dwangsom.unwrap();
+        assert_eq!(dwangsom.accrues_from, date(2026, 2, 24));
+        assert_eq!(dwangsom.days, 6);
+        assert_eq!(dwangsom.amount_eur, 6 * 23);
+        assert_eq!(dwangsom.max_amount_eur, 1442);
+        assert_eq!(status.days_overdue, 27);
+
+        let status = engine.evaluate(&woo(date(2026, 1, 5), events.clone()), date(2026, 6, 1)).unwrap();
+        assert_eq!(status.dwangsom.unwrap().amount_eur, 1442);
+
+        // Decided on the 20th penalty day: 19 days accrued
+        let mut decided = events;
+        decided.push(CaseEvent::Decided { date: date(2026, 3, 15) });
+        let status = engine.evaluate(&woo(date(2026, 1, 5), decided), date(2026, 6, 1)).unwrap();
+        assert_eq!(status.dwangsom.unwrap().amount_eur, 14 * 23 + 5 * 35);
+        assert_eq!(status.days_overdue, 41);
+    }
+
+    #[test]
+    fn test_premature_notice_has_no_effect() {
+        let engine = LegalDeadlineEngine::netherlands();
+        let case = woo(date(2026, 1, 5), vec![CaseEvent::NoticeOfDefault { date: date(2026, 1, 20) }]);
+        let status = engine.evaluate(&case, date(2026, 3, 1)).unwrap();
+        assert!(status.dwangsom.is_none());
+    }
+
+    #[test]
+    fn test_bezwaar_with_committee() {
+        let engine = LegalDeadlineEngine::netherlands();
+        // Decision sent Friday 6 March 2026; objection period ends 17 April
+        let start = objection_period_end(engine.calculator(), date(2026, 3, 6));
+        assert_eq!(start, date(2026, 4, 17));
+
+        let case = LegalCase {
+            procedure: Procedure::Bezwaar { advisory_committee: true },
+            start,
+            events: vec![
+                CaseEvent::Extended { date: date(2026, 7, 1) },
+                CaseEvent::Postponed { date: date(2026, 8, 1), weeks: 4 },
+            ],
+        };
+        let status = engine.evaluate(&case, date(2026, 8, 1)).unwrap();
+        assert_eq!(status.history[0].due_date, Some(date(2026, 7, 10)));
+        assert_eq!(status.history[1].due_date, Some(date(2026, 8, 21)));
+        assert_eq!(status.due_date, Some(date(2026, 9, 18)));
+        assert_eq!(status.history[2].legal_basis, "art. 7:10 lid 4 Awb");
+    }
+
+    #[test]
+    fn test_dwangsom_amount_bands() {
+        assert_eq!(dwangsom_amount(0), 0);
+        assert_eq!(dwangsom_amount(14), 322);
+        assert_eq!(dwangsom_amount(28), 812);
+        assert_eq!(dwangsom_amount(100), 1442);
+    }
+}
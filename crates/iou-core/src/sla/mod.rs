@@ -1,8 +1,18 @@
 //! SLA (Service Level Agreement) calculation module
 //!
 //! Provides deadline calculation with business hours, weekend skipping,
-//! and holiday support.
+//! holiday support (including the Dutch public holidays), time zones,
+//! calendar-day terms and the legal decision terms of Woo and Awb cases.
 
 mod calculator;
+pub mod calendar;
+pub mod holidays;
+pub mod legal;
 
-pub use calculator::{SlaCalculator, SlaConfig};
+pub use calculator::{DeadlineRule, SlaCalculator, SlaConfig};
+pub use calendar::{SlaTimeZone, WorkingDay, WorkingHours};
+pub use holidays::{dutch_public_holidays, easter_sunday, Holiday, LiberationDay};
+pub use legal::{
+    CaseEvent, DeadlineChange, DeadlineStatus, Dwangsom, LegalCase, LegalDeadlineEngine,
+    LegalDeadlineError, Procedure,
+};
@@ -0,0 +1,420 @@
+//! Minimal CBOR (RFC 8949) for ISO mdoc
+//!
+//! Decodes any well-formed data item, including indefinite lengths, and
+//! encodes with definite, shortest-form lengths. mdoc digests and
+//! signatures are computed over embedded byte strings, so re-encoding only
+//! has to be canonical for the structures the verifier builds itself.
+
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::ssi::encoding::base64url_encode;
+
+/// Tag of an embedded data item (`#6.24(bstr .cbor T)`)
+pub const TAG_ENCODED_CBOR: u64 = 24;
+
+/// Nesting limit; mdoc structures are a handful of levels deep
+const MAX_DEPTH: usize = 32;
+
+/// CBOR data item
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cbor {
+    Unsigned(u64),
+    /// `-1 - n`
+    Negative(u64),
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<Cbor>),
+    /// Entries in encoded order
+    Map(Vec<(Cbor, Cbor)>),
+    Tag(u64, Box<Cbor>),
+    Bool(bool),
+    Null,
+    Undefined,
+    Float(f64),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Invalid CBOR: {0}")]
+pub struct CborError(pub String);
+
+impl Cbor {
+    /// Decode exactly one data item
+    pub fn decode(bytes: &[u8]) -> Result<Self, CborError> {
+        let mut decoder = Decoder { bytes, pos: 0 };
+        let item = decoder.item(0)?;
+        if decoder.pos != bytes.len() {
+            return Err(CborError("trailing bytes".into()));
+        }
+        Ok(item)
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write(&mut out);
+        out
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Unsigned(n) => head(out, 0, *n),
+            Self::Negative(n) => head(out, 1, *n),
+            Self::Bytes(bytes) => {
+                head(out, 2, bytes.len() as u64);
+                out.extend_from_slice(bytes);
+            }
+            Self::Text(text) => {
+                head(out, 3, text.len() as u64);
+                out.extend_from_slice(text.as_bytes());
+            }
+            Self::Array(items) => {
+                head(out, 4, items.len() as u64);
+                items.iter().for_each(|item| item.write(out));
+            }
+            Self::Map(entries) => {
+                head(out, 5, entries.len() as u64);
+                for (key, value) in entries {
+                    key.write(out);
+                    value.write(out);
+                }
+            }
+            Self::Tag(tag, item) => {
+                head(out, 6, *tag);
+                item.write(out);
+            }
+            Self::Bool(false) => out.push(0xf4),
+            Self::Bool(true) => out.push(0xf5),
+            Self::Null => out.push(0xf6),
+            Self::Undefined => out.push(0xf7),
+            Self::Float(value) => {
+                out.push(0xfb);
+                out.extend_from_slice(&value.to_be_bytes());
+            }
+        }
+    }
+
+    pub fn text(value: impl Into<String>) -> Self {
+        Self::Text(value.into())
+    }
+
+    pub fn int(value: i64) -> Self {
+        if value < 0 {
+            Self::Negative((-1 - value) as u64)
+        } else {
+            Self::Unsigned(value as u64)
+        }
+    }
+
+    /// `#6.24(bstr)` holding the encoding of `item`
+    pub fn embed(item: &Cbor) -> Self {
+        Self::Tag(TAG_ENCODED_CBOR, Box::new(Self::Bytes(item.encode())))
+    }
+
+    /// Data item inside a `#6.24(bstr)`
+    pub fn embedded(&self) -> Result<Cbor, CborError> {
+        match self {
+            Self::Tag(TAG_ENCODED_CBOR, inner) => match inner.as_ref() {
+                Self::Bytes(bytes) => Cbor::decode(bytes),
+                _ => Err(CborError("tag 24 without byte string".into())),
+            },
+            _ => Err(CborError("expected an embedded data item (tag 24)".into())),
+        }
+    }
+
+    /// Map value under a text key
+    pub fn get(&self, key: &str) -> Option<&Cbor> {
+        self.as_map()?.iter().find(|(k, _)| k.as_text() == Some(key)).map(|(_, v)| v)
+    }
+
+    /// Map value under an integer key, e.g. a COSE label
+    pub fn get_int(&self, key: i64) -> Option<&Cbor> {
+        self.as_map()?.iter().find(|(k, _)| k.as_i64() == Some(key)).map(|(_, v)| v)
+    }
+
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Self::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Cbor]> {
+        match self {
+            Self::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_map(&self) -> Option<&[(Cbor, Cbor)]> {
+        match self {
+            Self::Map(entries) => Some(entries),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Unsigned(n) => i64::try_from(*n).ok(),
+            Self::Negative(n) => i64::try_from(*n).ok().map(|n| -1 - n),
+            _ => None,
+        }
+    }
+
+    /// Untagged text, e.g. a `tdate` (tag 0) or `full-date` (tag 1004)
+    pub fn as_date_text(&self) -> Option<&str> {
+        match self {
+            Self::Tag(_, inner) => inner.as_date_text(),
+            other => other.as_text(),
+        }
+    }
+
+    /// JSON form of an element value: byte strings become base64url, tags
+    /// are dropped, non-text map keys are rendered as JSON
+    pub fn to_json(&self) -> Value {
+        match self {
+            Self::Unsigned(n) => Value::from(*n),
+            Self::Negative(n) => i64::try_from(*n).map(|n| Value::from(-1 - n)).unwrap_or(Value::Null),
+            Self::Bytes(bytes) => Value::String(base64url_encode(bytes)),
+            Self::Text(text) => Value::String(text.clone()),
+            Self::Array(items) => Value::Array(items.iter().map(Cbor::to_json).collect()),
+            Self::Map(entries) => Value::Object(
+                entries
+                    .iter()
+                    .map(|(key, value)| {
+                        let key = key.as_text().map(str::to_string).unwrap_or_else(|| key.to_json().to_string());
+                        (key, value.to_json())
+                    })
+                    .collect(),
+            ),
+            Self::Tag(_, item) => item.to_json(),
+            Self::Bool(value) => Value::Bool(*value),
+            Self::Null | Self::Undefined => Value::Null,
+            Self::Float(value) => serde_json::Number::from_f64(*value).map(Value::Number).unwrap_or(Value::Null),
+        }
+    }
+}
+
+/// Initial byte and argument, shortest form
+fn head(out: &mut Vec<u8>, major: u8, argument: u64) {
+    let major = major << 5;
+    match argument {
+        0..=23 => out.push(major | argument as u8),
+        24..=0xff => out.extend_from_slice(&[major | 24, argument as u8]),
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend_from_slice(&(argument as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(major | 26);
+            out.extend_from_slice(&(argument as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend_from_slice(&argument.to_be_bytes());
+        }
+    }
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+/// `0xff` ends an indefinite-length item
+const BREAK: u8 = 0xff;
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, len: u64) -> Result<&'a [u8], CborError> {
+        let len = usize::try_from(len).map_err(|_| CborError("length too large".into()))?;
+        if self.bytes.len() - self.pos < len {
+            return Err(CborError("unexpected end of input".into()));
+        }
+        let slice = &self.bytes[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(slice)
+    }
+
+    fn byte(&mut self) -> Result<u8, CborError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn at_break(&mut self) -> Result<bool, CborError> {
+        match self.bytes.get(self.pos) {
+            Some(&BREAK) => {
+                self.pos += 1;
+                Ok(true)
+            }
+            Some(_) => Ok(false),
+            None => Err(CborError("unterminated indefinite-length item".into())),
+        }
+    }
+
+    fn uint(&mut self, len: u64) -> Result<u64, CborError> {
+        Ok(self.take(len)?.iter().fold(0u64, |acc, &b| (acc << 8) | u64::from(b)))
+    }
+
+    /// Argument of the initial byte; `None` for an indefinite length
+    fn argument(&mut self, info: u8) -> Result<Option<u64>, CborError> {
+        match info {
+            0..=23 => Ok(Some(u64::from(info))),
+            24 => self.uint(1).map(Some),
+            25 => self.uint(2).map(Some),
+            26 => self.uint(4).map(Some),
+            27 => self.uint(8).map(Some),
+            31 => Ok(None),
+            _ => Err(CborError(format!("reserved additional information {}", info))),
+        }
+    }
+
+    fn item(&mut self, depth: usize) -> Result<Cbor, CborError> {
+        if depth > MAX_DEPTH {
+            return Err(CborError("nested too deeply".into()));
+        }
+        let initial = self.byte()?;
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        if major == 7 {
+            return self.simple(info);
+        }
+
+        match (major, self.argument(info)?) {
+            (0, Some(n)) => Ok(Cbor::Unsigned(n)),
+            (1, Some(n)) => Ok(Cbor::Negative(n)),
+            (2, Some(len)) => Ok(Cbor::Bytes(self.take(len)?.to_vec())),
+            (3, Some(len)) => String::from_utf8(self.take(len)?.to_vec())
+                .map(Cbor::Text)
+                .map_err(|_| CborError("text is not UTF-8".into())),
+            (2 | 3, None) => {
+                let mut chunks = Vec::new();
+                while !self.at_break()? {
+                    match (major, self.item(depth + 1)?) {
+                        (2, Cbor::Bytes(chunk)) => chunks.extend(chunk),
+                        (3, Cbor::Text(chunk)) => chunks.extend(chunk.into_bytes()),
+                        _ => return Err(CborError("chunk of the wrong type".into())),
+                    }
+                }
+                if major == 2 {
+                    Ok(Cbor::Bytes(chunks))
+                } else {
+                    String::from_utf8(chunks).map(Cbor::Text).map_err(|_| CborError("text is not UTF-8".into()))
+                }
+            }
+            (4, len) => {
+                let mut items = Vec::new();
+                match len {
+                    Some(len) => {
+                        for _ in 0..len {
+                            items.push(self.item(depth + 1)?);
+                        }
+                    }
+                    None => {
+                        while !self.at_break()? {
+                            items.push(self.item(depth + 1)?);
+                        }
+                    }
+                }
+                Ok(Cbor::Array(items))
+            }
+            (5, len) => {
+                let mut entries = Vec::new();
+                match len {
+                    Some(len) => {
+                        for _ in 0..len {
+                            entries.push((self.item(depth + 1)?, self.item(depth + 1)?));
+                        }
+                    }
+                    None => {
+                        while !self.at_break()? {
+                            entries.push((self.item(depth + 1)?, self.item(depth + 1)?));
+                        }
+                    }
+                }
+                Ok(Cbor::Map(entries))
+            }
+            (6, Some(tag)) => Ok(Cbor::Tag(tag, Box::new(self.item(depth + 1)?))),
+            _ => Err(CborError(format!("indefinite length for major type {}", major))),
+        }
+    }
+
+    fn simple(&mut self, info: u8) -> Result<Cbor, CborError> {
+        match info {
+            20 => Ok(Cbor::Bool(false)),
+            21 => Ok(Cbor::Bool(true)),
+            22 => Ok(Cbor::Null),
+            23 => Ok(Cbor::Undefined),
+            25 => Ok(Cbor::Float(half(self.uint(2)? as u16))),
+            26 => Ok(Cbor::Float(f64::from(f32::from_bits(self.uint(4)? as u32)))),
+            27 => Ok(Cbor::Float(f64::from_bits(self.uint(8)?))),
+            other => Err(CborError(format!("unsupported simple value {}", other))),
+        }
+    }
+}
+
+/// IEEE 754 half precision
+fn half(bits: u16) -> f64 {
+    let exponent = i32::from((bits >> 10) & 0x1f);
+    let mantissa = f64::from(bits & 0x3ff);
+    let value = match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        31 if mantissa == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (mantissa + 1024.0) * 2f64.powi(exponent - 25),
+    };
+    if bits & 0x8000 != 0 { -value } else { value }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(s: &str) -> Vec<u8> {
+        ::hex::decode(s).unwrap()
+    }
+
+    #[test]
+    fn test_rfc8949_examples() {
+        // RFC 8949 appendix A
+        assert_eq!(Cbor::decode(&hex("1903e8")).unwrap(), Cbor::Unsigned(1000));
+        assert_eq!(Cbor::decode(&hex("3863")).unwrap().as_i64(), Some(-100));
+        assert_eq!(Cbor::decode(&hex("f93c00")).unwrap(), Cbor::Float(1.0));
+        assert_eq!(Cbor::decode(&hex("f97bff")).unwrap(), Cbor::Float(65504.0));
+        assert_eq!(Cbor::decode(&hex("6449455446")).unwrap(), Cbor::text("IETF"));
+        assert_eq!(
+            Cbor::decode(&hex("c074323031332d30332d32315432303a30343a30305a")).unwrap().as_date_text(),
+            Some("2013-03-21T20:04:00Z")
+        );
+
+        let map = Cbor::decode(&hex("a26161016162820203")).unwrap();
+        assert_eq!(map.get("a"), Some(&Cbor::Unsigned(1)));
+        assert_eq!(map.encode(), hex("a26161016162820203"));
+
+        // Indefinite lengths decode to the definite form
+        let indefinite = Cbor::decode(&hex("bf6346756ef563416d7421ff")).unwrap();
+        assert_eq!(indefinite.get("Amt").and_then(Cbor::as_i64), Some(-2));
+        assert_eq!(Cbor::decode(&hex("5f42010243030405ff")).unwrap(), Cbor::Bytes(vec![1, 2, 3, 4, 5]));
+    }
+
+    #[test]
+    fn test_embedded_round_trip() {
+        let item = Cbor::Map(vec![(Cbor::text("digestID"), Cbor::int(7)), (Cbor::int(-1), Cbor::Bytes(vec![0xff]))]);
+        let embedded = Cbor::embed(&item);
+        assert_eq!(Cbor::decode(&embedded.encode()).unwrap().embedded().unwrap(), item);
+        assert_eq!(item.get_int(-1).and_then(Cbor::as_bytes), Some(&[0xff][..]));
+        assert_eq!(item.to_json(), serde_json::json!({"digestID": 7, "-1": "_w"}));
+    }
+
+    #[test]
+    fn test_rejects_malformed_input() {
+        assert!(Cbor::decode(&hex("1903")).is_err());
+        assert!(Cbor::decode(&hex("0000")).is_err());
+        assert!(Cbor::decode(&hex("5affffffff")).is_err());
+        assert!(Cbor::decode(&hex("9f01")).is_err());
+        assert!(Cbor::decode(&[0x81; 64]).is_err());
+    }
+}
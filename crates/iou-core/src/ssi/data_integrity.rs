@@ -0,0 +1,258 @@
+//! Data Integrity proofs (W3C VC Data Integrity 1.0)
+//!
+//! Supports the JCS cryptosuites `eddsa-jcs-2022` and `ecdsa-jcs-2019`
+//! (P-256). The RDF canonicalization suites need a JSON-LD processor and
+//! are rejected as unsupported.
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::ssi::encoding::multibase_decode;
+use crate::ssi::jose::{JwsAlgorithm, PublicKey};
+use crate::ssi::verifiable_credential::VCValidationError;
+
+pub const EDDSA_JCS_2022: &str = "eddsa-jcs-2022";
+pub const ECDSA_JCS_2019: &str = "ecdsa-jcs-2019";
+
+/// Embedded proof of a secured document
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataIntegrityProof {
+    pub cryptosuite: String,
+    pub verification_method: String,
+    pub proof_purpose: String,
+    pub created: Option<String>,
+    /// Nonce of a presentation
+    pub challenge: Option<String>,
+    /// Audience of a presentation
+    pub domain: Option<String>,
+    /// Proof options without `proofValue`, as signed
+    config: Value,
+    signature: Vec<u8>,
+}
+
+impl DataIntegrityProof {
+    /// The single proof of `document`
+    pub fn from_document(document: &Value) -> Result<Self, VCValidationError> {
+        let proof = match document.get("proof") {
+            Some(Value::Object(_)) => document["proof"].clone(),
+            Some(Value::Array(_)) => {
+                return Err(VCValidationError::UnsupportedProof("proof sets".into()));
+            }
+            _ => return Err(VCValidationError::MissingProof),
+        };
+
+        let field = |name: &str| proof.get(name).and_then(Value::as_str).map(str::to_string);
+        let proof_type = field("type").unwrap_or_default();
+        if proof_type != "DataIntegrityProof" {
+            return Err(VCValidationError::UnsupportedProof(proof_type));
+        }
+        let cryptosuite = field("cryptosuite").unwrap_or_default();
+        if cryptosuite != EDDSA_JCS_2022 && cryptosuite != ECDSA_JCS_2019 {
+            return Err(VCValidationError::UnsupportedProof(cryptosuite));
+        }
+
+        let missing = |name: &str| VCValidationError::InvalidFormat(format!("Proof without {}", name));
+        let proof_value = field("proofValue").ok_or_else(|| missing("proofValue"))?;
+        let signature = multibase_decode(&proof_value)
+            .map_err(|e| VCValidationError::InvalidFormat(format!("Invalid proofValue: {}", e)))?;
+
+        let mut config = proof.clone();
+        if let Some(options) = config.as_object_mut() {
+            options.remove("proofValue");
+        }
+
+        Ok(Self {
+            verification_method: field("verificationMethod").ok_or_else(|| missing("verificationMethod"))?,
+            proof_purpose: field("proofPurpose").ok_or_else(|| missing("proofPurpose"))?,
+            created: field("created"),
+            challenge: field("challenge"),
+            domain: field("domain"),
+            cryptosuite,
+            config,
+            signature,
+        })
+    }
+
+    /// Verify the proof over `document` with the key of the verification method
+    pub fn verify(&self, document: &Value, key: &PublicKey) -> Result<(), VCValidationError> {
+        let mut unsecured = document.clone();
+        if let Some(fields) = unsecured.as_object_mut() {
+            fields.remove("proof");
+        }
+
+        // Proof options may carry the document context; it must match
+        if let Some(context) = self.config.get("@context") {
+            if !context_extends(document.get("@context"), context) {
+                return Err(VCValidationError::InvalidSignature(
+                    "proof @context does not match the document".into(),
+                ));
+            }
+            unsecured["@context"] = context.clone();
+        }
+
+        let hash_data = [
+            Sha256::digest(canonicalize(&self.config).as_bytes()),
+            Sha256::digest(canonicalize(&unsecured).as_bytes()),
+        ]
+        .concat();
+
+        let alg = match self.cryptosuite.as_str() {
+            EDDSA_JCS_2022 => JwsAlgorithm::EdDSA,
+            _ => JwsAlgorithm::ES256,
+        };
+        key.verify(alg, &hash_data, &self.signature)
+    }
+}
+
+/// Whether the document context starts with the proof context
+fn context_extends(document: Option<&Value>, proof: &Value) -> bool {
+    let as_list = |value: &Value| match value {
+        Value::Array(items) => items.clone(),
+        other => vec![other.clone()],
+    };
+    let document = document.map(as_list).unwrap_or_default();
+    let proof = as_list(proof);
+    document.len() >= proof.len() && document[..proof.len()] == proof[..]
+}
+
+/// JSON Canonicalization Scheme (RFC 8785)
+///
+/// Object members are sorted by their UTF-16 code units; numbers use the
+/// shortest ECMAScript form.
+pub fn canonicalize(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(fields) => {
+            let mut members: Vec<(&String, &Value)> = fields.iter().collect();
+            members.sort_by(|a, b| a.0.encode_utf16().cmp(b.0.encode_utf16()));
+            out.push('{');
+            for (i, (name, value)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(name.clone()).to_string());
+                out.push(':');
+                write_canonical(value, out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        Value::Number(number) => match number.as_f64() {
+            // Integral floats print without a fraction, e.g. 1.0 as 1
+            Some(f) if number.is_f64() && f.fract() == 0.0 && f.abs() < 1e21 => {
+                out.push_str(&format!("{}", f as i64));
+            }
+            _ => out.push_str(&number.to_string()),
+        },
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+/// Sign `document` the way an issuer would
+#[cfg(test)]
+pub(crate) fn secure(document: &Value, key: &crate::ssi::jose::test_keys::TestKey, options: Value) -> Value {
+    let cryptosuite = match key.alg() {
+        JwsAlgorithm::EdDSA => EDDSA_JCS_2022,
+        _ => ECDSA_JCS_2019,
+    };
+    let mut config = options;
+    config["type"] = "DataIntegrityProof".into();
+    config["cryptosuite"] = cryptosuite.into();
+    let hash_data = [
+        Sha256::digest(canonicalize(&config).as_bytes()),
+        Sha256::digest(canonicalize(document).as_bytes()),
+    ]
+    .concat();
+    config["proofValue"] = format!("z{}", crate::ssi::encoding::base58_encode(&key.sign(&hash_data))).into();
+
+    let mut secured = document.clone();
+    secured["proof"] = config;
+    secured
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ssi::jose::test_keys::TestKey;
+    use serde_json::json;
+
+    fn credential() -> Value {
+        json!({
+            "@context": ["https://www.w3.org/ns/credentials/v2"],
+            "type": ["VerifiableCredential", "MedewerkerCredential"],
+            "issuer": "did:example:bzk",
+            "validFrom": "2026-01-01T00:00:00Z",
+            "credentialSubject": {"id": "did:example:jan", "municipality": "utrecht"}
+        })
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        // RFC 8785 section 3.2.3 ordering, with a non-BMP key
+        let value = json!({"\u{20ac}": 1, "\r": 2, "\u{1f600}": 3, "1": 4, "\u{80}": 5, "\u{fb33}": 6});
+        assert_eq!(
+            canonicalize(&value),
+            "{\"\\r\":2,\"1\":4,\"\u{80}\":5,\"\u{20ac}\":1,\"\u{1f600}\":3,\"\u{fb33}\":6}"
+        );
+        assert_eq!(canonicalize(&json!({"b": [1.0, 0.5, true, null], "a": "x"})), r#"{"a":"x","b":[1,0.5,true,null]}"#);
+    }
+
+    #[test]
+    fn test_verify_jcs_suites() {
+        for key in [TestKey::ed25519(3), TestKey::p256()] {
+            let options = json!({
+                "verificationMethod": "did:example:bzk#key-1",
+                "proofPurpose": "assertionMethod",
+                "created": "2026-01-01T00:00:00Z"
+            });
+            let secured = secure(&credential(), &key, options);
+
+            let proof = DataIntegrityProof::from_document(&secured).unwrap();
+            assert_eq!(proof.verification_method, "did:example:bzk#key-1");
+            proof.verify(&secured, &key.public()).unwrap();
+
+            // Changing a claim invalidates the proof
+            let mut forged = secured.clone();
+            forged["credentialSubject"]["municipality"] = "amsterdam".into();
+            assert!(proof.verify(&forged, &key.public()).is_err());
+
+            // So does changing the proof options
+            let mut forged = secured.clone();
+            forged["proof"]["proofPurpose"] = "authentication".into();
+            let forged_proof = DataIntegrityProof::from_document(&forged).unwrap();
+            assert!(forged_proof.verify(&forged, &key.public()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_unsupported_proofs() {
+        let mut document = credential();
+        assert!(matches!(DataIntegrityProof::from_document(&document), Err(VCValidationError::MissingProof)));
+
+        document["proof"] = json!({"type": "Ed25519Signature2018", "jws": "e30..c2ln"});
+        assert!(matches!(
+            DataIntegrityProof::from_document(&document),
+            Err(VCValidationError::UnsupportedProof(t)) if t == "Ed25519Signature2018"
+        ));
+
+        document["proof"] = json!({"type": "DataIntegrityProof", "cryptosuite": "eddsa-rdfc-2022"});
+        assert!(matches!(
+            DataIntegrityProof::from_document(&document),
+            Err(VCValidationError::UnsupportedProof(_))
+        ));
+    }
+}
@@ -8,6 +8,8 @@ use thiserror::Error as ThisError;
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DidMethod {
     Key,
+    Jwk,
+    Peer,
     Web,
     Ebsi,
     PolygonId, // For European identity systems
@@ -20,6 +22,8 @@ impl std::str::FromStr for DidMethod {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "key" => Ok(DidMethod::Key),
+            "jwk" => Ok(DidMethod::Jwk),
+            "peer" => Ok(DidMethod::Peer),
             "web" => Ok(DidMethod::Web),
             "ebsi" => Ok(DidMethod::Ebsi),
             "polygonid" => Ok(DidMethod::PolygonId),
@@ -36,12 +40,13 @@ pub struct DidDocument {
 
     pub id: String,
 
-    #[serde(rename = "verificationMethod")]
+    #[serde(rename = "verificationMethod", default)]
     pub verification_method: Vec<VerificationMethod>,
 
+    #[serde(default)]
     pub authentication: Vec<serde_json::Value>,
 
-    #[serde(rename = "assertionMethod")]
+    #[serde(rename = "assertionMethod", default)]
     pub assertion_method: Vec<serde_json::Value>,
 
     pub service: Option<Vec<Service>>,
@@ -58,6 +63,63 @@ pub struct VerificationMethod {
     pub public_key_jwk: Option<Jwk>,
     #[serde(rename = "publicKeyBase58")]
     pub public_key_base58: Option<String>,
+    #[serde(rename = "publicKeyMultibase", default, skip_serializing_if = "Option::is_none")]
+    pub public_key_multibase: Option<String>,
+}
+
+/// Purpose a key is listed for in a DID document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationRelationship {
+    /// Holder proofs (presentations, key binding)
+    Authentication,
+    /// Issuer proofs (credentials)
+    AssertionMethod,
+}
+
+impl DidDocument {
+    /// Verification method listed for `relationship`
+    ///
+    /// `id` may be absolute (`did:example:123#key-1`) or relative
+    /// (`#key-1`); without an id the document must list exactly one
+    /// method for the relationship.
+    pub fn verification_method_for(
+        &self,
+        id: Option<&str>,
+        relationship: VerificationRelationship,
+    ) -> Option<VerificationMethod> {
+        let listed = match relationship {
+            VerificationRelationship::Authentication => &self.authentication,
+            VerificationRelationship::AssertionMethod => &self.assertion_method,
+        };
+
+        let methods: Vec<VerificationMethod> = listed
+            .iter()
+            .filter_map(|entry| match entry {
+                // Reference to a method in verificationMethod
+                serde_json::Value::String(reference) => self
+                    .verification_method
+                    .iter()
+                    .find(|m| self.absolute(&m.id) == self.absolute(reference))
+                    .cloned(),
+                // Embedded method
+                embedded => serde_json::from_value(embedded.clone()).ok(),
+            })
+            .collect();
+
+        match id {
+            Some(id) => methods.into_iter().find(|m| self.absolute(&m.id) == self.absolute(id)),
+            None if methods.len() == 1 => methods.into_iter().next(),
+            None => None,
+        }
+    }
+
+    fn absolute(&self, id: &str) -> String {
+        if id.starts_with('#') {
+            format!("{}{}", self.id, id)
+        } else {
+            id.to_string()
+        }
+    }
 }
 
 /// JSON Web Key
@@ -142,6 +204,32 @@ mod tests {
         assert_eq!(id, "example.com");
     }
 
+    #[test]
+    fn test_verification_method_for() {
+        let document: DidDocument = serde_json::from_value(serde_json::json!({
+            "@context": "https://www.w3.org/ns/did/v1",
+            "id": "did:example:123",
+            "verificationMethod": [{
+                "id": "did:example:123#key-1",
+                "type": "JsonWebKey2020",
+                "controller": "did:example:123",
+                "publicKeyJwk": {"kty": "OKP", "crv": "Ed25519", "x": "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"}
+            }],
+            "assertionMethod": ["#key-1"]
+        }))
+        .unwrap();
+
+        let assertion = VerificationRelationship::AssertionMethod;
+        assert!(document.verification_method_for(Some("did:example:123#key-1"), assertion).is_some());
+        assert!(document.verification_method_for(Some("#key-1"), assertion).is_some());
+        assert!(document.verification_method_for(None, assertion).is_some());
+        assert!(document.verification_method_for(Some("#key-2"), assertion).is_none());
+        // Not listed for authentication
+        assert!(document
+            .verification_method_for(None, VerificationRelationship::Authentication)
+            .is_none());
+    }
+
     #[test]
     fn test_invalid_did() {
         let result = parse_did("not-a-did");
@@ -0,0 +1,283 @@
+//! Self-describing DID methods: did:key, did:jwk and did:peer
+//!
+//! The DID document is derived from the identifier itself, so these
+//! methods resolve without network access.
+
+use serde_json::{json, Value};
+
+use crate::ssi::did::{DidDocument, Jwk, Service, VerificationMethod};
+use crate::ssi::encoding::{base64url_decode, multibase_decode};
+use crate::ssi::jose::PublicKey;
+use crate::ssi::resolver::ResolverError;
+
+const DID_CONTEXT: &str = "https://www.w3.org/ns/did/v1";
+const MULTIKEY_CONTEXT: &str = "https://w3id.org/security/multikey/v1";
+const JWS_2020_CONTEXT: &str = "https://w3id.org/security/suites/jws-2020/v1";
+
+/// Multicodec prefix of an X25519 key (`x25519-pub`), key agreement only
+const X25519_MULTICODEC: [u8; 2] = [0xec, 0x01];
+
+/// did:key:z6Mk... (Ed25519), zDn... (P-256), zQ3s... (secp256k1)
+pub fn resolve_did_key(did: &str) -> Result<DidDocument, ResolverError> {
+    let multibase = did
+        .strip_prefix("did:key:")
+        .ok_or_else(|| ResolverError::InvalidFormat(format!("{} is not a did:key", did)))?;
+    signing_key(multibase)?;
+    Ok(single_key_document(did, multibase))
+}
+
+/// did:jwk:<base64url JWK>
+pub fn resolve_did_jwk(did: &str) -> Result<DidDocument, ResolverError> {
+    let encoded = did
+        .strip_prefix("did:jwk:")
+        .ok_or_else(|| ResolverError::InvalidFormat(format!("{} is not a did:jwk", did)))?;
+    let invalid = |message: String| ResolverError::InvalidFormat(format!("{}: {}", did, message));
+
+    let bytes = base64url_decode(encoded).map_err(|e| invalid(e.to_string()))?;
+    let value: Value = serde_json::from_slice(&bytes).map_err(|e| invalid(e.to_string()))?;
+    if value.get("d").is_some() {
+        return Err(invalid("JWK contains a private key".into()));
+    }
+    let jwk: Jwk = serde_json::from_value(value.clone()).map_err(|e| invalid(e.to_string()))?;
+
+    let id = format!("{}#0", did);
+    // Encryption keys are for key agreement, which is not modelled here
+    let purposes = if value.get("use").and_then(Value::as_str) == Some("enc") {
+        vec![]
+    } else {
+        vec![json!(id)]
+    };
+    Ok(DidDocument {
+        context: json!([DID_CONTEXT, JWS_2020_CONTEXT]),
+        id: did.to_string(),
+        verification_method: vec![VerificationMethod {
+            id,
+            key_type: "JsonWebKey2020".to_string(),
+            controller: did.to_string(),
+            public_key_jwk: Some(jwk),
+            public_key_base58: None,
+            public_key_multibase: None,
+        }],
+        authentication: purposes.clone(),
+        assertion_method: purposes,
+        service: None,
+    })
+}
+
+/// did:peer with numalgo 0 (one inception key) or 2 (multiple keys and
+/// services)
+pub fn resolve_did_peer(did: &str) -> Result<DidDocument, ResolverError> {
+    let id = did
+        .strip_prefix("did:peer:")
+        .ok_or_else(|| ResolverError::InvalidFormat(format!("{} is not a did:peer", did)))?;
+
+    if let Some(multibase) = id.strip_prefix('0') {
+        signing_key(multibase)?;
+        return Ok(single_key_document(did, multibase));
+    }
+    if let Some(elements) = id.strip_prefix("2.") {
+        return peer_numalgo_2(did, elements);
+    }
+    Err(ResolverError::MethodNotSupported(format!(
+        "did:peer numalgo {}",
+        id.chars().next().unwrap_or(' ')
+    )))
+}
+
+/// Elements separated by `.`, each prefixed with its purpose: `V`
+/// authentication, `A` assertion, `E` key agreement, `I`/`D` capability
+/// invocation and delegation, `S` a base64url service
+fn peer_numalgo_2(did: &str, elements: &str) -> Result<DidDocument, ResolverError> {
+    let invalid = |message: String| ResolverError::InvalidFormat(format!("{}: {}", did, message));
+    let mut document = empty_document(did, json!([DID_CONTEXT, MULTIKEY_CONTEXT]));
+    let mut services = Vec::new();
+
+    for element in elements.split('.') {
+        let mut chars = element.chars();
+        let (Some(purpose), value) = (chars.next(), chars.as_str()) else {
+            return Err(invalid("empty element".into()));
+        };
+        if purpose == 'S' {
+            let bytes = base64url_decode(value).map_err(|e| invalid(e.to_string()))?;
+            let decoded: Value = serde_json::from_slice(&bytes).map_err(|e| invalid(e.to_string()))?;
+            let entries = match decoded {
+                Value::Array(entries) => entries,
+                entry => vec![entry],
+            };
+            for entry in &entries {
+                let service = peer_service(entry, services.len()).ok_or_else(|| invalid("invalid service".into()))?;
+                services.push(service);
+            }
+            continue;
+        }
+
+        let relative = format!("#key-{}", document.verification_method.len() + 1);
+        match purpose {
+            'E' => key_agreement_key(value)?,
+            'V' | 'A' | 'I' | 'D' => {
+                signing_key(value)?;
+            }
+            other => return Err(invalid(format!("unknown purpose code {:?}", other))),
+        }
+        document.verification_method.push(multikey(did, &relative, value));
+        match purpose {
+            'V' => document.authentication.push(json!(relative)),
+            'A' => document.assertion_method.push(json!(relative)),
+            _ => {}
+        }
+    }
+
+    if !services.is_empty() {
+        document.service = Some(services);
+    }
+    Ok(document)
+}
+
+/// Service in the abbreviated did:peer form (`t`, `s`, `dm` for
+/// DIDCommMessaging); the endpoint may be a URI or an object with `uri`
+fn peer_service(entry: &Value, index: usize) -> Option<Service> {
+    let field = |long: &str, short: &str| entry.get(long).or_else(|| entry.get(short));
+    let service_type = match field("type", "t")?.as_str()? {
+        "dm" => "DIDCommMessaging",
+        other => other,
+    };
+    let endpoint = field("serviceEndpoint", "s")?;
+    let service_endpoint = endpoint.as_str().or_else(|| endpoint.get("uri")?.as_str())?;
+    let id = match entry.get("id").and_then(Value::as_str) {
+        Some(id) => id.to_string(),
+        None if index == 0 => "#service".to_string(),
+        None => format!("#service-{}", index),
+    };
+    Some(Service {
+        id,
+        service_type: service_type.to_string(),
+        service_endpoint: service_endpoint.to_string(),
+    })
+}
+
+/// Document of did:key and did:peer numalgo 0: one key, usable for both
+/// authentication and assertions
+fn single_key_document(did: &str, multibase: &str) -> DidDocument {
+    let id = format!("{}#{}", did, multibase);
+    let mut document = empty_document(did, json!([DID_CONTEXT, MULTIKEY_CONTEXT]));
+    document.verification_method.push(multikey(did, &id, multibase));
+    document.authentication.push(json!(id));
+    document.assertion_method.push(json!(id));
+    document
+}
+
+fn empty_document(did: &str, context: Value) -> DidDocument {
+    DidDocument {
+        context,
+        id: did.to_string(),
+        verification_method: vec![],
+        authentication: vec![],
+        assertion_method: vec![],
+        service: None,
+    }
+}
+
+fn multikey(did: &str, id: &str, multibase: &str) -> VerificationMethod {
+    VerificationMethod {
+        id: id.to_string(),
+        key_type: "Multikey".to_string(),
+        controller: did.to_string(),
+        public_key_jwk: None,
+        public_key_base58: None,
+        public_key_multibase: Some(multibase.to_string()),
+    }
+}
+
+/// Ed25519, P-256 or secp256k1 key in multibase (base58btc) form
+fn signing_key(multibase: &str) -> Result<PublicKey, ResolverError> {
+    if !multibase.starts_with('z') {
+        return Err(ResolverError::InvalidFormat(format!("{} is not base58btc", multibase)));
+    }
+    let bytes = multibase_decode(multibase).map_err(|e| ResolverError::InvalidFormat(e.to_string()))?;
+    PublicKey::from_multicodec(&bytes).map_err(|e| ResolverError::InvalidFormat(e.to_string()))
+}
+
+fn key_agreement_key(multibase: &str) -> Result<(), ResolverError> {
+    let bytes = multibase_decode(multibase).map_err(|e| ResolverError::InvalidFormat(e.to_string()))?;
+    match bytes.strip_prefix(&X25519_MULTICODEC) {
+        Some(key) if key.len() == 32 => Ok(()),
+        _ => Err(ResolverError::InvalidFormat(format!("{} is not an X25519 key", multibase))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ssi::did::VerificationRelationship;
+    use crate::ssi::encoding::{base58_encode, base64url_encode};
+    use crate::ssi::jose::test_keys::TestKey;
+
+    fn key_of(document: &DidDocument, relationship: VerificationRelationship) -> PublicKey {
+        let method = document.verification_method_for(None, relationship).unwrap();
+        PublicKey::from_verification_method(&method).unwrap()
+    }
+
+    #[test]
+    fn test_did_key_vectors() {
+        // did:key test vectors (w3c-ccg/did-method-key)
+        let document = resolve_did_key("did:key:z6MkiTBz1ymuepAQ4HEHYSF1H8quG5GLVVQR3djdX3mDooWp").unwrap();
+        assert_eq!(
+            document.verification_method[0].id,
+            "did:key:z6MkiTBz1ymuepAQ4HEHYSF1H8quG5GLVVQR3djdX3mDooWp#z6MkiTBz1ymuepAQ4HEHYSF1H8quG5GLVVQR3djdX3mDooWp"
+        );
+        assert!(matches!(key_of(&document, VerificationRelationship::AssertionMethod), PublicKey::Ed25519(_)));
+
+        let document = resolve_did_key("did:key:zDnaerDaTF5BXEavCrfRZEk316dpbLsfPDZ3WJ5hRTPFU2169").unwrap();
+        let key = key_of(&document, VerificationRelationship::Authentication);
+        let jwk = key.to_jwk();
+        assert_eq!(jwk.x.as_deref(), Some("fyNYMN0976ci7xqiSdag3buk-ZCwgXU4kz9XNkBlNUI"));
+        assert_eq!(jwk.y.as_deref(), Some("hW2ojTNfH7Jbi8--CJUo3OCbH3y5n91g-IMA9MLMbTU"));
+
+        let document = resolve_did_key("did:key:zQ3shokFTS3brHcDQrn82RUDfCZESWL1ZdCEJwekUDPQiYBme").unwrap();
+        assert!(matches!(key_of(&document, VerificationRelationship::Authentication), PublicKey::Secp256k1 { .. }));
+
+        // X25519 cannot sign
+        assert!(resolve_did_key("did:key:z6LSeu9HkTHSfLLeUs2nnzUSNedgDUevfNQgQjQC23ZCit6F").is_err());
+        assert!(resolve_did_key("did:key:z6Mk").is_err());
+    }
+
+    #[test]
+    fn test_did_jwk() {
+        let key = TestKey::p256().public();
+        let did = format!("did:jwk:{}", base64url_encode(serde_json::to_vec(&key.to_jwk()).unwrap()));
+        let document = resolve_did_jwk(&did).unwrap();
+        assert_eq!(document.verification_method[0].id, format!("{}#0", did));
+        assert_eq!(key_of(&document, VerificationRelationship::AssertionMethod), key);
+
+        let private = json!({"kty": "OKP", "crv": "Ed25519", "x": "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo", "d": "nWGxne_9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A"});
+        assert!(resolve_did_jwk(&format!("did:jwk:{}", base64url_encode(private.to_string()))).is_err());
+    }
+
+    #[test]
+    fn test_did_peer() {
+        let key = TestKey::ed25519(4).public();
+        let multibase = format!("z{}", base58_encode(&key.to_multicodec()));
+
+        let document = resolve_did_peer(&format!("did:peer:0{}", multibase)).unwrap();
+        assert_eq!(key_of(&document, VerificationRelationship::Authentication), key);
+
+        let x25519 = "z6LSbysY2xFMRpGMhb7tFTLMpeuPRaqaWM1yECx2AtzE3KCc";
+        let service = base64url_encode(r#"{"t":"dm","s":{"uri":"https://peer.example.com","a":["didcomm/v2"]}}"#);
+        let did = format!("did:peer:2.E{}.V{}.S{}", x25519, multibase, service);
+        let document = resolve_did_peer(&did).unwrap();
+        assert_eq!(document.verification_method.len(), 2);
+        assert_eq!(document.verification_method[1].id, "#key-2");
+        assert_eq!(key_of(&document, VerificationRelationship::Authentication), key);
+        assert!(document.verification_method_for(None, VerificationRelationship::AssertionMethod).is_none());
+        let services = document.service.unwrap();
+        assert_eq!(services[0].id, "#service");
+        assert_eq!(services[0].service_type, "DIDCommMessaging");
+        assert_eq!(services[0].service_endpoint, "https://peer.example.com");
+
+        assert!(matches!(
+            resolve_did_peer(&format!("did:peer:4{}", multibase)),
+            Err(ResolverError::MethodNotSupported(_))
+        ));
+        assert!(resolve_did_peer(&format!("did:peer:2.X{}", multibase)).is_err());
+    }
+}
@@ -0,0 +1,102 @@
+//! Pinned DID documents
+//!
+//! A [`DidDocumentStore`] answers before any network lookup, so tests and
+//! air-gapped deployments can resolve did:web, did:ebsi and other
+//! registry-based DIDs from documents shipped alongside the service.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use parking_lot::RwLock;
+
+use crate::ssi::did::DidDocument;
+use crate::ssi::resolver::ResolverError;
+
+/// Source of pinned DID documents
+#[async_trait]
+pub trait DidDocumentStore: Send + Sync {
+    /// Pinned document of `did`, `None` if it is not pinned
+    async fn get(&self, did: &str) -> Result<Option<DidDocument>, ResolverError>;
+}
+
+/// In-memory pinned documents, optionally loaded from a directory
+#[derive(Default)]
+pub struct PinnedDocuments {
+    documents: RwLock<HashMap<String, Arc<DidDocument>>>,
+}
+
+impl PinnedDocuments {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Documents from every `*.json` file in `dir`
+    pub fn from_dir(dir: impl AsRef<Path>) -> Result<Self, ResolverError> {
+        let store = Self::new();
+        store.load_dir(dir)?;
+        Ok(store)
+    }
+
+    /// Pin every `*.json` file in `dir`, keyed by the document `id`
+    ///
+    /// A file holds a DID document or a resolution result with a
+    /// `didDocument` member, as exported by a universal resolver. Returns
+    /// the number of documents loaded; an unreadable file fails the load.
+    pub fn load_dir(&self, dir: impl AsRef<Path>) -> Result<usize, ResolverError> {
+        let dir = dir.as_ref();
+        let store_error = |path: &Path, message: String| ResolverError::Store(format!("{}: {}", path.display(), message));
+
+        let mut paths: Vec<_> = std::fs::read_dir(dir)
+            .map_err(|e| store_error(dir, e.to_string()))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+
+        let mut loaded = Vec::with_capacity(paths.len());
+        for path in &paths {
+            let json = std::fs::read(path).map_err(|e| store_error(path, e.to_string()))?;
+            let mut value: serde_json::Value =
+                serde_json::from_slice(&json).map_err(|e| store_error(path, e.to_string()))?;
+            if let Some(document) = value.get_mut("didDocument") {
+                value = document.take();
+            }
+            let document: DidDocument =
+                serde_json::from_value(value).map_err(|e| store_error(path, e.to_string()))?;
+            loaded.push(document);
+        }
+
+        let count = loaded.len();
+        for document in loaded {
+            self.pin(document);
+        }
+        tracing::info!("Pinned {} DID documents from {}", count, dir.display());
+        Ok(count)
+    }
+
+    /// Pin `document` under its `id`, replacing an earlier pin
+    pub fn pin(&self, document: DidDocument) {
+        self.documents.write().insert(document.id.clone(), Arc::new(document));
+    }
+
+    pub fn unpin(&self, did: &str) -> Option<DidDocument> {
+        self.documents.write().remove(did).map(|document| document.as_ref().clone())
+    }
+
+    pub fn len(&self) -> usize {
+        self.documents.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.documents.read().is_empty()
+    }
+}
+
+#[async_trait]
+impl DidDocumentStore for PinnedDocuments {
+    async fn get(&self, did: &str) -> Result<Option<DidDocument>, ResolverError> {
+        Ok(self.documents.read().get(did).map(|document| document.as_ref().clone()))
+    }
+}
@@ -0,0 +1,83 @@
+//! SEC1 point encoding for P-256 and secp256k1 keys
+//!
+//! did:key and `Multikey` carry EC keys as compressed points; decoding
+//! checks that the point lies on the curve.
+
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+
+/// Curve whose points can be decoded from SEC1
+pub struct Curve {
+    /// Uncompressed SEC1 encoding (`04 || x || y`) of a valid point
+    uncompressed: fn(&[u8]) -> Option<Vec<u8>>,
+}
+
+pub const P256: Curve = Curve {
+    uncompressed: |point| {
+        let key = p256::PublicKey::from_sec1_bytes(point).ok()?;
+        Some(key.to_encoded_point(false).as_bytes().to_vec())
+    },
+};
+
+pub const SECP256K1: Curve = Curve {
+    uncompressed: |point| {
+        let key = k256::PublicKey::from_sec1_bytes(point).ok()?;
+        Some(key.to_encoded_point(false).as_bytes().to_vec())
+    },
+};
+
+impl Curve {
+    /// Affine coordinates (32 bytes each) of a compressed (`02`/`03`) or
+    /// uncompressed (`04`) point on the curve
+    pub fn decode_point(&self, point: &[u8]) -> Option<(Vec<u8>, Vec<u8>)> {
+        let uncompressed = (self.uncompressed)(point)?;
+        let (x, y) = uncompressed.get(1..)?.split_at(32);
+        Some((x.to_vec(), y.to_vec()))
+    }
+
+    /// Whether `(x, y)` is a point on the curve
+    pub fn is_valid_point(&self, x: &[u8], y: &[u8]) -> bool {
+        x.len() == 32 && y.len() == 32 && self.decode_point(&[&[0x04][..], x, y].concat()).is_some()
+    }
+}
+
+/// Compressed point: `02`/`03` by the parity of `y`, then `x`
+pub fn compress(x: &[u8], y: &[u8]) -> Vec<u8> {
+    let prefix = if y.last().is_some_and(|b| b & 1 == 1) { 3 } else { 2 };
+    std::iter::once(prefix).chain(x.iter().copied()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // secp256k1 generator
+    const GX: &str = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+    const GY: &str = "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+
+    #[test]
+    fn test_compressed_round_trip() {
+        let (x, y) = (::hex::decode(GX).unwrap(), ::hex::decode(GY).unwrap());
+        let compressed = compress(&x, &y);
+        assert_eq!(compressed[0], 2);
+        assert_eq!(SECP256K1.decode_point(&compressed), Some((x.clone(), y.clone())));
+
+        // Opposite parity gives the negated point
+        let mut negated = compressed.clone();
+        negated[0] = 3;
+        let (_, minus_y) = SECP256K1.decode_point(&negated).unwrap();
+        assert_ne!(minus_y, y);
+
+        let uncompressed: Vec<u8> = std::iter::once(4).chain(x.clone()).chain(y).collect();
+        assert!(SECP256K1.decode_point(&uncompressed).is_some());
+        // Not a P-256 point
+        assert!(P256.decode_point(&uncompressed).is_none());
+    }
+
+    #[test]
+    fn test_rejects_points_off_curve() {
+        let (x, mut y) = (::hex::decode(GX).unwrap(), ::hex::decode(GY).unwrap());
+        assert!(SECP256K1.is_valid_point(&x, &y));
+        y[31] ^= 1;
+        assert!(!SECP256K1.is_valid_point(&x, &y));
+    }
+}
@@ -0,0 +1,135 @@
+//! Binary encodings used in DIDs, JOSE and Data Integrity proofs
+//!
+//! Base64url (JWS, JWK), base64 (`x5c`, PEM), base58btc (`publicKeyBase58`,
+//! multibase `z`) and multibase prefixes.
+
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE_NO_PAD};
+use thiserror::Error;
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Unpadded base64url, as used by JOSE
+pub fn base64url_encode(data: impl AsRef<[u8]>) -> String {
+    URL_SAFE_NO_PAD.encode(data)
+}
+
+/// Decode base64url, tolerating padding
+pub fn base64url_decode(input: &str) -> Result<Vec<u8>, EncodingError> {
+    URL_SAFE_NO_PAD
+        .decode(input.trim_end_matches('='))
+        .map_err(|e| EncodingError::Base64(e.to_string()))
+}
+
+/// Padded base64, as in `x5c` certificate chains
+pub fn base64_encode(data: impl AsRef<[u8]>) -> String {
+    STANDARD.encode(data)
+}
+
+/// Decode base64, tolerating missing padding
+pub fn base64_decode(input: &str) -> Result<Vec<u8>, EncodingError> {
+    STANDARD_NO_PAD
+        .decode(input.trim_end_matches('='))
+        .map_err(|e| EncodingError::Base64(e.to_string()))
+}
+
+/// Bitcoin base58 alphabet
+pub fn base58_encode(data: &[u8]) -> String {
+    let zeros = data.iter().take_while(|&&b| b == 0).count();
+    // Little-endian base58 digits
+    let mut digits: Vec<u8> = Vec::with_capacity(data.len() * 138 / 100 + 1);
+    for &byte in &data[zeros..] {
+        let mut carry = u32::from(byte);
+        for digit in digits.iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    std::iter::repeat_n('1', zeros)
+        .chain(digits.iter().rev().map(|&d| BASE58_ALPHABET[d as usize] as char))
+        .collect()
+}
+
+pub fn base58_decode(input: &str) -> Result<Vec<u8>, EncodingError> {
+    let zeros = input.bytes().take_while(|&b| b == b'1').count();
+    // Little-endian bytes
+    let mut bytes: Vec<u8> = Vec::with_capacity(input.len() * 733 / 1000 + 1);
+    for c in input.bytes().skip(zeros) {
+        let mut carry = BASE58_ALPHABET
+            .iter()
+            .position(|&a| a == c)
+            .ok_or(EncodingError::Base58(c as char))? as u32;
+        for byte in bytes.iter_mut() {
+            carry += u32::from(*byte) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    Ok(std::iter::repeat_n(0, zeros).chain(bytes.into_iter().rev()).collect())
+}
+
+/// Decode a multibase string (`z` base58btc, `u` base64url)
+pub fn multibase_decode(input: &str) -> Result<Vec<u8>, EncodingError> {
+    let mut chars = input.chars();
+    match chars.next() {
+        Some('z') => base58_decode(chars.as_str()),
+        Some('u') => base64url_decode(chars.as_str()),
+        Some(other) => Err(EncodingError::UnsupportedMultibase(other)),
+        None => Err(EncodingError::UnsupportedMultibase(' ')),
+    }
+}
+
+/// Encoding errors
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum EncodingError {
+    #[error("Invalid base64url: {0}")]
+    Base64(String),
+
+    #[error("Invalid base58 character: {0:?}")]
+    Base58(char),
+
+    #[error("Unsupported multibase prefix: {0:?}")]
+    UnsupportedMultibase(char),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base58_round_trip() {
+        assert_eq!(base58_encode(b"hello world"), "StV1DL6CwTryKyV");
+        assert_eq!(base58_decode("StV1DL6CwTryKyV").unwrap(), b"hello world");
+
+        let leading = [0u8, 0, 1, 2, 255];
+        assert_eq!(base58_decode(&base58_encode(&leading)).unwrap(), leading);
+        assert_eq!(base58_encode(&[]), "");
+        assert_eq!(base58_decode("0"), Err(EncodingError::Base58('0')));
+    }
+
+    #[test]
+    fn test_multibase() {
+        assert_eq!(multibase_decode("zStV1DL6CwTryKyV").unwrap(), b"hello world");
+        assert_eq!(multibase_decode("uaGVsbG8").unwrap(), b"hello");
+        assert!(matches!(multibase_decode("f68656c6c6f"), Err(EncodingError::UnsupportedMultibase('f'))));
+    }
+
+    #[test]
+    fn test_base64url_tolerates_padding() {
+        assert_eq!(base64url_decode("aGk=").unwrap(), b"hi");
+        assert_eq!(base64url_encode(b"hi"), "aGk");
+        assert_eq!(base64_decode("+/8").unwrap(), base64_decode("+/8=").unwrap());
+        assert_eq!(base64_encode([0xfb, 0xff]), "+/8=");
+    }
+}
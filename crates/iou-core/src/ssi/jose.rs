@@ -0,0 +1,531 @@
+//! JSON Web Signatures and public keys for credential verification
+//!
+//! Supports the algorithms used by EUDI and EBSI wallets: EdDSA (Ed25519),
+//! ES256 (P-256) and ES256K (secp256k1).
+
+use k256::ecdsa::signature::Verifier;
+use ring::signature::{self, UnparsedPublicKey};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::ssi::did::{Jwk, VerificationMethod};
+use crate::ssi::ec;
+use crate::ssi::encoding::{base58_decode, base64url_decode, base64url_encode, multibase_decode};
+use crate::ssi::verifiable_credential::VCValidationError;
+
+/// Multicodec prefixes (unsigned varint) of public keys:
+/// `ed25519-pub`, `p256-pub` and `secp256k1-pub`
+const ED25519_MULTICODEC: [u8; 2] = [0xed, 0x01];
+const P256_MULTICODEC: [u8; 2] = [0x80, 0x24];
+const SECP256K1_MULTICODEC: [u8; 2] = [0xe7, 0x01];
+
+/// Signature algorithm (JWS `alg`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JwsAlgorithm {
+    EdDSA,
+    ES256,
+    ES256K,
+}
+
+impl JwsAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::EdDSA => "EdDSA",
+            Self::ES256 => "ES256",
+            Self::ES256K => "ES256K",
+        }
+    }
+}
+
+impl std::str::FromStr for JwsAlgorithm {
+    type Err = VCValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "EdDSA" | "Ed25519" => Ok(Self::EdDSA),
+            "ES256" => Ok(Self::ES256),
+            "ES256K" => Ok(Self::ES256K),
+            other => Err(VCValidationError::UnsupportedProof(format!("algorithm {}", other))),
+        }
+    }
+}
+
+/// Public key of an issuer or holder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PublicKey {
+    /// 32-byte Ed25519 key
+    Ed25519(Vec<u8>),
+    /// P-256 point, 32-byte coordinates
+    P256 { x: Vec<u8>, y: Vec<u8> },
+    /// secp256k1 point, 32-byte coordinates
+    Secp256k1 { x: Vec<u8>, y: Vec<u8> },
+}
+
+impl PublicKey {
+    pub fn from_jwk(jwk: &Jwk) -> Result<Self, VCValidationError> {
+        let coordinate = |value: &Option<String>, name: &str| -> Result<Vec<u8>, VCValidationError> {
+            let bytes = value
+                .as_deref()
+                .ok_or_else(|| invalid_key(format!("JWK without '{}'", name)))
+                .and_then(|v| base64url_decode(v).map_err(|e| invalid_key(e.to_string())))?;
+            if bytes.len() != 32 {
+                return Err(invalid_key(format!("JWK '{}' must be 32 bytes", name)));
+            }
+            Ok(bytes)
+        };
+
+        match (jwk.kty.as_str(), jwk.crv.as_deref()) {
+            ("OKP", Some("Ed25519")) => Ok(Self::Ed25519(coordinate(&jwk.x, "x")?)),
+            ("EC", Some("P-256")) => Ok(Self::P256 {
+                x: coordinate(&jwk.x, "x")?,
+                y: coordinate(&jwk.y, "y")?,
+            }),
+            ("EC", Some("secp256k1")) => {
+                let (x, y) = (coordinate(&jwk.x, "x")?, coordinate(&jwk.y, "y")?);
+                if !ec::SECP256K1.is_valid_point(&x, &y) {
+                    return Err(invalid_key("secp256k1 point is not on the curve".into()));
+                }
+                Ok(Self::Secp256k1 { x, y })
+            }
+            (kty, crv) => Err(invalid_key(format!("unsupported key type {} {}", kty, crv.unwrap_or("")))),
+        }
+    }
+
+    pub fn to_jwk(&self) -> Jwk {
+        let (kty, crv, x, y) = match self {
+            Self::Ed25519(x) => ("OKP", "Ed25519", x, None),
+            Self::P256 { x, y } => ("EC", "P-256", x, Some(y)),
+            Self::Secp256k1 { x, y } => ("EC", "secp256k1", x, Some(y)),
+        };
+        Jwk {
+            kty: kty.to_string(),
+            kid: None,
+            n: None,
+            e: None,
+            x: Some(base64url_encode(x)),
+            y: y.map(base64url_encode),
+            crv: Some(crv.to_string()),
+        }
+    }
+
+    /// Key of a DID document verification method
+    ///
+    /// Reads `publicKeyJwk`, `publicKeyBase58` (Ed25519) or a multicodec
+    /// `publicKeyMultibase`.
+    pub fn from_verification_method(method: &VerificationMethod) -> Result<Self, VCValidationError> {
+        if let Some(jwk) = &method.public_key_jwk {
+            return Self::from_jwk(jwk);
+        }
+        if let Some(base58) = &method.public_key_base58 {
+            let key = base58_decode(base58).map_err(|e| invalid_key(e.to_string()))?;
+            return Self::ed25519(key);
+        }
+        if let Some(multibase) = &method.public_key_multibase {
+            let bytes = multibase_decode(multibase).map_err(|e| invalid_key(e.to_string()))?;
+            return match Self::from_multicodec(&bytes) {
+                Ok(key) => Ok(key),
+                // Raw key in an Ed25519VerificationKey2020
+                Err(_) if method.key_type.starts_with("Ed25519") => Self::ed25519(bytes),
+                Err(e) => Err(e),
+            };
+        }
+        Err(invalid_key(format!("no public key in {}", method.id)))
+    }
+
+    /// Key with a multicodec prefix, as in did:key and `Multikey`
+    ///
+    /// EC points may be compressed or uncompressed.
+    pub fn from_multicodec(bytes: &[u8]) -> Result<Self, VCValidationError> {
+        if let Some(key) = bytes.strip_prefix(&ED25519_MULTICODEC) {
+            return Self::ed25519(key.to_vec());
+        }
+        if let Some(point) = bytes.strip_prefix(&P256_MULTICODEC) {
+            let (x, y) = ec::P256
+                .decode_point(point)
+                .ok_or_else(|| invalid_key("P-256 point is not on the curve".into()))?;
+            return Ok(Self::P256 { x, y });
+        }
+        if let Some(point) = bytes.strip_prefix(&SECP256K1_MULTICODEC) {
+            let (x, y) = ec::SECP256K1
+                .decode_point(point)
+                .ok_or_else(|| invalid_key("secp256k1 point is not on the curve".into()))?;
+            return Ok(Self::Secp256k1 { x, y });
+        }
+        Err(invalid_key("unsupported multicodec key".into()))
+    }
+
+    /// Multicodec form with compressed EC points, inverse of
+    /// [`Self::from_multicodec`]
+    pub fn to_multicodec(&self) -> Vec<u8> {
+        let (prefix, key) = match self {
+            Self::Ed25519(key) => (ED25519_MULTICODEC, key.clone()),
+            Self::P256 { x, y } => (P256_MULTICODEC, ec::compress(x, y)),
+            Self::Secp256k1 { x, y } => (SECP256K1_MULTICODEC, ec::compress(x, y)),
+        };
+        prefix.into_iter().chain(key).collect()
+    }
+
+    fn ed25519(key: Vec<u8>) -> Result<Self, VCValidationError> {
+        if key.len() != 32 {
+            return Err(invalid_key("Ed25519 key must be 32 bytes".into()));
+        }
+        Ok(Self::Ed25519(key))
+    }
+
+    /// JWK thumbprint (RFC 7638), base64url SHA-256
+    pub fn thumbprint(&self) -> String {
+        let jwk = self.to_jwk();
+        // Required members in lexicographic order
+        let canonical = match &jwk.y {
+            Some(y) => format!(
+                r#"{{"crv":"{}","kty":"{}","x":"{}","y":"{}"}}"#,
+                jwk.crv.unwrap_or_default(),
+                jwk.kty,
+                jwk.x.unwrap_or_default(),
+                y
+            ),
+            None => format!(
+                r#"{{"crv":"{}","kty":"{}","x":"{}"}}"#,
+                jwk.crv.unwrap_or_default(),
+                jwk.kty,
+                jwk.x.unwrap_or_default()
+            ),
+        };
+        base64url_encode(Sha256::digest(canonical.as_bytes()))
+    }
+
+    /// Verify `signature` over `message`; the algorithm must fit the key
+    pub fn verify(&self, alg: JwsAlgorithm, message: &[u8], signature: &[u8]) -> Result<(), VCValidationError> {
+        let valid = match (self, alg) {
+            (Self::Ed25519(key), JwsAlgorithm::EdDSA) => UnparsedPublicKey::new(&signature::ED25519, key)
+                .verify(message, signature)
+                .is_ok(),
+            (Self::P256 { x, y }, JwsAlgorithm::ES256) => {
+                let point = [&[0x04][..], x, y].concat();
+                UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_FIXED, point)
+                    .verify(message, signature)
+                    .is_ok()
+            }
+            (Self::Secp256k1 { x, y }, JwsAlgorithm::ES256K) => {
+                let point = [&[0x04][..], x, y].concat();
+                match (
+                    k256::ecdsa::VerifyingKey::from_sec1_bytes(&point),
+                    k256::ecdsa::Signature::from_slice(signature),
+                ) {
+                    (Ok(key), Ok(signature)) => key.verify(message, &signature).is_ok(),
+                    _ => false,
+                }
+            }
+            (key, alg) => {
+                return Err(VCValidationError::InvalidSignature(format!(
+                    "{} cannot be used with a {} key",
+                    alg.as_str(),
+                    key.curve()
+                )));
+            }
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(VCValidationError::InvalidSignature("signature does not match".into()))
+        }
+    }
+
+    fn curve(&self) -> &'static str {
+        match self {
+            Self::Ed25519(_) => "Ed25519",
+            Self::P256 { .. } => "P-256",
+            Self::Secp256k1 { .. } => "secp256k1",
+        }
+    }
+}
+
+fn invalid_key(message: String) -> VCValidationError {
+    VCValidationError::InvalidFormat(format!("Invalid public key: {}", message))
+}
+
+/// Protected header of a JWS
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JwsHeader {
+    pub alg: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub typ: Option<String>,
+    /// Embedded key, e.g. in key binding JWTs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jwk: Option<Jwk>,
+    /// Signer's certificate chain, leaf first, base64 DER
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x5c: Option<Vec<String>>,
+}
+
+/// Compact JWS (`header.payload.signature`)
+#[derive(Debug, Clone)]
+pub struct Jws {
+    pub header: JwsHeader,
+    pub payload: Vec<u8>,
+    signing_input: String,
+    signature: Vec<u8>,
+}
+
+impl Jws {
+    pub fn parse(compact: &str) -> Result<Self, VCValidationError> {
+        let format = |message: &str| VCValidationError::InvalidFormat(format!("Invalid JWS: {}", message));
+        let mut parts = compact.trim().split('.');
+        let (Some(header), Some(payload), Some(signature), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(format("expected three parts"));
+        };
+
+        let decode = |part: &str| base64url_decode(part).map_err(|e| format(&e.to_string()));
+        let header_json = decode(header)?;
+        Ok(Self {
+            header: serde_json::from_slice(&header_json).map_err(|e| format(&e.to_string()))?,
+            payload: decode(payload)?,
+            signing_input: format!("{}.{}", header, payload),
+            signature: decode(signature)?,
+        })
+    }
+
+    pub fn algorithm(&self) -> Result<JwsAlgorithm, VCValidationError> {
+        self.header.alg.parse()
+    }
+
+    /// Payload as JSON
+    pub fn claims<T: DeserializeOwned>(&self) -> Result<T, VCValidationError> {
+        serde_json::from_slice(&self.payload)
+            .map_err(|e| VCValidationError::InvalidFormat(format!("Invalid JWT claims: {}", e)))
+    }
+
+    pub fn verify(&self, key: &PublicKey) -> Result<(), VCValidationError> {
+        key.verify(self.algorithm()?, self.signing_input.as_bytes(), &self.signature)
+    }
+}
+
+/// Private key of this service, for signing requests to wallets
+///
+/// Credentials and presentations are only ever verified; this signs
+/// what the verifier itself sends, such as OpenID4VP request objects.
+pub struct SigningKey {
+    pair: KeyPair,
+}
+
+enum KeyPair {
+    Ed25519(signature::Ed25519KeyPair),
+    P256(signature::EcdsaKeyPair),
+}
+
+impl SigningKey {
+    /// P-256 or Ed25519 key in PKCS#8 DER
+    pub fn from_pkcs8(der: &[u8]) -> Result<Self, VCValidationError> {
+        let rng = ring::rand::SystemRandom::new();
+        let p256 = signature::EcdsaKeyPair::from_pkcs8(&signature::ECDSA_P256_SHA256_FIXED_SIGNING, der, &rng);
+        let pair = match p256 {
+            Ok(pair) => KeyPair::P256(pair),
+            Err(_) => signature::Ed25519KeyPair::from_pkcs8_maybe_unchecked(der)
+                .map(KeyPair::Ed25519)
+                .map_err(|_| invalid_key("signing key must be P-256 or Ed25519 PKCS#8".into()))?,
+        };
+        Ok(Self { pair })
+    }
+
+    /// Fresh P-256 key, for development setups without a configured key
+    pub fn generate_p256() -> Result<Self, VCValidationError> {
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = signature::EcdsaKeyPair::generate_pkcs8(&signature::ECDSA_P256_SHA256_FIXED_SIGNING, &rng)
+            .map_err(|_| invalid_key("key generation failed".into()))?;
+        Self::from_pkcs8(pkcs8.as_ref())
+    }
+
+    pub fn algorithm(&self) -> JwsAlgorithm {
+        match self.pair {
+            KeyPair::Ed25519(_) => JwsAlgorithm::EdDSA,
+            KeyPair::P256(_) => JwsAlgorithm::ES256,
+        }
+    }
+
+    pub fn public(&self) -> PublicKey {
+        use ring::signature::KeyPair as _;
+        match &self.pair {
+            KeyPair::Ed25519(pair) => PublicKey::Ed25519(pair.public_key().as_ref().to_vec()),
+            KeyPair::P256(pair) => {
+                let point = pair.public_key().as_ref();
+                PublicKey::P256 { x: point[1..33].to_vec(), y: point[33..].to_vec() }
+            }
+        }
+    }
+
+    /// Raw signature: `r || s` for ES256
+    pub fn sign(&self, message: &[u8]) -> Result<Vec<u8>, VCValidationError> {
+        match &self.pair {
+            KeyPair::Ed25519(pair) => Ok(pair.sign(message).as_ref().to_vec()),
+            KeyPair::P256(pair) => pair
+                .sign(&ring::rand::SystemRandom::new(), message)
+                .map(|signature| signature.as_ref().to_vec())
+                .map_err(|_| VCValidationError::InvalidSignature("signing failed".into())),
+        }
+    }
+
+    /// Compact JWS over `claims`; `alg` is set from the key
+    pub fn sign_jwt<T: Serialize>(&self, mut header: JwsHeader, claims: &T) -> Result<String, VCValidationError> {
+        header.alg = self.algorithm().as_str().to_string();
+        let json = |result: serde_json::Result<Vec<u8>>| {
+            result
+                .map(base64url_encode)
+                .map_err(|e| VCValidationError::InvalidFormat(format!("Invalid JWT: {}", e)))
+        };
+        let input = format!("{}.{}", json(serde_json::to_vec(&header))?, json(serde_json::to_vec(claims))?);
+        let signature = self.sign(input.as_bytes())?;
+        Ok(format!("{}.{}", input, base64url_encode(signature)))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_keys {
+    //! Signing keys for tests; production code only verifies
+
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{EcdsaKeyPair, Ed25519KeyPair, KeyPair};
+
+    pub enum TestKey {
+        Ed25519(Ed25519KeyPair),
+        P256(EcdsaKeyPair),
+    }
+
+    impl TestKey {
+        pub fn ed25519(seed: u8) -> Self {
+            Self::Ed25519(Ed25519KeyPair::from_seed_unchecked(&[seed; 32]).unwrap())
+        }
+
+        pub fn p256() -> Self {
+            let rng = SystemRandom::new();
+            let alg = &signature::ECDSA_P256_SHA256_FIXED_SIGNING;
+            let pkcs8 = EcdsaKeyPair::generate_pkcs8(alg, &rng).unwrap();
+            Self::P256(EcdsaKeyPair::from_pkcs8(alg, pkcs8.as_ref(), &rng).unwrap())
+        }
+
+        pub fn alg(&self) -> JwsAlgorithm {
+            match self {
+                Self::Ed25519(_) => JwsAlgorithm::EdDSA,
+                Self::P256(_) => JwsAlgorithm::ES256,
+            }
+        }
+
+        pub fn public(&self) -> PublicKey {
+            match self {
+                Self::Ed25519(pair) => PublicKey::Ed25519(pair.public_key().as_ref().to_vec()),
+                Self::P256(pair) => {
+                    let point = pair.public_key().as_ref();
+                    PublicKey::P256 { x: point[1..33].to_vec(), y: point[33..].to_vec() }
+                }
+            }
+        }
+
+        pub fn sign(&self, message: &[u8]) -> Vec<u8> {
+            match self {
+                Self::Ed25519(pair) => pair.sign(message).as_ref().to_vec(),
+                Self::P256(pair) => pair.sign(&SystemRandom::new(), message).unwrap().as_ref().to_vec(),
+            }
+        }
+
+        /// Compact JWS over `claims`
+        pub fn jwt(&self, header: serde_json::Value, claims: &serde_json::Value) -> String {
+            let mut header = header;
+            header["alg"] = self.alg().as_str().into();
+            let input = format!(
+                "{}.{}",
+                base64url_encode(header.to_string()),
+                base64url_encode(claims.to_string())
+            );
+            format!("{}.{}", input, base64url_encode(self.sign(input.as_bytes())))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_keys::TestKey;
+    use super::*;
+
+    #[test]
+    fn test_jws_round_trip_and_tampering() {
+        for key in [TestKey::ed25519(7), TestKey::p256()] {
+            let jwt = key.jwt(serde_json::json!({"kid": "did:example:issuer#key-1"}), &serde_json::json!({"iss": "did:example:issuer"}));
+            let jws = Jws::parse(&jwt).unwrap();
+            assert_eq!(jws.header.kid.as_deref(), Some("did:example:issuer#key-1"));
+            jws.verify(&key.public()).unwrap();
+
+            let other = TestKey::ed25519(8);
+            assert!(jws.verify(&other.public()).is_err());
+
+            // Swap the payload, keep the signature
+            let parts: Vec<&str> = jwt.split('.').collect();
+            let forged = format!("{}.{}.{}", parts[0], base64url_encode(r#"{"iss":"did:example:evil"}"#), parts[2]);
+            assert!(Jws::parse(&forged).unwrap().verify(&key.public()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_jwk_round_trip_and_thumbprint() {
+        let key = TestKey::p256().public();
+        assert_eq!(PublicKey::from_jwk(&key.to_jwk()).unwrap(), key);
+
+        // RFC 8037 appendix A.3
+        let jwk = Jwk {
+            kty: "OKP".into(),
+            kid: None,
+            n: None,
+            e: None,
+            x: Some("11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo".into()),
+            y: None,
+            crv: Some("Ed25519".into()),
+        };
+        let key = PublicKey::from_jwk(&jwk).unwrap();
+        assert_eq!(key.thumbprint(), "kPrK_qmxVWaYVA9wwBF6Iuo3vVzz7TxHCTwXBygrS4k");
+    }
+
+    #[test]
+    fn test_multicodec_round_trip() {
+        for key in [TestKey::ed25519(3).public(), TestKey::p256().public()] {
+            let bytes = key.to_multicodec();
+            assert_eq!(PublicKey::from_multicodec(&bytes).unwrap(), key);
+        }
+        assert_eq!(TestKey::p256().public().to_multicodec().len(), 2 + 33);
+        // X25519 (key agreement only)
+        assert!(PublicKey::from_multicodec(&[0xec, 0x01, 1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_es256k_known_signature() {
+        // Generated with OpenSSL (secp256k1, SHA-256)
+        let bytes = |s: &str| ::hex::decode(s).unwrap();
+        let key = PublicKey::Secp256k1 {
+            x: bytes("f812482455050e1887571192cb9e13d1d8a17cd7cb9dfa3cec08732edf64dfdd"),
+            y: bytes("fea23629ce537ff79d038214e766b4d4b3e4279038ccdde6a46d09941ae5c7ea"),
+        };
+        let sig = bytes(
+            "a5d351abdac15f068098f2d79bc0c17918bad1cbd0916150f17491c02fddf714\
+             7d699f312dbe9e229c73c29c55161c20d370e07858aeda5051ebd466ea454c50",
+        );
+        key.verify(JwsAlgorithm::ES256K, b"iou-modern es256k", &sig).unwrap();
+        assert!(key.verify(JwsAlgorithm::ES256K, b"iou-modern es256K", &sig).is_err());
+
+        let mut forged = sig.clone();
+        forged[40] ^= 1;
+        assert!(key.verify(JwsAlgorithm::ES256K, b"iou-modern es256k", &forged).is_err());
+        assert!(key.verify(JwsAlgorithm::ES256K, b"iou-modern es256k", &sig[..63]).is_err());
+    }
+
+    #[test]
+    fn test_algorithm_must_fit_key() {
+        let key = TestKey::ed25519(1);
+        let result = key.public().verify(JwsAlgorithm::ES256, b"message", &key.sign(b"message"));
+        assert!(matches!(result, Err(VCValidationError::InvalidSignature(_))));
+        assert!("HS256".parse::<JwsAlgorithm>().is_err());
+    }
+}
@@ -0,0 +1,558 @@
+//! ISO/IEC 18013-5 mdoc presentations (`mso_mdoc`)
+//!
+//! A `DeviceResponse` holds documents with issuer-signed elements and a
+//! device signature. The issuer signs a Mobile Security Object (MSO) with
+//! digests of every element and the device key; the wallet proves
+//! possession of that key by signing the session transcript, which for
+//! OpenID4VP binds the client id, nonce and response URI.
+
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::ssi::cbor::Cbor;
+use crate::ssi::ec;
+use crate::ssi::encoding::base64url_decode;
+use crate::ssi::jose::{JwsAlgorithm, PublicKey};
+use crate::ssi::verifiable_credential::VCValidationError;
+use crate::ssi::x509::{self, Certificate};
+
+/// COSE header labels and values (RFC 9052, RFC 9360)
+const COSE_ALG: i64 = 1;
+const COSE_X5CHAIN: i64 = 33;
+const COSE_ES256: i64 = -7;
+const COSE_EDDSA: i64 = -8;
+const COSE_ES256K: i64 = -47;
+
+/// COSE_Key labels and values (RFC 9053)
+const KEY_KTY: i64 = 1;
+const KEY_CRV: i64 = -1;
+const KEY_X: i64 = -2;
+const KEY_Y: i64 = -3;
+const KTY_OKP: i64 = 1;
+const KTY_EC2: i64 = 2;
+const CRV_P256: i64 = 1;
+const CRV_ED25519: i64 = 6;
+
+fn invalid(message: impl std::fmt::Display) -> VCValidationError {
+    VCValidationError::InvalidFormat(format!("Invalid mdoc: {}", message))
+}
+
+/// Parsed `DeviceResponse`
+#[derive(Debug, Clone)]
+pub struct DeviceResponse {
+    pub documents: Vec<Mdoc>,
+}
+
+impl DeviceResponse {
+    /// Base64url CBOR, as presented in a `vp_token`
+    pub fn parse(encoded: &str) -> Result<Self, VCValidationError> {
+        Self::from_cbor(&base64url_decode(encoded.trim()).map_err(invalid)?)
+    }
+
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self, VCValidationError> {
+        let response = Cbor::decode(bytes).map_err(invalid)?;
+        if let Some(status) = response.get("status").and_then(Cbor::as_i64)
+            && status != 0
+        {
+            return Err(invalid(format!("wallet returned status {}", status)));
+        }
+        let documents = response
+            .get("documents")
+            .and_then(Cbor::as_array)
+            .ok_or_else(|| invalid("no documents"))?
+            .iter()
+            .map(Mdoc::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { documents })
+    }
+}
+
+/// Issuer-signed element as disclosed
+#[derive(Debug, Clone)]
+struct IssuerSignedItem {
+    namespace: String,
+    digest_id: i64,
+    identifier: String,
+    value: Cbor,
+    /// `#6.24(bstr)` as presented, the input of the digest
+    encoded: Vec<u8>,
+}
+
+/// One document of a `DeviceResponse`
+#[derive(Debug, Clone)]
+pub struct Mdoc {
+    pub doc_type: String,
+    items: Vec<IssuerSignedItem>,
+    issuer_auth: CoseSign1,
+    mso: Cbor,
+    /// `DeviceNameSpacesBytes`, signed by the device
+    device_namespaces: Cbor,
+    device_signature: Option<CoseSign1>,
+}
+
+impl Mdoc {
+    fn parse(document: &Cbor) -> Result<Self, VCValidationError> {
+        let field = |item: &'_ Cbor, name: &str| item.get(name).cloned().ok_or_else(|| invalid(format!("no {}", name)));
+        let doc_type = field(document, "docType")?.as_text().ok_or_else(|| invalid("docType"))?.to_string();
+        let issuer_signed = field(document, "issuerSigned")?;
+        let device_signed = field(document, "deviceSigned")?;
+
+        let mut items = Vec::new();
+        for (namespace, elements) in issuer_signed.get("nameSpaces").and_then(Cbor::as_map).into_iter().flatten() {
+            let namespace = namespace.as_text().ok_or_else(|| invalid("namespace"))?;
+            for element in elements.as_array().ok_or_else(|| invalid("namespace elements"))? {
+                let item = element.embedded().map_err(invalid)?;
+                items.push(IssuerSignedItem {
+                    namespace: namespace.to_string(),
+                    digest_id: field(&item, "digestID")?.as_i64().ok_or_else(|| invalid("digestID"))?,
+                    identifier: field(&item, "elementIdentifier")?.as_text().ok_or_else(|| invalid("elementIdentifier"))?.to_string(),
+                    value: field(&item, "elementValue")?,
+                    encoded: element.encode(),
+                });
+            }
+        }
+
+        let issuer_auth = CoseSign1::parse(&field(&issuer_signed, "issuerAuth")?)?;
+        let payload = issuer_auth.payload.as_deref().ok_or_else(|| invalid("issuerAuth without payload"))?;
+        let mso = Cbor::decode(payload).and_then(|tagged| tagged.embedded()).map_err(invalid)?;
+
+        let device_signature = device_signed
+            .get("deviceAuth")
+            .and_then(|auth| auth.get("deviceSignature"))
+            .map(CoseSign1::parse)
+            .transpose()?;
+
+        Ok(Self {
+            doc_type,
+            items,
+            issuer_auth,
+            mso,
+            device_namespaces: field(&device_signed, "nameSpaces")?,
+            device_signature,
+        })
+    }
+
+    /// Disclosed elements per namespace, as JSON
+    pub fn claims(&self) -> Value {
+        let mut namespaces: Map<String, Value> = Map::new();
+        for item in &self.items {
+            let elements = namespaces.entry(item.namespace.clone()).or_insert_with(|| Value::Object(Map::new()));
+            elements[item.identifier.as_str()] = item.value.to_json();
+        }
+        Value::Object(namespaces)
+    }
+
+    /// Verify the issuer: document signer certificate chain, MSO signature,
+    /// element digests, document type and validity
+    ///
+    /// Returns the document signer certificate.
+    pub fn verify_issuer(&self, roots: &[Certificate], now: DateTime<Utc>) -> Result<Certificate, VCValidationError> {
+        let chain = self.issuer_auth.x5chain()?;
+        x509::verify_chain(&chain, roots, now)?;
+        let signer = chain.into_iter().next().ok_or_else(|| invalid("no document signer certificate"))?;
+        self.issuer_auth.verify(&signer.public_key()?, None)?;
+
+        if self.mso.get("docType").and_then(Cbor::as_text) != Some(self.doc_type.as_str()) {
+            return Err(VCValidationError::InvalidSignature("docType differs from the signed MSO".into()));
+        }
+        match self.mso.get("digestAlgorithm").and_then(Cbor::as_text) {
+            Some("SHA-256") => {}
+            other => return Err(VCValidationError::UnsupportedProof(format!("digest algorithm {:?}", other))),
+        }
+        let digests = self.mso.get("valueDigests").ok_or_else(|| invalid("MSO without valueDigests"))?;
+        for item in &self.items {
+            let signed = digests
+                .get(&item.namespace)
+                .and_then(|namespace| namespace.get_int(item.digest_id))
+                .and_then(Cbor::as_bytes);
+            if signed != Some(Sha256::digest(&item.encoded).as_slice()) {
+                return Err(VCValidationError::InvalidSignature(format!(
+                    "element {}/{} not covered by the issuer signature",
+                    item.namespace, item.identifier
+                )));
+            }
+        }
+
+        let validity = self.mso.get("validityInfo").ok_or_else(|| invalid("MSO without validityInfo"))?;
+        let date = |name: &str| {
+            validity
+                .get(name)
+                .and_then(Cbor::as_date_text)
+                .and_then(|text| DateTime::parse_from_rfc3339(text).ok())
+                .map(|date| date.with_timezone(&Utc))
+                .ok_or_else(|| invalid(format!("validityInfo.{}", name)))
+        };
+        if now < date("validFrom")? {
+            return Err(VCValidationError::NotYetValid);
+        }
+        if now > date("validUntil")? {
+            return Err(VCValidationError::Expired);
+        }
+        Ok(signer)
+    }
+
+    /// Verify the device signature over `session_transcript` with the key
+    /// the issuer put in the MSO
+    pub fn verify_device(&self, session_transcript: &Cbor) -> Result<(), VCValidationError> {
+        let holding = |message: &str| VCValidationError::HolderBinding(message.to_string());
+        let signature = self.device_signature.as_ref().ok_or_else(|| holding("no device signature (MAC is not supported)"))?;
+        let device_key = self
+            .mso
+            .get("deviceKeyInfo")
+            .and_then(|info| info.get("deviceKey"))
+            .ok_or_else(|| invalid("MSO without deviceKey"))?;
+
+        let authentication = Cbor::Array(vec![
+            Cbor::text("DeviceAuthentication"),
+            session_transcript.clone(),
+            Cbor::text(self.doc_type.clone()),
+            self.device_namespaces.clone(),
+        ]);
+        signature
+            .verify(&cose_key(device_key)?, Some(&Cbor::embed(&authentication).encode()))
+            .map_err(|e| holding(&e.to_string()))
+    }
+}
+
+/// `SessionTranscript` of an OpenID4VP request answered with `direct_post`
+/// (OpenID4VP 1.0, appendix B.2.6.1); `jwk_thumbprint` is that of the
+/// verifier's encryption key, when the response is encrypted
+pub fn openid4vp_session_transcript(
+    client_id: &str,
+    nonce: &str,
+    jwk_thumbprint: Option<&[u8]>,
+    response_uri: &str,
+) -> Cbor {
+    let info = Cbor::Array(vec![
+        Cbor::text(client_id),
+        Cbor::text(nonce),
+        jwk_thumbprint.map(|t| Cbor::Bytes(t.to_vec())).unwrap_or(Cbor::Null),
+        Cbor::text(response_uri),
+    ]);
+    let handover = Cbor::Array(vec![
+        Cbor::text("OpenID4VPHandover"),
+        Cbor::Bytes(Sha256::digest(info.encode()).to_vec()),
+    ]);
+    Cbor::Array(vec![Cbor::Null, Cbor::Null, handover])
+}
+
+/// Public key of a COSE_Key: EC2 P-256 or OKP Ed25519
+fn cose_key(key: &Cbor) -> Result<PublicKey, VCValidationError> {
+    let bytes = |label: i64| key.get_int(label).and_then(Cbor::as_bytes).ok_or_else(|| invalid("COSE_Key coordinate"));
+    let kty = key.get_int(KEY_KTY).and_then(Cbor::as_i64);
+    let crv = key.get_int(KEY_CRV).and_then(Cbor::as_i64);
+    match (kty, crv) {
+        (Some(KTY_EC2), Some(CRV_P256)) => {
+            let x = bytes(KEY_X)?;
+            let point = match key.get_int(KEY_Y) {
+                // Compressed: the sign bit of y
+                Some(Cbor::Bool(odd)) => [&[if *odd { 3 } else { 2 }][..], x].concat(),
+                _ => [&[4][..], x, bytes(KEY_Y)?].concat(),
+            };
+            let (x, y) = ec::P256.decode_point(&point).ok_or_else(|| invalid("device key is not on the curve"))?;
+            Ok(PublicKey::P256 { x, y })
+        }
+        (Some(KTY_OKP), Some(CRV_ED25519)) => Ok(PublicKey::Ed25519(bytes(KEY_X)?.to_vec())),
+        _ => Err(VCValidationError::UnsupportedProof(format!("COSE_Key kty {:?} crv {:?}", kty, crv))),
+    }
+}
+
+/// COSE_Sign1 (RFC 9052)
+#[derive(Debug, Clone)]
+struct CoseSign1 {
+    protected: Vec<u8>,
+    unprotected: Cbor,
+    payload: Option<Vec<u8>>,
+    signature: Vec<u8>,
+}
+
+impl CoseSign1 {
+    fn parse(item: &Cbor) -> Result<Self, VCValidationError> {
+        // Optionally tagged COSE_Sign1 (18)
+        let item = match item {
+            Cbor::Tag(18, inner) => inner.as_ref(),
+            other => other,
+        };
+        let [protected, unprotected, payload, signature] = item.as_array().unwrap_or_default() else {
+            return Err(invalid("COSE_Sign1 must have four elements"));
+        };
+        Ok(Self {
+            protected: protected.as_bytes().ok_or_else(|| invalid("COSE protected header"))?.to_vec(),
+            unprotected: unprotected.clone(),
+            payload: match payload {
+                Cbor::Null => None,
+                other => Some(other.as_bytes().ok_or_else(|| invalid("COSE payload"))?.to_vec()),
+            },
+            signature: signature.as_bytes().ok_or_else(|| invalid("COSE signature"))?.to_vec(),
+        })
+    }
+
+    fn protected_header(&self) -> Result<Cbor, VCValidationError> {
+        if self.protected.is_empty() {
+            return Ok(Cbor::Map(vec![]));
+        }
+        Cbor::decode(&self.protected).map_err(invalid)
+    }
+
+    /// Certificate chain (`x5chain`), leaf first
+    fn x5chain(&self) -> Result<Vec<Certificate>, VCValidationError> {
+        let protected = self.protected_header()?;
+        let chain = protected
+            .get_int(COSE_X5CHAIN)
+            .or_else(|| self.unprotected.get_int(COSE_X5CHAIN))
+            .ok_or_else(|| invalid("issuerAuth without x5chain"))?;
+        match chain {
+            Cbor::Bytes(der) => Ok(vec![Certificate::from_der(der)?]),
+            Cbor::Array(ders) => ders
+                .iter()
+                .map(|der| Certificate::from_der(der.as_bytes().ok_or_else(|| invalid("x5chain entry"))?))
+                .collect(),
+            _ => Err(invalid("x5chain")),
+        }
+    }
+
+    /// Verify over the attached payload, or `detached` when the payload is nil
+    fn verify(&self, key: &PublicKey, detached: Option<&[u8]>) -> Result<(), VCValidationError> {
+        let algorithm = match self.protected_header()?.get_int(COSE_ALG).and_then(Cbor::as_i64) {
+            Some(COSE_ES256) => JwsAlgorithm::ES256,
+            Some(COSE_EDDSA) => JwsAlgorithm::EdDSA,
+            Some(COSE_ES256K) => JwsAlgorithm::ES256K,
+            other => return Err(VCValidationError::UnsupportedProof(format!("COSE algorithm {:?}", other))),
+        };
+        let payload = match (&self.payload, detached) {
+            (Some(payload), None) => payload.as_slice(),
+            (None, Some(detached)) => detached,
+            _ => return Err(invalid("COSE payload must be either attached or detached")),
+        };
+        key.verify(algorithm, &sig_structure(&self.protected, payload), &self.signature)
+    }
+}
+
+/// `Sig_structure` for COSE_Sign1 without external AAD
+fn sig_structure(protected: &[u8], payload: &[u8]) -> Vec<u8> {
+    Cbor::Array(vec![
+        Cbor::text("Signature1"),
+        Cbor::Bytes(protected.to_vec()),
+        Cbor::Bytes(vec![]),
+        Cbor::Bytes(payload.to_vec()),
+    ])
+    .encode()
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    //! Issue mdocs and answer as a wallet in tests
+
+    use super::*;
+    use crate::ssi::encoding::base64url_encode;
+    use crate::ssi::jose::test_keys::TestKey;
+    use chrono::Duration;
+
+    /// Issuer-signed document, before selective disclosure
+    pub struct IssuedMdoc {
+        doc_type: String,
+        /// Namespace, element identifier and `#6.24` item
+        pub items: Vec<(String, String, Cbor)>,
+        issuer_auth: Cbor,
+    }
+
+    fn sign1(key: &TestKey, payload: Option<&[u8]>, detached: Option<&[u8]>, unprotected: Cbor) -> Cbor {
+        let protected = Cbor::Map(vec![(Cbor::int(COSE_ALG), Cbor::int(COSE_ES256))]).encode();
+        let signed = payload.or(detached).unwrap();
+        let signature = key.sign(&sig_structure(&protected, signed));
+        Cbor::Array(vec![
+            Cbor::Bytes(protected),
+            unprotected,
+            payload.map(|p| Cbor::Bytes(p.to_vec())).unwrap_or(Cbor::Null),
+            Cbor::Bytes(signature),
+        ])
+    }
+
+    fn date(time: DateTime<Utc>) -> Cbor {
+        Cbor::Tag(0, Box::new(Cbor::text(time.format("%Y-%m-%dT%H:%M:%SZ").to_string())))
+    }
+
+    pub fn issue(
+        doc_type: &str,
+        elements: &[(&str, &str, Cbor)],
+        issuer: &TestKey,
+        chain: &[Certificate],
+        device: &TestKey,
+    ) -> IssuedMdoc {
+        let mut items = Vec::new();
+        let mut digests: Vec<(Cbor, Cbor)> = Vec::new();
+        for (digest_id, (namespace, identifier, value)) in elements.iter().enumerate() {
+            let item = Cbor::embed(&Cbor::Map(vec![
+                (Cbor::text("digestID"), Cbor::int(digest_id as i64)),
+                (Cbor::text("random"), Cbor::Bytes(vec![digest_id as u8; 16])),
+                (Cbor::text("elementIdentifier"), Cbor::text(*identifier)),
+                (Cbor::text("elementValue"), value.clone()),
+            ]));
+            let digest = Cbor::Bytes(Sha256::digest(item.encode()).to_vec());
+            match digests.iter_mut().find(|(ns, _)| ns.as_text() == Some(namespace)) {
+                Some((_, Cbor::Map(entries))) => entries.push((Cbor::int(digest_id as i64), digest)),
+                _ => digests.push((Cbor::text(*namespace), Cbor::Map(vec![(Cbor::int(digest_id as i64), digest)]))),
+            }
+            items.push((namespace.to_string(), identifier.to_string(), item));
+        }
+
+        let PublicKey::P256 { x, y } = device.public() else {
+            panic!("device keys are P-256");
+        };
+        let now = Utc::now();
+        let mso = Cbor::Map(vec![
+            (Cbor::text("version"), Cbor::text("1.0")),
+            (Cbor::text("digestAlgorithm"), Cbor::text("SHA-256")),
+            (Cbor::text("valueDigests"), Cbor::Map(digests)),
+            (
+                Cbor::text("deviceKeyInfo"),
+                Cbor::Map(vec![(
+                    Cbor::text("deviceKey"),
+                    Cbor::Map(vec![
+                        (Cbor::int(KEY_KTY), Cbor::int(KTY_EC2)),
+                        (Cbor::int(KEY_CRV), Cbor::int(CRV_P256)),
+                        (Cbor::int(KEY_X), Cbor::Bytes(x)),
+                        (Cbor::int(KEY_Y), Cbor::Bytes(y)),
+                    ]),
+                )]),
+            ),
+            (Cbor::text("docType"), Cbor::text(doc_type)),
+            (
+                Cbor::text("validityInfo"),
+                Cbor::Map(vec![
+                    (Cbor::text("signed"), date(now)),
+                    (Cbor::text("validFrom"), date(now - Duration::days(1))),
+                    (Cbor::text("validUntil"), date(now + Duration::days(365))),
+                ]),
+            ),
+        ]);
+        let x5chain = Cbor::Array(chain.iter().map(|c| Cbor::Bytes(c.der().to_vec())).collect());
+        let issuer_auth = sign1(
+            issuer,
+            Some(&Cbor::embed(&mso).encode()),
+            None,
+            Cbor::Map(vec![(Cbor::int(COSE_X5CHAIN), x5chain)]),
+        );
+        IssuedMdoc { doc_type: doc_type.to_string(), items, issuer_auth }
+    }
+
+    /// Base64url `DeviceResponse` disclosing `disclose`, signed by `device`
+    /// over `session_transcript`
+    pub fn present(mdoc: &IssuedMdoc, disclose: &[&str], device: &TestKey, session_transcript: &Cbor) -> String {
+        let mut namespaces: Vec<(Cbor, Cbor)> = Vec::new();
+        for (namespace, _, item) in mdoc.items.iter().filter(|(_, id, _)| disclose.contains(&id.as_str())) {
+            match namespaces.iter_mut().find(|(ns, _)| ns.as_text() == Some(namespace)) {
+                Some((_, Cbor::Array(items))) => items.push(item.clone()),
+                _ => namespaces.push((Cbor::text(namespace.clone()), Cbor::Array(vec![item.clone()]))),
+            }
+        }
+
+        let device_namespaces = Cbor::embed(&Cbor::Map(vec![]));
+        let authentication = Cbor::Array(vec![
+            Cbor::text("DeviceAuthentication"),
+            session_transcript.clone(),
+            Cbor::text(mdoc.doc_type.clone()),
+            device_namespaces.clone(),
+        ]);
+        let device_signature = sign1(device, None, Some(&Cbor::embed(&authentication).encode()), Cbor::Map(vec![]));
+
+        let document = Cbor::Map(vec![
+            (Cbor::text("docType"), Cbor::text(mdoc.doc_type.clone())),
+            (
+                Cbor::text("issuerSigned"),
+                Cbor::Map(vec![
+                    (Cbor::text("nameSpaces"), Cbor::Map(namespaces)),
+                    (Cbor::text("issuerAuth"), mdoc.issuer_auth.clone()),
+                ]),
+            ),
+            (
+                Cbor::text("deviceSigned"),
+                Cbor::Map(vec![
+                    (Cbor::text("nameSpaces"), device_namespaces),
+                    (
+                        Cbor::text("deviceAuth"),
+                        Cbor::Map(vec![(Cbor::text("deviceSignature"), device_signature)]),
+                    ),
+                ]),
+            ),
+        ]);
+        let response = Cbor::Map(vec![
+            (Cbor::text("version"), Cbor::text("1.0")),
+            (Cbor::text("documents"), Cbor::Array(vec![document])),
+            (Cbor::text("status"), Cbor::int(0)),
+        ]);
+        base64url_encode(response.encode())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::*;
+    use super::*;
+    use crate::ssi::jose::test_keys::TestKey;
+    use crate::ssi::x509::test_support::certificate;
+
+    const PID: &str = "eu.europa.ec.eudi.pid.1";
+
+    #[test]
+    fn test_verify_device_response() {
+        let (root_key, issuer_key, device_key) = (TestKey::p256(), TestKey::p256(), TestKey::p256());
+        let root = certificate(("PID IACA", &root_key), ("PID IACA", &root_key), true);
+        let signer = certificate(("PID DS", &issuer_key), ("PID IACA", &root_key), false);
+        let mdoc = issue(
+            PID,
+            &[
+                (PID, "family_name", Cbor::text("Jansen")),
+                (PID, "given_name", Cbor::text("Anna")),
+                (PID, "age_over_18", Cbor::Bool(true)),
+            ],
+            &issuer_key,
+            std::slice::from_ref(&signer),
+            &device_key,
+        );
+        let transcript = openid4vp_session_transcript("x509_san_dns:iou.example", "n-0S6", None, "https://iou.example/r");
+        let now = Utc::now();
+
+        let response = DeviceResponse::parse(&present(&mdoc, &["family_name", "age_over_18"], &device_key, &transcript)).unwrap();
+        let document = &response.documents[0];
+        assert_eq!(document.verify_issuer(std::slice::from_ref(&root), now).unwrap().common_name().as_deref(), Some("PID DS"));
+        document.verify_device(&transcript).unwrap();
+        assert_eq!(
+            document.claims(),
+            serde_json::json!({PID: {"family_name": "Jansen", "age_over_18": true}})
+        );
+
+        // Signed for another verifier or nonce
+        let other = openid4vp_session_transcript("x509_san_dns:iou.example", "other", None, "https://iou.example/r");
+        assert!(matches!(document.verify_device(&other), Err(VCValidationError::HolderBinding(_))));
+        // Device key of someone else
+        let stolen = DeviceResponse::parse(&present(&mdoc, &["family_name"], &TestKey::p256(), &transcript)).unwrap();
+        assert!(stolen.documents[0].verify_device(&transcript).is_err());
+        // Unknown root
+        let other_root = certificate(("Other IACA", &TestKey::p256()), ("Other IACA", &root_key), true);
+        assert!(document.verify_issuer(&[other_root], now).is_err());
+    }
+
+    #[test]
+    fn test_rejects_altered_element() {
+        let (root_key, device_key) = (TestKey::p256(), TestKey::p256());
+        let root = certificate(("IACA", &root_key), ("IACA", &root_key), true);
+        let mdoc = issue(PID, &[(PID, "family_name", Cbor::text("Jansen"))], &root_key, std::slice::from_ref(&root), &device_key);
+        let transcript = openid4vp_session_transcript("client", "nonce", None, "https://iou.example/r");
+
+        let mut forged = mdoc;
+        let item = Cbor::embed(&Cbor::Map(vec![
+            (Cbor::text("digestID"), Cbor::int(0)),
+            (Cbor::text("random"), Cbor::Bytes(vec![0; 16])),
+            (Cbor::text("elementIdentifier"), Cbor::text("family_name")),
+            (Cbor::text("elementValue"), Cbor::text("de Vries")),
+        ]));
+        forged.items[0].2 = item;
+        let response = DeviceResponse::parse(&present(&forged, &["family_name"], &device_key, &transcript)).unwrap();
+        assert!(matches!(
+            response.documents[0].verify_issuer(&[root], Utc::now()),
+            Err(VCValidationError::InvalidSignature(_))
+        ));
+    }
+}
@@ -5,13 +5,34 @@
 
 pub mod verifiable_credential;
 pub mod did;
+pub mod did_methods;
+pub mod did_store;
 pub mod presentation;
 pub mod resolver;
+pub mod encoding;
+pub mod jose;
+pub mod data_integrity;
+pub mod sd_jwt;
+pub mod status_list;
+pub mod trust_registry;
+pub mod cbor;
+pub mod x509;
+pub mod mdoc;
+pub mod openid4vp;
+mod ec;
 
 pub use verifiable_credential::{
     VerifiableCredential, VerifiablePresentation, VCValidationError,
-    Claims, ClaimValue, DIDResolver,
+    Claims, ClaimValue, DIDResolver, PresentationRequirements, SecuredForm,
 };
-pub use did::{DidMethod, DidDocument, DidKey, parse_did};
+pub use did::{DidMethod, DidDocument, DidKey, VerificationRelationship, parse_did};
+pub use jose::{Jws, JwsAlgorithm, PublicKey, SigningKey};
+pub use data_integrity::DataIntegrityProof;
+pub use sd_jwt::SdJwt;
+pub use status_list::{CredentialState, StatusCachePolicy, StatusListClient};
+pub use trust_registry::{TrustAnchor, TrustRegistry, TrustRegistryDocument, TrustRegistryError, TrustedIssuer};
 pub use presentation::PresentationValidator;
-pub use resolver::UniversalDidResolver;
+pub use did_store::{DidDocumentStore, PinnedDocuments};
+pub use resolver::{ResolverError, UniversalDidResolver};
+pub use x509::Certificate;
+pub use openid4vp::{AuthorizationRequest, AuthorizationResponse, DisclosedCredential, Openid4vpVerifier, PresentationQuery};
@@ -0,0 +1,805 @@
+//! OpenID for Verifiable Presentations 1.0, verifier side
+//!
+//! The verifier creates an authorization request with a DCQL query or a
+//! Presentation Exchange definition, signs it as a request object (JAR,
+//! RFC 9101) and hands the wallet a link with a `request_uri`. The wallet
+//! posts its `vp_token` to the `response_uri` (`direct_post`). SD-JWT VCs
+//! and ISO mdocs in the token are verified against the nonce and client id
+//! of the request they answer.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use regex::Regex;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::ssi::cbor::Cbor;
+use crate::ssi::encoding::base64url_encode;
+use crate::ssi::jose::{JwsHeader, SigningKey};
+use crate::ssi::mdoc::{self, DeviceResponse};
+use crate::ssi::sd_jwt::SdJwt;
+use crate::ssi::status_list::StatusListClient;
+use crate::ssi::trust_registry::TrustRegistry;
+use crate::ssi::verifiable_credential::{
+    DIDResolver, PresentationRequirements, VCValidationError, VerifiableCredential,
+};
+use crate::ssi::x509::{self, Certificate};
+
+/// `typ` of signed request objects
+pub const REQUEST_OBJECT_TYP: &str = "oauth-authz-req+jwt";
+
+/// Credential format identifiers
+pub const FORMAT_SD_JWT: &str = "dc+sd-jwt";
+/// Format identifier of SD-JWT VCs in OpenID4VP drafts
+pub const FORMAT_SD_JWT_LEGACY: &str = "vc+sd-jwt";
+pub const FORMAT_MDOC: &str = "mso_mdoc";
+
+/// Audience of request objects for wallets without verifier metadata
+const SELF_ISSUED_AUDIENCE: &str = "https://self-issued.me/v2";
+
+/// Lifetime of request objects and the oldest accepted key binding
+const REQUEST_LIFETIME_SECONDS: i64 = 300;
+
+/// Digital Credentials Query Language query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DcqlQuery {
+    /// All credentials are required; `credential_sets` are not supported
+    pub credentials: Vec<CredentialQuery>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialQuery {
+    pub id: String,
+    pub format: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub multiple: bool,
+    #[serde(default)]
+    pub meta: CredentialMeta,
+    /// All claims are required; `claim_sets` are not supported
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub claims: Vec<ClaimsQuery>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CredentialMeta {
+    /// Accepted SD-JWT VC types
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vct_values: Option<Vec<String>>,
+    /// Required mdoc document type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doctype_value: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaimsQuery {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Claim names, array indices or `null` for all array elements; for
+    /// mdocs the namespace and the element identifier
+    pub path: Vec<Value>,
+    /// Accepted values
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub values: Option<Vec<Value>>,
+}
+
+/// DIF Presentation Exchange 2.0 definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresentationDefinition {
+    pub id: String,
+    pub input_descriptors: Vec<InputDescriptor>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputDescriptor {
+    pub id: String,
+    /// Accepted formats, keyed by format identifier
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<Value>,
+    #[serde(default)]
+    pub constraints: Constraints,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Constraints {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<Field>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit_disclosure: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Field {
+    /// JSONPath expressions, the first that selects a value counts
+    pub path: Vec<String>,
+    /// JSON Schema; `const`, `enum`, `type` and `pattern` are evaluated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Value>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub optional: bool,
+}
+
+/// Where each input descriptor is found in the `vp_token`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresentationSubmission {
+    pub id: String,
+    pub definition_id: String,
+    pub descriptor_map: Vec<DescriptorMapping>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DescriptorMapping {
+    pub id: String,
+    pub format: String,
+    /// `$` or `$[n]`
+    pub path: String,
+}
+
+/// What the verifier asks for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PresentationQuery {
+    #[serde(rename = "dcql_query")]
+    Dcql(DcqlQuery),
+    #[serde(rename = "presentation_definition")]
+    PresentationExchange(PresentationDefinition),
+}
+
+/// Authorization request, sent to the wallet as a signed request object
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorizationRequest {
+    /// Client identifier with its prefix, e.g. `x509_san_dns:iou.example.nl`
+    pub client_id: String,
+    pub response_type: String,
+    pub response_mode: String,
+    pub response_uri: String,
+    pub nonce: String,
+    pub state: String,
+    #[serde(flatten)]
+    pub query: PresentationQuery,
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub client_metadata: Value,
+}
+
+impl AuthorizationRequest {
+    /// `direct_post` request with a fresh nonce and state
+    pub fn new(client_id: impl Into<String>, response_uri: impl Into<String>, query: PresentationQuery) -> Self {
+        let algorithms = json!(["ES256", "EdDSA"]);
+        Self {
+            client_id: client_id.into(),
+            response_type: "vp_token".into(),
+            response_mode: "direct_post".into(),
+            response_uri: response_uri.into(),
+            nonce: random_token(),
+            state: random_token(),
+            query,
+            client_metadata: json!({
+                "vp_formats_supported": {
+                    FORMAT_SD_JWT: {"sd-jwt_alg_values": algorithms, "kb-jwt_alg_values": algorithms},
+                    FORMAT_MDOC: {"issuerauth_alg_values": [-7, -8], "deviceauth_alg_values": [-7, -8]},
+                }
+            }),
+        }
+    }
+
+    /// Request object (JAR) signed by the verifier; `x5c` is the
+    /// verifier's certificate chain for `x509_san_dns` client ids
+    pub fn sign(&self, key: &SigningKey, x5c: &[String], now: DateTime<Utc>) -> Result<String, VCValidationError> {
+        let mut claims = serde_json::to_value(self)
+            .map_err(|e| VCValidationError::InvalidFormat(format!("Invalid request: {}", e)))?;
+        claims["aud"] = SELF_ISSUED_AUDIENCE.into();
+        claims["iat"] = now.timestamp().into();
+        claims["exp"] = (now.timestamp() + REQUEST_LIFETIME_SECONDS).into();
+        let header = JwsHeader {
+            alg: String::new(),
+            kid: None,
+            typ: Some(REQUEST_OBJECT_TYP.into()),
+            jwk: None,
+            x5c: (!x5c.is_empty()).then(|| x5c.to_vec()),
+        };
+        key.sign_jwt(header, &claims)
+    }
+
+    /// Link for the wallet (same device) or QR code (cross device)
+    pub fn authorization_link(&self, request_uri: &str) -> String {
+        format!(
+            "openid4vp://?client_id={}&request_uri={}",
+            percent_encode(&self.client_id),
+            percent_encode(request_uri)
+        )
+    }
+
+    /// Session transcript mdoc device signatures must cover
+    pub fn session_transcript(&self) -> Cbor {
+        mdoc::openid4vp_session_transcript(&self.client_id, &self.nonce, None, &self.response_uri)
+    }
+}
+
+/// Authorization response as posted by the wallet (form encoded)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuthorizationResponse {
+    /// JSON object keyed by DCQL credential query id, or for Presentation
+    /// Exchange a single presentation or a JSON array of them
+    pub vp_token: Option<String>,
+    pub presentation_submission: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
+/// A verified credential from the response
+#[derive(Debug, Clone, Serialize)]
+pub struct DisclosedCredential {
+    /// DCQL credential query id or input descriptor id
+    pub query_id: String,
+    pub format: String,
+    /// `vct` or mdoc document type
+    pub credential_type: String,
+    /// Issuer DID or URL, or the common name of the document signer
+    pub issuer: String,
+    /// Disclosed claims; for mdocs keyed by namespace
+    pub claims: Value,
+}
+
+/// Verifies authorization responses
+pub struct Openid4vpVerifier {
+    resolver: Arc<dyn DIDResolver>,
+    trust_anchors: Vec<Certificate>,
+    trust_registry: Option<Arc<TrustRegistry>>,
+    status_lists: Option<Arc<StatusListClient>>,
+}
+
+impl Openid4vpVerifier {
+    pub fn new(resolver: Arc<dyn DIDResolver>) -> Self {
+        Self { resolver, trust_anchors: Vec::new(), trust_registry: None, status_lists: None }
+    }
+
+    /// Root certificates for issuers that sign with X.509 certificates:
+    /// mdoc IACAs and SD-JWT VC issuers with `x5c`
+    pub fn with_trust_anchors(mut self, anchors: Vec<Certificate>) -> Self {
+        self.trust_anchors = anchors;
+        self
+    }
+
+    /// Registry for issuers identified by a DID; without one, DID issuers
+    /// are not trusted
+    pub fn with_trust_registry(mut self, registry: Arc<TrustRegistry>) -> Self {
+        self.trust_registry = Some(registry);
+        self
+    }
+
+    /// Reject revoked and suspended SD-JWT VCs
+    pub fn with_status_lists(mut self, status_lists: Arc<StatusListClient>) -> Self {
+        self.status_lists = Some(status_lists);
+        self
+    }
+
+    /// Verify a response to `request` and match it against its query
+    pub async fn verify(
+        &self,
+        request: &AuthorizationRequest,
+        response: &AuthorizationResponse,
+    ) -> Result<Vec<DisclosedCredential>, VCValidationError> {
+        if let Some(error) = &response.error {
+            return Err(VCValidationError::InvalidFormat(format!(
+                "Wallet returned {}: {}",
+                error,
+                response.error_description.as_deref().unwrap_or_default()
+            )));
+        }
+        if response.state.as_deref() != Some(request.state.as_str()) {
+            return Err(VCValidationError::InvalidFormat("Response state does not match the request".into()));
+        }
+        let vp_token = response.vp_token.as_deref().ok_or_else(|| VCValidationError::MissingClaim("vp_token".into()))?;
+        let vp_token = match serde_json::from_str::<Value>(vp_token) {
+            Ok(value @ (Value::Object(_) | Value::Array(_))) => value,
+            _ => Value::String(vp_token.to_string()),
+        };
+        match &request.query {
+            PresentationQuery::Dcql(query) => self.verify_dcql(request, query, &vp_token).await,
+            PresentationQuery::PresentationExchange(definition) => {
+                let submission: PresentationSubmission = response
+                    .presentation_submission
+                    .as_deref()
+                    .map(serde_json::from_str)
+                    .transpose()
+                    .map_err(|e| VCValidationError::InvalidFormat(format!("Invalid presentation_submission: {}", e)))?
+                    .ok_or_else(|| VCValidationError::MissingClaim("presentation_submission".into()))?;
+                self.verify_presentation_exchange(request, definition, &submission, &vp_token).await
+            }
+        }
+    }
+
+    async fn verify_dcql(
+        &self,
+        request: &AuthorizationRequest,
+        query: &DcqlQuery,
+        vp_token: &Value,
+    ) -> Result<Vec<DisclosedCredential>, VCValidationError> {
+        let mut disclosed = Vec::new();
+        for credential_query in &query.credentials {
+            let presentations: Vec<&str> = match vp_token.get(&credential_query.id) {
+                Some(Value::String(presentation)) => vec![presentation],
+                Some(Value::Array(presentations)) => presentations.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            if presentations.is_empty() || (presentations.len() > 1 && !credential_query.multiple) {
+                return Err(VCValidationError::MissingClaim(format!(
+                    "exactly one presentation for credential query {}",
+                    credential_query.id
+                )));
+            }
+            for presentation in presentations {
+                let credential = self
+                    .verify_credential(request, &credential_query.id, &credential_query.format, presentation)
+                    .await?;
+                check_credential_query(credential_query, &credential)?;
+                disclosed.push(credential);
+            }
+        }
+        Ok(disclosed)
+    }
+
+    async fn verify_presentation_exchange(
+        &self,
+        request: &AuthorizationRequest,
+        definition: &PresentationDefinition,
+        submission: &PresentationSubmission,
+        vp_token: &Value,
+    ) -> Result<Vec<DisclosedCredential>, VCValidationError> {
+        if submission.definition_id != definition.id {
+            return Err(VCValidationError::InvalidFormat(format!(
+                "Submission answers definition {}, not {}",
+                submission.definition_id, definition.id
+            )));
+        }
+        let mut disclosed = Vec::new();
+        for descriptor in &definition.input_descriptors {
+            let mapping = submission
+                .descriptor_map
+                .iter()
+                .find(|mapping| mapping.id == descriptor.id)
+                .ok_or_else(|| VCValidationError::MissingClaim(format!("input descriptor {}", descriptor.id)))?;
+            if let Some(formats) = &descriptor.format
+                && formats.get(&mapping.format).is_none()
+            {
+                return Err(VCValidationError::InvalidFormat(format!(
+                    "Format {} not requested for {}",
+                    mapping.format, descriptor.id
+                )));
+            }
+            let presentation = match mapping.path.as_str() {
+                "$" => vp_token.as_str(),
+                path => path
+                    .strip_prefix("$[")
+                    .and_then(|index| index.strip_suffix(']'))
+                    .and_then(|index| index.parse::<usize>().ok())
+                    .and_then(|index| vp_token.get(index))
+                    .and_then(Value::as_str),
+            }
+            .ok_or_else(|| VCValidationError::InvalidFormat(format!("No presentation at {}", mapping.path)))?;
+
+            let credential = self.verify_credential(request, &descriptor.id, &mapping.format, presentation).await?;
+            check_input_descriptor(descriptor, &credential)?;
+            disclosed.push(credential);
+        }
+        Ok(disclosed)
+    }
+
+    async fn verify_credential(
+        &self,
+        request: &AuthorizationRequest,
+        query_id: &str,
+        format: &str,
+        presentation: &str,
+    ) -> Result<DisclosedCredential, VCValidationError> {
+        let now = Utc::now();
+        let (credential_type, issuer, claims) = match format {
+            FORMAT_SD_JWT | FORMAT_SD_JWT_LEGACY => self.verify_sd_jwt(request, presentation, now).await?,
+            FORMAT_MDOC => self.verify_mdoc(request, presentation, now)?,
+            other => return Err(VCValidationError::UnsupportedProof(format!("credential format {}", other))),
+        };
+        Ok(DisclosedCredential { query_id: query_id.to_string(), format: format.to_string(), credential_type, issuer, claims })
+    }
+
+    /// Issuer signature (X.509 or DID), validity, key binding to this
+    /// request and status
+    async fn verify_sd_jwt(
+        &self,
+        request: &AuthorizationRequest,
+        presentation: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(String, String, Value), VCValidationError> {
+        let sd_jwt = SdJwt::parse(presentation)?;
+        let credential = VerifiableCredential::from_compact(presentation)?;
+        let mut issuer = credential.issuer.clone();
+
+        match &sd_jwt.issuer_jwt.header.x5c {
+            Some(x5c) => {
+                let chain = x5c.iter().map(|der| Certificate::from_base64(der)).collect::<Result<Vec<_>, _>>()?;
+                x509::verify_chain(&chain, &self.trust_anchors, now)?;
+                sd_jwt.issuer_jwt.verify(&chain[0].public_key()?)?;
+                credential.check_validity_period(now)?;
+                if issuer.is_empty() {
+                    issuer = chain[0].common_name().unwrap_or_else(|| chain[0].fingerprint());
+                }
+            }
+            None => {
+                credential.validate(&*self.resolver).await?;
+                match &self.trust_registry {
+                    Some(registry) => registry.check(&credential, now)?,
+                    None => return Err(VCValidationError::UntrustedIssuer(issuer)),
+                }
+            }
+        }
+
+        let binding = sd_jwt.verify_key_binding()?;
+        PresentationRequirements {
+            nonce: Some(request.nonce.clone()),
+            audience: Some(request.client_id.clone()),
+            max_age: Some(Duration::seconds(REQUEST_LIFETIME_SECONDS)),
+        }
+        .check(binding.nonce.as_deref(), binding.aud.as_slice(), DateTime::from_timestamp(binding.iat, 0), now)?;
+
+        if let Some(status_lists) = &self.status_lists {
+            credential.check_status(status_lists, &*self.resolver).await?;
+        }
+
+        let claims = Value::Object(sd_jwt.claims()?);
+        let credential_type = claims.get("vct").and_then(Value::as_str).unwrap_or_default().to_string();
+        Ok((credential_type, issuer, claims))
+    }
+
+    /// Document signer chain, MSO and device signature over this request's
+    /// session transcript
+    fn verify_mdoc(
+        &self,
+        request: &AuthorizationRequest,
+        presentation: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(String, String, Value), VCValidationError> {
+        let response = DeviceResponse::parse(presentation)?;
+        let [document] = response.documents.as_slice() else {
+            return Err(VCValidationError::InvalidFormat("Expected one document per mdoc presentation".into()));
+        };
+        let signer = document.verify_issuer(&self.trust_anchors, now)?;
+        document.verify_device(&request.session_transcript())?;
+        let issuer = signer.common_name().unwrap_or_else(|| signer.fingerprint());
+        Ok((document.doc_type.clone(), issuer, document.claims()))
+    }
+}
+
+fn check_credential_query(query: &CredentialQuery, credential: &DisclosedCredential) -> Result<(), VCValidationError> {
+    let mismatch = |what: String| VCValidationError::MissingClaim(format!("credential query {}: {}", query.id, what));
+    if let Some(vct_values) = &query.meta.vct_values
+        && !vct_values.contains(&credential.credential_type)
+    {
+        return Err(mismatch(format!("type {} not requested", credential.credential_type)));
+    }
+    if let Some(doctype) = &query.meta.doctype_value
+        && *doctype != credential.credential_type
+    {
+        return Err(mismatch(format!("document type {} not requested", credential.credential_type)));
+    }
+    for claim in &query.claims {
+        let selected = select(&credential.claims, &claim.path);
+        let found = match &claim.values {
+            Some(values) => selected.iter().any(|value| values.contains(value)),
+            None => !selected.is_empty(),
+        };
+        if !found {
+            return Err(mismatch(format!("claim {} not disclosed", Value::from(claim.path.clone()))));
+        }
+    }
+    Ok(())
+}
+
+fn check_input_descriptor(descriptor: &InputDescriptor, credential: &DisclosedCredential) -> Result<(), VCValidationError> {
+    for field in descriptor.constraints.fields.iter().filter(|field| !field.optional) {
+        let found = field.path.iter().filter_map(|path| json_path(path)).any(|path| {
+            select(&credential.claims, &path)
+                .iter()
+                .any(|value| field.filter.as_ref().is_none_or(|filter| matches_filter(value, filter)))
+        });
+        if !found {
+            return Err(VCValidationError::MissingClaim(format!(
+                "input descriptor {}: no value at {}",
+                descriptor.id,
+                field.path.join(" or ")
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Values at a DCQL claims path
+fn select<'a>(value: &'a Value, path: &[Value]) -> Vec<&'a Value> {
+    let Some((segment, rest)) = path.split_first() else {
+        return vec![value];
+    };
+    match (segment, value) {
+        (Value::String(name), Value::Object(fields)) => fields.get(name).map(|v| select(v, rest)).unwrap_or_default(),
+        (Value::Number(index), Value::Array(items)) => index
+            .as_u64()
+            .and_then(|index| items.get(index as usize))
+            .map(|v| select(v, rest))
+            .unwrap_or_default(),
+        (Value::Null, Value::Array(items)) => items.iter().flat_map(|v| select(v, rest)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// `$.a.b`, `$['a']['b']`, `$.a[0]` and `$.a[*]` as a DCQL claims path;
+/// the JSONPath subset input descriptors use in practice
+fn json_path(path: &str) -> Option<Vec<Value>> {
+    let mut rest = path.strip_prefix('$')?;
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("['") {
+            let end = after.find("']")?;
+            segments.push(Value::from(&after[..end]));
+            rest = &after[end + 2..];
+        } else if let Some(after) = rest.strip_prefix("[*]") {
+            segments.push(Value::Null);
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']')?;
+            segments.push(Value::from(after[..end].parse::<u64>().ok()?));
+            rest = &after[end + 1..];
+        } else if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            segments.push(Value::from(&after[..end]));
+            rest = &after[end..];
+        } else {
+            return None;
+        }
+    }
+    Some(segments)
+}
+
+/// The JSON Schema keywords field filters use in practice
+fn matches_filter(value: &Value, filter: &Value) -> bool {
+    if let Some(expected) = filter.get("const")
+        && value != expected
+    {
+        return false;
+    }
+    if let Some(options) = filter.get("enum").and_then(Value::as_array)
+        && !options.contains(value)
+    {
+        return false;
+    }
+    let typed = match filter.get("type").and_then(Value::as_str) {
+        Some("string") => value.is_string(),
+        Some("number") => value.is_number(),
+        Some("integer") => value.is_i64() || value.is_u64(),
+        Some("boolean") => value.is_boolean(),
+        Some("array") => value.is_array(),
+        Some("object") => value.is_object(),
+        _ => true,
+    };
+    if !typed {
+        return false;
+    }
+    match filter.get("pattern").and_then(Value::as_str) {
+        Some(pattern) => match (value.as_str(), Regex::new(pattern)) {
+            (Some(text), Ok(pattern)) => pattern.is_match(text),
+            _ => false,
+        },
+        None => true,
+    }
+}
+
+/// 128 bit random value, base64url
+fn random_token() -> String {
+    let mut bytes = [0u8; 16];
+    SystemRandom::new().fill(&mut bytes).expect("system random number generator");
+    base64url_encode(bytes)
+}
+
+/// Percent-encoding for query parameter values (RFC 3986 unreserved
+/// characters are kept)
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ssi::jose::test_keys::TestKey;
+    use crate::ssi::jose::{Jws, PublicKey};
+    use crate::ssi::mdoc::test_support as mdoc_wallet;
+    use crate::ssi::resolver::UniversalDidResolver;
+    use crate::ssi::sd_jwt::test_support::{disclosure, issue, present};
+    use crate::ssi::trust_registry::TrustAnchor;
+    use crate::ssi::x509::test_support::certificate;
+
+    const CLIENT_ID: &str = "x509_san_dns:iou.example.nl";
+    const RESPONSE_URI: &str = "https://iou.example.nl/api/id/openid4vp/sessions/1/response";
+    const PID_VCT: &str = "urn:eudi:pid:1";
+    const MDL: &str = "org.iso.18013.5.1.mDL";
+
+    /// Local wallet with an SD-JWT PID from a did:jwk issuer and an mDL
+    /// signed under a test IACA
+    struct MockWallet {
+        holder: TestKey,
+        sd_jwt: String,
+        mdl: mdoc_wallet::IssuedMdoc,
+    }
+
+    impl MockWallet {
+        fn new(pid_issuer: &TestKey, pid_issuer_did: &str, iaca: &TestKey, iaca_certificate: &Certificate) -> Self {
+            let holder = TestKey::p256();
+            let disclosures = [
+                disclosure("salt-1", Some("given_name"), json!("Anna")),
+                disclosure("salt-2", Some("family_name"), json!("Jansen")),
+            ];
+            let sd_jwt = issue(
+                pid_issuer,
+                &format!("{}#0", pid_issuer_did),
+                json!({
+                    "iss": pid_issuer_did,
+                    "vct": PID_VCT,
+                    "iat": Utc::now().timestamp() - 60,
+                    "cnf": {"jwk": holder.public().to_jwk()},
+                }),
+                &disclosures,
+            );
+            let mdl = mdoc_wallet::issue(
+                MDL,
+                &[
+                    ("org.iso.18013.5.1", "family_name", Cbor::text("Jansen")),
+                    ("org.iso.18013.5.1", "driving_privileges", Cbor::Array(vec![])),
+                ],
+                iaca,
+                std::slice::from_ref(iaca_certificate),
+                &holder,
+            );
+            Self { holder, sd_jwt, mdl }
+        }
+
+        /// Fetch and check the request object, then answer with both
+        /// credentials
+        fn answer(&self, request_object: &str, verifier_key: &PublicKey) -> AuthorizationResponse {
+            let jws = Jws::parse(request_object).unwrap();
+            assert_eq!(jws.header.typ.as_deref(), Some(REQUEST_OBJECT_TYP));
+            jws.verify(verifier_key).unwrap();
+            let request: AuthorizationRequest = jws.claims().unwrap();
+
+            let sd_jwt = present(&self.sd_jwt, &self.holder, &request.nonce, &request.client_id, Utc::now().timestamp());
+            let mdl = mdoc_wallet::present(&self.mdl, &["family_name"], &self.holder, &request.session_transcript());
+            let vp_token = match &request.query {
+                PresentationQuery::Dcql(_) => json!({"pid": [sd_jwt], "mdl": [mdl]}),
+                PresentationQuery::PresentationExchange(_) => json!([sd_jwt, mdl]),
+            };
+            AuthorizationResponse {
+                vp_token: Some(vp_token.to_string()),
+                presentation_submission: matches!(request.query, PresentationQuery::PresentationExchange(_)).then(|| {
+                    json!({
+                        "id": "submission",
+                        "definition_id": "iou-login",
+                        "descriptor_map": [
+                            {"id": "pid", "format": FORMAT_SD_JWT, "path": "$[0]"},
+                            {"id": "mdl", "format": FORMAT_MDOC, "path": "$[1]"},
+                        ]
+                    })
+                    .to_string()
+                }),
+                state: Some(request.state),
+                ..Default::default()
+            }
+        }
+    }
+
+    fn dcql() -> PresentationQuery {
+        serde_json::from_value(json!({"dcql_query": {"credentials": [
+            {"id": "pid", "format": FORMAT_SD_JWT, "meta": {"vct_values": [PID_VCT]},
+             "claims": [{"path": ["given_name"]}, {"path": ["family_name"], "values": ["Jansen"]}]},
+            {"id": "mdl", "format": FORMAT_MDOC, "meta": {"doctype_value": MDL},
+             "claims": [{"path": ["org.iso.18013.5.1", "family_name"]}]},
+        ]}}))
+        .unwrap()
+    }
+
+    fn setup() -> (Openid4vpVerifier, MockWallet, SigningKey) {
+        let pid_issuer = TestKey::ed25519(21);
+        let did = format!("did:jwk:{}", base64url_encode(serde_json::to_vec(&pid_issuer.public().to_jwk()).unwrap()));
+        let iaca = TestKey::p256();
+        let iaca_certificate = certificate(("Test IACA", &iaca), ("Test IACA", &iaca), true);
+
+        let governance = TestKey::ed25519(22);
+        let registry = TrustRegistry::new(vec![TrustAnchor { kid: "governance-1".into(), key: governance.public() }]);
+        let issuers = json!([{"did": did, "credential_type": PID_VCT, "allowed_claims": ["*"]}]);
+        registry
+            .load_signed(&governance.jwt(json!({"kid": "governance-1"}), &json!({"version": 1, "issuers": issuers})))
+            .unwrap();
+        let verifier = Openid4vpVerifier::new(Arc::new(UniversalDidResolver::new()))
+            .with_trust_anchors(vec![iaca_certificate.clone()])
+            .with_trust_registry(Arc::new(registry));
+        let wallet = MockWallet::new(&pid_issuer, &did, &iaca, &iaca_certificate);
+        (verifier, wallet, SigningKey::generate_p256().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_dcql_round_trip_with_mock_wallet() {
+        let (verifier, wallet, signing_key) = setup();
+        let request = AuthorizationRequest::new(CLIENT_ID, RESPONSE_URI, dcql());
+        let request_object = request.sign(&signing_key, &[], Utc::now()).unwrap();
+        let response = wallet.answer(&request_object, &signing_key.public());
+
+        let disclosed = verifier.verify(&request, &response).await.unwrap();
+        assert_eq!(disclosed.len(), 2);
+        assert_eq!(disclosed[0].credential_type, PID_VCT);
+        assert_eq!(disclosed[0].claims["given_name"], "Anna");
+        assert_eq!(disclosed[1].issuer, "Test IACA");
+        assert_eq!(disclosed[1].claims["org.iso.18013.5.1"]["family_name"], "Jansen");
+
+        // The same response does not answer another request
+        let other = AuthorizationRequest { state: response.state.clone().unwrap(), ..AuthorizationRequest::new(CLIENT_ID, RESPONSE_URI, dcql()) };
+        assert!(matches!(verifier.verify(&other, &response).await, Err(VCValidationError::NonceMismatch)));
+    }
+
+    #[tokio::test]
+    async fn test_presentation_exchange_with_mock_wallet() {
+        let (verifier, wallet, signing_key) = setup();
+        let definition = serde_json::from_value(json!({"presentation_definition": {
+            "id": "iou-login",
+            "input_descriptors": [
+                {"id": "pid", "format": {FORMAT_SD_JWT: {}}, "constraints": {"fields": [
+                    {"path": ["$.vct"], "filter": {"const": PID_VCT}},
+                    {"path": ["$.family_name"], "filter": {"type": "string", "pattern": "^J"}},
+                ]}},
+                {"id": "mdl", "format": {FORMAT_MDOC: {}}, "constraints": {"fields": [
+                    {"path": ["$['org.iso.18013.5.1']['family_name']"]},
+                    {"path": ["$['org.iso.18013.5.1']['birth_date']"], "optional": true},
+                ]}},
+            ]
+        }}))
+        .unwrap();
+        let request = AuthorizationRequest::new(CLIENT_ID, RESPONSE_URI, definition);
+        let response = wallet.answer(&request.sign(&signing_key, &[], Utc::now()).unwrap(), &signing_key.public());
+        let disclosed = verifier.verify(&request, &response).await.unwrap();
+        assert_eq!(disclosed.iter().map(|c| c.query_id.as_str()).collect::<Vec<_>>(), ["pid", "mdl"]);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unrequested_or_untrusted() {
+        let (verifier, wallet, signing_key) = setup();
+        let request = AuthorizationRequest::new(CLIENT_ID, RESPONSE_URI, dcql());
+        let response = wallet.answer(&request.sign(&signing_key, &[], Utc::now()).unwrap(), &signing_key.public());
+
+        let replayed = AuthorizationResponse { state: Some("other".into()), ..response.clone() };
+        assert!(verifier.verify(&request, &replayed).await.is_err());
+
+        let PresentationQuery::Dcql(mut query) = dcql() else { unreachable!() };
+        query.credentials[0].claims[1].values = Some(vec![json!("de Vries")]);
+        let strict = AuthorizationRequest { query: PresentationQuery::Dcql(query), ..request.clone() };
+        assert!(matches!(verifier.verify(&strict, &response).await, Err(VCValidationError::MissingClaim(_))));
+
+        let without_registry = Openid4vpVerifier::new(Arc::new(UniversalDidResolver::new()));
+        assert!(matches!(
+            without_registry.verify(&request, &response).await,
+            Err(VCValidationError::UntrustedIssuer(_))
+        ));
+    }
+
+    #[test]
+    fn test_json_path_and_link() {
+        assert_eq!(json_path("$.address.street[0]"), Some(vec![json!("address"), json!("street"), json!(0)]));
+        assert_eq!(json_path("$['org.iso.18013.5.1'][*]"), Some(vec![json!("org.iso.18013.5.1"), Value::Null]));
+        assert_eq!(json_path("address"), None);
+
+        let request = AuthorizationRequest::new(CLIENT_ID, RESPONSE_URI, dcql());
+        assert_eq!(
+            request.authorization_link("https://iou.example.nl/r?id=1"),
+            "openid4vp://?client_id=x509_san_dns%3Aiou.example.nl&request_uri=https%3A%2F%2Fiou.example.nl%2Fr%3Fid%3D1"
+        );
+        assert_ne!(request.nonce, AuthorizationRequest::new(CLIENT_ID, RESPONSE_URI, dcql()).nonce);
+    }
+}
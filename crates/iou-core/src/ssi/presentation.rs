@@ -1,11 +1,19 @@
 //! Verifiable Presentation validation
 
-use crate::ssi::verifiable_credential::{VerifiablePresentation, DIDResolver, VCValidationError};
+use crate::ssi::status_list::StatusListClient;
+use crate::ssi::trust_registry::TrustRegistry;
+use crate::ssi::verifiable_credential::{
+    VerifiablePresentation, DIDResolver, VCValidationError, PresentationRequirements,
+};
+use std::sync::Arc;
 
 /// Presentation validator for VPs
 pub struct PresentationValidator {
     resolver: std::sync::Arc<dyn DIDResolver>,
     trusted_issuers: Vec<String>,
+    requirements: PresentationRequirements,
+    status_lists: Option<Arc<StatusListClient>>,
+    trust_registry: Option<Arc<TrustRegistry>>,
 }
 
 impl PresentationValidator {
@@ -14,7 +22,38 @@ impl PresentationValidator {
         resolver: std::sync::Arc<dyn DIDResolver>,
         trusted_issuers: Vec<String>,
     ) -> Self {
-        Self { resolver, trusted_issuers }
+        Self {
+            resolver,
+            trusted_issuers,
+            requirements: PresentationRequirements::default(),
+            status_lists: None,
+            trust_registry: None,
+        }
+    }
+
+    /// Default nonce, audience and age requirements for presentations
+    pub fn with_requirements(mut self, requirements: PresentationRequirements) -> Self {
+        self.requirements = requirements;
+        self
+    }
+
+    /// Requirements [`validate`](Self::validate) applies
+    pub fn requirements(&self) -> &PresentationRequirements {
+        &self.requirements
+    }
+
+    /// Accept only issuers the registry trusts for the credential type,
+    /// municipality and claims; replaces the flat trusted issuer list
+    /// once the registry has entries
+    pub fn with_trust_registry(mut self, registry: Arc<TrustRegistry>) -> Self {
+        self.trust_registry = Some(registry);
+        self
+    }
+
+    /// Reject revoked and suspended credentials
+    pub fn with_status_lists(mut self, status_lists: Arc<StatusListClient>) -> Self {
+        self.status_lists = Some(status_lists);
+        self
     }
 
     /// Validate a presentation
@@ -22,14 +61,36 @@ impl PresentationValidator {
         &self,
         vp: &VerifiablePresentation,
     ) -> Result<ValidatedPresentation, VCValidationError> {
-        // Validate all contained VCs
-        vp.validate(&*self.resolver).await?;
+        self.validate_with(vp, &self.requirements).await
+    }
+
+    /// Validate a presentation against session specific requirements,
+    /// e.g. the nonce handed out for this request
+    pub async fn validate_with(
+        &self,
+        vp: &VerifiablePresentation,
+        requirements: &PresentationRequirements,
+    ) -> Result<ValidatedPresentation, VCValidationError> {
+        // Validate all contained VCs, the holder proof and holder binding
+        vp.validate_with(&*self.resolver, requirements).await?;
+
+        // Check revocation of each credential
+        if let Some(status_lists) = &self.status_lists {
+            for vc in &vp.verifiable_credential {
+                vc.check_status(status_lists, &*self.resolver).await?;
+            }
+        }
 
         // Extract claims
         let claims = vp.extract_claims()?;
 
         // Check trusted issuers
-        if !self.trusted_issuers.is_empty() {
+        if let Some(registry) = self.trust_registry.as_deref().filter(|registry| !registry.is_empty()) {
+            let now = chrono::Utc::now();
+            for vc in &vp.verifiable_credential {
+                registry.check(vc, now)?;
+            }
+        } else if !self.trusted_issuers.is_empty() {
             let issuer_trusted = self.trusted_issuers.iter()
                 .any(|trusted| self.is_issuer_trusted(&claims.issuer, trusted));
 
@@ -57,6 +118,7 @@ impl PresentationValidator {
 }
 
 /// Validated presentation with claims
+#[derive(Clone)]
 pub struct ValidatedPresentation {
     pub holder_did: String,
     pub claims: crate::ssi::verifiable_credential::Claims,
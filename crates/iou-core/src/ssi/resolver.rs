@@ -1,17 +1,29 @@
 //! Universal DID resolver supporting multiple DID methods
-
-use crate::ssi::{did::DidDocument, verifiable_credential::DIDResolver};
+//!
+//! did:key, did:jwk and did:peer are derived locally; did:web, did:ebsi
+//! and did:polygonid are fetched over HTTP unless pinned in a
+//! [`DidDocumentStore`].
+
+use crate::ssi::{
+    did::DidDocument,
+    did_methods,
+    did_store::DidDocumentStore,
+    verifiable_credential::DIDResolver,
+};
 use async_trait::async_trait;
 use reqwest::Client;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
 /// Universal DID resolver
 pub struct UniversalDidResolver {
     http: Client,
-    cache: tokio::sync::RwLock<HashMap<String, Arc<DidDocument>>>,
+    cache: tokio::sync::RwLock<HashMap<String, (Instant, Arc<DidDocument>)>>,
     cache_ttl_seconds: u64,
+    pinned: Option<Arc<dyn DidDocumentStore>>,
+    offline: bool,
 }
 
 impl UniversalDidResolver {
@@ -25,9 +37,24 @@ impl UniversalDidResolver {
                 .expect("HTTP client creation failed"),
             cache: tokio::sync::RwLock::new(HashMap::new()),
             cache_ttl_seconds: 900, // 15 minutes
+            pinned: None,
+            offline: false,
         }
     }
 
+    /// Resolve DIDs from `store` before any other method
+    pub fn with_store(mut self, store: Arc<dyn DidDocumentStore>) -> Self {
+        self.pinned = Some(store);
+        self
+    }
+
+    /// Never go to the network: DIDs that are neither pinned nor
+    /// self-describing fail with [`ResolverError::Offline`]
+    pub fn offline(mut self) -> Self {
+        self.offline = true;
+        self
+    }
+
     /// Resolve a DID using appropriate method
     async fn resolve_by_method(&self, did: &str) -> Result<DidDocument, ResolverError> {
         if !did.starts_with("did:") {
@@ -39,15 +66,45 @@ impl UniversalDidResolver {
             return Err(ResolverError::InvalidFormat("Invalid DID format".into()));
         }
 
+        if let Some(store) = &self.pinned
+            && let Some(document) = store.get(did).await?
+        {
+            return Ok(document);
+        }
+
         let method = parts[1];
 
         match method {
+            "key" => return did_methods::resolve_did_key(did),
+            "jwk" => return did_methods::resolve_did_jwk(did),
+            "peer" => return did_methods::resolve_did_peer(did),
+            "web" | "ebsi" | "polygonid" if self.offline => return Err(ResolverError::Offline(did.to_string())),
+            _ => {}
+        }
+
+        // Network methods are cached
+        {
+            let cache = self.cache.read().await;
+            if let Some((fetched, doc)) = cache.get(did)
+                && fetched.elapsed() < Duration::from_secs(self.cache_ttl_seconds)
+            {
+                return Ok(doc.as_ref().clone());
+            }
+        }
+
+        let doc = match method {
             "web" => self.resolve_did_web(did).await,
-            "key" => self.resolve_did_key(did).await,
             "ebsi" => self.resolve_did_ebsi(did).await,
             "polygonid" => self.resolve_did_polygonid(did).await,
             _ => Err(ResolverError::MethodNotSupported(method.to_string())),
+        }?;
+
+        {
+            let mut cache = self.cache.write().await;
+            cache.insert(did.to_string(), (Instant::now(), Arc::new(doc.clone())));
         }
+
+        Ok(doc)
     }
 
     /// Resolve did:web DIDs
@@ -72,20 +129,6 @@ impl UniversalDidResolver {
         Ok(did_doc)
     }
 
-    /// Resolve did:key DIDs (generates DID document on the fly)
-    async fn resolve_did_key(&self, did: &str) -> Result<DidDocument, ResolverError> {
-        // did:key is self-describing - we can derive the DID document from the key
-        // For now, return a mock implementation
-        Ok(DidDocument {
-            context: serde_json::json!("https://w3id.org/did/v1"),
-            id: did.to_string(),
-            verification_method: vec![],
-            authentication: vec![],
-            assertion_method: vec![],
-            service: None,
-        })
-    }
-
     /// Resolve did:ebsi DIDs using EBSI resolver
     async fn resolve_did_ebsi(&self, did: &str) -> Result<DidDocument, ResolverError> {
         // Use EBSI DID resolver
@@ -134,24 +177,7 @@ impl Default for UniversalDidResolver {
 #[async_trait]
 impl DIDResolver for UniversalDidResolver {
     async fn resolve(&self, did: &str) -> Result<DidDocument, Box<dyn std::error::Error>> {
-        // Check cache first
-        {
-            let cache = self.cache.read().await;
-            if let Some(doc) = cache.get(did) {
-                return Ok(Arc::clone(doc).as_ref().clone());
-            }
-        }
-
-        // Resolve
-        let doc = self.resolve_by_method(did).await?;
-
-        // Cache
-        {
-            let mut cache = self.cache.write().await;
-            cache.insert(did.to_string(), Arc::new(doc.clone()));
-        }
-
-        Ok(doc)
+        Ok(self.resolve_by_method(did).await?)
     }
 }
 
@@ -172,11 +198,18 @@ pub enum ResolverError {
 
     #[error("Parse error: {0}")]
     ParseError(String),
+
+    #[error("DID {0} is not pinned and network resolution is disabled")]
+    Offline(String),
+
+    #[error("Pinned document store: {0}")]
+    Store(String),
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ssi::did_store::PinnedDocuments;
 
     #[tokio::test]
     async fn test_resolve_did_key() {
@@ -196,4 +229,42 @@ mod tests {
         // Second call should use cache
         let _ = resolver.resolve(did).await.unwrap();
     }
+
+    #[tokio::test]
+    async fn test_pinned_documents_offline() {
+        let dir = tempfile::tempdir().unwrap();
+        let document = serde_json::json!({
+            "@context": "https://www.w3.org/ns/did/v1",
+            "id": "did:web:issuer.gemeente.example",
+            "verificationMethod": [{
+                "id": "did:web:issuer.gemeente.example#key-1",
+                "type": "JsonWebKey2020",
+                "controller": "did:web:issuer.gemeente.example",
+                "publicKeyJwk": {"kty": "OKP", "crv": "Ed25519", "x": "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"}
+            }],
+            "assertionMethod": ["#key-1"]
+        });
+        // Resolution result as exported by a universal resolver
+        std::fs::write(
+            dir.path().join("issuer.json"),
+            serde_json::json!({"didDocument": document}).to_string(),
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("README.txt"), "not a document").unwrap();
+
+        let store = Arc::new(PinnedDocuments::from_dir(dir.path()).unwrap());
+        assert_eq!(store.len(), 1);
+        let resolver = UniversalDidResolver::new().with_store(store).offline();
+
+        let resolved = resolver.resolve("did:web:issuer.gemeente.example").await.unwrap();
+        assert_eq!(resolved.verification_method.len(), 1);
+        // Self-describing methods need no pin
+        resolver.resolve("did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK").await.unwrap();
+
+        let err = resolver.resolve_by_method("did:web:other.example").await.unwrap_err();
+        assert!(matches!(err, ResolverError::Offline(_)));
+
+        std::fs::write(dir.path().join("broken.json"), "{").unwrap();
+        assert!(matches!(PinnedDocuments::from_dir(dir.path()), Err(ResolverError::Store(_))));
+    }
 }
@@ -0,0 +1,356 @@
+//! Selective Disclosure JWTs (IETF SD-JWT, SD-JWT VC)
+//!
+//! An SD-JWT is `<issuer-jwt>~<disclosure>~...~[<key-binding-jwt>]`. The
+//! issuer signs digests of salted claims; the holder discloses a subset and
+//! proves possession of the key in `cnf` with a key binding JWT over the
+//! nonce and audience of the verifier.
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+
+use crate::ssi::encoding::{base64url_decode, base64url_encode};
+use crate::ssi::jose::{Jws, PublicKey};
+use crate::ssi::verifiable_credential::VCValidationError;
+
+/// `typ` of a key binding JWT
+pub const KEY_BINDING_TYP: &str = "kb+jwt";
+
+/// One disclosed claim or array element
+#[derive(Debug, Clone, PartialEq)]
+pub struct Disclosure {
+    /// As presented, base64url
+    pub encoded: String,
+    pub salt: String,
+    /// Claim name; `None` for an array element
+    pub name: Option<String>,
+    pub value: Value,
+}
+
+impl Disclosure {
+    pub fn parse(encoded: &str) -> Result<Self, VCValidationError> {
+        let invalid = |message: &str| VCValidationError::InvalidFormat(format!("Invalid disclosure: {}", message));
+        let json = base64url_decode(encoded).map_err(|e| invalid(&e.to_string()))?;
+        let parts: Vec<Value> = serde_json::from_slice(&json).map_err(|e| invalid(&e.to_string()))?;
+
+        let salt = parts.first().and_then(Value::as_str).ok_or_else(|| invalid("missing salt"))?;
+        let (name, value) = match parts.as_slice() {
+            [_, Value::String(name), value] => {
+                if name == "_sd" || name == "..." {
+                    return Err(invalid("reserved claim name"));
+                }
+                (Some(name.clone()), value.clone())
+            }
+            [_, value] => (None, value.clone()),
+            _ => return Err(invalid("expected two or three elements")),
+        };
+
+        Ok(Self { encoded: encoded.to_string(), salt: salt.to_string(), name, value })
+    }
+
+    /// Digest referenced from `_sd` or `...` (SHA-256)
+    pub fn digest(&self) -> String {
+        base64url_encode(Sha256::digest(self.encoded.as_bytes()))
+    }
+}
+
+/// Verified claims of a key binding JWT
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct KeyBinding {
+    pub iat: i64,
+    #[serde(default)]
+    pub aud: Option<String>,
+    #[serde(default)]
+    pub nonce: Option<String>,
+    pub sd_hash: String,
+}
+
+/// Parsed SD-JWT
+#[derive(Debug, Clone)]
+pub struct SdJwt {
+    pub issuer_jwt: Jws,
+    pub disclosures: Vec<Disclosure>,
+    pub key_binding: Option<Jws>,
+    /// Everything up to and including the last `~`, hashed into `sd_hash`
+    presented: String,
+}
+
+impl SdJwt {
+    pub fn parse(input: &str) -> Result<Self, VCValidationError> {
+        let input = input.trim();
+        let Some(last_tilde) = input.rfind('~') else {
+            return Err(VCValidationError::InvalidFormat("SD-JWT without '~'".into()));
+        };
+        let (presented, key_binding) = input.split_at(last_tilde + 1);
+
+        let mut parts = presented[..presented.len() - 1].split('~');
+        let issuer_jwt = Jws::parse(parts.next().unwrap_or_default())?;
+        let disclosures = parts.map(Disclosure::parse).collect::<Result<Vec<_>, _>>()?;
+        let key_binding = match key_binding {
+            "" => None,
+            jwt => Some(Jws::parse(jwt)?),
+        };
+
+        Ok(Self { issuer_jwt, disclosures, key_binding, presented: presented.to_string() })
+    }
+
+    /// Issuer claims with the disclosures applied
+    ///
+    /// Every disclosure must be referenced exactly once by the signed
+    /// payload; undisclosed digests are dropped.
+    pub fn claims(&self) -> Result<Map<String, Value>, VCValidationError> {
+        let mut payload: Value = self.issuer_jwt.claims()?;
+        match payload.get("_sd_alg").and_then(Value::as_str) {
+            None | Some("sha-256") => {}
+            Some(other) => return Err(VCValidationError::UnsupportedProof(format!("_sd_alg {}", other))),
+        }
+
+        let by_digest: HashMap<String, &Disclosure> = self.disclosures.iter().map(|d| (d.digest(), d)).collect();
+        if by_digest.len() != self.disclosures.len() {
+            return Err(VCValidationError::InvalidFormat("Duplicate disclosure".into()));
+        }
+        let mut used = HashSet::new();
+        resolve(&mut payload, &by_digest, &mut used)?;
+        if used.len() != self.disclosures.len() {
+            return Err(VCValidationError::InvalidSignature("disclosure not covered by the issuer signature".into()));
+        }
+
+        let Value::Object(mut claims) = payload else {
+            return Err(VCValidationError::InvalidFormat("SD-JWT payload is not an object".into()));
+        };
+        claims.remove("_sd_alg");
+        Ok(claims)
+    }
+
+    /// Holder key from the `cnf` claim
+    pub fn holder_key(&self) -> Result<Option<PublicKey>, VCValidationError> {
+        let payload: Value = self.issuer_jwt.claims()?;
+        match payload.pointer("/cnf/jwk") {
+            Some(jwk) => {
+                let jwk = serde_json::from_value(jwk.clone())
+                    .map_err(|e| VCValidationError::InvalidFormat(format!("Invalid cnf: {}", e)))?;
+                Ok(Some(PublicKey::from_jwk(&jwk)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// `sd_hash` a key binding JWT must carry
+    pub fn sd_hash(&self) -> String {
+        base64url_encode(Sha256::digest(self.presented.as_bytes()))
+    }
+
+    /// Verify the key binding JWT against the `cnf` key
+    ///
+    /// Nonce and audience are returned for the caller to check.
+    pub fn verify_key_binding(&self) -> Result<KeyBinding, VCValidationError> {
+        let holding = |message: &str| VCValidationError::HolderBinding(message.to_string());
+        let jwt = self.key_binding.as_ref().ok_or_else(|| holding("no key binding JWT"))?;
+        let key = self.holder_key()?.ok_or_else(|| holding("credential has no cnf key"))?;
+
+        if jwt.header.typ.as_deref() != Some(KEY_BINDING_TYP) {
+            return Err(holding("key binding JWT must have typ kb+jwt"));
+        }
+        jwt.verify(&key)?;
+
+        let binding: KeyBinding = jwt.claims()?;
+        if binding.sd_hash != self.sd_hash() {
+            return Err(holding("sd_hash does not match the presented disclosures"));
+        }
+        Ok(binding)
+    }
+}
+
+fn resolve(
+    value: &mut Value,
+    disclosures: &HashMap<String, &Disclosure>,
+    used: &mut HashSet<String>,
+) -> Result<(), VCValidationError> {
+    match value {
+        Value::Object(fields) => {
+            if let Some(digests) = fields.remove("_sd") {
+                for digest in digests.as_array().into_iter().flatten() {
+                    let Some(disclosure) = take(digest.as_str().unwrap_or_default(), disclosures, used)? else {
+                        continue;
+                    };
+                    let Some(name) = &disclosure.name else {
+                        return Err(VCValidationError::InvalidFormat("Array disclosure used for a claim".into()));
+                    };
+                    if fields.insert(name.clone(), disclosure.value.clone()).is_some() {
+                        return Err(VCValidationError::InvalidFormat(format!("Claim {} disclosed twice", name)));
+                    }
+                }
+            }
+            for field in fields.values_mut() {
+                resolve(field, disclosures, used)?;
+            }
+        }
+        Value::Array(items) => {
+            let mut resolved = Vec::with_capacity(items.len());
+            for mut item in items.drain(..) {
+                let digest = match item.as_object() {
+                    Some(element) if element.len() == 1 => element.get("...").and_then(Value::as_str).map(str::to_string),
+                    _ => None,
+                };
+                if let Some(digest) = digest {
+                    match take(&digest, disclosures, used)? {
+                        Some(Disclosure { name: None, value, .. }) => item = value.clone(),
+                        Some(_) => {
+                            return Err(VCValidationError::InvalidFormat("Claim disclosure used for an array element".into()));
+                        }
+                        // Not disclosed: the element is left out
+                        None => continue,
+                    }
+                }
+                resolve(&mut item, disclosures, used)?;
+                resolved.push(item);
+            }
+            *items = resolved;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Disclosure for `digest`, at most once
+fn take<'a>(
+    digest: &str,
+    disclosures: &HashMap<String, &'a Disclosure>,
+    used: &mut HashSet<String>,
+) -> Result<Option<&'a Disclosure>, VCValidationError> {
+    match disclosures.get(digest) {
+        Some(disclosure) if !used.insert(digest.to_string()) => Err(VCValidationError::InvalidFormat(
+            format!("Disclosure {} referenced twice", disclosure.encoded),
+        )),
+        found => Ok(found.copied()),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    //! Issue and present SD-JWTs in tests
+
+    use super::*;
+    use crate::ssi::jose::test_keys::TestKey;
+    use serde_json::json;
+
+    pub fn disclosure(salt: &str, name: Option<&str>, value: Value) -> Disclosure {
+        let parts = match name {
+            Some(name) => json!([salt, name, value]),
+            None => json!([salt, value]),
+        };
+        Disclosure::parse(&base64url_encode(parts.to_string())).unwrap()
+    }
+
+    /// `issuer~d1~d2~`, with `_sd` digests for the named disclosures
+    pub fn issue(issuer: &TestKey, kid: &str, mut payload: Value, disclosures: &[Disclosure]) -> String {
+        let digests: Vec<String> = disclosures.iter().filter(|d| d.name.is_some()).map(Disclosure::digest).collect();
+        payload["_sd"] = json!(digests);
+        payload["_sd_alg"] = "sha-256".into();
+        let jwt = issuer.jwt(json!({"typ": "dc+sd-jwt", "kid": kid}), &payload);
+        let mut sd_jwt = jwt;
+        for d in disclosures {
+            sd_jwt.push('~');
+            sd_jwt.push_str(&d.encoded);
+        }
+        sd_jwt.push('~');
+        sd_jwt
+    }
+
+    /// Append a key binding JWT signed by `holder`
+    pub fn present(sd_jwt: &str, holder: &TestKey, nonce: &str, audience: &str, iat: i64) -> String {
+        let sd_hash = base64url_encode(Sha256::digest(sd_jwt.as_bytes()));
+        let kb = holder.jwt(
+            json!({"typ": KEY_BINDING_TYP}),
+            &json!({"iat": iat, "aud": audience, "nonce": nonce, "sd_hash": sd_hash}),
+        );
+        format!("{}{}", sd_jwt, kb)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::*;
+    use super::*;
+    use crate::ssi::jose::test_keys::TestKey;
+    use serde_json::json;
+
+    fn issued(holder: &TestKey) -> (TestKey, String) {
+        let issuer = TestKey::ed25519(11);
+        let roles = disclosure("salt-3", None, json!("behandelaar"));
+        let payload = json!({
+            "iss": "did:example:bzk",
+            "vct": "MedewerkerCredential",
+            "cnf": {"jwk": holder.public().to_jwk()},
+            "roles": [{"...": roles.digest()}, "lezer"]
+        });
+        let disclosures = [
+            disclosure("salt-1", Some("municipality"), json!("utrecht")),
+            disclosure("salt-2", Some("email"), json!("jan@utrecht.nl")),
+            roles,
+        ];
+        let sd_jwt = issue(&issuer, "did:example:bzk#key-1", payload, &disclosures);
+        (issuer, sd_jwt)
+    }
+
+    #[test]
+    fn test_disclosed_claims() {
+        let holder = TestKey::p256();
+        let (issuer, sd_jwt) = issued(&holder);
+        let parsed = SdJwt::parse(&sd_jwt).unwrap();
+        parsed.issuer_jwt.verify(&issuer.public()).unwrap();
+
+        let claims = parsed.claims().unwrap();
+        assert_eq!(claims["municipality"], "utrecht");
+        assert_eq!(claims["roles"], json!(["behandelaar", "lezer"]));
+        assert!(!claims.contains_key("_sd"));
+
+        // Withholding a disclosure hides the claim
+        let parts: Vec<&str> = sd_jwt.split('~').collect();
+        let partial = format!("{}~{}~", parts[0], parts[1]);
+        let claims = SdJwt::parse(&partial).unwrap().claims().unwrap();
+        assert_eq!(claims["municipality"], "utrecht");
+        assert!(!claims.contains_key("email"));
+        assert_eq!(claims["roles"], json!(["lezer"]));
+    }
+
+    #[test]
+    fn test_rejects_injected_disclosure() {
+        let holder = TestKey::p256();
+        let (_, sd_jwt) = issued(&holder);
+        let injected = disclosure("salt-9", Some("roles"), json!(["admin"]));
+        let forged = format!("{}{}~", sd_jwt, injected.encoded);
+        assert!(matches!(
+            SdJwt::parse(&forged).unwrap().claims(),
+            Err(VCValidationError::InvalidSignature(_))
+        ));
+    }
+
+    #[test]
+    fn test_key_binding() {
+        let holder = TestKey::p256();
+        let (_, sd_jwt) = issued(&holder);
+        let presented = present(&sd_jwt, &holder, "n-0S6_WzA2Mj", "https://iou.example.nl", 1_790_000_000);
+
+        let binding = SdJwt::parse(&presented).unwrap().verify_key_binding().unwrap();
+        assert_eq!(binding.nonce.as_deref(), Some("n-0S6_WzA2Mj"));
+        assert_eq!(binding.aud.as_deref(), Some("https://iou.example.nl"));
+
+        // Signed by someone else
+        let thief = TestKey::p256();
+        let stolen = present(&sd_jwt, &thief, "n-0S6_WzA2Mj", "https://iou.example.nl", 1_790_000_000);
+        assert!(SdJwt::parse(&stolen).unwrap().verify_key_binding().is_err());
+
+        // Bound to other disclosures
+        let parts: Vec<&str> = presented.split('~').collect();
+        let reordered = format!("{}~{}~{}", parts[0], parts[1], parts.last().unwrap());
+        assert!(matches!(
+            SdJwt::parse(&reordered).unwrap().verify_key_binding(),
+            Err(VCValidationError::HolderBinding(_))
+        ));
+
+        // Missing entirely
+        assert!(SdJwt::parse(&sd_jwt).unwrap().verify_key_binding().is_err());
+    }
+}
@@ -0,0 +1,710 @@
+//! Credential status lists (revocation and suspension)
+//!
+//! Issuers publish the status of all credentials they issued as one
+//! compressed list; a credential points at its index. Two formats:
+//!
+//! - W3C StatusList2021 and Bitstring Status List: a status list
+//!   credential whose `encodedList` is a GZIP-compressed bitstring. Index 0
+//!   is the most significant bit of the first byte.
+//! - IETF Token Status List, used by NL Wallet in the SD-JWT VC `status`
+//!   claim: a `statuslist+jwt` whose `lst` is a ZLIB-compressed list of 1,
+//!   2, 4 or 8 bit statuses. Index 0 is in the least significant bits.
+//!
+//! Lists are signed by the issuer of the credential and cached according to
+//! a [`StatusCachePolicy`]. URL prefixes can be rewritten, so the status
+//! list URLs in production credentials can be served by a local fixture
+//! server.
+
+use chrono::{DateTime, Duration, Utc};
+use flate2::read::{GzDecoder, ZlibDecoder};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::Arc;
+
+use crate::ssi::did::VerificationRelationship;
+use crate::ssi::encoding::base64url_decode;
+use crate::ssi::jose::{Jws, PublicKey};
+use crate::ssi::verifiable_credential::{
+    ClaimValue, CredentialStatus, DIDResolver, VCValidationError, VerifiableCredential,
+};
+
+/// `typ` of an IETF status list token
+pub const STATUS_LIST_TYP: &str = "statuslist+jwt";
+
+/// `credentialStatus` type given to the SD-JWT VC `status.status_list` claim
+pub const TOKEN_STATUS_LIST_ENTRY: &str = "TokenStatusList";
+
+/// Largest decompressed list accepted (16 million 8-bit statuses)
+const MAX_LIST_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Status of one credential
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialState {
+    Valid,
+    Revoked,
+    Suspended,
+    /// Application specific status value
+    Other(u8),
+}
+
+/// How status lists are cached and refreshed
+#[derive(Debug, Clone)]
+pub struct StatusCachePolicy {
+    /// Lifetime of a list that states no `ttl` or expiry of its own
+    pub default_ttl: Duration,
+
+    /// Upper bound of any lifetime, including the list's own `ttl`
+    pub max_ttl: Duration,
+
+    /// Keep using an expired list this long while the status list server
+    /// is unreachable
+    pub stale_if_error: Duration,
+
+    /// Reject credentials whose status cannot be determined
+    pub fail_closed: bool,
+}
+
+impl Default for StatusCachePolicy {
+    fn default() -> Self {
+        Self {
+            default_ttl: Duration::minutes(5),
+            max_ttl: Duration::hours(1),
+            stale_if_error: Duration::minutes(15),
+            fail_closed: true,
+        }
+    }
+}
+
+/// Format of a status list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusListFormat {
+    /// W3C StatusList2021 / Bitstring Status List credential
+    Bitstring,
+    /// IETF Token Status List
+    Token,
+}
+
+/// Where a credential's status is published
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusReference {
+    pub format: StatusListFormat,
+    pub uri: String,
+    pub index: usize,
+    /// `revocation`, `suspension` or `message` (W3C only)
+    pub purpose: Option<String>,
+}
+
+impl StatusReference {
+    /// Read a `credentialStatus` entry
+    pub fn from_credential_status(status: &CredentialStatus) -> Result<Self, VCValidationError> {
+        let format = match status.status_type.as_str() {
+            "StatusList2021Entry" | "BitstringStatusListEntry" | "RevocationList2020Status" => {
+                StatusListFormat::Bitstring
+            }
+            TOKEN_STATUS_LIST_ENTRY => StatusListFormat::Token,
+            other => return Err(VCValidationError::UnsupportedProof(format!("credential status {}", other))),
+        };
+        let uri = status
+            .status_list_credential
+            .clone()
+            .ok_or_else(|| VCValidationError::InvalidFormat("credentialStatus without status list".into()))?;
+        let index = status
+            .status_list_index
+            .as_deref()
+            .and_then(|i| i.parse().ok())
+            .ok_or_else(|| VCValidationError::InvalidFormat("credentialStatus without valid index".into()))?;
+
+        Ok(Self { format, uri, index, purpose: status.status_purpose.clone() })
+    }
+}
+
+/// Decoded, verified status list
+#[derive(Debug, Clone)]
+pub struct StatusList {
+    pub format: StatusListFormat,
+    /// Issuer that signed the list
+    pub issuer: String,
+    /// Bits per status
+    pub bits: u8,
+    /// `statusPurpose` of a W3C list
+    pub purpose: Option<String>,
+    /// Until when the list may be used, as stated by the issuer
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Lifetime the issuer asks caches to respect
+    pub ttl: Option<Duration>,
+    bytes: Vec<u8>,
+}
+
+impl StatusList {
+    /// Status value at `index`
+    pub fn get(&self, index: usize) -> Result<u8, VCValidationError> {
+        let bits = usize::from(self.bits);
+        let position = index
+            .checked_mul(bits)
+            .filter(|p| p / 8 < self.bytes.len())
+            .ok_or_else(|| VCValidationError::InvalidFormat(format!("status index {} outside the list", index)))?;
+        let byte = self.bytes[position / 8];
+        let mask = ((1u16 << bits) - 1) as u8;
+        let shift = match self.format {
+            // Left to right
+            StatusListFormat::Bitstring => 8 - bits - position % 8,
+            // Right to left
+            StatusListFormat::Token => position % 8,
+        };
+        Ok((byte >> shift) & mask)
+    }
+
+    /// State of the credential at `reference`
+    pub fn state(&self, reference: &StatusReference) -> Result<CredentialState, VCValidationError> {
+        let value = self.get(reference.index)?;
+        let purpose = reference.purpose.as_deref().or(self.purpose.as_deref());
+        Ok(match (self.format, purpose, value) {
+            (_, _, 0) => CredentialState::Valid,
+            (StatusListFormat::Bitstring, Some("suspension"), 1) => CredentialState::Suspended,
+            (StatusListFormat::Bitstring, Some("revocation") | None, 1) => CredentialState::Revoked,
+            (StatusListFormat::Token, _, 1) => CredentialState::Revoked,
+            (StatusListFormat::Token, _, 2) => CredentialState::Suspended,
+            (_, _, value) => CredentialState::Other(value),
+        })
+    }
+
+    /// W3C status list credential, JSON or JWT
+    ///
+    /// The signature is checked by the caller; this reads the list.
+    pub fn from_credential(credential: &VerifiableCredential) -> Result<Self, VCValidationError> {
+        let subject = &credential.credential_subject.claims;
+        let text = |name: &str| subject.get(name).and_then(|v| v.as_str()).map(str::to_string);
+        let list_type = text("type").unwrap_or_default();
+
+        let encoded = text("encodedList")
+            .ok_or_else(|| VCValidationError::MissingClaim("encodedList".into()))?;
+        // Bitstring Status List uses multibase base64url (`u` prefix)
+        let encoded = match list_type.as_str() {
+            "BitstringStatusList" => encoded.strip_prefix('u').unwrap_or(&encoded).to_string(),
+            _ => encoded,
+        };
+        let compressed = base64url_decode(&encoded).map_err(|e| invalid(&e.to_string()))?;
+        let bytes = inflate(GzDecoder::new(compressed.as_slice()))?;
+
+        let bits = match subject.get("statusSize") {
+            Some(ClaimValue::Number(size)) => status_bits(*size)?,
+            _ => 1,
+        };
+        let expires_at = credential
+            .expiration_date
+            .as_deref()
+            .and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+            .map(|d| d.with_timezone(&Utc));
+        // Bitstring Status List `ttl` is in milliseconds
+        let ttl = match subject.get("ttl") {
+            Some(ClaimValue::Number(ms)) => Some(Duration::milliseconds(*ms)),
+            _ => None,
+        };
+
+        Ok(Self {
+            format: StatusListFormat::Bitstring,
+            issuer: credential.issuer.clone(),
+            bits,
+            purpose: text("statusPurpose"),
+            expires_at,
+            ttl,
+            bytes,
+        })
+    }
+
+    /// IETF status list token for `uri`
+    ///
+    /// The signature is checked by the caller; this reads the list.
+    pub fn from_token(jws: &Jws, uri: &str) -> Result<Self, VCValidationError> {
+        #[derive(Deserialize)]
+        struct Claims {
+            sub: String,
+            #[serde(default)]
+            iss: Option<String>,
+            #[serde(default)]
+            exp: Option<i64>,
+            #[serde(default)]
+            ttl: Option<i64>,
+            status_list: Lst,
+        }
+        #[derive(Deserialize)]
+        struct Lst {
+            bits: i64,
+            lst: String,
+        }
+
+        if jws.header.typ.as_deref() != Some(STATUS_LIST_TYP) {
+            return Err(invalid("status list token must have typ statuslist+jwt"));
+        }
+        let claims: Claims = jws.claims()?;
+        if claims.sub != uri {
+            return Err(invalid(&format!("token is the status list of {}, not {}", claims.sub, uri)));
+        }
+        let compressed = base64url_decode(&claims.status_list.lst).map_err(|e| invalid(&e.to_string()))?;
+
+        Ok(Self {
+            format: StatusListFormat::Token,
+            issuer: claims.iss.unwrap_or_default(),
+            bits: status_bits(claims.status_list.bits)?,
+            purpose: None,
+            expires_at: claims.exp.and_then(|exp| DateTime::from_timestamp(exp, 0)),
+            ttl: claims.ttl.map(Duration::seconds),
+            bytes: inflate(ZlibDecoder::new(compressed.as_slice()))?,
+        })
+    }
+}
+
+fn status_bits(bits: i64) -> Result<u8, VCValidationError> {
+    match bits {
+        1 | 2 | 4 | 8 => Ok(bits as u8),
+        other => Err(invalid(&format!("unsupported status size {}", other))),
+    }
+}
+
+fn inflate(decoder: impl Read) -> Result<Vec<u8>, VCValidationError> {
+    let mut bytes = Vec::new();
+    decoder
+        .take(MAX_LIST_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| invalid(&format!("cannot decompress: {}", e)))?;
+    if bytes.len() as u64 > MAX_LIST_BYTES {
+        return Err(invalid("list too large"));
+    }
+    Ok(bytes)
+}
+
+fn invalid(message: &str) -> VCValidationError {
+    VCValidationError::InvalidFormat(format!("Invalid status list: {}", message))
+}
+
+/// A list and when it was fetched
+struct CachedList {
+    list: Arc<StatusList>,
+    fresh_until: DateTime<Utc>,
+}
+
+/// Fetches, verifies and caches status lists
+pub struct StatusListClient {
+    http: Client,
+    policy: StatusCachePolicy,
+    /// URL prefix and its replacement
+    rewrites: Vec<(String, String)>,
+    cache: tokio::sync::RwLock<HashMap<String, CachedList>>,
+}
+
+impl StatusListClient {
+    pub fn new(policy: StatusCachePolicy) -> Self {
+        Self {
+            http: Client::builder()
+                .user_agent("iou-modern/status-list")
+                .timeout(std::time::Duration::from_secs(10))
+                .build()
+                .expect("HTTP client creation failed"),
+            policy,
+            rewrites: Vec::new(),
+            cache: tokio::sync::RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Fetch lists under `prefix` from `replacement` instead, e.g. a local
+    /// fixture server
+    pub fn with_rewrite(mut self, prefix: impl Into<String>, replacement: impl Into<String>) -> Self {
+        self.rewrites.push((prefix.into(), replacement.into()));
+        self
+    }
+
+    pub fn policy(&self) -> &StatusCachePolicy {
+        &self.policy
+    }
+
+    /// State of a credential; credentials without `credentialStatus` are
+    /// valid
+    pub async fn check(
+        &self,
+        credential: &VerifiableCredential,
+        resolver: &dyn DIDResolver,
+    ) -> Result<CredentialState, VCValidationError> {
+        let Some(status) = &credential.credential_status else {
+            return Ok(CredentialState::Valid);
+        };
+        let reference = StatusReference::from_credential_status(status)?;
+        let list = self.list(&reference, &credential.issuer, resolver).await?;
+        if list.issuer != credential.issuer {
+            return Err(VCValidationError::InvalidSignature(format!(
+                "status list {} is issued by {}, not {}",
+                reference.uri, list.issuer, credential.issuer
+            )));
+        }
+        list.state(&reference)
+    }
+
+    /// Drop a cached list, so the next check fetches it again
+    pub async fn invalidate(&self, uri: &str) {
+        self.cache.write().await.remove(uri);
+    }
+
+    /// Cached list, refreshed when it is past its lifetime
+    async fn list(
+        &self,
+        reference: &StatusReference,
+        issuer: &str,
+        resolver: &dyn DIDResolver,
+    ) -> Result<Arc<StatusList>, VCValidationError> {
+        let now = Utc::now();
+        let cached = self.cache.read().await.get(&reference.uri).map(|c| (c.list.clone(), c.fresh_until));
+        if let Some((list, fresh_until)) = &cached
+            && *fresh_until > now
+        {
+            return Ok(list.clone());
+        }
+
+        match self.fetch(reference, issuer, resolver).await {
+            Ok(list) => {
+                let list = Arc::new(list);
+                let cached = CachedList { list: list.clone(), fresh_until: now + self.lifetime(&list, now) };
+                self.cache.write().await.insert(reference.uri.clone(), cached);
+                Ok(list)
+            }
+            Err(e) => match cached {
+                Some((list, fresh_until))
+                    if fresh_until + self.policy.stale_if_error > now
+                        && list.expires_at.is_none_or(|exp| exp > now) =>
+                {
+                    tracing::warn!("Status list {} unavailable, using cached copy: {}", reference.uri, e);
+                    Ok(list)
+                }
+                _ => Err(e),
+            },
+        }
+    }
+
+    /// How long a freshly fetched list is used without asking again
+    fn lifetime(&self, list: &StatusList, now: DateTime<Utc>) -> Duration {
+        let mut lifetime = list.ttl.unwrap_or(self.policy.default_ttl).min(self.policy.max_ttl);
+        if let Some(expires_at) = list.expires_at {
+            lifetime = lifetime.min(expires_at - now);
+        }
+        lifetime.max(Duration::zero())
+    }
+
+    async fn fetch(
+        &self,
+        reference: &StatusReference,
+        issuer: &str,
+        resolver: &dyn DIDResolver,
+    ) -> Result<StatusList, VCValidationError> {
+        let url = self.rewrite(&reference.uri);
+        let accept = match reference.format {
+            StatusListFormat::Bitstring => "application/vc+ld+json, application/vc+jwt, application/json",
+            StatusListFormat::Token => "application/statuslist+jwt",
+        };
+        let unavailable = |e: String| VCValidationError::StatusUnavailable(format!("{}: {}", reference.uri, e));
+        let body = self
+            .http
+            .get(&url)
+            .header("Accept", accept)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| unavailable(e.to_string()))?
+            .text()
+            .await
+            .map_err(|e| unavailable(e.to_string()))?;
+
+        let document = resolver
+            .resolve(issuer)
+            .await
+            .map_err(|e| VCValidationError::DIDResolution(e.to_string()))?;
+
+        match reference.format {
+            StatusListFormat::Bitstring => {
+                let received = match serde_json::from_str::<Value>(&body) {
+                    Ok(json) => json,
+                    Err(_) => Value::String(body.trim().to_string()),
+                };
+                let credential: VerifiableCredential = serde_json::from_value(received)
+                    .map_err(|e| invalid(&e.to_string()))?;
+                credential.check_validity_period(Utc::now())?;
+                credential.verify_proof(&document)?;
+                StatusList::from_credential(&credential)
+            }
+            StatusListFormat::Token => {
+                let jws = Jws::parse(&body)?;
+                let mut list = StatusList::from_token(&jws, &reference.uri)?;
+                if list.issuer.is_empty() {
+                    list.issuer = issuer.to_string();
+                }
+                if list.issuer != document.id {
+                    return Err(VCValidationError::InvalidSignature(format!(
+                        "status list token of {} for a credential of {}",
+                        list.issuer, document.id
+                    )));
+                }
+                let key = document
+                    .verification_method_for(jws.header.kid.as_deref(), VerificationRelationship::AssertionMethod)
+                    .ok_or_else(|| VCValidationError::UnknownKey(jws.header.kid.clone().unwrap_or_default()))?;
+                jws.verify(&PublicKey::from_verification_method(&key)?)?;
+                if list.expires_at.is_some_and(|exp| exp < Utc::now()) {
+                    return Err(VCValidationError::Expired);
+                }
+                Ok(list)
+            }
+        }
+    }
+
+    fn rewrite(&self, uri: &str) -> String {
+        self.rewrites
+            .iter()
+            .find_map(|(prefix, replacement)| uri.strip_prefix(prefix.as_str()).map(|rest| format!("{}{}", replacement, rest)))
+            .unwrap_or_else(|| uri.to_string())
+    }
+}
+
+impl Default for StatusListClient {
+    fn default() -> Self {
+        Self::new(StatusCachePolicy::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ssi::data_integrity::secure;
+    use crate::ssi::did::DidDocument;
+    use crate::ssi::encoding::base64url_encode;
+    use crate::ssi::jose::test_keys::TestKey;
+    use crate::ssi::sd_jwt::test_support::issue;
+    use flate2::Compression;
+    use flate2::write::{GzEncoder, ZlibEncoder};
+    use serde_json::json;
+    use std::io::Write;
+    use std::sync::Mutex;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const ISSUER: &str = "did:example:utrecht";
+    const PUBLISHED: &str = "https://status.utrecht.nl";
+
+    struct Resolver(DidDocument);
+
+    #[async_trait::async_trait]
+    impl DIDResolver for Resolver {
+        async fn resolve(&self, did: &str) -> Result<DidDocument, Box<dyn std::error::Error>> {
+            if did == self.0.id { Ok(self.0.clone()) } else { Err(format!("unknown DID {}", did).into()) }
+        }
+    }
+
+    fn resolver(key: &TestKey) -> Resolver {
+        Resolver(
+            serde_json::from_value(json!({
+                "@context": "https://www.w3.org/ns/did/v1",
+                "id": ISSUER,
+                "verificationMethod": [{
+                    "id": format!("{}#key-1", ISSUER),
+                    "type": "JsonWebKey2020",
+                    "controller": ISSUER,
+                    "publicKeyJwk": key.public().to_jwk()
+                }],
+                "assertionMethod": ["#key-1"],
+                "authentication": []
+            }))
+            .unwrap(),
+        )
+    }
+
+    /// Serves fixed bodies by path and counts requests
+    struct FixtureServer {
+        base: String,
+        bodies: Arc<Mutex<HashMap<String, String>>>,
+        hits: Arc<Mutex<usize>>,
+    }
+
+    impl FixtureServer {
+        async fn start() -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let base = format!("http://{}", listener.local_addr().unwrap());
+            let bodies: Arc<Mutex<HashMap<String, String>>> = Arc::default();
+            let hits: Arc<Mutex<usize>> = Arc::default();
+            let (served, counted) = (bodies.clone(), hits.clone());
+            tokio::spawn(async move {
+                while let Ok((mut stream, _)) = listener.accept().await {
+                    let mut buffer = vec![0u8; 4096];
+                    let n = stream.read(&mut buffer).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buffer[..n]).to_string();
+                    let path = request.split_whitespace().nth(1).unwrap_or("/").to_string();
+                    *counted.lock().unwrap() += 1;
+                    let body = served.lock().unwrap().get(&path).cloned();
+                    let response = match body {
+                        Some(body) => format!("HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}", body.len(), body),
+                        None => "HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\nconnection: close\r\n\r\n".to_string(),
+                    };
+                    let _ = stream.write_all(response.as_bytes()).await;
+                }
+            });
+            Self { base, bodies, hits }
+        }
+
+        fn serve(&self, path: &str, body: String) {
+            self.bodies.lock().unwrap().insert(path.to_string(), body);
+        }
+
+        fn hits(&self) -> usize {
+            *self.hits.lock().unwrap()
+        }
+
+        fn client(&self, policy: StatusCachePolicy) -> StatusListClient {
+            StatusListClient::new(policy).with_rewrite(PUBLISHED, self.base.clone())
+        }
+    }
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn zlib(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    /// Bitstring with the given indices set, W3C bit order
+    fn bitstring(revoked: &[usize]) -> Vec<u8> {
+        let mut bytes = vec![0u8; 16 * 1024];
+        for &i in revoked {
+            bytes[i / 8] |= 0x80 >> (i % 8);
+        }
+        bytes
+    }
+
+    fn status_list_credential(key: &TestKey, revoked: &[usize]) -> String {
+        secure(
+            &json!({
+                "@context": ["https://www.w3.org/ns/credentials/v2"],
+                "type": ["VerifiableCredential", "BitstringStatusListCredential"],
+                "id": format!("{}/lists/1", PUBLISHED),
+                "issuer": ISSUER,
+                "validFrom": "2025-01-01T00:00:00Z",
+                "credentialSubject": {
+                    "id": format!("{}/lists/1#list", PUBLISHED),
+                    "type": "BitstringStatusList",
+                    "statusPurpose": "revocation",
+                    "encodedList": format!("u{}", base64url_encode(gzip(&bitstring(revoked))))
+                }
+            }),
+            key,
+            json!({"verificationMethod": format!("{}#key-1", ISSUER), "proofPurpose": "assertionMethod"}),
+        )
+        .to_string()
+    }
+
+    fn employee_credential(index: usize) -> VerifiableCredential {
+        serde_json::from_value(json!({
+            "@context": ["https://www.w3.org/ns/credentials/v2"],
+            "type": ["VerifiableCredential", "MedewerkerCredential"],
+            "issuer": ISSUER,
+            "credentialSubject": {"id": "did:example:jan", "roles": ["behandelaar"]},
+            "credentialStatus": {
+                "type": "BitstringStatusListEntry",
+                "statusPurpose": "revocation",
+                "statusListIndex": index.to_string(),
+                "statusListCredential": format!("{}/lists/1", PUBLISHED)
+            },
+            "proof": {"type": "DataIntegrityProof", "proofPurpose": "assertionMethod", "verificationMethod": ""}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_token_bit_order() {
+        // Example from the Token Status List draft: 2-bit statuses
+        let list = StatusList {
+            format: StatusListFormat::Token,
+            issuer: ISSUER.into(),
+            bits: 2,
+            purpose: None,
+            expires_at: None,
+            ttl: None,
+            bytes: vec![0xc9, 0x44, 0xf9],
+        };
+        let values: Vec<u8> = (0..12).map(|i| list.get(i).unwrap()).collect();
+        assert_eq!(values, [1, 2, 0, 3, 0, 1, 0, 1, 1, 2, 3, 3]);
+        assert!(list.get(12).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_revocation_via_bitstring_list() {
+        let key = TestKey::ed25519(31);
+        let resolver = resolver(&key);
+        let server = FixtureServer::start().await;
+        server.serve("/lists/1", status_list_credential(&key, &[7]));
+        let client = server.client(StatusCachePolicy::default());
+
+        assert_eq!(client.check(&employee_credential(3), &resolver).await.unwrap(), CredentialState::Valid);
+        assert_eq!(client.check(&employee_credential(7), &resolver).await.unwrap(), CredentialState::Revoked);
+        assert_eq!(server.hits(), 1, "second check uses the cache");
+
+        // The municipality revokes index 3; visible once the cache is refreshed
+        server.serve("/lists/1", status_list_credential(&key, &[3, 7]));
+        assert_eq!(client.check(&employee_credential(3), &resolver).await.unwrap(), CredentialState::Valid);
+        client.invalidate(&format!("{}/lists/1", PUBLISHED)).await;
+        assert!(matches!(
+            employee_credential(3).check_status(&client, &resolver).await,
+            Err(VCValidationError::Revoked)
+        ));
+
+        // A list signed by someone else
+        server.serve("/lists/1", status_list_credential(&TestKey::ed25519(32), &[]));
+        client.invalidate(&format!("{}/lists/1", PUBLISHED)).await;
+        assert!(matches!(
+            client.check(&employee_credential(3), &resolver).await,
+            Err(VCValidationError::InvalidSignature(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_token_status_list_for_sd_jwt() {
+        let key = TestKey::p256();
+        let resolver = resolver(&key);
+        let server = FixtureServer::start().await;
+        let uri = format!("{}/tokens/1", PUBLISHED);
+
+        // Index 1 suspended, index 2 revoked (2-bit statuses)
+        let token = key.jwt(
+            json!({"typ": STATUS_LIST_TYP, "kid": format!("{}#key-1", ISSUER)}),
+            &json!({
+                "sub": uri,
+                "iss": ISSUER,
+                "iat": Utc::now().timestamp(),
+                "ttl": 60,
+                "status_list": {"bits": 2, "lst": base64url_encode(zlib(&[0b0001_1000]))}
+            }),
+        );
+        server.serve("/tokens/1", token);
+        let client = server.client(StatusCachePolicy { stale_if_error: Duration::zero(), ..Default::default() });
+
+        let credential = |idx: usize| {
+            let sd_jwt = issue(
+                &key,
+                &format!("{}#key-1", ISSUER),
+                json!({"iss": ISSUER, "vct": "MedewerkerCredential", "status": {"status_list": {"idx": idx, "uri": uri}}}),
+                &[],
+            );
+            VerifiableCredential::from_compact(&sd_jwt).unwrap()
+        };
+        assert_eq!(client.check(&credential(0), &resolver).await.unwrap(), CredentialState::Valid);
+        assert_eq!(client.check(&credential(1), &resolver).await.unwrap(), CredentialState::Suspended);
+        assert_eq!(client.check(&credential(2), &resolver).await.unwrap(), CredentialState::Revoked);
+
+        // Server gone and cache expired: the status is unknown
+        let unreachable = StatusListClient::default().with_rewrite(PUBLISHED, "http://127.0.0.1:9");
+        assert!(matches!(
+            unreachable.check(&credential(0), &resolver).await,
+            Err(VCValidationError::StatusUnavailable(_))
+        ));
+    }
+}
@@ -0,0 +1,403 @@
+//! Registry of trusted credential issuers
+//!
+//! A valid signature only proves who issued a credential, not that the
+//! issuer may vouch for it. The registry lists, per credential type and
+//! tenant (municipality), which issuer DIDs are trusted, during which
+//! period, and which claims each may assert; e.g. only BZK may assert
+//! `roles` in a `MedewerkerCredential`.
+//!
+//! The registry is distributed as a signed configuration file: a compact
+//! JWS over a [`TrustRegistryDocument`], signed by one of the configured
+//! trust anchors. A document with a lower version than the one loaded is
+//! refused, so an old file cannot be replayed to restore revoked trust.
+
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::ssi::jose::{Jws, PublicKey};
+use crate::ssi::verifiable_credential::{VCValidationError, VerifiableCredential};
+
+/// Matches any credential type or claim
+pub const WILDCARD: &str = "*";
+
+/// Claims only entries of a signed registry file may allow
+pub const PRIVILEGED_CLAIMS: &[&str] = &["roles", WILDCARD];
+
+/// One issuer trusted for one credential type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrustedIssuer {
+    #[serde(default = "Uuid::new_v4")]
+    pub id: Uuid,
+
+    /// Issuer DID
+    pub did: String,
+
+    /// Display name, e.g. "Ministerie van BZK"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// Credential type, e.g. `MedewerkerCredential`, or `*`
+    pub credential_type: String,
+
+    /// Municipality the issuer may vouch for; `None` for credentials that
+    /// assert no municipality
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_from: Option<DateTime<Utc>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<DateTime<Utc>>,
+
+    /// Credential subject claims the issuer may assert, or `*`
+    pub allowed_claims: Vec<String>,
+
+    /// Added at runtime instead of by a signed registry file; such entries
+    /// are dropped when the next file is loaded
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub unsigned: bool,
+}
+
+impl TrustedIssuer {
+    /// Whether the entry covers a credential of `credential_type`, issued
+    /// by `did` for `tenant`, at `now`
+    pub fn covers(&self, did: &str, credential_type: &str, tenant: Option<&str>, now: DateTime<Utc>) -> bool {
+        self.did == did
+            && (self.credential_type == credential_type || self.credential_type == WILDCARD)
+            && self.tenant.as_deref() == tenant
+            && self.valid_from.is_none_or(|from| from <= now)
+            && self.valid_until.is_none_or(|until| now < until)
+    }
+
+    pub fn may_assert(&self, claim: &str) -> bool {
+        self.allowed_claims.iter().any(|c| c == claim || c == WILDCARD)
+    }
+}
+
+/// Contents of a signed registry file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrustRegistryDocument {
+    /// Increases with every published file
+    pub version: u64,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issued_at: Option<DateTime<Utc>>,
+
+    pub issuers: Vec<TrustedIssuer>,
+}
+
+/// Key allowed to sign registry files
+#[derive(Debug, Clone)]
+pub struct TrustAnchor {
+    pub kid: String,
+    pub key: PublicKey,
+}
+
+/// Trusted issuers per credential type and tenant
+#[derive(Default)]
+pub struct TrustRegistry {
+    anchors: Vec<TrustAnchor>,
+    document: RwLock<TrustRegistryDocument>,
+}
+
+impl TrustRegistry {
+    /// Empty registry that accepts files signed by `anchors`
+    pub fn new(anchors: Vec<TrustAnchor>) -> Self {
+        Self { anchors, document: RwLock::new(TrustRegistryDocument::default()) }
+    }
+
+    /// Load a signed registry file (compact JWS)
+    pub fn load_file(&self, path: impl AsRef<Path>) -> Result<u64, TrustRegistryError> {
+        let compact = std::fs::read_to_string(path.as_ref())
+            .map_err(|e| TrustRegistryError::Io(format!("{}: {}", path.as_ref().display(), e)))?;
+        self.load_signed(&compact)
+    }
+
+    /// Replace all entries with a signed registry document
+    ///
+    /// Returns the version that is now loaded.
+    pub fn load_signed(&self, compact: &str) -> Result<u64, TrustRegistryError> {
+        let document = self.verify(compact)?;
+        let mut current = self.document.write();
+        if document.version < current.version {
+            return Err(TrustRegistryError::Rollback { loaded: current.version, offered: document.version });
+        }
+        tracing::info!(
+            "Trust registry version {} loaded with {} issuers",
+            document.version,
+            document.issuers.len()
+        );
+        *current = document;
+        Ok(current.version)
+    }
+
+    /// Signed document, checked against the trust anchors
+    pub fn verify(&self, compact: &str) -> Result<TrustRegistryDocument, TrustRegistryError> {
+        let jws = Jws::parse(compact).map_err(|e| TrustRegistryError::InvalidSignature(e.to_string()))?;
+        let signed = self
+            .anchors
+            .iter()
+            .filter(|anchor| jws.header.kid.as_deref().is_none_or(|kid| kid == anchor.kid))
+            .any(|anchor| jws.verify(&anchor.key).is_ok());
+        if !signed {
+            return Err(TrustRegistryError::InvalidSignature(format!(
+                "not signed by a trust anchor (kid {})",
+                jws.header.kid.as_deref().unwrap_or("-")
+            )));
+        }
+        let mut document: TrustRegistryDocument =
+            jws.claims().map_err(|e| TrustRegistryError::InvalidDocument(e.to_string()))?;
+        for issuer in &mut document.issuers {
+            validate_entry(issuer)?;
+            issuer.unsigned = false;
+        }
+        Ok(document)
+    }
+
+    /// Loaded version; 0 before any file was loaded
+    pub fn version(&self) -> u64 {
+        self.document.read().version
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.document.read().issuers.is_empty()
+    }
+
+    /// Entries, optionally only those of one tenant
+    pub fn issuers(&self, tenant: Option<&str>) -> Vec<TrustedIssuer> {
+        self.document
+            .read()
+            .issuers
+            .iter()
+            .filter(|issuer| tenant.is_none() || issuer.tenant.as_deref() == tenant)
+            .cloned()
+            .collect()
+    }
+
+    /// Entry by id
+    pub fn issuer(&self, id: Uuid) -> Option<TrustedIssuer> {
+        self.document.read().issuers.iter().find(|issuer| issuer.id == id).cloned()
+    }
+
+    /// Add or replace an entry, marked as unsigned
+    ///
+    /// Local changes hold until the next signed file is loaded; they may
+    /// not allow [`PRIVILEGED_CLAIMS`]. Returns the stored entry.
+    pub fn upsert(&self, mut issuer: TrustedIssuer) -> Result<TrustedIssuer, TrustRegistryError> {
+        validate_entry(&issuer)?;
+        if let Some(claim) = issuer.allowed_claims.iter().find(|c| PRIVILEGED_CLAIMS.contains(&c.as_str())) {
+            return Err(TrustRegistryError::InvalidDocument(format!(
+                "issuer {}: only a signed registry file may allow {}",
+                issuer.did, claim
+            )));
+        }
+        issuer.unsigned = true;
+        let mut document = self.document.write();
+        match document.issuers.iter_mut().find(|existing| existing.id == issuer.id) {
+            Some(existing) => *existing = issuer.clone(),
+            None => document.issuers.push(issuer.clone()),
+        }
+        Ok(issuer)
+    }
+
+    pub fn remove(&self, id: Uuid) -> Option<TrustedIssuer> {
+        let mut document = self.document.write();
+        let position = document.issuers.iter().position(|issuer| issuer.id == id)?;
+        Some(document.issuers.remove(position))
+    }
+
+    /// Check that the issuer is trusted for the credential's type and
+    /// municipality, and may assert every claim in it
+    pub fn check(&self, credential: &VerifiableCredential, now: DateTime<Utc>) -> Result<(), VCValidationError> {
+        let tenant = credential.credential_subject.claims.get("municipality").and_then(|m| m.as_str());
+        let document = self.document.read();
+        let entries: Vec<&TrustedIssuer> = document
+            .issuers
+            .iter()
+            .filter(|issuer| {
+                credential
+                    .vc_type
+                    .iter()
+                    .filter(|t| *t != "VerifiableCredential")
+                    .any(|t| issuer.covers(&credential.issuer, t, tenant, now))
+            })
+            .collect();
+
+        if entries.is_empty() {
+            return Err(VCValidationError::UntrustedIssuer(format!(
+                "{} is not trusted for {} of {}",
+                credential.issuer,
+                credential.vc_type.join(", "),
+                tenant.unwrap_or("any municipality")
+            )));
+        }
+
+        if let Some(claim) = credential
+            .credential_subject
+            .claims
+            .keys()
+            .find(|claim| !entries.iter().any(|entry| entry.may_assert(claim)))
+        {
+            return Err(VCValidationError::UntrustedIssuer(format!(
+                "{} may not assert {}",
+                credential.issuer, claim
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn validate_entry(issuer: &TrustedIssuer) -> Result<(), TrustRegistryError> {
+    let invalid = |message: &str| TrustRegistryError::InvalidDocument(format!("issuer {}: {}", issuer.did, message));
+    if !issuer.did.starts_with("did:") {
+        return Err(invalid("not a DID"));
+    }
+    if issuer.credential_type.is_empty() {
+        return Err(invalid("no credential type"));
+    }
+    if let (Some(from), Some(until)) = (issuer.valid_from, issuer.valid_until)
+        && until <= from
+    {
+        return Err(invalid("validity ends before it starts"));
+    }
+    Ok(())
+}
+
+/// Trust registry errors
+#[derive(Debug, Error)]
+pub enum TrustRegistryError {
+    #[error("Invalid registry signature: {0}")]
+    InvalidSignature(String),
+
+    #[error("Invalid registry document: {0}")]
+    InvalidDocument(String),
+
+    #[error("Registry version {offered} is older than loaded version {loaded}")]
+    Rollback { loaded: u64, offered: u64 },
+
+    #[error("Cannot read registry: {0}")]
+    Io(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ssi::jose::test_keys::TestKey;
+    use serde_json::{json, Value};
+
+    const BZK: &str = "did:web:bzk.nl";
+    const UTRECHT: &str = "did:web:utrecht.nl";
+
+    fn entry(did: &str, tenant: Option<&str>, claims: &[&str]) -> Value {
+        json!({
+            "did": did,
+            "credential_type": "MedewerkerCredential",
+            "tenant": tenant,
+            "valid_from": "2025-01-01T00:00:00Z",
+            "allowed_claims": claims,
+        })
+    }
+
+    fn signed(anchor: &TestKey, version: u64, issuers: Vec<Value>) -> String {
+        anchor.jwt(json!({"kid": "governance-1"}), &json!({"version": version, "issuers": issuers}))
+    }
+
+    fn registry(anchor: &TestKey) -> TrustRegistry {
+        TrustRegistry::new(vec![TrustAnchor { kid: "governance-1".into(), key: anchor.public() }])
+    }
+
+    fn credential(issuer: &str, subject: Value) -> VerifiableCredential {
+        serde_json::from_value(json!({
+            "type": ["VerifiableCredential", "MedewerkerCredential"],
+            "issuer": issuer,
+            "credentialSubject": subject,
+            "proof": {"type": "DataIntegrityProof", "proofPurpose": "assertionMethod", "verificationMethod": ""}
+        }))
+        .unwrap()
+    }
+
+    fn now() -> DateTime<Utc> {
+        "2026-06-01T00:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn test_only_listed_issuers_and_claims() {
+        let anchor = TestKey::ed25519(41);
+        let registry = registry(&anchor);
+        registry
+            .load_signed(&signed(&anchor, 3, vec![
+                entry(BZK, Some("utrecht"), &["municipality", "roles", "displayName"]),
+                entry(UTRECHT, Some("utrecht"), &["municipality", "displayName"]),
+            ]))
+            .unwrap();
+
+        let roles = json!({"id": "did:example:jan", "municipality": "utrecht", "roles": ["behandelaar"]});
+        registry.check(&credential(BZK, roles.clone()), now()).unwrap();
+
+        // Utrecht may vouch for names, not for roles
+        let err = registry.check(&credential(UTRECHT, roles), now()).unwrap_err();
+        assert!(err.to_string().contains("may not assert roles"));
+        registry
+            .check(&credential(UTRECHT, json!({"municipality": "utrecht", "displayName": "Jan"})), now())
+            .unwrap();
+
+        // Not trusted for another municipality, nor before its validity
+        let amsterdam = json!({"municipality": "amsterdam", "roles": ["admin"]});
+        assert!(registry.check(&credential(BZK, amsterdam), now()).is_err());
+        let early = "2024-06-01T00:00:00Z".parse().unwrap();
+        assert!(registry.check(&credential(BZK, json!({"municipality": "utrecht"})), early).is_err());
+
+        // Unknown DID
+        assert!(matches!(
+            registry.check(&credential("did:web:evil.example", json!({"municipality": "utrecht"})), now()),
+            Err(VCValidationError::UntrustedIssuer(_))
+        ));
+    }
+
+    #[test]
+    fn test_signed_files_only_move_forward() {
+        let anchor = TestKey::ed25519(42);
+        let registry = registry(&anchor);
+        assert_eq!(registry.load_signed(&signed(&anchor, 2, vec![entry(BZK, None, &["*"])])).unwrap(), 2);
+
+        // Signed by someone else
+        let forged = signed(&TestKey::ed25519(43), 5, vec![entry("did:web:evil.example", None, &["*"])]);
+        assert!(matches!(registry.load_signed(&forged), Err(TrustRegistryError::InvalidSignature(_))));
+
+        // Replaying an older file
+        let old = signed(&anchor, 1, vec![]);
+        assert!(matches!(registry.load_signed(&old), Err(TrustRegistryError::Rollback { loaded: 2, offered: 1 })));
+        assert_eq!(registry.issuers(None).len(), 1);
+
+        assert!(!registry.issuers(None)[0].unsigned);
+
+        // Local changes until the next file, marked as unsigned
+        let mut added: TrustedIssuer = serde_json::from_value(entry(UTRECHT, Some("utrecht"), &["displayName"])).unwrap();
+        registry.upsert(added.clone()).unwrap();
+        added.allowed_claims.push("email".into());
+        let added = registry.upsert(added).unwrap();
+        assert!(added.unsigned);
+        assert_eq!(registry.issuers(Some("utrecht")), vec![added.clone()]);
+        assert_eq!(registry.issuer(added.id), Some(added.clone()));
+        assert_eq!(registry.remove(added.id), Some(added));
+        assert!(registry.issuers(Some("utrecht")).is_empty());
+
+        // Runtime entries cannot grant roles or every claim
+        for claim in PRIVILEGED_CLAIMS {
+            let privileged: TrustedIssuer =
+                serde_json::from_value(entry(UTRECHT, Some("utrecht"), &["displayName", claim])).unwrap();
+            assert!(matches!(registry.upsert(privileged), Err(TrustRegistryError::InvalidDocument(_))));
+        }
+        assert!(registry.issuers(Some("utrecht")).is_empty());
+
+        // A signed file cannot pass off entries as runtime additions
+        let marked = json!({"did": UTRECHT, "credential_type": "*", "allowed_claims": ["*"], "unsigned": true});
+        registry.load_signed(&signed(&anchor, 3, vec![marked])).unwrap();
+        assert!(!registry.issuers(None)[0].unsigned);
+    }
+}
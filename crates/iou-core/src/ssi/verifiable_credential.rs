@@ -1,13 +1,45 @@
 //! Verifiable Credential (VC) and Presentation (VP) validation
-
-use crate::ssi::did::DidDocument;
-use serde::{Deserialize, Serialize};
+//!
+//! Credentials and presentations arrive as JSON-LD with a Data Integrity
+//! proof, as JWT (JWT-VC, VP-JWT) or as SD-JWT VC. The received form is
+//! kept next to the parsed fields, so proofs are verified over exactly
+//! what the issuer and holder signed.
+
+use crate::ssi::data_integrity::DataIntegrityProof;
+use crate::ssi::did::{DidDocument, VerificationRelationship};
+use crate::ssi::jose::{Jws, PublicKey};
+use crate::ssi::sd_jwt::SdJwt;
+use crate::ssi::status_list::{CredentialState, StatusListClient, TOKEN_STATUS_LIST_ENTRY};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{json, Value};
 use std::collections::HashMap;
 use thiserror::Error;
-use uuid::Uuid;
+
+/// Tolerated clock difference with issuers and wallets
+const CLOCK_SKEW_SECONDS: i64 = 300;
+
+/// Claims of an SD-JWT VC that are not credential subject claims
+const SD_JWT_REGISTERED_CLAIMS: [&str; 10] =
+    ["iss", "sub", "iat", "nbf", "exp", "jti", "vct", "vct#integrity", "cnf", "status"];
+
+/// Form in which a credential or presentation was received
+#[derive(Debug, Clone, Default)]
+pub enum SecuredForm {
+    /// Constructed in code; there is no proof to verify
+    #[default]
+    Unsecured,
+    /// JSON-LD with an embedded Data Integrity proof
+    Json(Value),
+    /// Compact JWS (JWT-VC, VP-JWT)
+    Jwt(String),
+    /// SD-JWT with disclosures and, for presentations, key binding
+    SdJwt(String),
+}
 
 /// Verifiable Credential (W3C VC Data Model)
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "Received")]
 pub struct VerifiableCredential {
     #[serde(rename = "@context")]
     pub context: serde_json::Value,
@@ -34,12 +66,16 @@ pub struct VerifiableCredential {
 
     #[serde(rename = "proof")]
     pub proof: Proof,
+
+    #[serde(skip)]
+    source: SecuredForm,
 }
 
 /// Credential subject containing the claims
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CredentialSubject {
-    pub id: String, // DID of the subject
+    #[serde(default)]
+    pub id: String, // DID of the subject (empty for bearer credentials)
 
     /// Additional claims (role, municipality, loa, etc.)
     #[serde(flatten)]
@@ -56,6 +92,8 @@ pub enum ClaimValue {
     Bool(bool),
     Array(Vec<String>),
     Object(HashMap<String, ClaimValue>),
+    /// Anything else (null, mixed arrays), kept as JSON
+    Json(Value),
 }
 
 impl ClaimValue {
@@ -77,9 +115,30 @@ impl ClaimValue {
 /// Credential status for revocation checking
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CredentialStatus {
+    #[serde(default)]
     pub id: String,
     #[serde(rename = "type")]
     pub status_type: String,
+
+    /// `revocation` or `suspension`
+    #[serde(rename = "statusPurpose", default, skip_serializing_if = "Option::is_none")]
+    pub status_purpose: Option<String>,
+
+    #[serde(rename = "statusListIndex", default, deserialize_with = "index_text", skip_serializing_if = "Option::is_none")]
+    pub status_list_index: Option<String>,
+
+    /// URL of the status list credential or token
+    #[serde(rename = "statusListCredential", default, skip_serializing_if = "Option::is_none")]
+    pub status_list_credential: Option<String>,
+}
+
+/// `statusListIndex` is a string, some issuers send a number
+fn index_text<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    Ok(match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::String(index)) => Some(index),
+        Some(Value::Number(index)) => Some(index.to_string()),
+        _ => None,
+    })
 }
 
 /// Cryptographic proof
@@ -88,7 +147,7 @@ pub struct Proof {
     #[serde(rename = "type")]
     pub proof_type: String,
 
-    #[serde(rename = "created")]
+    #[serde(rename = "created", default)]
     pub created: String,
 
     #[serde(rename = "proofPurpose")]
@@ -99,10 +158,26 @@ pub struct Proof {
 
     #[serde(rename = "jws")]
     pub jws: Option<String>, // JSON Web Signature
+
+    /// Data Integrity cryptosuite, e.g. `eddsa-jcs-2022`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cryptosuite: Option<String>,
+
+    #[serde(rename = "proofValue", default, skip_serializing_if = "Option::is_none")]
+    pub proof_value: Option<String>,
+
+    /// Nonce of a presentation proof
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub challenge: Option<String>,
+
+    /// Audience of a presentation proof
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
 }
 
 /// Verifiable Presentation (containing one or more VCs)
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "Received")]
 pub struct VerifiablePresentation {
     #[serde(rename = "@context")]
     pub context: serde_json::Value,
@@ -119,6 +194,52 @@ pub struct VerifiablePresentation {
 
     #[serde(rename = "proof")]
     pub proof: Option<Proof>,
+
+    #[serde(skip)]
+    source: SecuredForm,
+}
+
+/// What a verifier expects of a presentation
+#[derive(Debug, Clone, Default)]
+pub struct PresentationRequirements {
+    /// Nonce the verifier handed out; the holder proof must repeat it
+    pub nonce: Option<String>,
+
+    /// Verifier identifier the presentation must be addressed to
+    pub audience: Option<String>,
+
+    /// Oldest acceptable holder proof
+    pub max_age: Option<Duration>,
+}
+
+impl PresentationRequirements {
+    pub(crate) fn check(
+        &self,
+        nonce: Option<&str>,
+        audience: &[String],
+        issued_at: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> Result<(), VCValidationError> {
+        if let Some(expected) = &self.nonce
+            && nonce != Some(expected.as_str())
+        {
+            return Err(VCValidationError::NonceMismatch);
+        }
+        if let Some(expected) = &self.audience
+            && !audience.contains(expected)
+        {
+            return Err(VCValidationError::AudienceMismatch(audience.join(", ")));
+        }
+        if let Some(max_age) = self.max_age {
+            let issued_at = issued_at.ok_or_else(|| {
+                VCValidationError::InvalidFormat("Presentation proof without creation time".into())
+            })?;
+            if issued_at < now - max_age || issued_at > now + Duration::seconds(CLOCK_SKEW_SECONDS) {
+                return Err(VCValidationError::Expired);
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Extracted claims from VC/VP
@@ -195,27 +316,307 @@ impl VerifiableCredential {
 
     /// Validate the credential (signature, expiration, revocation)
     pub async fn validate(&self, resolver: &dyn DIDResolver) -> Result<(), VCValidationError> {
-        // Check expiration
-        if let Some(exp) = &self.expiration_date {
-            let exp_date = chrono::DateTime::parse_from_rfc3339(exp)
-                .map_err(|_| VCValidationError::InvalidFormat("Invalid expiration date".into()))?;
+        self.check_validity_period(Utc::now())?;
 
-            if exp_date < chrono::Utc::now() {
-                return Err(VCValidationError::Expired);
+        // Resolve issuer DID and verify the proof with its assertion key
+        let issuer: DidDocument = resolver.resolve(&self.issuer).await
+            .map_err(|e| VCValidationError::DIDResolution(e.to_string()))?;
+        self.verify_proof(&issuer)?;
+
+        // Revocation needs the status list, see `check_status`
+        Ok(())
+    }
+
+    /// Check the status list the credential points at
+    ///
+    /// Revoked and suspended credentials are rejected. When the list cannot
+    /// be fetched, the client's policy decides.
+    pub async fn check_status(
+        &self,
+        status_lists: &StatusListClient,
+        resolver: &dyn DIDResolver,
+    ) -> Result<(), VCValidationError> {
+        match status_lists.check(self, resolver).await {
+            Ok(CredentialState::Valid) => Ok(()),
+            Ok(CredentialState::Revoked) => Err(VCValidationError::Revoked),
+            Ok(CredentialState::Suspended) => Err(VCValidationError::Suspended),
+            Ok(CredentialState::Other(value)) => Err(VCValidationError::StatusUnavailable(format!(
+                "credential {} has status 0x{:02x}",
+                self.id, value
+            ))),
+            Err(VCValidationError::StatusUnavailable(reason)) if !status_lists.policy().fail_closed => {
+                tracing::warn!("Accepting credential {} without status check: {}", self.id, reason);
+                Ok(())
             }
+            Err(e) => Err(e),
         }
+    }
 
-        // Resolve issuer DID and validate signature
-        let _: DidDocument = resolver.resolve(&self.issuer).await
-            .map_err(|e| VCValidationError::DIDResolution(e.to_string()))?;
-
-        // TODO: Verify JWS signature using public key from DID document
-        // This requires integration with a crypto library (bbs-signatures, etc.)
+    /// Form the credential was received in
+    pub fn source(&self) -> &SecuredForm {
+        &self.source
+    }
 
-        // TODO: Check revocation status if credentialStatus is present
+    /// Check issuance and expiration dates
+    pub fn check_validity_period(&self, now: DateTime<Utc>) -> Result<(), VCValidationError> {
+        let parse = |date: &str, what: &str| {
+            DateTime::parse_from_rfc3339(date)
+                .map(|d| d.with_timezone(&Utc))
+                .map_err(|_| VCValidationError::InvalidFormat(format!("Invalid {} date", what)))
+        };
 
+        if let Some(exp) = &self.expiration_date
+            && parse(exp, "expiration")? < now
+        {
+            return Err(VCValidationError::Expired);
+        }
+        if !self.issuance_date.is_empty()
+            && parse(&self.issuance_date, "issuance")? > now + Duration::seconds(CLOCK_SKEW_SECONDS)
+        {
+            return Err(VCValidationError::NotYetValid);
+        }
         Ok(())
     }
+
+    /// Verify the issuer's signature with a key the issuer lists for
+    /// assertions
+    pub fn verify_proof(&self, issuer: &DidDocument) -> Result<(), VCValidationError> {
+        if issuer.id != self.issuer {
+            return Err(VCValidationError::InvalidSignature(format!(
+                "DID document {} does not belong to issuer {}",
+                issuer.id, self.issuer
+            )));
+        }
+        let key = |id: Option<&str>| verification_key(issuer, id, VerificationRelationship::AssertionMethod);
+
+        match &self.source {
+            SecuredForm::Jwt(jwt) => {
+                let jws = Jws::parse(jwt)?;
+                jws.verify(&key(jws.header.kid.as_deref())?)
+            }
+            SecuredForm::SdJwt(sd_jwt) => {
+                let jws = SdJwt::parse(sd_jwt)?.issuer_jwt;
+                jws.verify(&key(jws.header.kid.as_deref())?)
+            }
+            SecuredForm::Json(document) => {
+                let proof = DataIntegrityProof::from_document(document)?;
+                if proof.proof_purpose != "assertionMethod" {
+                    return Err(VCValidationError::InvalidSignature(format!(
+                        "proof purpose {} is not assertionMethod",
+                        proof.proof_purpose
+                    )));
+                }
+                proof.verify(document, &key(Some(&proof.verification_method))?)
+            }
+            SecuredForm::Unsecured => Err(VCValidationError::MissingProof),
+        }
+    }
+
+    /// Holder key the issuer bound the credential to (`cnf`)
+    pub fn confirmation_key(&self) -> Result<Option<PublicKey>, VCValidationError> {
+        let cnf = match &self.source {
+            SecuredForm::SdJwt(sd_jwt) => return SdJwt::parse(sd_jwt)?.holder_key(),
+            SecuredForm::Jwt(jwt) => Jws::parse(jwt)?.claims::<Value>()?.pointer("/cnf/jwk").cloned(),
+            _ => None,
+        };
+        cnf.map(|jwk| {
+            serde_json::from_value(jwk)
+                .map_err(|e| VCValidationError::InvalidFormat(format!("Invalid cnf: {}", e)))
+                .and_then(|jwk| PublicKey::from_jwk(&jwk))
+        })
+        .transpose()
+    }
+
+    /// Parse a JWT-VC or SD-JWT VC
+    pub fn from_compact(compact: &str) -> Result<Self, VCValidationError> {
+        let compact = compact.trim();
+        if compact.contains('~') {
+            Self::from_sd_jwt(compact)
+        } else {
+            Self::from_jwt(compact)
+        }
+    }
+
+    /// JWT-VC: the `vc` claim, with the registered JWT claims taking
+    /// precedence
+    fn from_jwt(jwt: &str) -> Result<Self, VCValidationError> {
+        let jws = Jws::parse(jwt)?;
+        let claims: Value = jws.claims()?;
+        let mut vc = claims
+            .get("vc")
+            .filter(|vc| vc.is_object())
+            .cloned()
+            .ok_or_else(|| VCValidationError::InvalidFormat("JWT without vc claim".into()))?;
+
+        if let Some(iss) = claims.get("iss") {
+            vc["issuer"] = iss.clone();
+        }
+        if let Some(jti) = claims.get("jti") {
+            vc["id"] = jti.clone();
+        }
+        if let Some(sub) = claims.get("sub")
+            && vc["credentialSubject"].is_object()
+        {
+            vc["credentialSubject"]["id"] = sub.clone();
+        }
+        if let Some(date) = timestamp(&claims, "nbf").or_else(|| timestamp(&claims, "iat")) {
+            if let Some(fields) = vc.as_object_mut() {
+                fields.remove("validFrom");
+            }
+            vc["issuanceDate"] = date.into();
+        }
+        if let Some(date) = timestamp(&claims, "exp") {
+            if let Some(fields) = vc.as_object_mut() {
+                fields.remove("validUntil");
+            }
+            vc["expirationDate"] = date.into();
+        }
+        vc["proof"] = json!({
+            "type": "JwtProof2020",
+            "created": timestamp(&claims, "iat").unwrap_or_default(),
+            "proofPurpose": "assertionMethod",
+            "verificationMethod": jws.header.kid.clone().unwrap_or_else(|| vc["issuer"].as_str().unwrap_or_default().to_string()),
+            "jws": jwt,
+        });
+
+        CredentialJson::parse(vc, SecuredForm::Jwt(jwt.to_string()))
+    }
+
+    /// SD-JWT VC: disclosed claims become credential subject claims
+    fn from_sd_jwt(compact: &str) -> Result<Self, VCValidationError> {
+        let sd_jwt = SdJwt::parse(compact)?;
+        let claims = Value::Object(sd_jwt.claims()?);
+        let text = |name: &str| claims.get(name).and_then(Value::as_str).unwrap_or_default();
+
+        let mut subject: serde_json::Map<String, Value> = claims
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter(|(name, _)| !SD_JWT_REGISTERED_CLAIMS.contains(&name.as_str()))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        subject.insert("id".into(), text("sub").into());
+
+        // Token Status List reference, in the shape of a credentialStatus
+        let status = claims.pointer("/status/status_list").map(|list| {
+            json!({
+                "type": TOKEN_STATUS_LIST_ENTRY,
+                "statusListIndex": list.get("idx"),
+                "statusListCredential": list.get("uri"),
+            })
+        });
+
+        let vc = json!({
+            "@context": [],
+            "type": ["VerifiableCredential", text("vct")],
+            "id": text("jti"),
+            "issuer": text("iss"),
+            "issuanceDate": timestamp(&claims, "nbf").or_else(|| timestamp(&claims, "iat")).unwrap_or_default(),
+            "expirationDate": timestamp(&claims, "exp"),
+            "credentialSubject": subject,
+            "credentialStatus": status,
+            "proof": {
+                "type": "SD-JWT",
+                "created": timestamp(&claims, "iat").unwrap_or_default(),
+                "proofPurpose": "assertionMethod",
+                "verificationMethod": sd_jwt.issuer_jwt.header.kid.clone().unwrap_or_default(),
+                "jws": compact,
+            },
+        });
+
+        CredentialJson::parse(vc, SecuredForm::SdJwt(compact.to_string()))
+    }
+}
+
+/// RFC 3339 form of a NumericDate claim
+fn timestamp(claims: &Value, name: &str) -> Option<String> {
+    let seconds = claims.get(name)?.as_i64()?;
+    DateTime::from_timestamp(seconds, 0).map(|t| t.to_rfc3339())
+}
+
+/// Key of a verification method listed for `relationship`
+fn verification_key(
+    document: &DidDocument,
+    id: Option<&str>,
+    relationship: VerificationRelationship,
+) -> Result<PublicKey, VCValidationError> {
+    let method = document.verification_method_for(id, relationship).ok_or_else(|| {
+        VCValidationError::UnknownKey(id.unwrap_or(document.id.as_str()).to_string())
+    })?;
+    PublicKey::from_verification_method(&method)
+}
+
+/// Credential or presentation as it arrives: JSON or a compact string
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Received {
+    Compact(String),
+    Json(Value),
+}
+
+impl TryFrom<Received> for VerifiableCredential {
+    type Error = VCValidationError;
+
+    fn try_from(received: Received) -> Result<Self, Self::Error> {
+        match received {
+            Received::Compact(compact) => Self::from_compact(&compact),
+            Received::Json(value) => CredentialJson::parse(value.clone(), SecuredForm::Json(value)),
+        }
+    }
+}
+
+/// Fields of a JSON credential (VC Data Model 1.1 and 2.0)
+#[derive(Deserialize)]
+struct CredentialJson {
+    #[serde(rename = "@context", default)]
+    context: Value,
+    #[serde(rename = "type")]
+    vc_type: Vec<String>,
+    #[serde(default)]
+    id: String,
+    #[serde(deserialize_with = "issuer_id")]
+    issuer: String,
+    #[serde(rename = "issuanceDate", alias = "validFrom", default)]
+    issuance_date: String,
+    #[serde(rename = "expirationDate", alias = "validUntil", default)]
+    expiration_date: Option<String>,
+    #[serde(rename = "credentialSubject")]
+    credential_subject: CredentialSubject,
+    #[serde(rename = "credentialStatus", default)]
+    credential_status: Option<CredentialStatus>,
+    proof: Proof,
+}
+
+impl CredentialJson {
+    fn parse(value: Value, source: SecuredForm) -> Result<VerifiableCredential, VCValidationError> {
+        let fields: Self = serde_json::from_value(value)
+            .map_err(|e| VCValidationError::InvalidFormat(format!("Invalid credential: {}", e)))?;
+        Ok(VerifiableCredential {
+            context: fields.context,
+            vc_type: fields.vc_type,
+            id: fields.id,
+            issuer: fields.issuer,
+            issuance_date: fields.issuance_date,
+            expiration_date: fields.expiration_date,
+            credential_subject: fields.credential_subject,
+            credential_status: fields.credential_status,
+            proof: fields.proof,
+            source,
+        })
+    }
+}
+
+/// Issuer as a DID string or an object with an `id`
+fn issuer_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Issuer {
+        Id(String),
+        Object { id: String },
+    }
+    Ok(match Issuer::deserialize(deserializer)? {
+        Issuer::Id(id) | Issuer::Object { id } => id,
+    })
 }
 
 impl VerifiablePresentation {
@@ -265,15 +666,210 @@ impl VerifiablePresentation {
 
     /// Validate the presentation and all contained VCs
     pub async fn validate(&self, resolver: &dyn DIDResolver) -> Result<(), VCValidationError> {
+        self.validate_with(resolver, &PresentationRequirements::default()).await
+    }
+
+    /// Validate the credentials, the holder proof and the holder binding
+    pub async fn validate_with(
+        &self,
+        resolver: &dyn DIDResolver,
+        requirements: &PresentationRequirements,
+    ) -> Result<(), VCValidationError> {
+        if self.verifiable_credential.is_empty() {
+            return Err(VCValidationError::InvalidFormat("Presentation without credentials".into()));
+        }
+
         // Validate each contained VC
         for vc in &self.verifiable_credential {
             vc.validate(resolver).await?;
         }
 
-        // TODO: Verify presentation proof if present
+        let now = Utc::now();
+        match &self.source {
+            // Key binding JWT signed with the key in the credential's cnf
+            SecuredForm::SdJwt(sd_jwt) => {
+                let binding = SdJwt::parse(sd_jwt)?.verify_key_binding()?;
+                requirements.check(
+                    binding.nonce.as_deref(),
+                    binding.aud.as_slice(),
+                    DateTime::from_timestamp(binding.iat, 0),
+                    now,
+                )
+            }
+            SecuredForm::Jwt(jwt) => {
+                let jws = Jws::parse(jwt)?;
+                let key = self.holder_key(resolver, jws.header.kid.as_deref()).await?;
+                jws.verify(&key)?;
+
+                let claims: Value = jws.claims()?;
+                let audience: Vec<String> = match claims.get("aud") {
+                    Some(Value::String(aud)) => vec![aud.clone()],
+                    Some(Value::Array(auds)) => auds.iter().filter_map(|a| a.as_str().map(str::to_string)).collect(),
+                    _ => Vec::new(),
+                };
+                let issued_at = claims.get("iat").and_then(Value::as_i64).and_then(|t| DateTime::from_timestamp(t, 0));
+                requirements.check(claims.get("nonce").and_then(Value::as_str), &audience, issued_at, now)?;
+                self.check_holder_binding(&key)
+            }
+            SecuredForm::Json(document) => {
+                let proof = DataIntegrityProof::from_document(document)?;
+                if proof.proof_purpose != "authentication" {
+                    return Err(VCValidationError::InvalidSignature(format!(
+                        "proof purpose {} is not authentication",
+                        proof.proof_purpose
+                    )));
+                }
+                let key = self.holder_key(resolver, Some(&proof.verification_method)).await?;
+                proof.verify(document, &key)?;
+
+                let created = proof
+                    .created
+                    .as_deref()
+                    .and_then(|c| DateTime::parse_from_rfc3339(c).ok())
+                    .map(|c| c.with_timezone(&Utc));
+                requirements.check(proof.challenge.as_deref(), proof.domain.as_slice(), created, now)?;
+                self.check_holder_binding(&key)
+            }
+            SecuredForm::Unsecured => Err(VCValidationError::MissingProof),
+        }
+    }
+
+    /// Parse a presentation: JSON, VP-JWT or SD-JWT with key binding
+    pub fn parse(input: &str) -> Result<Self, VCValidationError> {
+        let input = input.trim();
+        if input.starts_with('{') {
+            let value: Value = serde_json::from_str(input)
+                .map_err(|e| VCValidationError::InvalidFormat(format!("Invalid presentation: {}", e)))?;
+            Self::try_from(Received::Json(value))
+        } else {
+            Self::try_from(Received::Compact(input.to_string()))
+        }
+    }
+
+    /// Form the presentation was received in
+    pub fn source(&self) -> &SecuredForm {
+        &self.source
+    }
+
+    /// Authentication key of the holder's DID
+    async fn holder_key(&self, resolver: &dyn DIDResolver, kid: Option<&str>) -> Result<PublicKey, VCValidationError> {
+        let did = kid
+            .filter(|kid| kid.starts_with("did:"))
+            .map(|kid| kid.split('#').next().unwrap_or(kid))
+            .unwrap_or(&self.holder);
+        if did != self.holder {
+            return Err(VCValidationError::HolderBinding(format!("{} is not a key of holder {}", did, self.holder)));
+        }
+        let document = resolver.resolve(did).await
+            .map_err(|e| VCValidationError::DIDResolution(e.to_string()))?;
+        verification_key(&document, kid, VerificationRelationship::Authentication)
+    }
 
+    /// Each credential must be issued to the holder: its subject is the
+    /// holder DID, or its `cnf` key signed the presentation
+    fn check_holder_binding(&self, holder_key: &PublicKey) -> Result<(), VCValidationError> {
+        for vc in &self.verifiable_credential {
+            let bound = vc.credential_subject.id == self.holder
+                || vc.confirmation_key()?.as_ref() == Some(holder_key);
+            if !bound {
+                return Err(VCValidationError::HolderBinding(format!(
+                    "credential {} is not issued to {}",
+                    vc.id, self.holder
+                )));
+            }
+        }
         Ok(())
     }
+
+    /// VP-JWT: the `vp` claim, `iss` is the holder
+    fn from_jwt(jwt: &str) -> Result<Self, VCValidationError> {
+        let jws = Jws::parse(jwt)?;
+        let claims: Value = jws.claims()?;
+        let mut vp = claims
+            .get("vp")
+            .filter(|vp| vp.is_object())
+            .cloned()
+            .ok_or_else(|| VCValidationError::InvalidFormat("JWT without vp claim".into()))?;
+        if let Some(iss) = claims.get("iss") {
+            vp["holder"] = iss.clone();
+        }
+        if let Some(jti) = claims.get("jti") {
+            vp["id"] = jti.clone();
+        }
+        PresentationJson::parse(vp, SecuredForm::Jwt(jwt.to_string()))
+    }
+
+    /// SD-JWT VC with key binding, as sent by EUDI wallets
+    fn from_sd_jwt(compact: &str) -> Result<Self, VCValidationError> {
+        let credential = VerifiableCredential::from_sd_jwt(compact)?;
+        let holder = match credential.confirmation_key()? {
+            Some(key) => format!("urn:ietf:params:oauth:jwk-thumbprint:sha-256:{}", key.thumbprint()),
+            None => credential.credential_subject.id.clone(),
+        };
+        Ok(Self {
+            context: json!([]),
+            vp_type: vec!["VerifiablePresentation".to_string()],
+            id: None,
+            holder,
+            verifiable_credential: vec![credential],
+            proof: None,
+            source: SecuredForm::SdJwt(compact.to_string()),
+        })
+    }
+}
+
+impl TryFrom<Received> for VerifiablePresentation {
+    type Error = VCValidationError;
+
+    fn try_from(received: Received) -> Result<Self, Self::Error> {
+        match received {
+            Received::Compact(compact) if compact.contains('~') => Self::from_sd_jwt(&compact),
+            Received::Compact(compact) => Self::from_jwt(&compact),
+            Received::Json(value) => PresentationJson::parse(value.clone(), SecuredForm::Json(value)),
+        }
+    }
+}
+
+/// Fields of a JSON presentation
+#[derive(Deserialize)]
+struct PresentationJson {
+    #[serde(rename = "@context", default)]
+    context: Value,
+    #[serde(rename = "type")]
+    vp_type: Vec<String>,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    holder: Option<String>,
+    #[serde(rename = "verifiableCredential", default)]
+    verifiable_credential: Vec<VerifiableCredential>,
+    #[serde(default)]
+    proof: Option<Proof>,
+}
+
+impl PresentationJson {
+    fn parse(value: Value, source: SecuredForm) -> Result<VerifiablePresentation, VCValidationError> {
+        let fields: Self = serde_json::from_value(value)
+            .map_err(|e| VCValidationError::InvalidFormat(format!("Invalid presentation: {}", e)))?;
+        // Without a holder, the DID of the proof key is the holder
+        let holder = fields
+            .holder
+            .or_else(|| {
+                let method = &fields.proof.as_ref()?.verification_method;
+                Some(method.split('#').next().unwrap_or(method).to_string())
+            })
+            .ok_or_else(|| VCValidationError::InvalidFormat("Presentation without holder".into()))?;
+
+        Ok(VerifiablePresentation {
+            context: fields.context,
+            vp_type: fields.vp_type,
+            id: fields.id,
+            holder,
+            verifiable_credential: fields.verifiable_credential,
+            proof: fields.proof,
+            source,
+        })
+    }
 }
 
 /// DID resolver trait
@@ -297,6 +893,12 @@ pub enum VCValidationError {
     #[error("Credential revoked")]
     Revoked,
 
+    #[error("Credential suspended")]
+    Suspended,
+
+    #[error("Credential status unavailable: {0}")]
+    StatusUnavailable(String),
+
     #[error("Invalid format: {0}")]
     InvalidFormat(String),
 
@@ -305,11 +907,254 @@ pub enum VCValidationError {
 
     #[error("Untrusted issuer: {0}")]
     UntrustedIssuer(String),
+
+    #[error("Credential not yet valid")]
+    NotYetValid,
+
+    #[error("Missing proof")]
+    MissingProof,
+
+    #[error("Unsupported proof: {0}")]
+    UnsupportedProof(String),
+
+    #[error("Unknown verification method: {0}")]
+    UnknownKey(String),
+
+    #[error("Holder binding failed: {0}")]
+    HolderBinding(String),
+
+    #[error("Presentation nonce does not match")]
+    NonceMismatch,
+
+    #[error("Presentation not addressed to this verifier: {0}")]
+    AudienceMismatch(String),
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ssi::data_integrity::secure;
+    use crate::ssi::jose::test_keys::TestKey;
+    use crate::ssi::sd_jwt::test_support::{disclosure, issue, present};
+
+    const ISSUER: &str = "did:example:bzk";
+    const HOLDER: &str = "did:example:jan";
+
+    /// Resolves a fixed set of DIDs
+    struct StaticResolver(HashMap<String, DidDocument>);
+
+    impl StaticResolver {
+        fn new(keys: &[(&str, &TestKey)]) -> Self {
+            let documents = keys
+                .iter()
+                .map(|(did, key)| {
+                    let document = json!({
+                        "@context": "https://www.w3.org/ns/did/v1",
+                        "id": did,
+                        "verificationMethod": [{
+                            "id": format!("{}#key-1", did),
+                            "type": "JsonWebKey2020",
+                            "controller": did,
+                            "publicKeyJwk": key.public().to_jwk()
+                        }],
+                        "assertionMethod": ["#key-1"],
+                        "authentication": ["#key-1"]
+                    });
+                    (did.to_string(), serde_json::from_value(document).unwrap())
+                })
+                .collect();
+            Self(documents)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl DIDResolver for StaticResolver {
+        async fn resolve(&self, did: &str) -> Result<DidDocument, Box<dyn std::error::Error>> {
+            self.0.get(did).cloned().ok_or_else(|| format!("unknown DID {}", did).into())
+        }
+    }
+
+    fn credential_json(subject: &str) -> Value {
+        json!({
+            "@context": ["https://www.w3.org/ns/credentials/v2"],
+            "type": ["VerifiableCredential", "MedewerkerCredential"],
+            "id": "urn:uuid:5d0a6e3c-1f7e-4a51-9a55-0f3c0d1c3a11",
+            "issuer": {"id": ISSUER, "name": "BZK"},
+            "validFrom": "2025-01-01T00:00:00Z",
+            "credentialSubject": {"id": subject, "municipality": "utrecht", "roles": ["behandelaar"]}
+        })
+    }
+
+    fn jwt_vc(issuer: &TestKey, subject: &str) -> String {
+        let mut vc = credential_json(subject);
+        vc.as_object_mut().unwrap().remove("issuer");
+        issuer.jwt(
+            json!({"kid": format!("{}#key-1", ISSUER)}),
+            &json!({"iss": ISSUER, "sub": subject, "nbf": 1735689600, "vc": vc}),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_jwt_vc_signature() {
+        let issuer = TestKey::ed25519(21);
+        let resolver = StaticResolver::new(&[(ISSUER, &issuer)]);
+
+        let jwt = jwt_vc(&issuer, HOLDER);
+        let vc: VerifiableCredential = serde_json::from_value(Value::String(jwt.clone())).unwrap();
+        assert_eq!(vc.issuer, ISSUER);
+        assert_eq!(vc.credential_subject.id, HOLDER);
+        assert_eq!(vc.issuance_date, "2025-01-01T00:00:00+00:00");
+        assert!(matches!(vc.source(), SecuredForm::Jwt(_)));
+        vc.validate(&resolver).await.unwrap();
+
+        // Signed by someone else
+        let forged = jwt_vc(&TestKey::ed25519(22), HOLDER);
+        let vc = VerifiableCredential::from_compact(&forged).unwrap();
+        assert!(matches!(vc.validate(&resolver).await, Err(VCValidationError::InvalidSignature(_))));
+
+        // Constructed credentials carry no proof
+        let mut vc = VerifiableCredential::from_compact(&jwt).unwrap();
+        vc.source = SecuredForm::Unsecured;
+        assert!(matches!(vc.validate(&resolver).await, Err(VCValidationError::MissingProof)));
+    }
+
+    #[tokio::test]
+    async fn test_data_integrity_presentation() {
+        let issuer = TestKey::p256();
+        let holder = TestKey::ed25519(23);
+        let resolver = StaticResolver::new(&[(ISSUER, &issuer), (HOLDER, &holder)]);
+
+        let vc = secure(
+            &credential_json(HOLDER),
+            &issuer,
+            json!({"verificationMethod": format!("{}#key-1", ISSUER), "proofPurpose": "assertionMethod"}),
+        );
+        let vp = secure(
+            &json!({
+                "@context": ["https://www.w3.org/ns/credentials/v2"],
+                "type": ["VerifiablePresentation"],
+                "holder": HOLDER,
+                "verifiableCredential": [vc]
+            }),
+            &holder,
+            json!({
+                "verificationMethod": format!("{}#key-1", HOLDER),
+                "proofPurpose": "authentication",
+                "created": Utc::now().to_rfc3339(),
+                "challenge": "n-0S6_WzA2Mj",
+                "domain": "https://iou.example.nl"
+            }),
+        );
+
+        let vp = VerifiablePresentation::parse(&vp.to_string()).unwrap();
+        let requirements = PresentationRequirements {
+            nonce: Some("n-0S6_WzA2Mj".into()),
+            audience: Some("https://iou.example.nl".into()),
+            max_age: Some(Duration::minutes(5)),
+        };
+        vp.validate_with(&resolver, &requirements).await.unwrap();
+        assert_eq!(vp.extract_claims().unwrap().issuer, ISSUER);
+
+        // Replayed for another session
+        let replay = PresentationRequirements { nonce: Some("other".into()), ..requirements.clone() };
+        assert!(matches!(vp.validate_with(&resolver, &replay).await, Err(VCValidationError::NonceMismatch)));
+
+        // Forged credential inside a validly signed presentation
+        let mut document = match vp.source() {
+            SecuredForm::Json(document) => document.clone(),
+            other => panic!("unexpected form {:?}", other),
+        };
+        document["verifiableCredential"][0]["credentialSubject"]["municipality"] = "amsterdam".into();
+        let forged = VerifiablePresentation::parse(&document.to_string()).unwrap();
+        assert!(forged.validate_with(&resolver, &requirements).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sd_jwt_presentation() {
+        let issuer = TestKey::ed25519(24);
+        let holder = TestKey::p256();
+        let resolver = StaticResolver::new(&[(ISSUER, &issuer)]);
+
+        let payload = json!({
+            "iss": ISSUER,
+            "iat": 1735689600,
+            "vct": "MedewerkerCredential",
+            "cnf": {"jwk": holder.public().to_jwk()}
+        });
+        let disclosures = [
+            disclosure("salt-1", Some("municipality"), json!("utrecht")),
+            disclosure("salt-2", Some("roles"), json!(["behandelaar"])),
+        ];
+        let sd_jwt = issue(&issuer, &format!("{}#key-1", ISSUER), payload, &disclosures);
+        let presented = present(&sd_jwt, &holder, "n-1", "https://iou.example.nl", Utc::now().timestamp());
+
+        let vp = VerifiablePresentation::parse(&presented).unwrap();
+        assert!(vp.holder.ends_with(&holder.public().thumbprint()));
+        let vc = &vp.verifiable_credential[0];
+        assert_eq!(vc.vc_type, ["VerifiableCredential", "MedewerkerCredential"]);
+        assert_eq!(vc.credential_subject.claims["municipality"].as_str(), Some("utrecht"));
+        assert!(!vc.credential_subject.claims.contains_key("cnf"));
+
+        let requirements = PresentationRequirements {
+            nonce: Some("n-1".into()),
+            audience: Some("https://iou.example.nl".into()),
+            max_age: Some(Duration::minutes(5)),
+        };
+        vp.validate_with(&resolver, &requirements).await.unwrap();
+
+        let elsewhere = PresentationRequirements { audience: Some("https://other.example".into()), ..requirements.clone() };
+        assert!(matches!(
+            vp.validate_with(&resolver, &elsewhere).await,
+            Err(VCValidationError::AudienceMismatch(_))
+        ));
+
+        // Without key binding the holder proved nothing
+        let vp = VerifiablePresentation::parse(&sd_jwt).unwrap();
+        assert!(matches!(vp.validate(&resolver).await, Err(VCValidationError::HolderBinding(_))));
+    }
+
+    #[tokio::test]
+    async fn test_vp_jwt_holder_binding() {
+        let issuer = TestKey::ed25519(25);
+        let holder = TestKey::ed25519(26);
+        let resolver = StaticResolver::new(&[(ISSUER, &issuer), (HOLDER, &holder)]);
+
+        let vp_jwt = |subject: &str| {
+            holder.jwt(
+                json!({"kid": format!("{}#key-1", HOLDER)}),
+                &json!({
+                    "iss": HOLDER,
+                    "aud": "https://iou.example.nl",
+                    "nonce": "n-2",
+                    "iat": Utc::now().timestamp(),
+                    "vp": {"type": ["VerifiablePresentation"], "verifiableCredential": [jwt_vc(&issuer, subject)]}
+                }),
+            )
+        };
+        let requirements = PresentationRequirements { nonce: Some("n-2".into()), ..Default::default() };
+
+        let vp = VerifiablePresentation::parse(&vp_jwt(HOLDER)).unwrap();
+        assert_eq!(vp.holder, HOLDER);
+        vp.validate_with(&resolver, &requirements).await.unwrap();
+
+        // A credential issued to someone else
+        let vp = VerifiablePresentation::parse(&vp_jwt("did:example:piet")).unwrap();
+        assert!(matches!(
+            vp.validate_with(&resolver, &requirements).await,
+            Err(VCValidationError::HolderBinding(_))
+        ));
+    }
+
+    #[test]
+    fn test_validity_period() {
+        let vc = VerifiableCredential::from_compact(&jwt_vc(&TestKey::ed25519(27), HOLDER)).unwrap();
+        let issued = DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        assert!(vc.check_validity_period(issued).is_ok());
+        assert!(matches!(
+            vc.check_validity_period(issued - Duration::hours(1)),
+            Err(VCValidationError::NotYetValid)
+        ));
+    }
 
     #[test]
     fn test_claim_value_as_str() {
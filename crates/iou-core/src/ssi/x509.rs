@@ -0,0 +1,485 @@
+//! X.509 certificates of credential issuers
+//!
+//! mdoc issuers (document signer certificates under an IACA root) and
+//! SD-JWT VC issuers with an `x5c` header are trusted through a
+//! certificate chain rather than a DID. Only what chain validation needs
+//! is parsed: names, validity, the subject key, basic constraints and the
+//! signature.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use ring::signature::{self, UnparsedPublicKey};
+use sha2::{Digest, Sha256};
+
+use crate::ssi::ec;
+use crate::ssi::encoding::base64_decode;
+use crate::ssi::jose::PublicKey;
+use crate::ssi::verifiable_credential::VCValidationError;
+
+const OID_EC_PUBLIC_KEY: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+const OID_P256: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+const OID_P384: &[u8] = &[0x2b, 0x81, 0x04, 0x00, 0x22];
+const OID_SECP256K1: &[u8] = &[0x2b, 0x81, 0x04, 0x00, 0x0a];
+const OID_ED25519: &[u8] = &[0x2b, 0x65, 0x70];
+const OID_ECDSA_SHA256: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02];
+const OID_ECDSA_SHA384: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x03];
+const OID_BASIC_CONSTRAINTS: &[u8] = &[0x55, 0x1d, 0x13];
+const OID_COMMON_NAME: &[u8] = &[0x55, 0x04, 0x03];
+
+const SEQUENCE: u8 = 0x30;
+const SET: u8 = 0x31;
+const INTEGER: u8 = 0x02;
+const BIT_STRING: u8 = 0x03;
+const OCTET_STRING: u8 = 0x04;
+const OID: u8 = 0x06;
+const BOOLEAN: u8 = 0x01;
+const UTC_TIME: u8 = 0x17;
+const GENERALIZED_TIME: u8 = 0x18;
+/// `[0] EXPLICIT` version
+const VERSION: u8 = 0xa0;
+/// `[3] EXPLICIT` extensions
+const EXTENSIONS: u8 = 0xa3;
+
+/// Curve of a subject public key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyAlgorithm {
+    P256,
+    P384,
+    Secp256k1,
+    Ed25519,
+}
+
+/// Parsed X.509 certificate
+#[derive(Debug, Clone)]
+pub struct Certificate {
+    der: Vec<u8>,
+    /// Signed part, with its DER header
+    tbs: Vec<u8>,
+    signature_algorithm: Vec<u8>,
+    signature: Vec<u8>,
+    issuer: Vec<u8>,
+    subject: Vec<u8>,
+    key_algorithm: KeyAlgorithm,
+    key: Vec<u8>,
+    is_ca: bool,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+}
+
+impl Certificate {
+    pub fn from_der(der: &[u8]) -> Result<Self, VCValidationError> {
+        let mut outer = Der::new(der);
+        let mut certificate = Der::new(outer.expect(SEQUENCE)?);
+        if !outer.is_empty() {
+            return Err(invalid("trailing data"));
+        }
+
+        let (tbs_content, tbs) = certificate.expect_with_header(SEQUENCE)?;
+        let signature_algorithm = Der::new(certificate.expect(SEQUENCE)?).expect(OID)?.to_vec();
+        let signature = bit_string(certificate.expect(BIT_STRING)?)?.to_vec();
+
+        let mut fields = Der::new(tbs_content);
+        if fields.peek() == Some(VERSION) {
+            fields.next()?;
+        }
+        fields.expect(INTEGER)?; // serial number
+        fields.expect(SEQUENCE)?; // signature algorithm, repeated outside
+        let (_, issuer) = fields.expect_with_header(SEQUENCE)?;
+        let mut validity = Der::new(fields.expect(SEQUENCE)?);
+        let not_before = time(&mut validity)?;
+        let not_after = time(&mut validity)?;
+        let (_, subject) = fields.expect_with_header(SEQUENCE)?;
+        let (key_algorithm, key) = subject_public_key(fields.expect(SEQUENCE)?)?;
+
+        let mut is_ca = false;
+        while let Some(tag) = fields.peek() {
+            let content = fields.next()?.1;
+            if tag == EXTENSIONS {
+                is_ca = basic_constraints_ca(Der::new(content).expect(SEQUENCE)?)?;
+            }
+        }
+
+        Ok(Self {
+            der: der.to_vec(),
+            tbs: tbs.to_vec(),
+            signature_algorithm,
+            signature,
+            issuer: issuer.to_vec(),
+            subject: subject.to_vec(),
+            key_algorithm,
+            key: key.to_vec(),
+            is_ca,
+            not_before,
+            not_after,
+        })
+    }
+
+    /// Base64 DER, as in an `x5c` header
+    pub fn from_base64(encoded: &str) -> Result<Self, VCValidationError> {
+        Self::from_der(&base64_decode(encoded).map_err(|e| invalid(&e.to_string()))?)
+    }
+
+    /// All `CERTIFICATE` blocks of a PEM file
+    pub fn from_pem(pem: &str) -> Result<Vec<Self>, VCValidationError> {
+        let mut certificates = Vec::new();
+        let mut block: Option<String> = None;
+        for line in pem.lines().map(str::trim) {
+            match (line, block.as_mut()) {
+                ("-----BEGIN CERTIFICATE-----", None) => block = Some(String::new()),
+                ("-----END CERTIFICATE-----", Some(body)) => {
+                    certificates.push(Self::from_base64(body)?);
+                    block = None;
+                }
+                (line, Some(body)) => body.push_str(line),
+                _ => {}
+            }
+        }
+        if block.is_some() {
+            return Err(invalid("unterminated PEM block"));
+        }
+        Ok(certificates)
+    }
+
+    pub fn der(&self) -> &[u8] {
+        &self.der
+    }
+
+    /// SHA-256 of the DER encoding, hex
+    pub fn fingerprint(&self) -> String {
+        hex::encode(Sha256::digest(&self.der))
+    }
+
+    /// First common name (CN) of the subject
+    pub fn common_name(&self) -> Option<String> {
+        let mut name = Der::new(&self.subject);
+        let mut rdns = Der::new(name.expect(SEQUENCE).ok()?);
+        while !rdns.is_empty() {
+            let mut set = Der::new(rdns.expect(SET).ok()?);
+            while !set.is_empty() {
+                let mut attribute = Der::new(set.expect(SEQUENCE).ok()?);
+                if attribute.expect(OID).ok()? == OID_COMMON_NAME {
+                    let (_, value, _) = attribute.next().ok()?;
+                    return String::from_utf8(value.to_vec()).ok();
+                }
+            }
+        }
+        None
+    }
+
+    /// Subject key, for verifying what the certificate holder signed
+    pub fn public_key(&self) -> Result<PublicKey, VCValidationError> {
+        let point = |curve: &ec::Curve| curve.decode_point(&self.key).ok_or_else(|| invalid("point is not on the curve"));
+        match self.key_algorithm {
+            KeyAlgorithm::P256 => point(&ec::P256).map(|(x, y)| PublicKey::P256 { x, y }),
+            KeyAlgorithm::Secp256k1 => point(&ec::SECP256K1).map(|(x, y)| PublicKey::Secp256k1 { x, y }),
+            KeyAlgorithm::Ed25519 if self.key.len() == 32 => Ok(PublicKey::Ed25519(self.key.clone())),
+            _ => Err(VCValidationError::UnsupportedProof(format!("{:?} certificate key", self.key_algorithm))),
+        }
+    }
+
+    pub fn is_valid_at(&self, now: DateTime<Utc>) -> bool {
+        self.not_before <= now && now <= self.not_after
+    }
+
+    /// Whether `issuer` signed this certificate
+    pub fn verify_issued_by(&self, issuer: &Certificate) -> Result<(), VCValidationError> {
+        if self.issuer != issuer.subject {
+            return Err(VCValidationError::InvalidSignature("certificate issuer does not match".into()));
+        }
+        let algorithm: &dyn signature::VerificationAlgorithm =
+            match (self.signature_algorithm.as_slice(), issuer.key_algorithm) {
+                (OID_ECDSA_SHA256, KeyAlgorithm::P256) => &signature::ECDSA_P256_SHA256_ASN1,
+                (OID_ECDSA_SHA384, KeyAlgorithm::P256) => &signature::ECDSA_P256_SHA384_ASN1,
+                (OID_ECDSA_SHA256, KeyAlgorithm::P384) => &signature::ECDSA_P384_SHA256_ASN1,
+                (OID_ECDSA_SHA384, KeyAlgorithm::P384) => &signature::ECDSA_P384_SHA384_ASN1,
+                (OID_ED25519, KeyAlgorithm::Ed25519) => &signature::ED25519,
+                _ => {
+                    return Err(VCValidationError::UnsupportedProof(format!(
+                        "certificate signature with a {:?} key",
+                        issuer.key_algorithm
+                    )));
+                }
+            };
+        UnparsedPublicKey::new(algorithm, &issuer.key)
+            .verify(&self.tbs, &self.signature)
+            .map_err(|_| VCValidationError::InvalidSignature("certificate signature does not match".into()))
+    }
+}
+
+/// Check that `chain` (leaf first) leads to one of `roots`
+///
+/// Certificates between the leaf and the root must be CAs. Revocation of
+/// certificates is not checked.
+pub fn verify_chain(chain: &[Certificate], roots: &[Certificate], now: DateTime<Utc>) -> Result<(), VCValidationError> {
+    let leaf = chain.first().ok_or_else(|| VCValidationError::UntrustedIssuer("empty certificate chain".into()))?;
+    if let Some(expired) = chain.iter().find(|certificate| !certificate.is_valid_at(now)) {
+        return Err(VCValidationError::UntrustedIssuer(format!(
+            "certificate {} is not valid at {}",
+            expired.common_name().unwrap_or_else(|| expired.fingerprint()),
+            now
+        )));
+    }
+    if roots.iter().any(|root| root.der == leaf.der) {
+        return Ok(());
+    }
+
+    let mut current = leaf;
+    for next in chain.iter().skip(1).map(Some).chain(std::iter::once(None)) {
+        if roots
+            .iter()
+            .any(|root| root.is_valid_at(now) && current.verify_issued_by(root).is_ok())
+        {
+            return Ok(());
+        }
+        match next {
+            Some(intermediate) if intermediate.is_ca => {
+                current.verify_issued_by(intermediate)?;
+                current = intermediate;
+            }
+            Some(_) => return Err(VCValidationError::UntrustedIssuer("intermediate certificate is not a CA".into())),
+            None => break,
+        }
+    }
+    Err(VCValidationError::UntrustedIssuer(format!(
+        "certificate {} does not chain to a trusted root",
+        leaf.common_name().unwrap_or_else(|| leaf.fingerprint())
+    )))
+}
+
+fn invalid(message: &str) -> VCValidationError {
+    VCValidationError::InvalidFormat(format!("Invalid certificate: {}", message))
+}
+
+/// Content of a BIT STRING without unused bits
+fn bit_string(content: &[u8]) -> Result<&[u8], VCValidationError> {
+    match content.split_first() {
+        Some((0, bits)) => Ok(bits),
+        _ => Err(invalid("bit string with unused bits")),
+    }
+}
+
+fn time(der: &mut Der) -> Result<DateTime<Utc>, VCValidationError> {
+    let (tag, content, _) = der.next()?;
+    let text = std::str::from_utf8(content).map_err(|_| invalid("time is not ASCII"))?;
+    let full = match tag {
+        // YYMMDDHHMMSSZ; years 50-99 are 19xx (RFC 5280)
+        UTC_TIME if text.len() == 13 => {
+            let century = if &text[..2] >= "50" { "19" } else { "20" };
+            format!("{}{}", century, text)
+        }
+        GENERALIZED_TIME if text.len() == 15 => text.to_string(),
+        _ => return Err(invalid("unsupported time format")),
+    };
+    NaiveDateTime::parse_from_str(&full, "%Y%m%d%H%M%SZ")
+        .map(|t| t.and_utc())
+        .map_err(|_| invalid("invalid time"))
+}
+
+fn subject_public_key(content: &[u8]) -> Result<(KeyAlgorithm, &[u8]), VCValidationError> {
+    let mut spki = Der::new(content);
+    let mut algorithm = Der::new(spki.expect(SEQUENCE)?);
+    let key = bit_string(spki.expect(BIT_STRING)?)?;
+
+    let key_algorithm = match algorithm.expect(OID)? {
+        OID_EC_PUBLIC_KEY => match algorithm.expect(OID)? {
+            OID_P256 => KeyAlgorithm::P256,
+            OID_P384 => KeyAlgorithm::P384,
+            OID_SECP256K1 => KeyAlgorithm::Secp256k1,
+            _ => return Err(VCValidationError::UnsupportedProof("certificate key curve".into())),
+        },
+        OID_ED25519 => KeyAlgorithm::Ed25519,
+        _ => return Err(VCValidationError::UnsupportedProof("certificate key algorithm".into())),
+    };
+    Ok((key_algorithm, key))
+}
+
+/// `cA` of the basic constraints extension, false when absent
+fn basic_constraints_ca(extensions: &[u8]) -> Result<bool, VCValidationError> {
+    let mut extensions = Der::new(extensions);
+    while !extensions.is_empty() {
+        let mut extension = Der::new(extensions.expect(SEQUENCE)?);
+        if extension.expect(OID)? != OID_BASIC_CONSTRAINTS {
+            continue;
+        }
+        if extension.peek() == Some(BOOLEAN) {
+            extension.next()?; // critical
+        }
+        let mut constraints = Der::new(extension.expect(OCTET_STRING)?);
+        let mut fields = Der::new(constraints.expect(SEQUENCE)?);
+        return Ok(fields.peek() == Some(BOOLEAN) && fields.expect(BOOLEAN)? == [0xff]);
+    }
+    Ok(false)
+}
+
+/// DER reader over a sequence of TLVs
+struct Der<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Der<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.first().copied()
+    }
+
+    /// Tag, content and the whole TLV
+    fn next(&mut self) -> Result<(u8, &'a [u8], &'a [u8]), VCValidationError> {
+        let truncated = || invalid("truncated DER");
+        let (&tag, rest) = self.bytes.split_first().ok_or_else(truncated)?;
+        let (&first, rest) = rest.split_first().ok_or_else(truncated)?;
+        let (len, rest) = match first {
+            0..=0x7f => (usize::from(first), rest),
+            0x81..=0x84 => {
+                let count = usize::from(first & 0x7f);
+                if rest.len() < count {
+                    return Err(truncated());
+                }
+                let len = rest[..count].iter().fold(0usize, |acc, &b| (acc << 8) | usize::from(b));
+                (len, &rest[count..])
+            }
+            _ => return Err(invalid("unsupported DER length")),
+        };
+        if rest.len() < len {
+            return Err(truncated());
+        }
+        let header = self.bytes.len() - rest.len();
+        let whole = &self.bytes[..header + len];
+        self.bytes = &rest[len..];
+        Ok((tag, &rest[..len], whole))
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<&'a [u8], VCValidationError> {
+        self.expect_with_header(expected).map(|(content, _)| content)
+    }
+
+    fn expect_with_header(&mut self, expected: u8) -> Result<(&'a [u8], &'a [u8]), VCValidationError> {
+        match self.next()? {
+            (tag, content, whole) if tag == expected => Ok((content, whole)),
+            (tag, _, _) => Err(invalid(&format!("expected tag 0x{:02x}, found 0x{:02x}", expected, tag))),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    //! Certificates for tests, signed with P-256 test keys
+
+    use super::*;
+    use crate::ssi::jose::test_keys::TestKey;
+
+    fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+        let mut out = vec![tag];
+        match content.len() {
+            len @ 0..=0x7f => out.push(len as u8),
+            len @ 0x80..=0xff => out.extend_from_slice(&[0x81, len as u8]),
+            len => out.extend_from_slice(&[0x82, (len >> 8) as u8, len as u8]),
+        }
+        out.extend_from_slice(content);
+        out
+    }
+
+    fn integer(bytes: &[u8]) -> Vec<u8> {
+        let trimmed = &bytes[bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len() - 1)..];
+        let mut content = Vec::new();
+        if trimmed[0] & 0x80 != 0 {
+            content.push(0);
+        }
+        content.extend_from_slice(trimmed);
+        tlv(INTEGER, &content)
+    }
+
+    fn name(common_name: &str) -> Vec<u8> {
+        let attribute = [tlv(OID, OID_COMMON_NAME), tlv(0x0c, common_name.as_bytes())].concat();
+        tlv(SEQUENCE, &tlv(SET, &tlv(SEQUENCE, &attribute)))
+    }
+
+    /// Certificate for `subject`'s key, signed by `issuer`
+    pub fn certificate(subject: (&str, &TestKey), issuer: (&str, &TestKey), ca: bool) -> Certificate {
+        let PublicKey::P256 { x, y } = subject.1.public() else {
+            panic!("test certificates hold P-256 keys");
+        };
+        let algorithm = tlv(SEQUENCE, &tlv(OID, OID_ECDSA_SHA256));
+        let spki = tlv(
+            SEQUENCE,
+            &[
+                tlv(SEQUENCE, &[tlv(OID, OID_EC_PUBLIC_KEY), tlv(OID, OID_P256)].concat()),
+                tlv(BIT_STRING, &[&[0, 4][..], &x, &y].concat()),
+            ]
+            .concat(),
+        );
+        let validity = tlv(
+            SEQUENCE,
+            &[tlv(UTC_TIME, b"240101000000Z"), tlv(GENERALIZED_TIME, b"20491231235959Z")].concat(),
+        );
+        let mut tbs = [
+            tlv(VERSION, &integer(&[2])),
+            integer(&[0x42]),
+            algorithm.clone(),
+            name(issuer.0),
+            validity,
+            name(subject.0),
+            spki,
+        ]
+        .concat();
+        if ca {
+            let constraints = tlv(SEQUENCE, &tlv(BOOLEAN, &[0xff]));
+            let extension = tlv(
+                SEQUENCE,
+                &[tlv(OID, OID_BASIC_CONSTRAINTS), tlv(BOOLEAN, &[0xff]), tlv(OCTET_STRING, &constraints)].concat(),
+            );
+            tbs.extend(tlv(EXTENSIONS, &tlv(SEQUENCE, &extension)));
+        }
+        let tbs = tlv(SEQUENCE, &tbs);
+
+        // r || s to an ASN.1 ECDSA-Sig-Value
+        let fixed = issuer.1.sign(&tbs);
+        let signature = tlv(SEQUENCE, &[integer(&fixed[..32]), integer(&fixed[32..])].concat());
+        let der = tlv(SEQUENCE, &[tbs, algorithm, tlv(BIT_STRING, &[&[0][..], &signature].concat())].concat());
+        Certificate::from_der(&der).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::certificate;
+    use super::*;
+    use crate::ssi::encoding::base64_encode;
+    use crate::ssi::jose::test_keys::TestKey;
+
+    #[test]
+    fn test_chain_to_root() {
+        let (root_key, ca_key, leaf_key) = (TestKey::p256(), TestKey::p256(), TestKey::p256());
+        let root = certificate(("IACA Test", &root_key), ("IACA Test", &root_key), true);
+        let intermediate = certificate(("Tussen-CA", &ca_key), ("IACA Test", &root_key), true);
+        let leaf = certificate(("Document Signer", &leaf_key), ("Tussen-CA", &ca_key), false);
+        let now = Utc::now();
+
+        assert_eq!(leaf.common_name().as_deref(), Some("Document Signer"));
+        assert_eq!(leaf.public_key().unwrap(), leaf_key.public());
+        assert!(root.is_ca && !leaf.is_ca);
+
+        verify_chain(&[leaf.clone(), intermediate.clone()], std::slice::from_ref(&root), now).unwrap();
+        // Missing intermediate
+        assert!(verify_chain(std::slice::from_ref(&leaf), std::slice::from_ref(&root), now).is_err());
+        // Outside the validity period
+        let later = "2050-01-01T00:00:00Z".parse().unwrap();
+        assert!(verify_chain(&[leaf.clone(), intermediate.clone()], std::slice::from_ref(&root), later).is_err());
+
+        // A leaf cannot issue further certificates
+        let rogue_key = TestKey::p256();
+        let rogue = certificate(("Rogue", &rogue_key), ("Document Signer", &leaf_key), false);
+        assert!(verify_chain(&[rogue, leaf.clone(), intermediate], std::slice::from_ref(&root), now).is_err());
+
+        let pem = format!(
+            "-----BEGIN CERTIFICATE-----\n{}\n-----END CERTIFICATE-----\n",
+            base64_encode(root.der())
+        );
+        assert_eq!(Certificate::from_pem(&pem).unwrap()[0].fingerprint(), root.fingerprint());
+    }
+}
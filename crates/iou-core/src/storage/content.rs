@@ -0,0 +1,69 @@
+//! Content addressing shared by the local storage backends
+//!
+//! Blobs are stored once under the SHA-256 of their content; keys (as used
+//! by [`StorageBackend`](super::StorageBackend)) are references to a blob.
+//! Uploading the same content under several keys stores it only once.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::S3Error;
+
+/// Hex-encoded SHA-256 of a blob, the address it is stored under
+pub fn content_address(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// Reference from a key to a stored blob
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobRef {
+    pub sha256: String,
+    pub size: u64,
+    pub content_type: String,
+}
+
+impl BlobRef {
+    pub fn new(data: &[u8], content_type: &str) -> Self {
+        Self {
+            sha256: content_address(data),
+            size: data.len() as u64,
+            content_type: content_type.to_string(),
+        }
+    }
+}
+
+/// Reject keys that cannot be mapped safely onto a path
+///
+/// Keys are `/`-separated like S3 keys, but empty, `.` and `..` segments,
+/// backslashes and NUL bytes are not allowed.
+pub fn validate_key(key: &str) -> Result<(), S3Error> {
+    let invalid = key.is_empty()
+        || key.contains(['\\', '\0'])
+        || key.split('/').any(|segment| matches!(segment, "" | "." | ".."));
+    if invalid {
+        return Err(S3Error::InvalidKey(key.to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_address() {
+        assert_eq!(
+            content_address(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn test_validate_key() {
+        assert!(validate_key("documents/abc/versions/v1").is_ok());
+        assert!(validate_key("documents/abc/v1.pipeline.json").is_ok());
+        for key in ["", "/abs", "a//b", "a/../b", "./a", "a/", "a\\b"] {
+            assert!(matches!(validate_key(key), Err(S3Error::InvalidKey(_))), "{}", key);
+        }
+    }
+}
@@ -0,0 +1,330 @@
+//! Content-addressed storage on the local filesystem
+//!
+//! For single-node deployments without MinIO. Layout under the root:
+//!
+//! ```text
+//! blobs/ab/abcdef…   content, named by its SHA-256
+//! refs/<key>.ref     JSON `BlobRef` per key
+//! tmp/               staging area for atomic writes
+//! ```
+//!
+//! Every file is written to `tmp/`, fsynced and renamed into place, after
+//! which the target directory is fsynced, so a crash never leaves a
+//! half-written blob or reference behind. Content is verified against its
+//! address on every download.
+
+use parking_lot::RwLock;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::content::{content_address, validate_key, BlobRef};
+use super::{S3Error, StorageBackend};
+
+const REF_SUFFIX: &str = ".ref";
+
+/// Content-addressed [`StorageBackend`] in a local directory
+#[derive(Clone)]
+pub struct FilesystemStorage {
+    root: Arc<PathBuf>,
+    /// Uploads share the lock; deletes and garbage collection take it
+    /// exclusively so a blob is never removed while a reference to it is
+    /// being written.
+    lock: Arc<RwLock<()>>,
+}
+
+impl FilesystemStorage {
+    /// Open (and create if needed) a store at `root`
+    pub fn new(root: impl Into<PathBuf>) -> Result<Self, S3Error> {
+        let root = root.into();
+        for dir in ["blobs", "refs", "tmp"] {
+            fs::create_dir_all(root.join(dir)).map_err(|e| {
+                S3Error::InvalidConfig(format!("Cannot create storage directory {}: {}", root.display(), e))
+            })?;
+        }
+        Ok(Self {
+            root: Arc::new(root),
+            lock: Arc::new(RwLock::new(())),
+        })
+    }
+
+    /// Root directory of the store
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Blob reference for a key
+    pub async fn stat(&self, key: &str) -> Result<Option<BlobRef>, S3Error> {
+        validate_key(key)?;
+        let this = self.clone();
+        let key = key.to_string();
+        blocking(move || match this.read_ref(&key) {
+            Ok(blob) => Ok(Some(blob)),
+            Err(S3Error::NotFound(_)) => Ok(None),
+            Err(e) => Err(e),
+        })
+        .await
+    }
+
+    /// Keys starting with `prefix`, sorted
+    pub async fn list(&self, prefix: &str) -> Result<Vec<String>, S3Error> {
+        let this = self.clone();
+        let prefix = prefix.to_string();
+        blocking(move || {
+            let mut keys: Vec<String> = this
+                .all_refs()?
+                .into_iter()
+                .map(|(key, _)| key)
+                .filter(|key| key.starts_with(&prefix))
+                .collect();
+            keys.sort();
+            Ok(keys)
+        })
+        .await
+    }
+
+    /// Remove blobs no key refers to and leftovers from interrupted writes
+    ///
+    /// Returns the number of blobs removed. Overwriting a key leaves the
+    /// previous blob in place until this runs.
+    pub async fn collect_garbage(&self) -> Result<usize, S3Error> {
+        let this = self.clone();
+        blocking(move || {
+            let _guard = this.lock.write();
+            let referenced: std::collections::HashSet<String> =
+                this.all_refs()?.into_iter().map(|(_, blob)| blob.sha256).collect();
+
+            let mut removed = 0;
+            for path in files(&this.root.join("blobs")).map_err(|e| io_error("blobs", e))? {
+                let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+                if !referenced.contains(name) {
+                    fs::remove_file(&path).map_err(|e| io_error(name, e))?;
+                    removed += 1;
+                }
+            }
+            for path in files(&this.root.join("tmp")).map_err(|e| io_error("tmp", e))? {
+                let _ = fs::remove_file(path);
+            }
+            Ok(removed)
+        })
+        .await
+    }
+
+    fn blob_path(&self, sha256: &str) -> PathBuf {
+        self.root.join("blobs").join(&sha256[..2]).join(sha256)
+    }
+
+    fn ref_path(&self, key: &str) -> PathBuf {
+        self.root.join("refs").join(format!("{}{}", key, REF_SUFFIX))
+    }
+
+    fn read_ref(&self, key: &str) -> Result<BlobRef, S3Error> {
+        let raw = match fs::read(self.ref_path(key)) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Err(S3Error::NotFound(key.to_string())),
+            Err(e) => return Err(S3Error::DownloadFailed(format!("{}: {}", key, e))),
+        };
+        serde_json::from_slice(&raw).map_err(|e| S3Error::DownloadFailed(format!("{}: corrupt reference: {}", key, e)))
+    }
+
+    /// Every key with its reference
+    fn all_refs(&self) -> Result<Vec<(String, BlobRef)>, S3Error> {
+        let refs = self.root.join("refs");
+        let mut found = Vec::new();
+        for path in files(&refs).map_err(|e| io_error("refs", e))? {
+            let Some(key) = path
+                .strip_prefix(&refs)
+                .ok()
+                .and_then(|p| p.to_str())
+                .and_then(|p| p.strip_suffix(REF_SUFFIX))
+                .map(|p| p.replace(std::path::MAIN_SEPARATOR, "/"))
+            else {
+                continue;
+            };
+            let blob = self.read_ref(&key)?;
+            found.push((key, blob));
+        }
+        Ok(found)
+    }
+
+    /// Write `data` to `target` via a fsynced temporary file and rename
+    fn write_atomic(&self, target: &Path, data: &[u8]) -> io::Result<()> {
+        let dir = target.parent().expect("storage paths have a parent");
+        fs::create_dir_all(dir)?;
+
+        let tmp = self.root.join("tmp").join(uuid::Uuid::new_v4().to_string());
+        let written = (|| {
+            let mut file = File::create(&tmp)?;
+            file.write_all(data)?;
+            file.sync_all()?;
+            fs::rename(&tmp, target)
+        })();
+        if written.is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+        written?;
+        sync_dir(dir)
+    }
+}
+
+#[async_trait::async_trait]
+impl StorageBackend for FilesystemStorage {
+    async fn upload(&self, key: &str, data: Vec<u8>, content_type: &str) -> Result<(), S3Error> {
+        validate_key(key)?;
+        let blob = BlobRef::new(&data, content_type);
+        let this = self.clone();
+        let key = key.to_string();
+        blocking(move || {
+            let _guard = this.lock.read();
+            let failed = |e: io::Error| S3Error::UploadFailed(format!("{}: {}", key, e));
+
+            let blob_path = this.blob_path(&blob.sha256);
+            if !blob_path.exists() {
+                this.write_atomic(&blob_path, &data).map_err(failed)?;
+            }
+            let reference = serde_json::to_vec(&blob).map_err(|e| S3Error::UploadFailed(e.to_string()))?;
+            this.write_atomic(&this.ref_path(&key), &reference).map_err(failed)?;
+
+            tracing::debug!("Stored {} as blob {} ({} bytes)", key, blob.sha256, blob.size);
+            Ok(())
+        })
+        .await
+    }
+
+    async fn download(&self, key: &str) -> Result<Vec<u8>, S3Error> {
+        validate_key(key)?;
+        let this = self.clone();
+        let key = key.to_string();
+        blocking(move || {
+            let blob = this.read_ref(&key)?;
+            let data = fs::read(this.blob_path(&blob.sha256))
+                .map_err(|e| S3Error::DownloadFailed(format!("{}: blob {}: {}", key, blob.sha256, e)))?;
+            if content_address(&data) != blob.sha256 {
+                return Err(S3Error::DownloadFailed(format!("{}: checksum mismatch", key)));
+            }
+            Ok(data)
+        })
+        .await
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, S3Error> {
+        validate_key(key)?;
+        Ok(self.ref_path(key).is_file())
+    }
+
+    /// Removes the key and, if no other key shares it, the content itself
+    async fn delete(&self, key: &str) -> Result<(), S3Error> {
+        validate_key(key)?;
+        let this = self.clone();
+        let key = key.to_string();
+        blocking(move || {
+            let _guard = this.lock.write();
+            let blob = match this.read_ref(&key) {
+                Ok(blob) => blob,
+                Err(S3Error::NotFound(_)) => return Ok(()),
+                Err(e) => return Err(e),
+            };
+            let failed = |e: io::Error| S3Error::S3Error(format!("delete {}: {}", key, e));
+
+            let ref_path = this.ref_path(&key);
+            fs::remove_file(&ref_path).map_err(failed)?;
+            sync_dir(ref_path.parent().expect("storage paths have a parent")).map_err(failed)?;
+
+            if !this.all_refs()?.iter().any(|(_, other)| other.sha256 == blob.sha256) {
+                let blob_path = this.blob_path(&blob.sha256);
+                match fs::remove_file(&blob_path) {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                    Err(e) => return Err(failed(e)),
+                }
+                sync_dir(blob_path.parent().expect("storage paths have a parent")).map_err(failed)?;
+            }
+            Ok(())
+        })
+        .await
+    }
+}
+
+async fn blocking<T, F>(f: F) -> Result<T, S3Error>
+where
+    F: FnOnce() -> Result<T, S3Error> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| S3Error::S3Error(format!("storage task failed: {}", e)))?
+}
+
+fn io_error(context: &str, e: io::Error) -> S3Error {
+    S3Error::S3Error(format!("{}: {}", context, e))
+}
+
+/// All regular files below `dir`, recursively
+fn files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut found = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                pending.push(entry.path());
+            } else if file_type.is_file() {
+                found.push(entry.path());
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// Persist a rename or unlink in `dir`
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        File::open(dir)?.sync_all()
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = dir;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_blob_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FilesystemStorage::new(dir.path()).unwrap();
+        storage.upload("documents/a/v1", b"# A".to_vec(), "text/markdown").await.unwrap();
+        let blob = storage.stat("documents/a/v1").await.unwrap().unwrap();
+        assert_eq!(blob.sha256, content_address(b"# A"));
+        assert_eq!(blob.size, 3);
+        assert!(dir.path().join("blobs").join(&blob.sha256[..2]).join(&blob.sha256).is_file());
+        assert!(dir.path().join("refs/documents/a/v1.ref").is_file());
+        assert!(files(&dir.path().join("tmp")).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_corruption_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FilesystemStorage::new(dir.path()).unwrap();
+        storage.upload("a", b"inhoud".to_vec(), "text/plain").await.unwrap();
+        let blob = storage.stat("a").await.unwrap().unwrap();
+        fs::write(storage.blob_path(&blob.sha256), b"gewijzigd").unwrap();
+
+        let result = storage.download("a").await;
+        assert!(matches!(result, Err(S3Error::DownloadFailed(msg)) if msg.contains("checksum")));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unsafe_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FilesystemStorage::new(dir.path()).unwrap();
+        let result = storage.upload("../buiten", b"x".to_vec(), "text/plain").await;
+        assert!(matches!(result, Err(S3Error::InvalidKey(_))));
+    }
+}
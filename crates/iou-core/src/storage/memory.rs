@@ -0,0 +1,132 @@
+//! In-memory content-addressed storage
+//!
+//! For tests and ephemeral deployments. Blobs are reference counted and
+//! dropped as soon as the last key pointing at them is deleted or
+//! overwritten.
+
+use parking_lot::RwLock;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use super::content::{validate_key, BlobRef};
+use super::{S3Error, StorageBackend};
+
+#[derive(Default)]
+struct State {
+    refs: BTreeMap<String, BlobRef>,
+    blobs: HashMap<String, (Arc<Vec<u8>>, usize)>,
+}
+
+impl State {
+    fn release(&mut self, sha256: &str) {
+        if let Some((_, count)) = self.blobs.get_mut(sha256) {
+            *count -= 1;
+            if *count == 0 {
+                self.blobs.remove(sha256);
+            }
+        }
+    }
+}
+
+/// Content-addressed [`StorageBackend`] held in memory
+///
+/// Cloning is cheap and clones share the same store.
+#[derive(Clone, Default)]
+pub struct MemoryStorage {
+    state: Arc<RwLock<State>>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Blob reference for a key
+    pub fn stat(&self, key: &str) -> Option<BlobRef> {
+        self.state.read().refs.get(key).cloned()
+    }
+
+    /// Keys starting with `prefix`, sorted
+    pub fn list(&self, prefix: &str) -> Vec<String> {
+        self.state
+            .read()
+            .refs
+            .range(prefix.to_string()..)
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    /// Number of distinct blobs stored
+    pub fn blob_count(&self) -> usize {
+        self.state.read().blobs.len()
+    }
+}
+
+#[async_trait::async_trait]
+impl StorageBackend for MemoryStorage {
+    async fn upload(&self, key: &str, data: Vec<u8>, content_type: &str) -> Result<(), S3Error> {
+        validate_key(key)?;
+        let blob = BlobRef::new(&data, content_type);
+
+        let mut state = self.state.write();
+        state
+            .blobs
+            .entry(blob.sha256.clone())
+            .or_insert_with(|| (Arc::new(data), 0))
+            .1 += 1;
+        if let Some(previous) = state.refs.insert(key.to_string(), blob) {
+            state.release(&previous.sha256);
+        }
+        Ok(())
+    }
+
+    async fn download(&self, key: &str) -> Result<Vec<u8>, S3Error> {
+        let state = self.state.read();
+        let blob = state
+            .refs
+            .get(key)
+            .ok_or_else(|| S3Error::NotFound(key.to_string()))?;
+        let (data, _) = state
+            .blobs
+            .get(&blob.sha256)
+            .ok_or_else(|| S3Error::DownloadFailed(format!("{}: blob {} missing", key, blob.sha256)))?;
+        Ok(data.as_ref().clone())
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, S3Error> {
+        Ok(self.state.read().refs.contains_key(key))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), S3Error> {
+        let mut state = self.state.write();
+        if let Some(blob) = state.refs.remove(key) {
+            state.release(&blob.sha256);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_deduplication_and_release() {
+        let storage = MemoryStorage::new();
+        storage.upload("a/v1", b"inhoud".to_vec(), "text/markdown").await.unwrap();
+        storage.upload("b/v1", b"inhoud".to_vec(), "text/markdown").await.unwrap();
+        assert_eq!(storage.blob_count(), 1);
+        assert_eq!(storage.stat("a/v1"), storage.stat("b/v1"));
+
+        // Overwriting one key keeps the shared blob for the other
+        storage.upload("a/v1", b"nieuw".to_vec(), "text/markdown").await.unwrap();
+        assert_eq!(storage.blob_count(), 2);
+        assert_eq!(storage.download("b/v1").await.unwrap(), b"inhoud");
+
+        storage.delete("b/v1").await.unwrap();
+        assert_eq!(storage.blob_count(), 1);
+        assert!(matches!(storage.download("b/v1").await, Err(S3Error::NotFound(_))));
+        assert_eq!(storage.list("a/"), vec!["a/v1".to_string()]);
+    }
+}
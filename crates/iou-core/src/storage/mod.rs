@@ -1,8 +1,25 @@
 //! Storage abstraction layer for S3/MinIO integration
 //!
 //! This module provides a unified interface for document storage operations,
-//! supporting S3-compatible backends including AWS S3 and MinIO.
+//! supporting S3-compatible backends including AWS S3 and MinIO, plus
+//! content-addressed backends on the local filesystem and in memory for
+//! single-node deployments and tests.
 
+pub mod content;
+pub mod filesystem;
+pub mod memory;
 pub mod s3;
 
-pub use s3::{S3Client, S3Config, S3Error};
+pub use content::{content_address, BlobRef};
+pub use filesystem::FilesystemStorage;
+pub use memory::MemoryStorage;
+pub use s3::{S3Client, S3Config, S3Error, S3Object};
+
+/// Storage backend abstraction used by the document and version services
+#[async_trait::async_trait]
+pub trait StorageBackend: Send + Sync {
+    async fn upload(&self, key: &str, data: Vec<u8>, content_type: &str) -> Result<(), S3Error>;
+    async fn download(&self, key: &str) -> Result<Vec<u8>, S3Error>;
+    async fn exists(&self, key: &str) -> Result<bool, S3Error>;
+    async fn delete(&self, key: &str) -> Result<(), S3Error>;
+}
@@ -1,19 +1,41 @@
-//! S3/MinIO client implementation with streaming support
+//! S3/MinIO client implementation
 //!
 //! Provides:
 //! - Client initialization from environment variables
 //! - Connectivity validation at startup
+//! - SigV4-signed requests over plain HTTP(S), no AWS SDK required
 //! - Path-style URL support for MinIO compatibility
-//! - 10MB size limit enforcement
-//!
-//! Note: Full S3 integration pending rust-s3 compatibility with Rust 2024 edition.
+//! - Multipart upload for large objects, ranged downloads and listing by prefix
+//! - SHA-256 checksums verified by the server on upload and by the client on download
+//! - 10MB size limit enforcement for documents
+
+mod sigv4;
+mod xml;
 
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{DateTime, Utc};
+use reqwest::{Method, StatusCode, Url};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::env;
+use std::ops::{Bound, RangeBounds};
+
+use super::StorageBackend;
+use sigv4::Signer;
 
 /// Maximum document size (10MB)
 pub const MAX_DOCUMENT_SIZE: usize = 10 * 1024 * 1024;
 
+/// Objects larger than this are uploaded in parts
+pub const MULTIPART_THRESHOLD: usize = 8 * 1024 * 1024;
+
+/// Part size for multipart uploads (the S3 minimum)
+pub const MULTIPART_PART_SIZE: usize = 5 * 1024 * 1024;
+
+const CHECKSUM_HEADER: &str = "x-amz-checksum-sha256";
+
 /// S3 client configuration loaded from environment
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct S3Config {
@@ -55,22 +77,37 @@ pub enum S3Error {
 
     #[error("HTTP error {code}: {message}")]
     HttpError { code: u16, message: String },
+
+    #[error("Invalid key: {0}")]
+    InvalidKey(String),
+}
+
+/// Object returned by [`S3Client::list`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct S3Object {
+    pub key: String,
+    pub size: u64,
+    pub etag: Option<String>,
+    pub last_modified: Option<DateTime<Utc>>,
 }
 
-/// S3 client wrapper with validation support
-///
-/// Stub implementation for Section 4. Full S3 operations will be
-/// implemented once rust-s3 is verified compatible with Rust 2024.
+/// S3 client for AWS S3 and S3-compatible stores (MinIO)
 pub struct S3Client {
     config: S3Config,
+    http: reqwest::Client,
 }
 
 impl S3Client {
     /// Create a new S3 client from environment variables
+    ///
+    /// Credentials are read from `S3_ACCESS_KEY`/`S3_SECRET_KEY`, falling
+    /// back to `S3_ACCESS_KEY_ID`/`S3_SECRET_ACCESS_KEY` as used in `.env.example`.
     pub fn new_from_env() -> Result<Self, S3Error> {
         let access_key = env::var("S3_ACCESS_KEY")
+            .or_else(|_| env::var("S3_ACCESS_KEY_ID"))
             .map_err(|_| S3Error::MissingEnvVar("S3_ACCESS_KEY".to_string()))?;
         let secret_key = env::var("S3_SECRET_KEY")
+            .or_else(|_| env::var("S3_SECRET_ACCESS_KEY"))
             .map_err(|_| S3Error::MissingEnvVar("S3_SECRET_KEY".to_string()))?;
         let bucket_name = env::var("S3_BUCKET")
             .map_err(|_| S3Error::MissingEnvVar("S3_BUCKET".to_string()))?;
@@ -90,25 +127,42 @@ impl S3Client {
             path_style,
         };
 
-        Ok(Self { config })
+        Self::with_config(config)
     }
 
     /// Create S3 client with explicit config
     pub fn with_config(config: S3Config) -> Result<Self, S3Error> {
-        Ok(Self { config })
+        let http = reqwest::Client::builder()
+            .connect_timeout(std::time::Duration::from_secs(10))
+            .build()
+            .map_err(|e| S3Error::InvalidConfig(e.to_string()))?;
+        Ok(Self { config, http })
     }
 
     /// Validate S3 connectivity
     ///
-    /// Stub implementation - always succeeds for now.
+    /// Checks that the endpoint is reachable, the credentials are accepted
+    /// and the bucket exists.
     pub async fn validate(&self) -> Result<(), S3Error> {
-        // TODO: Implement actual S3 connectivity check
-        Ok(())
+        let response = self.send(S3Request::new(Method::HEAD, None)).await?;
+
+        match response.status() {
+            status if status.is_success() => Ok(()),
+            StatusCode::NOT_FOUND => Err(S3Error::InvalidConfig(format!(
+                "Bucket {} does not exist",
+                self.config.bucket
+            ))),
+            status => Err(S3Error::HttpError {
+                code: status.as_u16(),
+                message: format!("Bucket {} is not accessible", self.config.bucket),
+            }),
+        }
     }
 
     /// Upload document data to S3
     ///
-    /// Stub implementation - stores data in memory for now.
+    /// Documents are limited to [`MAX_DOCUMENT_SIZE`]; use [`S3Client::put_object`]
+    /// for other artifacts.
     pub async fn upload(&self, key: &str, data: Vec<u8>, content_type: &str) -> Result<(), S3Error> {
         if data.len() > MAX_DOCUMENT_SIZE {
             return Err(S3Error::PayloadTooLarge {
@@ -117,32 +171,163 @@ impl S3Client {
             });
         }
 
-        // TODO: Implement actual S3 upload
-        tracing::debug!("S3 upload stub: {} ({} bytes, {})", key, data.len(), content_type);
+        self.put_object(key, data, content_type).await
+    }
+
+    /// Store an object of any size
+    ///
+    /// Objects above [`MULTIPART_THRESHOLD`] are uploaded in parts. The
+    /// SHA-256 checksum is sent along so the server rejects corrupted data.
+    pub async fn put_object(&self, key: &str, data: Vec<u8>, content_type: &str) -> Result<(), S3Error> {
+        if data.len() > MULTIPART_THRESHOLD {
+            return self.upload_multipart(key, &data, content_type).await;
+        }
+
+        let size = data.len();
+        let request = S3Request::new(Method::PUT, Some(key))
+            .header("content-type", content_type)
+            .header(CHECKSUM_HEADER, &checksum(&data))
+            .body(data);
+        let response = self.send(request).await?;
+        check(response, key).await?;
+
+        tracing::debug!("S3 upload: {} ({} bytes, {})", key, size, content_type);
         Ok(())
     }
 
     /// Download document from S3
     ///
-    /// Stub implementation - returns error for now.
+    /// The content is checked against the SHA-256 checksum stored with the
+    /// object when the server returns one.
     pub async fn download(&self, key: &str) -> Result<Vec<u8>, S3Error> {
-        // TODO: Implement actual S3 download
-        Err(S3Error::NotFound(format!("S3 download not implemented: {}", key)))
+        let request = S3Request::new(Method::GET, Some(key)).header("x-amz-checksum-mode", "ENABLED");
+        let response = self.send(request).await?;
+        let response = check(response, key).await?;
+
+        // Multipart objects carry a checksum of part checksums, which cannot
+        // be compared with the content directly
+        let expected = response
+            .headers()
+            .get(CHECKSUM_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| !v.contains('-'))
+            .map(str::to_string);
+        let composite = response
+            .headers()
+            .get("x-amz-checksum-type")
+            .is_some_and(|v| v.as_bytes().eq_ignore_ascii_case(b"COMPOSITE"));
+
+        let data = response
+            .bytes()
+            .await
+            .map_err(|e| S3Error::DownloadFailed(format!("{}: {}", key, e)))?
+            .to_vec();
+
+        if let Some(expected) = expected
+            && !composite
+            && checksum(&data) != expected
+        {
+            return Err(S3Error::DownloadFailed(format!("{}: checksum mismatch", key)));
+        }
+        Ok(data)
+    }
+
+    /// Download part of an object
+    ///
+    /// `range` is in bytes, e.g. `0..1024` or `1024..` for the remainder.
+    pub async fn download_range(&self, key: &str, range: impl RangeBounds<u64>) -> Result<Vec<u8>, S3Error> {
+        let start = match range.start_bound() {
+            Bound::Included(&s) => s,
+            Bound::Excluded(&s) => s + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&e) => Some(e),
+            Bound::Excluded(&0) => return Ok(Vec::new()),
+            Bound::Excluded(&e) => Some(e - 1),
+            Bound::Unbounded => None,
+        };
+        if end.is_some_and(|e| e < start) {
+            return Ok(Vec::new());
+        }
+
+        let header = match end {
+            Some(end) => format!("bytes={}-{}", start, end),
+            None => format!("bytes={}-", start),
+        };
+        let response = self.send(S3Request::new(Method::GET, Some(key)).header("range", &header)).await?;
+        let response = check(response, key).await?;
+
+        response
+            .bytes()
+            .await
+            .map(|b| b.to_vec())
+            .map_err(|e| S3Error::DownloadFailed(format!("{}: {}", key, e)))
     }
 
     /// Check if object exists in S3
     pub async fn exists(&self, key: &str) -> Result<bool, S3Error> {
-        // TODO: Implement actual S3 existence check
-        Ok(false)
+        let response = self.send(S3Request::new(Method::HEAD, Some(key))).await?;
+        match check(response, key).await {
+            Ok(_) => Ok(true),
+            Err(S3Error::NotFound(_)) => Ok(false),
+            Err(e) => Err(e),
+        }
     }
 
     /// Delete object from S3
+    ///
+    /// Deleting a key that does not exist succeeds, as in S3 itself.
     pub async fn delete(&self, key: &str) -> Result<(), S3Error> {
-        // TODO: Implement actual S3 delete
-        tracing::debug!("S3 delete stub: {}", key);
+        let response = self.send(S3Request::new(Method::DELETE, Some(key))).await?;
+        check(response, key).await?;
+        tracing::debug!("S3 delete: {}", key);
         Ok(())
     }
 
+    /// List all objects whose key starts with `prefix`
+    ///
+    /// Follows continuation tokens until the listing is complete.
+    pub async fn list(&self, prefix: &str) -> Result<Vec<S3Object>, S3Error> {
+        let mut objects = Vec::new();
+        let mut continuation: Option<String> = None;
+
+        loop {
+            let mut request = S3Request::new(Method::GET, None)
+                .query("list-type", "2")
+                .query("prefix", prefix);
+            if let Some(token) = &continuation {
+                request = request.query("continuation-token", token);
+            }
+            let response = self.send(request).await?;
+            let body = text(check(response, prefix).await?).await?;
+
+            for entry in xml::blocks(&body, "Contents") {
+                let Some(key) = xml::text(entry, "Key") else {
+                    continue;
+                };
+                objects.push(S3Object {
+                    key,
+                    size: xml::text(entry, "Size").and_then(|s| s.parse().ok()).unwrap_or(0),
+                    etag: xml::text(entry, "ETag").map(|e| e.trim_matches('"').to_string()),
+                    last_modified: xml::text(entry, "LastModified")
+                        .and_then(|t| DateTime::parse_from_rfc3339(&t).ok())
+                        .map(|t| t.with_timezone(&Utc)),
+                });
+            }
+
+            continuation = match xml::text(&body, "IsTruncated").as_deref() {
+                Some("true") => xml::text(&body, "NextContinuationToken"),
+                _ => None,
+            };
+            if continuation.is_none() {
+                break;
+            }
+        }
+
+        Ok(objects)
+    }
+
     /// Get the bucket name
     pub fn bucket_name(&self) -> &str {
         &self.config.bucket
@@ -152,12 +337,286 @@ impl S3Client {
     pub fn config(&self) -> &S3Config {
         &self.config
     }
+
+    async fn upload_multipart(&self, key: &str, data: &[u8], content_type: &str) -> Result<(), S3Error> {
+        let failed = |e: &dyn std::fmt::Display| S3Error::UploadFailed(format!("{}: {}", key, e));
+
+        let request = S3Request::new(Method::POST, Some(key))
+            .query("uploads", "")
+            .header("content-type", content_type)
+            .header("x-amz-checksum-algorithm", "SHA256");
+        let response = self.send(request).await?;
+        let body = text(check(response, key).await?).await?;
+        let upload_id = xml::text(&body, "UploadId").ok_or_else(|| failed(&"no UploadId in response"))?;
+
+        let mut parts = Vec::new();
+        for (index, chunk) in data.chunks(MULTIPART_PART_SIZE).enumerate() {
+            match self.upload_part(key, &upload_id, index + 1, chunk).await {
+                Ok(part) => parts.push(part),
+                Err(e) => {
+                    self.abort_multipart(key, &upload_id).await;
+                    return Err(e);
+                }
+            }
+        }
+
+        let mut manifest = String::from("<CompleteMultipartUpload>");
+        for (number, etag, sum) in &parts {
+            manifest.push_str(&format!(
+                "<Part><PartNumber>{}</PartNumber><ETag>{}</ETag><ChecksumSHA256>{}</ChecksumSHA256></Part>",
+                number,
+                xml::escape(etag),
+                sum
+            ));
+        }
+        manifest.push_str("</CompleteMultipartUpload>");
+
+        let request = S3Request::new(Method::POST, Some(key))
+            .query("uploadId", &upload_id)
+            .header("content-type", "application/xml")
+            .body(manifest.into_bytes());
+        let response = match self.send(request).await {
+            Ok(response) => response,
+            Err(e) => {
+                self.abort_multipart(key, &upload_id).await;
+                return Err(e);
+            }
+        };
+        // CompleteMultipartUpload can report failure in a 200 response
+        let body = text(check(response, key).await?).await?;
+        if body.contains("<Error>") {
+            let message = xml::text(&body, "Message").unwrap_or(body);
+            return Err(failed(&message));
+        }
+
+        tracing::debug!(
+            "S3 multipart upload: {} ({} bytes in {} parts, {})",
+            key,
+            data.len(),
+            parts.len(),
+            content_type
+        );
+        Ok(())
+    }
+
+    /// Upload one part; returns its number, ETag and checksum
+    async fn upload_part(
+        &self,
+        key: &str,
+        upload_id: &str,
+        number: usize,
+        chunk: &[u8],
+    ) -> Result<(usize, String, String), S3Error> {
+        let sum = checksum(chunk);
+        let request = S3Request::new(Method::PUT, Some(key))
+            .query("partNumber", &number.to_string())
+            .query("uploadId", upload_id)
+            .header(CHECKSUM_HEADER, &sum)
+            .body(chunk.to_vec());
+        let response = self.send(request).await?;
+        let response = check(response, key).await?;
+
+        let etag = response
+            .headers()
+            .get("etag")
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| S3Error::UploadFailed(format!("{} part {}: no ETag", key, number)))?
+            .to_string();
+        Ok((number, etag, sum))
+    }
+
+    /// Best effort: leftover parts are cleaned up by bucket lifecycle rules otherwise
+    async fn abort_multipart(&self, key: &str, upload_id: &str) {
+        let request = S3Request::new(Method::DELETE, Some(key)).query("uploadId", upload_id);
+        if let Err(e) = self.send(request).await {
+            tracing::warn!("Failed to abort multipart upload {} for {}: {}", upload_id, key, e);
+        }
+    }
+
+    /// Sign and send a request
+    async fn send(&self, request: S3Request<'_>) -> Result<reqwest::Response, S3Error> {
+        let (url, host, path) = self.target(request.key)?;
+        let now = Utc::now();
+        let hash = sigv4::payload_hash(&request.body);
+
+        let mut headers = request.headers;
+        headers.insert("host".to_string(), host);
+        headers.insert("x-amz-content-sha256".to_string(), hash.clone());
+        headers.insert("x-amz-date".to_string(), now.format("%Y%m%dT%H%M%SZ").to_string());
+
+        let signer = Signer {
+            access_key: &self.config.access_key,
+            secret_key: &self.config.secret_key,
+            region: &self.config.region,
+        };
+        let authorization = signer.authorization(
+            request.method.as_str(),
+            &path,
+            &request.query,
+            &headers,
+            &hash,
+            now,
+        );
+
+        let mut url = url;
+        if !request.query.is_empty() {
+            url.push('?');
+            url.push_str(&sigv4::canonical_query(&request.query));
+        }
+
+        let mut builder = self
+            .http
+            .request(request.method, url)
+            .header("authorization", authorization);
+        for (name, value) in &headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+
+        builder
+            .body(request.body)
+            .send()
+            .await
+            .map_err(|e| S3Error::ConnectionFailed(e.to_string()))
+    }
+
+    /// URL (without query), host header and canonical path for a key
+    fn target(&self, key: Option<&str>) -> Result<(String, String, String), S3Error> {
+        let raw = self
+            .config
+            .endpoint
+            .clone()
+            .unwrap_or_else(|| format!("https://s3.{}.amazonaws.com", self.config.region));
+        let endpoint =
+            Url::parse(&raw).map_err(|e| S3Error::InvalidConfig(format!("S3 endpoint {}: {}", raw, e)))?;
+        let domain = endpoint
+            .host_str()
+            .ok_or_else(|| S3Error::InvalidConfig(format!("S3 endpoint {} has no host", raw)))?;
+        let authority = match endpoint.port() {
+            Some(port) => format!("{}:{}", domain, port),
+            None => domain.to_string(),
+        };
+
+        let object = key.map(|k| sigv4::uri_encode(k, true)).unwrap_or_default();
+        let (host, path) = if self.config.path_style {
+            let base = endpoint.path().trim_end_matches('/');
+            let mut path = format!("{}/{}", base, sigv4::uri_encode(&self.config.bucket, false));
+            if key.is_some() {
+                path = format!("{}/{}", path, object);
+            }
+            (authority, path)
+        } else {
+            (format!("{}.{}", self.config.bucket, authority), format!("/{}", object))
+        };
+
+        let url = format!("{}://{}{}", endpoint.scheme(), host, path);
+        Ok((url, host, path))
+    }
+}
+
+#[async_trait::async_trait]
+impl StorageBackend for S3Client {
+    async fn upload(&self, key: &str, data: Vec<u8>, content_type: &str) -> Result<(), S3Error> {
+        S3Client::upload(self, key, data, content_type).await
+    }
+
+    async fn download(&self, key: &str) -> Result<Vec<u8>, S3Error> {
+        S3Client::download(self, key).await
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, S3Error> {
+        S3Client::exists(self, key).await
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), S3Error> {
+        S3Client::delete(self, key).await
+    }
+}
+
+/// Request before signing
+struct S3Request<'a> {
+    method: Method,
+    key: Option<&'a str>,
+    query: Vec<(String, String)>,
+    headers: BTreeMap<String, String>,
+    body: Vec<u8>,
+}
+
+impl<'a> S3Request<'a> {
+    fn new(method: Method, key: Option<&'a str>) -> Self {
+        Self {
+            method,
+            key,
+            query: Vec::new(),
+            headers: BTreeMap::new(),
+            body: Vec::new(),
+        }
+    }
+
+    fn query(mut self, name: &str, value: &str) -> Self {
+        self.query.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.insert(name.to_ascii_lowercase(), value.to_string());
+        self
+    }
+
+    fn body(mut self, body: Vec<u8>) -> Self {
+        self.body = body;
+        self
+    }
+}
+
+/// Base64-encoded SHA-256, the format of `x-amz-checksum-sha256`
+fn checksum(data: &[u8]) -> String {
+    BASE64.encode(Sha256::digest(data))
+}
+
+/// Map non-success responses to errors, using the S3 error body when present
+async fn check(response: reqwest::Response, key: &str) -> Result<reqwest::Response, S3Error> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    if status == StatusCode::NOT_FOUND {
+        return Err(S3Error::NotFound(key.to_string()));
+    }
+
+    let body = response.text().await.unwrap_or_default();
+    let message = match (xml::text(&body, "Code"), xml::text(&body, "Message")) {
+        (Some(code), Some(message)) => format!("{}: {}", code, message),
+        (Some(code), None) => code,
+        _ => status.canonical_reason().unwrap_or("unknown error").to_string(),
+    };
+    Err(S3Error::HttpError {
+        code: status.as_u16(),
+        message,
+    })
+}
+
+async fn text(response: reqwest::Response) -> Result<String, S3Error> {
+    response
+        .text()
+        .await
+        .map_err(|e| S3Error::S3Error(e.to_string()))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn config(endpoint: Option<&str>, path_style: bool) -> S3Config {
+        S3Config {
+            access_key: "test".to_string(),
+            secret_key: "test".to_string(),
+            bucket: "iou-documents".to_string(),
+            endpoint: endpoint.map(str::to_string),
+            region: "eu-central-1".to_string(),
+            path_style,
+        }
+    }
+
     #[test]
     fn test_max_document_size() {
         assert_eq!(MAX_DOCUMENT_SIZE, 10 * 1024 * 1024);
@@ -179,4 +638,31 @@ mod tests {
             assert!(matches!(result, Err(S3Error::PayloadTooLarge { .. })));
         });
     }
+
+    #[test]
+    fn test_path_style_target() {
+        let client = S3Client::with_config(config(Some("http://localhost:9000"), true)).unwrap();
+        let (url, host, path) = client.target(Some("documents/a b.md")).unwrap();
+        assert_eq!(url, "http://localhost:9000/iou-documents/documents/a%20b.md");
+        assert_eq!(host, "localhost:9000");
+        assert_eq!(path, "/iou-documents/documents/a%20b.md");
+
+        let (url, _, _) = client.target(None).unwrap();
+        assert_eq!(url, "http://localhost:9000/iou-documents");
+    }
+
+    #[test]
+    fn test_virtual_host_target() {
+        let client = S3Client::with_config(config(None, false)).unwrap();
+        let (url, host, path) = client.target(Some("documents/x.md")).unwrap();
+        assert_eq!(url, "https://iou-documents.s3.eu-central-1.amazonaws.com/documents/x.md");
+        assert_eq!(host, "iou-documents.s3.eu-central-1.amazonaws.com");
+        assert_eq!(path, "/documents/x.md");
+    }
+
+    #[test]
+    fn test_checksum_format() {
+        // Base64 of the SHA-256 of the empty string
+        assert_eq!(checksum(b""), "47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=");
+    }
 }
@@ -0,0 +1,66 @@
+//! Minimal reader for S3 XML responses
+//!
+//! S3 responses (ListObjectsV2, InitiateMultipartUpload, error bodies) are
+//! flat and namespace-free enough that pulling out elements by name is all
+//! the client needs.
+
+/// Text of the first `<tag>` element, unescaped
+pub fn text(body: &str, tag: &str) -> Option<String> {
+    blocks(body, tag).into_iter().next().map(unescape)
+}
+
+/// Inner content of every `<tag>` element, in document order
+pub fn blocks<'a>(body: &'a str, tag: &str) -> Vec<&'a str> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let mut found = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find(&open) {
+        let inner = &rest[start + open.len()..];
+        let Some(end) = inner.find(&close) else {
+            break;
+        };
+        found.push(&inner[..end]);
+        rest = &inner[end + close.len()..];
+    }
+    found
+}
+
+/// Resolve the five predefined XML entities
+pub fn unescape(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// Escape text for use inside an element
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_objects_response() {
+        let body = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Name>iou</Name><Prefix>docs/</Prefix><IsTruncated>false</IsTruncated>
+  <Contents><Key>docs/a&amp;b.md</Key><Size>12</Size></Contents>
+  <Contents><Key>docs/c.md</Key><Size>3</Size></Contents>
+</ListBucketResult>"#;
+
+        let contents = blocks(body, "Contents");
+        assert_eq!(contents.len(), 2);
+        assert_eq!(text(contents[0], "Key").as_deref(), Some("docs/a&b.md"));
+        assert_eq!(text(contents[1], "Size").as_deref(), Some("3"));
+        assert_eq!(text(body, "IsTruncated").as_deref(), Some("false"));
+        assert_eq!(text(body, "NextContinuationToken"), None);
+    }
+}
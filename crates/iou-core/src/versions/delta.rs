@@ -0,0 +1,116 @@
+//! Reverse line deltas for version chains
+//!
+//! An older version is stored as the edits that turn its successor back
+//! into it: runs of lines copied from the successor plus inserted text.
+//! The SHA-256 of the reconstructed content is stored alongside, so a
+//! broken chain is detected instead of returning wrong content.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use similar::{DiffOp, TextDiff};
+
+/// Storage format of a version stored in full
+pub const FORMAT_FULL: &str = "markdown";
+
+/// Storage format of a version stored as a [`LineDelta`] against its successor
+pub const FORMAT_DELTA: &str = "markdown+delta";
+
+/// One edit in a [`LineDelta`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeltaOp {
+    /// Copy `len` lines of the base starting at line `start`
+    Copy { start: usize, len: usize },
+    /// Insert literal text
+    Insert(String),
+}
+
+/// Edits that reconstruct a version from its base (its successor)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineDelta {
+    /// Version number of the base this delta applies to
+    pub base_version: i32,
+    /// SHA-256 (hex) of the reconstructed content
+    pub sha256: String,
+    pub ops: Vec<DeltaOp>,
+}
+
+impl LineDelta {
+    /// Delta that turns `base` into `target`
+    pub fn compute(base: &str, target: &str, base_version: i32) -> Self {
+        let diff = TextDiff::from_lines(base, target);
+        let new_lines = diff.new_slices();
+        let mut ops = Vec::new();
+
+        for op in diff.ops() {
+            match *op {
+                DiffOp::Equal { old_index, len, .. } => ops.push(DeltaOp::Copy { start: old_index, len }),
+                DiffOp::Delete { .. } => {}
+                DiffOp::Insert { new_index, new_len, .. } | DiffOp::Replace { new_index, new_len, .. } => {
+                    ops.push(DeltaOp::Insert(new_lines[new_index..new_index + new_len].concat()));
+                }
+            }
+        }
+
+        Self {
+            base_version,
+            sha256: hex::encode(Sha256::digest(target.as_bytes())),
+            ops,
+        }
+    }
+
+    /// Reconstruct the target from `base`
+    ///
+    /// Fails when the delta does not fit the base or the result does not
+    /// match the recorded hash.
+    pub fn apply(&self, base: &str) -> Result<String, String> {
+        let lines: Vec<&str> = base.split_inclusive('\n').collect();
+        let mut out = String::with_capacity(base.len());
+
+        for op in &self.ops {
+            match op {
+                DeltaOp::Copy { start, len } => {
+                    let copied = lines
+                        .get(*start..start + len)
+                        .ok_or_else(|| format!("delta copies lines {}..{} beyond base", start, start + len))?;
+                    copied.iter().for_each(|line| out.push_str(line));
+                }
+                DeltaOp::Insert(text) => out.push_str(text),
+            }
+        }
+
+        if hex::encode(Sha256::digest(out.as_bytes())) != self.sha256 {
+            return Err(format!("delta against v{} does not reproduce the stored hash", self.base_version));
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let old = "# Nota\n\nEerste alinea.\n\n## Besluit\n\nHet college besluit.\n";
+        let new = "# Nota\n\nEerste alinea, herzien.\n\n## Besluit\n\nHet college besluit.\n\n## Bijlagen\n";
+        let delta = LineDelta::compute(new, old, 2);
+        assert_eq!(delta.apply(new).unwrap(), old);
+        assert!(delta.ops.iter().any(|op| matches!(op, DeltaOp::Copy { .. })));
+    }
+
+    #[test]
+    fn test_without_trailing_newline() {
+        let delta = LineDelta::compute("a\nb\nc", "a\nc", 1);
+        assert_eq!(delta.apply("a\nb\nc").unwrap(), "a\nc");
+        let delta = LineDelta::compute("", "nieuw", 1);
+        assert_eq!(delta.apply("").unwrap(), "nieuw");
+    }
+
+    #[test]
+    fn test_wrong_base_rejected() {
+        let delta = LineDelta::compute("a\nb\n", "a\nx\n", 3);
+        assert!(delta.apply("z\nb\n").is_err());
+        assert!(delta.apply("").is_err());
+    }
+}
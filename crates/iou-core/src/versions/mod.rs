@@ -4,12 +4,19 @@
 //! - Version creation with S3 storage
 //! - Version listing with metadata
 //! - Version restoration with audit trail
-//! - Automatic compression of old versions
+//! - Reverse-delta storage of old versions with periodic keyframes
+//! - Structured change summaries per version
 //! - Parent-child version tracking
+//! - Optimistic concurrency with three-way merge of concurrent saves
 
+pub mod delta;
 pub mod service;
+pub mod summary;
+
+pub use delta::{DeltaOp, LineDelta};
+pub use summary::DiffSummary;
 
 pub use service::{
     VersionService, VersionRecord, VersionContent, RestoreResult, VersionError,
-    StorageBackend, DatabaseBackend,
+    StorageBackend, DatabaseBackend, SaveOutcome, ConflictSet, DEFAULT_KEYFRAME_INTERVAL,
 };
@@ -2,10 +2,21 @@
 //!
 //! Manages document version lifecycle including creation, storage,
 //! compression, and restoration with full audit trail.
-
+//!
+//! Older versions are stored as reverse deltas: the newest
+//! `full_versions_keep` versions are kept in full, older ones become a
+//! gzipped [`LineDelta`] against their successor. Every
+//! `keyframe_interval`-th version stays a full (gzipped) keyframe so that
+//! reconstructing any version walks at most that many deltas.
+
+use super::delta::{LineDelta, FORMAT_DELTA, FORMAT_FULL};
+use super::summary::DiffSummary;
+use crate::diff::{DiffGenerator, MergeConflict};
 use crate::storage::S3Error;
+pub use crate::storage::StorageBackend;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -13,15 +24,7 @@ use flate2::read::GzDecoder;
 use flate2::write::GzEncoder;
 use flate2::Compression;
 use std::io::{Read, Write};
-use tokio::sync::RwLock;
-
-/// Storage backend abstraction for testability
-#[async_trait::async_trait]
-pub trait StorageBackend: Send + Sync {
-    async fn upload(&self, key: &str, data: Vec<u8>, content_type: &str) -> Result<(), S3Error>;
-    async fn download(&self, key: &str) -> Result<Vec<u8>, S3Error>;
-    async fn exists(&self, key: &str) -> Result<bool, S3Error>;
-}
+use tokio::sync::{Mutex, RwLock};
 
 /// Database backend abstraction for testability
 #[async_trait::async_trait]
@@ -31,6 +34,7 @@ pub trait DatabaseBackend: Send + Sync {
     async fn list_versions(&self, document_id: Uuid) -> Result<Vec<VersionRecord>, VersionError>;
     async fn get_version(&self, version_id: Uuid) -> Result<Option<VersionRecord>, VersionError>;
     async fn update_version_compression(&self, version_id: Uuid, is_compressed: bool) -> Result<(), VersionError>;
+    async fn update_version_storage(&self, version_id: Uuid, format: &str, is_compressed: bool) -> Result<(), VersionError>;
     async fn update_version_current(&self, version_id: Uuid, is_current: bool) -> Result<(), VersionError>;
     async fn get_next_version_number(&self, document_id: Uuid) -> Result<i32, VersionError>;
     async fn update_document_content(&self, document_id: Uuid, content: &str) -> Result<(), VersionError>;
@@ -72,6 +76,32 @@ pub struct RestoreResult {
     pub created_at: DateTime<Utc>,
 }
 
+/// Outcome of [`VersionService::save_version`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum SaveOutcome {
+    /// The save was based on the current version and stored as is
+    Created { version: VersionRecord },
+    /// The base was stale; the edits merged cleanly with the versions saved since
+    Merged { version: VersionRecord, merged_with: Uuid },
+    /// The base was stale and the edits overlap; nothing was stored
+    Conflict(ConflictSet),
+}
+
+/// Overlapping edits for the editor to resolve
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictSet {
+    pub document_id: Uuid,
+    /// Version the editor started from
+    pub base_version_id: Option<Uuid>,
+    /// Version saved by someone else in the meantime
+    pub current_version_id: Uuid,
+    /// Merge result with conflict markers; "ours" is the current version,
+    /// "theirs" the incoming save
+    pub merged: String,
+    pub conflicts: Vec<MergeConflict>,
+}
+
 /// Version service errors
 #[derive(Debug, thiserror::Error)]
 pub enum VersionError {
@@ -100,11 +130,17 @@ pub enum VersionError {
     InvalidInput(String),
 }
 
+/// Default distance between full keyframes in a delta chain
+pub const DEFAULT_KEYFRAME_INTERVAL: i32 = 10;
+
 /// Version storage service
 pub struct VersionService {
     storage: Arc<dyn StorageBackend>,
     db: Arc<dyn DatabaseBackend>,
     full_versions_keep: i32,
+    keyframe_interval: i32,
+    /// Serializes the base check and write in `save_version`
+    save_lock: Mutex<()>,
 }
 
 impl VersionService {
@@ -118,6 +154,8 @@ impl VersionService {
             storage: Arc::new(storage),
             db: Arc::new(db),
             full_versions_keep,
+            keyframe_interval: DEFAULT_KEYFRAME_INTERVAL,
+            save_lock: Mutex::new(()),
         }
     }
 
@@ -130,9 +168,17 @@ impl VersionService {
             storage: Arc::new(storage),
             db: Arc::new(MockDatabase::new()),
             full_versions_keep,
+            keyframe_interval: DEFAULT_KEYFRAME_INTERVAL,
+            save_lock: Mutex::new(()),
         }
     }
 
+    /// Keep every `interval`-th version as a full keyframe (minimum 1)
+    pub fn with_keyframe_interval(mut self, interval: i32) -> Self {
+        self.keyframe_interval = interval.max(1);
+        self
+    }
+
     /// Create a new version for a document
     pub async fn create_version(
         &self,
@@ -149,6 +195,21 @@ impl VersionService {
 
         let parent_version_id = current_version.as_ref().map(|v| v.id);
 
+        // Summarize the changes against the previous version
+        let previous_content = match &current_version {
+            Some(prev) => match self.get_version_content(prev.id).await {
+                Ok(content) => Some(content),
+                Err(e) => {
+                    tracing::warn!("Cannot read version {} for diff summary: {}", prev.id, e);
+                    None
+                }
+            },
+            None => Some(String::new()),
+        };
+        let diff_summary = previous_content
+            .map(|previous| DiffSummary::between(&previous, content))
+            .and_then(|summary| serde_json::to_value(summary).ok());
+
         // Mark previous current version as non-current
         if let Some(ref prev) = current_version {
             let _ = self.db.update_version_current(prev.id, false).await;
@@ -171,14 +232,14 @@ impl VersionService {
             document_id,
             version_number,
             storage_key: storage_key.clone(),
-            format: "markdown".to_string(),
+            format: FORMAT_FULL.to_string(),
             created_at: Utc::now(),
             created_by: changed_by.to_string(),
             change_summary: Some(change_summary.to_string()),
             is_current: true,
             is_compressed: false,
-            parent_version_id: parent_version_id,
-            diff_summary: None, // TODO: Generate using diff service
+            parent_version_id,
+            diff_summary,
             compliance_score: None,
         };
 
@@ -192,6 +253,89 @@ impl VersionService {
         Ok(saved)
     }
 
+    /// Save an edit made on top of `base_version_id`
+    ///
+    /// When the base is still the current version this is a plain
+    /// [`create_version`](Self::create_version). When another save landed
+    /// in between, the edit is merged three-way (base vs. current vs.
+    /// incoming): non-overlapping changes are stored as a new version,
+    /// overlapping ones are returned as a [`ConflictSet`] without storing
+    /// anything. `None` means the editor started from an empty document.
+    pub async fn save_version(
+        &self,
+        document_id: Uuid,
+        base_version_id: Option<Uuid>,
+        content: &str,
+        changed_by: Uuid,
+        change_summary: &str,
+    ) -> Result<SaveOutcome, VersionError> {
+        let _guard = self.save_lock.lock().await;
+
+        let current = self.db.get_current_version(document_id).await?;
+        let current = match current {
+            Some(current) if Some(current.id) != base_version_id => current,
+            _ if base_version_id.is_some() && current.is_none() => {
+                return Err(VersionError::InvalidInput(
+                    "Base version given for a document without versions".to_string(),
+                ));
+            }
+            _ => {
+                let version = self
+                    .create_version(document_id, content, changed_by, change_summary)
+                    .await?;
+                return Ok(SaveOutcome::Created { version });
+            }
+        };
+
+        let base_content = match base_version_id {
+            Some(base_id) => {
+                let base = self
+                    .db
+                    .get_version(base_id)
+                    .await?
+                    .ok_or(VersionError::VersionNotFound(base_id))?;
+                if base.document_id != document_id {
+                    return Err(VersionError::InvalidInput(
+                        "Base version does not belong to document".to_string(),
+                    ));
+                }
+                self.get_version_content(base_id).await?
+            }
+            None => String::new(),
+        };
+        let current_content = self.get_version_content(current.id).await?;
+
+        let result = DiffGenerator.three_way_merge(
+            &base_content,
+            &current_content,
+            content,
+            &format!("version {}", current.version_number),
+            "incoming",
+        );
+
+        if !result.is_clean() {
+            return Ok(SaveOutcome::Conflict(ConflictSet {
+                document_id,
+                base_version_id,
+                current_version_id: current.id,
+                merged: result.merged,
+                conflicts: result.conflicts,
+            }));
+        }
+
+        let summary = format!(
+            "{} (merged with version {})",
+            change_summary, current.version_number
+        );
+        let version = self
+            .create_version(document_id, &result.merged, changed_by, &summary)
+            .await?;
+        Ok(SaveOutcome::Merged {
+            version,
+            merged_with: current.id,
+        })
+    }
+
     /// List all versions for a document, ordered by created_at DESC
     pub async fn list_versions(
         &self,
@@ -216,7 +360,7 @@ impl VersionService {
         })
     }
 
-    /// Get version content (handles decompression)
+    /// Get version content (handles decompression and delta chains)
     pub async fn get_version_content(&self, version_id: Uuid) -> Result<String, VersionError> {
         let record = self
             .db
@@ -224,25 +368,51 @@ impl VersionService {
             .await?
             .ok_or(VersionError::VersionNotFound(version_id))?;
 
-        // For compressed versions, the data is stored at {storage_key}.gz
-        let storage_key = if record.is_compressed {
-            format!("{}.gz", record.storage_key)
-        } else {
-            record.storage_key.clone()
-        };
-
-        let data = self.storage.download(&storage_key).await?;
+        if record.format != FORMAT_DELTA {
+            return Self::utf8(self.load_stored(&record).await?);
+        }
 
-        let content = if record.is_compressed {
-            Self::decompress(&data)?
-        } else {
-            String::from_utf8(data)
-                .map_err(|e| VersionError::Decompression(format!("UTF-8 error: {}", e)))?
-        };
+        // Walk towards newer versions until one is stored in full
+        let by_number: HashMap<i32, VersionRecord> = self
+            .list_versions(record.document_id)
+            .await?
+            .into_iter()
+            .map(|v| (v.version_number, v))
+            .collect();
+        let mut deltas = Vec::new();
+        let mut current = record;
+        while current.format == FORMAT_DELTA {
+            let delta: LineDelta = serde_json::from_slice(&self.load_stored(&current).await?).map_err(|e| {
+                VersionError::Decompression(format!("Invalid delta for version {}: {}", current.id, e))
+            })?;
+            let base = by_number.get(&delta.base_version).cloned().ok_or_else(|| {
+                VersionError::Decompression(format!(
+                    "Delta chain of document {} broken at version {}",
+                    current.document_id, delta.base_version
+                ))
+            })?;
+            deltas.push(delta);
+            current = base;
+        }
 
+        let mut content = Self::utf8(self.load_stored(&current).await?)?;
+        for delta in deltas.iter().rev() {
+            content = delta.apply(&content).map_err(VersionError::Decompression)?;
+        }
         Ok(content)
     }
 
+    /// Raw stored payload of a version, gunzipped when compressed
+    async fn load_stored(&self, record: &VersionRecord) -> Result<Vec<u8>, VersionError> {
+        // For compressed versions, the data is stored at {storage_key}.gz
+        if record.is_compressed {
+            let data = self.storage.download(&format!("{}.gz", record.storage_key)).await?;
+            Self::decompress(&data)
+        } else {
+            Ok(self.storage.download(&record.storage_key).await?)
+        }
+    }
+
     /// Restore a previous version
     pub async fn restore_version(
         &self,
@@ -300,33 +470,54 @@ impl VersionService {
     }
 
     /// Compress old versions beyond the full_versions_keep threshold
+    ///
+    /// Keyframes are gzipped in full, all other versions are replaced by a
+    /// gzipped reverse delta against their successor.
     async fn compress_old_versions(&self, document_id: Uuid) -> Result<(), VersionError> {
-        let versions = self.list_versions(document_id).await?;
+        let mut versions = self.list_versions(document_id).await?;
+        versions.sort_by_key(|v| std::cmp::Reverse(v.version_number));
 
-        // Versions are ordered DESC (newest first)
-        // Compress versions beyond the keep threshold (skip newest N)
+        // Newest first; compress versions beyond the keep threshold (skip
+        // newest N), each against its direct successor
         for (i, version) in versions.iter().enumerate() {
-            if i >= self.full_versions_keep as usize && !version.is_compressed {
-                let _ = self.compress_version(&version.storage_key).await;
-                let _ = self
-                    .db
-                    .update_version_compression(version.id, true)
-                    .await;
+            if i == 0 || i < self.full_versions_keep as usize || version.is_compressed {
+                continue;
+            }
+            if let Err(e) = self.compress_version(version, &versions[i - 1]).await {
+                tracing::warn!("Failed to compress version {}: {}", version.id, e);
             }
         }
 
         Ok(())
     }
 
-    /// Compress a single version's stored content
-    async fn compress_version(&self, storage_key: &str) -> Result<(), VersionError> {
-        let data = self.storage.download(storage_key).await?;
-        let compressed = Self::compress(&data)?;
+    /// Store a single version compressed, as keyframe or delta
+    async fn compress_version(&self, version: &VersionRecord, successor: &VersionRecord) -> Result<(), VersionError> {
+        let content = self.get_version_content(version.id).await?;
 
-        // Upload compressed version back
+        let (format, payload) = if version.version_number % self.keyframe_interval == 0 {
+            (FORMAT_FULL, content.into_bytes())
+        } else {
+            let base = self.get_version_content(successor.id).await?;
+            let delta = LineDelta::compute(&base, &content, successor.version_number);
+            let payload = serde_json::to_vec(&delta).map_err(|e| VersionError::Compression(e.to_string()))?;
+            (FORMAT_DELTA, payload)
+        };
+
+        // Upload compressed version, switch the record over, then drop the
+        // full copy; a failure in between leaves a readable version
         self.storage
-            .upload(&format!("{}.gz", storage_key), compressed, "application/gzip")
+            .upload(
+                &format!("{}.gz", version.storage_key),
+                Self::compress(&payload)?,
+                "application/gzip",
+            )
             .await?;
+        self.db.update_version_storage(version.id, format, true).await?;
+        match self.storage.delete(&version.storage_key).await {
+            Ok(()) | Err(S3Error::NotFound(_)) => {}
+            Err(e) => tracing::warn!("Full copy of version {} not removed: {}", version.id, e),
+        }
 
         Ok(())
     }
@@ -348,14 +539,17 @@ impl VersionService {
     }
 
     /// Decompress gzip data
-    fn decompress(data: &[u8]) -> Result<String, VersionError> {
+    fn decompress(data: &[u8]) -> Result<Vec<u8>, VersionError> {
         let mut decoder = GzDecoder::new(data);
         let mut decompressed = Vec::new();
         decoder
             .read_to_end(&mut decompressed)
             .map_err(|e| VersionError::Decompression(e.to_string()))?;
-        String::from_utf8(decompressed)
-            .map_err(|e| VersionError::Decompression(format!("UTF-8 error: {}", e)))
+        Ok(decompressed)
+    }
+
+    fn utf8(data: Vec<u8>) -> Result<String, VersionError> {
+        String::from_utf8(data).map_err(|e| VersionError::Decompression(format!("UTF-8 error: {}", e)))
     }
 }
 
@@ -423,6 +617,20 @@ impl DatabaseBackend for MockDatabase {
         Ok(())
     }
 
+    async fn update_version_storage(
+        &self,
+        version_id: Uuid,
+        format: &str,
+        is_compressed: bool,
+    ) -> Result<(), VersionError> {
+        let mut versions = self.versions.write().await;
+        if let Some(v) = versions.iter_mut().find(|v| v.id == version_id) {
+            v.format = format.to_string();
+            v.is_compressed = is_compressed;
+        }
+        Ok(())
+    }
+
     async fn update_version_current(
         &self,
         version_id: Uuid,
@@ -0,0 +1,125 @@
+//! Structured change summary between two versions
+//!
+//! Stored in `VersionRecord::diff_summary`. Lines are counted from a line
+//! diff, where a replaced block counts as changed lines up to the shorter
+//! side and the rest as added or removed. Sections are Markdown headings
+//! matched by their title.
+
+use serde::{Deserialize, Serialize};
+use similar::{DiffOp, TextDiff};
+use std::collections::HashMap;
+
+/// Counts of changed lines and sections
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffSummary {
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    pub lines_changed: usize,
+    pub sections_added: Vec<String>,
+    pub sections_removed: Vec<String>,
+    pub sections_changed: Vec<String>,
+}
+
+impl DiffSummary {
+    /// Summary of the changes from `old` to `new`
+    pub fn between(old: &str, new: &str) -> Self {
+        let mut summary = Self::default();
+
+        for op in TextDiff::from_lines(old, new).ops() {
+            match *op {
+                DiffOp::Equal { .. } => {}
+                DiffOp::Insert { new_len, .. } => summary.lines_added += new_len,
+                DiffOp::Delete { old_len, .. } => summary.lines_removed += old_len,
+                DiffOp::Replace { old_len, new_len, .. } => {
+                    let changed = old_len.min(new_len);
+                    summary.lines_changed += changed;
+                    summary.lines_added += new_len - changed;
+                    summary.lines_removed += old_len - changed;
+                }
+            }
+        }
+
+        let old_sections = sections(old);
+        let new_sections = sections(new);
+        let old_by_title: HashMap<&str, &str> = old_sections.iter().map(|(t, b)| (t.as_str(), b.as_str())).collect();
+        let new_by_title: HashMap<&str, &str> = new_sections.iter().map(|(t, b)| (t.as_str(), b.as_str())).collect();
+
+        for (title, body) in &new_sections {
+            match old_by_title.get(title.as_str()) {
+                None => summary.sections_added.push(title.clone()),
+                Some(old_body) if old_body != body => summary.sections_changed.push(title.clone()),
+                Some(_) => {}
+            }
+        }
+        for (title, _) in &old_sections {
+            if !new_by_title.contains_key(title.as_str()) {
+                summary.sections_removed.push(title.clone());
+            }
+        }
+
+        summary
+    }
+
+    /// True when the versions are identical
+    pub fn is_empty(&self) -> bool {
+        self.lines_added == 0 && self.lines_removed == 0 && self.lines_changed == 0
+    }
+}
+
+/// Markdown sections as (heading title, body) in document order
+///
+/// Text before the first heading is left out; headings inside fenced code
+/// blocks are not sections.
+fn sections(markdown: &str) -> Vec<(String, String)> {
+    let mut found: Vec<(String, String)> = Vec::new();
+    let mut in_code = false;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+        }
+        let hashes = trimmed.chars().take_while(|c| *c == '#').count();
+        let is_heading = !in_code
+            && (1..=6).contains(&hashes)
+            && trimmed[hashes..].starts_with(char::is_whitespace);
+
+        if is_heading {
+            found.push((trimmed[hashes..].trim().to_string(), String::new()));
+        } else if let Some((_, body)) = found.last_mut() {
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_counts() {
+        let old = "a\nb\nc\nd\n";
+        let new = "a\nB\nc\nd\ne\nf\n";
+        let summary = DiffSummary::between(old, new);
+        assert_eq!(summary.lines_changed, 1);
+        assert_eq!(summary.lines_added, 2);
+        assert_eq!(summary.lines_removed, 0);
+    }
+
+    #[test]
+    fn test_sections() {
+        let old = "# Nota\nIntro\n## Aanleiding\nTekst\n## Financiën\nBedrag\n";
+        let new = "# Nota\nIntro\n## Aanleiding\nNieuwe tekst\n## Besluit\nAkkoord\n```\n# geen kop\n```\n";
+        let summary = DiffSummary::between(old, new);
+        assert_eq!(summary.sections_added, vec!["Besluit"]);
+        assert_eq!(summary.sections_removed, vec!["Financiën"]);
+        assert_eq!(summary.sections_changed, vec!["Aanleiding"]);
+    }
+
+    #[test]
+    fn test_identical() {
+        assert!(DiffSummary::between("# A\nb\n", "# A\nb\n").is_empty());
+    }
+}
@@ -291,3 +291,42 @@ fn test_generate_diff_handles_multi_line_mixed_operations() {
     assert!(has_unchanged);
     assert!(has_change);
 }
+
+#[test]
+fn test_generate_version_diff_uses_version_identifiers() {
+    let result = DiffGenerator.generate_version_diff("v3", "a", "v4", "b", DiffFormat::Unified);
+    assert_eq!(result.from_version, "v3");
+    assert_eq!(result.to_version, "v4");
+}
+
+#[test]
+fn test_side_by_side_rows_pair_replaced_lines() {
+    let old = "one\ntwo\nthree\nfour";
+    let new = "one\n2\nthree\nfour\nfive";
+
+    let result = DiffGenerator.generate_diff(old, new, DiffFormat::SideBySide);
+
+    let rows: Vec<_> = result.rows.iter().map(|r| (r.old_line, r.new_line)).collect();
+    assert_eq!(
+        rows,
+        vec![(Some(1), Some(1)), (Some(2), Some(2)), (Some(3), Some(3)), (Some(4), Some(4)), (None, Some(5))]
+    );
+    assert_eq!(result.rows[1].old_text.as_deref(), Some("two"));
+    assert_eq!(result.rows[1].new_text.as_deref(), Some("2"));
+    assert!(!result.rows[1].words.is_empty());
+    assert!(result.rows[0].words.is_empty());
+    assert_eq!(result.changes[1], DiffChange::Replaced { old: "two".to_string(), new: "2".to_string() });
+}
+
+#[test]
+fn test_inline_diff_is_word_level() {
+    let result = DiffGenerator.generate_diff("de raad besluit", "het college besluit", DiffFormat::Inline);
+    assert_eq!(
+        result.changes,
+        vec![
+            DiffChange::Replaced { old: "de raad".to_string(), new: "het college".to_string() },
+            DiffChange::Unchanged(" besluit".to_string()),
+        ]
+    );
+    assert!(result.rows.is_empty());
+}
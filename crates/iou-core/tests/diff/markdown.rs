@@ -0,0 +1,131 @@
+use iou_core::diff::{BlockChange, BlockKind, DiffChange, DiffGenerator, SectionStatus};
+
+const BESLUIT: &str = "\
+# Besluit op Woo-verzoek
+
+Inleiding van het besluit.
+
+## 1 Verzoek
+
+U heeft op 3 maart een verzoek ingediend.
+
+## 2 Besluit
+
+Ik maak de documenten gedeeltelijk openbaar.
+
+## 3 Overwegingen
+
+### 3.1 Persoonsgegevens
+
+Namen van medewerkers zijn weggelakt.
+
+### 3.2 Bedrijfsgegevens
+
+Bedrijfsgegevens zijn vertrouwelijk meegedeeld.
+
+| Document | Oordeel |
+|----------|---------|
+| 1 | openbaar |
+| 2 | deels openbaar |
+";
+
+#[test]
+fn test_identical_documents_have_no_changes() {
+    let diff = DiffGenerator.markdown_diff("v1", BESLUIT, "v2", BESLUIT);
+    assert!(diff.is_empty());
+    assert_eq!(diff.from_version, "v1");
+    assert_eq!(diff.to_version, "v2");
+}
+
+#[test]
+fn test_section_numbers_follow_headings() {
+    let new = BESLUIT.replace("Namen van medewerkers", "Namen en telefoonnummers van medewerkers");
+    let diff = DiffGenerator.markdown_diff("v1", BESLUIT, "v2", &new);
+
+    assert_eq!(diff.sections.len(), 1);
+    let section = &diff.sections[0];
+    assert_eq!(section.number, "3.1");
+    assert_eq!(section.title, "Persoonsgegevens");
+    assert_eq!(section.status, SectionStatus::Modified);
+    let BlockChange::Modified { kind, words, .. } = &section.changes[0] else {
+        panic!("expected Modified, got {:?}", section.changes[0]);
+    };
+    assert_eq!(*kind, BlockKind::Paragraph);
+    assert!(words.contains(&DiffChange::Inserted("en telefoonnummers ".to_string())));
+}
+
+#[test]
+fn test_numbers_computed_without_explicit_numbering() {
+    let old = "# Titel\n\n## Aanleiding\n\nA.\n\n## Voorstel\n\nB.\n";
+    let new = "# Titel\n\n## Aanleiding\n\nA.\n\n## Voorstel\n\nC.\n";
+    let diff = DiffGenerator.markdown_diff("1", old, "2", new);
+    assert_eq!(diff.sections[0].label(), "2 Voorstel");
+}
+
+#[test]
+fn test_rewritten_section() {
+    let new = BESLUIT.replace(
+        "Namen van medewerkers zijn weggelakt.",
+        "Na afweging van het publieke belang wegen de privacybelangen van derden hier niet zwaarder.",
+    );
+    let diff = DiffGenerator.markdown_diff("v1", BESLUIT, "v2", &new);
+    let section = diff.sections.iter().find(|s| s.number == "3.1").unwrap();
+    assert_eq!(section.status, SectionStatus::Rewritten);
+    assert_eq!(section.label(), "3.1 Persoonsgegevens");
+}
+
+#[test]
+fn test_added_and_removed_sections() {
+    let new = BESLUIT
+        .replace("## 1 Verzoek\n\nU heeft op 3 maart een verzoek ingediend.\n\n", "")
+        .replace("## 3 Overwegingen", "## 3 Overwegingen\n\n### 3.0 Wettelijk kader\n\nArtikel 5.1 Woo.");
+    let diff = DiffGenerator.markdown_diff("v1", BESLUIT, "v2", &new);
+
+    let statuses: Vec<(&str, SectionStatus)> =
+        diff.sections.iter().map(|s| (s.title.as_str(), s.status)).collect();
+    assert_eq!(
+        statuses,
+        vec![("Verzoek", SectionStatus::Removed), ("Wettelijk kader", SectionStatus::Added)]
+    );
+}
+
+#[test]
+fn test_moved_paragraph() {
+    let old = "## A\n\nEen.\n\nVerplaatste alinea.\n\n## B\n\nTwee.\n";
+    let new = "## A\n\nEen.\n\n## B\n\nTwee.\n\nVerplaatste alinea.\n";
+    let diff = DiffGenerator.markdown_diff("1", old, "2", new);
+
+    assert!(diff.sections.is_empty(), "{:?}", diff);
+    assert_eq!(diff.moved.len(), 1);
+    assert_eq!(diff.moved[0].text, "Verplaatste alinea.");
+    assert_eq!(diff.moved[0].from_section, "1 A");
+    assert_eq!(diff.moved[0].to_section, "2 B");
+}
+
+#[test]
+fn test_table_cell_changes() {
+    let new = BESLUIT
+        .replace("| 2 | deels openbaar |", "| 2 | openbaar |")
+        .replace("| 2 | openbaar |\n", "| 2 | openbaar |\n| 3 | niet openbaar |\n");
+    let diff = DiffGenerator.markdown_diff("v1", BESLUIT, "v2", &new);
+
+    let section = diff.sections.iter().find(|s| s.number == "3.2").unwrap();
+    let BlockChange::TableChanged { cells, rows_added, rows_removed } = &section.changes[0] else {
+        panic!("expected TableChanged, got {:?}", section.changes[0]);
+    };
+    assert_eq!(cells.len(), 1);
+    assert_eq!((cells[0].row, cells[0].column), (2, 1));
+    assert_eq!(cells[0].old, "deels openbaar");
+    assert_eq!(cells[0].new, "openbaar");
+    assert_eq!(rows_added, &vec![vec!["3".to_string(), "niet openbaar".to_string()]]);
+    assert!(rows_removed.is_empty());
+}
+
+#[test]
+fn test_headings_in_code_blocks_are_not_sections() {
+    let old = "## A\n\n```\n# geen kop\n```\n";
+    let new = "## A\n\n```\n# nog steeds geen kop\n```\n";
+    let diff = DiffGenerator.markdown_diff("1", old, "2", new);
+    assert_eq!(diff.sections.len(), 1);
+    assert!(matches!(diff.sections[0].changes[0], BlockChange::Modified { kind: BlockKind::Code, .. }));
+}
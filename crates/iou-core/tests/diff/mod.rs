@@ -1 +1,2 @@
 mod generator;
+mod markdown;
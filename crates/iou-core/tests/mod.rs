@@ -11,5 +11,6 @@ mod category;
 mod purpose;
 mod graphrag;
 mod sla;
+mod storage;
 mod versions;
 mod workflows;
@@ -70,22 +70,26 @@ async fn test_is_overdue_false() {
 async fn test_hours_until_deadline_future() {
     let calculator = SlaCalculator::new();
 
-    let deadline = Utc::now() + Duration::hours(48);
-    let hours = calculator.hours_until_deadline(deadline);
+    // Friday 10 AM, deadline 48 hours later on Sunday: only Friday counts
+    let now = DateTime::parse_from_rfc3339("2024-01-05T10:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    let hours = calculator.hours_until_deadline_at(now + Duration::hours(48), now);
 
-    assert!(hours > 0);
-    assert!(hours <= 48); // May be less due to weekends
+    assert_eq!(hours, 14);
 }
 
 #[tokio::test]
 async fn test_hours_until_deadline_past() {
     let calculator = SlaCalculator::new();
 
-    let deadline = Utc::now() - Duration::hours(24);
-    let hours = calculator.hours_until_deadline(deadline);
+    // Tuesday 10 AM, deadline a day earlier
+    let now = DateTime::parse_from_rfc3339("2024-01-09T10:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    let hours = calculator.hours_until_deadline_at(now - Duration::hours(24), now);
 
-    assert!(hours < 0);
-    assert!(hours >= -48); // May be more negative due to weekends
+    assert_eq!(hours, -24);
 }
 
 #[tokio::test]
@@ -157,3 +161,19 @@ async fn test_sla_config_dutch_weekend() {
     assert!(calculator.is_weekend(saturday));
     assert!(calculator.is_weekend(sunday));
 }
+
+#[tokio::test]
+async fn test_netherlands_config_skips_koningsdag() {
+    let calculator = SlaCalculator::with_config(SlaConfig::netherlands());
+
+    // Friday 24 April 2026 16:00 CEST + 2 working hours: Monday 27 April
+    // is Koningsdag, so the deadline is Tuesday 10:00 CEST
+    let start = DateTime::parse_from_rfc3339("2026-04-24T14:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+
+    let deadline = calculator.calculate_deadline(start, 2);
+
+    assert_eq!(deadline.weekday(), Weekday::Tue);
+    assert_eq!(deadline, DateTime::parse_from_rfc3339("2026-04-28T08:00:00Z").unwrap());
+}
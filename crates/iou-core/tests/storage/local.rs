@@ -0,0 +1,98 @@
+//! Content-addressed filesystem and in-memory backends
+//!
+//! Both run the full version flow without any external service.
+
+use iou_core::storage::{content_address, FilesystemStorage, MemoryStorage, S3Error, StorageBackend};
+use iou_core::versions::VersionService;
+use uuid::Uuid;
+
+async fn version_flow<S: StorageBackend + Clone + 'static>(storage: S) {
+    let service = VersionService::new_with_storage(storage.clone(), 5);
+    let document_id = Uuid::new_v4();
+    let user_id = Uuid::new_v4();
+
+    let first = service
+        .create_version(document_id, "# Beleidsnota\n\nEerste versie", user_id, "Concept")
+        .await
+        .unwrap();
+    service
+        .create_version(document_id, "# Beleidsnota\n\nTweede versie", user_id, "Review")
+        .await
+        .unwrap();
+
+    let restored = service.restore_version(document_id, first.id, user_id).await.unwrap();
+    assert_eq!(restored.content, "# Beleidsnota\n\nEerste versie");
+    assert_eq!(
+        service.get_version_content(restored.id).await.unwrap(),
+        "# Beleidsnota\n\nEerste versie"
+    );
+    assert!(storage.exists(&first.storage_key).await.unwrap());
+}
+
+#[tokio::test]
+async fn test_memory_version_flow() {
+    let storage = MemoryStorage::new();
+    version_flow(storage.clone()).await;
+    // The restored version has the same content as the first one
+    assert_eq!(storage.blob_count(), 2);
+}
+
+#[tokio::test]
+async fn test_filesystem_version_flow() {
+    let dir = tempfile::tempdir().unwrap();
+    let storage = FilesystemStorage::new(dir.path()).unwrap();
+    version_flow(storage.clone()).await;
+    assert_eq!(storage.collect_garbage().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_filesystem_deduplicates_and_survives_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    let storage = FilesystemStorage::new(dir.path()).unwrap();
+    let data = b"%PDF-1.7 besluit".to_vec();
+
+    storage.upload("documents/a.pdf", data.clone(), "application/pdf").await.unwrap();
+    storage.upload("archief/a.pdf", data.clone(), "application/pdf").await.unwrap();
+    let a = storage.stat("documents/a.pdf").await.unwrap().unwrap();
+    let b = storage.stat("archief/a.pdf").await.unwrap().unwrap();
+    assert_eq!(a.sha256, content_address(&data));
+    assert_eq!(a, b);
+
+    let reopened = FilesystemStorage::new(dir.path()).unwrap();
+    assert_eq!(reopened.download("archief/a.pdf").await.unwrap(), data);
+    assert_eq!(
+        reopened.list("").await.unwrap(),
+        vec!["archief/a.pdf".to_string(), "documents/a.pdf".to_string()]
+    );
+}
+
+#[tokio::test]
+async fn test_filesystem_delete_destroys_unshared_content() {
+    let dir = tempfile::tempdir().unwrap();
+    let storage = FilesystemStorage::new(dir.path()).unwrap();
+    storage.upload("a", b"gedeeld".to_vec(), "text/plain").await.unwrap();
+    storage.upload("b", b"gedeeld".to_vec(), "text/plain").await.unwrap();
+    let sha256 = content_address(b"gedeeld");
+    let blob = dir.path().join("blobs").join(&sha256[..2]).join(&sha256);
+
+    storage.delete("a").await.unwrap();
+    assert!(blob.is_file(), "still referenced by b");
+    assert_eq!(storage.download("b").await.unwrap(), b"gedeeld");
+
+    storage.delete("b").await.unwrap();
+    assert!(!blob.exists());
+    assert!(matches!(storage.download("b").await, Err(S3Error::NotFound(_))));
+    // Deleting a missing key is not an error
+    storage.delete("b").await.unwrap();
+}
+
+#[tokio::test]
+async fn test_filesystem_garbage_collection_after_overwrite() {
+    let dir = tempfile::tempdir().unwrap();
+    let storage = FilesystemStorage::new(dir.path()).unwrap();
+    storage.upload("doc", b"oud".to_vec(), "text/plain").await.unwrap();
+    storage.upload("doc", b"nieuw".to_vec(), "text/plain").await.unwrap();
+
+    assert_eq!(storage.collect_garbage().await.unwrap(), 1);
+    assert_eq!(storage.download("doc").await.unwrap(), b"nieuw");
+}
@@ -0,0 +1,4 @@
+//! Storage backend tests
+
+mod local;
+mod s3;
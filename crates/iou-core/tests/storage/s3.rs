@@ -0,0 +1,467 @@
+//! S3 client tests against an in-process S3 stand-in
+//!
+//! The stand-in speaks just enough of the S3 REST API (path-style) for the
+//! client: object PUT/GET/HEAD/DELETE with ranges and SHA-256 checksums,
+//! ListObjectsV2 with continuation and multipart uploads. It rejects
+//! unsigned requests and payloads whose hash does not match.
+//!
+//! `test_against_minio` runs the same round trip against a real MinIO
+//! configured through the usual `S3_*` environment variables.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use iou_core::storage::s3::{MAX_DOCUMENT_SIZE, MULTIPART_THRESHOLD};
+use iou_core::storage::{S3Client, S3Config, S3Error};
+use iou_core::versions::{StorageBackend, VersionService};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use uuid::Uuid;
+
+const BUCKET: &str = "iou-documents";
+
+/// Objects listed per page, small to exercise continuation tokens
+const PAGE_SIZE: usize = 2;
+
+#[derive(Default)]
+struct State {
+    /// Data, content type and the checksum computed on upload
+    objects: BTreeMap<String, (Vec<u8>, String, String)>,
+    uploads: HashMap<String, (String, BTreeMap<usize, Vec<u8>>)>,
+    completed_multipart: usize,
+}
+
+/// Minimal S3 server on a random local port
+struct S3StandIn {
+    endpoint: String,
+    state: Arc<Mutex<State>>,
+}
+
+impl S3StandIn {
+    async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(State::default()));
+
+        let shared = state.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, shared.clone()));
+            }
+        });
+
+        Self { endpoint, state }
+    }
+
+    fn client(&self) -> S3Client {
+        S3Client::with_config(S3Config {
+            access_key: "minioadmin".to_string(),
+            secret_key: "minioadmin".to_string(),
+            bucket: BUCKET.to_string(),
+            endpoint: Some(self.endpoint.clone()),
+            region: "us-east-1".to_string(),
+            path_style: true,
+        })
+        .unwrap()
+    }
+
+    fn completed_multipart(&self) -> usize {
+        self.state.lock().unwrap().completed_multipart
+    }
+
+    fn corrupt(&self, key: &str) {
+        let mut state = self.state.lock().unwrap();
+        let (data, _, _) = state.objects.get_mut(key).unwrap();
+        data[0] ^= 0xff;
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+struct Response {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn new(status: u16) -> Self {
+        Self { status, headers: Vec::new(), body: Vec::new() }
+    }
+
+    fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    fn error(status: u16, code: &str) -> Self {
+        Self::new(status).body(format!("<Error><Code>{}</Code><Message>{}</Message></Error>", code, code))
+    }
+}
+
+async fn serve(stream: TcpStream, state: Arc<Mutex<State>>) {
+    let mut reader = BufReader::new(stream);
+    while let Some(request) = read_request(&mut reader).await {
+        let response = handle(request, &state);
+        let mut out = format!("HTTP/1.1 {} X\r\ncontent-length: {}\r\n", response.status, response.body.len());
+        for (name, value) in &response.headers {
+            out.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        out.push_str("\r\n");
+        let stream = reader.get_mut();
+        if stream.write_all(out.as_bytes()).await.is_err() || stream.write_all(&response.body).await.is_err() {
+            return;
+        }
+    }
+}
+
+async fn read_request(reader: &mut BufReader<TcpStream>) -> Option<Request> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).await.ok()? == 0 {
+        return None;
+    }
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let target = parts.next()?.to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).await.ok()?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line.split_once(':')?;
+        headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+    }
+
+    let length = headers.get("content-length").and_then(|l| l.parse().ok()).unwrap_or(0);
+    let mut body = vec![0u8; length];
+    reader.read_exact(&mut body).await.ok()?;
+
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    let query = query
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let (k, v) = p.split_once('=').unwrap_or((p, ""));
+            (decode(k), decode(v))
+        })
+        .collect();
+
+    Some(Request { method, path: decode(path), query, headers, body })
+}
+
+fn decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            out.push(u8::from_str_radix(&input[i + 1..i + 3], 16).unwrap());
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).unwrap()
+}
+
+fn checksum(data: &[u8]) -> String {
+    BASE64.encode(Sha256::digest(data))
+}
+
+fn handle(request: Request, state: &Mutex<State>) -> Response {
+    let signed = request
+        .headers
+        .get("authorization")
+        .is_some_and(|a| a.starts_with("AWS4-HMAC-SHA256 Credential=minioadmin/"));
+    if !signed {
+        return Response::error(403, "AccessDenied");
+    }
+    if request.headers.get("x-amz-content-sha256") != Some(&hex::encode(Sha256::digest(&request.body))) {
+        return Response::error(400, "XAmzContentSHA256Mismatch");
+    }
+    if let Some(expected) = request.headers.get("x-amz-checksum-sha256")
+        && *expected != checksum(&request.body)
+    {
+        return Response::error(400, "BadDigest");
+    }
+
+    let path = request.path.trim_start_matches('/');
+    let (bucket, key) = path.split_once('/').unwrap_or((path, ""));
+    if bucket != BUCKET {
+        return Response::error(404, "NoSuchBucket");
+    }
+
+    let mut state = state.lock().unwrap();
+    let query = &request.query;
+    match (request.method.as_str(), key.is_empty()) {
+        ("HEAD", true) => Response::new(200),
+        ("GET", true) => list(&state, query),
+        ("POST", false) if query.contains_key("uploads") => {
+            let upload_id = Uuid::new_v4().to_string();
+            let content_type = request.headers.get("content-type").cloned().unwrap_or_default();
+            state.uploads.insert(upload_id.clone(), (content_type, BTreeMap::new()));
+            Response::new(200).body(format!(
+                "<InitiateMultipartUploadResult><Bucket>{}</Bucket><Key>{}</Key><UploadId>{}</UploadId></InitiateMultipartUploadResult>",
+                BUCKET, key, upload_id
+            ))
+        }
+        ("PUT", false) if query.contains_key("partNumber") => {
+            let number: usize = query["partNumber"].parse().unwrap();
+            let Some((_, parts)) = state.uploads.get_mut(&query["uploadId"]) else {
+                return Response::error(404, "NoSuchUpload");
+            };
+            let etag = format!("\"{}\"", hex::encode(&Sha256::digest(&request.body)[..16]));
+            parts.insert(number, request.body);
+            Response::new(200).header("etag", &etag)
+        }
+        ("POST", false) if query.contains_key("uploadId") => {
+            let Some((content_type, parts)) = state.uploads.remove(&query["uploadId"]) else {
+                return Response::error(404, "NoSuchUpload");
+            };
+            let manifest = String::from_utf8(request.body).unwrap();
+            if manifest.matches("<Part>").count() != parts.len() {
+                return Response::new(200).body("<Error><Code>InvalidPart</Code><Message>InvalidPart</Message></Error>");
+            }
+            let data: Vec<u8> = parts.into_values().flatten().collect();
+            let sum = checksum(&data);
+            state.objects.insert(key.to_string(), (data, content_type, sum));
+            state.completed_multipart += 1;
+            Response::new(200).body("<CompleteMultipartUploadResult></CompleteMultipartUploadResult>")
+        }
+        ("DELETE", false) if query.contains_key("uploadId") => {
+            state.uploads.remove(&query["uploadId"]);
+            Response::new(204)
+        }
+        ("PUT", false) => {
+            let content_type = request.headers.get("content-type").cloned().unwrap_or_default();
+            let sum = checksum(&request.body);
+            state.objects.insert(key.to_string(), (request.body, content_type, sum));
+            Response::new(200).header("etag", "\"stand-in\"")
+        }
+        ("HEAD", false) => match state.objects.get(key) {
+            Some((data, _, _)) => Response::new(200).header("x-amz-meta-size", &data.len().to_string()),
+            None => Response::new(404),
+        },
+        ("GET", false) => {
+            let Some((data, content_type, sum)) = state.objects.get(key) else {
+                return Response::error(404, "NoSuchKey");
+            };
+            if let Some(range) = request.headers.get("range") {
+                let (start, end) = range.trim_start_matches("bytes=").split_once('-').unwrap();
+                let start: usize = start.parse().unwrap();
+                let end = end.parse::<usize>().map(|e| e + 1).unwrap_or(data.len()).min(data.len());
+                if start >= data.len() {
+                    return Response::error(416, "InvalidRange");
+                }
+                return Response::new(206).body(data[start..end].to_vec());
+            }
+            let mut response = Response::new(200).header("content-type", content_type);
+            if request.headers.get("x-amz-checksum-mode").map(String::as_str) == Some("ENABLED") {
+                response = response.header("x-amz-checksum-sha256", sum);
+            }
+            response.body(data.clone())
+        }
+        ("DELETE", false) => {
+            state.objects.remove(key);
+            Response::new(204)
+        }
+        _ => Response::error(405, "MethodNotAllowed"),
+    }
+}
+
+fn list(state: &State, query: &HashMap<String, String>) -> Response {
+    let prefix = query.get("prefix").cloned().unwrap_or_default();
+    let after = query.get("continuation-token").cloned().unwrap_or_default();
+    let matching: Vec<_> = state
+        .objects
+        .iter()
+        .filter(|(key, _)| key.starts_with(&prefix) && **key > after)
+        .collect();
+
+    let mut body = format!("<ListBucketResult><Name>{}</Name><Prefix>{}</Prefix>", BUCKET, prefix);
+    for (key, (data, _, _)) in matching.iter().take(PAGE_SIZE) {
+        body.push_str(&format!(
+            "<Contents><Key>{}</Key><Size>{}</Size><ETag>&quot;x&quot;</ETag><LastModified>2026-01-01T00:00:00.000Z</LastModified></Contents>",
+            key.replace('&', "&amp;"),
+            data.len()
+        ));
+    }
+    if matching.len() > PAGE_SIZE {
+        body.push_str(&format!(
+            "<IsTruncated>true</IsTruncated><NextContinuationToken>{}</NextContinuationToken>",
+            matching[PAGE_SIZE - 1].0
+        ));
+    } else {
+        body.push_str("<IsTruncated>false</IsTruncated>");
+    }
+    body.push_str("</ListBucketResult>");
+    Response::new(200).body(body)
+}
+
+#[tokio::test]
+async fn test_validate() {
+    let s3 = S3StandIn::start().await;
+    s3.client().validate().await.unwrap();
+
+    let mut config = s3.client().config().clone();
+    config.bucket = "missing".to_string();
+    let result = S3Client::with_config(config).unwrap().validate().await;
+    assert!(matches!(result, Err(S3Error::InvalidConfig(_))));
+}
+
+#[tokio::test]
+async fn test_validate_unreachable() {
+    let client = S3Client::with_config(S3Config {
+        access_key: "minioadmin".to_string(),
+        secret_key: "minioadmin".to_string(),
+        bucket: BUCKET.to_string(),
+        endpoint: Some("http://127.0.0.1:1".to_string()),
+        region: "us-east-1".to_string(),
+        path_style: true,
+    })
+    .unwrap();
+    assert!(matches!(client.validate().await, Err(S3Error::ConnectionFailed(_))));
+}
+
+#[tokio::test]
+async fn test_upload_download_roundtrip() {
+    let s3 = S3StandIn::start().await;
+    let client = s3.client();
+    let key = "documents/besluit 2026/ü.md";
+
+    assert!(!client.exists(key).await.unwrap());
+    client.upload(key, b"# Besluit".to_vec(), "text/markdown").await.unwrap();
+    assert!(client.exists(key).await.unwrap());
+    assert_eq!(client.download(key).await.unwrap(), b"# Besluit");
+
+    client.delete(key).await.unwrap();
+    assert!(!client.exists(key).await.unwrap());
+    assert!(matches!(client.download(key).await, Err(S3Error::NotFound(_))));
+    // Deleting again is not an error
+    client.delete(key).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_download_detects_corruption() {
+    let s3 = S3StandIn::start().await;
+    let client = s3.client();
+    client.upload("a.txt", b"inhoud".to_vec(), "text/plain").await.unwrap();
+    s3.corrupt("a.txt");
+
+    let result = client.download("a.txt").await;
+    assert!(matches!(result, Err(S3Error::DownloadFailed(msg)) if msg.contains("checksum")));
+}
+
+#[tokio::test]
+async fn test_multipart_upload() {
+    let s3 = S3StandIn::start().await;
+    let client = s3.client();
+    let data: Vec<u8> = (0..MULTIPART_THRESHOLD + 1_000_000).map(|i| (i % 251) as u8).collect();
+
+    client.upload("large.pdf", data.clone(), "application/pdf").await.unwrap();
+    assert_eq!(s3.completed_multipart(), 1);
+    assert_eq!(client.download("large.pdf").await.unwrap(), data);
+
+    // The document limit still applies to upload, not to put_object
+    let archive = vec![7u8; MAX_DOCUMENT_SIZE + 1];
+    assert!(matches!(
+        client.upload("archive.zip", archive.clone(), "application/zip").await,
+        Err(S3Error::PayloadTooLarge { .. })
+    ));
+    client.put_object("archive.zip", archive.clone(), "application/zip").await.unwrap();
+    assert_eq!(s3.completed_multipart(), 2);
+    assert_eq!(client.download("archive.zip").await.unwrap().len(), archive.len());
+}
+
+#[tokio::test]
+async fn test_ranged_download() {
+    let s3 = S3StandIn::start().await;
+    let client = s3.client();
+    client.upload("r.txt", b"0123456789".to_vec(), "text/plain").await.unwrap();
+
+    assert_eq!(client.download_range("r.txt", 2..5).await.unwrap(), b"234");
+    assert_eq!(client.download_range("r.txt", 7..).await.unwrap(), b"789");
+    assert_eq!(client.download_range("r.txt", ..=1).await.unwrap(), b"01");
+    assert!(client.download_range("r.txt", 3..3).await.unwrap().is_empty());
+    assert!(matches!(
+        client.download_range("r.txt", 20..).await,
+        Err(S3Error::HttpError { code: 416, .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_list_by_prefix() {
+    let s3 = S3StandIn::start().await;
+    let client = s3.client();
+    for key in ["docs/a.md", "docs/b&c.md", "docs/d.md", "docs/e.md", "other/x.md"] {
+        client.upload(key, key.as_bytes().to_vec(), "text/markdown").await.unwrap();
+    }
+
+    let objects = client.list("docs/").await.unwrap();
+    let keys: Vec<_> = objects.iter().map(|o| o.key.as_str()).collect();
+    assert_eq!(keys, ["docs/a.md", "docs/b&c.md", "docs/d.md", "docs/e.md"]);
+    assert_eq!(objects[1].size, "docs/b&c.md".len() as u64);
+    assert_eq!(objects[0].etag.as_deref(), Some("x"));
+    assert!(objects[0].last_modified.is_some());
+
+    assert!(client.list("none/").await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_version_service_persists_to_s3() {
+    let s3 = S3StandIn::start().await;
+    let client = s3.client();
+    let document_id = Uuid::new_v4();
+
+    let service = VersionService::new_with_storage(s3.client(), 5);
+    let version = service
+        .create_version(document_id, "# Eerste versie", Uuid::new_v4(), "Eerste versie")
+        .await
+        .unwrap();
+
+    let stored = client.list(&format!("documents/{}/", document_id)).await.unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].key, version.storage_key);
+    assert!(StorageBackend::exists(&client, &version.storage_key).await.unwrap());
+}
+
+#[tokio::test]
+#[ignore = "Requires MinIO (S3_ENDPOINT, S3_ACCESS_KEY, S3_SECRET_KEY, S3_BUCKET)"]
+async fn test_against_minio() {
+    let client = S3Client::new_from_env().unwrap();
+    client.validate().await.unwrap();
+
+    let prefix = format!("iou-core-test/{}/", Uuid::new_v4());
+    let small = format!("{}small.md", prefix);
+    let large = format!("{}large.bin", prefix);
+    let data: Vec<u8> = (0..MULTIPART_THRESHOLD + 1).map(|i| (i % 256) as u8).collect();
+
+    client.upload(&small, b"# MinIO".to_vec(), "text/markdown").await.unwrap();
+    client.upload(&large, data.clone(), "application/octet-stream").await.unwrap();
+    assert_eq!(client.download(&small).await.unwrap(), b"# MinIO");
+    assert_eq!(client.download(&large).await.unwrap(), data);
+    assert_eq!(client.download_range(&large, 256..260).await.unwrap(), [0, 1, 2, 3]);
+    assert_eq!(client.list(&prefix).await.unwrap().len(), 2);
+
+    client.delete(&small).await.unwrap();
+    client.delete(&large).await.unwrap();
+    assert!(client.list(&prefix).await.unwrap().is_empty());
+}
@@ -1,6 +1,6 @@
 //! Version storage service tests
 
-use iou_core::versions::{VersionService, VersionError};
+use iou_core::versions::{SaveOutcome, VersionService, VersionError};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -62,6 +62,11 @@ impl iou_core::versions::StorageBackend for MockStorage {
     async fn exists(&self, key: &str) -> Result<bool, iou_core::storage::S3Error> {
         Ok(self.storage.read().await.contains_key(key))
     }
+
+    async fn delete(&self, key: &str) -> Result<(), iou_core::storage::S3Error> {
+        self.storage.write().await.remove(key);
+        Ok(())
+    }
 }
 
 #[tokio::test]
@@ -525,3 +530,166 @@ async fn version_record_contains_all_required_fields() {
     assert!(!version.is_compressed); // First version not compressed
     assert_eq!(version.parent_version_id, None); // First version has no parent
 }
+
+#[tokio::test]
+async fn old_versions_are_stored_as_reverse_deltas_with_keyframes() {
+    let mock_storage = MockStorage::new();
+    let service = VersionService::new_with_storage(mock_storage.clone(), 2).with_keyframe_interval(3);
+    let document_id = random_document_id();
+    let user_id = random_user_id();
+
+    let mut contents = Vec::new();
+    let mut versions = Vec::new();
+    let mut text = String::from("# Beleidsnota\n\n## Aanleiding\n\nTekst.\n");
+    for i in 1..=7 {
+        text.push_str(&format!("\n## Paragraaf {}\n\nInhoud {}.\n", i, i));
+        contents.push(text.clone());
+        versions.push(
+            service
+                .create_version(document_id, &text, user_id, &format!("Revisie {}", i))
+                .await
+                .unwrap(),
+        );
+    }
+
+    let records = service.list_versions(document_id).await.unwrap();
+    let format_of = |n: i32| records.iter().find(|v| v.version_number == n).unwrap().format.clone();
+    // v7 and v6 stay full, v3 is a keyframe, the rest are deltas
+    assert_eq!(format_of(7), "markdown");
+    assert_eq!(format_of(6), "markdown");
+    assert_eq!(format_of(3), "markdown");
+    for n in [1, 2, 4, 5] {
+        assert_eq!(format_of(n), "markdown+delta", "v{}", n);
+    }
+
+    // Full copies of compressed versions are gone
+    assert!(!mock_storage.contains_key(&versions[0].storage_key).await);
+    assert!(mock_storage.contains_key(&format!("{}.gz", versions[0].storage_key)).await);
+
+    for (version, content) in versions.iter().zip(&contents) {
+        assert_eq!(&service.get_version_content(version.id).await.unwrap(), content);
+    }
+}
+
+#[tokio::test]
+async fn create_version_fills_diff_summary() {
+    let service = VersionService::new_with_storage(MockStorage::new(), 5);
+    let document_id = random_document_id();
+    let user_id = random_user_id();
+
+    let v1 = service
+        .create_version(document_id, "# Nota\n\n## Aanleiding\n\nTekst.\n", user_id, "Eerste")
+        .await
+        .unwrap();
+    let summary: iou_core::versions::DiffSummary =
+        serde_json::from_value(v1.diff_summary.unwrap()).unwrap();
+    assert_eq!(summary.lines_added, 5);
+    assert_eq!(summary.sections_added, vec!["Nota", "Aanleiding"]);
+
+    let v2 = service
+        .create_version(document_id, "# Nota\n\n## Aanleiding\n\nNieuwe tekst.\n\n## Besluit\n", user_id, "Tweede")
+        .await
+        .unwrap();
+    let summary: iou_core::versions::DiffSummary =
+        serde_json::from_value(v2.diff_summary.unwrap()).unwrap();
+    assert_eq!(summary.lines_changed, 1);
+    assert_eq!(summary.lines_added, 2);
+    assert_eq!(summary.lines_removed, 0);
+    assert_eq!(summary.sections_added, vec!["Besluit"]);
+    assert_eq!(summary.sections_changed, vec!["Aanleiding"]);
+}
+
+#[tokio::test]
+async fn save_version_on_current_base_creates_version() {
+    let service = VersionService::new_with_storage(MockStorage::new(), 5);
+    let document_id = random_document_id();
+    let user_id = random_user_id();
+
+    let v1 = match service.save_version(document_id, None, "a\nb\n", user_id, "v1").await.unwrap() {
+        SaveOutcome::Created { version } => version,
+        other => panic!("expected Created, got {:?}", other),
+    };
+    let outcome = service
+        .save_version(document_id, Some(v1.id), "a\nB\n", user_id, "v2")
+        .await
+        .unwrap();
+    assert!(matches!(outcome, SaveOutcome::Created { ref version } if version.version_number == 2));
+}
+
+#[tokio::test]
+async fn save_version_on_stale_base_merges_non_overlapping_edits() {
+    let service = VersionService::new_with_storage(MockStorage::new(), 5);
+    let document_id = random_document_id();
+    let alice = random_user_id();
+    let bob = random_user_id();
+
+    let base = service
+        .create_version(document_id, "# Nota\n\nAanleiding.\n\nBesluit.\n", alice, "v1")
+        .await
+        .unwrap();
+    let alice_version = service
+        .save_version(document_id, Some(base.id), "# Nota\n\nAanleiding, herzien.\n\nBesluit.\n", alice, "Aanleiding")
+        .await
+        .unwrap();
+    let SaveOutcome::Created { version: alice_version } = alice_version else {
+        panic!("first save must not merge");
+    };
+
+    let outcome = service
+        .save_version(document_id, Some(base.id), "# Nota\n\nAanleiding.\n\nBesluit van het college.\n", bob, "Besluit")
+        .await
+        .unwrap();
+    let SaveOutcome::Merged { version, merged_with } = outcome else {
+        panic!("expected Merged, got {:?}", outcome);
+    };
+    assert_eq!(merged_with, alice_version.id);
+    assert_eq!(version.parent_version_id, Some(alice_version.id));
+    assert_eq!(
+        service.get_version_content(version.id).await.unwrap(),
+        "# Nota\n\nAanleiding, herzien.\n\nBesluit van het college.\n"
+    );
+}
+
+#[tokio::test]
+async fn save_version_on_stale_base_returns_conflicts_without_saving() {
+    let service = VersionService::new_with_storage(MockStorage::new(), 5);
+    let document_id = random_document_id();
+    let user_id = random_user_id();
+
+    let base = service.create_version(document_id, "bedrag: 100\n", user_id, "v1").await.unwrap();
+    let current = service.create_version(document_id, "bedrag: 150\n", user_id, "v2").await.unwrap();
+
+    let outcome = service
+        .save_version(document_id, Some(base.id), "bedrag: 200\n", user_id, "v3")
+        .await
+        .unwrap();
+    let SaveOutcome::Conflict(conflict) = outcome else {
+        panic!("expected Conflict, got {:?}", outcome);
+    };
+    assert_eq!(conflict.current_version_id, current.id);
+    assert_eq!(conflict.base_version_id, Some(base.id));
+    assert_eq!(conflict.conflicts.len(), 1);
+    assert_eq!(conflict.conflicts[0].ours, "bedrag: 150\n");
+    assert_eq!(conflict.conflicts[0].theirs, "bedrag: 200\n");
+    assert!(conflict.merged.contains("<<<<<<< version 2\n"));
+    assert_eq!(service.list_versions(document_id).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn save_version_rejects_base_of_other_document() {
+    let service = VersionService::new_with_storage(MockStorage::new(), 5);
+    let user_id = random_user_id();
+    let document_id = random_document_id();
+    let other = service.create_version(random_document_id(), "x\n", user_id, "v1").await.unwrap();
+    service.create_version(document_id, "y\n", user_id, "v1").await.unwrap();
+
+    let result = service
+        .save_version(document_id, Some(other.id), "z\n", user_id, "v2")
+        .await;
+    assert!(matches!(result, Err(VersionError::InvalidInput(_))));
+
+    let result = service
+        .save_version(random_document_id(), Some(other.id), "z\n", user_id, "v1")
+        .await;
+    assert!(matches!(result, Err(VersionError::InvalidInput(_))));
+}
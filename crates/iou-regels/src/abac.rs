@@ -0,0 +1,982 @@
+//! Attribute-based access control (ABAC)
+//!
+//! Access decisions are made by declarative policies instead of a fixed
+//! role matrix. A [`Policy`] is a YAML document with rules that match on
+//! the action, on subject attributes (roles, level of assurance,
+//! department, delegations) and on resource attributes (kind,
+//! classification, domain, Woo status, purpose), optionally narrowed by
+//! a FEEL condition:
+//!
+//! ```yaml
+//! tenant: provincie-utrecht
+//! version: "2025-03"
+//! remove: [woo-publisher]
+//! rules:
+//!   - id: geheim-alleen-hoog
+//!     description: Geheime stukken alleen met LoA hoog
+//!     effect: deny
+//!     actions: ["object_*"]
+//!     resource: { classifications: [geheim] }
+//!     condition: subject.loa_level < 2
+//! tests:
+//!   - name: substantieel mag geen geheim stuk lezen
+//!     subject: { roles: [domain_viewer], loa: substantial }
+//!     action: object_read
+//!     resource: { kind: information_object, classification: geheim }
+//!     expect: deny
+//! ```
+//!
+//! The [`PolicyEngine`] holds one default policy and a policy per tenant.
+//! A tenant policy extends the defaults: a rule with the id of a default
+//! rule replaces it, and `remove` drops default rules. Provinces with
+//! slightly different rules only ship the difference.
+//!
+//! Decisions are deny-overrides with default deny: one applicable deny
+//! rule denies, otherwise one applicable permit rule permits. A
+//! condition that cannot be evaluated makes a deny rule apply (fail
+//! closed) and a permit rule not apply. Every [`Decision`] carries a
+//! trace of the rules for the action and why each did or did not apply,
+//! which [`Decision::reason`] turns into a "why denied" explanation.
+//!
+//! The `tests` of a policy run when it is loaded; a policy whose tests
+//! fail is rejected and the previous one stays active.
+//!
+//! # FEEL variables
+//!
+//! Conditions see `action`, `subject.id`, `subject.roles`, `subject.loa`,
+//! `subject.loa_level` (0–2), `subject.department`, `subject.delegated`,
+//! `subject.delegators`, `resource.kind`, `resource.id`,
+//! `resource.classification`, `resource.classification_level` (0–3),
+//! `resource.domain`, `resource.woo_status` and `resource.purpose`, plus
+//! free attributes as `subject.<naam>` and `resource.<naam>`. Attributes
+//! without a value are not defined, so a condition that uses them
+//! cannot be evaluated.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+use iou_core::compliance::{Classification, WooDisclosureClass};
+use iou_core::delegation::Delegation;
+use iou_core::tenancy::{LoA, TenantContext};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::dmn::DecisionValue;
+use crate::dmn::feel::{Expression, Value};
+
+/// Policy errors
+#[derive(Debug, Error)]
+pub enum PolicyError {
+    #[error("Invalid policy document: {0}")]
+    Parse(String),
+
+    #[error("Invalid rule '{rule}': {reason}")]
+    InvalidRule { rule: String, reason: String },
+
+    #[error("Rule id '{0}' is used more than once")]
+    DuplicateRule(String),
+
+    #[error("Policy removes unknown default rule '{0}'")]
+    UnknownRule(String),
+
+    #[error("Policy is for tenant '{found}', not '{expected}'")]
+    TenantMismatch { expected: String, found: String },
+
+    #[error("{} policy test(s) failed: {}", .0.failed.len(), .0.failed_names().join(", "))]
+    TestsFailed(TestReport),
+}
+
+/// Effect of a rule or decision
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Effect {
+    Permit,
+    Deny,
+}
+
+/// Subject conditions of a rule; empty lists match everyone
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubjectMatch {
+    /// Any of these roles
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<String>,
+    /// Minimum level of assurance
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_loa: Option<LoA>,
+    /// Any of these departments
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub departments: Vec<String>,
+    /// Whether the subject must (or must not) act under a delegation for the resource
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delegated: Option<bool>,
+}
+
+/// Resource conditions of a rule; empty lists match every resource
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourceMatch {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub kinds: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub classifications: Vec<Classification>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub domains: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub woo_status: Vec<WooDisclosureClass>,
+    /// Purpose ids, e.g. `P001`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub purposes: Vec<String>,
+}
+
+/// Rule as written in a policy document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    pub effect: Effect,
+    /// Action names; `*` matches every action and `object_*` every action with that prefix
+    pub actions: Vec<String>,
+    #[serde(default)]
+    pub subject: SubjectMatch,
+    #[serde(default)]
+    pub resource: ResourceMatch,
+    /// FEEL expression that must evaluate to `true`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+}
+
+/// Test case embedded in a policy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyTest {
+    pub name: String,
+    #[serde(default)]
+    pub subject: Subject,
+    pub action: String,
+    #[serde(default)]
+    pub resource: Resource,
+    pub expect: Effect,
+    /// Rule that must decide, if it matters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+}
+
+/// Policy document
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Policy {
+    /// Tenant the policy is written for; `None` for the defaults
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Default rules this tenant policy drops
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub remove: Vec<String>,
+    pub rules: Vec<Rule>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tests: Vec<PolicyTest>,
+}
+
+impl Policy {
+    /// Parse a YAML (or JSON) policy document
+    pub fn from_yaml(source: &str) -> Result<Self, PolicyError> {
+        serde_yaml::from_str(source).map_err(|e| PolicyError::Parse(e.to_string()))
+    }
+}
+
+/// Who asks for access
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Subject {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub roles: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loa: Option<LoA>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub department: Option<String>,
+    /// Delegations the subject received
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub delegations: Vec<Delegation>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub attributes: HashMap<String, DecisionValue>,
+}
+
+impl Subject {
+    pub fn with_roles<I, S>(roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self { roles: roles.into_iter().map(Into::into).collect(), ..Default::default() }
+    }
+
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub fn with_loa(mut self, loa: LoA) -> Self {
+        self.loa = Some(loa);
+        self
+    }
+
+    pub fn with_department(mut self, department: impl Into<String>) -> Self {
+        self.department = Some(department.into());
+        self
+    }
+
+    pub fn with_delegations(mut self, delegations: Vec<Delegation>) -> Self {
+        self.delegations = delegations;
+        self
+    }
+
+    pub fn with_attribute(mut self, name: impl Into<String>, value: impl Into<DecisionValue>) -> Self {
+        self.attributes.insert(name.into(), value.into());
+        self
+    }
+
+    /// Active delegations that cover `resource`
+    fn delegations_for<'a>(&'a self, resource: &'a Resource) -> impl Iterator<Item = &'a Delegation> + 'a {
+        let document_id = resource.id.as_deref().and_then(|id| Uuid::parse_str(id).ok());
+        self.delegations.iter().filter(move |d| {
+            d.is_currently_active()
+                && match document_id {
+                    Some(id) => d.applies_to_document(id, &resource.kind),
+                    None => d.document_id.is_none() && d.applies_to_document_type(&resource.kind),
+                }
+        })
+    }
+}
+
+impl From<&TenantContext> for Subject {
+    fn from(tenant: &TenantContext) -> Self {
+        Self::with_roles(tenant.roles.iter().cloned())
+            .with_id(tenant.holder_did.clone())
+            .with_loa(tenant.loa)
+    }
+}
+
+/// What access is asked for; the default is "no particular resource"
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Resource {
+    /// Resource kind, e.g. `information_object` or `domain`
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub classification: Option<Classification>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub woo_status: Option<WooDisclosureClass>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub attributes: HashMap<String, DecisionValue>,
+}
+
+impl Resource {
+    pub fn new(kind: impl Into<String>) -> Self {
+        Self { kind: kind.into(), ..Default::default() }
+    }
+
+    pub fn with_id(mut self, id: impl ToString) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    pub fn with_classification(mut self, classification: Classification) -> Self {
+        self.classification = Some(classification);
+        self
+    }
+
+    pub fn with_domain(mut self, domain: impl ToString) -> Self {
+        self.domain = Some(domain.to_string());
+        self
+    }
+
+    pub fn with_woo_status(mut self, status: WooDisclosureClass) -> Self {
+        self.woo_status = Some(status);
+        self
+    }
+
+    pub fn with_purpose(mut self, purpose: impl Into<String>) -> Self {
+        self.purpose = Some(purpose.into());
+        self
+    }
+
+    pub fn with_attribute(mut self, name: impl Into<String>, value: impl Into<DecisionValue>) -> Self {
+        self.attributes.insert(name.into(), value.into());
+        self
+    }
+}
+
+/// How one rule fared in a decision
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum RuleOutcome {
+    Applied,
+    NotApplicable { reason: String },
+    /// The condition could not be evaluated
+    Error { message: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleTrace {
+    pub rule: String,
+    pub effect: Effect,
+    #[serde(flatten)]
+    pub outcome: RuleOutcome,
+}
+
+/// Access decision with the rules that led to it
+#[derive(Debug, Clone, Serialize)]
+pub struct Decision {
+    pub effect: Effect,
+    pub action: String,
+    /// Deciding rule; `None` when no rule applied (default deny)
+    pub rule: Option<String>,
+    /// Human-readable explanation, see [`Decision::reason`]
+    pub reason: String,
+    /// Every rule for the action, in policy order
+    pub trace: Vec<RuleTrace>,
+}
+
+impl Decision {
+    pub fn is_permitted(&self) -> bool {
+        self.effect == Effect::Permit
+    }
+
+    /// Why access was granted or denied
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+
+    fn explain(effect: Effect, action: &str, rule: Option<&CompiledRule>, trace: &[RuleTrace]) -> String {
+        match (effect, rule) {
+            (Effect::Permit, Some(rule)) => format!("'{}' permitted by rule '{}'", action, rule.spec.id),
+            (Effect::Deny, Some(rule)) => {
+                let mut reason = format!("'{}' denied by rule '{}'", action, rule.spec.id);
+                if !rule.spec.description.is_empty() {
+                    reason.push_str(&format!(" ({})", rule.spec.description));
+                }
+                if let Some(RuleTrace { outcome: RuleOutcome::Error { message }, .. }) =
+                    trace.iter().find(|t| t.rule == rule.spec.id)
+                {
+                    reason.push_str(&format!(": condition failed: {}", message));
+                }
+                reason
+            }
+            (_, None) if trace.is_empty() => format!("no rule covers '{}'", action),
+            (_, None) => {
+                let why: Vec<String> = trace
+                    .iter()
+                    .filter(|t| t.effect == Effect::Permit)
+                    .map(|t| match &t.outcome {
+                        RuleOutcome::NotApplicable { reason } => format!("{}: {}", t.rule, reason),
+                        RuleOutcome::Error { message } => format!("{}: {}", t.rule, message),
+                        RuleOutcome::Applied => t.rule.clone(),
+                    })
+                    .collect();
+                if why.is_empty() {
+                    format!("no rule permits '{}'", action)
+                } else {
+                    format!("no rule permits '{}' ({})", action, why.join("; "))
+                }
+            }
+        }
+    }
+}
+
+/// Failed policy test
+#[derive(Debug, Clone, Serialize)]
+pub struct TestFailure {
+    pub test: String,
+    pub expected: Effect,
+    pub actual: Effect,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_rule: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual_rule: Option<String>,
+    pub reason: String,
+}
+
+/// Outcome of running policy tests
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TestReport {
+    pub passed: usize,
+    pub failed: Vec<TestFailure>,
+}
+
+impl TestReport {
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    fn failed_names(&self) -> Vec<&str> {
+        self.failed.iter().map(|f| f.test.as_str()).collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    spec: Rule,
+    condition: Option<Expression>,
+}
+
+impl CompiledRule {
+    fn compile(spec: Rule) -> Result<Self, PolicyError> {
+        let invalid = |reason: String| PolicyError::InvalidRule { rule: spec.id.clone(), reason };
+        if spec.id.trim().is_empty() {
+            return Err(PolicyError::Parse("Rule without id".into()));
+        }
+        if spec.actions.is_empty() {
+            return Err(invalid("no actions".into()));
+        }
+        let condition = match &spec.condition {
+            Some(source) => Some(Expression::parse(source).map_err(|e| invalid(e.to_string()))?),
+            None => None,
+        };
+        Ok(Self { spec, condition })
+    }
+
+    fn covers(&self, action: &str) -> bool {
+        self.spec.actions.iter().any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => action.starts_with(prefix),
+            None => pattern == action,
+        })
+    }
+
+    /// Why the rule does not apply, if it does not
+    fn mismatch(&self, subject: &Subject, resource: &Resource, delegated: bool) -> Option<String> {
+        let s = &self.spec.subject;
+        if !s.roles.is_empty() && !subject.roles.iter().any(|r| s.roles.contains(r)) {
+            return Some(format!("requires one of the roles {}", s.roles.join(", ")));
+        }
+        if let Some(min) = s.min_loa
+            && subject.loa.is_none_or(|loa| loa < min)
+        {
+            return Some(format!("requires LoA {} or higher", min.as_str()));
+        }
+        if !s.departments.is_empty() && !subject.department.as_ref().is_some_and(|d| s.departments.contains(d)) {
+            return Some(format!("requires department {}", s.departments.join(", ")));
+        }
+        if let Some(required) = s.delegated
+            && required != delegated
+        {
+            return Some(if required { "requires a delegation" } else { "not under a delegation" }.to_string());
+        }
+
+        let r = &self.spec.resource;
+        if !r.kinds.is_empty() && !r.kinds.contains(&resource.kind) {
+            return Some(format!("resource kind is not {}", r.kinds.join(", ")));
+        }
+        if !r.classifications.is_empty() && !resource.classification.is_some_and(|c| r.classifications.contains(&c)) {
+            let names: Vec<String> = r.classifications.iter().map(|c| c.to_string()).collect();
+            return Some(format!("classification is not {}", names.join(", ")));
+        }
+        if !r.domains.is_empty() && !resource.domain.as_ref().is_some_and(|d| r.domains.contains(d)) {
+            return Some(format!("domain is not {}", r.domains.join(", ")));
+        }
+        if !r.woo_status.is_empty() && !resource.woo_status.is_some_and(|w| r.woo_status.contains(&w)) {
+            let names: Vec<String> = r.woo_status.iter().map(serde_name).collect();
+            return Some(format!("Woo status is not {}", names.join(", ")));
+        }
+        if !r.purposes.is_empty() && !resource.purpose.as_ref().is_some_and(|p| r.purposes.contains(p)) {
+            return Some(format!("purpose is not {}", r.purposes.join(", ")));
+        }
+        None
+    }
+
+    fn evaluate(
+        &self,
+        subject: &Subject,
+        resource: &Resource,
+        delegated: bool,
+        variables: &HashMap<String, DecisionValue>,
+    ) -> RuleOutcome {
+        if let Some(reason) = self.mismatch(subject, resource, delegated) {
+            return RuleOutcome::NotApplicable { reason };
+        }
+        let Some(condition) = &self.condition else {
+            return RuleOutcome::Applied;
+        };
+        match condition.evaluate(variables) {
+            Ok(Value::Boolean(true)) => RuleOutcome::Applied,
+            Ok(Value::Boolean(false) | Value::Null) => RuleOutcome::NotApplicable {
+                reason: format!("condition `{}` does not hold", condition.source()),
+            },
+            Ok(other) => RuleOutcome::Error {
+                message: format!("condition `{}` is a {}, not a boolean", condition.source(), other.type_name()),
+            },
+            Err(e) => RuleOutcome::Error { message: e.to_string() },
+        }
+    }
+}
+
+/// Effective rules of one policy (defaults merged with a tenant policy)
+#[derive(Debug)]
+struct CompiledPolicy {
+    source: Policy,
+    rules: Vec<CompiledRule>,
+}
+
+impl CompiledPolicy {
+    fn compile(source: Policy, defaults: Option<&CompiledPolicy>) -> Result<Self, PolicyError> {
+        let mut own = Vec::with_capacity(source.rules.len());
+        let mut ids = HashSet::new();
+        for rule in &source.rules {
+            if !ids.insert(rule.id.as_str()) {
+                return Err(PolicyError::DuplicateRule(rule.id.clone()));
+            }
+            own.push(CompiledRule::compile(rule.clone())?);
+        }
+
+        let mut rules = Vec::new();
+        if let Some(defaults) = defaults {
+            for removed in &source.remove {
+                if !defaults.rules.iter().any(|r| &r.spec.id == removed) {
+                    return Err(PolicyError::UnknownRule(removed.clone()));
+                }
+            }
+            // Overridden default rules keep their position
+            for rule in &defaults.rules {
+                if source.remove.contains(&rule.spec.id) {
+                    continue;
+                }
+                match own.iter().position(|r| r.spec.id == rule.spec.id) {
+                    Some(index) => rules.push(own.remove(index)),
+                    None => rules.push(rule.clone()),
+                }
+            }
+        } else if let Some(removed) = source.remove.first() {
+            return Err(PolicyError::UnknownRule(removed.clone()));
+        }
+        rules.extend(own);
+
+        Ok(Self { source, rules })
+    }
+
+    fn decide(&self, subject: &Subject, action: &str, resource: &Resource) -> Decision {
+        let delegations: Vec<&Delegation> = subject.delegations_for(resource).collect();
+        let delegated = !delegations.is_empty();
+        let variables = variables(subject, action, resource, &delegations);
+
+        let mut trace = Vec::new();
+        let mut deny = None;
+        let mut permit = None;
+        for rule in self.rules.iter().filter(|r| r.covers(action)) {
+            let outcome = rule.evaluate(subject, resource, delegated, &variables);
+            match (&outcome, rule.spec.effect) {
+                (RuleOutcome::Applied | RuleOutcome::Error { .. }, Effect::Deny) => {
+                    deny.get_or_insert(rule);
+                }
+                (RuleOutcome::Applied, Effect::Permit) => {
+                    permit.get_or_insert(rule);
+                }
+                _ => {}
+            }
+            trace.push(RuleTrace { rule: rule.spec.id.clone(), effect: rule.spec.effect, outcome });
+        }
+
+        let (effect, rule) = match (deny, permit) {
+            (Some(rule), _) => (Effect::Deny, Some(rule)),
+            (None, Some(rule)) => (Effect::Permit, Some(rule)),
+            (None, None) => (Effect::Deny, None),
+        };
+        Decision {
+            effect,
+            action: action.to_string(),
+            rule: rule.map(|r| r.spec.id.clone()),
+            reason: Decision::explain(effect, action, rule, &trace),
+            trace,
+        }
+    }
+
+    fn run_tests(&self) -> TestReport {
+        let mut report = TestReport::default();
+        for test in &self.source.tests {
+            let decision = self.decide(&test.subject, &test.action, &test.resource);
+            let rule_ok = test.rule.is_none() || test.rule == decision.rule;
+            if decision.effect == test.expect && rule_ok {
+                report.passed += 1;
+            } else {
+                report.failed.push(TestFailure {
+                    test: test.name.clone(),
+                    expected: test.expect,
+                    actual: decision.effect,
+                    expected_rule: test.rule.clone(),
+                    actual_rule: decision.rule,
+                    reason: decision.reason,
+                });
+            }
+        }
+        report
+    }
+}
+
+/// FEEL variables for a request
+fn variables(
+    subject: &Subject,
+    action: &str,
+    resource: &Resource,
+    delegations: &[&Delegation],
+) -> HashMap<String, DecisionValue> {
+    let mut vars = HashMap::new();
+    for (name, value) in &subject.attributes {
+        vars.insert(format!("subject.{}", name), value.clone());
+    }
+    for (name, value) in &resource.attributes {
+        vars.insert(format!("resource.{}", name), value.clone());
+    }
+
+    vars.insert("action".into(), action.to_string().into());
+    let strings = |items: &[String]| DecisionValue::Array(items.iter().cloned().map(DecisionValue::String).collect());
+
+    if let Some(id) = &subject.id {
+        vars.insert("subject.id".into(), id.clone().into());
+    }
+    vars.insert("subject.roles".into(), strings(&subject.roles));
+    if let Some(loa) = subject.loa {
+        vars.insert("subject.loa".into(), loa.as_str().to_string().into());
+        vars.insert("subject.loa_level".into(), (loa as i64).into());
+    }
+    if let Some(department) = &subject.department {
+        vars.insert("subject.department".into(), department.clone().into());
+    }
+    vars.insert("subject.delegated".into(), (!delegations.is_empty()).into());
+    let delegators: Vec<String> = delegations.iter().map(|d| d.from_user_id.to_string()).collect();
+    vars.insert("subject.delegators".into(), strings(&delegators));
+
+    vars.insert("resource.kind".into(), resource.kind.clone().into());
+    if let Some(id) = &resource.id {
+        vars.insert("resource.id".into(), id.clone().into());
+    }
+    if let Some(classification) = resource.classification {
+        vars.insert("resource.classification".into(), classification.to_string().into());
+        vars.insert("resource.classification_level".into(), classification_level(classification).into());
+    }
+    if let Some(domain) = &resource.domain {
+        vars.insert("resource.domain".into(), domain.clone().into());
+    }
+    if let Some(status) = &resource.woo_status {
+        vars.insert("resource.woo_status".into(), serde_name(status).into());
+    }
+    if let Some(purpose) = &resource.purpose {
+        vars.insert("resource.purpose".into(), purpose.clone().into());
+    }
+    vars
+}
+
+fn classification_level(classification: Classification) -> i64 {
+    match classification {
+        Classification::Openbaar => 0,
+        Classification::Intern => 1,
+        Classification::Vertrouwelijk => 2,
+        Classification::Geheim => 3,
+    }
+}
+
+/// Name of a unit variant as it appears in policy documents
+fn serde_name<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Default policy plus a policy per tenant
+///
+/// Tenant policies can be replaced at runtime; decisions in flight keep
+/// the policy they started with.
+#[derive(Debug)]
+pub struct PolicyEngine {
+    defaults: Arc<CompiledPolicy>,
+    tenants: RwLock<HashMap<String, Arc<CompiledPolicy>>>,
+}
+
+impl PolicyEngine {
+    /// Engine with `defaults`, whose tests must pass
+    pub fn new(defaults: Policy) -> Result<Self, PolicyError> {
+        let compiled = CompiledPolicy::compile(defaults, None)?;
+        let report = compiled.run_tests();
+        if !report.is_success() {
+            return Err(PolicyError::TestsFailed(report));
+        }
+        Ok(Self { defaults: Arc::new(compiled), tenants: RwLock::new(HashMap::new()) })
+    }
+
+    pub fn from_yaml(defaults: &str) -> Result<Self, PolicyError> {
+        Self::new(Policy::from_yaml(defaults)?)
+    }
+
+    pub fn default_policy(&self) -> &Policy {
+        &self.defaults.source
+    }
+
+    pub fn tenant_policy(&self, tenant: &str) -> Option<Policy> {
+        self.tenants.read().unwrap().get(tenant).map(|p| p.source.clone())
+    }
+
+    /// Tenants with their own policy, sorted
+    pub fn tenants(&self) -> Vec<String> {
+        let mut tenants: Vec<String> = self.tenants.read().unwrap().keys().cloned().collect();
+        tenants.sort();
+        tenants
+    }
+
+    fn compile_for(&self, tenant: &str, policy: Policy) -> Result<CompiledPolicy, PolicyError> {
+        if let Some(found) = &policy.tenant
+            && found != tenant
+        {
+            return Err(PolicyError::TenantMismatch { expected: tenant.to_string(), found: found.clone() });
+        }
+        CompiledPolicy::compile(policy, Some(&self.defaults))
+    }
+
+    /// Run the tests of a tenant policy without activating it
+    pub fn test_policy(&self, tenant: &str, policy: Policy) -> Result<TestReport, PolicyError> {
+        Ok(self.compile_for(tenant, policy)?.run_tests())
+    }
+
+    /// Activate a tenant policy if its tests pass
+    pub fn set_tenant_policy(&self, tenant: &str, policy: Policy) -> Result<TestReport, PolicyError> {
+        let compiled = self.compile_for(tenant, policy)?;
+        let report = compiled.run_tests();
+        if !report.is_success() {
+            return Err(PolicyError::TestsFailed(report));
+        }
+        self.tenants.write().unwrap().insert(tenant.to_string(), Arc::new(compiled));
+        Ok(report)
+    }
+
+    /// Drop a tenant policy; the tenant falls back to the defaults
+    pub fn remove_tenant_policy(&self, tenant: &str) -> Option<Policy> {
+        self.tenants.write().unwrap().remove(tenant).map(|p| p.source.clone())
+    }
+
+    /// Decide on `action` under the tenant's policy, or the defaults
+    pub fn decide(&self, tenant: Option<&str>, subject: &Subject, action: &str, resource: &Resource) -> Decision {
+        let policy = tenant
+            .and_then(|t| self.tenants.read().unwrap().get(t).cloned())
+            .unwrap_or_else(|| self.defaults.clone());
+        policy.decide(subject, action, resource)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    const DEFAULTS: &str = r#"
+rules:
+  - id: admin
+    effect: permit
+    actions: ["*"]
+    subject: { roles: [admin] }
+  - id: viewer
+    effect: permit
+    actions: [domain_read, object_read]
+    subject: { roles: [domain_viewer] }
+  - id: editor
+    effect: permit
+    actions: ["object_*"]
+    subject: { roles: [object_editor] }
+tests:
+  - name: admin mag alles
+    subject: { roles: [admin] }
+    action: user_manage
+    expect: permit
+  - name: viewer mag niet wijzigen
+    subject: { roles: [domain_viewer] }
+    action: object_update
+    expect: deny
+"#;
+
+    fn engine() -> PolicyEngine {
+        PolicyEngine::from_yaml(DEFAULTS).unwrap()
+    }
+
+    fn object(classification: Classification) -> Resource {
+        Resource::new("information_object").with_classification(classification)
+    }
+
+    #[test]
+    fn test_default_policy() {
+        let engine = engine();
+        let viewer = Subject::with_roles(["domain_viewer"]);
+
+        let decision = engine.decide(None, &viewer, "object_read", &Resource::default());
+        assert!(decision.is_permitted());
+        assert_eq!(decision.rule.as_deref(), Some("viewer"));
+
+        let decision = engine.decide(None, &viewer, "object_delete", &Resource::default());
+        assert!(!decision.is_permitted());
+        assert_eq!(decision.rule, None);
+        assert!(decision.reason().contains("editor: requires one of the roles object_editor"));
+
+        let editor = Subject::with_roles(["object_editor"]);
+        assert!(engine.decide(None, &editor, "object_classify", &Resource::default()).is_permitted());
+        assert!(!engine.decide(None, &editor, "domain_read", &Resource::default()).is_permitted());
+    }
+
+    #[test]
+    fn test_tenant_policy_extends_defaults() {
+        let engine = engine();
+        let policy = Policy::from_yaml(
+            r#"
+tenant: provincie-utrecht
+remove: [editor]
+rules:
+  - id: viewer
+    effect: permit
+    actions: [domain_read, object_read]
+    subject: { roles: [domain_viewer], departments: [ruimte] }
+  - id: geheim-alleen-hoog
+    description: Geheime stukken alleen met LoA hoog
+    effect: deny
+    actions: ["object_*"]
+    resource: { classifications: [geheim] }
+    condition: subject.loa_level < 2
+tests:
+  - name: geheim vereist hoog
+    subject: { roles: [domain_viewer], department: ruimte, loa: substantial }
+    action: object_read
+    resource: { kind: information_object, classification: geheim }
+    expect: deny
+    rule: geheim-alleen-hoog
+"#,
+        )
+        .unwrap();
+        let report = engine.set_tenant_policy("provincie-utrecht", policy).unwrap();
+        assert_eq!(report.passed, 1);
+
+        let tenant = Some("provincie-utrecht");
+        let viewer = Subject::with_roles(["domain_viewer"]).with_department("ruimte");
+        assert!(engine.decide(tenant, &viewer, "object_read", &object(Classification::Intern)).is_permitted());
+
+        let high = viewer.clone().with_loa(LoA::High);
+        assert!(engine.decide(tenant, &high, "object_read", &object(Classification::Geheim)).is_permitted());
+
+        // Without a LoA the condition cannot be evaluated: the deny rule applies
+        let decision = engine.decide(tenant, &viewer, "object_read", &object(Classification::Geheim));
+        assert_eq!(decision.effect, Effect::Deny);
+        assert!(decision.reason().contains("geheim-alleen-hoog"));
+        assert!(decision.reason().contains("condition failed"));
+
+        let other = Subject::with_roles(["domain_viewer"]).with_department("financien");
+        let decision = engine.decide(tenant, &other, "object_read", &Resource::default());
+        assert!(!decision.is_permitted());
+        assert!(decision.reason().contains("requires department ruimte"));
+
+        let editor = Subject::with_roles(["object_editor"]);
+        assert!(!engine.decide(tenant, &editor, "object_update", &Resource::default()).is_permitted());
+        assert!(engine.decide(None, &editor, "object_update", &Resource::default()).is_permitted());
+        assert!(engine.decide(Some("provincie-zeeland"), &editor, "object_update", &Resource::default()).is_permitted());
+    }
+
+    #[test]
+    fn test_failing_tests_reject_policy() {
+        let engine = engine();
+        let policy = Policy::from_yaml(
+            r#"
+remove: [admin]
+tests:
+  - name: admin mag nog steeds alles
+    subject: { roles: [admin] }
+    action: role_manage
+    expect: permit
+"#,
+        )
+        .unwrap();
+
+        let report = engine.test_policy("provincie-zeeland", policy.clone()).unwrap();
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].actual, Effect::Deny);
+
+        let err = engine.set_tenant_policy("provincie-zeeland", policy).unwrap_err();
+        assert!(matches!(err, PolicyError::TestsFailed(_)));
+        assert!(err.to_string().contains("admin mag nog steeds alles"));
+        assert!(engine.tenants().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_policies() {
+        let engine = engine();
+        let unknown = Policy { remove: vec!["bestaat-niet".into()], ..Default::default() };
+        assert!(matches!(engine.test_policy("t", unknown), Err(PolicyError::UnknownRule(_))));
+
+        let bad_condition = Policy::from_yaml(
+            "rules:\n  - { id: x, effect: permit, actions: [object_read], condition: \"subject.loa_level <\" }\n",
+        )
+        .unwrap();
+        assert!(matches!(engine.test_policy("t", bad_condition), Err(PolicyError::InvalidRule { .. })));
+
+        let other_tenant = Policy { tenant: Some("provincie-utrecht".into()), ..Default::default() };
+        assert!(matches!(
+            engine.set_tenant_policy("provincie-zeeland", other_tenant),
+            Err(PolicyError::TenantMismatch { .. })
+        ));
+
+        let broken_defaults = "rules: []\ntests:\n  - { name: t, action: object_read, expect: permit }\n";
+        assert!(matches!(PolicyEngine::from_yaml(broken_defaults), Err(PolicyError::TestsFailed(_))));
+    }
+
+    #[test]
+    fn test_resource_attributes_and_delegations() {
+        let engine = engine();
+        let policy = Policy::from_yaml(
+            r#"
+rules:
+  - id: woo-publiceren
+    effect: permit
+    actions: [woo_publish]
+    subject: { roles: [woo_officer] }
+    resource: { woo_status: [openbaar, gedeeltelijk_openbaar], purposes: [P001] }
+  - id: vervanging
+    effect: permit
+    actions: [compliance_approve]
+    subject: { delegated: true }
+    condition: resource.classification_level <= 2
+"#,
+        )
+        .unwrap();
+        engine.set_tenant_policy("gemeente-a", policy).unwrap();
+        let tenant = Some("gemeente-a");
+
+        let officer = Subject::with_roles(["woo_officer"]);
+        let publishable = Resource::new("information_object")
+            .with_woo_status(WooDisclosureClass::GedeeltelijkOpenbaar)
+            .with_purpose("P001");
+        assert!(engine.decide(tenant, &officer, "woo_publish", &publishable).is_permitted());
+        let withheld = publishable.clone().with_woo_status(WooDisclosureClass::NietOpenbaar);
+        let decision = engine.decide(tenant, &officer, "woo_publish", &withheld);
+        assert!(decision.reason().contains("Woo status is not openbaar, gedeeltelijk_openbaar"));
+
+        let manager = Uuid::new_v4();
+        let deputy = Uuid::new_v4();
+        let document = Uuid::new_v4();
+        let delegation = Delegation::new_temporary(
+            manager,
+            deputy,
+            vec!["besluit".into()],
+            Utc::now() - Duration::hours(1),
+            Utc::now() + Duration::days(7),
+            manager,
+        );
+        let subject = Subject::default().with_id(deputy.to_string()).with_delegations(vec![delegation]);
+        let besluit = Resource::new("besluit").with_id(document).with_classification(Classification::Intern);
+        assert!(engine.decide(tenant, &subject, "compliance_approve", &besluit).is_permitted());
+
+        let notitie = Resource::new("notitie").with_id(document).with_classification(Classification::Intern);
+        let decision = engine.decide(tenant, &subject, "compliance_approve", &notitie);
+        assert!(!decision.is_permitted());
+        assert!(decision.reason().contains("requires a delegation"));
+    }
+}
@@ -2,11 +2,32 @@
 //!
 //! This module integrates BPMN process definitions with the orchestrator,
 //! enabling DMN decision service calls within process flows.
+//!
+//! [`BpmnProcessEngine`] executes processes in-process with token
+//! semantics: tokens follow sequence flows, gateways route them based on
+//! FEEL conditions over the process variables, business rule tasks call
+//! the DMN evaluator, and user, receive and service tasks, message events
+//! and timers park their token until [`BpmnProcessEngine::complete_task`]
+//! or [`BpmnProcessEngine::trigger_timers`] moves it on.
+//!
+//! Definitions are read from and written to BPMN 2.0 XML with
+//! [`parse_bpmn_definitions`] and [`to_bpmn_xml`], including the
+//! `zeebe:` (Camunda 8) and `camunda:` (Camunda 7) extensions for task
+//! types, decision references and user task assignment.
+
+mod engine;
+mod parser;
+mod store;
+mod writer;
+
+pub use store::{InMemoryProcessStore, ProcessInstanceStore};
 
 use crate::dmn::{DmnEvaluator, DecisionContext, DecisionValue};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
+use strum::{Display, EnumString};
 use thiserror::Error;
 use uuid::Uuid;
 
@@ -28,6 +49,96 @@ pub struct ProcessDefinition {
 
     /// Events (start, end, intermediate)
     pub events: Vec<Event>,
+
+    /// Sequence flows connecting activities, gateways and events
+    #[serde(default)]
+    pub sequence_flows: Vec<SequenceFlow>,
+
+    /// Messages referenced by message events
+    #[serde(default)]
+    pub messages: Vec<MessageDefinition>,
+}
+
+impl ProcessDefinition {
+    /// Check that the definition can be executed
+    ///
+    /// Requires a start event, unique flow node IDs, sequence flows between
+    /// existing nodes, gateway default flows that leave their gateway, and
+    /// timers on timer events.
+    pub fn validate(&self) -> Result<(), BpmnError> {
+        let invalid = |msg: String| Err(BpmnError::InvalidDefinition(format!("{}: {}", self.id, msg)));
+
+        let mut nodes = std::collections::HashSet::new();
+        let ids = self.activities.iter().map(|a| &a.id)
+            .chain(self.gateways.iter().map(|g| &g.id))
+            .chain(self.events.iter().map(|e| &e.id));
+        for id in ids {
+            if !nodes.insert(id.as_str()) {
+                return invalid(format!("duplicate flow node {}", id));
+            }
+        }
+
+        if !self.events.iter().any(|e| matches!(e.event_type, EventType::Start)) {
+            return invalid("no start event".to_string());
+        }
+
+        for flow in &self.sequence_flows {
+            for node in [&flow.source_ref, &flow.target_ref] {
+                if !nodes.contains(node.as_str()) {
+                    return invalid(format!("sequence flow {} refers to unknown node {}", flow.id, node));
+                }
+            }
+        }
+
+        for gateway in &self.gateways {
+            if let Some(default) = &gateway.default_flow {
+                let leaves = self.sequence_flows.iter()
+                    .any(|f| &f.id == default && f.source_ref == gateway.id);
+                if !leaves {
+                    return invalid(format!(
+                        "default flow {} of gateway {} does not leave the gateway",
+                        default, gateway.id
+                    ));
+                }
+            }
+        }
+
+        for event in &self.events {
+            if matches!(event.event_type, EventType::Timer) && event.timer.is_none() {
+                return invalid(format!("timer event {} has no timer", event.id));
+            }
+            let unknown_message = event.message_ref.as_ref()
+                .filter(|message| !self.messages.iter().any(|m| &&m.id == message));
+            if let Some(message) = unknown_message {
+                return invalid(format!("event {} refers to unknown message {}", event.id, message));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A message that message events wait for or send
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageDefinition {
+    pub id: String,
+    pub name: String,
+    /// Zeebe correlation key expression (e.g. `=documentId`)
+    #[serde(default)]
+    pub correlation_key: Option<String>,
+}
+
+/// Sequence flow between two flow nodes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequenceFlow {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    pub source_ref: String,
+    pub target_ref: String,
+    /// FEEL condition over the process variables (a leading `=` is allowed)
+    #[serde(default)]
+    pub condition: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +156,24 @@ pub struct Activity {
     pub activity_type: ActivityType,
     pub incoming: Vec<String>,
     pub outgoing: Vec<String>,
+    /// Decision evaluated by a business rule task (defaults to the task name)
+    #[serde(default)]
+    pub decision_ref: Option<String>,
+    /// Variable that receives a single-output decision result
+    #[serde(default)]
+    pub result_variable: Option<String>,
+    /// Job type (`zeebe:taskDefinition`) or external task topic (`camunda:topic`)
+    #[serde(default)]
+    pub task_type: Option<String>,
+    /// Job retries for service tasks
+    #[serde(default)]
+    pub retries: Option<u32>,
+    /// User task assignee
+    #[serde(default)]
+    pub assignee: Option<String>,
+    /// User task form
+    #[serde(default)]
+    pub form_key: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,6 +196,9 @@ pub struct Gateway {
     pub gateway_type: GatewayType,
     pub incoming: Vec<String>,
     pub outgoing: Vec<String>,
+    /// Flow taken when no condition of an exclusive or inclusive gateway holds
+    #[serde(default)]
+    pub default_flow: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -83,6 +215,15 @@ pub struct Event {
     pub id: String,
     pub name: String,
     pub event_type: EventType,
+    /// Timer of a timer event
+    #[serde(default)]
+    pub timer: Option<TimerDefinition>,
+    /// Message of a message event (a [`MessageDefinition`] ID)
+    #[serde(default)]
+    pub message_ref: Option<String>,
+    /// Activity a boundary event is attached to
+    #[serde(default)]
+    pub attached_to: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -98,8 +239,30 @@ pub enum EventType {
     Message,
 }
 
+/// When a timer event fires
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TimerDefinition {
+    /// ISO 8601 duration after the token arrives, e.g. `P14D`
+    Duration(String),
+    /// Fixed RFC 3339 date and time
+    Date(String),
+    /// ISO 8601 repeating interval, e.g. `R3/PT1H` (not executed by the engine)
+    Cycle(String),
+}
+
+/// Extension attributes written by [`to_bpmn_xml`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BpmnDialect {
+    /// Camunda 8 (`zeebe:` extension elements)
+    #[default]
+    Zeebe,
+    /// Camunda 7 (`camunda:` attributes)
+    Camunda,
+}
+
 /// Process instance (running process)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessInstance {
     pub id: Uuid,
     pub definition_id: String,
@@ -108,9 +271,71 @@ pub struct ProcessInstance {
     pub current_activity: Option<String>,
     pub started_at: chrono::DateTime<chrono::Utc>,
     pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Tokens waiting in the process
+    pub tokens: Vec<Token>,
+    /// Flow nodes the instance has passed, in completion order
+    pub history: Vec<ActivityRecord>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl ProcessInstance {
+    /// Flow nodes waiting for [`BpmnProcessEngine::complete_task`]
+    pub fn waiting_tasks(&self) -> Vec<&str> {
+        self.tokens
+            .iter()
+            .filter(|t| matches!(t.wait, TokenWait::Task))
+            .map(|t| t.element_id.as_str())
+            .collect()
+    }
+
+    /// Earliest due timer, if any
+    pub fn next_timer(&self) -> Option<DateTime<Utc>> {
+        self.tokens
+            .iter()
+            .filter_map(|t| match t.wait {
+                TokenWait::Timer { due } => Some(due),
+                _ => None,
+            })
+            .min()
+    }
+}
+
+/// A token parked at a flow node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Token {
+    pub id: Uuid,
+    pub element_id: String,
+    /// Sequence flow the token arrived through
+    pub arrived_via: Option<String>,
+    pub entered_at: DateTime<Utc>,
+    pub wait: TokenWait,
+}
+
+/// What a parked token is waiting for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TokenWait {
+    /// External completion of a task or message event
+    Task,
+    /// A timer event
+    Timer { due: DateTime<Utc> },
+    /// The other branches of a joining gateway
+    Join,
+}
+
+/// A completed flow node in the instance history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityRecord {
+    pub element_id: String,
+    pub element_name: String,
+    /// BPMN element type, e.g. `userTask` or `exclusiveGateway`
+    pub element_type: String,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Display, EnumString)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
 pub enum ProcessInstanceState {
     Running,
     Suspended,
@@ -123,6 +348,7 @@ pub enum ProcessInstanceState {
 pub struct BpmnProcessEngine {
     evaluator: Arc<DmnEvaluator>,
     processes: HashMap<String, ProcessDefinition>,
+    store: Option<Arc<dyn ProcessInstanceStore>>,
 }
 
 impl BpmnProcessEngine {
@@ -131,6 +357,61 @@ impl BpmnProcessEngine {
         Self {
             evaluator,
             processes: HashMap::new(),
+            store: None,
+        }
+    }
+
+    /// Persist instances in the given store after every step
+    pub fn with_store(mut self, store: Arc<dyn ProcessInstanceStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Load a stored process instance
+    ///
+    /// Returns `None` when the instance is unknown or the engine has no store.
+    pub async fn get_instance(&self, id: Uuid) -> Result<Option<ProcessInstance>, BpmnError> {
+        match &self.store {
+            Some(store) => store.load(id).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Completed flow nodes of a stored instance, oldest first
+    pub async fn instance_history(&self, id: Uuid) -> Result<Vec<ActivityRecord>, BpmnError> {
+        match &self.store {
+            Some(store) => store.history(id).await,
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Fire due timers of all stored running instances
+    ///
+    /// Meant to be called periodically; after a restart it picks up the
+    /// timers of instances started before. Returns the number of timers
+    /// fired. An instance that fails is marked failed and skipped.
+    pub async fn trigger_stored_timers(&self, now: DateTime<Utc>) -> Result<usize, BpmnError> {
+        let Some(store) = &self.store else {
+            return Ok(0);
+        };
+
+        let mut fired = 0;
+        for mut instance in store.list_running().await? {
+            if instance.next_timer().is_none_or(|due| due > now) {
+                continue;
+            }
+            match self.trigger_timers(&mut instance, now).await {
+                Ok(count) => fired += count,
+                Err(e) => tracing::warn!("Timer of process instance {} failed: {}", instance.id, e),
+            }
+        }
+        Ok(fired)
+    }
+
+    async fn persist(&self, instance: &ProcessInstance) -> Result<(), BpmnError> {
+        match &self.store {
+            Some(store) => store.save(instance).await,
+            None => Ok(()),
         }
     }
 
@@ -140,56 +421,179 @@ impl BpmnProcessEngine {
         Ok(())
     }
 
+    /// Load and validate all processes in a BPMN 2.0 XML document
+    ///
+    /// Returns the IDs of the loaded processes.
+    pub fn load_bpmn_xml(&mut self, xml: &str) -> Result<Vec<String>, BpmnError> {
+        let definitions = parse_bpmn_definitions(xml)?;
+        for definition in &definitions {
+            definition.validate()?;
+        }
+
+        let ids = definitions.iter().map(|d| d.id.clone()).collect();
+        for definition in definitions {
+            self.load_process(definition)?;
+        }
+        Ok(ids)
+    }
+
     /// Start a new process instance
+    ///
+    /// Runs the process from its start event until every token waits or
+    /// has reached an end event.
     pub async fn start_process(
         &self,
         definition_id: &str,
         variables: HashMap<String, DecisionValue>,
     ) -> Result<ProcessInstance, BpmnError> {
-        let _definition = self.processes
+        let definition = self.processes
             .get(definition_id)
             .ok_or_else(|| BpmnError::ProcessNotFound(definition_id.to_string()))?;
 
-        let instance = ProcessInstance {
+        let start = definition.events.iter()
+            .find(|e| matches!(e.event_type, EventType::Start))
+            .ok_or_else(|| BpmnError::ExecutionError(format!(
+                "Process {} has no start event", definition_id
+            )))?;
+
+        let mut variables = variables;
+        for var in &definition.variables {
+            if let Some(default) = &var.default {
+                variables.entry(var.name.clone()).or_insert_with(|| default.clone());
+            }
+        }
+
+        let now = Utc::now();
+        let mut instance = ProcessInstance {
             id: Uuid::new_v4(),
             definition_id: definition_id.to_string(),
             state: ProcessInstanceState::Running,
             variables,
             current_activity: None,
-            started_at: chrono::Utc::now(),
+            started_at: now,
             completed_at: None,
+            tokens: vec![],
+            history: vec![],
         };
 
+        let arrivals = VecDeque::from([engine::Arrival::new(&start.id, None)]);
+        self.run(definition, &mut instance, arrivals, now)?;
+        self.persist(&instance).await?;
+
         Ok(instance)
     }
 
+    /// Complete a waiting user, receive or service task (or message event)
+    ///
+    /// The given variables are merged into the process variables before
+    /// the token moves on.
+    pub async fn complete_task(
+        &self,
+        instance: &mut ProcessInstance,
+        element_id: &str,
+        variables: HashMap<String, DecisionValue>,
+    ) -> Result<(), BpmnError> {
+        let definition = self.running_definition(instance)?;
+
+        let index = instance.tokens.iter()
+            .position(|t| t.element_id == element_id && t.wait == TokenWait::Task)
+            .ok_or_else(|| BpmnError::InvalidActivity(format!(
+                "No task waiting at {}", element_id
+            )))?;
+
+        let token = instance.tokens.remove(index);
+        instance.variables.extend(variables);
+
+        let now = Utc::now();
+        let result = self.resume(definition, instance, vec![token], now);
+        self.persist(instance).await?;
+        result
+    }
+
+    /// Fire all timers that are due at `now`
+    ///
+    /// Returns the number of timers that fired.
+    pub async fn trigger_timers(
+        &self,
+        instance: &mut ProcessInstance,
+        now: DateTime<Utc>,
+    ) -> Result<usize, BpmnError> {
+        let definition = self.running_definition(instance)?;
+
+        let (due, waiting): (Vec<Token>, Vec<Token>) = std::mem::take(&mut instance.tokens)
+            .into_iter()
+            .partition(|t| matches!(t.wait, TokenWait::Timer { due } if due <= now));
+        instance.tokens = waiting;
+
+        let fired = due.len();
+        if fired > 0 {
+            let result = self.resume(definition, instance, due, now);
+            self.persist(instance).await?;
+            result?;
+        }
+        Ok(fired)
+    }
+
+    fn running_definition(&self, instance: &ProcessInstance) -> Result<&ProcessDefinition, BpmnError> {
+        if instance.state != ProcessInstanceState::Running {
+            return Err(BpmnError::ExecutionError(format!(
+                "Process instance {} is {:?}", instance.id, instance.state
+            )));
+        }
+        self.processes
+            .get(&instance.definition_id)
+            .ok_or_else(|| BpmnError::ProcessNotFound(instance.definition_id.clone()))
+    }
+
+    /// Move parked tokens on; a failure marks the instance as failed
+    fn resume(
+        &self,
+        definition: &ProcessDefinition,
+        instance: &mut ProcessInstance,
+        tokens: Vec<Token>,
+        now: DateTime<Utc>,
+    ) -> Result<(), BpmnError> {
+        let mut arrivals = VecDeque::new();
+        let result = tokens.into_iter()
+            .try_for_each(|token| self.leave(definition, instance, &token, &mut arrivals, now))
+            .and_then(|_| self.run(definition, instance, arrivals, now));
+
+        if result.is_err() {
+            instance.state = ProcessInstanceState::Failed;
+        }
+        result
+    }
+
     /// Execute a Business Rule Task (calls DMN evaluator)
     pub async fn execute_business_rule_task(
         &self,
         task: &Activity,
         instance: &ProcessInstance,
+    ) -> Result<HashMap<String, DecisionValue>, BpmnError> {
+        self.evaluate_decision(task, &instance.variables)
+    }
+
+    fn evaluate_decision(
+        &self,
+        task: &Activity,
+        variables: &HashMap<String, DecisionValue>,
     ) -> Result<HashMap<String, DecisionValue>, BpmnError> {
         if !matches!(task.activity_type, ActivityType::BusinessRuleTask) {
             return Err(BpmnError::InvalidActivity("Not a business rule task".into()));
         }
 
-        // Extract decision ID from task (stored in task name or extension elements)
-        let decision_id = task.name.clone();
+        // Decision ID from the task's decision reference, or the task name
+        let decision_id = task.decision_ref.as_deref().unwrap_or(&task.name);
 
         // Build decision context from process variables
-        let mut context_inputs = HashMap::new();
-        for var in instance.variables.iter() {
-            context_inputs.insert(var.0.clone(), var.1.clone());
-        }
-
         let context = DecisionContext {
-            inputs: context_inputs,
+            inputs: variables.clone(),
             tenant_id: None, // Will be set from VC context
             context: HashMap::new(),
         };
 
         let result = self.evaluator
-            .evaluate(&decision_id, &context)
+            .evaluate(decision_id, &context)
             .map_err(|e| BpmnError::EvaluationError(e.to_string()))?;
 
         Ok(result.outputs)
@@ -216,11 +620,17 @@ pub enum BpmnError {
     #[error("Parse error: {0}")]
     ParseError(String),
 
+    #[error("Invalid process definition: {0}")]
+    InvalidDefinition(String),
+
     #[error("Execution error: {0}")]
     ExecutionError(String),
 
     #[error("Fetch error: {0}")]
     FetchError(String),
+
+    #[error("Store error: {0}")]
+    StoreError(String),
 }
 
 /// Integration helper: Load BPMN process from Open Regels
@@ -250,48 +660,524 @@ pub async fn load_process_from_open_regels(
     parse_bpmn_xml(&bpmn_xml)
 }
 
-/// Parse BPMN XML (simplified)
-fn parse_bpmn_xml(_xml: &str) -> Result<ProcessDefinition, BpmnError> {
-    // For a production implementation, use a proper BPMN parser
-    // This is a simplified version that handles basic processes
-    Ok(ProcessDefinition {
-        id: Uuid::new_v4().to_string(),
-        name: "Unnamed Process".to_string(),
-        version: "1.0".to_string(),
-        variables: vec![],
-        activities: vec![],
-        gateways: vec![],
-        events: vec![],
-    })
+/// Parse all processes in a BPMN 2.0 XML document
+pub fn parse_bpmn_definitions(xml: &str) -> Result<Vec<ProcessDefinition>, BpmnError> {
+    parser::parse_definitions(xml)
+}
+
+/// Parse a BPMN 2.0 XML document and return its first process
+pub fn parse_bpmn_xml(xml: &str) -> Result<ProcessDefinition, BpmnError> {
+    parser::parse_definitions(xml)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| BpmnError::ParseError("No process found".into()))
+}
+
+/// Write process definitions as BPMN 2.0 XML
+///
+/// The output has no diagram interchange (`bpmndi:`) section, so modelers
+/// lay the process out again when it is opened.
+pub fn to_bpmn_xml(processes: &[ProcessDefinition], dialect: BpmnDialect) -> String {
+    writer::write_definitions(processes, dialect)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_process_instance_creation() {
-        let evaluator = DmnEvaluator::new();
-        let mut engine = BpmnProcessEngine::new(Arc::new(evaluator));
+    fn event(id: &str, event_type: EventType) -> Event {
+        Event {
+            id: id.to_string(),
+            name: id.to_string(),
+            event_type,
+            timer: None,
+            message_ref: None,
+            attached_to: None,
+        }
+    }
+
+    fn activity(id: &str, activity_type: ActivityType) -> Activity {
+        Activity {
+            id: id.to_string(),
+            name: id.to_string(),
+            activity_type,
+            incoming: vec![],
+            outgoing: vec![],
+            decision_ref: None,
+            result_variable: None,
+            task_type: None,
+            retries: None,
+            assignee: None,
+            form_key: None,
+        }
+    }
+
+    fn gateway(id: &str, gateway_type: GatewayType) -> Gateway {
+        Gateway {
+            id: id.to_string(),
+            name: id.to_string(),
+            gateway_type,
+            incoming: vec![],
+            outgoing: vec![],
+            default_flow: None,
+        }
+    }
+
+    fn flow(source: &str, target: &str, condition: Option<&str>) -> SequenceFlow {
+        SequenceFlow {
+            id: format!("{}-{}", source, target),
+            name: String::new(),
+            source_ref: source.to_string(),
+            target_ref: target.to_string(),
+            condition: condition.map(str::to_string),
+        }
+    }
 
-        let definition = ProcessDefinition {
-            id: "test-process".to_string(),
-            name: "Test Process".to_string(),
+    fn process(id: &str) -> ProcessDefinition {
+        ProcessDefinition {
+            id: id.to_string(),
+            name: id.to_string(),
             version: "1.0".to_string(),
             variables: vec![],
             activities: vec![],
             gateways: vec![],
             events: vec![],
-        };
+            sequence_flows: vec![],
+            messages: vec![],
+        }
+    }
+
+    fn visited(instance: &ProcessInstance) -> Vec<&str> {
+        instance.history.iter().map(|r| r.element_id.as_str()).collect()
+    }
 
+    const ROUTERING_DMN: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<definitions xmlns="https://www.omg.org/spec/DMN/20191111/MODEL/" id="routering" name="Routering" namespace="urn:iou">
+  <decision id="risico" name="Risico">
+    <decisionTable>
+      <input id="i1"><inputExpression typeRef="number"><text>bedrag</text></inputExpression></input>
+      <output id="o1" name="risico" typeRef="string"/>
+      <rule id="r_hoog"><inputEntry><text>&gt;= 10000</text></inputEntry><outputEntry><text>"hoog"</text></outputEntry></rule>
+      <rule id="r_laag"><inputEntry><text>&lt; 10000</text></inputEntry><outputEntry><text>"laag"</text></outputEntry></rule>
+    </decisionTable>
+  </decision>
+</definitions>"#;
+
+    /// start -> risico (DMN) -> xor -> beoordelen (user task) -> end
+    ///                              \-> end (default)
+    fn subsidie_engine() -> BpmnProcessEngine {
+        let mut evaluator = DmnEvaluator::new();
+        evaluator.load_dmn_xml(ROUTERING_DMN).unwrap();
+        let mut engine = BpmnProcessEngine::new(Arc::new(evaluator));
+
+        let mut definition = process("subsidie");
+        definition.events = vec![event("start", EventType::Start), event("end", EventType::End)];
+        let mut risico = activity("bepaal_risico", ActivityType::BusinessRuleTask);
+        risico.decision_ref = Some("risico".to_string());
+        definition.activities = vec![risico, activity("beoordelen", ActivityType::UserTask)];
+        let mut xor = gateway("xor", GatewayType::Exclusive);
+        xor.default_flow = Some("xor-end".to_string());
+        definition.gateways = vec![xor];
+        definition.sequence_flows = vec![
+            flow("start", "bepaal_risico", None),
+            flow("bepaal_risico", "xor", None),
+            flow("xor", "beoordelen", Some(r#"= risico = "hoog""#)),
+            flow("xor", "end", None),
+            flow("beoordelen", "end", None),
+        ];
         engine.load_process(definition).unwrap();
+        engine
+    }
 
-        let instance = tokio::runtime::Runtime::new()
-            .unwrap()
-            .block_on(engine.start_process("test-process", HashMap::new()))
+    #[tokio::test]
+    async fn test_process_instance_creation() {
+        let engine = subsidie_engine();
+
+        let instance = engine
+            .start_process(
+                "subsidie",
+                HashMap::from([("bedrag".to_string(), DecisionValue::Integer(25000))]),
+            )
+            .await
             .unwrap();
 
-        assert_eq!(instance.definition_id, "test-process");
+        assert_eq!(instance.definition_id, "subsidie");
         assert_eq!(instance.state, ProcessInstanceState::Running);
+        assert_eq!(instance.current_activity.as_deref(), Some("beoordelen"));
+
+        assert!(engine.start_process("onbekend", HashMap::new()).await.is_err());
+        let mut engine = engine;
+        engine.load_process(process("leeg")).unwrap();
+        assert!(engine.start_process("leeg", HashMap::new()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_business_rule_task_and_exclusive_gateway() {
+        let engine = subsidie_engine();
+
+        // Low risk takes the default flow straight to the end
+        let instance = engine
+            .start_process(
+                "subsidie",
+                HashMap::from([("bedrag".to_string(), DecisionValue::Integer(500))]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(instance.state, ProcessInstanceState::Completed);
+        assert!(instance.completed_at.is_some());
+        assert_eq!(instance.variables["risico"].as_str(), Some("laag"));
+        assert_eq!(visited(&instance), vec!["start", "bepaal_risico", "xor", "end"]);
+        assert_eq!(instance.history[1].element_type, "businessRuleTask");
+
+        // High risk parks on the user task until it is completed
+        let mut instance = engine
+            .start_process(
+                "subsidie",
+                HashMap::from([("bedrag".to_string(), DecisionValue::Integer(25000))]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(instance.waiting_tasks(), vec!["beoordelen"]);
+        assert!(engine.complete_task(&mut instance, "xor", HashMap::new()).await.is_err());
+
+        engine
+            .complete_task(
+                &mut instance,
+                "beoordelen",
+                HashMap::from([("akkoord".to_string(), DecisionValue::Boolean(true))]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(instance.state, ProcessInstanceState::Completed);
+        assert!(matches!(instance.variables["akkoord"], DecisionValue::Boolean(true)));
+        assert_eq!(visited(&instance), vec!["start", "bepaal_risico", "xor", "beoordelen", "end"]);
+
+        // Completed instances accept no more work
+        assert!(engine.complete_task(&mut instance, "beoordelen", HashMap::new()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_exclusive_gateway_without_matching_flow_fails() {
+        let mut engine = BpmnProcessEngine::new(Arc::new(DmnEvaluator::new()));
+        let mut definition = process("strikt");
+        definition.events = vec![event("start", EventType::Start), event("end", EventType::End)];
+        definition.gateways = vec![gateway("xor", GatewayType::Exclusive)];
+        definition.sequence_flows = vec![
+            flow("start", "xor", None),
+            flow("xor", "end", Some("bedrag > 100")),
+        ];
+        engine.load_process(definition).unwrap();
+
+        let vars = HashMap::from([("bedrag".to_string(), DecisionValue::Integer(5))]);
+        assert!(matches!(
+            engine.start_process("strikt", vars).await,
+            Err(BpmnError::ExecutionError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_parallel_gateway_waits_for_all_branches() {
+        let mut engine = BpmnProcessEngine::new(Arc::new(DmnEvaluator::new()));
+        let mut definition = process("parallel");
+        definition.events = vec![event("start", EventType::Start), event("end", EventType::End)];
+        definition.activities = vec![
+            activity("juridisch", ActivityType::UserTask),
+            activity("financieel", ActivityType::UserTask),
+        ];
+        definition.gateways = vec![
+            gateway("split", GatewayType::Parallel),
+            gateway("join", GatewayType::Parallel),
+        ];
+        definition.sequence_flows = vec![
+            flow("start", "split", None),
+            flow("split", "juridisch", None),
+            flow("split", "financieel", None),
+            flow("juridisch", "join", None),
+            flow("financieel", "join", None),
+            flow("join", "end", None),
+        ];
+        engine.load_process(definition).unwrap();
+
+        let mut instance = engine.start_process("parallel", HashMap::new()).await.unwrap();
+        assert_eq!(instance.waiting_tasks(), vec!["juridisch", "financieel"]);
+
+        engine.complete_task(&mut instance, "financieel", HashMap::new()).await.unwrap();
+        assert_eq!(instance.state, ProcessInstanceState::Running);
+        assert_eq!(instance.waiting_tasks(), vec!["juridisch"]);
+        assert_eq!(instance.tokens.iter().filter(|t| t.wait == TokenWait::Join).count(), 1);
+
+        engine.complete_task(&mut instance, "juridisch", HashMap::new()).await.unwrap();
+        assert_eq!(instance.state, ProcessInstanceState::Completed);
+        assert!(instance.tokens.is_empty());
+        assert_eq!(visited(&instance).iter().filter(|id| **id == "join").count(), 1);
+        assert_eq!(visited(&instance).last(), Some(&"end"));
+    }
+
+    #[tokio::test]
+    async fn test_inclusive_gateway_joins_taken_branches() {
+        let mut engine = BpmnProcessEngine::new(Arc::new(DmnEvaluator::new()));
+        let mut definition = process("inclusief");
+        definition.events = vec![event("start", EventType::Start), event("end", EventType::End)];
+        definition.activities = vec![
+            activity("advies_a", ActivityType::UserTask),
+            activity("advies_b", ActivityType::Task),
+        ];
+        definition.gateways = vec![
+            gateway("or_split", GatewayType::Inclusive),
+            gateway("or_join", GatewayType::Inclusive),
+        ];
+        definition.sequence_flows = vec![
+            flow("start", "or_split", None),
+            flow("or_split", "advies_a", Some("a")),
+            flow("or_split", "advies_b", Some("b")),
+            flow("advies_a", "or_join", None),
+            flow("advies_b", "or_join", None),
+            flow("or_join", "end", None),
+        ];
+        engine.load_process(definition).unwrap();
+
+        // Only branch b is taken, so the join does not wait for a
+        let vars = HashMap::from([
+            ("a".to_string(), DecisionValue::Boolean(false)),
+            ("b".to_string(), DecisionValue::Boolean(true)),
+        ]);
+        let instance = engine.start_process("inclusief", vars).await.unwrap();
+        assert_eq!(instance.state, ProcessInstanceState::Completed);
+
+        // With both branches the join waits for the user task
+        let vars = HashMap::from([
+            ("a".to_string(), DecisionValue::Boolean(true)),
+            ("b".to_string(), DecisionValue::Boolean(true)),
+        ]);
+        let mut instance = engine.start_process("inclusief", vars).await.unwrap();
+        assert_eq!(instance.waiting_tasks(), vec!["advies_a"]);
+        engine.complete_task(&mut instance, "advies_a", HashMap::new()).await.unwrap();
+        assert_eq!(instance.state, ProcessInstanceState::Completed);
+    }
+
+    #[tokio::test]
+    async fn test_timer_event() {
+        let mut engine = BpmnProcessEngine::new(Arc::new(DmnEvaluator::new()));
+        let mut definition = process("bezwaartermijn");
+        let mut wacht = event("wacht", EventType::Timer);
+        wacht.timer = Some(TimerDefinition::Duration("P6W".to_string()));
+        definition.events = vec![event("start", EventType::Start), wacht, event("end", EventType::End)];
+        definition.sequence_flows = vec![flow("start", "wacht", None), flow("wacht", "end", None)];
+        engine.load_process(definition).unwrap();
+
+        let mut instance = engine.start_process("bezwaartermijn", HashMap::new()).await.unwrap();
+        let due = instance.next_timer().unwrap();
+        assert_eq!(due - instance.started_at, chrono::Duration::weeks(6));
+
+        let fired = engine.trigger_timers(&mut instance, due - chrono::Duration::seconds(1)).await.unwrap();
+        assert_eq!(fired, 0);
+        assert_eq!(instance.state, ProcessInstanceState::Running);
+
+        let fired = engine.trigger_timers(&mut instance, due).await.unwrap();
+        assert_eq!(fired, 1);
+        assert_eq!(instance.state, ProcessInstanceState::Completed);
+        assert_eq!(visited(&instance), vec!["start", "wacht", "end"]);
+        assert_eq!(instance.history[1].started_at, instance.started_at);
+    }
+
+    /// The pipeline deployed to Zeebe by iou-camunda-worker
+    const DOCUMENT_PIPELINE: &str = include_str!("../../../infra/camunda/bpmn/document-pipeline.bpmn");
+
+    const BESLUIT_CAMUNDA7: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<definitions xmlns="http://www.omg.org/spec/BPMN/20100524/MODEL"
+    xmlns:camunda="http://camunda.org/schema/1.0/bpmn" id="defs" targetNamespace="urn:iou">
+  <process id="besluit" name="Besluit &amp; bezwaar" isExecutable="true" camunda:versionTag="2.1">
+    <startEvent id="start"/>
+    <businessRuleTask id="bepaal" name="Bepaal risico" camunda:decisionRef="risico" camunda:resultVariable="uitkomst"/>
+    <userTask id="toets" name="Toetsen" camunda:assignee="jurist" camunda:formKey="embedded:toets"/>
+    <serviceTask id="publiceer" camunda:type="external" camunda:topic="woo-publicatie"/>
+    <intermediateCatchEvent id="wacht"><timerEventDefinition><timeDuration>P6W</timeDuration></timerEventDefinition></intermediateCatchEvent>
+    <endEvent id="end"/>
+    <sequenceFlow id="f1" sourceRef="start" targetRef="bepaal"/>
+    <sequenceFlow id="f2" sourceRef="bepaal" targetRef="toets"/>
+    <sequenceFlow id="f3" sourceRef="toets" targetRef="publiceer"/>
+    <sequenceFlow id="f4" sourceRef="publiceer" targetRef="wacht"/>
+    <sequenceFlow id="f5" sourceRef="wacht" targetRef="end"/>
+  </process>
+</definitions>"#;
+
+    #[test]
+    fn test_parse_zeebe_bpmn() {
+        let definition = parse_bpmn_xml(DOCUMENT_PIPELINE).unwrap();
+        definition.validate().unwrap();
+
+        assert_eq!(definition.id, "DocumentPipeline");
+        assert_eq!(definition.name, "Document pipeline");
+        assert_eq!(definition.activities.len(), 2);
+        assert_eq!(definition.sequence_flows.len(), 6);
+
+        let pipeline = &definition.activities[0];
+        assert!(matches!(pipeline.activity_type, ActivityType::ServiceTask));
+        assert_eq!(pipeline.task_type.as_deref(), Some("iou-run-pipeline"));
+        assert_eq!(pipeline.retries, Some(3));
+        assert_eq!(pipeline.incoming, vec!["Flow_to_RunPipeline"]);
+        assert_eq!(pipeline.outgoing, vec!["Flow_to_DeepAgent"]);
+
+        let gateway = &definition.gateways[0];
+        assert_eq!(gateway.default_flow.as_deref(), Some("Flow_SkipWait"));
+        assert_eq!(gateway.outgoing.len(), 2);
+
+        let wait = definition.events.iter().find(|e| e.id == "Event_WaitApproval").unwrap();
+        assert!(matches!(wait.event_type, EventType::Message));
+        assert_eq!(wait.message_ref.as_deref(), Some("Message_DocumentApproved"));
+        assert_eq!(definition.messages[0].name, "document_approved");
+        assert_eq!(definition.messages[0].correlation_key.as_deref(), Some("=documentId"));
+
+        let condition = definition.sequence_flows.iter()
+            .find(|f| f.id == "Flow_WaitApproval")
+            .and_then(|f| f.condition.as_deref());
+        assert_eq!(condition, Some("=requiresHumanApproval = true"));
+    }
+
+    #[tokio::test]
+    async fn test_simulate_zeebe_bpmn() {
+        let mut engine = BpmnProcessEngine::new(Arc::new(DmnEvaluator::new()));
+        assert_eq!(engine.load_bpmn_xml(DOCUMENT_PIPELINE).unwrap(), vec!["DocumentPipeline"]);
+
+        let mut instance = engine.start_process("DocumentPipeline", HashMap::new()).await.unwrap();
+        assert_eq!(instance.waiting_tasks(), vec!["Task_RunPipeline"]);
+
+        engine.complete_task(&mut instance, "Task_RunPipeline", HashMap::new()).await.unwrap();
+        engine
+            .complete_task(
+                &mut instance,
+                "Task_DeepAgent",
+                HashMap::from([("requiresHumanApproval".to_string(), DecisionValue::Boolean(true))]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(instance.waiting_tasks(), vec!["Event_WaitApproval"]);
+
+        engine.complete_task(&mut instance, "Event_WaitApproval", HashMap::new()).await.unwrap();
+        assert_eq!(instance.state, ProcessInstanceState::Completed);
+        assert_eq!(instance.history.last().unwrap().element_id, "EndEvent_Done");
+    }
+
+    #[test]
+    fn test_parse_camunda7_extensions() {
+        let definition = parse_bpmn_xml(BESLUIT_CAMUNDA7).unwrap();
+        definition.validate().unwrap();
+
+        assert_eq!(definition.name, "Besluit & bezwaar");
+        assert_eq!(definition.version, "2.1");
+
+        let bepaal = &definition.activities[0];
+        assert_eq!(bepaal.decision_ref.as_deref(), Some("risico"));
+        assert_eq!(bepaal.result_variable.as_deref(), Some("uitkomst"));
+
+        let toets = &definition.activities[1];
+        assert_eq!(toets.assignee.as_deref(), Some("jurist"));
+        assert_eq!(toets.form_key.as_deref(), Some("embedded:toets"));
+
+        assert_eq!(definition.activities[2].task_type.as_deref(), Some("woo-publicatie"));
+
+        let wacht = &definition.events[1];
+        assert!(matches!(wacht.event_type, EventType::Timer));
+        assert!(matches!(&wacht.timer, Some(TimerDefinition::Duration(d)) if d == "P6W"));
+    }
+
+    #[test]
+    fn test_bpmn_xml_round_trip() {
+        for (source, dialect) in [
+            (DOCUMENT_PIPELINE, BpmnDialect::Zeebe),
+            (BESLUIT_CAMUNDA7, BpmnDialect::Camunda),
+        ] {
+            let original = parse_bpmn_xml(source).unwrap();
+            let xml = to_bpmn_xml(std::slice::from_ref(&original), dialect);
+            let parsed = parse_bpmn_xml(&xml).unwrap();
+
+            // Definitions have no PartialEq; compare their serialized form
+            assert_eq!(
+                serde_json::to_value(&parsed).unwrap(),
+                serde_json::to_value(&original).unwrap(),
+                "{}",
+                xml
+            );
+        }
+
+        // Zeebe output carries the extension elements Camunda 8 deploys with
+        let xml = to_bpmn_xml(&[parse_bpmn_xml(BESLUIT_CAMUNDA7).unwrap()], BpmnDialect::Zeebe);
+        assert!(xml.contains(r#"<zeebe:calledDecision decisionId="risico" resultVariable="uitkomst" />"#));
+        assert!(xml.contains(r#"<zeebe:taskDefinition type="woo-publicatie" />"#));
+        assert!(xml.contains("Besluit &amp; bezwaar"));
+    }
+
+    #[test]
+    fn test_validate_rejects_broken_definitions() {
+        let broken = DOCUMENT_PIPELINE.replace(r#"targetRef="EndEvent_Done" />"#, r#"targetRef="Nergens" />"#);
+        let mut engine = BpmnProcessEngine::new(Arc::new(DmnEvaluator::new()));
+        assert!(matches!(engine.load_bpmn_xml(&broken), Err(BpmnError::InvalidDefinition(_))));
+
+        let broken = DOCUMENT_PIPELINE.replace(r#"default="Flow_SkipWait""#, r#"default="Flow_to_End""#);
+        assert!(matches!(parse_bpmn_xml(&broken).unwrap().validate(), Err(BpmnError::InvalidDefinition(_))));
+
+        assert!(matches!(parse_bpmn_xml("<definitions/>"), Err(BpmnError::ParseError(_))));
+        assert!(matches!(parse_bpmn_xml("<process id=\"p\"/>"), Err(BpmnError::ParseError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_resume_stored_instance_after_restart() {
+        let store = Arc::new(InMemoryProcessStore::new());
+        let engine = subsidie_engine().with_store(store.clone());
+
+        let instance = engine
+            .start_process(
+                "subsidie",
+                HashMap::from([("bedrag".to_string(), DecisionValue::Integer(25000))]),
+            )
+            .await
+            .unwrap();
+        drop(engine);
+
+        // A fresh engine on the same store picks the instance up again
+        let engine = subsidie_engine().with_store(store.clone());
+        let mut resumed = engine.get_instance(instance.id).await.unwrap().unwrap();
+        assert_eq!(resumed.waiting_tasks(), vec!["beoordelen"]);
+        assert_eq!(resumed.variables["risico"].as_str(), Some("hoog"));
+
+        engine.complete_task(&mut resumed, "beoordelen", HashMap::new()).await.unwrap();
+
+        let stored = engine.get_instance(instance.id).await.unwrap().unwrap();
+        assert_eq!(stored.state, ProcessInstanceState::Completed);
+        let history = engine.instance_history(instance.id).await.unwrap();
+        let ids: Vec<_> = history.iter().map(|r| r.element_id.as_str()).collect();
+        assert_eq!(ids, vec!["start", "bepaal_risico", "xor", "beoordelen", "end"]);
+
+        // History is append-only, whatever a caller does to its copy
+        resumed.history.clear();
+        store.save(&resumed).await.unwrap();
+        assert_eq!(engine.instance_history(instance.id).await.unwrap().len(), 5);
+
+        let json = serde_json::to_string(&stored).unwrap();
+        let restored: ProcessInstance = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.state, ProcessInstanceState::Completed);
+        assert_eq!("completed".parse::<ProcessInstanceState>().unwrap(), ProcessInstanceState::Completed);
+    }
+
+    #[tokio::test]
+    async fn test_trigger_stored_timers() {
+        let store = Arc::new(InMemoryProcessStore::new());
+        let mut engine = BpmnProcessEngine::new(Arc::new(DmnEvaluator::new())).with_store(store.clone());
+        let mut definition = process("herinnering");
+        let mut wacht = event("wacht", EventType::Timer);
+        wacht.timer = Some(TimerDefinition::Duration("PT1H".to_string()));
+        definition.events = vec![event("start", EventType::Start), wacht, event("end", EventType::End)];
+        definition.sequence_flows = vec![flow("start", "wacht", None), flow("wacht", "end", None)];
+        engine.load_process(definition).unwrap();
+
+        let instance = engine.start_process("herinnering", HashMap::new()).await.unwrap();
+        let due = instance.next_timer().unwrap();
+
+        assert_eq!(engine.trigger_stored_timers(instance.started_at).await.unwrap(), 0);
+        assert_eq!(engine.trigger_stored_timers(due).await.unwrap(), 1);
+
+        let stored = engine.get_instance(instance.id).await.unwrap().unwrap();
+        assert_eq!(stored.state, ProcessInstanceState::Completed);
+        assert!(store.list_running().await.unwrap().is_empty());
     }
 }
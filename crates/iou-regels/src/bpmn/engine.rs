@@ -0,0 +1,444 @@
+//! Token execution for [`BpmnProcessEngine`]
+
+use super::{
+    Activity, ActivityRecord, ActivityType, BpmnError, BpmnProcessEngine, Event, EventType,
+    Gateway, GatewayType, ProcessDefinition, ProcessInstance, ProcessInstanceState, SequenceFlow,
+    TimerDefinition, Token, TokenWait,
+};
+use crate::dmn::feel;
+use crate::dmn::DecisionValue;
+use chrono::{DateTime, Months, Utc};
+use std::collections::{HashMap, HashSet, VecDeque};
+use uuid::Uuid;
+
+/// Upper bound on flow node visits per run, to stop processes that loop
+/// without ever waiting
+const MAX_STEPS: usize = 10_000;
+
+/// A token arriving at a flow node
+pub(super) struct Arrival {
+    element_id: String,
+    via: Option<String>,
+}
+
+impl Arrival {
+    pub fn new(element_id: &str, via: Option<&str>) -> Self {
+        Self {
+            element_id: element_id.to_string(),
+            via: via.map(str::to_string),
+        }
+    }
+}
+
+/// An activity, gateway or event
+#[derive(Clone, Copy)]
+enum Node<'a> {
+    Activity(&'a Activity),
+    Gateway(&'a Gateway),
+    Event(&'a Event),
+}
+
+impl<'a> Node<'a> {
+    fn find(definition: &'a ProcessDefinition, id: &str) -> Option<Self> {
+        definition.activities.iter().find(|a| a.id == id).map(Node::Activity)
+            .or_else(|| definition.gateways.iter().find(|g| g.id == id).map(Node::Gateway))
+            .or_else(|| definition.events.iter().find(|e| e.id == id).map(Node::Event))
+    }
+
+    fn id(&self) -> &'a str {
+        match self {
+            Node::Activity(a) => &a.id,
+            Node::Gateway(g) => &g.id,
+            Node::Event(e) => &e.id,
+        }
+    }
+
+    fn name(&self) -> &'a str {
+        match self {
+            Node::Activity(a) => &a.name,
+            Node::Gateway(g) => &g.name,
+            Node::Event(e) => &e.name,
+        }
+    }
+
+    /// BPMN element name, as used in the XML
+    fn element_type(&self) -> &'static str {
+        match self {
+            Node::Activity(a) => match a.activity_type {
+                ActivityType::Task => "task",
+                ActivityType::UserTask => "userTask",
+                ActivityType::ServiceTask => "serviceTask",
+                ActivityType::SendTask => "sendTask",
+                ActivityType::ReceiveTask => "receiveTask",
+                ActivityType::ScriptTask => "scriptTask",
+                ActivityType::BusinessRuleTask => "businessRuleTask",
+                ActivityType::SubProcess => "subProcess",
+            },
+            Node::Gateway(g) => match g.gateway_type {
+                GatewayType::Exclusive => "exclusiveGateway",
+                GatewayType::Inclusive => "inclusiveGateway",
+                GatewayType::Parallel => "parallelGateway",
+                GatewayType::EventBased => "eventBasedGateway",
+            },
+            Node::Event(e) => match e.event_type {
+                EventType::Start => "startEvent",
+                EventType::End => "endEvent",
+                EventType::Boundary => "boundaryEvent",
+                EventType::Timer | EventType::Message => "intermediateCatchEvent",
+                EventType::Intermediate if e.timer.is_some() => "intermediateCatchEvent",
+                EventType::Intermediate => "intermediateThrowEvent",
+            },
+        }
+    }
+}
+
+impl BpmnProcessEngine {
+    /// Move tokens forward until every token waits or has been consumed
+    pub(super) fn run(
+        &self,
+        definition: &ProcessDefinition,
+        instance: &mut ProcessInstance,
+        mut arrivals: VecDeque<Arrival>,
+        now: DateTime<Utc>,
+    ) -> Result<(), BpmnError> {
+        let mut steps = 0;
+
+        loop {
+            while let Some(arrival) = arrivals.pop_front() {
+                steps += 1;
+                if steps > MAX_STEPS {
+                    return Err(BpmnError::ExecutionError(format!(
+                        "Process {} did not reach a wait state after {} steps",
+                        definition.id, MAX_STEPS
+                    )));
+                }
+                self.arrive(definition, instance, arrival, &mut arrivals, now)?;
+            }
+
+            // Inclusive joins can only fire once nothing else is moving
+            match ready_inclusive_join(definition, instance) {
+                Some(gateway) => {
+                    let (joined, waiting): (Vec<Token>, Vec<Token>) =
+                        std::mem::take(&mut instance.tokens)
+                            .into_iter()
+                            .partition(|t| t.element_id == gateway.id && t.wait == TokenWait::Join);
+                    instance.tokens = waiting;
+
+                    let started_at = joined.iter().map(|t| t.entered_at).min().unwrap_or(now);
+                    record(instance, Node::Gateway(gateway), started_at, now);
+                    self.follow_flows(definition, instance, Node::Gateway(gateway), false, &mut arrivals)?;
+                }
+                None => break,
+            }
+        }
+
+        instance.current_activity = instance.tokens.first().map(|t| t.element_id.clone());
+        if instance.tokens.is_empty() {
+            instance.state = ProcessInstanceState::Completed;
+            instance.completed_at = Some(now);
+        }
+        Ok(())
+    }
+
+    /// Move a parked token on after its task completed or its timer fired
+    pub(super) fn leave(
+        &self,
+        definition: &ProcessDefinition,
+        instance: &mut ProcessInstance,
+        token: &Token,
+        arrivals: &mut VecDeque<Arrival>,
+        now: DateTime<Utc>,
+    ) -> Result<(), BpmnError> {
+        let node = find_node(definition, &token.element_id)?;
+        record(instance, node, token.entered_at, now);
+        self.follow_flows(definition, instance, node, false, arrivals)
+    }
+
+    /// Handle a token arriving at a flow node
+    fn arrive(
+        &self,
+        definition: &ProcessDefinition,
+        instance: &mut ProcessInstance,
+        arrival: Arrival,
+        arrivals: &mut VecDeque<Arrival>,
+        now: DateTime<Utc>,
+    ) -> Result<(), BpmnError> {
+        let node = find_node(definition, &arrival.element_id)?;
+
+        match node {
+            Node::Event(event) => match (&event.event_type, &event.timer) {
+                (EventType::End, _) => record(instance, node, now, now),
+                (EventType::Message, _) => park(instance, arrival, TokenWait::Task, now),
+                (EventType::Timer | EventType::Intermediate, Some(timer)) => {
+                    let due = timer_due(timer, now)?;
+                    park(instance, arrival, TokenWait::Timer { due }, now);
+                }
+                (EventType::Timer, None) => {
+                    return Err(BpmnError::ExecutionError(format!(
+                        "Timer event {} has no timer definition",
+                        event.id
+                    )));
+                }
+                (EventType::Start | EventType::Intermediate | EventType::Boundary, _) => {
+                    record(instance, node, now, now);
+                    self.follow_flows(definition, instance, node, false, arrivals)?;
+                }
+            },
+
+            Node::Activity(activity) => match activity.activity_type {
+                ActivityType::UserTask | ActivityType::ReceiveTask | ActivityType::ServiceTask => {
+                    park(instance, arrival, TokenWait::Task, now);
+                }
+                ActivityType::BusinessRuleTask => {
+                    let outputs = self.evaluate_decision(activity, &instance.variables)?;
+                    // A single-output result is also stored under the result variable
+                    let single = match outputs.len() {
+                        1 => outputs.values().next().cloned(),
+                        _ => None,
+                    };
+                    if let (Some(variable), Some(value)) = (&activity.result_variable, single) {
+                        instance.variables.insert(variable.clone(), value);
+                    }
+                    instance.variables.extend(outputs);
+                    record(instance, node, now, now);
+                    self.follow_flows(definition, instance, node, false, arrivals)?;
+                }
+                ActivityType::Task | ActivityType::SendTask | ActivityType::ScriptTask => {
+                    record(instance, node, now, now);
+                    self.follow_flows(definition, instance, node, false, arrivals)?;
+                }
+                ActivityType::SubProcess => {
+                    return Err(BpmnError::ExecutionError(format!(
+                        "Sub-process {} cannot be executed by the embedded engine",
+                        activity.id
+                    )));
+                }
+            },
+
+            Node::Gateway(gateway) => {
+                let incoming = incoming_flows(definition, &gateway.id).count();
+
+                match gateway.gateway_type {
+                    GatewayType::EventBased => {
+                        return Err(BpmnError::ExecutionError(format!(
+                            "Event-based gateway {} cannot be executed by the embedded engine",
+                            gateway.id
+                        )));
+                    }
+                    GatewayType::Exclusive => {
+                        record(instance, node, now, now);
+                        self.follow_flows(definition, instance, node, true, arrivals)?;
+                    }
+                    GatewayType::Parallel | GatewayType::Inclusive if incoming <= 1 => {
+                        record(instance, node, now, now);
+                        self.follow_flows(definition, instance, node, false, arrivals)?;
+                    }
+                    GatewayType::Inclusive => park(instance, arrival, TokenWait::Join, now),
+                    GatewayType::Parallel => {
+                        park(instance, arrival, TokenWait::Join, now);
+
+                        // Fire once a token waits on every incoming flow
+                        let mut joined = Vec::new();
+                        let mut covered = HashSet::new();
+                        for (i, token) in instance.tokens.iter().enumerate() {
+                            if token.element_id == gateway.id
+                                && token.wait == TokenWait::Join
+                                && covered.insert(token.arrived_via.clone())
+                            {
+                                joined.push(i);
+                            }
+                        }
+                        if covered.len() == incoming {
+                            let mut started_at = now;
+                            for i in joined.into_iter().rev() {
+                                started_at = started_at.min(instance.tokens.remove(i).entered_at);
+                            }
+                            record(instance, node, started_at, now);
+                            self.follow_flows(definition, instance, node, false, arrivals)?;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Send tokens over the outgoing flows of a node
+    ///
+    /// Unconditional flows are always taken, conditional flows when their
+    /// condition holds, and the default flow when nothing else was taken.
+    /// `exclusive` stops at the first flow taken. A node without outgoing
+    /// flows ends its token.
+    fn follow_flows(
+        &self,
+        definition: &ProcessDefinition,
+        instance: &ProcessInstance,
+        node: Node<'_>,
+        exclusive: bool,
+        arrivals: &mut VecDeque<Arrival>,
+    ) -> Result<(), BpmnError> {
+        let default_flow = match node {
+            Node::Gateway(g) => g.default_flow.as_deref(),
+            _ => None,
+        };
+
+        let mut outgoing = 0;
+        let mut taken: Vec<&SequenceFlow> = Vec::new();
+        let mut default = None;
+        for flow in outgoing_flows(definition, node.id()) {
+            outgoing += 1;
+            if Some(flow.id.as_str()) == default_flow {
+                default = Some(flow);
+                continue;
+            }
+            let take = match &flow.condition {
+                Some(condition) => evaluate_condition(flow, condition, &instance.variables)?,
+                None => true,
+            };
+            if take {
+                taken.push(flow);
+                if exclusive {
+                    break;
+                }
+            }
+        }
+
+        if taken.is_empty() {
+            match default {
+                Some(flow) => taken.push(flow),
+                None if outgoing == 0 => return Ok(()),
+                None => {
+                    return Err(BpmnError::ExecutionError(format!(
+                        "No outgoing flow of {} can be taken",
+                        node.id()
+                    )));
+                }
+            }
+        }
+
+        arrivals.extend(taken.into_iter().map(|f| Arrival::new(&f.target_ref, Some(&f.id))));
+        Ok(())
+    }
+}
+
+fn find_node<'a>(definition: &'a ProcessDefinition, id: &str) -> Result<Node<'a>, BpmnError> {
+    Node::find(definition, id).ok_or_else(|| {
+        BpmnError::ExecutionError(format!("Unknown flow node {} in process {}", id, definition.id))
+    })
+}
+
+fn outgoing_flows<'a>(
+    definition: &'a ProcessDefinition,
+    id: &'a str,
+) -> impl Iterator<Item = &'a SequenceFlow> + 'a {
+    definition.sequence_flows.iter().filter(move |f| f.source_ref == id)
+}
+
+fn incoming_flows<'a>(
+    definition: &'a ProcessDefinition,
+    id: &'a str,
+) -> impl Iterator<Item = &'a SequenceFlow> + 'a {
+    definition.sequence_flows.iter().filter(move |f| f.target_ref == id)
+}
+
+fn park(instance: &mut ProcessInstance, arrival: Arrival, wait: TokenWait, now: DateTime<Utc>) {
+    instance.tokens.push(Token {
+        id: Uuid::new_v4(),
+        element_id: arrival.element_id,
+        arrived_via: arrival.via,
+        entered_at: now,
+        wait,
+    });
+}
+
+fn record(instance: &mut ProcessInstance, node: Node<'_>, started_at: DateTime<Utc>, now: DateTime<Utc>) {
+    instance.history.push(ActivityRecord {
+        element_id: node.id().to_string(),
+        element_name: node.name().to_string(),
+        element_type: node.element_type().to_string(),
+        started_at,
+        completed_at: now,
+    });
+}
+
+/// Evaluate a sequence flow condition; `null` counts as false
+fn evaluate_condition(
+    flow: &SequenceFlow,
+    condition: &str,
+    variables: &HashMap<String, DecisionValue>,
+) -> Result<bool, BpmnError> {
+    // Zeebe writes FEEL expressions as `=expression`
+    let source = condition.trim();
+    let source = source.strip_prefix('=').unwrap_or(source);
+
+    let value = feel::Expression::parse(source)
+        .and_then(|e| e.evaluate(variables))
+        .map_err(|e| BpmnError::EvaluationError(format!("Condition of flow {}: {}", flow.id, e)))?;
+
+    match value {
+        feel::Value::Boolean(b) => Ok(b),
+        feel::Value::Null => Ok(false),
+        other => Err(BpmnError::EvaluationError(format!(
+            "Condition of flow {} gives a {}, not a boolean",
+            flow.id,
+            other.type_name()
+        ))),
+    }
+}
+
+fn timer_due(timer: &TimerDefinition, now: DateTime<Utc>) -> Result<DateTime<Utc>, BpmnError> {
+    let invalid = |value: &str| BpmnError::ExecutionError(format!("Invalid timer {}", value));
+
+    match timer {
+        TimerDefinition::Date(date) => DateTime::parse_from_rfc3339(date.trim())
+            .map(|d| d.with_timezone(&Utc))
+            .map_err(|_| invalid(date)),
+        TimerDefinition::Duration(duration) => match feel::parse_duration(duration.trim()) {
+            Ok(feel::Value::DaysTime(d)) => now.checked_add_signed(d),
+            Ok(feel::Value::YearsMonths(m)) if m >= 0 => now.checked_add_months(Months::new(m as u32)),
+            Ok(feel::Value::YearsMonths(m)) => now.checked_sub_months(Months::new(m.unsigned_abs() as u32)),
+            _ => None,
+        }
+        .ok_or_else(|| invalid(duration)),
+        TimerDefinition::Cycle(cycle) => Err(BpmnError::ExecutionError(format!(
+            "Timer cycle {} cannot be executed by the embedded engine",
+            cycle
+        ))),
+    }
+}
+
+/// An inclusive gateway with waiting tokens that no other token can still reach
+fn ready_inclusive_join<'a>(
+    definition: &'a ProcessDefinition,
+    instance: &ProcessInstance,
+) -> Option<&'a Gateway> {
+    definition
+        .gateways
+        .iter()
+        .filter(|g| matches!(g.gateway_type, GatewayType::Inclusive))
+        .filter(|g| {
+            instance.tokens.iter().any(|t| t.element_id == g.id && t.wait == TokenWait::Join)
+        })
+        .find(|g| {
+            !instance
+                .tokens
+                .iter()
+                .filter(|t| t.element_id != g.id)
+                .any(|t| can_reach(definition, &t.element_id, &g.id))
+        })
+}
+
+fn can_reach(definition: &ProcessDefinition, from: &str, to: &str) -> bool {
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::from([from]);
+    while let Some(id) = queue.pop_front() {
+        if id == to {
+            return true;
+        }
+        if seen.insert(id) {
+            queue.extend(outgoing_flows(definition, id).map(|f| f.target_ref.as_str()));
+        }
+    }
+    false
+}
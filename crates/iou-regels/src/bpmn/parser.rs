@@ -0,0 +1,258 @@
+//! BPMN 2.0 XML parser
+//!
+//! Maps the `<process>` elements of a `<definitions>` document onto
+//! [`ProcessDefinition`]s. Camunda 7 extensions are attributes in the
+//! `camunda:` namespace (`camunda:decisionRef`, `camunda:topic`, ...);
+//! Camunda 8 uses `zeebe:` elements inside `<extensionElements>`. Both
+//! are read. Diagram interchange and unsupported elements (lanes, data
+//! objects, annotations) are ignored.
+
+use super::{
+    Activity, ActivityType, BpmnError, Event, EventType, Gateway, GatewayType,
+    MessageDefinition, ProcessDefinition, SequenceFlow, TimerDefinition,
+};
+use crate::xml::{self, XmlElement};
+
+/// Parse all processes in a BPMN `definitions` document
+pub(super) fn parse_definitions(source: &str) -> Result<Vec<ProcessDefinition>, BpmnError> {
+    let root = xml::parse(source).map_err(BpmnError::ParseError)?;
+
+    if root.name != "definitions" {
+        return Err(BpmnError::ParseError(format!(
+            "Expected <definitions> root element, found <{}>",
+            root.name
+        )));
+    }
+
+    let messages = root
+        .children_named("message")
+        .map(|message| {
+            let id = required_id(message, "Message")?;
+            Ok(MessageDefinition {
+                name: message.attr("name").unwrap_or(&id).to_string(),
+                correlation_key: extension(message, "subscription")
+                    .and_then(|s| s.attr("correlationKey"))
+                    .map(str::to_string),
+                id,
+            })
+        })
+        .collect::<Result<Vec<_>, BpmnError>>()?;
+
+    let processes = root
+        .children_named("process")
+        .map(|process| parse_process(process, &messages))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if processes.is_empty() {
+        return Err(BpmnError::ParseError("Definitions contain no processes".into()));
+    }
+
+    Ok(processes)
+}
+
+fn parse_process(
+    element: &XmlElement,
+    messages: &[MessageDefinition],
+) -> Result<ProcessDefinition, BpmnError> {
+    let id = required_id(element, "Process")?;
+    let version = element
+        .attr("versionTag")
+        .or_else(|| extension(element, "versionTag").and_then(|v| v.attr("value")))
+        .unwrap_or("1.0")
+        .to_string();
+
+    let mut definition = ProcessDefinition {
+        name: element.attr("name").unwrap_or(&id).to_string(),
+        id,
+        version,
+        variables: vec![],
+        activities: vec![],
+        gateways: vec![],
+        events: vec![],
+        sequence_flows: vec![],
+        messages: vec![],
+    };
+
+    for child in &element.children {
+        let activity_type = match child.name.as_str() {
+            "task" | "manualTask" => Some(ActivityType::Task),
+            "userTask" => Some(ActivityType::UserTask),
+            "serviceTask" => Some(ActivityType::ServiceTask),
+            "sendTask" => Some(ActivityType::SendTask),
+            "receiveTask" => Some(ActivityType::ReceiveTask),
+            "scriptTask" => Some(ActivityType::ScriptTask),
+            "businessRuleTask" => Some(ActivityType::BusinessRuleTask),
+            "subProcess" | "callActivity" => Some(ActivityType::SubProcess),
+            _ => None,
+        };
+        if let Some(activity_type) = activity_type {
+            definition.activities.push(parse_activity(child, activity_type)?);
+            continue;
+        }
+
+        let gateway_type = match child.name.as_str() {
+            "exclusiveGateway" => Some(GatewayType::Exclusive),
+            "inclusiveGateway" => Some(GatewayType::Inclusive),
+            "parallelGateway" => Some(GatewayType::Parallel),
+            "eventBasedGateway" => Some(GatewayType::EventBased),
+            _ => None,
+        };
+        if let Some(gateway_type) = gateway_type {
+            definition.gateways.push(Gateway {
+                id: required_id(child, "Gateway")?,
+                name: child.attr("name").unwrap_or_default().to_string(),
+                gateway_type,
+                incoming: vec![],
+                outgoing: vec![],
+                default_flow: child.attr("default").map(str::to_string),
+            });
+            continue;
+        }
+
+        match child.name.as_str() {
+            "startEvent" | "endEvent" | "intermediateCatchEvent" | "intermediateThrowEvent"
+            | "boundaryEvent" => definition.events.push(parse_event(child)?),
+            "sequenceFlow" => definition.sequence_flows.push(parse_sequence_flow(child)?),
+            _ => {}
+        }
+    }
+
+    // Incoming/outgoing lists follow the sequence flows, whatever the
+    // <incoming>/<outgoing> children say
+    for activity in &mut definition.activities {
+        activity.incoming = flow_ids(&definition.sequence_flows, |f| f.target_ref == activity.id);
+        activity.outgoing = flow_ids(&definition.sequence_flows, |f| f.source_ref == activity.id);
+    }
+    for gateway in &mut definition.gateways {
+        gateway.incoming = flow_ids(&definition.sequence_flows, |f| f.target_ref == gateway.id);
+        gateway.outgoing = flow_ids(&definition.sequence_flows, |f| f.source_ref == gateway.id);
+    }
+
+    definition.messages = messages
+        .iter()
+        .filter(|m| definition.events.iter().any(|e| e.message_ref.as_ref() == Some(&m.id)))
+        .cloned()
+        .collect();
+
+    Ok(definition)
+}
+
+fn parse_activity(element: &XmlElement, activity_type: ActivityType) -> Result<Activity, BpmnError> {
+    let id = required_id(element, "Activity")?;
+
+    let task_definition = extension(element, "taskDefinition");
+    let called_decision = extension(element, "calledDecision");
+
+    let retries = match task_definition.and_then(|t| t.attr("retries")) {
+        Some(retries) => Some(retries.trim().parse::<u32>().map_err(|_| {
+            BpmnError::ParseError(format!("Activity '{}': invalid retries {}", id, retries))
+        })?),
+        None => None,
+    };
+
+    Ok(Activity {
+        name: element.attr("name").unwrap_or_default().to_string(),
+        activity_type,
+        incoming: vec![],
+        outgoing: vec![],
+        decision_ref: called_decision
+            .and_then(|d| d.attr("decisionId"))
+            .or_else(|| element.attr("decisionRef"))
+            .map(str::to_string),
+        result_variable: called_decision
+            .and_then(|d| d.attr("resultVariable"))
+            .or_else(|| element.attr("resultVariable"))
+            .map(str::to_string),
+        task_type: task_definition
+            .and_then(|t| t.attr("type"))
+            .or_else(|| element.attr("topic"))
+            .map(str::to_string),
+        retries,
+        assignee: extension(element, "assignmentDefinition")
+            .and_then(|a| a.attr("assignee"))
+            .or_else(|| element.attr("assignee"))
+            .map(str::to_string),
+        form_key: extension(element, "formDefinition")
+            .and_then(|f| f.attr("formKey"))
+            .or_else(|| element.attr("formKey"))
+            .map(str::to_string),
+        id,
+    })
+}
+
+fn parse_event(element: &XmlElement) -> Result<Event, BpmnError> {
+    let id = required_id(element, "Event")?;
+
+    let timer = element
+        .child("timerEventDefinition")
+        .map(|definition| {
+            let timer = if let Some(duration) = definition.child_text("timeDuration") {
+                TimerDefinition::Duration(duration.to_string())
+            } else if let Some(date) = definition.child_text("timeDate") {
+                TimerDefinition::Date(date.to_string())
+            } else if let Some(cycle) = definition.child_text("timeCycle") {
+                TimerDefinition::Cycle(cycle.to_string())
+            } else {
+                return Err(BpmnError::ParseError(format!("Timer event '{}' has no timer", id)));
+            };
+            Ok(timer)
+        })
+        .transpose()?;
+
+    let message = element.child("messageEventDefinition");
+    let message_ref = message.and_then(|m| m.attr("messageRef")).map(str::to_string);
+
+    let event_type = match element.name.as_str() {
+        "startEvent" => EventType::Start,
+        "endEvent" => EventType::End,
+        "boundaryEvent" => EventType::Boundary,
+        "intermediateCatchEvent" if timer.is_some() => EventType::Timer,
+        "intermediateCatchEvent" if message.is_some() => EventType::Message,
+        _ => EventType::Intermediate,
+    };
+
+    Ok(Event {
+        name: element.attr("name").unwrap_or_default().to_string(),
+        event_type,
+        timer,
+        message_ref,
+        attached_to: element.attr("attachedToRef").map(str::to_string),
+        id,
+    })
+}
+
+fn parse_sequence_flow(element: &XmlElement) -> Result<SequenceFlow, BpmnError> {
+    let id = required_id(element, "Sequence flow")?;
+    let endpoint = |attr: &str| {
+        element.attr(attr).map(str::to_string).ok_or_else(|| {
+            BpmnError::ParseError(format!("Sequence flow '{}' has no {}", id, attr))
+        })
+    };
+
+    Ok(SequenceFlow {
+        name: element.attr("name").unwrap_or_default().to_string(),
+        source_ref: endpoint("sourceRef")?,
+        target_ref: endpoint("targetRef")?,
+        condition: element
+            .child_text("conditionExpression")
+            .filter(|c| !c.is_empty())
+            .map(str::to_string),
+        id,
+    })
+}
+
+fn required_id(element: &XmlElement, what: &str) -> Result<String, BpmnError> {
+    element
+        .attr("id")
+        .map(str::to_string)
+        .ok_or_else(|| BpmnError::ParseError(format!("{} <{}> without id", what, element.name)))
+}
+
+/// A `zeebe:` (or other) extension element
+fn extension<'a>(element: &'a XmlElement, name: &str) -> Option<&'a XmlElement> {
+    element.child("extensionElements")?.child(name)
+}
+
+fn flow_ids(flows: &[SequenceFlow], select: impl Fn(&SequenceFlow) -> bool) -> Vec<String> {
+    flows.iter().filter(|f| select(f)).map(|f| f.id.clone()).collect()
+}
@@ -0,0 +1,85 @@
+//! Persistence for process instances
+//!
+//! An engine built [`with_store`](super::BpmnProcessEngine::with_store)
+//! saves each instance after every start, task completion and timer, so
+//! running processes survive a restart: load them again with
+//! [`get_instance`](super::BpmnProcessEngine::get_instance) and continue
+//! where they stopped.
+//!
+//! History is append-only. `save` adds the records a store has not seen
+//! yet and never rewrites earlier ones, so [`ProcessInstanceStore::history`]
+//! can serve as an audit trail.
+
+use super::{ActivityRecord, BpmnError, ProcessInstance, ProcessInstanceState};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use uuid::Uuid;
+
+/// Durable storage for process instances
+#[async_trait]
+pub trait ProcessInstanceStore: Send + Sync {
+    /// Insert or update an instance and append its new history records
+    async fn save(&self, instance: &ProcessInstance) -> Result<(), BpmnError>;
+
+    /// Load an instance, including its full history
+    async fn load(&self, id: Uuid) -> Result<Option<ProcessInstance>, BpmnError>;
+
+    /// All instances that are still running
+    async fn list_running(&self) -> Result<Vec<ProcessInstance>, BpmnError>;
+
+    /// Completed flow nodes of an instance, oldest first
+    async fn history(&self, id: Uuid) -> Result<Vec<ActivityRecord>, BpmnError>;
+}
+
+/// Process instance store that keeps everything in memory
+///
+/// For tests and one-off simulations; nothing survives the process.
+#[derive(Default)]
+pub struct InMemoryProcessStore {
+    instances: RwLock<HashMap<Uuid, ProcessInstance>>,
+}
+
+impl InMemoryProcessStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ProcessInstanceStore for InMemoryProcessStore {
+    async fn save(&self, instance: &ProcessInstance) -> Result<(), BpmnError> {
+        let mut instances = self.instances.write()
+            .map_err(|e| BpmnError::StoreError(e.to_string()))?;
+
+        let mut saved = instance.clone();
+        if let Some(previous) = instances.get(&instance.id) {
+            let stored = previous.history.len().min(instance.history.len());
+            saved.history = previous.history.clone();
+            saved.history.extend_from_slice(&instance.history[stored..]);
+        }
+        instances.insert(instance.id, saved);
+        Ok(())
+    }
+
+    async fn load(&self, id: Uuid) -> Result<Option<ProcessInstance>, BpmnError> {
+        let instances = self.instances.read()
+            .map_err(|e| BpmnError::StoreError(e.to_string()))?;
+        Ok(instances.get(&id).cloned())
+    }
+
+    async fn list_running(&self) -> Result<Vec<ProcessInstance>, BpmnError> {
+        let instances = self.instances.read()
+            .map_err(|e| BpmnError::StoreError(e.to_string()))?;
+        Ok(instances.values()
+            .filter(|i| i.state == ProcessInstanceState::Running)
+            .cloned()
+            .collect())
+    }
+
+    async fn history(&self, id: Uuid) -> Result<Vec<ActivityRecord>, BpmnError> {
+        let instances = self.instances.read()
+            .map_err(|e| BpmnError::StoreError(e.to_string()))?;
+        Ok(instances.get(&id).map(|i| i.history.clone()).unwrap_or_default())
+    }
+}
@@ -0,0 +1,291 @@
+//! BPMN 2.0 XML writer
+//!
+//! Writes [`ProcessDefinition`]s as a `<definitions>` document that the
+//! parser reads back unchanged and that Camunda 7 or 8 can deploy,
+//! depending on the [`BpmnDialect`].
+
+use super::{
+    Activity, ActivityType, BpmnDialect, Event, EventType, Gateway, GatewayType,
+    MessageDefinition, ProcessDefinition, SequenceFlow, TimerDefinition,
+};
+use quick_xml::escape::escape;
+use std::fmt::Write;
+
+const BPMN_NS: &str = "http://www.omg.org/spec/BPMN/20100524/MODEL";
+const XSI_NS: &str = "http://www.w3.org/2001/XMLSchema-instance";
+const ZEEBE_NS: &str = "http://camunda.org/schema/zeebe/1.0";
+const CAMUNDA_NS: &str = "http://camunda.org/schema/1.0/bpmn";
+
+/// Write process definitions as a BPMN `definitions` document
+pub(super) fn write_definitions(processes: &[ProcessDefinition], dialect: BpmnDialect) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+
+    let (prefix, namespace) = match dialect {
+        BpmnDialect::Zeebe => ("zeebe", ZEEBE_NS),
+        BpmnDialect::Camunda => ("camunda", CAMUNDA_NS),
+    };
+    let id = processes.first().map(|p| p.id.as_str()).unwrap_or("process");
+    let _ = writeln!(
+        out,
+        "<bpmn:definitions xmlns:bpmn=\"{}\" xmlns:xsi=\"{}\" xmlns:{}=\"{}\" id=\"Definitions_{}\" \
+         targetNamespace=\"http://iou-modern.local/schema/bpmn\" exporter=\"IOU-Modern\" exporterVersion=\"1.0\">",
+        BPMN_NS, XSI_NS, prefix, namespace, escape(id)
+    );
+
+    // Messages live at definitions level and may be shared between processes
+    let mut messages: Vec<&MessageDefinition> = Vec::new();
+    for message in processes.iter().flat_map(|p| &p.messages) {
+        if !messages.iter().any(|m| m.id == message.id) {
+            messages.push(message);
+        }
+    }
+    for message in messages {
+        write_message(&mut out, message, dialect);
+    }
+
+    for process in processes {
+        write_process(&mut out, process, dialect);
+    }
+
+    out.push_str("</bpmn:definitions>\n");
+    out
+}
+
+fn write_message(out: &mut String, message: &MessageDefinition, dialect: BpmnDialect) {
+    let attrs = format!("id=\"{}\" name=\"{}\"", escape(&message.id), escape(&message.name));
+    match (&message.correlation_key, dialect) {
+        (Some(key), BpmnDialect::Zeebe) => {
+            let _ = writeln!(out, "  <bpmn:message {}>", attrs);
+            let _ = writeln!(
+                out,
+                "    <bpmn:extensionElements>\n      <zeebe:subscription correlationKey=\"{}\" />\n    </bpmn:extensionElements>",
+                escape(key)
+            );
+            out.push_str("  </bpmn:message>\n");
+        }
+        _ => {
+            let _ = writeln!(out, "  <bpmn:message {} />", attrs);
+        }
+    }
+}
+
+fn write_process(out: &mut String, process: &ProcessDefinition, dialect: BpmnDialect) {
+    let mut attrs = format!(
+        "id=\"{}\" name=\"{}\" isExecutable=\"true\"",
+        escape(&process.id),
+        escape(&process.name)
+    );
+    if dialect == BpmnDialect::Camunda {
+        let _ = write!(attrs, " camunda:versionTag=\"{}\"", escape(&process.version));
+    }
+    let _ = writeln!(out, "  <bpmn:process {}>", attrs);
+    if dialect == BpmnDialect::Zeebe {
+        let _ = writeln!(
+            out,
+            "    <bpmn:extensionElements>\n      <zeebe:versionTag value=\"{}\" />\n    </bpmn:extensionElements>",
+            escape(&process.version)
+        );
+    }
+
+    for event in &process.events {
+        write_event(out, process, event);
+    }
+    for activity in &process.activities {
+        write_activity(out, process, activity, dialect);
+    }
+    for gateway in &process.gateways {
+        write_gateway(out, process, gateway);
+    }
+    for flow in &process.sequence_flows {
+        write_sequence_flow(out, flow);
+    }
+
+    out.push_str("  </bpmn:process>\n");
+}
+
+fn write_event(out: &mut String, process: &ProcessDefinition, event: &Event) {
+    let element = match event.event_type {
+        EventType::Start => "startEvent",
+        EventType::End => "endEvent",
+        EventType::Boundary => "boundaryEvent",
+        EventType::Timer | EventType::Message => "intermediateCatchEvent",
+        EventType::Intermediate if event.timer.is_some() => "intermediateCatchEvent",
+        EventType::Intermediate => "intermediateThrowEvent",
+    };
+
+    let mut attrs = node_attrs(&event.id, &event.name);
+    if let Some(activity) = &event.attached_to {
+        let _ = write!(attrs, " attachedToRef=\"{}\"", escape(activity));
+    }
+
+    let mut body = String::new();
+    write_flow_refs(&mut body, process, &event.id);
+    if let Some(timer) = &event.timer {
+        let (element, value) = match timer {
+            TimerDefinition::Duration(v) => ("timeDuration", v),
+            TimerDefinition::Date(v) => ("timeDate", v),
+            TimerDefinition::Cycle(v) => ("timeCycle", v),
+        };
+        let _ = writeln!(
+            body,
+            "      <bpmn:timerEventDefinition id=\"{}_timer\">\n        \
+             <bpmn:{} xsi:type=\"bpmn:tFormalExpression\">{}</bpmn:{}>\n      \
+             </bpmn:timerEventDefinition>",
+            escape(&event.id), element, escape(value), element
+        );
+    }
+    if let Some(message) = &event.message_ref {
+        let _ = writeln!(
+            body,
+            "      <bpmn:messageEventDefinition id=\"{}_message\" messageRef=\"{}\" />",
+            escape(&event.id),
+            escape(message)
+        );
+    }
+
+    write_element(out, element, &attrs, &body);
+}
+
+fn write_activity(out: &mut String, process: &ProcessDefinition, activity: &Activity, dialect: BpmnDialect) {
+    let element = match activity.activity_type {
+        ActivityType::Task => "task",
+        ActivityType::UserTask => "userTask",
+        ActivityType::ServiceTask => "serviceTask",
+        ActivityType::SendTask => "sendTask",
+        ActivityType::ReceiveTask => "receiveTask",
+        ActivityType::ScriptTask => "scriptTask",
+        ActivityType::BusinessRuleTask => "businessRuleTask",
+        ActivityType::SubProcess => "subProcess",
+    };
+
+    let mut attrs = node_attrs(&activity.id, &activity.name);
+    let mut extensions = String::new();
+
+    match dialect {
+        BpmnDialect::Zeebe => {
+            if let Some(task_type) = &activity.task_type {
+                let _ = write!(extensions, "        <zeebe:taskDefinition type=\"{}\"", escape(task_type));
+                if let Some(retries) = activity.retries {
+                    let _ = write!(extensions, " retries=\"{}\"", retries);
+                }
+                extensions.push_str(" />\n");
+            }
+            if let Some(decision) = &activity.decision_ref {
+                // Zeebe requires a result variable
+                let result = activity.result_variable.as_ref().unwrap_or(decision);
+                let _ = writeln!(
+                    extensions,
+                    "        <zeebe:calledDecision decisionId=\"{}\" resultVariable=\"{}\" />",
+                    escape(decision),
+                    escape(result)
+                );
+            }
+            if let Some(assignee) = &activity.assignee {
+                let _ = writeln!(
+                    extensions,
+                    "        <zeebe:assignmentDefinition assignee=\"{}\" />",
+                    escape(assignee)
+                );
+            }
+            if let Some(form_key) = &activity.form_key {
+                let _ = writeln!(
+                    extensions,
+                    "        <zeebe:formDefinition formKey=\"{}\" />",
+                    escape(form_key)
+                );
+            }
+        }
+        BpmnDialect::Camunda => {
+            let optional = [
+                ("camunda:decisionRef", &activity.decision_ref),
+                ("camunda:resultVariable", &activity.result_variable),
+                ("camunda:assignee", &activity.assignee),
+                ("camunda:formKey", &activity.form_key),
+            ];
+            for (name, value) in optional {
+                if let Some(value) = value {
+                    let _ = write!(attrs, " {}=\"{}\"", name, escape(value));
+                }
+            }
+            if let Some(topic) = &activity.task_type {
+                let _ = write!(attrs, " camunda:type=\"external\" camunda:topic=\"{}\"", escape(topic));
+            }
+        }
+    }
+
+    let mut body = String::new();
+    if !extensions.is_empty() {
+        let _ = write!(body, "      <bpmn:extensionElements>\n{}      </bpmn:extensionElements>\n", extensions);
+    }
+    write_flow_refs(&mut body, process, &activity.id);
+
+    write_element(out, element, &attrs, &body);
+}
+
+fn write_gateway(out: &mut String, process: &ProcessDefinition, gateway: &Gateway) {
+    let element = match gateway.gateway_type {
+        GatewayType::Exclusive => "exclusiveGateway",
+        GatewayType::Inclusive => "inclusiveGateway",
+        GatewayType::Parallel => "parallelGateway",
+        GatewayType::EventBased => "eventBasedGateway",
+    };
+
+    let mut attrs = node_attrs(&gateway.id, &gateway.name);
+    if let Some(default) = &gateway.default_flow {
+        let _ = write!(attrs, " default=\"{}\"", escape(default));
+    }
+
+    let mut body = String::new();
+    write_flow_refs(&mut body, process, &gateway.id);
+    write_element(out, element, &attrs, &body);
+}
+
+fn write_sequence_flow(out: &mut String, flow: &SequenceFlow) {
+    let mut attrs = format!("id=\"{}\"", escape(&flow.id));
+    if !flow.name.is_empty() {
+        let _ = write!(attrs, " name=\"{}\"", escape(&flow.name));
+    }
+    let _ = write!(
+        attrs,
+        " sourceRef=\"{}\" targetRef=\"{}\"",
+        escape(&flow.source_ref),
+        escape(&flow.target_ref)
+    );
+
+    let mut body = String::new();
+    if let Some(condition) = &flow.condition {
+        let _ = writeln!(
+            body,
+            "      <bpmn:conditionExpression xsi:type=\"bpmn:tFormalExpression\">{}</bpmn:conditionExpression>",
+            escape(condition)
+        );
+    }
+    write_element(out, "sequenceFlow", &attrs, &body);
+}
+
+fn node_attrs(id: &str, name: &str) -> String {
+    let mut attrs = format!("id=\"{}\"", escape(id));
+    if !name.is_empty() {
+        let _ = write!(attrs, " name=\"{}\"", escape(name));
+    }
+    attrs
+}
+
+/// `<bpmn:incoming>`/`<bpmn:outgoing>` children, taken from the sequence flows
+fn write_flow_refs(body: &mut String, process: &ProcessDefinition, id: &str) {
+    for flow in process.sequence_flows.iter().filter(|f| f.target_ref == id) {
+        let _ = writeln!(body, "      <bpmn:incoming>{}</bpmn:incoming>", escape(&flow.id));
+    }
+    for flow in process.sequence_flows.iter().filter(|f| f.source_ref == id) {
+        let _ = writeln!(body, "      <bpmn:outgoing>{}</bpmn:outgoing>", escape(&flow.id));
+    }
+}
+
+fn write_element(out: &mut String, element: &str, attrs: &str, body: &str) {
+    if body.is_empty() {
+        let _ = writeln!(out, "    <bpmn:{} {} />", element, attrs);
+    } else {
+        let _ = write!(out, "    <bpmn:{} {}>\n{}    </bpmn:{}>\n", element, attrs, body, element);
+    }
+}
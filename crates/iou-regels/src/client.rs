@@ -3,10 +3,18 @@
 //! Biedt een dunne wrapper om SPARQL SELECT en CONSTRUCT queries
 //! te sturen naar regels.overheid.nl of de acc-omgeving.
 //!
+//! Zonder netwerk kan dezelfde client een lokale [`RdfStore`] bevragen
+//! (zie [`OpenRegelsClient::lokaal`]), en met [`OpenRegelsClient::with_cache`]
+//! komt er een read-through cache met TTL voor het endpoint te staan.
+//!
 //! Dit is een native-only module (niet beschikbaar voor WASM builds).
 
 #[cfg(not(target_arch = "wasm32"))]
 use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::{Arc, Mutex};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{Duration, Instant};
 
 #[cfg(not(target_arch = "wasm32"))]
 use anyhow::Result;
@@ -17,6 +25,9 @@ use serde::Deserialize;
 #[cfg(not(target_arch = "wasm32"))]
 use tracing::{debug, instrument};
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::rdf::RdfStore;
+
 // ── SPARQL response types ────────────────────────────────────────────────────
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -49,14 +60,24 @@ pub type Bindings = Vec<HashMap<String, SparqlValue>>;
 /// # Omgevingen
 ///
 /// Gebruik `OpenRegelsClient::acc()` tijdens ontwikkeling en
-/// `OpenRegelsClient::productie()` voor productiegebruik.
+/// `OpenRegelsClient::productie()` voor productiegebruik. Voor offline
+/// gebruik en tests is er `OpenRegelsClient::lokaal()`.
 #[cfg(not(target_arch = "wasm32"))]
 #[derive(Clone)]
 pub struct OpenRegelsClient {
-    http: Client,
+    bron: Bron,
+    cache: Option<Arc<QueryCache>>,
     pub endpoint: String,
 }
 
+/// Waar de antwoorden vandaan komen
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone)]
+enum Bron {
+    Endpoint(Client),
+    Lokaal(Arc<RdfStore>),
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 impl OpenRegelsClient {
     /// Maak een client aan voor de **acceptatieomgeving** (veilig om mee te experimenteren)
@@ -78,19 +99,66 @@ impl OpenRegelsClient {
             .expect("HTTP client aanmaken mislukt");
 
         Self {
-            http,
+            bron: Bron::Endpoint(http),
+            cache: None,
             endpoint: endpoint.to_string(),
         }
     }
 
+    /// Maak een client aan die een lokale triple store bevraagt in plaats van een endpoint
+    ///
+    /// `select`, `construct` en `fetch_resource` geven dezelfde vorm antwoord
+    /// als het live endpoint, zodat tools en evaluators ongewijzigd werken.
+    pub fn lokaal(store: RdfStore) -> Self {
+        Self {
+            bron: Bron::Lokaal(Arc::new(store)),
+            cache: None,
+            endpoint: "lokaal".to_string(),
+        }
+    }
+
+    /// Zet een read-through cache voor de bron; antwoorden blijven `ttl` geldig
+    pub fn with_cache(mut self, ttl: Duration) -> Self {
+        self.cache = Some(Arc::new(QueryCache::new(ttl)));
+        self
+    }
+
+    /// Geeft aan of deze client een lokale store bevraagt
+    pub fn is_lokaal(&self) -> bool {
+        matches!(self.bron, Bron::Lokaal(_))
+    }
+
+    /// Leeg de cache (bijvoorbeeld na een nieuwe publicatie in het register)
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.bindings.clear();
+            cache.documents.clear();
+        }
+    }
+
     /// Voer een SPARQL SELECT query uit; geeft de ruwe bindings terug
     #[instrument(skip(self, sparql), fields(endpoint = %self.endpoint))]
     pub async fn select(&self, sparql: &str) -> Result<Bindings> {
+        if let Some(bindings) = self.cache.as_ref().and_then(|c| c.bindings.get(sparql)) {
+            debug!("SPARQL SELECT uit cache");
+            return Ok(bindings);
+        }
         debug!("SPARQL SELECT:\n{sparql}");
 
-        let response = self
-            .http
-            .get(&self.endpoint)
+        let bindings = match &self.bron {
+            Bron::Lokaal(store) => store.select(sparql)?,
+            Bron::Endpoint(http) => Self::select_remote(http, &self.endpoint, sparql).await?,
+        };
+
+        if let Some(cache) = &self.cache {
+            cache.bindings.insert(sparql, bindings.clone());
+        }
+        Ok(bindings)
+    }
+
+    async fn select_remote(http: &Client, endpoint: &str, sparql: &str) -> Result<Bindings> {
+        let response = http
+            .get(endpoint)
             .query(&[("query", sparql)])
             .header("Accept", "application/sparql-results+json")
             .send()
@@ -104,11 +172,27 @@ impl OpenRegelsClient {
     /// Voer een SPARQL CONSTRUCT query uit; geeft JSON-LD terug
     #[instrument(skip(self, sparql), fields(endpoint = %self.endpoint))]
     pub async fn construct(&self, sparql: &str) -> Result<serde_json::Value> {
+        let key = format!("construct\n{sparql}");
+        if let Some(document) = self.cache.as_ref().and_then(|c| c.documents.get(&key)) {
+            debug!("SPARQL CONSTRUCT uit cache");
+            return Ok(document);
+        }
         debug!("SPARQL CONSTRUCT:\n{sparql}");
 
-        let response = self
-            .http
-            .get(&self.endpoint)
+        let document = match &self.bron {
+            Bron::Lokaal(store) => store.construct(sparql)?,
+            Bron::Endpoint(http) => Self::construct_remote(http, &self.endpoint, sparql).await?,
+        };
+
+        if let Some(cache) = &self.cache {
+            cache.documents.insert(&key, document.clone());
+        }
+        Ok(document)
+    }
+
+    async fn construct_remote(http: &Client, endpoint: &str, sparql: &str) -> Result<serde_json::Value> {
+        let response = http
+            .get(endpoint)
             .query(&[("query", sparql)])
             .header("Accept", "application/ld+json")
             .send()
@@ -119,9 +203,28 @@ impl OpenRegelsClient {
     }
 
     /// Haal een specifieke resource op via content negotiation (JSON-LD)
+    ///
+    /// Een lokale store geeft alle triples met `uri` als subject terug
+    /// (inclusief bijbehorende blank nodes) als expanded JSON-LD.
     pub async fn fetch_resource(&self, uri: &str) -> Result<serde_json::Value> {
-        let response = self
-            .http
+        let key = format!("resource\n{uri}");
+        if let Some(document) = self.cache.as_ref().and_then(|c| c.documents.get(&key)) {
+            return Ok(document);
+        }
+
+        let document = match &self.bron {
+            Bron::Lokaal(store) => store.describe(uri),
+            Bron::Endpoint(http) => Self::fetch_remote(http, uri).await?,
+        };
+
+        if let Some(cache) = &self.cache {
+            cache.documents.insert(&key, document.clone());
+        }
+        Ok(document)
+    }
+
+    async fn fetch_remote(http: &Client, uri: &str) -> Result<serde_json::Value> {
+        let response = http
             .get(uri)
             .header("Accept", "application/ld+json, application/json")
             .send()
@@ -131,3 +234,82 @@ impl OpenRegelsClient {
         Ok(response.json().await?)
     }
 }
+
+// ── Cache ────────────────────────────────────────────────────────────────────
+
+/// Read-through cache voor query-antwoorden, per query-tekst
+#[cfg(not(target_arch = "wasm32"))]
+struct QueryCache {
+    bindings: TtlCache<Bindings>,
+    documents: TtlCache<serde_json::Value>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl QueryCache {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            bindings: TtlCache::new(ttl),
+            documents: TtlCache::new(ttl),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+struct TtlCache<V> {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (Instant, V)>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<V: Clone> TtlCache<V> {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Geldige waarde voor `key`; verlopen entries worden meteen opgeruimd
+    fn get(&self, key: &str) -> Option<V> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        match entries.get(key) {
+            Some((stored, value)) if stored.elapsed() < self.ttl => Some(value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&self, key: &str, value: V) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.insert(key.to_string(), (Instant::now(), value));
+    }
+
+    fn clear(&self) {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+// ── Tests ────────────────────────────────────────────────────────────────────
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ttl_cache_verloopt() {
+        let cache = TtlCache::new(Duration::from_secs(60));
+        cache.insert("q", 1);
+        assert_eq!(cache.get("q"), Some(1));
+        assert_eq!(cache.get("andere"), None);
+        cache.clear();
+        assert_eq!(cache.get("q"), None);
+
+        let verlopen = TtlCache::new(Duration::ZERO);
+        verlopen.insert("q", 1);
+        assert_eq!(verlopen.get("q"), None);
+        assert!(verlopen.entries.lock().unwrap().is_empty());
+    }
+}
@@ -2,6 +2,20 @@
 //!
 //! This module integrates DMN decision tables with the Open Regels
 //! specificaties, allowing for business rule evaluation.
+//!
+//! Decisions are loaded from DMN 1.3/1.4 XML (see [`DmnEvaluator::load_dmn_xml`]);
+//! a single `definitions` file may contain several decision tables.
+//!
+//! Decision tables honour their [`HitPolicy`], and decisions that require
+//! other decisions (a decision requirements graph) are evaluated in
+//! dependency order, with each required decision's outputs available as
+//! inputs downstream.
+
+pub mod feel;
+mod hit_policy;
+mod parser;
+
+pub use hit_policy::{Aggregation, HitPolicy};
 
 use crate::client::OpenRegelsClient;
 use crate::model::{Regel, RegelType};
@@ -9,7 +23,6 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use thiserror::Error;
-use uuid::Uuid;
 
 /// DMN decision context
 #[derive(Debug, Clone)]
@@ -88,13 +101,47 @@ pub struct DecisionResult {
     /// Whether the decision matched any rule
     pub matched: bool,
 
-    /// Matched rule ID
+    /// Matched rule ID (the selected rule for single-hit policies, the
+    /// first matching rule otherwise)
     pub matched_rule_id: Option<String>,
 
+    /// All rules that matched, in the order the hit policy returns them
+    #[serde(default)]
+    pub matched_rules: Vec<String>,
+
+    /// Evaluation trace per decision, required decisions first
+    #[serde(default)]
+    pub trace: Vec<DecisionTrace>,
+
     /// Evaluation metadata
     pub metadata: DecisionMetadata,
 }
 
+/// Evaluation trace of a single decision table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionTrace {
+    /// Decision ID
+    pub decision_id: String,
+
+    /// Decision name
+    pub decision_name: String,
+
+    /// Hit policy applied
+    pub hit_policy: HitPolicy,
+
+    /// Resolved input values, by input name
+    pub inputs: HashMap<String, DecisionValue>,
+
+    /// Rules that matched, in the order the hit policy returns them
+    pub matched_rules: Vec<String>,
+
+    /// Output values of this decision
+    pub outputs: HashMap<String, DecisionValue>,
+
+    /// Evaluation time in microseconds
+    pub evaluation_time_us: u64,
+}
+
 /// Decision metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DecisionMetadata {
@@ -116,6 +163,11 @@ pub struct DecisionMetadata {
 pub struct Decision {
     pub id: String,
     pub name: String,
+    pub hit_policy: HitPolicy,
+    /// Aggregation for the COLLECT hit policy
+    pub aggregation: Option<Aggregation>,
+    /// IDs of decisions whose results are inputs to this one
+    pub required_decisions: Vec<String>,
     pub inputs: Vec<InputClause>,
     pub outputs: Vec<OutputClause>,
     pub rules: Vec<DecisionRule>,
@@ -135,6 +187,9 @@ pub struct OutputClause {
     pub name: String,
     pub type_ref: String,
     pub default_value: Option<DecisionValue>,
+    /// Allowed output values, highest priority first (PRIORITY and
+    /// OUTPUT ORDER hit policies)
+    pub output_values: Vec<DecisionValue>,
 }
 
 #[derive(Debug, Clone)]
@@ -162,12 +217,19 @@ pub enum ConditionOperator {
     In,
     NotIn,
     Between,
+    /// FEEL unary tests that don't reduce to a single operator, such as
+    /// half-open ranges or tests on other variables; `value` then holds the
+    /// source text
+    Feel(feel::UnaryTests),
 }
 
 #[derive(Debug, Clone)]
 pub struct Conclusion {
     pub output_id: String,
     pub value: DecisionValue,
+    /// FEEL output expression; when set it is evaluated against the
+    /// decision inputs and `value` holds the source text
+    pub expression: Option<feel::Expression>,
 }
 
 /// DMN evaluator with Open Regels integration
@@ -191,11 +253,27 @@ impl DmnEvaluator {
         self
     }
 
-    /// Load a decision from DMN XML
-    pub fn load_dmn_xml(&mut self, xml: &str) -> Result<(), DmnError> {
-        let decision = Self::parse_dmn_xml(xml)?;
-        self.decisions.insert(decision.id.clone(), decision);
-        Ok(())
+    /// Load all decisions from a DMN `definitions` document
+    ///
+    /// Returns the IDs of the loaded decisions, in document order. Decisions
+    /// with an ID that is already loaded are replaced.
+    pub fn load_dmn_xml(&mut self, xml: &str) -> Result<Vec<String>, DmnError> {
+        let decisions = parser::parse_definitions(xml)?;
+        let ids = decisions.iter().map(|d| d.id.clone()).collect();
+        for decision in decisions {
+            self.decisions.insert(decision.id.clone(), decision);
+        }
+        Ok(ids)
+    }
+
+    /// Get a loaded decision by ID
+    pub fn get_decision(&self, decision_id: &str) -> Option<&Decision> {
+        self.decisions.get(decision_id)
+    }
+
+    /// IDs of all loaded decisions
+    pub fn decision_ids(&self) -> impl Iterator<Item = &str> {
+        self.decisions.keys().map(String::as_str)
     }
 
     /// Load a decision from Open Regels by URI
@@ -228,9 +306,18 @@ impl DmnEvaluator {
             .map(|v| v.value.clone())
             .ok_or(DmnError::ParseError("No DMN XML found".into()))?;
 
-        self.load_dmn_xml(&dmn_xml)?;
+        let ids = self.load_dmn_xml(&dmn_xml)?;
+
+        for id in &ids {
+            if let Some(decision) = self.decisions.get_mut(id) {
+                decision.metadata.open_regels_uri = Some(regel_uri.to_string());
+            }
+        }
 
-        self.decisions.get(regel_uri)
+        // The Open Regels resource is the definitions document; its first
+        // decision is the entry point.
+        ids.first()
+            .and_then(|id| self.decisions.get(id))
             .cloned()
             .ok_or(DmnError::DecisionNotFound(regel_uri.to_string()))
     }
@@ -286,6 +373,10 @@ impl DmnEvaluator {
     }
 
     /// Evaluate a decision
+    ///
+    /// Required decisions are evaluated first, in dependency order. Their
+    /// results are added to the inputs of dependent decisions under the
+    /// decision name (single output) and as `decisionName.outputName`.
     pub fn evaluate(
         &self,
         decision_id: &str,
@@ -293,17 +384,104 @@ impl DmnEvaluator {
     ) -> Result<DecisionResult, DmnError> {
         let start = std::time::Instant::now();
 
-        let decision = self.decisions
-            .get(decision_id)
+        let order = self.evaluation_order(decision_id)?;
+
+        let mut context = context.clone();
+        let mut trace = Vec::with_capacity(order.len());
+        let mut last = None;
+
+        for id in order {
+            let decision = &self.decisions[id];
+            let outcome = self.evaluate_table(decision, &context)?;
+
+            for (name, value) in &outcome.trace.outputs {
+                context.inputs.insert(format!("{}.{}", decision.name, name), value.clone());
+            }
+            let single_output = match decision.outputs.as_slice() {
+                [output] => outcome.trace.outputs.get(&output.name),
+                _ => None,
+            };
+            if let Some(value) = single_output {
+                context.inputs.insert(decision.name.clone(), value.clone());
+            }
+
+            trace.push(outcome.trace.clone());
+            last = Some((decision, outcome));
+        }
+
+        let (decision, outcome) = last
             .ok_or_else(|| DmnError::DecisionNotFound(decision_id.to_string()))?;
 
-        // Find matching rule
-        let matched_rule = decision.rules.iter()
-            .find(|rule| self.rule_matches(rule, &decision.inputs, context));
+        Ok(DecisionResult {
+            decision: decision_id.to_string(),
+            outputs: outcome.trace.outputs,
+            matched: !outcome.trace.matched_rules.is_empty(),
+            matched_rule_id: outcome.selected_rule.clone(),
+            matched_rules: outcome.trace.matched_rules,
+            trace,
+            metadata: DecisionMetadata {
+                matched_rule: outcome.selected_rule,
+                evaluation_time_us: start.elapsed().as_micros() as u64,
+                dmn_version: decision.metadata.dmn_version.clone(),
+                open_regels_uri: decision.metadata.open_regels_uri.clone(),
+            },
+        })
+    }
+
+    /// Topologically sort the requirements graph below `decision_id`
+    fn evaluation_order<'a>(&'a self, decision_id: &'a str) -> Result<Vec<&'a str>, DmnError> {
+        fn visit<'a>(
+            evaluator: &'a DmnEvaluator,
+            id: &'a str,
+            visiting: &mut Vec<&'a str>,
+            order: &mut Vec<&'a str>,
+        ) -> Result<(), DmnError> {
+            if order.contains(&id) {
+                return Ok(());
+            }
+            if visiting.contains(&id) {
+                visiting.push(id);
+                return Err(DmnError::EvaluationError(format!(
+                    "Cycle in decision requirements: {}",
+                    visiting.join(" -> ")
+                )));
+            }
+
+            let decision = evaluator.decisions
+                .get(id)
+                .ok_or_else(|| DmnError::DecisionNotFound(id.to_string()))?;
+
+            visiting.push(id);
+            for required in &decision.required_decisions {
+                visit(evaluator, required, visiting, order)?;
+            }
+            visiting.pop();
+            order.push(id);
+            Ok(())
+        }
 
-        let mut outputs = HashMap::new();
+        let mut order = Vec::new();
+        visit(self, decision_id, &mut Vec::new(), &mut order)?;
+        Ok(order)
+    }
+
+    /// Evaluate a single decision table, without its requirements
+    fn evaluate_table(
+        &self,
+        decision: &Decision,
+        context: &DecisionContext,
+    ) -> Result<hit_policy::TableOutcome, DmnError> {
+        let start = std::time::Instant::now();
 
-        let matched_rule_id = if let Some(rule) = matched_rule {
+        let input_values = self.resolve_inputs(&decision.inputs, context);
+
+        let mut hits = Vec::new();
+        for rule in &decision.rules {
+            if !self.rule_matches(rule, &input_values, context) {
+                continue;
+            }
+
+            let mut outputs = HashMap::new();
             for conclusion in &rule.conclusions {
                 let output = decision.outputs.iter()
                     .find(|o| o.id == conclusion.output_id)
@@ -311,112 +489,153 @@ impl DmnEvaluator {
                         "Output {} not found", conclusion.output_id
                     )))?;
 
-                outputs.insert(output.name.clone(), conclusion.value.clone());
+                let value = match &conclusion.expression {
+                    Some(expression) => expression.evaluate(&context.inputs)
+                        .map_err(|e| DmnError::EvaluationError(format!(
+                            "Rule {}, output {}: {}", rule.id, output.name, e
+                        )))?
+                        .into_decision_value(),
+                    None => Some(conclusion.value.clone()),
+                };
+
+                // A FEEL null leaves the output unset
+                if let Some(value) = value {
+                    outputs.insert(output.name.clone(), value);
+                }
             }
-            Some(rule.id.clone())
-        } else {
-            // Use default values
+            hits.push(hit_policy::RuleHit { rule_id: rule.id.clone(), outputs });
+
+            // FIRST can stop at the first match
+            if decision.hit_policy == HitPolicy::First {
+                break;
+            }
+        }
+
+        let (selected_rule, matched_rules, mut outputs) = hit_policy::apply(decision, hits)?;
+
+        if matched_rules.is_empty() {
             for output in &decision.outputs {
                 let value = output.default_value.clone()
                     .unwrap_or(DecisionValue::String("".to_string()));
                 outputs.insert(output.name.clone(), value);
             }
-            None
-        };
+        }
 
-        Ok(DecisionResult {
-            decision: decision_id.to_string(),
-            outputs,
-            matched: matched_rule.is_some(),
-            matched_rule_id,
-            metadata: DecisionMetadata {
-                matched_rule: matched_rule.map(|r| r.id.clone()),
+        let inputs = decision.inputs.iter()
+            .filter_map(|input| {
+                input_values.get(&input.id).map(|v| (input.name.clone(), v.clone()))
+            })
+            .collect();
+
+        Ok(hit_policy::TableOutcome {
+            selected_rule,
+            trace: DecisionTrace {
+                decision_id: decision.id.clone(),
+                decision_name: decision.name.clone(),
+                hit_policy: decision.hit_policy,
+                inputs,
+                matched_rules,
+                outputs,
                 evaluation_time_us: start.elapsed().as_micros() as u64,
-                dmn_version: "1.4".to_string(),
-                open_regels_uri: None,
             },
         })
     }
 
-    /// Check if a rule matches the context
+    /// Resolve the value of every input clause, keyed by input ID
+    ///
+    /// An input is looked up by name in the context first; if it is not
+    /// there, the input expression is evaluated as FEEL (e.g. `aanvraag.datum`
+    /// or `leeftijd + 1`). Inputs that cannot be resolved are left out.
+    fn resolve_inputs(
+        &self,
+        inputs: &[InputClause],
+        context: &DecisionContext,
+    ) -> HashMap<String, DecisionValue> {
+        inputs.iter()
+            .filter_map(|input| {
+                let value = match context.inputs.get(&input.name) {
+                    Some(value) => Some(value.clone()),
+                    None => feel::Expression::parse(&input.name)
+                        .and_then(|e| e.evaluate(&context.inputs))
+                        .ok()
+                        .and_then(feel::Value::into_decision_value),
+                };
+                value.map(|v| (input.id.clone(), v))
+            })
+            .collect()
+    }
+
+    /// Check if a rule matches the resolved input values
     fn rule_matches(
         &self,
         rule: &DecisionRule,
-        inputs: &[InputClause],
+        input_values: &HashMap<String, DecisionValue>,
         context: &DecisionContext,
     ) -> bool {
-        for condition in &rule.conditions {
-            let input = inputs.iter()
-                .find(|i| i.id == condition.input_id);
-
-            let Some(input) = input else {
-                return false;
-            };
-
-            let value = context.inputs.get(&input.name);
-
-            if !self.evaluate_condition(&condition.operator, value, &condition.value) {
-                return false;
+        rule.conditions.iter().all(|condition| {
+            let value = input_values.get(&condition.input_id);
+
+            match &condition.operator {
+                ConditionOperator::Feel(tests) => {
+                    let input = value.map(feel::Value::from).unwrap_or(feel::Value::Null);
+                    // Evaluation errors (e.g. type mismatches) mean "no match"
+                    tests.matches(&input, &context.inputs).unwrap_or(false)
+                }
+                operator => self.evaluate_condition(operator, value, &condition.value),
             }
-        }
-
-        true
+        })
     }
 
     /// Evaluate a single condition
+    ///
+    /// Numbers compare by value regardless of Integer/Double, dates compare
+    /// chronologically. `In`/`NotIn` expect an array of candidates and
+    /// `Between` an inclusive `[low, high]` array.
     fn evaluate_condition(
         &self,
         operator: &ConditionOperator,
         actual: Option<&DecisionValue>,
         expected: &DecisionValue,
     ) -> bool {
-        match (actual, expected) {
-            (Some(DecisionValue::String(a)), DecisionValue::String(b)) => match operator {
-                ConditionOperator::Equal => a == b,
-                ConditionOperator::NotEqual => a != b,
-                _ => false,
-            },
-            (Some(DecisionValue::Integer(a)), DecisionValue::Integer(b)) => match operator {
-                ConditionOperator::Equal => a == b,
-                ConditionOperator::NotEqual => a != b,
-                ConditionOperator::LessThan => a < b,
-                ConditionOperator::LessThanOrEqual => a <= b,
-                ConditionOperator::GreaterThan => a > b,
-                ConditionOperator::GreaterThanOrEqual => a >= b,
-                _ => false,
-            },
-            (Some(DecisionValue::Double(a)), DecisionValue::Double(b)) => match operator {
-                ConditionOperator::Equal => (a - b).abs() < f64::EPSILON,
-                ConditionOperator::LessThan => a < b,
-                ConditionOperator::GreaterThan => a > b,
+        use std::cmp::Ordering;
+
+        let Some(actual) = actual.map(feel::Value::from) else {
+            return false;
+        };
+        let expected = feel::Value::from(expected);
+        let ordering = || feel::compare(&actual, &expected);
+        let equal = |candidate: &feel::Value| feel::equals(&actual, candidate) == Some(true);
+
+        match operator {
+            ConditionOperator::Equal => equal(&expected),
+            ConditionOperator::NotEqual => feel::equals(&actual, &expected) == Some(false),
+            ConditionOperator::LessThan => ordering() == Some(Ordering::Less),
+            ConditionOperator::LessThanOrEqual => {
+                matches!(ordering(), Some(Ordering::Less | Ordering::Equal))
+            }
+            ConditionOperator::GreaterThan => ordering() == Some(Ordering::Greater),
+            ConditionOperator::GreaterThanOrEqual => {
+                matches!(ordering(), Some(Ordering::Greater | Ordering::Equal))
+            }
+            ConditionOperator::In | ConditionOperator::NotIn => {
+                let found = match &expected {
+                    feel::Value::List(candidates) => candidates.iter().any(equal),
+                    single => equal(single),
+                };
+                found == matches!(operator, ConditionOperator::In)
+            }
+            ConditionOperator::Between => match &expected {
+                feel::Value::List(bounds) if bounds.len() == 2 => {
+                    matches!(feel::compare(&actual, &bounds[0]), Some(Ordering::Greater | Ordering::Equal))
+                        && matches!(feel::compare(&actual, &bounds[1]), Some(Ordering::Less | Ordering::Equal))
+                }
                 _ => false,
             },
-            (Some(DecisionValue::Boolean(a)), DecisionValue::Boolean(b)) => a == b,
-            _ => false,
+            ConditionOperator::Feel(tests) => tests
+                .matches(&actual, &HashMap::new())
+                .unwrap_or(false),
         }
     }
-
-    /// Parse DMN XML (simplified implementation)
-    fn parse_dmn_xml(_xml: &str) -> Result<Decision, DmnError> {
-        // Parse DMN XML structure
-        // For a production implementation, use a proper DMN parser
-        // This is a simplified version that handles basic decision tables
-
-        // For now, create a minimal decision structure
-        Ok(Decision {
-            id: Uuid::new_v4().to_string(),
-            name: "Unnamed Decision".to_string(),
-            inputs: vec![],
-            outputs: vec![],
-            rules: vec![],
-            metadata: DecisionMetadata {
-                matched_rule: None,
-                evaluation_time_us: 0,
-                dmn_version: "1.4".to_string(),
-                open_regels_uri: None,
-            },
-        })
-    }
 }
 
 impl Default for DmnEvaluator {
@@ -444,6 +663,15 @@ pub enum DmnError {
     OpenRegelsNotAvailable,
 }
 
+impl From<feel::FeelError> for DmnError {
+    fn from(e: feel::FeelError) -> Self {
+        match e {
+            feel::FeelError::Syntax(msg) => DmnError::ParseError(msg),
+            feel::FeelError::Evaluation(msg) => DmnError::EvaluationError(msg),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -470,4 +698,486 @@ mod tests {
         assert!(evaluator.evaluate_condition(&ConditionOperator::GreaterThan, val.as_ref(), &expected));
         assert!(!evaluator.evaluate_condition(&ConditionOperator::LessThan, val.as_ref(), &expected));
     }
+
+    const LEEFTIJD_DMN: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<definitions xmlns="https://www.omg.org/spec/DMN/20191111/MODEL/" id="defs" name="Toeslagen" namespace="urn:iou">
+  <decision id="leeftijdscategorie" name="Leeftijdscategorie">
+    <decisionTable id="dt1">
+      <input id="in_leeftijd" label="Leeftijd">
+        <inputExpression typeRef="number"><text>leeftijd</text></inputExpression>
+      </input>
+      <input id="in_gemeente" label="Gemeente">
+        <inputExpression typeRef="string"><text>gemeente</text></inputExpression>
+      </input>
+      <output id="out_cat" name="categorie" typeRef="string">
+        <defaultOutputEntry><text>"onbekend"</text></defaultOutputEntry>
+      </output>
+      <output id="out_bedrag" name="bedrag" typeRef="number"/>
+      <rule id="r_jong">
+        <inputEntry><text>&lt; 18</text></inputEntry>
+        <inputEntry><text>-</text></inputEntry>
+        <outputEntry><text>"minderjarig"</text></outputEntry>
+        <outputEntry><text>0</text></outputEntry>
+      </rule>
+      <rule id="r_volwassen">
+        <inputEntry><text>&gt;= 18</text></inputEntry>
+        <inputEntry><text>"Utrecht"</text></inputEntry>
+        <outputEntry><text>"volwassen"</text></outputEntry>
+        <outputEntry><text>125.50</text></outputEntry>
+      </rule>
+    </decisionTable>
+  </decision>
+  <decision id="spoed" name="Spoed">
+    <decisionTable id="dt2">
+      <input id="in_prio"><inputExpression typeRef="boolean"><text>prioriteit</text></inputExpression></input>
+      <output id="out_spoed" name="spoed" typeRef="boolean"/>
+      <rule id="r_spoed">
+        <inputEntry><text>true</text></inputEntry>
+        <outputEntry><text>true</text></outputEntry>
+      </rule>
+    </decisionTable>
+  </decision>
+</definitions>"#;
+
+    fn context(inputs: Vec<(&str, DecisionValue)>) -> DecisionContext {
+        DecisionContext {
+            inputs: inputs.into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
+            tenant_id: None,
+            context: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_load_dmn_xml_parses_all_decisions() {
+        let mut evaluator = DmnEvaluator::new();
+        let ids = evaluator.load_dmn_xml(LEEFTIJD_DMN).unwrap();
+        assert_eq!(ids, vec!["leeftijdscategorie", "spoed"]);
+
+        let decision = evaluator.get_decision("leeftijdscategorie").unwrap();
+        assert_eq!(decision.name, "Leeftijdscategorie");
+        assert_eq!(decision.metadata.dmn_version, "1.3");
+        assert_eq!(decision.inputs.len(), 2);
+        assert_eq!(decision.inputs[0].name, "leeftijd");
+        assert_eq!(decision.inputs[0].type_ref, "number");
+        assert_eq!(decision.outputs.len(), 2);
+        assert_eq!(decision.rules.len(), 2);
+        // "-" is an "any" test and produces no condition
+        assert_eq!(decision.rules[0].conditions.len(), 1);
+        assert_eq!(decision.rules[1].conditions.len(), 2);
+    }
+
+    #[test]
+    fn test_evaluate_parsed_decision() {
+        let mut evaluator = DmnEvaluator::new();
+        evaluator.load_dmn_xml(LEEFTIJD_DMN).unwrap();
+
+        let result = evaluator
+            .evaluate(
+                "leeftijdscategorie",
+                &context(vec![
+                    ("leeftijd", DecisionValue::Integer(34)),
+                    ("gemeente", DecisionValue::String("Utrecht".into())),
+                ]),
+            )
+            .unwrap();
+        assert!(result.matched);
+        assert_eq!(result.matched_rule_id.as_deref(), Some("r_volwassen"));
+        assert_eq!(result.outputs["categorie"].as_str(), Some("volwassen"));
+        assert!(matches!(result.outputs["bedrag"], DecisionValue::Double(b) if b == 125.5));
+
+        let result = evaluator
+            .evaluate("spoed", &context(vec![("prioriteit", DecisionValue::Boolean(true))]))
+            .unwrap();
+        assert!(matches!(result.outputs["spoed"], DecisionValue::Boolean(true)));
+    }
+
+    #[test]
+    fn test_evaluate_parsed_decision_uses_default_output() {
+        let mut evaluator = DmnEvaluator::new();
+        evaluator.load_dmn_xml(LEEFTIJD_DMN).unwrap();
+
+        let result = evaluator
+            .evaluate(
+                "leeftijdscategorie",
+                &context(vec![
+                    ("leeftijd", DecisionValue::Integer(40)),
+                    ("gemeente", DecisionValue::String("Zwolle".into())),
+                ]),
+            )
+            .unwrap();
+        assert!(!result.matched);
+        assert_eq!(result.outputs["categorie"].as_str(), Some("onbekend"));
+    }
+
+    #[test]
+    fn test_parse_unary_tests() {
+        use parser::parse_unary_test;
+
+        assert!(parse_unary_test("-").unwrap().is_none());
+        assert!(matches!(
+            parse_unary_test("<= 10").unwrap(),
+            Some((ConditionOperator::LessThanOrEqual, DecisionValue::Integer(10)))
+        ));
+        assert!(matches!(
+            parse_unary_test(r#""a","b""#).unwrap(),
+            Some((ConditionOperator::In, DecisionValue::Array(ref v))) if v.len() == 2
+        ));
+        assert!(matches!(
+            parse_unary_test(r#"not("a")"#).unwrap(),
+            Some((ConditionOperator::NotEqual, DecisionValue::String(ref s))) if s == "a"
+        ));
+        assert!(matches!(
+            parse_unary_test("[1..10]").unwrap(),
+            Some((ConditionOperator::Between, DecisionValue::Array(ref v))) if v.len() == 2
+        ));
+        assert!(matches!(
+            parse_unary_test(r#"date("2024-01-01")"#).unwrap(),
+            Some((ConditionOperator::Equal, DecisionValue::Date(_)))
+        ));
+    }
+
+    #[test]
+    fn test_load_dmn_xml_rejects_invalid_documents() {
+        let mut evaluator = DmnEvaluator::new();
+        assert!(matches!(
+            evaluator.load_dmn_xml("<process/>"),
+            Err(DmnError::ParseError(_))
+        ));
+        assert!(matches!(
+            evaluator.load_dmn_xml("<definitions><decision id=\"x\"/></definitions>"),
+            Err(DmnError::ParseError(_))
+        ));
+
+        // Entry count must match the number of inputs
+        let mismatched = LEEFTIJD_DMN.replacen("<inputEntry><text>-</text></inputEntry>", "", 1);
+        assert!(matches!(
+            evaluator.load_dmn_xml(&mismatched),
+            Err(DmnError::ParseError(msg)) if msg.contains("r_jong")
+        ));
+    }
+
+    const BEWAARTERMIJN_DMN: &str = r#"<definitions xmlns="https://www.omg.org/spec/DMN/20211108/MODEL/" id="provisa" namespace="urn:iou">
+  <decision id="bewaartermijn" name="Bewaartermijn">
+    <decisionTable id="dt">
+      <input id="in_cat"><inputExpression typeRef="string"><text>categorie</text></inputExpression></input>
+      <input id="in_datum"><inputExpression typeRef="date"><text>datum</text></inputExpression></input>
+      <input id="in_jaren"><inputExpression typeRef="number"><text>jaren</text></inputExpression></input>
+      <output id="out_vernietig" name="vernietigingsdatum" typeRef="date"/>
+      <output id="out_label" name="label" typeRef="string"/>
+      <rule id="r_oud">
+        <inputEntry><text>"1.1", "1.2"</text></inputEntry>
+        <inputEntry><text>[date("2000-01-01")..date("2020-01-01"))</text></inputEntry>
+        <inputEntry><text>-</text></inputEntry>
+        <outputEntry><text>datum + duration("P10Y")</text></outputEntry>
+        <outputEntry><text>upper case(categorie) + "-oud"</text></outputEntry>
+      </rule>
+      <rule id="r_nieuw">
+        <inputEntry><text>not("9.9")</text></inputEntry>
+        <inputEntry><text>&gt;= date("2020-01-01")</text></inputEntry>
+        <inputEntry><text>[1..30)</text></inputEntry>
+        <outputEntry><text>datum + duration("P1Y") * jaren</text></outputEntry>
+        <outputEntry><text>"nieuw"</text></outputEntry>
+      </rule>
+    </decisionTable>
+  </decision>
+</definitions>"#;
+
+    fn date(s: &str) -> DecisionValue {
+        DecisionValue::Date(chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap())
+    }
+
+    #[test]
+    fn test_evaluate_feel_entries() {
+        let mut evaluator = DmnEvaluator::new();
+        evaluator.load_dmn_xml(BEWAARTERMIJN_DMN).unwrap();
+
+        let result = evaluator
+            .evaluate(
+                "bewaartermijn",
+                &context(vec![
+                    ("categorie", DecisionValue::String("1.2".into())),
+                    ("datum", date("2015-06-30")),
+                    ("jaren", DecisionValue::Integer(7)),
+                ]),
+            )
+            .unwrap();
+        assert_eq!(result.matched_rule_id.as_deref(), Some("r_oud"));
+        assert!(matches!(result.outputs["vernietigingsdatum"], DecisionValue::Date(d) if d.to_string() == "2025-06-30"));
+        assert_eq!(result.outputs["label"].as_str(), Some("1.2-oud"));
+
+        // Half-open range: 2020-01-01 falls in the second rule, and a Double
+        // input is compared against the integer range bounds
+        let result = evaluator
+            .evaluate(
+                "bewaartermijn",
+                &context(vec![
+                    ("categorie", DecisionValue::String("1.2".into())),
+                    ("datum", date("2020-01-01")),
+                    ("jaren", DecisionValue::Double(5.0)),
+                ]),
+            )
+            .unwrap();
+        assert_eq!(result.matched_rule_id.as_deref(), Some("r_nieuw"));
+        assert!(matches!(result.outputs["vernietigingsdatum"], DecisionValue::Date(d) if d.to_string() == "2025-01-01"));
+
+        // 30 is outside [1..30)
+        let result = evaluator
+            .evaluate(
+                "bewaartermijn",
+                &context(vec![
+                    ("categorie", DecisionValue::String("1.2".into())),
+                    ("datum", date("2021-01-01")),
+                    ("jaren", DecisionValue::Integer(30)),
+                ]),
+            )
+            .unwrap();
+        assert!(!result.matched);
+    }
+
+    #[test]
+    fn test_condition_evaluation_mixed_types() {
+        let evaluator = DmnEvaluator::new();
+
+        let int = Some(DecisionValue::Integer(10));
+        assert!(evaluator.evaluate_condition(&ConditionOperator::Equal, int.as_ref(), &DecisionValue::Double(10.0)));
+        assert!(evaluator.evaluate_condition(&ConditionOperator::LessThan, int.as_ref(), &DecisionValue::Double(10.5)));
+
+        let list = DecisionValue::Array(vec!["a".to_string().into(), "b".to_string().into()]);
+        let b = Some(DecisionValue::String("b".into()));
+        assert!(evaluator.evaluate_condition(&ConditionOperator::In, b.as_ref(), &list));
+        assert!(!evaluator.evaluate_condition(&ConditionOperator::NotIn, b.as_ref(), &list));
+
+        let bounds = DecisionValue::Array(vec![date("2020-01-01"), date("2020-12-31")]);
+        let d = Some(date("2020-12-31"));
+        assert!(evaluator.evaluate_condition(&ConditionOperator::Between, d.as_ref(), &bounds));
+        assert!(!evaluator.evaluate_condition(&ConditionOperator::Between, None, &bounds));
+    }
+
+    const HIT_POLICY_DMN: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<definitions xmlns="https://www.omg.org/spec/DMN/20191111/MODEL/" id="toeslagen" name="Toeslagen" namespace="urn:iou">
+  <decision id="prioriteit" name="Prioriteit">
+    <decisionTable hitPolicy="PRIORITY">
+      <input id="p_in"><inputExpression typeRef="number"><text>score</text></inputExpression></input>
+      <output id="p_out" name="prioriteit" typeRef="string">
+        <outputValues><text>"hoog","middel","laag"</text></outputValues>
+      </output>
+      <rule id="p_laag"><inputEntry><text>&gt; 0</text></inputEntry><outputEntry><text>"laag"</text></outputEntry></rule>
+      <rule id="p_hoog"><inputEntry><text>&gt; 90</text></inputEntry><outputEntry><text>"hoog"</text></outputEntry></rule>
+      <rule id="p_middel"><inputEntry><text>&gt; 50</text></inputEntry><outputEntry><text>"middel"</text></outputEntry></rule>
+    </decisionTable>
+  </decision>
+  <decision id="advies" name="Advies">
+    <informationRequirement id="ir1"><requiredDecision href="#prioriteit"/></informationRequirement>
+    <decisionTable hitPolicy="FIRST">
+      <input id="a_in"><inputExpression typeRef="string"><text>Prioriteit</text></inputExpression></input>
+      <output id="a_out" name="advies" typeRef="string"/>
+      <rule id="a_direct"><inputEntry><text>"hoog"</text></inputEntry><outputEntry><text>"direct behandelen"</text></outputEntry></rule>
+      <rule id="a_regulier"><inputEntry><text>-</text></inputEntry><outputEntry><text>"regulier"</text></outputEntry></rule>
+      <rule id="a_nooit"><inputEntry><text>-</text></inputEntry><outputEntry><text>"nooit"</text></outputEntry></rule>
+    </decisionTable>
+  </decision>
+  <decision id="toeslag" name="Toeslag">
+    <decisionTable hitPolicy="COLLECT" aggregation="SUM">
+      <input id="t_in"><inputExpression typeRef="number"><text>bedrag</text></inputExpression></input>
+      <output id="t_out" name="toeslag" typeRef="number"/>
+      <rule id="t1"><inputEntry><text>&gt;= 0</text></inputEntry><outputEntry><text>10</text></outputEntry></rule>
+      <rule id="t2"><inputEntry><text>&gt;= 100</text></inputEntry><outputEntry><text>25</text></outputEntry></rule>
+      <rule id="t3"><inputEntry><text>&gt;= 1000</text></inputEntry><outputEntry><text>10</text></outputEntry></rule>
+    </decisionTable>
+  </decision>
+  <decision id="kanalen" name="Kanalen">
+    <decisionTable hitPolicy="RULE ORDER">
+      <input id="k_in"><inputExpression typeRef="boolean"><text>spoed</text></inputExpression></input>
+      <output id="k_out" name="kanaal" typeRef="string"/>
+      <rule id="k_sms"><inputEntry><text>true</text></inputEntry><outputEntry><text>"sms"</text></outputEntry></rule>
+      <rule id="k_post"><inputEntry><text>-</text></inputEntry><outputEntry><text>"post"</text></outputEntry></rule>
+    </decisionTable>
+  </decision>
+  <decision id="doelgroep" name="Doelgroep">
+    <decisionTable>
+      <input id="d_in"><inputExpression typeRef="number"><text>leeftijd</text></inputExpression></input>
+      <output id="d_out" name="doelgroep" typeRef="string"/>
+      <rule id="d_volwassen"><inputEntry><text>&gt;= 18</text></inputEntry><outputEntry><text>"volwassen"</text></outputEntry></rule>
+      <rule id="d_senior"><inputEntry><text>&gt;= 65</text></inputEntry><outputEntry><text>"senior"</text></outputEntry></rule>
+    </decisionTable>
+  </decision>
+  <decision id="regio" name="Regio">
+    <decisionTable hitPolicy="ANY">
+      <input id="r_in"><inputExpression typeRef="string"><text>gemeente</text></inputExpression></input>
+      <output id="r_out" name="regio" typeRef="string"/>
+      <rule id="r_utrecht"><inputEntry><text>"Utrecht","Zeist"</text></inputEntry><outputEntry><text>"midden"</text></outputEntry></rule>
+      <rule id="r_zeist"><inputEntry><text>"Zeist"</text></inputEntry><outputEntry><text>"midden"</text></outputEntry></rule>
+      <rule id="r_fout"><inputEntry><text>"Zeist","Zwolle"</text></inputEntry><outputEntry><text>"oost"</text></outputEntry></rule>
+    </decisionTable>
+  </decision>
+</definitions>"##;
+
+    #[test]
+    fn test_parse_hit_policies() {
+        let mut evaluator = DmnEvaluator::new();
+        evaluator.load_dmn_xml(HIT_POLICY_DMN).unwrap();
+
+        let policy = |id: &str| evaluator.get_decision(id).unwrap().hit_policy;
+        assert_eq!(policy("prioriteit"), HitPolicy::Priority);
+        assert_eq!(policy("kanalen"), HitPolicy::RuleOrder);
+        assert_eq!(policy("doelgroep"), HitPolicy::Unique);
+
+        let toeslag = evaluator.get_decision("toeslag").unwrap();
+        assert_eq!(toeslag.aggregation, Some(Aggregation::Sum));
+
+        let prioriteit = evaluator.get_decision("prioriteit").unwrap();
+        assert_eq!(prioriteit.outputs[0].output_values.len(), 3);
+        assert_eq!(evaluator.get_decision("advies").unwrap().required_decisions, vec!["prioriteit"]);
+
+        assert_eq!(HitPolicy::parse("O"), Some(HitPolicy::OutputOrder));
+        assert_eq!(Aggregation::parse("#"), Some(Aggregation::Count));
+        assert!(HitPolicy::parse("SOMETIMES").is_none());
+
+        let invalid = HIT_POLICY_DMN.replace(r#"hitPolicy="RULE ORDER""#, r#"hitPolicy="RULE ORDER" aggregation="SUM""#);
+        assert!(matches!(DmnEvaluator::new().load_dmn_xml(&invalid), Err(DmnError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_single_hit_policies() {
+        let mut evaluator = DmnEvaluator::new();
+        evaluator.load_dmn_xml(HIT_POLICY_DMN).unwrap();
+
+        // PRIORITY picks the output that comes first in outputValues
+        let result = evaluator
+            .evaluate("prioriteit", &context(vec![("score", DecisionValue::Integer(95))]))
+            .unwrap();
+        assert_eq!(result.outputs["prioriteit"].as_str(), Some("hoog"));
+        assert_eq!(result.matched_rule_id.as_deref(), Some("p_hoog"));
+        assert_eq!(result.matched_rules, vec!["p_hoog", "p_middel", "p_laag"]);
+
+        // UNIQUE fails when more than one rule matches
+        let result = evaluator
+            .evaluate("doelgroep", &context(vec![("leeftijd", DecisionValue::Integer(30))]))
+            .unwrap();
+        assert_eq!(result.outputs["doelgroep"].as_str(), Some("volwassen"));
+        assert!(matches!(
+            evaluator.evaluate("doelgroep", &context(vec![("leeftijd", DecisionValue::Integer(70))])),
+            Err(DmnError::EvaluationError(_))
+        ));
+
+        // ANY accepts overlapping rules only when they agree
+        let result = evaluator
+            .evaluate("regio", &context(vec![("gemeente", DecisionValue::String("Utrecht".into()))]))
+            .unwrap();
+        assert_eq!(result.outputs["regio"].as_str(), Some("midden"));
+        assert!(evaluator
+            .evaluate("regio", &context(vec![("gemeente", DecisionValue::String("Zeist".into()))]))
+            .is_err());
+    }
+
+    #[test]
+    fn test_multi_hit_policies() {
+        let mut evaluator = DmnEvaluator::new();
+        evaluator.load_dmn_xml(HIT_POLICY_DMN).unwrap();
+
+        let result = evaluator
+            .evaluate("toeslag", &context(vec![("bedrag", DecisionValue::Integer(250))]))
+            .unwrap();
+        assert!(matches!(result.outputs["toeslag"], DecisionValue::Integer(35)));
+        assert_eq!(result.matched_rules, vec!["t1", "t2"]);
+
+        let result = evaluator
+            .evaluate("kanalen", &context(vec![("spoed", DecisionValue::Boolean(true))]))
+            .unwrap();
+        match &result.outputs["kanaal"] {
+            DecisionValue::Array(values) => {
+                let kanalen: Vec<_> = values.iter().filter_map(|v| v.as_str()).collect();
+                assert_eq!(kanalen, vec!["sms", "post"]);
+            }
+            other => panic!("expected a list, got {:?}", other),
+        }
+
+        let values = [10, 25, 10].map(DecisionValue::Integer);
+        let mut decision = evaluator.get_decision("toeslag").unwrap().clone();
+        for (aggregation, expected) in [(Aggregation::Count, 2), (Aggregation::Min, 10), (Aggregation::Max, 25)] {
+            decision.aggregation = Some(aggregation);
+            let hits = values.iter().enumerate().map(|(i, v)| hit_policy::RuleHit {
+                rule_id: format!("t{}", i + 1),
+                outputs: HashMap::from([("toeslag".to_string(), v.clone())]),
+            });
+            let (_, _, outputs) = hit_policy::apply(&decision, hits.collect()).unwrap();
+            assert_eq!(outputs["toeslag"].as_int(), Some(expected), "{:?}", aggregation);
+        }
+    }
+
+    #[test]
+    fn test_evaluate_decision_requirements_graph() {
+        let mut evaluator = DmnEvaluator::new();
+        evaluator.load_dmn_xml(HIT_POLICY_DMN).unwrap();
+
+        let result = evaluator
+            .evaluate("advies", &context(vec![("score", DecisionValue::Integer(99))]))
+            .unwrap();
+        assert_eq!(result.outputs["advies"].as_str(), Some("direct behandelen"));
+        // FIRST stops after the first matching rule
+        assert_eq!(result.matched_rules, vec!["a_direct"]);
+
+        assert_eq!(result.trace.len(), 2);
+        assert_eq!(result.trace[0].decision_id, "prioriteit");
+        assert_eq!(result.trace[0].outputs["prioriteit"].as_str(), Some("hoog"));
+        assert_eq!(result.trace[1].decision_id, "advies");
+        assert_eq!(result.trace[1].inputs["Prioriteit"].as_str(), Some("hoog"));
+
+        let result = evaluator
+            .evaluate("advies", &context(vec![("score", DecisionValue::Integer(60))]))
+            .unwrap();
+        assert_eq!(result.outputs["advies"].as_str(), Some("regulier"));
+    }
+
+    #[test]
+    fn test_evaluate_rejects_requirement_cycles() {
+        let cyclic = HIT_POLICY_DMN.replace(
+            r#"<decisionTable hitPolicy="PRIORITY">"#,
+            r##"<informationRequirement id="ir2"><requiredDecision href="#advies"/></informationRequirement>
+    <decisionTable hitPolicy="PRIORITY">"##,
+        );
+        let mut evaluator = DmnEvaluator::new();
+        evaluator.load_dmn_xml(&cyclic).unwrap();
+
+        match evaluator.evaluate("advies", &context(vec![])) {
+            Err(DmnError::EvaluationError(msg)) => assert!(msg.contains("advies -> prioriteit -> advies"), "{}", msg),
+            other => panic!("expected a cycle error, got {:?}", other),
+        }
+        assert!(matches!(
+            evaluator.evaluate("onbekend", &context(vec![])),
+            Err(DmnError::DecisionNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_load_from_local_open_regels_store() {
+        let mut store = crate::rdf::RdfStore::new();
+        store.load_json_ld_value(&serde_json::json!({
+            "@context": { "dmn": "http://www.omg.org/spec/DMN/20191111/MODEL/" },
+            "@id": "https://regels.overheid.nl/toeslagen",
+            "@type": "dmn:Definition",
+            "dmn:name": "Toeslagen",
+            "dmn:definition": {
+                "dmn:expression": LEEFTIJD_DMN
+            }
+        })).unwrap();
+        let client = Arc::new(OpenRegelsClient::lokaal(store));
+        let mut evaluator = DmnEvaluator::new().with_open_regels(client);
+
+        let gevonden = evaluator.discover_dmn_decisions(Some("toeslag")).await.unwrap();
+        assert_eq!(gevonden.len(), 1);
+        assert_eq!(gevonden[0].uri, "https://regels.overheid.nl/toeslagen");
+        assert!(evaluator.discover_dmn_decisions(Some("bouw")).await.unwrap().is_empty());
+
+        let decision = evaluator.load_from_open_regels("https://regels.overheid.nl/toeslagen").await.unwrap();
+        assert_eq!(decision.id, "leeftijdscategorie");
+        assert_eq!(
+            decision.metadata.open_regels_uri.as_deref(),
+            Some("https://regels.overheid.nl/toeslagen")
+        );
+        assert!(matches!(
+            evaluator.load_from_open_regels("https://regels.overheid.nl/onbekend").await,
+            Err(DmnError::DecisionNotFound(_))
+        ));
+    }
 }
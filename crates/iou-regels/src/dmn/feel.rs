@@ -0,0 +1,1764 @@
+//! FEEL (Friendly Enough Expression Language) subset
+//!
+//! Covers what the provincial decision tables actually use:
+//!
+//! - literals: numbers, strings, booleans, `null`, lists, ranges
+//!   (`[1..10)`, `]a..b[`), `date(..)`, `date and time(..)`, `duration(..)`
+//! - arithmetic (`+ - * / **`), including date ± duration and date − date
+//! - comparisons, `and`/`or`, `not(..)`, `between .. and ..`, `in`,
+//!   `if .. then .. else ..`
+//! - property access on dates and durations (`datum.year`, `termijn.days`)
+//! - string, list, number and date/duration built-in functions
+//! - unary tests as used in decision table input entries, including `-`,
+//!   comparison endpoints, intervals, lists and `not(..)`
+//!
+//! Expressions are compiled once ([`Expression::parse`], [`UnaryTests::parse`])
+//! and evaluated against the decision inputs.
+
+use super::DecisionValue;
+use chrono::{Datelike, Months, NaiveDate, NaiveDateTime, Timelike};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// FEEL errors
+#[derive(Debug, Clone, Error, PartialEq)]
+pub enum FeelError {
+    #[error("FEEL syntax error: {0}")]
+    Syntax(String),
+
+    #[error("FEEL evaluation error: {0}")]
+    Evaluation(String),
+}
+
+type FeelResult<T> = Result<T, FeelError>;
+
+fn eval_err<T>(msg: impl Into<String>) -> FeelResult<T> {
+    Err(FeelError::Evaluation(msg.into()))
+}
+
+// ── Values ───────────────────────────────────────────────────────────────────
+
+/// Runtime value of a FEEL expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Boolean(bool),
+    Integer(i64),
+    Double(f64),
+    String(String),
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+    /// Years and months duration, in months
+    YearsMonths(i64),
+    /// Days and time duration
+    DaysTime(chrono::Duration),
+    List(Vec<Value>),
+    Range(Box<Range>),
+}
+
+/// Interval with inclusive or exclusive endpoints
+#[derive(Debug, Clone, PartialEq)]
+pub struct Range {
+    pub start: Value,
+    pub start_inclusive: bool,
+    pub end: Value,
+    pub end_inclusive: bool,
+}
+
+impl Range {
+    fn contains(&self, value: &Value) -> bool {
+        let after_start = match compare(value, &self.start) {
+            Some(Ordering::Greater) => true,
+            Some(Ordering::Equal) => self.start_inclusive,
+            _ => false,
+        };
+        let before_end = match compare(value, &self.end) {
+            Some(Ordering::Less) => true,
+            Some(Ordering::Equal) => self.end_inclusive,
+            _ => false,
+        };
+        after_start && before_end
+    }
+}
+
+impl From<&DecisionValue> for Value {
+    fn from(value: &DecisionValue) -> Self {
+        match value {
+            DecisionValue::String(s) => Value::String(s.clone()),
+            DecisionValue::Integer(i) => Value::Integer(*i),
+            DecisionValue::Double(f) => Value::Double(*f),
+            DecisionValue::Boolean(b) => Value::Boolean(*b),
+            DecisionValue::Date(d) => Value::Date(*d),
+            DecisionValue::Array(items) => Value::List(items.iter().map(Value::from).collect()),
+        }
+    }
+}
+
+impl Value {
+    /// Convert to a decision value; `null` has no decision value representation
+    pub fn into_decision_value(self) -> Option<DecisionValue> {
+        Some(match self {
+            Value::Null => return None,
+            Value::Boolean(b) => DecisionValue::Boolean(b),
+            Value::Integer(i) => DecisionValue::Integer(i),
+            Value::Double(f) => DecisionValue::Double(f),
+            Value::String(s) => DecisionValue::String(s),
+            Value::Date(d) => DecisionValue::Date(d),
+            Value::DateTime(dt) => DecisionValue::String(dt.format("%Y-%m-%dT%H:%M:%S").to_string()),
+            v @ (Value::YearsMonths(_) | Value::DaysTime(_)) => DecisionValue::String(v.to_string()),
+            Value::List(items) => DecisionValue::Array(
+                items.into_iter().filter_map(Value::into_decision_value).collect(),
+            ),
+            v @ Value::Range(_) => DecisionValue::String(v.to_string()),
+        })
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Integer(i) => Some(*i as f64),
+            Value::Double(f) => Some(*f),
+            _ => None,
+        }
+    }
+
+    /// FEEL type name, for error messages
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::Boolean(_) => "boolean",
+            Value::Integer(_) | Value::Double(_) => "number",
+            Value::String(_) => "string",
+            Value::Date(_) => "date",
+            Value::DateTime(_) => "date and time",
+            Value::YearsMonths(_) => "years and months duration",
+            Value::DaysTime(_) => "days and time duration",
+            Value::List(_) => "list",
+            Value::Range(_) => "range",
+        }
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Boolean(b) => write!(f, "{}", b),
+            Value::Integer(i) => write!(f, "{}", i),
+            Value::Double(d) => write!(f, "{}", d),
+            Value::String(s) => write!(f, "{}", s),
+            Value::Date(d) => write!(f, "{}", d.format("%Y-%m-%d")),
+            Value::DateTime(dt) => write!(f, "{}", dt.format("%Y-%m-%dT%H:%M:%S")),
+            Value::YearsMonths(months) => {
+                let sign = if *months < 0 { "-" } else { "" };
+                let (years, months) = (months.abs() / 12, months.abs() % 12);
+                match (years, months) {
+                    (0, m) => write!(f, "{}P{}M", sign, m),
+                    (y, 0) => write!(f, "{}P{}Y", sign, y),
+                    (y, m) => write!(f, "{}P{}Y{}M", sign, y, m),
+                }
+            }
+            Value::DaysTime(d) => {
+                let total = d.num_seconds();
+                if total == 0 {
+                    return write!(f, "PT0S");
+                }
+                let sign = if total < 0 { "-" } else { "" };
+                let total = total.abs();
+                let (days, hours, minutes, seconds) =
+                    (total / 86_400, total % 86_400 / 3600, total % 3600 / 60, total % 60);
+                write!(f, "{}P", sign)?;
+                if days > 0 {
+                    write!(f, "{}D", days)?;
+                }
+                if hours + minutes + seconds > 0 {
+                    write!(f, "T")?;
+                    if hours > 0 {
+                        write!(f, "{}H", hours)?;
+                    }
+                    if minutes > 0 {
+                        write!(f, "{}M", minutes)?;
+                    }
+                    if seconds > 0 {
+                        write!(f, "{}S", seconds)?;
+                    }
+                }
+                Ok(())
+            }
+            Value::List(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Value::Range(r) => write!(
+                f,
+                "{}{}..{}{}",
+                if r.start_inclusive { "[" } else { "(" },
+                r.start,
+                r.end,
+                if r.end_inclusive { "]" } else { ")" }
+            ),
+        }
+    }
+}
+
+/// FEEL equality; `None` when the values are not comparable
+pub fn equals(a: &Value, b: &Value) -> Option<bool> {
+    match (a, b) {
+        (Value::Null, Value::Null) => Some(true),
+        (Value::Null, _) | (_, Value::Null) => Some(false),
+        (Value::Boolean(x), Value::Boolean(y)) => Some(x == y),
+        (Value::List(x), Value::List(y)) => {
+            if x.len() != y.len() {
+                return Some(false);
+            }
+            for (a, b) in x.iter().zip(y) {
+                if !equals(a, b)? {
+                    return Some(false);
+                }
+            }
+            Some(true)
+        }
+        _ => compare(a, b).map(|o| o == Ordering::Equal),
+    }
+}
+
+/// FEEL ordering; `None` when the values are not comparable
+pub fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Integer(x), Value::Integer(y)) => Some(x.cmp(y)),
+        (Value::Integer(_) | Value::Double(_), Value::Integer(_) | Value::Double(_)) => {
+            a.as_f64()?.partial_cmp(&b.as_f64()?)
+        }
+        (Value::String(x), Value::String(y)) => Some(x.cmp(y)),
+        (Value::Date(x), Value::Date(y)) => Some(x.cmp(y)),
+        (Value::DateTime(x), Value::DateTime(y)) => Some(x.cmp(y)),
+        (Value::Date(x), Value::DateTime(y)) => Some(x.and_hms_opt(0, 0, 0)?.cmp(y)),
+        (Value::DateTime(x), Value::Date(y)) => Some(x.cmp(&y.and_hms_opt(0, 0, 0)?)),
+        (Value::YearsMonths(x), Value::YearsMonths(y)) => Some(x.cmp(y)),
+        (Value::DaysTime(x), Value::DaysTime(y)) => Some(x.cmp(y)),
+        (Value::Boolean(x), Value::Boolean(y)) if x == y => Some(Ordering::Equal),
+        _ => None,
+    }
+}
+
+// ── Lexer ────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(String),
+    Str(String),
+    Name(String),
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+    Dot,
+    DotDot,
+    Plus,
+    Minus,
+    Star,
+    StarStar,
+    Slash,
+    Eq,
+    NotEq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Question,
+}
+
+/// Built-in functions whose names contain spaces; the lexer treats these as
+/// a single name when they are followed by `(`.
+const MULTI_WORD_FUNCTIONS: &[&str] = &[
+    "years and months duration",
+    "date and time",
+    "string length",
+    "upper case",
+    "lower case",
+    "starts with",
+    "ends with",
+    "substring before",
+    "substring after",
+    "list contains",
+];
+
+fn tokenize(source: &str) -> FeelResult<Vec<Token>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '"' => {
+                let mut s = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(FeelError::Syntax("Unterminated string".into())),
+                        Some('"') => break,
+                        Some('\\') => {
+                            match chars.get(i + 1) {
+                                Some('n') => s.push('\n'),
+                                Some('t') => s.push('\t'),
+                                Some(other) => s.push(*other),
+                                None => return Err(FeelError::Syntax("Unterminated string".into())),
+                            }
+                            i += 2;
+                            continue;
+                        }
+                        Some(ch) => s.push(*ch),
+                    }
+                    i += 1;
+                }
+                i += 1;
+                tokens.push(Token::Str(s));
+            }
+            c if c.is_ascii_digit() => {
+                let start = i;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+                // A single dot followed by a digit is a decimal point; `..` is a range
+                if chars.get(i) == Some(&'.') && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()) {
+                    i += 1;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+                tokens.push(Token::Number(chars[start..i].iter().collect()));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let rest: String = chars[i..].iter().collect();
+                if let Some(name) = MULTI_WORD_FUNCTIONS.iter().find(|name| {
+                    rest.starts_with(*name) && rest[name.len()..].trim_start().starts_with('(')
+                }) {
+                    i += name.chars().count();
+                    tokens.push(Token::Name(name.to_string()));
+                    continue;
+                }
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(Token::Name(chars[start..i].iter().collect()));
+            }
+            _ => {
+                let next = chars.get(i + 1).copied();
+                let (token, len) = match (c, next) {
+                    ('.', Some('.')) => (Token::DotDot, 2),
+                    ('*', Some('*')) => (Token::StarStar, 2),
+                    ('!', Some('=')) => (Token::NotEq, 2),
+                    ('<', Some('=')) => (Token::Le, 2),
+                    ('>', Some('=')) => (Token::Ge, 2),
+                    ('(', _) => (Token::LParen, 1),
+                    (')', _) => (Token::RParen, 1),
+                    ('[', _) => (Token::LBracket, 1),
+                    (']', _) => (Token::RBracket, 1),
+                    (',', _) => (Token::Comma, 1),
+                    ('.', _) => (Token::Dot, 1),
+                    ('+', _) => (Token::Plus, 1),
+                    ('-', _) => (Token::Minus, 1),
+                    ('*', _) => (Token::Star, 1),
+                    ('/', _) => (Token::Slash, 1),
+                    ('=', _) => (Token::Eq, 1),
+                    ('<', _) => (Token::Lt, 1),
+                    ('>', _) => (Token::Gt, 1),
+                    ('?', _) => (Token::Question, 1),
+                    _ => return Err(FeelError::Syntax(format!("Unexpected character '{}'", c))),
+                };
+                tokens.push(token);
+                i += len;
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+// ── AST and parser ───────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+    Eq,
+    NotEq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Literal(Value),
+    /// Variable reference; `?` in unary tests is the input value
+    Name(String),
+    Input,
+    Negate(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Between(Box<Expr>, Box<Expr>, Box<Expr>),
+    In(Box<Expr>, Box<Expr>),
+    If(Box<Expr>, Box<Expr>, Box<Expr>),
+    Path(Box<Expr>, String),
+    Index(Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+    List(Vec<Expr>),
+    Range {
+        start: Box<Expr>,
+        start_inclusive: bool,
+        end: Box<Expr>,
+        end_inclusive: bool,
+    },
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Set while parsing a range end, where `[` closes an open interval
+    /// (`[1..10[`) instead of indexing a list
+    in_range_end: bool,
+}
+
+impl Parser {
+    fn new(source: &str) -> FeelResult<Self> {
+        Ok(Self {
+            tokens: tokenize(source)?,
+            pos: 0,
+            in_range_end: false,
+        })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Name(n)) if n == keyword)
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if self.is_keyword(keyword) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &Token) -> FeelResult<()> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(FeelError::Syntax(format!(
+                "Expected {:?}, found {:?}",
+                token,
+                self.peek()
+            )))
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> FeelResult<()> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(FeelError::Syntax(format!(
+                "Expected '{}', found {:?}",
+                keyword,
+                self.peek()
+            )))
+        }
+    }
+
+    fn expression(&mut self) -> FeelResult<Expr> {
+        if self.eat_keyword("if") {
+            let condition = self.expression()?;
+            self.expect_keyword("then")?;
+            let then = self.expression()?;
+            self.expect_keyword("else")?;
+            let otherwise = self.expression()?;
+            return Ok(Expr::If(Box::new(condition), Box::new(then), Box::new(otherwise)));
+        }
+        self.disjunction()
+    }
+
+    fn disjunction(&mut self) -> FeelResult<Expr> {
+        let mut left = self.conjunction()?;
+        while self.eat_keyword("or") {
+            let right = self.conjunction()?;
+            left = Expr::Binary(BinaryOp::Or, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn conjunction(&mut self) -> FeelResult<Expr> {
+        let mut left = self.comparison()?;
+        while self.eat_keyword("and") {
+            let right = self.comparison()?;
+            left = Expr::Binary(BinaryOp::And, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn comparison(&mut self) -> FeelResult<Expr> {
+        let left = self.additive()?;
+
+        let op = match self.peek() {
+            Some(Token::Eq) => Some(BinaryOp::Eq),
+            Some(Token::NotEq) => Some(BinaryOp::NotEq),
+            Some(Token::Lt) => Some(BinaryOp::Lt),
+            Some(Token::Le) => Some(BinaryOp::Le),
+            Some(Token::Gt) => Some(BinaryOp::Gt),
+            Some(Token::Ge) => Some(BinaryOp::Ge),
+            _ => None,
+        };
+        if let Some(op) = op {
+            self.advance();
+            let right = self.additive()?;
+            return Ok(Expr::Binary(op, Box::new(left), Box::new(right)));
+        }
+
+        if self.eat_keyword("between") {
+            let low = self.additive()?;
+            self.expect_keyword("and")?;
+            let high = self.additive()?;
+            return Ok(Expr::Between(Box::new(left), Box::new(low), Box::new(high)));
+        }
+
+        if self.eat_keyword("in") {
+            // `x in (a, b)` is a list of candidates; `x in [1..5]` a single range or list
+            let target = if self.peek() == Some(&Token::LParen) && !self.paren_is_range() {
+                self.advance();
+                let items = self.comma_list(&Token::RParen)?;
+                if items.len() == 1 {
+                    items.into_iter().next().unwrap_or(Expr::List(vec![]))
+                } else {
+                    Expr::List(items)
+                }
+            } else {
+                self.additive()?
+            };
+            return Ok(Expr::In(Box::new(left), Box::new(target)));
+        }
+
+        Ok(left)
+    }
+
+    /// Does the `(` at the current position open an interval like `(1..5]`?
+    fn paren_is_range(&self) -> bool {
+        let mut depth = 0usize;
+        for token in &self.tokens[self.pos..] {
+            match token {
+                Token::LParen | Token::LBracket => depth += 1,
+                Token::RParen | Token::RBracket => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        return false;
+                    }
+                }
+                Token::DotDot if depth == 1 => return true,
+                Token::Comma if depth == 1 => return false,
+                _ => {}
+            }
+        }
+        false
+    }
+
+    fn additive(&mut self) -> FeelResult<Expr> {
+        let mut left = self.multiplicative()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Plus) => BinaryOp::Add,
+                Some(Token::Minus) => BinaryOp::Sub,
+                _ => return Ok(left),
+            };
+            self.advance();
+            let right = self.multiplicative()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn multiplicative(&mut self) -> FeelResult<Expr> {
+        let mut left = self.power()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Star) => BinaryOp::Mul,
+                Some(Token::Slash) => BinaryOp::Div,
+                _ => return Ok(left),
+            };
+            self.advance();
+            let right = self.power()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn power(&mut self) -> FeelResult<Expr> {
+        let mut left = self.unary()?;
+        while self.eat(&Token::StarStar) {
+            let right = self.unary()?;
+            left = Expr::Binary(BinaryOp::Pow, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> FeelResult<Expr> {
+        if self.eat(&Token::Minus) {
+            return Ok(Expr::Negate(Box::new(self.unary()?)));
+        }
+        self.postfix()
+    }
+
+    fn postfix(&mut self) -> FeelResult<Expr> {
+        let mut expr = self.primary()?;
+        loop {
+            if self.eat(&Token::Dot) {
+                match self.advance() {
+                    Some(Token::Name(member)) => expr = Expr::Path(Box::new(expr), member),
+                    other => {
+                        return Err(FeelError::Syntax(format!(
+                            "Expected property name, found {:?}",
+                            other
+                        )))
+                    }
+                }
+            } else if self.peek() == Some(&Token::LBracket) && !self.in_range_end {
+                self.advance();
+                let index = self.expression()?;
+                self.expect(&Token::RBracket)?;
+                expr = Expr::Index(Box::new(expr), Box::new(index));
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn primary(&mut self) -> FeelResult<Expr> {
+        match self.advance() {
+            Some(Token::Number(n)) => Ok(Expr::Literal(parse_number(&n)?)),
+            Some(Token::Str(s)) => Ok(Expr::Literal(Value::String(s))),
+            Some(Token::Question) => Ok(Expr::Input),
+            Some(Token::Name(name)) => match name.as_str() {
+                "true" => Ok(Expr::Literal(Value::Boolean(true))),
+                "false" => Ok(Expr::Literal(Value::Boolean(false))),
+                "null" => Ok(Expr::Literal(Value::Null)),
+                _ if self.peek() == Some(&Token::LParen) => {
+                    self.advance();
+                    let args = self.comma_list(&Token::RParen)?;
+                    Ok(Expr::Call(name, args))
+                }
+                _ => Ok(Expr::Name(name)),
+            },
+            Some(Token::LParen) => {
+                let first = self.expression()?;
+                if self.eat(&Token::DotDot) {
+                    return self.range_rest(first, false);
+                }
+                self.expect(&Token::RParen)?;
+                Ok(first)
+            }
+            Some(Token::RBracket) => {
+                // `]a..b]` is an interval with an open start
+                let first = self.expression()?;
+                self.expect(&Token::DotDot)?;
+                self.range_rest(first, false)
+            }
+            Some(Token::LBracket) => {
+                if self.eat(&Token::RBracket) {
+                    return Ok(Expr::List(vec![]));
+                }
+                let first = self.expression()?;
+                if self.eat(&Token::DotDot) {
+                    return self.range_rest(first, true);
+                }
+                let mut items = vec![first];
+                while self.eat(&Token::Comma) {
+                    items.push(self.expression()?);
+                }
+                self.expect(&Token::RBracket)?;
+                Ok(Expr::List(items))
+            }
+            other => Err(FeelError::Syntax(format!("Unexpected token {:?}", other))),
+        }
+    }
+
+    fn range_rest(&mut self, start: Expr, start_inclusive: bool) -> FeelResult<Expr> {
+        let outer = std::mem::replace(&mut self.in_range_end, true);
+        let end = self.expression();
+        self.in_range_end = outer;
+        let end = end?;
+        let end_inclusive = match self.advance() {
+            Some(Token::RBracket) => true,
+            Some(Token::RParen) | Some(Token::LBracket) => false,
+            other => {
+                return Err(FeelError::Syntax(format!(
+                    "Expected end of range, found {:?}",
+                    other
+                )))
+            }
+        };
+        Ok(Expr::Range {
+            start: Box::new(start),
+            start_inclusive,
+            end: Box::new(end),
+            end_inclusive,
+        })
+    }
+
+    fn comma_list(&mut self, close: &Token) -> FeelResult<Vec<Expr>> {
+        let mut items = Vec::new();
+        if self.eat(close) {
+            return Ok(items);
+        }
+        loop {
+            items.push(self.expression()?);
+            if self.eat(close) {
+                return Ok(items);
+            }
+            self.expect(&Token::Comma)?;
+        }
+    }
+
+    fn finish(&self) -> FeelResult<()> {
+        if self.at_end() {
+            Ok(())
+        } else {
+            Err(FeelError::Syntax(format!("Unexpected trailing {:?}", self.peek())))
+        }
+    }
+}
+
+fn parse_number(text: &str) -> FeelResult<Value> {
+    if let Ok(i) = text.parse::<i64>() {
+        return Ok(Value::Integer(i));
+    }
+    text.parse::<f64>()
+        .map(Value::Double)
+        .map_err(|_| FeelError::Syntax(format!("Invalid number {}", text)))
+}
+
+// ── Public compiled forms ────────────────────────────────────────────────────
+
+/// Compiled FEEL expression
+#[derive(Debug, Clone)]
+pub struct Expression {
+    source: String,
+    expr: Expr,
+}
+
+impl Expression {
+    /// Compile an expression
+    pub fn parse(source: &str) -> Result<Self, FeelError> {
+        let mut parser = Parser::new(source)?;
+        if parser.at_end() {
+            return Err(FeelError::Syntax("Empty expression".into()));
+        }
+        let expr = parser.expression()?;
+        parser.finish()?;
+        Ok(Self {
+            source: source.trim().to_string(),
+            expr,
+        })
+    }
+
+    /// Source text of the expression
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Evaluate against a set of variables
+    pub fn evaluate(&self, variables: &HashMap<String, DecisionValue>) -> Result<Value, FeelError> {
+        eval(&self.expr, &Scope { variables, input: None })
+    }
+}
+
+/// A single positive unary test
+#[derive(Debug, Clone)]
+enum Test {
+    Compare(BinaryOp, Expr),
+    Expr(Expr),
+}
+
+/// Compiled FEEL unary tests, as used in decision table input entries
+#[derive(Debug, Clone)]
+pub struct UnaryTests {
+    source: String,
+    negated: bool,
+    /// Empty means "any" (`-`)
+    tests: Vec<Test>,
+}
+
+impl UnaryTests {
+    /// Compile unary tests such as `-`, `< 18`, `"a","b"`, `[1..10)` or `not(..)`
+    pub fn parse(source: &str) -> Result<Self, FeelError> {
+        let trimmed = source.trim();
+        if trimmed.is_empty() || trimmed == "-" {
+            return Ok(Self {
+                source: trimmed.to_string(),
+                negated: false,
+                tests: vec![],
+            });
+        }
+
+        let mut parser = Parser::new(trimmed)?;
+
+        // `not(...)` around the whole entry negates the test list
+        let negated = parser.is_keyword("not")
+            && parser.peek_at(1) == Some(&Token::LParen)
+            && closing_paren(&parser.tokens, 1) == Some(parser.tokens.len() - 1);
+        if negated {
+            parser.pos = 2;
+            parser.tokens.pop();
+        }
+
+        let mut tests = Vec::new();
+        loop {
+            let op = match parser.peek() {
+                Some(Token::Lt) => Some(BinaryOp::Lt),
+                Some(Token::Le) => Some(BinaryOp::Le),
+                Some(Token::Gt) => Some(BinaryOp::Gt),
+                Some(Token::Ge) => Some(BinaryOp::Ge),
+                Some(Token::Eq) => Some(BinaryOp::Eq),
+                Some(Token::NotEq) => Some(BinaryOp::NotEq),
+                _ => None,
+            };
+            let test = match op {
+                Some(op) => {
+                    parser.advance();
+                    Test::Compare(op, parser.additive()?)
+                }
+                None => Test::Expr(parser.expression()?),
+            };
+            tests.push(test);
+            if !parser.eat(&Token::Comma) {
+                break;
+            }
+        }
+        parser.finish()?;
+
+        Ok(Self {
+            source: trimmed.to_string(),
+            negated,
+            tests,
+        })
+    }
+
+    /// Source text of the unary tests
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether this is the "any" test (`-`)
+    pub fn is_any(&self) -> bool {
+        self.tests.is_empty()
+    }
+
+    /// Test an input value; other variables may be referenced by name
+    pub fn matches(
+        &self,
+        input: &Value,
+        variables: &HashMap<String, DecisionValue>,
+    ) -> Result<bool, FeelError> {
+        if self.tests.is_empty() {
+            return Ok(true);
+        }
+
+        let scope = Scope {
+            variables,
+            input: Some(input),
+        };
+
+        let mut any = false;
+        for test in &self.tests {
+            if test_matches(test, input, &scope)? {
+                any = true;
+                break;
+            }
+        }
+
+        Ok(any != self.negated)
+    }
+}
+
+impl Serialize for UnaryTests {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for UnaryTests {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        UnaryTests::parse(&source).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Index of the token closing the parenthesis opened at `open`
+fn closing_paren(tokens: &[Token], open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        match token {
+            Token::LParen => depth += 1,
+            Token::RParen => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn test_matches(test: &Test, input: &Value, scope: &Scope<'_>) -> FeelResult<bool> {
+    match test {
+        Test::Compare(op, endpoint) => {
+            let endpoint = eval(endpoint, scope)?;
+            Ok(matches!(compare_op(*op, input, &endpoint)?, Value::Boolean(true)))
+        }
+        Test::Expr(expr) => {
+            // Tests referring to `?` are boolean expressions over the input
+            if references_input(expr) {
+                return Ok(matches!(eval(expr, scope)?, Value::Boolean(true)));
+            }
+            let expected = eval(expr, scope)?;
+            Ok(value_in(input, &expected))
+        }
+    }
+}
+
+fn references_input(expr: &Expr) -> bool {
+    match expr {
+        Expr::Input => true,
+        Expr::Literal(_) | Expr::Name(_) => false,
+        Expr::Negate(e) | Expr::Path(e, _) => references_input(e),
+        Expr::Binary(_, a, b) | Expr::In(a, b) | Expr::Index(a, b) => {
+            references_input(a) || references_input(b)
+        }
+        Expr::Between(a, b, c) | Expr::If(a, b, c) => {
+            references_input(a) || references_input(b) || references_input(c)
+        }
+        Expr::Call(_, args) | Expr::List(args) => args.iter().any(references_input),
+        Expr::Range { start, end, .. } => references_input(start) || references_input(end),
+    }
+}
+
+/// Membership as used by `in` and unary tests: lists and ranges contain,
+/// anything else must be equal.
+fn value_in(value: &Value, target: &Value) -> bool {
+    match target {
+        Value::List(items) => items.iter().any(|item| value_in(value, item)),
+        Value::Range(range) => range.contains(value),
+        other => equals(value, other) == Some(true),
+    }
+}
+
+// ── Evaluation ───────────────────────────────────────────────────────────────
+
+struct Scope<'a> {
+    variables: &'a HashMap<String, DecisionValue>,
+    input: Option<&'a Value>,
+}
+
+impl Scope<'_> {
+    fn lookup(&self, name: &str) -> Option<Value> {
+        self.variables.get(name).map(Value::from)
+    }
+}
+
+/// Flatten `a.b.c` into `"a.b.c"` so dotted context keys can be looked up
+fn dotted_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Name(name) => Some(name.clone()),
+        Expr::Path(base, member) => dotted_name(base).map(|b| format!("{}.{}", b, member)),
+        _ => None,
+    }
+}
+
+fn eval(expr: &Expr, scope: &Scope<'_>) -> FeelResult<Value> {
+    match expr {
+        Expr::Literal(v) => Ok(v.clone()),
+        Expr::Input => scope
+            .input
+            .cloned()
+            .ok_or_else(|| FeelError::Evaluation("'?' used outside a unary test".into())),
+        Expr::Name(name) => scope
+            .lookup(name)
+            .ok_or_else(|| FeelError::Evaluation(format!("Unknown variable '{}'", name))),
+        Expr::Negate(inner) => match eval(inner, scope)? {
+            Value::Integer(i) => Ok(Value::Integer(-i)),
+            Value::Double(f) => Ok(Value::Double(-f)),
+            Value::YearsMonths(m) => Ok(Value::YearsMonths(-m)),
+            Value::DaysTime(d) => Ok(Value::DaysTime(-d)),
+            Value::Null => Ok(Value::Null),
+            other => eval_err(format!("Cannot negate {}", other.type_name())),
+        },
+        Expr::Binary(BinaryOp::And, a, b) => {
+            // Three-valued logic: false wins over null
+            let a = eval(a, scope)?;
+            if a == Value::Boolean(false) {
+                return Ok(a);
+            }
+            let b = eval(b, scope)?;
+            Ok(match (a, b) {
+                (_, Value::Boolean(false)) => Value::Boolean(false),
+                (Value::Boolean(true), Value::Boolean(true)) => Value::Boolean(true),
+                _ => Value::Null,
+            })
+        }
+        Expr::Binary(BinaryOp::Or, a, b) => {
+            let a = eval(a, scope)?;
+            if a == Value::Boolean(true) {
+                return Ok(a);
+            }
+            let b = eval(b, scope)?;
+            Ok(match (a, b) {
+                (_, Value::Boolean(true)) => Value::Boolean(true),
+                (Value::Boolean(false), Value::Boolean(false)) => Value::Boolean(false),
+                _ => Value::Null,
+            })
+        }
+        Expr::Binary(op, a, b) => {
+            let a = eval(a, scope)?;
+            let b = eval(b, scope)?;
+            match op {
+                BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Pow => {
+                    arithmetic(*op, a, b)
+                }
+                _ => compare_op(*op, &a, &b),
+            }
+        }
+        Expr::Between(value, low, high) => {
+            let value = eval(value, scope)?;
+            let low = eval(low, scope)?;
+            let high = eval(high, scope)?;
+            match (compare(&value, &low), compare(&value, &high)) {
+                (Some(l), Some(h)) => Ok(Value::Boolean(l != Ordering::Less && h != Ordering::Greater)),
+                _ => Ok(Value::Null),
+            }
+        }
+        Expr::In(value, target) => {
+            let value = eval(value, scope)?;
+            let target = eval(target, scope)?;
+            Ok(Value::Boolean(value_in(&value, &target)))
+        }
+        Expr::If(condition, then, otherwise) => match eval(condition, scope)? {
+            Value::Boolean(true) => eval(then, scope),
+            _ => eval(otherwise, scope),
+        },
+        Expr::Path(base, member) => {
+            if let Some(value) = dotted_name(expr).and_then(|name| scope.lookup(&name)) {
+                return Ok(value);
+            }
+            let base = eval(base, scope)?;
+            property(&base, member)
+        }
+        Expr::Index(list, index) => {
+            let list = eval(list, scope)?;
+            let index = eval(index, scope)?;
+            match (list, index) {
+                (Value::List(items), Value::Integer(i)) => {
+                    // FEEL lists are 1-based; negative indexes count from the end
+                    let len = items.len() as i64;
+                    let pos = if i > 0 { i - 1 } else { len + i };
+                    Ok(usize::try_from(pos)
+                        .ok()
+                        .and_then(|p| items.get(p).cloned())
+                        .unwrap_or(Value::Null))
+                }
+                (list, index) => eval_err(format!(
+                    "Cannot index {} with {}",
+                    list.type_name(),
+                    index.type_name()
+                )),
+            }
+        }
+        Expr::Call(name, args) => {
+            let args = args
+                .iter()
+                .map(|a| eval(a, scope))
+                .collect::<FeelResult<Vec<_>>>()?;
+            call(name, args)
+        }
+        Expr::List(items) => Ok(Value::List(
+            items.iter().map(|i| eval(i, scope)).collect::<FeelResult<_>>()?,
+        )),
+        Expr::Range {
+            start,
+            start_inclusive,
+            end,
+            end_inclusive,
+        } => Ok(Value::Range(Box::new(Range {
+            start: eval(start, scope)?,
+            start_inclusive: *start_inclusive,
+            end: eval(end, scope)?,
+            end_inclusive: *end_inclusive,
+        }))),
+    }
+}
+
+fn compare_op(op: BinaryOp, a: &Value, b: &Value) -> FeelResult<Value> {
+    Ok(match op {
+        BinaryOp::Eq => equals(a, b).map(Value::Boolean).unwrap_or(Value::Null),
+        BinaryOp::NotEq => equals(a, b).map(|e| Value::Boolean(!e)).unwrap_or(Value::Null),
+        _ => match compare(a, b) {
+            Some(ordering) => Value::Boolean(match op {
+                BinaryOp::Lt => ordering == Ordering::Less,
+                BinaryOp::Le => ordering != Ordering::Greater,
+                BinaryOp::Gt => ordering == Ordering::Greater,
+                BinaryOp::Ge => ordering != Ordering::Less,
+                _ => return eval_err(format!("{:?} is not a comparison", op)),
+            }),
+            None => Value::Null,
+        },
+    })
+}
+
+fn add_months(date: NaiveDate, months: i64) -> FeelResult<NaiveDate> {
+    let result = if months >= 0 {
+        date.checked_add_months(Months::new(months as u32))
+    } else {
+        date.checked_sub_months(Months::new(months.unsigned_abs() as u32))
+    };
+    result.ok_or_else(|| FeelError::Evaluation("Date out of range".into()))
+}
+
+fn arithmetic(op: BinaryOp, a: Value, b: Value) -> FeelResult<Value> {
+    use Value::*;
+
+    if matches!(a, Null) || matches!(b, Null) {
+        return Ok(Null);
+    }
+
+    let overflow = || FeelError::Evaluation("Numeric overflow".into());
+
+    Ok(match (op, a, b) {
+        (BinaryOp::Add, Integer(x), Integer(y)) => Integer(x.checked_add(y).ok_or_else(overflow)?),
+        (BinaryOp::Sub, Integer(x), Integer(y)) => Integer(x.checked_sub(y).ok_or_else(overflow)?),
+        (BinaryOp::Mul, Integer(x), Integer(y)) => Integer(x.checked_mul(y).ok_or_else(overflow)?),
+        (BinaryOp::Div, _, Integer(0)) => return eval_err("Division by zero"),
+        // i64::MIN / -1 overflows: fall through to the decimal path
+        (BinaryOp::Div, Integer(x), Integer(y)) if x.checked_rem(y) == Some(0) => {
+            Integer(x.checked_div(y).ok_or_else(overflow)?)
+        }
+        (BinaryOp::Pow, Integer(x), Integer(y)) if (0..=u32::MAX as i64).contains(&y) => {
+            Integer(x.checked_pow(y as u32).ok_or_else(overflow)?)
+        }
+        (op, a @ (Integer(_) | Double(_)), b @ (Integer(_) | Double(_))) => {
+            let (x, y) = (a.as_f64().unwrap_or_default(), b.as_f64().unwrap_or_default());
+            Double(match op {
+                BinaryOp::Add => x + y,
+                BinaryOp::Sub => x - y,
+                BinaryOp::Mul => x * y,
+                BinaryOp::Div if y == 0.0 => return eval_err("Division by zero"),
+                BinaryOp::Div => x / y,
+                _ => x.powf(y),
+            })
+        }
+        (BinaryOp::Add, String(x), String(y)) => String(x + &y),
+
+        // Dates and durations
+        (BinaryOp::Add, Date(d), YearsMonths(m)) | (BinaryOp::Add, YearsMonths(m), Date(d)) => {
+            Date(add_months(d, m)?)
+        }
+        (BinaryOp::Sub, Date(d), YearsMonths(m)) => Date(add_months(d, -m)?),
+        (BinaryOp::Add, Date(d), DaysTime(dur)) | (BinaryOp::Add, DaysTime(dur), Date(d)) => {
+            date_plus(d, dur)?
+        }
+        (BinaryOp::Sub, Date(d), DaysTime(dur)) => date_plus(d, -dur)?,
+        (BinaryOp::Add, DateTime(dt), YearsMonths(m)) | (BinaryOp::Add, YearsMonths(m), DateTime(dt)) => {
+            DateTime(add_months(dt.date(), m)?.and_time(dt.time()))
+        }
+        (BinaryOp::Sub, DateTime(dt), YearsMonths(m)) => {
+            DateTime(add_months(dt.date(), -m)?.and_time(dt.time()))
+        }
+        (BinaryOp::Add, DateTime(dt), DaysTime(dur)) | (BinaryOp::Add, DaysTime(dur), DateTime(dt)) => {
+            DateTime(dt.checked_add_signed(dur).ok_or_else(overflow)?)
+        }
+        (BinaryOp::Sub, DateTime(dt), DaysTime(dur)) => {
+            DateTime(dt.checked_sub_signed(dur).ok_or_else(overflow)?)
+        }
+        (BinaryOp::Sub, Date(x), Date(y)) => DaysTime(x.signed_duration_since(y)),
+        (BinaryOp::Sub, DateTime(x), DateTime(y)) => DaysTime(x.signed_duration_since(y)),
+        (BinaryOp::Add, YearsMonths(x), YearsMonths(y)) => YearsMonths(x + y),
+        (BinaryOp::Sub, YearsMonths(x), YearsMonths(y)) => YearsMonths(x - y),
+        (BinaryOp::Add, DaysTime(x), DaysTime(y)) => DaysTime(x + y),
+        (BinaryOp::Sub, DaysTime(x), DaysTime(y)) => DaysTime(x - y),
+        (BinaryOp::Mul, YearsMonths(m), Integer(n)) | (BinaryOp::Mul, Integer(n), YearsMonths(m)) => {
+            YearsMonths(m.checked_mul(n).ok_or_else(overflow)?)
+        }
+        (BinaryOp::Mul, DaysTime(d), Integer(n)) | (BinaryOp::Mul, Integer(n), DaysTime(d)) => {
+            DaysTime(d * i32::try_from(n).map_err(|_| overflow())?)
+        }
+        (BinaryOp::Mul, YearsMonths(m), Double(n)) | (BinaryOp::Mul, Double(n), YearsMonths(m)) => {
+            YearsMonths((m as f64 * n).round() as i64)
+        }
+        (BinaryOp::Mul, DaysTime(d), Double(n)) | (BinaryOp::Mul, Double(n), DaysTime(d)) => {
+            DaysTime(chrono::Duration::seconds((d.num_seconds() as f64 * n).round() as i64))
+        }
+        (op, a, b) => {
+            return eval_err(format!(
+                "Cannot apply {:?} to {} and {}",
+                op,
+                a.type_name(),
+                b.type_name()
+            ))
+        }
+    })
+}
+
+/// `date + days-time duration` stays a date for whole days
+fn date_plus(date: NaiveDate, duration: chrono::Duration) -> FeelResult<Value> {
+    let overflow = || FeelError::Evaluation("Date out of range".into());
+    if duration.num_seconds() % 86_400 == 0 {
+        Ok(Value::Date(
+            date.checked_add_signed(chrono::Duration::days(duration.num_days()))
+                .ok_or_else(overflow)?,
+        ))
+    } else {
+        Ok(Value::DateTime(
+            date.and_hms_opt(0, 0, 0)
+                .and_then(|dt| dt.checked_add_signed(duration))
+                .ok_or_else(overflow)?,
+        ))
+    }
+}
+
+fn property(value: &Value, member: &str) -> FeelResult<Value> {
+    let int = |i: i64| Ok(Value::Integer(i));
+    match (value, member) {
+        (Value::Null, _) => Ok(Value::Null),
+        (Value::Date(d), "year") => int(d.year() as i64),
+        (Value::Date(d), "month") => int(d.month() as i64),
+        (Value::Date(d), "day") => int(d.day() as i64),
+        (Value::Date(d), "weekday") => int(d.weekday().number_from_monday() as i64),
+        (Value::DateTime(dt), "year") => int(dt.year() as i64),
+        (Value::DateTime(dt), "month") => int(dt.month() as i64),
+        (Value::DateTime(dt), "day") => int(dt.day() as i64),
+        (Value::DateTime(dt), "weekday") => int(dt.weekday().number_from_monday() as i64),
+        (Value::DateTime(dt), "hour") => int(dt.hour() as i64),
+        (Value::DateTime(dt), "minute") => int(dt.minute() as i64),
+        (Value::DateTime(dt), "second") => int(dt.second() as i64),
+        (Value::YearsMonths(m), "years") => int(m / 12),
+        (Value::YearsMonths(m), "months") => int(m % 12),
+        (Value::DaysTime(d), "days") => int(d.num_days()),
+        (Value::DaysTime(d), "hours") => int(d.num_hours() % 24),
+        (Value::DaysTime(d), "minutes") => int(d.num_minutes() % 60),
+        (Value::DaysTime(d), "seconds") => int(d.num_seconds() % 60),
+        (value, member) => eval_err(format!(
+            "{} has no property '{}'",
+            value.type_name(),
+            member
+        )),
+    }
+}
+
+// ── Built-in functions ───────────────────────────────────────────────────────
+
+fn parse_date(s: &str) -> FeelResult<NaiveDate> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|_| FeelError::Evaluation(format!("Invalid date \"{}\"", s)))
+}
+
+fn parse_date_time(s: &str) -> FeelResult<NaiveDateTime> {
+    // Offsets and zone ids are accepted but not applied; all comparisons
+    // are done on local date and time.
+    let local = s
+        .split(['Z', '@', '+'])
+        .next()
+        .unwrap_or(s);
+    let local = match local.rfind('-') {
+        Some(pos) if pos > 10 => &local[..pos],
+        _ => local,
+    };
+    NaiveDateTime::parse_from_str(local, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(local, "%Y-%m-%dT%H:%M"))
+        .or_else(|_| parse_date(local).map(|d| d.and_hms_opt(0, 0, 0).unwrap_or_default()))
+        .map_err(|_| FeelError::Evaluation(format!("Invalid date and time \"{}\"", s)))
+}
+
+/// Parse an ISO 8601 duration (`P20Y`, `P1Y6M`, `P14D`, `PT36H`, `-P1D`)
+pub fn parse_duration(s: &str) -> Result<Value, FeelError> {
+    let invalid = || FeelError::Evaluation(format!("Invalid duration \"{}\"", s));
+
+    let (negative, rest) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    let rest = rest.strip_prefix('P').ok_or_else(invalid)?;
+    let (date_part, time_part) = match rest.split_once('T') {
+        Some((d, t)) => (d, Some(t)),
+        None => (rest, None),
+    };
+
+    let mut months = 0i64;
+    let mut seconds = 0i64;
+    let mut has_ym = false;
+    let mut has_dt = time_part.is_some();
+
+    let mut number = String::new();
+    for c in date_part.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let n: i64 = number.parse().map_err(|_| invalid())?;
+        number.clear();
+        match c {
+            'Y' => {
+                months += n * 12;
+                has_ym = true;
+            }
+            'M' => {
+                months += n;
+                has_ym = true;
+            }
+            'W' => {
+                seconds += n * 7 * 86_400;
+                has_dt = true;
+            }
+            'D' => {
+                seconds += n * 86_400;
+                has_dt = true;
+            }
+            _ => return Err(invalid()),
+        }
+    }
+    for c in time_part.unwrap_or("").chars() {
+        if c.is_ascii_digit() || c == '.' {
+            number.push(c);
+            continue;
+        }
+        let n: f64 = number.parse().map_err(|_| invalid())?;
+        number.clear();
+        seconds += match c {
+            'H' => n * 3600.0,
+            'M' => n * 60.0,
+            'S' => n,
+            _ => return Err(invalid()),
+        } as i64;
+    }
+
+    if !number.is_empty() || (has_ym && has_dt) || (!has_ym && !has_dt) {
+        return Err(invalid());
+    }
+
+    let sign = if negative { -1 } else { 1 };
+    Ok(if has_ym {
+        Value::YearsMonths(sign * months)
+    } else {
+        Value::DaysTime(chrono::Duration::seconds(sign * seconds))
+    })
+}
+
+fn arity(name: &str, args: &[Value], allowed: std::ops::RangeInclusive<usize>) -> FeelResult<()> {
+    if allowed.contains(&args.len()) {
+        Ok(())
+    } else {
+        eval_err(format!(
+            "{}() expects {} argument(s), got {}",
+            name,
+            if allowed.start() == allowed.end() {
+                allowed.start().to_string()
+            } else {
+                format!("{}-{}", allowed.start(), allowed.end())
+            },
+            args.len()
+        ))
+    }
+}
+
+fn string_arg<'a>(name: &str, value: &'a Value) -> FeelResult<&'a str> {
+    match value {
+        Value::String(s) => Ok(s),
+        other => eval_err(format!("{}() expects a string, got {}", name, other.type_name())),
+    }
+}
+
+fn number_arg(name: &str, value: &Value) -> FeelResult<f64> {
+    value.as_f64().ok_or_else(|| {
+        FeelError::Evaluation(format!("{}() expects a number, got {}", name, value.type_name()))
+    })
+}
+
+/// Flatten `f(list)` and `f(a, b, c)` calling conventions
+fn list_args(args: Vec<Value>) -> Vec<Value> {
+    match <[Value; 1]>::try_from(args) {
+        Ok([Value::List(items)]) => items,
+        Ok([single]) => vec![single],
+        Err(args) => args,
+    }
+}
+
+fn number_value(f: f64) -> Value {
+    if f.fract() == 0.0 && f.abs() < i64::MAX as f64 {
+        Value::Integer(f as i64)
+    } else {
+        Value::Double(f)
+    }
+}
+
+fn call(name: &str, args: Vec<Value>) -> FeelResult<Value> {
+    match name {
+        "not" => {
+            arity(name, &args, 1..=1)?;
+            Ok(match args[0] {
+                Value::Boolean(b) => Value::Boolean(!b),
+                _ => Value::Null,
+            })
+        }
+        "date" => match args.as_slice() {
+            [Value::String(s)] => Ok(Value::Date(parse_date(s)?)),
+            [Value::Date(d)] => Ok(Value::Date(*d)),
+            [Value::DateTime(dt)] => Ok(Value::Date(dt.date())),
+            [Value::Integer(y), Value::Integer(m), Value::Integer(d)] => {
+                NaiveDate::from_ymd_opt(*y as i32, *m as u32, *d as u32)
+                    .map(Value::Date)
+                    .ok_or_else(|| FeelError::Evaluation(format!("Invalid date {}-{}-{}", y, m, d)))
+            }
+            _ => eval_err("date() expects a string, a date and time or year, month, day"),
+        },
+        "date and time" => match args.as_slice() {
+            [Value::String(s)] => Ok(Value::DateTime(parse_date_time(s)?)),
+            [Value::Date(d)] => Ok(Value::DateTime(d.and_hms_opt(0, 0, 0).unwrap_or_default())),
+            _ => eval_err("date and time() expects a string"),
+        },
+        "duration" => {
+            arity(name, &args, 1..=1)?;
+            parse_duration(string_arg(name, &args[0])?)
+        }
+        "years and months duration" => match args.as_slice() {
+            [Value::Date(from), Value::Date(to)] => {
+                let mut months = (to.year() - from.year()) as i64 * 12
+                    + to.month() as i64
+                    - from.month() as i64;
+                if months > 0 && to.day() < from.day() {
+                    months -= 1;
+                } else if months < 0 && to.day() > from.day() {
+                    months += 1;
+                }
+                Ok(Value::YearsMonths(months))
+            }
+            _ => eval_err("years and months duration() expects two dates"),
+        },
+        "today" => {
+            arity(name, &args, 0..=0)?;
+            Ok(Value::Date(chrono::Utc::now().date_naive()))
+        }
+        "string" => {
+            arity(name, &args, 1..=1)?;
+            Ok(match &args[0] {
+                Value::Null => Value::Null,
+                other => Value::String(other.to_string()),
+            })
+        }
+        "number" => {
+            arity(name, &args, 1..=1)?;
+            parse_number(string_arg(name, &args[0])?.trim())
+                .map_err(|_| FeelError::Evaluation(format!("Not a number: {}", args[0])))
+        }
+        "string length" => {
+            arity(name, &args, 1..=1)?;
+            Ok(Value::Integer(string_arg(name, &args[0])?.chars().count() as i64))
+        }
+        "upper case" => {
+            arity(name, &args, 1..=1)?;
+            Ok(Value::String(string_arg(name, &args[0])?.to_uppercase()))
+        }
+        "lower case" => {
+            arity(name, &args, 1..=1)?;
+            Ok(Value::String(string_arg(name, &args[0])?.to_lowercase()))
+        }
+        "contains" | "starts with" | "ends with" | "substring before" | "substring after" => {
+            arity(name, &args, 2..=2)?;
+            let s = string_arg(name, &args[0])?;
+            let m = string_arg(name, &args[1])?;
+            Ok(match name {
+                "contains" => Value::Boolean(s.contains(m)),
+                "starts with" => Value::Boolean(s.starts_with(m)),
+                "ends with" => Value::Boolean(s.ends_with(m)),
+                "substring before" => {
+                    Value::String(s.split_once(m).map(|(b, _)| b).unwrap_or("").to_string())
+                }
+                _ => Value::String(s.split_once(m).map(|(_, a)| a).unwrap_or("").to_string()),
+            })
+        }
+        "substring" => {
+            arity(name, &args, 2..=3)?;
+            let chars: Vec<char> = string_arg(name, &args[0])?.chars().collect();
+            let start = number_arg(name, &args[1])? as i64;
+            let len = chars.len() as i64;
+            // 1-based; negative start counts from the end
+            let from = if start > 0 { start - 1 } else { len + start };
+            let from = from.clamp(0, len) as usize;
+            let to = match args.get(2) {
+                Some(length) => (from as i64 + number_arg(name, length)? as i64).clamp(0, len) as usize,
+                None => chars.len(),
+            };
+            Ok(Value::String(chars[from..to.max(from)].iter().collect()))
+        }
+        "list contains" => {
+            arity(name, &args, 2..=2)?;
+            match &args[0] {
+                Value::List(items) => Ok(Value::Boolean(
+                    items.iter().any(|i| equals(i, &args[1]) == Some(true)),
+                )),
+                other => eval_err(format!("list contains() expects a list, got {}", other.type_name())),
+            }
+        }
+        "count" => Ok(Value::Integer(list_args(args).len() as i64)),
+        "sum" | "mean" => {
+            let items = list_args(args);
+            if items.is_empty() {
+                return Ok(Value::Null);
+            }
+            let mut total = Value::Integer(0);
+            for item in &items {
+                total = arithmetic(BinaryOp::Add, total, item.clone())?;
+            }
+            if name == "mean" {
+                arithmetic(BinaryOp::Div, total, Value::Integer(items.len() as i64))
+            } else {
+                Ok(total)
+            }
+        }
+        "min" | "max" => {
+            let wanted = if name == "min" { Ordering::Less } else { Ordering::Greater };
+            let mut best: Option<Value> = None;
+            for item in list_args(args) {
+                best = match best {
+                    None => Some(item),
+                    Some(current) => match compare(&item, &current) {
+                        Some(o) if o == wanted => Some(item),
+                        Some(_) => Some(current),
+                        None => return eval_err(format!("{}() on incomparable values", name)),
+                    },
+                };
+            }
+            Ok(best.unwrap_or(Value::Null))
+        }
+        "abs" => {
+            arity(name, &args, 1..=1)?;
+            Ok(match &args[0] {
+                Value::Integer(i) => i.checked_abs().map_or(Value::Double((*i as f64).abs()), Value::Integer),
+                other => Value::Double(number_arg(name, other)?.abs()),
+            })
+        }
+        "floor" | "ceiling" => {
+            arity(name, &args, 1..=1)?;
+            let n = number_arg(name, &args[0])?;
+            Ok(number_value(if name == "floor" { n.floor() } else { n.ceil() }))
+        }
+        "decimal" => {
+            arity(name, &args, 2..=2)?;
+            let n = number_arg(name, &args[0])?;
+            let scale = number_arg(name, &args[1])? as i32;
+            let factor = 10f64.powi(scale);
+            // FEEL rounds half to even
+            let scaled = n * factor;
+            let rounded = if (scaled.fract().abs() - 0.5).abs() < 1e-9 {
+                let floor = scaled.floor();
+                if floor % 2.0 == 0.0 { floor } else { floor + 1.0 }
+            } else {
+                scaled.round()
+            };
+            Ok(if scale <= 0 {
+                number_value(rounded / factor)
+            } else {
+                Value::Double(rounded / factor)
+            })
+        }
+        "modulo" => {
+            arity(name, &args, 2..=2)?;
+            match (&args[0], &args[1]) {
+                (_, Value::Integer(0)) => eval_err("Division by zero"),
+                (Value::Integer(a), Value::Integer(b)) if a.checked_rem_euclid(*b).is_some() => {
+                    Ok(Value::Integer(a.rem_euclid(*b)))
+                }
+                (a, b) => {
+                    let (a, b) = (number_arg(name, a)?, number_arg(name, b)?);
+                    Ok(Value::Double(a - b * (a / b).floor()))
+                }
+            }
+        }
+        _ => eval_err(format!("Unknown function '{}'", name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, DecisionValue)]) -> HashMap<String, DecisionValue> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.clone())).collect()
+    }
+
+    fn eval_str(source: &str, variables: &HashMap<String, DecisionValue>) -> Value {
+        Expression::parse(source).unwrap().evaluate(variables).unwrap()
+    }
+
+    fn test(source: &str, input: Value) -> bool {
+        UnaryTests::parse(source).unwrap().matches(&input, &HashMap::new()).unwrap()
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_arithmetic_and_mixed_numbers() {
+        let empty = HashMap::new();
+        assert_eq!(eval_str("1 + 2 * 3", &empty), Value::Integer(7));
+        assert_eq!(eval_str("(1 + 2) * 3", &empty), Value::Integer(9));
+        assert_eq!(eval_str("7 / 2", &empty), Value::Double(3.5));
+        assert_eq!(eval_str("2 ** 10", &empty), Value::Integer(1024));
+        assert_eq!(eval_str("-3 + 1.5", &empty), Value::Double(-1.5));
+        assert_eq!(eval_str("1 = 1.0", &empty), Value::Boolean(true));
+        assert_eq!(eval_str("2 > 1.5", &empty), Value::Boolean(true));
+        assert!(Expression::parse("1 / 0").unwrap().evaluate(&empty).is_err());
+    }
+
+    #[test]
+    fn test_integer_overflow_does_not_panic() {
+        let v = vars(&[("min", DecisionValue::Integer(i64::MIN))]);
+        assert_eq!(eval_str("min / -1", &v), Value::Double(-(i64::MIN as f64)));
+        assert_eq!(eval_str("min / 1", &v), Value::Integer(i64::MIN));
+        assert_eq!(eval_str("modulo(min, -1)", &v), Value::Double(0.0));
+        assert_eq!(eval_str("modulo(-7, 3)", &v), Value::Integer(2));
+        assert_eq!(eval_str("abs(min)", &v), Value::Double(-(i64::MIN as f64)));
+    }
+
+    #[test]
+    fn test_variables_logic_and_conditionals() {
+        let v = vars(&[
+            ("leeftijd", DecisionValue::Integer(17)),
+            ("inkomen", DecisionValue::Double(24_000.0)),
+            ("aanvraag.type", DecisionValue::String("woo".into())),
+        ]);
+        assert_eq!(eval_str("leeftijd < 18 and inkomen <= 30000", &v), Value::Boolean(true));
+        assert_eq!(eval_str("leeftijd >= 18 or false", &v), Value::Boolean(false));
+        assert_eq!(eval_str("not(leeftijd between 12 and 16)", &v), Value::Boolean(true));
+        assert_eq!(eval_str("leeftijd in [0..18)", &v), Value::Boolean(true));
+        assert_eq!(eval_str("aanvraag.type in (\"woo\", \"wob\")", &v), Value::Boolean(true));
+        assert_eq!(
+            eval_str("if leeftijd < 18 then \"minderjarig\" else \"meerderjarig\"", &v),
+            Value::String("minderjarig".into())
+        );
+        assert!(Expression::parse("onbekend + 1").unwrap().evaluate(&v).is_err());
+    }
+
+    #[test]
+    fn test_dates_and_durations() {
+        let v = vars(&[("datum", DecisionValue::Date(date("2020-02-29")))]);
+        assert_eq!(eval_str("datum + duration(\"P20Y\")", &v), Value::Date(date("2040-02-29")));
+        assert_eq!(eval_str("datum + duration(\"P1Y\")", &v), Value::Date(date("2021-02-28")));
+        assert_eq!(eval_str("datum + duration(\"P14D\")", &v), Value::Date(date("2020-03-14")));
+        assert_eq!(eval_str("datum.year", &v), Value::Integer(2020));
+        assert_eq!(
+            eval_str("(date(\"2020-03-14\") - datum).days", &v),
+            Value::Integer(14)
+        );
+        assert_eq!(
+            eval_str("years and months duration(datum, date(\"2030-03-01\"))", &v),
+            Value::YearsMonths(120)
+        );
+        assert_eq!(eval_str("duration(\"P4W\") > duration(\"P27D\")", &v), Value::Boolean(true));
+        assert_eq!(
+            eval_str("date and time(\"2024-01-01T10:30:00\").hour", &v),
+            Value::Integer(10)
+        );
+        assert_eq!(Value::YearsMonths(18).to_string(), "P1Y6M");
+        assert_eq!(Value::DaysTime(chrono::Duration::hours(36)).to_string(), "P1DT12H");
+        assert!(parse_duration("P1Y2D").is_err());
+    }
+
+    #[test]
+    fn test_string_and_list_functions() {
+        let empty = HashMap::new();
+        assert_eq!(eval_str("string length(\"provincie\")", &empty), Value::Integer(9));
+        assert_eq!(eval_str("upper case(\"pz\")", &empty), Value::String("PZ".into()));
+        assert_eq!(eval_str("substring(\"Utrecht\", 1, 3)", &empty), Value::String("Utr".into()));
+        assert_eq!(eval_str("substring(\"Utrecht\", -4)", &empty), Value::String("echt".into()));
+        assert_eq!(eval_str("starts with(\"PV-123\", \"PV\")", &empty), Value::Boolean(true));
+        assert_eq!(eval_str("\"a\" + \"b\"", &empty), Value::String("ab".into()));
+        assert_eq!(eval_str("sum([1, 2, 3.5])", &empty), Value::Double(6.5));
+        assert_eq!(eval_str("max(3, 9, 4)", &empty), Value::Integer(9));
+        assert_eq!(eval_str("count([1, 2])", &empty), Value::Integer(2));
+        assert_eq!(eval_str("list contains([1, 2], 2.0)", &empty), Value::Boolean(true));
+        assert_eq!(eval_str("[10, 20, 30][-1]", &empty), Value::Integer(30));
+        assert_eq!(eval_str("decimal(2.345, 2)", &empty), Value::Double(2.34));
+        assert_eq!(eval_str("floor(2.7)", &empty), Value::Integer(2));
+    }
+
+    #[test]
+    fn test_unary_tests() {
+        assert!(test("-", Value::Integer(5)));
+        assert!(test("< 10", Value::Double(9.5)));
+        assert!(!test("< 10", Value::Integer(10)));
+        assert!(test("[1..10)", Value::Integer(1)));
+        assert!(!test("[1..10)", Value::Integer(10)));
+        assert!(test("]1..10]", Value::Integer(10)));
+        assert!(!test("[1..10[", Value::Integer(10)));
+        assert!(!test("(1..10]", Value::Integer(1)));
+        assert!(test("\"a\", \"b\"", Value::String("b".into())));
+        assert!(test("not(\"a\", \"b\")", Value::String("c".into())));
+        assert!(!test("not(\"a\", \"b\")", Value::String("a".into())));
+        assert!(test("< 5, > 10", Value::Integer(11)));
+        assert!(test("[date(\"2020-01-01\")..date(\"2020-12-31\")]", Value::Date(date("2020-06-01"))));
+        assert!(test(">= date(\"2020-01-01\")", Value::Date(date("2020-01-01"))));
+        assert!(test("? > 3 and ? < 6", Value::Integer(4)));
+        assert!(test("null", Value::Null));
+
+        // Other variables may be referenced in tests
+        let v = vars(&[("grens", DecisionValue::Integer(100))]);
+        let tests = UnaryTests::parse("<= grens").unwrap();
+        assert!(tests.matches(&Value::Integer(100), &v).unwrap());
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        assert!(matches!(Expression::parse("1 +"), Err(FeelError::Syntax(_))));
+        assert!(matches!(Expression::parse("\"open"), Err(FeelError::Syntax(_))));
+        assert!(matches!(UnaryTests::parse("[1..2"), Err(FeelError::Syntax(_))));
+        assert!(matches!(Expression::parse("1 2"), Err(FeelError::Syntax(_))));
+    }
+}
@@ -0,0 +1,240 @@
+//! Decision table hit policies (DMN 1.3 section 8.2.11)
+
+use super::feel::{self, Value};
+use super::{Decision, DecisionTrace, DecisionValue, DmnError};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Hit policy of a decision table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HitPolicy {
+    /// At most one rule may match (the DMN default)
+    #[default]
+    Unique,
+    /// The first matching rule in table order wins
+    First,
+    /// The matching rule with the highest output priority wins
+    Priority,
+    /// Several rules may match, but they must all give the same output
+    Any,
+    /// All matches, in arbitrary order, optionally aggregated
+    Collect,
+    /// All matches, in rule order
+    RuleOrder,
+    /// All matches, in decreasing output priority
+    OutputOrder,
+}
+
+impl HitPolicy {
+    /// Parse the `hitPolicy` attribute (full names and DMN 1.1 abbreviations)
+    pub fn parse(s: &str) -> Option<Self> {
+        Some(match s.trim().to_uppercase().as_str() {
+            "UNIQUE" | "U" => Self::Unique,
+            "FIRST" | "F" => Self::First,
+            "PRIORITY" | "P" => Self::Priority,
+            "ANY" | "A" => Self::Any,
+            "COLLECT" | "C" => Self::Collect,
+            "RULE ORDER" | "R" => Self::RuleOrder,
+            "OUTPUT ORDER" | "O" => Self::OutputOrder,
+            _ => return None,
+        })
+    }
+
+    /// Whether the policy returns a single rule's outputs
+    pub fn is_single_hit(&self) -> bool {
+        matches!(self, Self::Unique | Self::First | Self::Priority | Self::Any)
+    }
+}
+
+/// Aggregation for the COLLECT hit policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Aggregation {
+    Sum,
+    Min,
+    Max,
+    /// Number of distinct output values
+    Count,
+}
+
+impl Aggregation {
+    /// Parse the `aggregation` attribute (`SUM`, `MIN`, `MAX`, `COUNT` or `C+`, `C<`, `C>`, `C#`)
+    pub fn parse(s: &str) -> Option<Self> {
+        Some(match s.trim().to_uppercase().as_str() {
+            "SUM" | "+" => Self::Sum,
+            "MIN" | "<" => Self::Min,
+            "MAX" | ">" => Self::Max,
+            "COUNT" | "#" => Self::Count,
+            _ => return None,
+        })
+    }
+}
+
+/// A matching rule and its evaluated outputs
+pub(super) struct RuleHit {
+    pub rule_id: String,
+    pub outputs: HashMap<String, DecisionValue>,
+}
+
+/// Result of evaluating one decision table
+pub(super) struct TableOutcome {
+    pub selected_rule: Option<String>,
+    pub trace: DecisionTrace,
+}
+
+type Applied = (Option<String>, Vec<String>, HashMap<String, DecisionValue>);
+
+/// Apply the decision's hit policy to the matching rules
+///
+/// Returns the selected rule (first rule for multi-hit policies), all
+/// matched rule IDs in result order, and the decision outputs.
+pub(super) fn apply(decision: &Decision, mut hits: Vec<RuleHit>) -> Result<Applied, DmnError> {
+    if hits.is_empty() {
+        return Ok((None, vec![], HashMap::new()));
+    }
+
+    match decision.hit_policy {
+        HitPolicy::Unique if hits.len() > 1 => {
+            return Err(DmnError::EvaluationError(format!(
+                "Decision {} has hit policy UNIQUE but rules {} all match",
+                decision.id,
+                rule_ids(&hits).join(", ")
+            )));
+        }
+        HitPolicy::Any => {
+            let first = &hits[0];
+            if let Some(other) = hits[1..].iter().find(|h| !same_outputs(&first.outputs, &h.outputs)) {
+                return Err(DmnError::EvaluationError(format!(
+                    "Decision {} has hit policy ANY but rules {} and {} give different outputs",
+                    decision.id, first.rule_id, other.rule_id
+                )));
+            }
+        }
+        HitPolicy::Priority | HitPolicy::OutputOrder => {
+            // Stable sort keeps rule order between equal priorities
+            hits.sort_by_key(|hit| priority_key(decision, hit));
+        }
+        _ => {}
+    }
+
+    let matched = rule_ids(&hits);
+    let selected = matched.first().cloned();
+
+    if decision.hit_policy.is_single_hit() {
+        let first = hits.swap_remove(0);
+        return Ok((selected, matched, first.outputs));
+    }
+
+    let mut outputs = HashMap::new();
+    for output in &decision.outputs {
+        let values: Vec<DecisionValue> = hits
+            .iter()
+            .filter_map(|hit| hit.outputs.get(&output.name).cloned())
+            .collect();
+
+        let value = match decision.aggregation {
+            None => Some(DecisionValue::Array(values)),
+            Some(aggregation) => aggregate(aggregation, &values).map_err(|msg| {
+                DmnError::EvaluationError(format!(
+                    "Decision {}, output {}: {}",
+                    decision.id, output.name, msg
+                ))
+            })?,
+        };
+        if let Some(value) = value {
+            outputs.insert(output.name.clone(), value);
+        }
+    }
+
+    Ok((selected, matched, outputs))
+}
+
+fn rule_ids(hits: &[RuleHit]) -> Vec<String> {
+    hits.iter().map(|h| h.rule_id.clone()).collect()
+}
+
+fn same_outputs(a: &HashMap<String, DecisionValue>, b: &HashMap<String, DecisionValue>) -> bool {
+    a.len() == b.len()
+        && a.iter().all(|(name, value)| {
+            b.get(name)
+                .is_some_and(|other| feel::equals(&Value::from(value), &Value::from(other)) == Some(true))
+        })
+}
+
+/// Position of each output value in its output's list of allowed values;
+/// lower is higher priority. Values not in the list rank last.
+fn priority_key(decision: &Decision, hit: &RuleHit) -> Vec<usize> {
+    decision
+        .outputs
+        .iter()
+        .map(|output| {
+            let Some(value) = hit.outputs.get(&output.name).map(Value::from) else {
+                return usize::MAX;
+            };
+            output
+                .output_values
+                .iter()
+                .position(|allowed| feel::equals(&Value::from(allowed), &value) == Some(true))
+                .unwrap_or(usize::MAX)
+        })
+        .collect()
+}
+
+fn aggregate(aggregation: Aggregation, values: &[DecisionValue]) -> Result<Option<DecisionValue>, String> {
+    let values: Vec<Value> = values.iter().map(Value::from).collect();
+
+    match aggregation {
+        Aggregation::Count => {
+            let mut distinct: Vec<&Value> = Vec::new();
+            for value in &values {
+                if !distinct.iter().any(|d| feel::equals(d, value) == Some(true)) {
+                    distinct.push(value);
+                }
+            }
+            Ok(Some(DecisionValue::Integer(distinct.len() as i64)))
+        }
+        Aggregation::Sum => {
+            if values.is_empty() {
+                return Ok(None);
+            }
+            if values.iter().all(|v| matches!(v, Value::Integer(_))) {
+                let sum = values
+                    .iter()
+                    .map(|v| if let Value::Integer(i) = v { *i } else { 0 })
+                    .try_fold(0i64, |acc, i| acc.checked_add(i))
+                    .ok_or("numeric overflow in SUM")?;
+                return Ok(Some(DecisionValue::Integer(sum)));
+            }
+            let mut sum = 0.0;
+            for value in &values {
+                sum += match value {
+                    Value::Integer(i) => *i as f64,
+                    Value::Double(f) => *f,
+                    other => return Err(format!("cannot SUM a {} value", other.type_name())),
+                };
+            }
+            Ok(Some(DecisionValue::Double(sum)))
+        }
+        Aggregation::Min | Aggregation::Max => {
+            let wanted = if aggregation == Aggregation::Min {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            };
+            let mut best: Option<&Value> = None;
+            for value in &values {
+                best = match best {
+                    None => Some(value),
+                    Some(current) => match feel::compare(value, current) {
+                        Some(o) if o == wanted => Some(value),
+                        Some(_) => Some(current),
+                        None => return Err("values are not comparable".to_string()),
+                    },
+                };
+            }
+            Ok(best.cloned().and_then(Value::into_decision_value))
+        }
+    }
+}
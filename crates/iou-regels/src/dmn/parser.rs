@@ -0,0 +1,429 @@
+//! DMN 1.3/1.4 XML parser
+//!
+//! Maps a `<definitions>` document onto [`Decision`] values. Every
+//! `<decision>` with a `<decisionTable>` becomes one decision. Entries that
+//! are plain literals or simple comparisons map onto [`ConditionOperator`]s;
+//! everything else is compiled as FEEL (see [`super::feel`]).
+
+use super::feel::{Expression, UnaryTests};
+use super::{
+    Aggregation, Conclusion, Condition, ConditionOperator, Decision, DecisionMetadata,
+    DecisionRule, DecisionValue, DmnError, HitPolicy, InputClause, OutputClause,
+};
+use crate::xml::{self, XmlElement};
+
+/// DMN model namespaces and the specification version they belong to
+const DMN_NAMESPACES: &[(&str, &str)] = &[
+    ("http://www.omg.org/spec/DMN/20151101/dmn.xsd", "1.1"),
+    ("http://www.omg.org/spec/DMN/20180521/MODEL/", "1.2"),
+    ("https://www.omg.org/spec/DMN/20191111/MODEL/", "1.3"),
+    ("https://www.omg.org/spec/DMN/20211108/MODEL/", "1.4"),
+    ("https://www.omg.org/spec/DMN/20230324/MODEL/", "1.5"),
+];
+
+/// Parse all decision tables in a DMN `definitions` document
+pub(super) fn parse_definitions(source: &str) -> Result<Vec<Decision>, DmnError> {
+    let root = xml::parse(source).map_err(DmnError::ParseError)?;
+
+    if root.name != "definitions" {
+        return Err(DmnError::ParseError(format!(
+            "Expected <definitions> root element, found <{}>",
+            root.name
+        )));
+    }
+
+    let dmn_version = detect_version(&root);
+
+    let decisions = root
+        .children_named("decision")
+        .map(|decision| parse_decision(decision, &dmn_version))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if decisions.is_empty() {
+        return Err(DmnError::ParseError("Definitions contain no decisions".into()));
+    }
+
+    Ok(decisions)
+}
+
+fn detect_version(root: &XmlElement) -> String {
+    root.attributes
+        .iter()
+        .filter(|(key, _)| key == "xmlns" || key.starts_with("xmlns:"))
+        .find_map(|(_, ns)| {
+            DMN_NAMESPACES
+                .iter()
+                .find(|(known, _)| known.trim_end_matches('/') == ns.trim_end_matches('/'))
+                .map(|(_, version)| version.to_string())
+        })
+        .unwrap_or_else(|| "1.4".to_string())
+}
+
+fn parse_decision(element: &XmlElement, dmn_version: &str) -> Result<Decision, DmnError> {
+    let id = element
+        .attr("id")
+        .ok_or_else(|| DmnError::ParseError("Decision without id".into()))?
+        .to_string();
+    let name = element.attr("name").unwrap_or(&id).to_string();
+
+    let table = element.child("decisionTable").ok_or_else(|| {
+        DmnError::ParseError(format!("Decision '{}' has no decision table", id))
+    })?;
+
+    let hit_policy = match table.attr("hitPolicy") {
+        Some(policy) => HitPolicy::parse(policy).ok_or_else(|| {
+            DmnError::ParseError(format!("Decision '{}': unknown hit policy {}", id, policy))
+        })?,
+        None => HitPolicy::default(),
+    };
+
+    let aggregation = match table.attr("aggregation") {
+        Some(aggregation) => Some(Aggregation::parse(aggregation).ok_or_else(|| {
+            DmnError::ParseError(format!(
+                "Decision '{}': unknown aggregation {}",
+                id, aggregation
+            ))
+        })?),
+        None => None,
+    };
+    if aggregation.is_some() && hit_policy != HitPolicy::Collect {
+        return Err(DmnError::ParseError(format!(
+            "Decision '{}': aggregation requires the COLLECT hit policy",
+            id
+        )));
+    }
+
+    // `<requiredDecision href="#otherDecision"/>` links to decisions in the
+    // same definitions document
+    let required_decisions = element
+        .children_named("informationRequirement")
+        .filter_map(|req| req.child("requiredDecision"))
+        .filter_map(|req| req.attr("href"))
+        .map(|href| href.rsplit('#').next().unwrap_or(href).to_string())
+        .collect();
+
+    let inputs: Vec<InputClause> = table
+        .children_named("input")
+        .enumerate()
+        .map(|(i, input)| parse_input(input, i))
+        .collect();
+
+    let outputs = table
+        .children_named("output")
+        .enumerate()
+        .map(|(i, output)| parse_output(output, i))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if outputs.is_empty() {
+        return Err(DmnError::ParseError(format!("Decision '{}' has no outputs", id)));
+    }
+
+    let rules = table
+        .children_named("rule")
+        .enumerate()
+        .map(|(i, rule)| parse_rule(rule, i, &inputs, &outputs))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| match e {
+            DmnError::ParseError(msg) => {
+                DmnError::ParseError(format!("Decision '{}': {}", id, msg))
+            }
+            other => other,
+        })?;
+
+    Ok(Decision {
+        id,
+        name,
+        hit_policy,
+        aggregation,
+        required_decisions,
+        inputs,
+        outputs,
+        rules,
+        metadata: DecisionMetadata {
+            matched_rule: None,
+            evaluation_time_us: 0,
+            dmn_version: dmn_version.to_string(),
+            open_regels_uri: None,
+        },
+    })
+}
+
+fn parse_input(element: &XmlElement, index: usize) -> InputClause {
+    let id = element
+        .attr("id")
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("input_{}", index + 1));
+
+    let expression = element.child("inputExpression");
+
+    // The input expression is what gets looked up in the context; the
+    // label is only a display name and is used as a fallback.
+    let name = expression
+        .and_then(|e| e.child_text("text"))
+        .filter(|t| !t.is_empty())
+        .or_else(|| element.attr("label"))
+        .unwrap_or(&id)
+        .to_string();
+
+    let type_ref = expression
+        .and_then(|e| e.attr("typeRef"))
+        .or_else(|| element.attr("typeRef"))
+        .unwrap_or("string")
+        .to_string();
+
+    InputClause { id, name, type_ref }
+}
+
+fn parse_output(element: &XmlElement, index: usize) -> Result<OutputClause, DmnError> {
+    let id = element
+        .attr("id")
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("output_{}", index + 1));
+
+    let name = element
+        .attr("name")
+        .or_else(|| element.attr("label"))
+        .unwrap_or(&id)
+        .to_string();
+
+    let default_value = match element
+        .child("defaultOutputEntry")
+        .and_then(|e| e.child_text("text"))
+    {
+        Some(text) if !text.is_empty() => match parse_literal(text) {
+            Some(value) => Some(value),
+            // Constant expressions such as `duration("P20Y")`
+            None => Expression::parse(text)
+                .and_then(|e| e.evaluate(&Default::default()))
+                .map_err(|e| {
+                    DmnError::ParseError(format!(
+                        "Invalid default output entry for '{}': {}",
+                        name, e
+                    ))
+                })?
+                .into_decision_value(),
+        },
+        _ => None,
+    };
+
+    let output_values = match element
+        .child("outputValues")
+        .and_then(|values| values.child_text("text"))
+    {
+        Some(text) if !text.is_empty() => parse_literal_list(text).ok_or_else(|| {
+            DmnError::ParseError(format!("Invalid output values for '{}': {}", name, text))
+        })?,
+        _ => vec![],
+    };
+
+    Ok(OutputClause {
+        id,
+        name,
+        type_ref: element.attr("typeRef").unwrap_or("string").to_string(),
+        default_value,
+        output_values,
+    })
+}
+
+fn parse_rule(
+    element: &XmlElement,
+    index: usize,
+    inputs: &[InputClause],
+    outputs: &[OutputClause],
+) -> Result<DecisionRule, DmnError> {
+    let id = element
+        .attr("id")
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("rule_{}", index + 1));
+
+    let input_entries: Vec<&str> = element
+        .children_named("inputEntry")
+        .map(|e| e.child_text("text").unwrap_or(""))
+        .collect();
+    let output_entries: Vec<&str> = element
+        .children_named("outputEntry")
+        .map(|e| e.child_text("text").unwrap_or(""))
+        .collect();
+
+    if input_entries.len() != inputs.len() {
+        return Err(DmnError::ParseError(format!(
+            "Rule '{}' has {} input entries, table has {} inputs",
+            id,
+            input_entries.len(),
+            inputs.len()
+        )));
+    }
+    if output_entries.len() != outputs.len() {
+        return Err(DmnError::ParseError(format!(
+            "Rule '{}' has {} output entries, table has {} outputs",
+            id,
+            output_entries.len(),
+            outputs.len()
+        )));
+    }
+
+    let mut conditions = Vec::new();
+    for (input, text) in inputs.iter().zip(input_entries) {
+        if let Some((operator, value)) = parse_unary_test(text).map_err(|msg| {
+            DmnError::ParseError(format!("Rule '{}', input '{}': {}", id, input.name, msg))
+        })? {
+            conditions.push(Condition {
+                input_id: input.id.clone(),
+                operator,
+                value,
+            });
+        }
+    }
+
+    let mut conclusions = Vec::new();
+    for (output, text) in outputs.iter().zip(output_entries) {
+        if text.is_empty() {
+            continue;
+        }
+        let conclusion = match parse_literal(text) {
+            Some(value) => Conclusion {
+                output_id: output.id.clone(),
+                value,
+                expression: None,
+            },
+            None => Conclusion {
+                output_id: output.id.clone(),
+                value: DecisionValue::String(text.to_string()),
+                expression: Some(Expression::parse(text).map_err(|e| {
+                    DmnError::ParseError(format!(
+                        "Rule '{}', output '{}': {}",
+                        id, output.name, e
+                    ))
+                })?),
+            },
+        };
+        conclusions.push(conclusion);
+    }
+
+    Ok(DecisionRule {
+        id,
+        conditions,
+        conclusions,
+    })
+}
+
+/// Parse a FEEL unary test into an operator and comparison value.
+///
+/// Returns `Ok(None)` for the "any" test (`-` or empty). Tests that don't
+/// map onto a single operator are compiled as [`ConditionOperator::Feel`].
+pub(super) fn parse_unary_test(
+    text: &str,
+) -> Result<Option<(ConditionOperator, DecisionValue)>, String> {
+    let text = text.trim();
+    if text.is_empty() || text == "-" {
+        return Ok(None);
+    }
+
+    if let Some(simple) = simple_unary_test(text) {
+        return Ok(Some(simple));
+    }
+
+    let tests = UnaryTests::parse(text).map_err(|e| e.to_string())?;
+    Ok(Some((
+        ConditionOperator::Feel(tests),
+        DecisionValue::String(text.to_string()),
+    )))
+}
+
+/// Map literal lists, closed ranges and comparisons with a literal onto
+/// the structured operators
+fn simple_unary_test(text: &str) -> Option<(ConditionOperator, DecisionValue)> {
+    if let Some(inner) = text.strip_prefix("not(").and_then(|t| t.strip_suffix(')')) {
+        let items = parse_literal_list(inner)?;
+        return Some(match <[DecisionValue; 1]>::try_from(items) {
+            Ok([single]) => (ConditionOperator::NotEqual, single),
+            Err(items) => (ConditionOperator::NotIn, DecisionValue::Array(items)),
+        });
+    }
+
+    if let Some(inner) = text.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+        let (low, high) = inner.split_once("..")?;
+        let low = parse_literal(low.trim())?;
+        let high = parse_literal(high.trim())?;
+        return Some((ConditionOperator::Between, DecisionValue::Array(vec![low, high])));
+    }
+
+    for (prefix, operator) in [
+        ("<=", ConditionOperator::LessThanOrEqual),
+        (">=", ConditionOperator::GreaterThanOrEqual),
+        ("<", ConditionOperator::LessThan),
+        (">", ConditionOperator::GreaterThan),
+    ] {
+        if let Some(rest) = text.strip_prefix(prefix) {
+            return parse_literal(rest.trim()).map(|value| (operator, value));
+        }
+    }
+
+    let items = parse_literal_list(text)?;
+    Some(match <[DecisionValue; 1]>::try_from(items) {
+        Ok([single]) => (ConditionOperator::Equal, single),
+        Err(items) => (ConditionOperator::In, DecisionValue::Array(items)),
+    })
+}
+
+/// Parse a comma separated list of FEEL literals
+fn parse_literal_list(text: &str) -> Option<Vec<DecisionValue>> {
+    split_top_level(text).into_iter().map(parse_literal).collect()
+}
+
+/// Split on commas that are not inside quotes or parentheses
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut start = 0;
+
+    for (i, c) in text.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '(' | '[' if !in_string => depth += 1,
+            ')' | ']' if !in_string => depth = depth.saturating_sub(1),
+            ',' if !in_string && depth == 0 => {
+                parts.push(text[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(text[start..].trim());
+    parts
+}
+
+/// Parse a FEEL literal: string, number, boolean or `date("...")`
+pub(super) fn parse_literal(text: &str) -> Option<DecisionValue> {
+    let text = text.trim();
+
+    if let Some(inner) = text.strip_prefix('"').and_then(|t| t.strip_suffix('"')) {
+        return Some(DecisionValue::String(inner.replace("\\\"", "\"")));
+    }
+
+    match text {
+        "true" => return Some(DecisionValue::Boolean(true)),
+        "false" => return Some(DecisionValue::Boolean(false)),
+        _ => {}
+    }
+
+    if let Some(inner) = text
+        .strip_prefix("date(\"")
+        .and_then(|t| t.strip_suffix("\")"))
+    {
+        return chrono::NaiveDate::parse_from_str(inner, "%Y-%m-%d")
+            .ok()
+            .map(DecisionValue::Date);
+    }
+
+    if let Ok(i) = text.parse::<i64>() {
+        return Some(DecisionValue::Integer(i));
+    }
+
+    if text.contains('.') && !text.contains("..") {
+        return text.parse::<f64>().ok().map(DecisionValue::Double);
+    }
+
+    None
+}
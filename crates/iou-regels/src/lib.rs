@@ -7,10 +7,14 @@
 //!
 //! - [`architektur`]: IOU Architectuur componenten en standaarden
 //! - [`client`]: SPARQL client voor de Open Regels Linked Data endpoint
+//! - [`rdf`]: Lokale triple store voor offline gebruik van Open Regels dumps
 //! - [`model`]: Domeinmodellen voor regelspecificaties (FLINT, DMN, ReSpec)
 //! - [`tools`]: Agentic tools — plug-in klaar voor LLM tool_use loops
 //! - [`compliance`]: Koppeling van regelspecificaties aan iou-core compliance types
 //! - [`provisa`]: Provinciale selectielijsten en archiefwetgeving
+//! - [`vernietiging`]: Vernietigingslijsten, beoordeling en verklaring van vernietiging
+//! - [`overbrenging`]: Overdrachtspakketten (MDTO/ToPX) voor het e-Depot
+//! - [`abac`]: Attribuut-gebaseerde toegangsregels met beleid per tenant en uitleg van besluiten
 //!
 //! # Gebruik
 //!
@@ -49,9 +53,17 @@
 pub mod architektur;
 pub mod compliance;
 pub mod provisa;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod vernietiging;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod overbrenging;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod abac;
 
 // DMN/BPMN business rules integration
 #[cfg(not(target_arch = "wasm32"))]
+mod xml;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod dmn;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod bpmn;
@@ -62,11 +74,15 @@ pub mod client;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod model;
 #[cfg(not(target_arch = "wasm32"))]
+pub mod rdf;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod tools;
 
 #[cfg(not(target_arch = "wasm32"))]
 pub use client::OpenRegelsClient;
 #[cfg(not(target_arch = "wasm32"))]
+pub use rdf::{RdfError, RdfStore};
+#[cfg(not(target_arch = "wasm32"))]
 pub use model::{Regel, RegelDetail, RegelType, JuriconnectRef};
 #[cfg(not(target_arch = "wasm32"))]
 pub use tools::OpenRegelsTools;
@@ -76,9 +92,10 @@ pub use tools::OpenRegelsTools;
 pub use dmn::{DmnEvaluator, DecisionContext, DecisionValue, DecisionResult, DmnError};
 #[cfg(not(target_arch = "wasm32"))]
 pub use bpmn::{
-    BpmnProcessEngine, ProcessDefinition, ProcessInstance,
-    ActivityType, GatewayType, EventType, ProcessInstanceState,
-    load_process_from_open_regels,
+    BpmnProcessEngine, ProcessDefinition, ProcessInstance, SequenceFlow, ActivityRecord,
+    ActivityType, GatewayType, EventType, ProcessInstanceState, BpmnDialect,
+    ProcessInstanceStore, InMemoryProcessStore, Token, TokenWait, BpmnError,
+    load_process_from_open_regels, parse_bpmn_xml, to_bpmn_xml,
 };
 
 pub use architektur::{
@@ -88,4 +105,19 @@ pub use provisa::{
     ProvisaVersion, ProvincieOrgaan, PetraCategorie, BesluitType,
     Bewaartermijn, Archiefwaarde, ProvisaBepaling, ProvisaSelectielijst,
     Hotspot, HotspotRegister, ProvisaBeoordeling,
+    SelectielijstImport, ImportFormaat, ProvisaImportError,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use vernietiging::{
+    Archiefstuk, Vernietigingslijst, VernietigingslijstStatus, VernietigingsItem,
+    VernietigingsUitvoerder, VerklaringVanVernietiging, VernietigingError, OverslagReden,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use overbrenging::{
+    Archiefvormer, InhoudBron, MetadataSchema, OverTeBrengenStuk, Overdrachtspakket,
+    OverbrengingError,
+};
+#[cfg(not(target_arch = "wasm32"))]
+pub use abac::{
+    Effect, Policy, PolicyEngine, PolicyError, PolicyTest, Resource, Subject, TestReport,
 };
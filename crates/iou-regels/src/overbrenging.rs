@@ -0,0 +1,973 @@
+//! Overbrenging naar het e-Depot volgens de Archiefwet
+//!
+//! Blijvend te bewaren stukken worden na de overbrengingstermijn overgedragen
+//! aan de archiefdienst. Dit module stelt daarvoor overdrachtspakketten samen
+//! in een SIP-indeling (Submission Information Package):
+//!
+//! ```text
+//! sip-2024-01-01-1a2b3c4d/
+//!   manifest.xml                       alle bestanden met grootte en SHA-256
+//!   checksums.sha256                   sha256sum-formaat, ook voor manifest.xml
+//!   <object-id>/
+//!     <object-id>.mdto.xml             metagegevens informatieobject
+//!     besluit.pdf                      inhoud
+//!     besluit.pdf.mdto.xml             metagegevens bestand (met checksum)
+//! ```
+//!
+//! Metagegevens worden geschreven volgens MDTO of, voor e-Depots die nog
+//! TMLO verwachten, als ToPX ([`MetadataSchema`]). Ze worden afgeleid uit
+//! [`InformationObject`], de [`RetentionPolicy`] uit de PROVISA beoordeling
+//! en de [`WooMetadata`] (openbaarheidsbeperkingen).
+//!
+//! Alleen stukken waarvoor [`ProvisaBeoordeling::is_overdraagbaar_per`] geldt
+//! komen in het pakket. [`Overdrachtspakket::valideer`] controleert het pakket
+//! lokaal (manifest, checksums, verplichte metagegevens) en
+//! [`Overdrachtspakket::exporteer`] schrijft alleen gevalideerde pakketten weg.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::path::{Component, Path, PathBuf};
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use iou_core::compliance::{RetentionPolicy, WooDisclosureClass, WooMetadata};
+use iou_core::objects::InformationObject;
+use quick_xml::escape::escape;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::provisa::{
+    BesluitType, HotspotRegister, PetraCategorie, ProvisaBeoordeling, ProvisaSelectielijst,
+};
+
+mod mdto;
+mod topx;
+
+/// Naam van het manifest in de root van het pakket
+pub const MANIFEST: &str = "manifest.xml";
+
+/// Naam van het checksumbestand in de root van het pakket
+pub const CHECKSUMS: &str = "checksums.sha256";
+
+const MANIFEST_NS: &str = "urn:iou-modern:sip:1.0";
+
+/// Bron van de identificatiekenmerken in de metagegevens
+const IDENTIFICATIE_BRON: &str = "IOU-Modern";
+
+/// Fouten bij het samenstellen en exporteren van overdrachtspakketten
+#[derive(Debug, Error)]
+pub enum OverbrengingError {
+    #[error("Bestandsfout: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Inhoud van {object_id} niet beschikbaar: {melding}")]
+    Inhoud { object_id: Uuid, melding: String },
+
+    #[error("Geen overdraagbare stukken: pakket zou leeg zijn")]
+    GeenStukken,
+
+    #[error("Pakket ongeldig: {}", .0.join("; "))]
+    Validatie(Vec<String>),
+}
+
+/// Metagegevensschema voor het e-Depot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetadataSchema {
+    /// MDTO 1.0 (Metagegevens Duurzaam Toegankelijke Overheidsinformatie)
+    #[default]
+    Mdto,
+    /// ToPX 2.3, de XML-vorm van TMLO
+    Topx,
+}
+
+impl MetadataSchema {
+    /// Extensie van metagegevensbestanden (`.mdto.xml` / `.topx.xml`)
+    pub fn extensie(&self) -> &'static str {
+        match self {
+            Self::Mdto => "mdto.xml",
+            Self::Topx => "topx.xml",
+        }
+    }
+
+    fn naam(&self) -> &'static str {
+        match self {
+            Self::Mdto => "MDTO",
+            Self::Topx => "ToPX",
+        }
+    }
+}
+
+/// De organisatie die de stukken heeft gevormd en overdraagt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Archiefvormer {
+    /// Naam, bijv. "Provincie Flevoland"
+    pub naam: String,
+    /// Identificatie, bij voorkeur een TOOI-URI of OIN
+    pub identificatie: String,
+}
+
+impl Archiefvormer {
+    pub fn new(naam: impl Into<String>, identificatie: impl Into<String>) -> Self {
+        Self {
+            naam: naam.into(),
+            identificatie: identificatie.into(),
+        }
+    }
+}
+
+/// Een informatieobject met zijn PROVISA beoordeling
+#[derive(Debug, Clone)]
+pub struct OverTeBrengenStuk {
+    pub object: InformationObject,
+    pub categorie: PetraCategorie,
+    pub besluit_type: BesluitType,
+    pub beoordeling: ProvisaBeoordeling,
+    pub woo: Option<WooMetadata>,
+}
+
+impl OverTeBrengenStuk {
+    /// Beoordeel een informatieobject tegen de selectielijst
+    ///
+    /// De bewaartermijn gaat in op `creatie_datum` (meestal de afsluiting van
+    /// het dossier, niet de aanmaakdatum van het object).
+    pub fn beoordeel(
+        object: InformationObject,
+        categorie: PetraCategorie,
+        besluit_type: BesluitType,
+        creatie_datum: NaiveDate,
+        selectielijst: &ProvisaSelectielijst,
+        hotspots: Option<&HotspotRegister>,
+    ) -> Self {
+        let beoordeling = ProvisaBeoordeling::beoordeel(
+            selectielijst,
+            &categorie,
+            &besluit_type,
+            creatie_datum,
+            hotspots,
+        );
+        Self {
+            object,
+            categorie,
+            besluit_type,
+            beoordeling,
+            woo: None,
+        }
+    }
+
+    /// Woo-metagegevens (openbaarheid en weigeringsgronden)
+    pub fn met_woo(mut self, woo: WooMetadata) -> Self {
+        self.woo = Some(woo);
+        self
+    }
+
+    /// Bewaarbeleid volgens de beoordeling
+    pub fn retentie(&self) -> RetentionPolicy {
+        self.beoordeling.clone().into()
+    }
+}
+
+/// Leest de inhoud van een informatieobject uit opslag
+#[async_trait]
+pub trait InhoudBron: Send + Sync {
+    async fn lees(&self, object: &InformationObject) -> Result<Vec<u8>, OverbrengingError>;
+}
+
+/// Een bestand in het pakket, met pad relatief aan de pakketmap
+#[derive(Debug, Clone)]
+pub struct PakketBestand {
+    pub pad: String,
+    pub inhoud: Vec<u8>,
+}
+
+impl PakketBestand {
+    /// Hex-gecodeerde SHA-256 van de inhoud
+    pub fn sha256(&self) -> String {
+        sha256_hex(&self.inhoud)
+    }
+}
+
+/// Een stuk dat niet in het pakket is opgenomen
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NietOvergebracht {
+    pub object_id: Uuid,
+    pub titel: String,
+    pub reden: String,
+}
+
+/// Overdrachtspakket voor het e-Depot
+#[derive(Debug, Clone)]
+pub struct Overdrachtspakket {
+    /// Naam van het pakket, tevens naam van de pakketmap
+    pub id: String,
+    pub archiefvormer: Archiefvormer,
+    pub peildatum: NaiveDate,
+    pub schema: MetadataSchema,
+    pub aangemaakt_op: DateTime<Utc>,
+    /// Alle bestanden, inclusief manifest en checksums
+    pub bestanden: Vec<PakketBestand>,
+    /// Ids van de overgebrachte informatieobjecten
+    pub stukken: Vec<Uuid>,
+    pub niet_overgebracht: Vec<NietOvergebracht>,
+}
+
+impl Overdrachtspakket {
+    /// Stel een overdrachtspakket samen
+    ///
+    /// Stukken die niet blijvend te bewaren zijn, nog niet overdraagbaar zijn
+    /// per `peildatum` of waarvan de inhoud niet leesbaar is komen in
+    /// [`niet_overgebracht`](Self::niet_overgebracht).
+    pub async fn samenstellen(
+        stukken: impl IntoIterator<Item = OverTeBrengenStuk>,
+        archiefvormer: Archiefvormer,
+        peildatum: NaiveDate,
+        schema: MetadataSchema,
+        bron: &dyn InhoudBron,
+    ) -> Result<Self, OverbrengingError> {
+        let aangemaakt_op = Utc::now();
+        let id = format!("sip-{}-{}", peildatum, &Uuid::new_v4().simple().to_string()[..8]);
+        let mut bestanden = Vec::new();
+        let mut opgenomen = Vec::new();
+        let mut niet_overgebracht = Vec::new();
+
+        for stuk in stukken {
+            let reden = if !stuk.beoordeling.moet_overbrengen() {
+                Some("Niet blijvend te bewaren".to_string())
+            } else if !stuk.beoordeling.is_overdraagbaar_per(peildatum) {
+                Some(match stuk.beoordeling.overbrengingsdatum {
+                    Some(datum) => format!("Overdraagbaar per {}", datum),
+                    None => "Geen overbrengingsdatum".to_string(),
+                })
+            } else {
+                None
+            };
+            if let Some(reden) = reden {
+                niet_overgebracht.push(NietOvergebracht {
+                    object_id: stuk.object.id,
+                    titel: stuk.object.title.clone(),
+                    reden,
+                });
+                continue;
+            }
+
+            let inhoud = match bron.lees(&stuk.object).await {
+                Ok(inhoud) => inhoud,
+                Err(e) => {
+                    tracing::warn!("Inhoud van {} niet gelezen: {}", stuk.object.id, e);
+                    niet_overgebracht.push(NietOvergebracht {
+                        object_id: stuk.object.id,
+                        titel: stuk.object.title.clone(),
+                        reden: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            let map = stuk.object.id.to_string();
+            let bestandsnaam = bestandsnaam(&stuk.object);
+            let bestand = BestandGegevens {
+                id: format!("{}-{}", stuk.object.id, 1),
+                naam: bestandsnaam.clone(),
+                grootte: inhoud.len() as u64,
+                sha256: sha256_hex(&inhoud),
+                mime_type: stuk.object.mime_type.clone(),
+                datum: aangemaakt_op,
+            };
+
+            let (object_xml, bestand_xml) = match schema {
+                MetadataSchema::Mdto => (
+                    mdto::informatieobject(&stuk, &archiefvormer, &bestand),
+                    mdto::bestand(&stuk, &bestand),
+                ),
+                MetadataSchema::Topx => (
+                    topx::aggregatie(&stuk, &archiefvormer),
+                    topx::bestand(&stuk, &bestand),
+                ),
+            };
+
+            bestanden.push(PakketBestand {
+                pad: format!("{}/{}.{}", map, map, schema.extensie()),
+                inhoud: object_xml.into_bytes(),
+            });
+            bestanden.push(PakketBestand {
+                pad: format!("{}/{}", map, bestandsnaam),
+                inhoud,
+            });
+            bestanden.push(PakketBestand {
+                pad: format!("{}/{}.{}", map, bestandsnaam, schema.extensie()),
+                inhoud: bestand_xml.into_bytes(),
+            });
+            opgenomen.push(stuk.object.id);
+        }
+
+        if opgenomen.is_empty() {
+            return Err(OverbrengingError::GeenStukken);
+        }
+
+        let mut pakket = Self {
+            id,
+            archiefvormer,
+            peildatum,
+            schema,
+            aangemaakt_op,
+            bestanden,
+            stukken: opgenomen,
+            niet_overgebracht,
+        };
+        pakket.sluit_af();
+        Ok(pakket)
+    }
+
+    /// Lees een eerder geëxporteerd pakket terug (bijv. om het te valideren)
+    pub fn lees_map(map: &Path) -> Result<Self, OverbrengingError> {
+        let mut bestanden = Vec::new();
+        lees_recursief(map, map, &mut bestanden)?;
+
+        let manifest = bestanden
+            .iter()
+            .find(|b| b.pad == MANIFEST)
+            .and_then(|b| std::str::from_utf8(&b.inhoud).ok())
+            .and_then(|xml| crate::xml::parse(xml).ok())
+            .ok_or_else(|| OverbrengingError::Validatie(vec![format!("{} ontbreekt of is ongeldig", MANIFEST)]))?;
+
+        let schema = match manifest.attr("schema") {
+            Some("ToPX") => MetadataSchema::Topx,
+            _ => MetadataSchema::Mdto,
+        };
+        let id = manifest.attr("pakket").map(str::to_string).unwrap_or_else(|| {
+            map.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()
+        });
+        let stukken = manifest
+            .children_named("bestand")
+            .filter_map(|b| b.attr("object").and_then(|o| o.parse().ok()))
+            .collect::<HashSet<Uuid>>()
+            .into_iter()
+            .collect();
+
+        Ok(Self {
+            id,
+            archiefvormer: Archiefvormer::new(
+                manifest.attr("archiefvormer").unwrap_or_default(),
+                manifest.attr("archiefvormerId").unwrap_or_default(),
+            ),
+            peildatum: manifest
+                .attr("peildatum")
+                .and_then(|d| d.parse().ok())
+                .unwrap_or_default(),
+            schema,
+            aangemaakt_op: manifest
+                .attr("aangemaakt")
+                .and_then(|d| d.parse().ok())
+                .unwrap_or_default(),
+            bestanden,
+            stukken,
+            niet_overgebracht: Vec::new(),
+        })
+    }
+
+    /// Valideer het pakket
+    ///
+    /// Controleert dat manifest en checksums alle bestanden dekken en
+    /// kloppen, dat elk stuk metagegevens heeft met de verplichte elementen,
+    /// en dat de checksum in de bestandsmetagegevens overeenkomt met de inhoud.
+    pub fn valideer(&self) -> Result<(), OverbrengingError> {
+        let mut fouten = Vec::new();
+        let per_pad: HashMap<&str, &PakketBestand> =
+            self.bestanden.iter().map(|b| (b.pad.as_str(), b)).collect();
+
+        if per_pad.len() != self.bestanden.len() {
+            fouten.push("Pakket bevat dubbele paden".to_string());
+        }
+        for bestand in &self.bestanden {
+            if !is_veilig_pad(&bestand.pad) {
+                fouten.push(format!("Ongeldig pad: {}", bestand.pad));
+            }
+        }
+
+        // Manifest: elk bestand (behalve manifest en checksums) met juiste grootte en hash
+        match per_pad.get(MANIFEST).map(|b| std::str::from_utf8(&b.inhoud)) {
+            Some(Ok(xml)) => match crate::xml::parse(xml) {
+                Ok(manifest) => {
+                    let mut vermeld = HashSet::new();
+                    for regel in manifest.children_named("bestand") {
+                        let pad = regel.attr("pad").unwrap_or_default();
+                        vermeld.insert(pad);
+                        match per_pad.get(pad) {
+                            None => fouten.push(format!("Manifest noemt ontbrekend bestand {}", pad)),
+                            Some(b) => {
+                                if regel.attr("sha256") != Some(b.sha256().as_str()) {
+                                    fouten.push(format!("Checksum van {} klopt niet met manifest", pad));
+                                }
+                                if regel.attr("grootte") != Some(b.inhoud.len().to_string().as_str()) {
+                                    fouten.push(format!("Grootte van {} klopt niet met manifest", pad));
+                                }
+                            }
+                        }
+                    }
+                    for pad in per_pad.keys().filter(|p| **p != MANIFEST && **p != CHECKSUMS) {
+                        if !vermeld.contains(pad) {
+                            fouten.push(format!("{} staat niet in het manifest", pad));
+                        }
+                    }
+                }
+                Err(e) => fouten.push(format!("{} is geen geldige XML: {}", MANIFEST, e)),
+            },
+            _ => fouten.push(format!("{} ontbreekt", MANIFEST)),
+        }
+
+        // Checksums: sha256sum-formaat over alle bestanden behalve zichzelf
+        match per_pad.get(CHECKSUMS).map(|b| String::from_utf8_lossy(&b.inhoud)) {
+            Some(inhoud) => {
+                let mut gedekt = HashSet::new();
+                for regel in inhoud.lines().filter(|r| !r.trim().is_empty()) {
+                    let Some((hash, pad)) = regel.split_once("  ") else {
+                        fouten.push(format!("Ongeldige regel in {}: {}", CHECKSUMS, regel));
+                        continue;
+                    };
+                    gedekt.insert(pad);
+                    match per_pad.get(pad) {
+                        Some(b) if b.sha256() == hash => {}
+                        Some(_) => fouten.push(format!("Checksum van {} klopt niet", pad)),
+                        None => fouten.push(format!("{} noemt ontbrekend bestand {}", CHECKSUMS, pad)),
+                    }
+                }
+                for pad in per_pad.keys().filter(|p| **p != CHECKSUMS) {
+                    if !gedekt.contains(pad) {
+                        fouten.push(format!("{} ontbreekt in {}", pad, CHECKSUMS));
+                    }
+                }
+            }
+            None => fouten.push(format!("{} ontbreekt", CHECKSUMS)),
+        }
+
+        // Metagegevens per stuk en per bestand
+        let extensie = format!(".{}", self.schema.extensie());
+        for bestand in &self.bestanden {
+            let Some((map, naam)) = bestand.pad.split_once('/') else {
+                continue;
+            };
+            if naam.ends_with(&extensie) {
+                continue;
+            }
+            let object_meta = format!("{}/{}{}", map, map, extensie);
+            let bestand_meta = format!("{}{}", bestand.pad, extensie);
+            match per_pad.get(object_meta.as_str()) {
+                Some(meta) => fouten.extend(self.controleer_metagegevens(meta, None)),
+                None => fouten.push(format!("Metagegevens {} ontbreken", object_meta)),
+            }
+            match per_pad.get(bestand_meta.as_str()) {
+                Some(meta) => fouten.extend(self.controleer_metagegevens(meta, Some(bestand))),
+                None => fouten.push(format!("Metagegevens {} ontbreken", bestand_meta)),
+            }
+        }
+
+        if self.stukken.is_empty() {
+            fouten.push("Pakket bevat geen stukken".to_string());
+        }
+
+        if fouten.is_empty() {
+            Ok(())
+        } else {
+            fouten.sort();
+            fouten.dedup();
+            Err(OverbrengingError::Validatie(fouten))
+        }
+    }
+
+    /// Valideer het pakket en schrijf het weg in `doelmap/<id>`
+    ///
+    /// Geeft het pad van de pakketmap terug. Een bestaande map met dezelfde
+    /// naam wordt niet overschreven.
+    pub fn exporteer(&self, doelmap: &Path) -> Result<PathBuf, OverbrengingError> {
+        self.valideer()?;
+
+        let map = doelmap.join(&self.id);
+        if map.exists() {
+            return Err(OverbrengingError::Io(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("{} bestaat al", map.display()),
+            )));
+        }
+        for bestand in &self.bestanden {
+            let pad = map.join(&bestand.pad);
+            if let Some(ouder) = pad.parent() {
+                std::fs::create_dir_all(ouder)?;
+            }
+            std::fs::write(pad, &bestand.inhoud)?;
+        }
+        tracing::info!(
+            "Overdrachtspakket {} geëxporteerd: {} stukken, {} bestanden",
+            self.id,
+            self.stukken.len(),
+            self.bestanden.len()
+        );
+        Ok(map)
+    }
+
+    /// Voeg manifest en checksums toe
+    fn sluit_af(&mut self) {
+        let mut manifest = String::new();
+        manifest.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            manifest,
+            "<manifest xmlns=\"{}\" pakket=\"{}\" schema=\"{}\" archiefvormer=\"{}\" archiefvormerId=\"{}\" peildatum=\"{}\" aangemaakt=\"{}\">",
+            MANIFEST_NS,
+            escape(&self.id),
+            self.schema.naam(),
+            escape(&self.archiefvormer.naam),
+            escape(&self.archiefvormer.identificatie),
+            self.peildatum,
+            self.aangemaakt_op.to_rfc3339(),
+        );
+        let extensie = format!(".{}", self.schema.extensie());
+        for bestand in &self.bestanden {
+            let soort = if bestand.pad.ends_with(&extensie) { "metagegevens" } else { "inhoud" };
+            let object = bestand.pad.split('/').next().unwrap_or_default();
+            let _ = writeln!(
+                manifest,
+                "  <bestand pad=\"{}\" object=\"{}\" soort=\"{}\" grootte=\"{}\" sha256=\"{}\"/>",
+                escape(&bestand.pad),
+                escape(object),
+                soort,
+                bestand.inhoud.len(),
+                bestand.sha256()
+            );
+        }
+        manifest.push_str("</manifest>\n");
+        self.bestanden.push(PakketBestand {
+            pad: MANIFEST.to_string(),
+            inhoud: manifest.into_bytes(),
+        });
+
+        let mut checksums = String::new();
+        for bestand in &self.bestanden {
+            let _ = writeln!(checksums, "{}  {}", bestand.sha256(), bestand.pad);
+        }
+        self.bestanden.push(PakketBestand {
+            pad: CHECKSUMS.to_string(),
+            inhoud: checksums.into_bytes(),
+        });
+    }
+
+    fn controleer_metagegevens(
+        &self,
+        meta: &PakketBestand,
+        inhoud: Option<&PakketBestand>,
+    ) -> Vec<String> {
+        let xml = match std::str::from_utf8(&meta.inhoud).map_err(|e| e.to_string()).and_then(crate::xml::parse) {
+            Ok(xml) => xml,
+            Err(e) => return vec![format!("{} is geen geldige XML: {}", meta.pad, e)],
+        };
+        let (wortel, object, verplicht, checksum): (_, _, &[&str], _) = match (self.schema, inhoud) {
+            (MetadataSchema::Mdto, None) => (
+                "MDTO",
+                "informatieobject",
+                &["identificatie", "naam", "waardering", "archiefvormer"],
+                None,
+            ),
+            (MetadataSchema::Mdto, Some(_)) => (
+                "MDTO",
+                "bestand",
+                &["identificatie", "naam", "omvang", "checksum", "isRepresentatieVan"],
+                xml.child("bestand")
+                    .and_then(|b| b.child("checksum"))
+                    .and_then(|c| c.child_text("checksumWaarde")),
+            ),
+            (MetadataSchema::Topx, None) => (
+                "ToPX",
+                "aggregatie",
+                &["identificatiekenmerk", "aggregatieniveau", "naam", "context"],
+                None,
+            ),
+            (MetadataSchema::Topx, Some(_)) => (
+                "ToPX",
+                "bestand",
+                &["identificatiekenmerk", "naam", "formaat"],
+                xml.child("bestand")
+                    .and_then(|b| b.child("formaat"))
+                    .and_then(|f| f.child("fysiekeIntegriteit"))
+                    .and_then(|i| i.child_text("waarde")),
+            ),
+        };
+
+        if xml.name != wortel {
+            return vec![format!("{}: verwacht <{}>, gevonden <{}>", meta.pad, wortel, xml.name)];
+        }
+        let Some(element) = xml.child(object) else {
+            return vec![format!("{}: <{}> ontbreekt", meta.pad, object)];
+        };
+        let mut fouten: Vec<String> = verplicht
+            .iter()
+            .filter(|naam| element.child(naam).is_none())
+            .map(|naam| format!("{}: verplicht element <{}> ontbreekt", meta.pad, naam))
+            .collect();
+        if let Some(inhoud) = inhoud
+            && checksum != Some(inhoud.sha256().as_str())
+        {
+            fouten.push(format!("{}: checksum komt niet overeen met {}", meta.pad, inhoud.pad));
+        }
+        fouten
+    }
+}
+
+/// Gegevens over het inhoudsbestand voor de metagegevens
+pub(crate) struct BestandGegevens {
+    pub id: String,
+    pub naam: String,
+    pub grootte: u64,
+    pub sha256: String,
+    pub mime_type: Option<String>,
+    pub datum: DateTime<Utc>,
+}
+
+/// Openbaarheid volgens de Woo-metagegevens: label en nadere beschrijving
+fn openbaarheid(stuk: &OverTeBrengenStuk) -> Option<(&'static str, String)> {
+    let woo = stuk.woo.as_ref()?;
+    let label = match woo.disclosure_class? {
+        WooDisclosureClass::Openbaar => "Openbaar",
+        WooDisclosureClass::GedeeltelijkOpenbaar => "Beperkt openbaar",
+        WooDisclosureClass::NietOpenbaar => "Niet openbaar",
+        WooDisclosureClass::NogNietBeoordeeld => "Openbaarheid niet beoordeeld",
+    };
+    let mut beschrijving: Vec<String> = woo
+        .refusal_grounds
+        .iter()
+        .filter_map(|g| g.artikel())
+        .map(|artikel| format!("Woo art. {}", artikel))
+        .collect();
+    beschrijving.extend(woo.explanation.clone());
+    Some((label, beschrijving.join("; ")))
+}
+
+fn sha256_hex(inhoud: &[u8]) -> String {
+    hex::encode(Sha256::digest(inhoud))
+}
+
+/// Bestandsnaam uit de opslaglocatie, of `<id>.<extensie>` als die onbruikbaar is
+fn bestandsnaam(object: &InformationObject) -> String {
+    let naam: String = object
+        .content_location
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .collect();
+    let naam = naam.trim_start_matches('.');
+    if naam.contains('.') && !naam.is_empty() {
+        return naam.to_string();
+    }
+    let extensie = match object.mime_type.as_deref() {
+        Some("application/pdf") => "pdf",
+        Some("application/xml" | "text/xml") => "xml",
+        Some("text/plain") => "txt",
+        Some("application/vnd.openxmlformats-officedocument.wordprocessingml.document") => "docx",
+        Some("application/vnd.oasis.opendocument.text") => "odt",
+        Some("message/rfc822") => "eml",
+        _ => "bin",
+    };
+    format!("{}.{}", object.id, extensie)
+}
+
+/// Alleen relatieve paden binnen het pakket
+fn is_veilig_pad(pad: &str) -> bool {
+    !pad.is_empty()
+        && Path::new(pad)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+}
+
+fn lees_recursief(
+    root: &Path,
+    map: &Path,
+    bestanden: &mut Vec<PakketBestand>,
+) -> Result<(), OverbrengingError> {
+    let mut entries = std::fs::read_dir(map)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|e| e.path());
+    for entry in entries {
+        let pad = entry.path();
+        if pad.is_dir() {
+            lees_recursief(root, &pad, bestanden)?;
+        } else {
+            let relatief = pad
+                .strip_prefix(root)
+                .unwrap_or(&pad)
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            bestanden.push(PakketBestand {
+                pad: relatief,
+                inhoud: std::fs::read(&pad)?,
+            });
+        }
+    }
+    Ok(())
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provisa::Hotspot;
+    use iou_core::compliance::WooRefusalGround;
+    use iou_core::objects::ObjectType;
+
+    fn datum(jaar: i32, maand: u32, dag: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(jaar, maand, dag).unwrap()
+    }
+
+    struct VasteInhoud(HashMap<String, Vec<u8>>);
+
+    #[async_trait]
+    impl InhoudBron for VasteInhoud {
+        async fn lees(&self, object: &InformationObject) -> Result<Vec<u8>, OverbrengingError> {
+            self.0
+                .get(&object.content_location)
+                .cloned()
+                .ok_or_else(|| OverbrengingError::Inhoud {
+                    object_id: object.id,
+                    melding: "niet gevonden".to_string(),
+                })
+        }
+    }
+
+    fn object(titel: &str, locatie: &str) -> InformationObject {
+        let mut object = InformationObject::new(
+            Uuid::new_v4(),
+            ObjectType::Besluit,
+            titel.to_string(),
+            locatie.to_string(),
+            Uuid::new_v4(),
+        );
+        object.mime_type = Some("application/pdf".to_string());
+        object.description = Some("Besluit van Gedeputeerde Staten".to_string());
+        object
+    }
+
+    /// Verordening (permanent, 2000), GS-besluit (permanent, 2015) en contract (tijdelijk)
+    fn stukken() -> Vec<OverTeBrengenStuk> {
+        let lijst = ProvisaSelectielijst::provinciaal_2020();
+        vec![
+            OverTeBrengenStuk::beoordeel(
+                object("Verordening natuurbeheer 2000", "s3://archief/verordening-2000.pdf"),
+                PetraCategorie::Bestuur,
+                BesluitType::Verordening,
+                datum(2000, 5, 1),
+                &lijst,
+                None,
+            )
+            .met_woo(WooMetadata {
+                is_relevant: true,
+                disclosure_class: Some(WooDisclosureClass::GedeeltelijkOpenbaar),
+                publication_date: Some(datum(2000, 6, 1)),
+                refusal_grounds: vec![WooRefusalGround::PersoonlijkeLevenssfeer],
+                explanation: Some("Namen van insprekers gelakt".to_string()),
+            }),
+            OverTeBrengenStuk::beoordeel(
+                object("GS-besluit 2015", "s3://archief/gs-2015.pdf"),
+                PetraCategorie::Bestuur,
+                BesluitType::Besluit,
+                datum(2015, 1, 1),
+                &lijst,
+                None,
+            ),
+            OverTeBrengenStuk::beoordeel(
+                object("Contract schoonmaak", "s3://archief/contract.pdf"),
+                PetraCategorie::Financien,
+                BesluitType::Contract,
+                datum(2000, 1, 1),
+                &lijst,
+                None,
+            ),
+        ]
+    }
+
+    fn bron() -> VasteInhoud {
+        VasteInhoud(HashMap::from([
+            ("s3://archief/verordening-2000.pdf".to_string(), b"%PDF-1.7 verordening".to_vec()),
+            ("s3://archief/gs-2015.pdf".to_string(), b"%PDF-1.7 besluit".to_vec()),
+            ("s3://archief/contract.pdf".to_string(), b"%PDF-1.7 contract".to_vec()),
+        ]))
+    }
+
+    fn flevoland() -> Archiefvormer {
+        Archiefvormer::new(
+            "Provincie Flevoland",
+            "https://identifier.overheid.nl/tooi/id/provincie/pv24",
+        )
+    }
+
+    async fn pakket(schema: MetadataSchema) -> Overdrachtspakket {
+        Overdrachtspakket::samenstellen(stukken(), flevoland(), datum(2024, 1, 1), schema, &bron())
+            .await
+            .unwrap()
+    }
+
+    fn bestand<'a>(pakket: &'a Overdrachtspakket, suffix: &str) -> &'a str {
+        pakket
+            .bestanden
+            .iter()
+            .find(|b| b.pad.ends_with(suffix))
+            .map(|b| std::str::from_utf8(&b.inhoud).unwrap())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_alleen_overdraagbare_stukken_in_pakket() {
+        let pakket = pakket(MetadataSchema::Mdto).await;
+        assert_eq!(pakket.stukken.len(), 1);
+        assert_eq!(pakket.niet_overgebracht.len(), 2);
+        assert!(pakket
+            .niet_overgebracht
+            .iter()
+            .any(|n| n.titel == "GS-besluit 2015" && n.reden == "Overdraagbaar per 2035-01-01"));
+        assert!(pakket
+            .niet_overgebracht
+            .iter()
+            .any(|n| n.titel == "Contract schoonmaak" && n.reden == "Niet blijvend te bewaren"));
+
+        // 3 bestanden per stuk plus manifest en checksums
+        assert_eq!(pakket.bestanden.len(), 5);
+        assert!(pakket.id.starts_with("sip-2024-01-01-"));
+        pakket.valideer().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_mdto_metagegevens() {
+        let pakket = pakket(MetadataSchema::Mdto).await;
+        let object = bestand(&pakket, &format!("{0}/{0}.mdto.xml", pakket.stukken[0]));
+        assert!(object.contains("<naam>Verordening natuurbeheer 2000</naam>"));
+        assert!(object.contains("<begripLabel>Blijvend te bewaren</begripLabel>"));
+        assert!(object.contains("<verwijzingNaam>Provincie Flevoland</verwijzingNaam>"));
+        assert!(object.contains("<begripCode>na-2022-31454164-v</begripCode>"));
+        assert!(object.contains("Woo art. 5.1.2.e"));
+        assert!(object.contains("<verwijzingNaam>verordening-2000.pdf</verwijzingNaam>"));
+
+        let bestand = bestand(&pakket, "verordening-2000.pdf.mdto.xml");
+        assert!(bestand.contains(&format!("<checksumWaarde>{}</checksumWaarde>", sha256_hex(b"%PDF-1.7 verordening"))));
+        assert!(bestand.contains("<omvang>20</omvang>"));
+    }
+
+    #[tokio::test]
+    async fn test_topx_metagegevens() {
+        let pakket = pakket(MetadataSchema::Topx).await;
+        pakket.valideer().unwrap();
+        let aggregatie = bestand(&pakket, &format!("{0}/{0}.topx.xml", pakket.stukken[0]));
+        assert!(aggregatie.contains("<aggregatieniveau>Record</aggregatieniveau>"));
+        assert!(aggregatie.contains("<geautoriseerdeNaam>Provincie Flevoland</geautoriseerdeNaam>"));
+        assert!(bestand(&pakket, "manifest.xml").contains("schema=\"ToPX\""));
+    }
+
+    #[tokio::test]
+    async fn test_validatie_vindt_gewijzigde_inhoud() {
+        let mut pakket = pakket(MetadataSchema::Mdto).await;
+        let pdf = pakket
+            .bestanden
+            .iter_mut()
+            .find(|b| b.pad.ends_with(".pdf"))
+            .unwrap();
+        pdf.inhoud = b"gewijzigd".to_vec();
+
+        let Err(OverbrengingError::Validatie(fouten)) = pakket.valideer() else {
+            panic!("validatie had moeten falen");
+        };
+        assert!(fouten.iter().any(|f| f.contains("klopt niet met manifest")));
+        assert!(fouten.iter().any(|f| f.contains("checksum komt niet overeen")));
+        assert!(pakket.exporteer(&std::env::temp_dir()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validatie_vereist_metagegevens_en_manifest() {
+        let mut pakket = pakket(MetadataSchema::Mdto).await;
+        pakket.bestanden.retain(|b| !b.pad.ends_with(".pdf.mdto.xml") && b.pad != MANIFEST);
+        pakket.bestanden.push(PakketBestand {
+            pad: "../buiten.txt".to_string(),
+            inhoud: vec![],
+        });
+        let Err(OverbrengingError::Validatie(fouten)) = pakket.valideer() else {
+            panic!("validatie had moeten falen");
+        };
+        assert!(fouten.iter().any(|f| f == "manifest.xml ontbreekt"));
+        assert!(fouten.iter().any(|f| f.ends_with(".pdf.mdto.xml ontbreken")));
+        assert!(fouten.iter().any(|f| f == "Ongeldig pad: ../buiten.txt"));
+    }
+
+    #[tokio::test]
+    async fn test_hotspot_en_ontbrekende_inhoud() {
+        let lijst = ProvisaSelectielijst::provinciaal_2020();
+        let mut register = HotspotRegister::new("Flevoland");
+        register.voeg_toe(
+            Hotspot::new("HS-01", "Aanbesteding", "Parlementaire enquête", datum(1999, 1, 1))
+                .met_categorieen(vec![PetraCategorie::Financien]),
+        );
+        let contract = OverTeBrengenStuk::beoordeel(
+            object("Contract schoonmaak", "s3://archief/contract.pdf"),
+            PetraCategorie::Financien,
+            BesluitType::Contract,
+            datum(2000, 1, 1),
+            &lijst,
+            Some(&register),
+        );
+        let zoek = OverTeBrengenStuk::beoordeel(
+            object("Verordening zoek", "s3://archief/zoek.pdf"),
+            PetraCategorie::Bestuur,
+            BesluitType::Verordening,
+            datum(2000, 1, 1),
+            &lijst,
+            None,
+        );
+
+        let pakket = Overdrachtspakket::samenstellen(
+            vec![contract, zoek],
+            flevoland(),
+            datum(2024, 1, 1),
+            MetadataSchema::Mdto,
+            &bron(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(pakket.stukken.len(), 1);
+        assert!(pakket.niet_overgebracht[0].reden.contains("niet gevonden"));
+
+        let leeg = Overdrachtspakket::samenstellen(
+            stukken().into_iter().skip(1),
+            flevoland(),
+            datum(2024, 1, 1),
+            MetadataSchema::Mdto,
+            &bron(),
+        )
+        .await;
+        assert!(matches!(leeg, Err(OverbrengingError::GeenStukken)));
+    }
+
+    #[tokio::test]
+    async fn test_exporteer_en_lees_terug() {
+        let pakket = pakket(MetadataSchema::Mdto).await;
+        let doel = std::env::temp_dir().join(format!("iou-edepot-{}", Uuid::new_v4()));
+        let map = pakket.exporteer(&doel).unwrap();
+
+        assert!(map.join(MANIFEST).is_file());
+        assert!(map.join(CHECKSUMS).is_file());
+        assert!(pakket.exporteer(&doel).is_err(), "bestaand pakket niet overschrijven");
+
+        let gelezen = Overdrachtspakket::lees_map(&map).unwrap();
+        assert_eq!(gelezen.id, pakket.id);
+        assert_eq!(gelezen.stukken, pakket.stukken);
+        assert_eq!(gelezen.archiefvormer.naam, "Provincie Flevoland");
+        gelezen.valideer().unwrap();
+
+        std::fs::remove_dir_all(&doel).unwrap();
+    }
+
+    #[test]
+    fn test_bestandsnaam() {
+        let mut o = object("x", "s3://bucket/map/Besluit GS (def).pdf");
+        assert_eq!(bestandsnaam(&o), "Besluit_GS__def_.pdf");
+        o.content_location = "s3://bucket/zonder-extensie".to_string();
+        assert_eq!(bestandsnaam(&o), format!("{}.pdf", o.id));
+    }
+}
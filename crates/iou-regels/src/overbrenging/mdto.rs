@@ -0,0 +1,190 @@
+//! MDTO 1.0 metagegevens
+//!
+//! Schrijft één `<informatieobject>` per stuk en één `<bestand>` per
+//! representatie, elk in een eigen `<MDTO>` document zoals het e-Depot
+//! van het Nationaal Archief en de regionale archieven ze inlezen.
+
+use std::fmt::Write;
+
+use iou_core::compliance::{ArchivalValue, Classification};
+use quick_xml::escape::escape;
+
+use super::{openbaarheid, Archiefvormer, BestandGegevens, OverTeBrengenStuk, IDENTIFICATIE_BRON};
+
+const MDTO_NS: &str = "https://www.nationaalarchief.nl/mdto";
+const XSI_NS: &str = "http://www.w3.org/2001/XMLSchema-instance";
+const MDTO_XSD: &str = "https://www.nationaalarchief.nl/mdto https://www.nationaalarchief.nl/mdto/MDTO-XML1.0.1.xsd";
+
+/// `<informatieobject>` voor een stuk met één representatie
+pub(super) fn informatieobject(
+    stuk: &OverTeBrengenStuk,
+    archiefvormer: &Archiefvormer,
+    bestand: &BestandGegevens,
+) -> String {
+    let object = &stuk.object;
+    let retentie = stuk.retentie();
+    let mut out = open();
+    out.push_str("  <informatieobject>\n");
+
+    identificatie(&mut out, "identificatie", &object.id.to_string());
+    element(&mut out, "naam", &object.title);
+    begrip(&mut out, "aggregatieniveau", "Archiefstuk", None, "Begrippenlijst Aggregatieniveau MDTO");
+    begrip(
+        &mut out,
+        "classificatie",
+        stuk.categorie.description(),
+        Some(&stuk.categorie.to_string()),
+        "PETRA",
+    );
+    if let Some(omschrijving) = &object.description {
+        element(&mut out, "omschrijving", omschrijving);
+    }
+
+    event(&mut out, "Creatie", &object.created_at.to_rfc3339());
+    if let Some(publicatie) = stuk.woo.as_ref().and_then(|w| w.publication_date) {
+        event(&mut out, "Publicatie", &publicatie.to_string());
+    }
+
+    let (waardering, code) = match retentie.archival_value {
+        ArchivalValue::Permanent => ("Blijvend te bewaren", "B"),
+        ArchivalValue::Tijdelijk => ("Tijdelijk te bewaren", "V"),
+    };
+    begrip(&mut out, "waardering", waardering, Some(code), "Begrippenlijst Waarderingen MDTO");
+    begrip(
+        &mut out,
+        "informatiecategorie",
+        &stuk.besluit_type.to_string(),
+        retentie.selection_list_ref.as_deref(),
+        &format!("Selectielijst provincies (PROVISA {})", stuk.beoordeling.versie),
+    );
+
+    let _ = writeln!(out, "    <heeftRepresentatie>");
+    let _ = writeln!(out, "      <verwijzingNaam>{}</verwijzingNaam>", escape(&bestand.naam));
+    verwijzing_identificatie(&mut out, &bestand.id);
+    out.push_str("    </heeftRepresentatie>\n");
+
+    out.push_str("    <archiefvormer>\n");
+    let _ = writeln!(out, "      <verwijzingNaam>{}</verwijzingNaam>", escape(&archiefvormer.naam));
+    let _ = writeln!(
+        out,
+        "      <verwijzingIdentificatie>\n        <identificatieKenmerk>{}</identificatieKenmerk>\n        <identificatieBron>TOOI</identificatieBron>\n      </verwijzingIdentificatie>",
+        escape(&archiefvormer.identificatie)
+    );
+    out.push_str("    </archiefvormer>\n");
+
+    if let Some((label, beschrijving)) = openbaarheid(stuk) {
+        beperking(&mut out, label, "Openbaarheid Woo", &beschrijving);
+    }
+    match object.classification {
+        Classification::Vertrouwelijk => beperking(&mut out, "Vertrouwelijk", "Classificatie BIO", ""),
+        Classification::Geheim => beperking(&mut out, "Geheim", "Classificatie BIO", ""),
+        Classification::Openbaar | Classification::Intern => {}
+    }
+
+    out.push_str("  </informatieobject>\n</MDTO>\n");
+    out
+}
+
+/// `<bestand>` voor de representatie van een stuk
+pub(super) fn bestand(stuk: &OverTeBrengenStuk, bestand: &BestandGegevens) -> String {
+    let mut out = open();
+    out.push_str("  <bestand>\n");
+
+    identificatie(&mut out, "identificatie", &bestand.id);
+    element(&mut out, "naam", &bestand.naam);
+    element(&mut out, "omvang", &bestand.grootte.to_string());
+    if let Some(mime) = &bestand.mime_type {
+        begrip(&mut out, "bestandsformaat", mime, None, "IANA Media Types");
+    }
+
+    out.push_str("    <checksum>\n");
+    out.push_str(
+        "      <checksumAlgoritme>\n        <begripLabel>SHA-256</begripLabel>\n        <begripBegrippenlijst>\n          <verwijzingNaam>Begrippenlijst ChecksumAlgoritme MDTO</verwijzingNaam>\n        </begripBegrippenlijst>\n      </checksumAlgoritme>\n",
+    );
+    let _ = writeln!(out, "      <checksumWaarde>{}</checksumWaarde>", bestand.sha256);
+    let _ = writeln!(out, "      <checksumDatum>{}</checksumDatum>", bestand.datum.to_rfc3339());
+    out.push_str("    </checksum>\n");
+
+    out.push_str("    <isRepresentatieVan>\n");
+    let _ = writeln!(out, "      <verwijzingNaam>{}</verwijzingNaam>", escape(&stuk.object.title));
+    verwijzing_identificatie(&mut out, &stuk.object.id.to_string());
+    out.push_str("    </isRepresentatieVan>\n");
+
+    out.push_str("  </bestand>\n</MDTO>\n");
+    out
+}
+
+fn open() -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        "<MDTO xmlns=\"{}\" xmlns:xsi=\"{}\" xsi:schemaLocation=\"{}\">",
+        MDTO_NS, XSI_NS, MDTO_XSD
+    );
+    out
+}
+
+fn element(out: &mut String, naam: &str, tekst: &str) {
+    let _ = writeln!(out, "    <{0}>{1}</{0}>", naam, escape(tekst));
+}
+
+fn identificatie(out: &mut String, naam: &str, kenmerk: &str) {
+    let _ = writeln!(
+        out,
+        "    <{0}>\n      <identificatieKenmerk>{1}</identificatieKenmerk>\n      <identificatieBron>{2}</identificatieBron>\n    </{0}>",
+        naam,
+        escape(kenmerk),
+        IDENTIFICATIE_BRON
+    );
+}
+
+fn verwijzing_identificatie(out: &mut String, kenmerk: &str) {
+    let _ = writeln!(
+        out,
+        "      <verwijzingIdentificatie>\n        <identificatieKenmerk>{}</identificatieKenmerk>\n        <identificatieBron>{}</identificatieBron>\n      </verwijzingIdentificatie>",
+        escape(kenmerk),
+        IDENTIFICATIE_BRON
+    );
+}
+
+/// Begrip uit een begrippenlijst (label, optionele code en de lijst)
+fn begrip(out: &mut String, naam: &str, label: &str, code: Option<&str>, lijst: &str) {
+    let _ = writeln!(out, "    <{}>", naam);
+    let _ = writeln!(out, "      <begripLabel>{}</begripLabel>", escape(label));
+    if let Some(code) = code {
+        let _ = writeln!(out, "      <begripCode>{}</begripCode>", escape(code));
+    }
+    let _ = writeln!(
+        out,
+        "      <begripBegrippenlijst>\n        <verwijzingNaam>{}</verwijzingNaam>\n      </begripBegrippenlijst>\n    </{}>",
+        escape(lijst),
+        naam
+    );
+}
+
+fn event(out: &mut String, soort: &str, tijd: &str) {
+    let _ = writeln!(
+        out,
+        "    <event>\n      <eventType>\n        <begripLabel>{}</begripLabel>\n        <begripBegrippenlijst>\n          <verwijzingNaam>Begrippenlijst Events MDTO</verwijzingNaam>\n        </begripBegrippenlijst>\n      </eventType>\n      <eventTijd>{}</eventTijd>\n    </event>",
+        soort,
+        escape(tijd)
+    );
+}
+
+fn beperking(out: &mut String, label: &str, lijst: &str, beschrijving: &str) {
+    out.push_str("    <beperkingGebruik>\n");
+    let _ = writeln!(
+        out,
+        "      <beperkingGebruikType>\n        <begripLabel>{}</begripLabel>\n        <begripBegrippenlijst>\n          <verwijzingNaam>{}</verwijzingNaam>\n        </begripBegrippenlijst>\n      </beperkingGebruikType>",
+        escape(label),
+        escape(lijst)
+    );
+    if !beschrijving.is_empty() {
+        let _ = writeln!(
+            out,
+            "      <beperkingGebruikNadereBeschrijving>{}</beperkingGebruikNadereBeschrijving>",
+            escape(beschrijving)
+        );
+    }
+    out.push_str("    </beperkingGebruik>\n");
+}
@@ -0,0 +1,120 @@
+//! ToPX 2.3 metagegevens (TMLO)
+//!
+//! Voor e-Depots die nog geen MDTO inlezen: een `<aggregatie>` op
+//! recordniveau per stuk en een `<bestand>` per representatie.
+
+use std::fmt::Write;
+
+use iou_core::compliance::Classification;
+use quick_xml::escape::escape;
+
+use super::{openbaarheid, Archiefvormer, BestandGegevens, OverTeBrengenStuk};
+
+const TOPX_NS: &str = "http://www.nationaalarchief.nl/ToPX/v2.3";
+
+/// `<aggregatie>` op recordniveau
+pub(super) fn aggregatie(stuk: &OverTeBrengenStuk, archiefvormer: &Archiefvormer) -> String {
+    let object = &stuk.object;
+    let mut out = open();
+    out.push_str("  <aggregatie>\n");
+
+    element(&mut out, "identificatiekenmerk", &object.id.to_string());
+    element(&mut out, "aggregatieniveau", "Record");
+    element(&mut out, "naam", &object.title);
+    let _ = writeln!(
+        out,
+        "    <classificatie>\n      <code>{}</code>\n      <omschrijving>{}</omschrijving>\n      <bron>PETRA</bron>\n    </classificatie>",
+        escape(stuk.categorie.to_string()),
+        escape(stuk.categorie.description())
+    );
+    if let Some(omschrijving) = &object.description {
+        element(&mut out, "omschrijving", omschrijving);
+    }
+    let _ = writeln!(
+        out,
+        "    <eventGeschiedenis>\n      <datumOfPeriode>\n        <datum>{}</datum>\n      </datumOfPeriode>\n      <type>Creatie</type>\n    </eventGeschiedenis>",
+        object.created_at.date_naive()
+    );
+    let _ = writeln!(
+        out,
+        "    <context>\n      <actor>\n        <identificatiekenmerk>{}</identificatiekenmerk>\n        <aggregatieniveau>Organisatie</aggregatieniveau>\n        <geautoriseerdeNaam>{}</geautoriseerdeNaam>\n      </actor>\n    </context>",
+        escape(&archiefvormer.identificatie),
+        escape(&archiefvormer.naam)
+    );
+    if let Some((label, beschrijving)) = openbaarheid(stuk) {
+        let tekst = if beschrijving.is_empty() {
+            label.to_string()
+        } else {
+            format!("{}: {}", label, beschrijving)
+        };
+        let _ = writeln!(
+            out,
+            "    <openbaarheid>\n      <omschrijvingBeperkingen>{}</omschrijvingBeperkingen>\n    </openbaarheid>",
+            escape(&tekst)
+        );
+    }
+    let niveau = match object.classification {
+        Classification::Openbaar => "Openbaar",
+        Classification::Intern => "Departementaal vertrouwelijk",
+        Classification::Vertrouwelijk => "Vertrouwelijk",
+        Classification::Geheim => "Geheim",
+    };
+    let _ = writeln!(
+        out,
+        "    <vertrouwelijkheid>\n      <classificatieNiveau>{}</classificatieNiveau>\n    </vertrouwelijkheid>",
+        niveau
+    );
+
+    out.push_str("  </aggregatie>\n</ToPX>\n");
+    out
+}
+
+/// `<bestand>` met formaat en fysieke integriteit
+pub(super) fn bestand(stuk: &OverTeBrengenStuk, bestand: &BestandGegevens) -> String {
+    let mut out = open();
+    out.push_str("  <bestand>\n");
+
+    element(&mut out, "identificatiekenmerk", &bestand.id);
+    element(&mut out, "aggregatieniveau", "Bestand");
+    element(&mut out, "naam", &bestand.naam);
+
+    let (naam, extensie) = bestand.naam.rsplit_once('.').unwrap_or((&bestand.naam, ""));
+    out.push_str("    <formaat>\n");
+    let _ = writeln!(out, "      <identificatiekenmerk>{}</identificatiekenmerk>", escape(&bestand.id));
+    let _ = writeln!(
+        out,
+        "      <bestandsnaam>\n        <naam>{}</naam>\n        <extensie>{}</extensie>\n      </bestandsnaam>",
+        escape(naam),
+        escape(extensie)
+    );
+    let _ = writeln!(out, "      <omvang>{}</omvang>", bestand.grootte);
+    if let Some(mime) = &bestand.mime_type {
+        let _ = writeln!(out, "      <bestandsformaat>{}</bestandsformaat>", escape(mime));
+    }
+    let _ = writeln!(
+        out,
+        "      <fysiekeIntegriteit>\n        <algoritme>SHA-256</algoritme>\n        <waarde>{}</waarde>\n        <datumEnTijd>{}</datumEnTijd>\n      </fysiekeIntegriteit>",
+        bestand.sha256,
+        bestand.datum.to_rfc3339()
+    );
+    out.push_str("    </formaat>\n");
+
+    let _ = writeln!(
+        out,
+        "    <relatie>\n      <relatieID>{}</relatieID>\n      <typeRelatie>Is bestand van</typeRelatie>\n    </relatie>",
+        stuk.object.id
+    );
+
+    out.push_str("  </bestand>\n</ToPX>\n");
+    out
+}
+
+fn open() -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(out, "<ToPX xmlns=\"{}\">", TOPX_NS);
+    out
+}
+
+fn element(out: &mut String, naam: &str, tekst: &str) {
+    let _ = writeln!(out, "    <{0}>{1}</{0}>", naam, escape(tekst));
+}
@@ -31,6 +31,16 @@
 //!
 //! Bij maatschappelijk relevante gebeurtenissen kan de waardering wijzigen
 //! van tijdelijk naar permanent.
+//!
+//! # Import
+//!
+//! Selectielijsten en hotspotregisters kunnen uit CSV-, spreadsheet- en
+//! JSON-exports worden ingelezen; zie [`ProvisaSelectielijst::importeer`],
+//! [`SelectielijstImport`] en [`HotspotRegister::importeer`].
+
+mod import;
+
+pub use import::{ImportFormaat, ProvisaImportError, SelectielijstImport};
 
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
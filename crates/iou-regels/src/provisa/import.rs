@@ -0,0 +1,839 @@
+//! Import van selectielijsten en hotspotregisters uit bestanden
+//!
+//! Informatiebeheerders onderhouden de selectielijsttabellen en hotspots in
+//! spreadsheets. Dit module leest die exports in als CSV (komma of
+//! puntkomma), spreadsheet (xlsx, xls, ods; eerste werkblad) of JSON.
+//!
+//! # Selectielijst
+//!
+//! Eén rij per bepaling. Kolomnamen zijn hoofdletterongevoelig; spaties,
+//! `_` en `-` tellen niet mee.
+//!
+//! | Kolom | Verplicht | Voorbeeld |
+//! |-------|-----------|-----------|
+//! | `categorie` | ja | `RUIMTELIJKE-PLANNING`, `RuimtelijkePlanning` |
+//! | `besluittype` | ja | `VERGUNNING` |
+//! | `waardering` | ja | `B`/`bewaren`/`permanent` of `V`/`vernietigen`/`tijdelijk` |
+//! | `termijn` | bij V | `10` of `10 jaar` |
+//! | `referentie` | ja | `1.2.3` of `na-2022-31454164-v` |
+//! | `orgaan` | nee | `provinciaal`, `cdk` |
+//! | `versie` | nee | `2020`, `Provisa 2014` |
+//! | `toelichting` | nee | vrije tekst |
+//!
+//! Ontbrekende `orgaan`/`versie` worden aangevuld met de standaardwaarden van
+//! de aanroeper. JSON mag een array van zulke rijen zijn, of een object met
+//! `versie`, `orgaan`, `naam`, `url` en `bepalingen`.
+//!
+//! # Hotspotregister
+//!
+//! Kolommen `id`, `naam`, `beschrijving`, `startdatum`, `einddatum`,
+//! `categorieen` (gescheiden door `;`, `,` of `|`), `publicatiedatum` en
+//! `url`. Datums als `2024-03-01` of `01-03-2024`. JSON mag een array zijn
+//! of een object met `provincie` en `hotspots`.
+//!
+//! Alle rijen worden gecontroleerd voordat er iets wordt teruggegeven; een
+//! [`ProvisaImportError::Validatie`] bevat alle gevonden fouten met rijnummer.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde_json::{Map, Value};
+
+use super::{
+    Archiefwaarde, BesluitType, Bewaartermijn, Hotspot, HotspotRegister, PetraCategorie,
+    ProvincieOrgaan, ProvisaBepaling, ProvisaSelectielijst, ProvisaVersion,
+};
+
+/// Fouten bij het importeren van selectielijsten en hotspotregisters
+#[derive(Debug, thiserror::Error)]
+pub enum ProvisaImportError {
+    #[error("Bestand lezen mislukt: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Ongeldige CSV: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("Ongeldige spreadsheet: {0}")]
+    Spreadsheet(String),
+
+    #[error("Ongeldige JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Onbekend bestandsformaat: {0}")]
+    OnbekendFormaat(String),
+
+    #[error("Verplichte kolom ontbreekt: {0}")]
+    OntbrekendeKolom(String),
+
+    #[error("Geen bepalingen voor Provisa {versie} ({orgaan})")]
+    GeenBepalingen {
+        versie: ProvisaVersion,
+        orgaan: ProvincieOrgaan,
+    },
+
+    #[error("Validatie mislukt: {}", .0.join("; "))]
+    Validatie(Vec<String>),
+}
+
+/// Bestandsformaat van een export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormaat {
+    Csv,
+    /// xlsx, xlsm, xls of ods; het eerste werkblad wordt gelezen
+    Spreadsheet,
+    Json,
+}
+
+impl ImportFormaat {
+    /// Bepaal het formaat aan de hand van de extensie
+    pub fn uit_pad(pad: &Path) -> Result<Self, ProvisaImportError> {
+        let extensie = pad
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase)
+            .unwrap_or_default();
+
+        match extensie.as_str() {
+            "csv" | "txt" => Ok(Self::Csv),
+            "xlsx" | "xlsm" | "xls" | "ods" => Ok(Self::Spreadsheet),
+            "json" => Ok(Self::Json),
+            _ => Err(ProvisaImportError::OnbekendFormaat(pad.display().to_string())),
+        }
+    }
+}
+
+// ── Selectielijst ────────────────────────────────────────────────────────────
+
+/// Resultaat van een selectielijstimport
+///
+/// Eén export kan bepalingen voor meerdere versies of organen bevatten;
+/// [`SelectielijstImport::selectielijsten`] splitst ze op.
+#[derive(Debug, Clone)]
+pub struct SelectielijstImport {
+    /// Versie uit de export, anders de opgegeven standaard
+    pub versie: ProvisaVersion,
+    /// Orgaan uit de export, anders de opgegeven standaard
+    pub orgaan: ProvincieOrgaan,
+    /// Naam van de selectielijst (alleen uit JSON)
+    pub naam: Option<String>,
+    /// URL naar de officiële publicatie (alleen uit JSON)
+    pub url: Option<String>,
+    /// Alle ingelezen bepalingen, in bestandsvolgorde
+    pub bepalingen: Vec<ProvisaBepaling>,
+}
+
+impl SelectielijstImport {
+    /// Lees een export van schijf; het formaat volgt uit de extensie
+    pub fn lees_bestand(
+        pad: impl AsRef<Path>,
+        versie: ProvisaVersion,
+        orgaan: ProvincieOrgaan,
+    ) -> Result<Self, ProvisaImportError> {
+        let pad = pad.as_ref();
+        let formaat = ImportFormaat::uit_pad(pad)?;
+        Self::lees(&std::fs::read(pad)?, formaat, versie, orgaan)
+    }
+
+    /// Lees een export uit het geheugen
+    ///
+    /// `versie` en `orgaan` gelden voor rijen (en JSON-documenten) die deze
+    /// zelf niet vermelden.
+    pub fn lees(
+        inhoud: &[u8],
+        formaat: ImportFormaat,
+        versie: ProvisaVersion,
+        orgaan: ProvincieOrgaan,
+    ) -> Result<Self, ProvisaImportError> {
+        let tabel = lees_tabel(inhoud, formaat, "bepalingen")?;
+
+        let tekst = |sleutel: &str| tabel.kop.get(sleutel).and_then(Value::as_str).map(str::to_string);
+        let mut fouten = Vec::new();
+
+        let versie = match tekst("versie") {
+            Some(v) => parse_versie(&v).unwrap_or_else(|e| {
+                fouten.push(format!("kop: {}", e));
+                versie
+            }),
+            None => versie,
+        };
+        let orgaan = match tekst("orgaan") {
+            Some(o) => parse_orgaan(&o).unwrap_or_else(|e| {
+                fouten.push(format!("kop: {}", e));
+                orgaan
+            }),
+            None => orgaan,
+        };
+
+        tabel.vereis_kolommen(&[&["categorie"], BESLUITTYPE, WAARDERING, REFERENTIE])?;
+
+        let mut bepalingen = Vec::new();
+        let mut gezien = HashSet::new();
+        for rij in &tabel.rijen {
+            match bepaling_uit_rij(rij, versie, orgaan) {
+                Ok(bepaling) if !gezien.insert((bepaling.key(), bepaling.versie)) => fouten.push(format!(
+                    "{}: dubbele bepaling voor {} / {}",
+                    rij.plaats, bepaling.categorie, bepaling.besluit_type
+                )),
+                Ok(bepaling) => bepalingen.push(bepaling),
+                Err(e) => fouten.push(format!("{}: {}", rij.plaats, e)),
+            }
+        }
+
+        if !fouten.is_empty() {
+            return Err(ProvisaImportError::Validatie(fouten));
+        }
+
+        Ok(Self {
+            versie,
+            orgaan,
+            naam: tekst("naam"),
+            url: tekst("url"),
+            bepalingen,
+        })
+    }
+
+    /// De selectielijst voor [`Self::versie`] en [`Self::orgaan`]
+    ///
+    /// Bepalingen voor andere versies of organen worden overgeslagen.
+    pub fn selectielijst(&self) -> ProvisaSelectielijst {
+        self.selectielijst_voor(self.versie, self.orgaan)
+    }
+
+    /// Eén selectielijst per combinatie van versie en orgaan in de export
+    pub fn selectielijsten(&self) -> Vec<ProvisaSelectielijst> {
+        let mut combinaties = Vec::new();
+        for bepaling in &self.bepalingen {
+            let combinatie = (bepaling.versie, bepaling.orgaan);
+            if !combinaties.contains(&combinatie) {
+                combinaties.push(combinatie);
+            }
+        }
+        combinaties
+            .into_iter()
+            .map(|(versie, orgaan)| self.selectielijst_voor(versie, orgaan))
+            .collect()
+    }
+
+    fn selectielijst_voor(&self, versie: ProvisaVersion, orgaan: ProvincieOrgaan) -> ProvisaSelectielijst {
+        let mut lijst = ProvisaSelectielijst::new(versie, orgaan);
+        if (versie, orgaan) == (self.versie, self.orgaan) {
+            if let Some(naam) = &self.naam {
+                lijst.naam = naam.clone();
+            }
+            if self.url.is_some() {
+                lijst.url = self.url.clone();
+            }
+        }
+        for bepaling in self
+            .bepalingen
+            .iter()
+            .filter(|b| b.versie == versie && b.orgaan == orgaan)
+        {
+            lijst.voeg_bepaling_toe(bepaling.categorie, bepaling.besluit_type, bepaling.bewaartermijn.clone());
+        }
+        lijst
+    }
+}
+
+impl ProvisaSelectielijst {
+    /// Importeer een selectielijst uit een CSV-, spreadsheet- of JSON-export
+    ///
+    /// Vervangt de ingebouwde lijsten ([`Self::provinciaal_2020`],
+    /// [`Self::cdk_2020`]) door de actuele tabel van de informatiebeheerders.
+    pub fn importeer(
+        pad: impl AsRef<Path>,
+        versie: ProvisaVersion,
+        orgaan: ProvincieOrgaan,
+    ) -> Result<Self, ProvisaImportError> {
+        let import = SelectielijstImport::lees_bestand(pad, versie, orgaan)?;
+        let lijst = import.selectielijst();
+        if lijst.bepalingen.is_empty() {
+            return Err(ProvisaImportError::GeenBepalingen {
+                versie: import.versie,
+                orgaan: import.orgaan,
+            });
+        }
+        Ok(lijst)
+    }
+}
+
+const BESLUITTYPE: &[&str] = &["besluittype", "type"];
+const WAARDERING: &[&str] = &["waardering", "waarde", "archiefwaarde"];
+const TERMIJN: &[&str] = &["termijn", "bewaartermijn", "jaren"];
+const REFERENTIE: &[&str] = &["referentie", "ref", "selectielijstref", "categorienummer"];
+
+fn bepaling_uit_rij(
+    rij: &Rij,
+    versie: ProvisaVersion,
+    orgaan: ProvincieOrgaan,
+) -> Result<ProvisaBepaling, String> {
+    let categorie = parse_categorie(rij.verplicht(&["categorie"])?)?;
+    let besluit_type = parse_enum::<BesluitType>(rij.verplicht(BESLUITTYPE)?, "besluittype")?;
+    let referentie = rij.verplicht(REFERENTIE)?;
+
+    let bewaartermijn = match parse_waardering(rij.verplicht(WAARDERING)?)? {
+        Archiefwaarde::Permanent => Bewaartermijn::permanent(referentie),
+        Archiefwaarde::Tijdelijk => {
+            let termijn = rij
+                .veld(TERMIJN)
+                .ok_or("termijn is verplicht bij waardering V (vernietigen)")?;
+            Bewaartermijn::tijdelijk(parse_termijn(termijn)?, referentie)
+        }
+    };
+
+    let orgaan = rij.veld(&["orgaan"]).map(parse_orgaan).transpose()?.unwrap_or(orgaan);
+    let versie = rij.veld(&["versie"]).map(parse_versie).transpose()?.unwrap_or(versie);
+
+    let mut bepaling = ProvisaBepaling::new(categorie, besluit_type, orgaan, bewaartermijn).met_versie(versie);
+    if let Some(toelichting) = rij.veld(&["toelichting", "opmerking"]) {
+        bepaling = bepaling.met_toelichting(toelichting);
+    }
+    Ok(bepaling)
+}
+
+fn parse_waardering(waarde: &str) -> Result<Archiefwaarde, String> {
+    match waarde.trim().to_lowercase().as_str() {
+        "b" | "bewaren" | "permanent" | "blijvend" | "blijvend te bewaren" => Ok(Archiefwaarde::Permanent),
+        "v" | "vernietigen" | "tijdelijk" | "te vernietigen" => Ok(Archiefwaarde::Tijdelijk),
+        _ => Err(format!("onbekende waardering '{}' (verwacht B of V)", waarde)),
+    }
+}
+
+/// `10`, `10 jaar` of `10.0` (spreadsheetgetal)
+fn parse_termijn(termijn: &str) -> Result<u32, String> {
+    let getal = termijn
+        .trim()
+        .trim_end_matches(|c: char| c.is_alphabetic() || c.is_whitespace());
+    let getal = getal.strip_suffix(".0").unwrap_or(getal);
+    match getal.parse::<u32>() {
+        Ok(jaren) if jaren > 0 => Ok(jaren),
+        _ => Err(format!("ongeldige termijn '{}' (verwacht een aantal jaren)", termijn)),
+    }
+}
+
+fn parse_versie(versie: &str) -> Result<ProvisaVersion, String> {
+    let jaar: String = versie
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(char::is_ascii_digit)
+        .collect();
+    ProvisaVersion::from_str(&jaar).map_err(|_| format!("onbekende Provisa-versie '{}'", versie))
+}
+
+fn parse_orgaan(orgaan: &str) -> Result<ProvincieOrgaan, String> {
+    match kolomnaam(orgaan).as_str() {
+        "provincialeorganen" | "provinciaal" | "provincie" | "psgs" => Ok(ProvincieOrgaan::ProvincialeOrganen),
+        "commissarisvandekoning" | "cdk" | "rijksorgaan" => Ok(ProvincieOrgaan::CommissarisVanDeKoning),
+        _ => Err(format!("onbekend orgaan '{}'", orgaan)),
+    }
+}
+
+fn parse_categorie(categorie: &str) -> Result<PetraCategorie, String> {
+    parse_enum::<PetraCategorie>(categorie, "PETRA-categorie")
+}
+
+/// Strum-waarden (`RUIMTELIJKE-PLANNING`) maar ook `ruimtelijke_planning`,
+/// `Ruimtelijke planning` en `RuimtelijkePlanning`
+fn parse_enum<T: FromStr>(waarde: &str, soort: &str) -> Result<T, String> {
+    let waarde = waarde.trim();
+    let woorden: Vec<String> = if waarde.contains(['-', '_', ' ']) {
+        waarde
+            .split(['-', '_', ' '])
+            .filter(|w| !w.is_empty())
+            .map(str::to_uppercase)
+            .collect()
+    } else {
+        // CamelCase → losse woorden
+        let mut woorden: Vec<String> = Vec::new();
+        for c in waarde.chars() {
+            match woorden.last_mut() {
+                Some(woord) if !c.is_uppercase() => woord.push(c),
+                _ => woorden.push(c.to_string()),
+            }
+        }
+        woorden.iter().map(|w| w.to_uppercase()).collect()
+    };
+
+    // A fully capitalised single word ("BESTUUR") splits into letters above
+    let kandidaten = [woorden.join("-"), waarde.to_uppercase()];
+    kandidaten
+        .iter()
+        .find_map(|k| T::from_str(k).ok())
+        .ok_or_else(|| format!("onbekende {} '{}'", soort, waarde))
+}
+
+// ── Hotspotregister ──────────────────────────────────────────────────────────
+
+impl HotspotRegister {
+    /// Importeer een hotspotregister uit een CSV-, spreadsheet- of JSON-export
+    ///
+    /// `provincie` geldt als de export die zelf niet vermeldt.
+    pub fn importeer(pad: impl AsRef<Path>, provincie: &str) -> Result<Self, ProvisaImportError> {
+        let pad = pad.as_ref();
+        let formaat = ImportFormaat::uit_pad(pad)?;
+        Self::lees(&std::fs::read(pad)?, formaat, provincie)
+    }
+
+    /// Lees een hotspotregister uit het geheugen
+    pub fn lees(inhoud: &[u8], formaat: ImportFormaat, provincie: &str) -> Result<Self, ProvisaImportError> {
+        let tabel = lees_tabel(inhoud, formaat, "hotspots")?;
+        tabel.vereis_kolommen(&[&["id"], &["naam"], STARTDATUM, CATEGORIEEN])?;
+
+        let provincie = tabel
+            .kop
+            .get("provincie")
+            .and_then(Value::as_str)
+            .unwrap_or(provincie);
+        let mut register = HotspotRegister::new(provincie);
+
+        let mut fouten = Vec::new();
+        for rij in &tabel.rijen {
+            match hotspot_uit_rij(rij) {
+                Ok(hotspot) => register.voeg_toe(hotspot),
+                Err(e) => fouten.push(format!("{}: {}", rij.plaats, e)),
+            }
+        }
+        if !fouten.is_empty() {
+            return Err(ProvisaImportError::Validatie(fouten));
+        }
+
+        register.valideer()?;
+        Ok(register)
+    }
+
+    /// Controleer het register op dubbele id's, hotspots zonder categorie en
+    /// einddatums vóór de startdatum
+    pub fn valideer(&self) -> Result<(), ProvisaImportError> {
+        let mut fouten = Vec::new();
+        let mut ids = HashSet::new();
+
+        for hotspot in &self.hotspots {
+            if !ids.insert(hotspot.id.as_str()) {
+                fouten.push(format!("hotspot '{}': dubbele id", hotspot.id));
+            }
+            if hotspot.categorieen.is_empty() {
+                fouten.push(format!("hotspot '{}': geen PETRA-categorieën", hotspot.id));
+            }
+            if hotspot.eind_datum.is_some_and(|eind| eind < hotspot.start_datum) {
+                fouten.push(format!("hotspot '{}': einddatum ligt vóór de startdatum", hotspot.id));
+            }
+        }
+
+        if fouten.is_empty() {
+            Ok(())
+        } else {
+            Err(ProvisaImportError::Validatie(fouten))
+        }
+    }
+}
+
+const STARTDATUM: &[&str] = &["startdatum", "start", "vanaf"];
+const CATEGORIEEN: &[&str] = &["categorieen", "categorieën", "categorie", "petracategorieen"];
+
+fn hotspot_uit_rij(rij: &Rij) -> Result<Hotspot, String> {
+    let start = parse_datum(rij.verplicht(STARTDATUM)?)?;
+    let mut hotspot = Hotspot::new(
+        rij.verplicht(&["id"])?,
+        rij.verplicht(&["naam"])?,
+        rij.veld(&["beschrijving", "omschrijving"]).unwrap_or_default(),
+        start,
+    );
+
+    let mut categorieen = Vec::new();
+    for naam in rij.verplicht(CATEGORIEEN)?.split([';', ',', '|']).map(str::trim) {
+        if naam.is_empty() {
+            continue;
+        }
+        let categorie = parse_categorie(naam)?;
+        if !categorieen.contains(&categorie) {
+            categorieen.push(categorie);
+        }
+    }
+    hotspot = hotspot.met_categorieen(categorieen);
+
+    if let Some(eind) = rij.veld(&["einddatum", "eind", "tot"]) {
+        hotspot = hotspot.met_einddatum(parse_datum(eind)?);
+    }
+    if let Some(publicatie) = rij.veld(&["publicatiedatum", "publicatie"]) {
+        let url = rij.veld(&["url", "bekendmaking"]).map(str::to_string);
+        hotspot = hotspot.met_publicatie(parse_datum(publicatie)?, url);
+    } else if let Some(url) = rij.veld(&["url", "bekendmaking"]) {
+        hotspot.url = Some(url.to_string());
+    }
+    Ok(hotspot)
+}
+
+fn parse_datum(datum: &str) -> Result<NaiveDate, String> {
+    let datum = datum.trim();
+    // Spreadsheets export dates with a time component
+    let datum = datum.split(['T', ' ']).next().unwrap_or(datum);
+    ["%Y-%m-%d", "%d-%m-%Y", "%d/%m/%Y"]
+        .iter()
+        .find_map(|formaat| NaiveDate::parse_from_str(datum, formaat).ok())
+        .ok_or_else(|| format!("ongeldige datum '{}' (verwacht JJJJ-MM-DD of DD-MM-JJJJ)", datum))
+}
+
+// ── Tabellen lezen ───────────────────────────────────────────────────────────
+
+/// Ingelezen export: JSON-kopgegevens en de rijen
+struct Tabel {
+    kop: Map<String, Value>,
+    kolommen: HashSet<String>,
+    rijen: Vec<Rij>,
+}
+
+impl Tabel {
+    fn vereis_kolommen(&self, kolommen: &[&[&str]]) -> Result<(), ProvisaImportError> {
+        // JSON rows need not share keys; their fields are checked per row
+        if self.kolommen.is_empty() {
+            return Ok(());
+        }
+        match kolommen
+            .iter()
+            .find(|namen| !namen.iter().any(|n| self.kolommen.contains(&kolomnaam(n))))
+        {
+            Some(namen) => Err(ProvisaImportError::OntbrekendeKolom(namen[0].to_string())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Eén rij uit een export, met genormaliseerde kolomnamen
+struct Rij {
+    /// `rij 3` (spreadsheetnummering, kop = rij 1) of `item 2` (JSON)
+    plaats: String,
+    velden: HashMap<String, String>,
+}
+
+impl Rij {
+    /// Eerste niet-lege waarde onder een van de kolomnamen
+    fn veld(&self, namen: &[&str]) -> Option<&str> {
+        namen
+            .iter()
+            .filter_map(|n| self.velden.get(&kolomnaam(n)))
+            .map(|w| w.trim())
+            .find(|w| !w.is_empty())
+    }
+
+    fn verplicht(&self, namen: &[&str]) -> Result<&str, String> {
+        self.veld(namen).ok_or_else(|| format!("{} ontbreekt", namen[0]))
+    }
+}
+
+/// `Besluit type` → `besluittype`
+fn kolomnaam(naam: &str) -> String {
+    naam.chars()
+        .filter(|c| !matches!(c, ' ' | '_' | '-' | '/' | '.'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn lees_tabel(inhoud: &[u8], formaat: ImportFormaat, sleutel: &str) -> Result<Tabel, ProvisaImportError> {
+    match formaat {
+        ImportFormaat::Csv => lees_csv(inhoud),
+        ImportFormaat::Spreadsheet => lees_spreadsheet(inhoud),
+        ImportFormaat::Json => lees_json(inhoud, sleutel),
+    }
+}
+
+fn lees_csv(inhoud: &[u8]) -> Result<Tabel, ProvisaImportError> {
+    let inhoud = inhoud.strip_prefix("\u{feff}".as_bytes()).unwrap_or(inhoud);
+
+    // Dutch Excel exports use ';' as separator
+    let eerste_regel = inhoud.split(|&b| b == b'\n').next().unwrap_or_default();
+    let aantal = |teken: u8| eerste_regel.iter().filter(|&&b| b == teken).count();
+    let scheidingsteken = if aantal(b';') > aantal(b',') { b';' } else { b',' };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(scheidingsteken)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(inhoud);
+
+    let kop: Vec<String> = reader.headers()?.iter().map(kolomnaam).collect();
+    let mut rijen = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let record = record?;
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+        rijen.push(Rij {
+            plaats: format!("rij {}", index + 2),
+            velden: kop.iter().cloned().zip(record.iter().map(str::to_string)).collect(),
+        });
+    }
+
+    Ok(Tabel {
+        kop: Map::new(),
+        kolommen: kop.into_iter().collect(),
+        rijen,
+    })
+}
+
+#[cfg(feature = "calamine")]
+fn lees_spreadsheet(inhoud: &[u8]) -> Result<Tabel, ProvisaImportError> {
+    use calamine::{Data, DataType, Reader};
+
+    let fout = |e: calamine::Error| ProvisaImportError::Spreadsheet(e.to_string());
+    let mut werkmap = calamine::open_workbook_auto_from_rs(std::io::Cursor::new(inhoud)).map_err(fout)?;
+    let bereik = werkmap
+        .worksheet_range_at(0)
+        .ok_or_else(|| ProvisaImportError::Spreadsheet("geen werkbladen".into()))?
+        .map_err(fout)?;
+
+    let cel = |data: &Data| match data {
+        Data::Empty | Data::Error(_) => String::new(),
+        Data::String(s) | Data::DateTimeIso(s) | Data::DurationIso(s) => s.clone(),
+        Data::Float(f) if f.fract() == 0.0 => (*f as i64).to_string(),
+        Data::DateTime(_) => data.as_date().map(|d| d.to_string()).unwrap_or_default(),
+        other => other.to_string(),
+    };
+
+    // The header is the first non-empty row; the range may not start at A1
+    let eerste_rij = bereik.start().map(|(rij, _)| rij as usize).unwrap_or(0);
+    let mut rijen_iter = bereik
+        .rows()
+        .enumerate()
+        .filter(|(_, cellen)| cellen.iter().any(|c| !c.is_empty()));
+    let Some((_, kopcellen)) = rijen_iter.next() else {
+        return Ok(Tabel { kop: Map::new(), kolommen: HashSet::new(), rijen: Vec::new() });
+    };
+    let kop: Vec<String> = kopcellen.iter().map(|c| kolomnaam(&cel(c))).collect();
+
+    let rijen = rijen_iter
+        .map(|(index, cellen)| Rij {
+            plaats: format!("rij {}", eerste_rij + index + 1),
+            velden: kop.iter().cloned().zip(cellen.iter().map(cel)).collect(),
+        })
+        .collect();
+
+    Ok(Tabel {
+        kop: Map::new(),
+        kolommen: kop.into_iter().filter(|k| !k.is_empty()).collect(),
+        rijen,
+    })
+}
+
+#[cfg(not(feature = "calamine"))]
+fn lees_spreadsheet(_inhoud: &[u8]) -> Result<Tabel, ProvisaImportError> {
+    Err(ProvisaImportError::OnbekendFormaat(
+        "spreadsheets vereisen de 'calamine' feature".into(),
+    ))
+}
+
+fn lees_json(inhoud: &[u8], sleutel: &str) -> Result<Tabel, ProvisaImportError> {
+    let document: Value = serde_json::from_slice(inhoud)?;
+    let (kop, items) = match document {
+        Value::Array(items) => (Map::new(), items),
+        Value::Object(mut kop) => match kop.remove(sleutel) {
+            Some(Value::Array(items)) => (kop, items),
+            _ => return Err(ProvisaImportError::OntbrekendeKolom(sleutel.to_string())),
+        },
+        _ => return Err(ProvisaImportError::OntbrekendeKolom(sleutel.to_string())),
+    };
+
+    let waarde = |v: &Value| match v {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    };
+
+    let mut rijen = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        let plaats = format!("item {}", index + 1);
+        let Value::Object(velden) = item else {
+            return Err(ProvisaImportError::Validatie(vec![format!("{}: object verwacht", plaats)]));
+        };
+        let velden = velden
+            .iter()
+            .map(|(k, v)| {
+                let v = match v {
+                    Value::Array(lijst) => lijst.iter().map(waarde).collect::<Vec<_>>().join(";"),
+                    v => waarde(v),
+                };
+                (kolomnaam(k), v)
+            })
+            .collect();
+        rijen.push(Rij { plaats, velden });
+    }
+
+    Ok(Tabel { kop, kolommen: HashSet::new(), rijen })
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SELECTIELIJST_CSV: &str = "\u{feff}Categorie;Besluit type;Waardering;Termijn;Referentie;Orgaan;Toelichting
+BESTUUR;VERORDENING;B;;1.1;;Provinciale verordeningen
+Financien;Subsidie;V;20 jaar;3.4;;
+ruimtelijke planning;besluit;bewaren;;5.1;;
+Bestuur;Aanstelling;B;;cdk-1;cdk;Burgemeestersbenoemingen
+";
+
+    #[test]
+    fn test_importeer_selectielijst_csv() {
+        let import = SelectielijstImport::lees(
+            SELECTIELIJST_CSV.as_bytes(),
+            ImportFormaat::Csv,
+            ProvisaVersion::V2020,
+            ProvincieOrgaan::ProvincialeOrganen,
+        )
+        .unwrap();
+        assert_eq!(import.bepalingen.len(), 4);
+        assert_eq!(import.bepalingen[0].toelichting.as_deref(), Some("Provinciale verordeningen"));
+
+        let lijst = import.selectielijst();
+        assert_eq!(lijst.bepalingen.len(), 3);
+        let subsidie = lijst
+            .zoek_bewaartermijn(&PetraCategorie::Financien, &BesluitType::Subsidie)
+            .unwrap();
+        assert_eq!(subsidie, &Bewaartermijn::tijdelijk(20, "3.4"));
+        assert_eq!(
+            lijst
+                .zoek_bewaartermijn(&PetraCategorie::RuimtelijkePlanning, &BesluitType::Besluit)
+                .map(|t| t.waarde),
+            Some(Archiefwaarde::Permanent)
+        );
+
+        let lijsten = import.selectielijsten();
+        assert_eq!(lijsten.len(), 2);
+        assert_eq!(lijsten[1].orgaan, ProvincieOrgaan::CommissarisVanDeKoning);
+        assert_eq!(lijsten[1].bepalingen.len(), 1);
+    }
+
+    #[test]
+    fn test_importeer_selectielijst_json_met_versie() {
+        let json = r#"{
+            "versie": "Provisa 2014",
+            "naam": "Selectielijst provinciale organen 2014-2019",
+            "bepalingen": [
+                { "categorie": "Milieu", "besluittype": "Vergunning", "waardering": "V", "termijn": 15, "referentie": "m-1" }
+            ]
+        }"#;
+        let import = SelectielijstImport::lees(
+            json.as_bytes(),
+            ImportFormaat::Json,
+            ProvisaVersion::V2020,
+            ProvincieOrgaan::ProvincialeOrganen,
+        )
+        .unwrap();
+
+        assert_eq!(import.versie, ProvisaVersion::V2014);
+        let lijst = import.selectielijst();
+        assert_eq!(lijst.versie, ProvisaVersion::V2014);
+        assert_eq!(lijst.naam, "Selectielijst provinciale organen 2014-2019");
+        assert_eq!(
+            lijst.zoek_bewaartermijn(&PetraCategorie::Milieu, &BesluitType::Vergunning),
+            Some(&Bewaartermijn::tijdelijk(15, "m-1"))
+        );
+    }
+
+    #[test]
+    fn test_importeer_selectielijst_meldt_alle_fouten() {
+        let csv = "categorie,besluittype,waardering,termijn,referentie
+Bestuur,Verordening,B,,1
+Onbekend,Verordening,B,,2
+Bestuur,Brief,V,,3
+Bestuur,Verordening,B,,4
+";
+        let fout = SelectielijstImport::lees(
+            csv.as_bytes(),
+            ImportFormaat::Csv,
+            ProvisaVersion::V2020,
+            ProvincieOrgaan::ProvincialeOrganen,
+        )
+        .unwrap_err();
+
+        let ProvisaImportError::Validatie(fouten) = fout else {
+            panic!("validatiefout verwacht, kreeg {fout:?}");
+        };
+        assert_eq!(fouten.len(), 3);
+        assert!(fouten[0].starts_with("rij 3: onbekende PETRA-categorie"));
+        assert!(fouten[1].starts_with("rij 4: termijn is verplicht"));
+        assert!(fouten[2].starts_with("rij 5: dubbele bepaling"));
+
+        let zonder_referentie = "categorie,besluittype,waardering\nBestuur,Verordening,B\n";
+        assert!(matches!(
+            SelectielijstImport::lees(
+                zonder_referentie.as_bytes(),
+                ImportFormaat::Csv,
+                ProvisaVersion::V2020,
+                ProvincieOrgaan::ProvincialeOrganen,
+            ),
+            Err(ProvisaImportError::OntbrekendeKolom(k)) if k == "referentie"
+        ));
+    }
+
+    #[test]
+    fn test_importeer_hotspotregister() {
+        let csv = "id,naam,beschrijving,startdatum,einddatum,categorieen,url
+hs-1,Lelystad Airport,Herstructurering,2024-01-01,,VERKEER-VERVOER; Milieu,https://zoek.officielebekendmakingen.nl/stcrt-2024-1
+hs-2,Stikstof,Stikstofaanpak,01-06-2022,31-12-2025,NatuurLandschap|Landbouw,
+";
+        let register = HotspotRegister::lees(csv.as_bytes(), ImportFormaat::Csv, "Flevoland").unwrap();
+        assert_eq!(register.provincie, "Flevoland");
+        assert_eq!(register.hotspots.len(), 2);
+        assert_eq!(
+            register.hotspots[0].categorieen,
+            vec![PetraCategorie::VerkeerVervoer, PetraCategorie::Milieu]
+        );
+        assert!(register.hotspots[0].url.is_some());
+        assert_eq!(register.hotspots[1].eind_datum, NaiveDate::from_ymd_opt(2025, 12, 31));
+
+        let datum = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        assert!(register.upgrade_naar_permanent(&PetraCategorie::Landbouw, datum).is_some());
+
+        let json = r#"{"provincie": "Utrecht", "hotspots": [
+            {"id": "hs-3", "naam": "Crisis", "startdatum": "2020-03-01", "categorieen": ["BRANDWEER-CRISIS", "Gezondheid"]}
+        ]}"#;
+        let register = HotspotRegister::lees(json.as_bytes(), ImportFormaat::Json, "onbekend").unwrap();
+        assert_eq!(register.provincie, "Utrecht");
+        assert_eq!(register.hotspots[0].categorieen.len(), 2);
+    }
+
+    #[test]
+    fn test_hotspotregister_validatie() {
+        let csv = "id;naam;startdatum;einddatum;categorieen
+hs-1;A;2024-01-01;2023-01-01;Bestuur
+hs-1;B;2024-01-01;;Bestuur
+hs-2;C;2024-01-01;;Havens
+";
+        let Err(ProvisaImportError::Validatie(fouten)) =
+            HotspotRegister::lees(csv.as_bytes(), ImportFormaat::Csv, "Zeeland")
+        else {
+            panic!("validatiefout verwacht");
+        };
+        assert_eq!(fouten, vec!["rij 4: onbekende PETRA-categorie 'Havens'".to_string()]);
+
+        let mut register = HotspotRegister::new("Zeeland");
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        register.voeg_toe(
+            Hotspot::new("hs-1", "A", "", start)
+                .met_einddatum(NaiveDate::from_ymd_opt(2023, 1, 1).unwrap())
+                .met_categorieen(vec![PetraCategorie::Bestuur]),
+        );
+        register.voeg_toe(Hotspot::new("hs-1", "B", "", start));
+        let Err(ProvisaImportError::Validatie(fouten)) = register.valideer() else {
+            panic!("validatiefout verwacht");
+        };
+        assert_eq!(fouten.len(), 3);
+    }
+
+    #[test]
+    fn test_parse_enum_varianten() {
+        for invoer in ["RUIMTELIJKE-PLANNING", "ruimtelijke_planning", "Ruimtelijke planning", "RuimtelijkePlanning"] {
+            assert_eq!(parse_categorie(invoer), Ok(PetraCategorie::RuimtelijkePlanning), "{invoer}");
+        }
+        assert_eq!(parse_categorie("BESTUUR"), Ok(PetraCategorie::Bestuur));
+        assert_eq!(parse_enum::<BesluitType>("AgendaNotulen", "besluittype"), Ok(BesluitType::AgendaNotulen));
+        assert_eq!(parse_orgaan("CdK"), Ok(ProvincieOrgaan::CommissarisVanDeKoning));
+        assert_eq!(parse_termijn("10.0"), Ok(10));
+        assert!(parse_termijn("0").is_err());
+        assert_eq!(ImportFormaat::uit_pad(Path::new("lijst.XLSX")).unwrap(), ImportFormaat::Spreadsheet);
+    }
+}
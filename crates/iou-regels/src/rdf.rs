@@ -0,0 +1,533 @@
+//! Lokale RDF-store voor offline gebruik van Open Regels
+//!
+//! Laadt Turtle-, N-Triples- en JSON-LD-dumps van het regelregister in een
+//! in-memory triple store en beantwoordt daar dezelfde SPARQL SELECT- en
+//! CONSTRUCT-queries mee als het live endpoint. Zo werken
+//! [`OpenRegelsTools`](crate::OpenRegelsTools), DMN-discovery en het laden
+//! van BPMN-processen ook zonder netwerk, bijvoorbeeld in CI of in een
+//! afgeschermde omgeving. Gebruik [`OpenRegelsClient::lokaal`](crate::OpenRegelsClient::lokaal).
+//!
+//! De SPARQL-ondersteuning dekt wat de tools gebruiken: basic graph
+//! patterns, `OPTIONAL`, `UNION`, geneste groepen, `FILTER`, `BIND`,
+//! `DISTINCT`, `ORDER BY`, `LIMIT` en `OFFSET`. Property paths,
+//! aggregaten, subqueries en named graphs geven een [`RdfError::Query`].
+
+mod jsonld;
+mod sparql;
+mod turtle;
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::client::{Bindings, SparqlValue};
+
+pub(crate) const RDF_TYPE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+const RDF_FIRST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#first";
+const RDF_REST: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#rest";
+const RDF_NIL: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#nil";
+const XSD: &str = "http://www.w3.org/2001/XMLSchema#";
+const XSD_STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
+const XSD_BOOLEAN: &str = "http://www.w3.org/2001/XMLSchema#boolean";
+const XSD_INTEGER: &str = "http://www.w3.org/2001/XMLSchema#integer";
+const XSD_DECIMAL: &str = "http://www.w3.org/2001/XMLSchema#decimal";
+const XSD_DOUBLE: &str = "http://www.w3.org/2001/XMLSchema#double";
+
+/// Fouten bij het laden of bevragen van de lokale RDF-store
+#[derive(Error, Debug)]
+pub enum RdfError {
+    #[error("Ongeldige Turtle (regel {line}): {message}")]
+    Turtle { line: usize, message: String },
+
+    #[error("Ongeldige JSON-LD: {0}")]
+    JsonLd(String),
+
+    #[error("Ongeldige of niet-ondersteunde SPARQL: {0}")]
+    Query(String),
+
+    #[error("Onbekend bestandsformaat: {0}")]
+    UnsupportedFormat(String),
+
+    #[error("Bestand lezen mislukt: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// RDF-term: IRI, blank node of literal
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Term {
+    Iri(String),
+    BlankNode(String),
+    /// Literal; `datatype` is `None` voor gewone strings (`xsd:string`)
+    /// en strings met een taallabel
+    Literal {
+        value: String,
+        datatype: Option<String>,
+        language: Option<String>,
+    },
+}
+
+impl Term {
+    pub fn iri(iri: impl Into<String>) -> Self {
+        Term::Iri(iri.into())
+    }
+
+    /// Gewone string-literal
+    pub fn literal(value: impl Into<String>) -> Self {
+        Term::Literal { value: value.into(), datatype: None, language: None }
+    }
+
+    /// Literal met datatype; `xsd:string` wordt als gewone string opgeslagen
+    pub fn typed_literal(value: impl Into<String>, datatype: impl Into<String>) -> Self {
+        let datatype = datatype.into();
+        Term::Literal {
+            value: value.into(),
+            datatype: (datatype != XSD_STRING).then_some(datatype),
+            language: None,
+        }
+    }
+
+    /// Literal met taallabel (bijv. `nl`)
+    pub fn lang_literal(value: impl Into<String>, language: impl Into<String>) -> Self {
+        Term::Literal {
+            value: value.into(),
+            datatype: None,
+            language: Some(language.into().to_lowercase()),
+        }
+    }
+
+    /// IRI, blank node label of lexicale waarde
+    pub fn value(&self) -> &str {
+        match self {
+            Term::Iri(v) | Term::BlankNode(v) => v,
+            Term::Literal { value, .. } => value,
+        }
+    }
+
+    pub fn is_literal(&self) -> bool {
+        matches!(self, Term::Literal { .. })
+    }
+
+    /// Term als SPARQL JSON result-waarde
+    pub fn to_sparql_value(&self) -> SparqlValue {
+        let kind = match self {
+            Term::Iri(_) => "uri",
+            Term::BlankNode(_) => "bnode",
+            Term::Literal { .. } => "literal",
+        };
+        SparqlValue {
+            value: self.value().to_string(),
+            kind: kind.to_string(),
+        }
+    }
+}
+
+/// Eén RDF-statement
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Triple {
+    pub subject: Term,
+    pub predicate: Term,
+    pub object: Term,
+}
+
+impl Triple {
+    pub fn new(subject: Term, predicate: Term, object: Term) -> Self {
+        Self { subject, predicate, object }
+    }
+}
+
+/// In-memory triple store met indexen op subject en predicaat
+#[derive(Debug, Clone, Default)]
+pub struct RdfStore {
+    triples: Vec<Triple>,
+    seen: HashSet<Triple>,
+    by_subject: HashMap<Term, Vec<usize>>,
+    by_predicate: HashMap<Term, Vec<usize>>,
+    /// Aantal geladen documenten; houdt blank nodes van verschillende dumps uit elkaar
+    documents: usize,
+}
+
+impl RdfStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Aantal triples in de store
+    pub fn len(&self) -> usize {
+        self.triples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.triples.is_empty()
+    }
+
+    /// Voeg één triple toe; geeft `false` als die er al in stond
+    pub fn insert(&mut self, triple: Triple) -> bool {
+        if self.seen.contains(&triple) {
+            return false;
+        }
+        let index = self.triples.len();
+        self.by_subject.entry(triple.subject.clone()).or_default().push(index);
+        self.by_predicate.entry(triple.predicate.clone()).or_default().push(index);
+        self.seen.insert(triple.clone());
+        self.triples.push(triple);
+        true
+    }
+
+    /// Laad een Turtle- of N-Triples-document; geeft het aantal nieuwe triples terug
+    pub fn load_turtle(&mut self, source: &str) -> Result<usize, RdfError> {
+        let triples = turtle::parse(source)?;
+        Ok(self.insert_document(triples))
+    }
+
+    /// Laad een JSON-LD-document; geeft het aantal nieuwe triples terug
+    ///
+    /// Inline `@context`-objecten worden verwerkt; remote contexts (een URL
+    /// als `@context`) kunnen offline niet worden opgehaald en worden genegeerd.
+    pub fn load_json_ld(&mut self, source: &str) -> Result<usize, RdfError> {
+        let document: serde_json::Value = serde_json::from_str(source)
+            .map_err(|e| RdfError::JsonLd(e.to_string()))?;
+        self.load_json_ld_value(&document)
+    }
+
+    /// Laad een al geparst JSON-LD-document
+    pub fn load_json_ld_value(&mut self, document: &serde_json::Value) -> Result<usize, RdfError> {
+        let triples = jsonld::parse(document)?;
+        Ok(self.insert_document(triples))
+    }
+
+    /// Laad een dump; het formaat volgt uit de extensie
+    /// (`.ttl`, `.turtle`, `.nt`, `.jsonld`, `.json`)
+    pub fn load_file(&mut self, path: impl AsRef<Path>) -> Result<usize, RdfError> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase)
+            .unwrap_or_default();
+
+        match extension.as_str() {
+            "ttl" | "turtle" | "nt" => self.load_turtle(&std::fs::read_to_string(path)?),
+            "jsonld" | "json" => self.load_json_ld(&std::fs::read_to_string(path)?),
+            _ => Err(RdfError::UnsupportedFormat(path.display().to_string())),
+        }
+    }
+
+    /// Laad alle dumps in een map (niet recursief), in alfabetische volgorde
+    ///
+    /// Bestanden met een onbekende extensie worden overgeslagen.
+    pub fn load_directory(&mut self, dir: impl AsRef<Path>) -> Result<usize, RdfError> {
+        let mut paths = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.is_file())
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        let mut loaded = 0;
+        for path in paths {
+            match self.load_file(&path) {
+                Ok(n) => loaded += n,
+                Err(RdfError::UnsupportedFormat(_)) => {
+                    tracing::debug!("Overgeslagen: {}", path.display());
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(loaded)
+    }
+
+    /// Alle triples die op het patroon passen; `None` matcht alles
+    pub fn matching<'a>(
+        &'a self,
+        subject: Option<&'a Term>,
+        predicate: Option<&'a Term>,
+        object: Option<&'a Term>,
+    ) -> impl Iterator<Item = &'a Triple> + 'a {
+        let candidates: Box<dyn Iterator<Item = &'a Triple> + 'a> = match (subject, predicate) {
+            (Some(s), _) => Box::new(self.indexed(self.by_subject.get(s))),
+            (None, Some(p)) => Box::new(self.indexed(self.by_predicate.get(p))),
+            (None, None) => Box::new(self.triples.iter()),
+        };
+        candidates.filter(move |t| {
+            subject.is_none_or(|s| &t.subject == s)
+                && predicate.is_none_or(|p| &t.predicate == p)
+                && object.is_none_or(|o| &t.object == o)
+        })
+    }
+
+    /// Voer een SPARQL SELECT-query uit
+    pub fn select(&self, query: &str) -> Result<Bindings, RdfError> {
+        sparql::select(self, query)
+    }
+
+    /// Voer een SPARQL CONSTRUCT-query uit; geeft expanded JSON-LD terug
+    pub fn construct(&self, query: &str) -> Result<serde_json::Value, RdfError> {
+        sparql::construct(self, query)
+    }
+
+    /// Alle statements over een resource als JSON-LD, inclusief de blank
+    /// nodes waar die naar verwijst
+    ///
+    /// Tegenhanger van content negotiation op de resource-URI.
+    pub fn describe(&self, uri: &str) -> serde_json::Value {
+        let mut triples = Vec::new();
+        let mut pending = vec![Term::iri(uri)];
+        let mut visited = HashSet::new();
+
+        while let Some(subject) = pending.pop() {
+            if !visited.insert(subject.clone()) {
+                continue;
+            }
+            for triple in self.indexed(self.by_subject.get(&subject)) {
+                if let Term::BlankNode(_) = triple.object {
+                    pending.push(triple.object.clone());
+                }
+                triples.push(triple);
+            }
+        }
+
+        jsonld::serialize(&triples)
+    }
+
+    fn indexed<'a>(&'a self, indexes: Option<&'a Vec<usize>>) -> impl Iterator<Item = &'a Triple> + 'a {
+        indexes.into_iter().flatten().map(|&i| &self.triples[i])
+    }
+
+    fn insert_document(&mut self, triples: Vec<Triple>) -> usize {
+        self.documents += 1;
+        let document = self.documents;
+        let scope = |term: Term| match term {
+            Term::BlankNode(label) => Term::BlankNode(format!("d{}_{}", document, label)),
+            other => other,
+        };
+
+        triples
+            .into_iter()
+            .map(|t| Triple::new(scope(t.subject), t.predicate, scope(t.object)))
+            .filter(|t| self.insert(t.clone()))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REGISTER: &str = r#"
+@prefix dcterms: <http://purl.org/dc/terms/> .
+@prefix cpsv:    <http://purl.org/vocab/cpsv#> .
+@prefix xsd:     <http://www.w3.org/2001/XMLSchema#> .
+@prefix ex:      <https://regels.example.nl/id/> .
+
+# Twee regelsets en een wet
+ex:zorgtoeslag a cpsv:PublicService ;
+    dcterms:title "Zorgtoeslag berekening"@nl ;
+    dcterms:description "Berekent de hoogte van de zorgtoeslag" ;
+    dcterms:source <https://wetten.overheid.nl/BWBR0018451> ;
+    dcterms:publisher "Belastingdienst" ;
+    ex:drempel 25000 , 30000.50 ;
+    ex:actief true .
+
+ex:boomkap a cpsv:PublicService ;
+    dcterms:title 'Boomkap vergunning' ;
+    dcterms:subject "omgeving", "natuur" ;
+    ex:contact [ ex:naam "Loket" ; ex:telefoon "14 024" ] ;
+    ex:stappen ( "aanvraag" "besluit" ) .
+
+<https://regels.example.nl/id/leeg> dcterms:description """Meerdere
+regels met "quotes" en \t tab""" .
+"#;
+
+    fn store() -> RdfStore {
+        let mut store = RdfStore::new();
+        store.load_turtle(REGISTER).unwrap();
+        store
+    }
+
+    fn values(bindings: &Bindings, var: &str) -> Vec<String> {
+        bindings.iter().filter_map(|b| b.get(var).map(|v| v.value.clone())).collect()
+    }
+
+    #[test]
+    fn test_load_turtle() {
+        let store = store();
+        let zorgtoeslag = Term::iri("https://regels.example.nl/id/zorgtoeslag");
+        let drempel = Term::iri("https://regels.example.nl/id/drempel");
+
+        let objects: Vec<&Term> = store
+            .matching(Some(&zorgtoeslag), Some(&drempel), None)
+            .map(|t| &t.object)
+            .collect();
+        assert_eq!(objects, vec![
+            &Term::typed_literal("25000", XSD_INTEGER),
+            &Term::typed_literal("30000.50", XSD_DECIMAL),
+        ]);
+
+        let title = Term::iri("http://purl.org/dc/terms/title");
+        let label = store.matching(Some(&zorgtoeslag), Some(&title), None).next().unwrap();
+        assert_eq!(label.object, Term::lang_literal("Zorgtoeslag berekening", "nl"));
+
+        let description = Term::iri("http://purl.org/dc/terms/description");
+        let leeg = Term::iri("https://regels.example.nl/id/leeg");
+        let long = store.matching(Some(&leeg), Some(&description), None).next().unwrap();
+        assert_eq!(long.object.value(), "Meerdere\nregels met \"quotes\" en \t tab");
+
+        // Blank node property list en collection
+        let rest = Term::iri(RDF_REST);
+        assert_eq!(store.matching(None, Some(&rest), Some(&Term::iri(RDF_NIL))).count(), 1);
+        assert_eq!(store.len(), 21);
+    }
+
+    #[test]
+    fn test_turtle_errors_report_line() {
+        let err = RdfStore::new()
+            .load_turtle("@prefix ex: <https://example.nl/> .\n\nex:a ex:b .")
+            .unwrap_err();
+        assert!(matches!(err, RdfError::Turtle { line: 3, .. }), "{}", err);
+
+        let err = RdfStore::new().load_turtle("onbekend:a onbekend:b onbekend:c .").unwrap_err();
+        assert!(err.to_string().contains("onbekend"));
+    }
+
+    #[test]
+    fn test_blank_nodes_are_scoped_per_document() {
+        let mut store = RdfStore::new();
+        let doc = "_:b <https://example.nl/p> \"x\" .";
+        assert_eq!(store.load_turtle(doc).unwrap(), 1);
+        assert_eq!(store.load_turtle(doc).unwrap(), 1);
+        // Dezelfde IRI-triple twee keer laden voegt niets toe
+        let iri = "<https://example.nl/a> <https://example.nl/p> \"x\" .";
+        assert_eq!(store.load_turtle(iri).unwrap(), 1);
+        assert_eq!(store.load_turtle(iri).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_load_json_ld() {
+        let mut store = RdfStore::new();
+        let loaded = store.load_json_ld(r#"{
+            "@context": {
+                "dcterms": "http://purl.org/dc/terms/",
+                "cpsv": "http://purl.org/vocab/cpsv#",
+                "title": { "@id": "dcterms:title", "@language": "nl" },
+                "bron": { "@id": "dcterms:source", "@type": "@id" }
+            },
+            "@graph": [
+                {
+                    "@id": "https://regels.example.nl/id/aow",
+                    "@type": "cpsv:PublicService",
+                    "title": "AOW-leeftijd",
+                    "bron": "https://wetten.overheid.nl/BWBR0002221",
+                    "dcterms:publisher": { "@id": "https://svb.nl" },
+                    "dcterms:extent": 67,
+                    "dcterms:valid": { "@value": "2024-01-01", "@type": "http://www.w3.org/2001/XMLSchema#date" },
+                    "onbekend": "zonder @vocab genegeerd"
+                }
+            ]
+        }"#).unwrap();
+        assert_eq!(loaded, 6);
+
+        let aow = Term::iri("https://regels.example.nl/id/aow");
+        let objects: HashSet<&Term> = store.matching(Some(&aow), None, None).map(|t| &t.object).collect();
+        assert!(objects.contains(&Term::iri("http://purl.org/vocab/cpsv#PublicService")));
+        assert!(objects.contains(&Term::lang_literal("AOW-leeftijd", "nl")));
+        assert!(objects.contains(&Term::iri("https://wetten.overheid.nl/BWBR0002221")));
+        assert!(objects.contains(&Term::typed_literal("67", XSD_INTEGER)));
+        assert!(objects.contains(&Term::typed_literal("2024-01-01", "http://www.w3.org/2001/XMLSchema#date")));
+    }
+
+    #[test]
+    fn test_select_optional_filter_order() {
+        let store = store();
+        let bindings = store.select(r#"
+PREFIX dcterms: <http://purl.org/dc/terms/>
+PREFIX cpsv:    <http://purl.org/vocab/cpsv#>
+
+SELECT DISTINCT ?regel ?label ?eigenaar
+WHERE {
+  ?regel a cpsv:PublicService .
+  OPTIONAL { ?regel dcterms:title     ?label    }
+  OPTIONAL { ?regel dcterms:publisher ?eigenaar }
+}
+ORDER BY DESC(?label)
+"#).unwrap();
+        assert_eq!(values(&bindings, "label"), vec!["Zorgtoeslag berekening", "Boomkap vergunning"]);
+        assert_eq!(values(&bindings, "eigenaar"), vec!["Belastingdienst"]);
+        assert_eq!(bindings[0]["regel"].kind, "uri");
+
+        let bindings = store.select(r#"
+PREFIX dcterms: <http://purl.org/dc/terms/>
+SELECT ?regel WHERE {
+  ?regel dcterms:title ?label ; dcterms:description ?beschrijving .
+  FILTER (
+    CONTAINS(LCASE(STR(?label)), LCASE("ZORG"))
+    || CONTAINS(LCASE(STR(?beschrijving)), LCASE("ZORG"))
+  )
+}
+"#).unwrap();
+        assert_eq!(values(&bindings, "regel"), vec!["https://regels.example.nl/id/zorgtoeslag"]);
+    }
+
+    #[test]
+    fn test_select_expressions() {
+        let store = store();
+        let bindings = store.select(r#"
+PREFIX ex: <https://regels.example.nl/id/>
+SELECT ?bedrag ?dubbel WHERE {
+  ex:zorgtoeslag ex:drempel ?bedrag .
+  FILTER (?bedrag > 26000 && ?bedrag != 0)
+  BIND (?bedrag * 2 AS ?dubbel)
+}
+"#).unwrap();
+        assert_eq!(values(&bindings, "bedrag"), vec!["30000.50"]);
+        assert_eq!(values(&bindings, "dubbel"), vec!["60001"]);
+
+        let bindings = store.select(r#"
+PREFIX ex: <https://regels.example.nl/id/>
+SELECT * WHERE {
+  { ?r ex:actief true } UNION { ?r ex:contact [ ex:naam ?naam ] }
+  FILTER (REGEX(STR(?r), "ZORG|boom", "i"))
+}
+ORDER BY ?naam
+LIMIT 5 OFFSET 0
+"#).unwrap();
+        assert_eq!(bindings.len(), 2);
+        assert!(!bindings[0].contains_key("naam"));
+        assert_eq!(bindings[1]["naam"].value, "Loket");
+        assert!(bindings.iter().all(|b| b.keys().all(|k| k == "r" || k == "naam")));
+    }
+
+    #[test]
+    fn test_construct_round_trip() {
+        let store = store();
+        let json_ld = store.construct(r#"
+CONSTRUCT { <https://regels.example.nl/id/boomkap> ?p ?o }
+WHERE     { <https://regels.example.nl/id/boomkap> ?p ?o }
+"#).unwrap();
+
+        let nodes = json_ld.as_array().unwrap();
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0]["@id"], "https://regels.example.nl/id/boomkap");
+        assert_eq!(nodes[0]["@type"][0], "http://purl.org/vocab/cpsv#PublicService");
+
+        let mut copy = RdfStore::new();
+        assert_eq!(copy.load_json_ld_value(&json_ld).unwrap(), 6);
+
+        // describe volgt ook de blank nodes
+        let described = store.describe("https://regels.example.nl/id/boomkap");
+        assert_eq!(described.as_array().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_unsupported_queries() {
+        let store = store();
+        for query in [
+            "SELECT ?x WHERE { ?x <https://example.nl/a>/<https://example.nl/b> ?y }",
+            "SELECT (COUNT(?x) AS ?n) WHERE { ?x ?p ?o }",
+            "SELECT ?x WHERE { GRAPH ?g { ?x ?p ?o } }",
+            "ASK { ?x ?p ?o }",
+            "SELECT ?x WHERE { ?x onbekend:p ?o }",
+        ] {
+            assert!(matches!(store.select(query), Err(RdfError::Query(_))), "{}", query);
+        }
+    }
+}
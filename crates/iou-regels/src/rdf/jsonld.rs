@@ -0,0 +1,415 @@
+//! JSON-LD naar triples en terug
+//!
+//! Het lezen ondersteunt inline contexts (prefixen, termdefinities met
+//! `@id`/`@type`/`@language`, `@vocab`, `@base`), `@graph`, geneste
+//! node-objecten, value-objecten, `@list` en `@set`. Schrijven levert
+//! expanded JSON-LD: een array van node-objecten met volledige IRI's,
+//! zoals een SPARQL-endpoint dat teruggeeft voor `application/ld+json`.
+
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+
+use super::turtle::resolve;
+use super::{
+    RdfError, Term, Triple, RDF_FIRST, RDF_NIL, RDF_REST, RDF_TYPE, XSD_BOOLEAN, XSD_DOUBLE,
+    XSD_INTEGER,
+};
+
+/// Maximum nesting of term definitions that refer to each other
+const MAX_EXPANSION_DEPTH: usize = 16;
+
+/// Zet een JSON-LD-document om naar triples
+pub(super) fn parse(document: &Value) -> Result<Vec<Triple>, RdfError> {
+    let mut reader = Reader { triples: Vec::new(), blank_nodes: 0 };
+    reader.document(document, &Context::default())?;
+    Ok(reader.triples)
+}
+
+/// Schrijf triples als expanded JSON-LD, één node-object per subject
+pub(super) fn serialize(triples: &[&Triple]) -> Value {
+    let mut order: Vec<&Term> = Vec::new();
+    let mut nodes: HashMap<&Term, Map<String, Value>> = HashMap::new();
+
+    for triple in triples {
+        let node = nodes.entry(&triple.subject).or_insert_with(|| {
+            order.push(&triple.subject);
+            let mut node = Map::new();
+            node.insert("@id".into(), Value::String(node_id(&triple.subject)));
+            node
+        });
+
+        let (key, value) = match (&triple.predicate, &triple.object) {
+            (Term::Iri(p), Term::Iri(o)) if p == RDF_TYPE => ("@type".to_string(), Value::String(o.clone())),
+            (predicate, object) => (predicate.value().to_string(), object_value(object)),
+        };
+        if let Value::Array(values) = node.entry(key).or_insert_with(|| Value::Array(vec![])) {
+            values.push(value);
+        }
+    }
+
+    Value::Array(
+        order
+            .into_iter()
+            .filter_map(|subject| nodes.remove(subject))
+            .map(Value::Object)
+            .collect(),
+    )
+}
+
+fn node_id(term: &Term) -> String {
+    match term {
+        Term::BlankNode(label) => format!("_:{}", label),
+        other => other.value().to_string(),
+    }
+}
+
+fn object_value(term: &Term) -> Value {
+    let mut object = Map::new();
+    match term {
+        Term::Iri(_) | Term::BlankNode(_) => {
+            object.insert("@id".into(), Value::String(node_id(term)));
+        }
+        Term::Literal { value, datatype, language } => {
+            object.insert("@value".into(), Value::String(value.clone()));
+            if let Some(datatype) = datatype {
+                object.insert("@type".into(), Value::String(datatype.clone()));
+            }
+            if let Some(language) = language {
+                object.insert("@language".into(), Value::String(language.clone()));
+            }
+        }
+    }
+    Value::Object(object)
+}
+
+// ── Reading ──────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default)]
+struct Context {
+    terms: HashMap<String, TermDefinition>,
+    vocab: Option<String>,
+    base: Option<String>,
+    language: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct TermDefinition {
+    /// IRI, compact IRI or other term
+    id: String,
+    /// `@id`, `@vocab` or a datatype IRI
+    value_type: Option<String>,
+    /// `Some(None)` clears the default language for this term
+    language: Option<Option<String>>,
+    list: bool,
+}
+
+impl Context {
+    /// Apply a local `@context` value
+    fn extend(&self, local: &Value) -> Result<Context, RdfError> {
+        let mut context = self.clone();
+        match local {
+            Value::Null => context = Context::default(),
+            Value::Array(items) => {
+                for item in items {
+                    context = context.extend(item)?;
+                }
+            }
+            Value::String(url) => {
+                tracing::warn!("Remote JSON-LD context '{}' wordt offline genegeerd", url);
+            }
+            Value::Object(definitions) => {
+                for (key, value) in definitions {
+                    match (key.as_str(), value) {
+                        ("@vocab", Value::String(vocab)) => context.vocab = Some(vocab.clone()),
+                        ("@vocab", Value::Null) => context.vocab = None,
+                        ("@base", Value::String(base)) => context.base = Some(base.clone()),
+                        ("@base", Value::Null) => context.base = None,
+                        ("@language", Value::String(language)) => {
+                            context.language = Some(language.to_lowercase())
+                        }
+                        ("@language", Value::Null) => context.language = None,
+                        (key, _) if key.starts_with('@') => {}
+                        (term, Value::String(id)) => {
+                            context.terms.insert(term.to_string(), TermDefinition {
+                                id: id.clone(),
+                                ..TermDefinition::default()
+                            });
+                        }
+                        (term, Value::Object(definition)) => {
+                            let id = match definition.get("@id") {
+                                Some(Value::String(id)) => id.clone(),
+                                _ => term.to_string(),
+                            };
+                            let language = definition.get("@language").map(|l| {
+                                l.as_str().map(str::to_lowercase)
+                            });
+                            context.terms.insert(term.to_string(), TermDefinition {
+                                id,
+                                value_type: definition
+                                    .get("@type")
+                                    .and_then(Value::as_str)
+                                    .map(str::to_string),
+                                language,
+                                list: definition.get("@container").and_then(Value::as_str) == Some("@list"),
+                            });
+                        }
+                        (term, Value::Null) => {
+                            context.terms.remove(term);
+                        }
+                        (term, other) => {
+                            return Err(RdfError::JsonLd(format!(
+                                "ongeldige termdefinitie voor '{}': {}",
+                                term, other
+                            )));
+                        }
+                    }
+                }
+            }
+            other => {
+                return Err(RdfError::JsonLd(format!("ongeldige @context: {}", other)));
+            }
+        }
+        Ok(context)
+    }
+
+    /// Expand a term, compact IRI or (relative) IRI
+    ///
+    /// `vocab` selects vocabulary-relative expansion (keys, `@type`) over
+    /// document-relative expansion (`@id`). Returns `None` for keys that
+    /// do not map to an IRI and are therefore dropped.
+    fn expand(&self, value: &str, vocab: bool) -> Option<String> {
+        self.expand_depth(value, vocab, 0)
+    }
+
+    fn expand_depth(&self, value: &str, vocab: bool, depth: usize) -> Option<String> {
+        if depth > MAX_EXPANSION_DEPTH {
+            return None;
+        }
+        if value.starts_with('@') {
+            return Some(value.to_string());
+        }
+        if let Some(definition) = self.terms.get(value).filter(|d| vocab && d.id != value) {
+            return self.expand_depth(&definition.id, true, depth + 1);
+        }
+        if let Some((prefix, suffix)) = value.split_once(':') {
+            if prefix == "_" || suffix.starts_with("//") {
+                return Some(value.to_string());
+            }
+            if let Some(definition) = self.terms.get(prefix) {
+                let namespace = self.expand_depth(&definition.id, true, depth + 1)?;
+                return Some(format!("{}{}", namespace, suffix));
+            }
+            return Some(value.to_string());
+        }
+        if vocab {
+            return self.vocab.as_ref().map(|v| format!("{}{}", v, value));
+        }
+        Some(resolve(self.base.as_deref(), value))
+    }
+}
+
+struct Reader {
+    triples: Vec<Triple>,
+    blank_nodes: usize,
+}
+
+impl Reader {
+    fn document(&mut self, value: &Value, context: &Context) -> Result<(), RdfError> {
+        match value {
+            Value::Array(items) => {
+                for item in items {
+                    self.document(item, context)?;
+                }
+                Ok(())
+            }
+            Value::Object(object) => {
+                let context = match object.get("@context") {
+                    Some(local) => context.extend(local)?,
+                    None => context.clone(),
+                };
+                // A bare @graph container: its nodes are top-level nodes
+                let is_container = object.keys().all(|k| k == "@context" || k == "@graph");
+                match object.get("@graph") {
+                    Some(graph) if is_container => self.document(graph, &context),
+                    _ => self.node(object, &context).map(|_| ()),
+                }
+            }
+            other => Err(RdfError::JsonLd(format!("node-object verwacht, gevonden {}", other))),
+        }
+    }
+
+    /// Process a node object and return its subject
+    fn node(&mut self, object: &Map<String, Value>, context: &Context) -> Result<Term, RdfError> {
+        let context = match object.get("@context") {
+            Some(local) => context.extend(local)?,
+            None => context.clone(),
+        };
+
+        let subject = match object.get("@id").and_then(Value::as_str) {
+            Some(id) => self.resource(&context, id, false)?,
+            None => self.fresh_blank_node(),
+        };
+
+        for (key, value) in object {
+            match key.as_str() {
+                "@type" => {
+                    for class in as_array(value) {
+                        let class = class.as_str().ok_or_else(|| {
+                            RdfError::JsonLd(format!("@type moet een string zijn: {}", class))
+                        })?;
+                        let class = self.resource(&context, class, true)?;
+                        self.push(subject.clone(), Term::iri(RDF_TYPE), class);
+                    }
+                }
+                "@graph" => self.document(value, &context)?,
+                key if key.starts_with('@') => {}
+                key => {
+                    let Some(predicate) = context.expand(key, true) else {
+                        continue;
+                    };
+                    let predicate = Term::iri(predicate);
+                    let definition = context.terms.get(key);
+                    if definition.is_some_and(|d| d.list) {
+                        let list = self.list(as_array(value), &context, definition)?;
+                        self.push(subject.clone(), predicate, list);
+                        continue;
+                    }
+                    for object in self.objects(value, &context, definition)? {
+                        self.push(subject.clone(), predicate.clone(), object);
+                    }
+                }
+            }
+        }
+
+        Ok(subject)
+    }
+
+    /// Object terms for a property value; arrays and `@set` are flattened
+    /// and `null` is dropped
+    fn objects(
+        &mut self,
+        value: &Value,
+        context: &Context,
+        definition: Option<&TermDefinition>,
+    ) -> Result<Vec<Term>, RdfError> {
+        let value_type = definition.and_then(|d| d.value_type.as_deref());
+        let term = match value {
+            Value::Null => return Ok(vec![]),
+            Value::Bool(b) => Term::typed_literal(b.to_string(), XSD_BOOLEAN),
+            Value::Number(n) if n.is_i64() || n.is_u64() => Term::typed_literal(n.to_string(), XSD_INTEGER),
+            Value::Number(n) => Term::typed_literal(n.to_string(), XSD_DOUBLE),
+            Value::String(s) => match value_type {
+                Some("@id") => self.resource(context, s, false)?,
+                Some("@vocab") => self.resource(context, s, true)?,
+                Some(datatype) => {
+                    let datatype = context.expand(datatype, true).unwrap_or_else(|| datatype.to_string());
+                    Term::typed_literal(s.clone(), datatype)
+                }
+                None => {
+                    let language = match definition.and_then(|d| d.language.clone()) {
+                        Some(language) => language,
+                        None => context.language.clone(),
+                    };
+                    match language {
+                        Some(language) => Term::lang_literal(s.clone(), language),
+                        None => Term::literal(s.clone()),
+                    }
+                }
+            },
+            Value::Array(items) => {
+                let mut terms = Vec::new();
+                for item in items {
+                    terms.extend(self.objects(item, context, definition)?);
+                }
+                return Ok(terms);
+            }
+            Value::Object(object) => {
+                if let Some(literal) = object.get("@value") {
+                    self.value_object(literal, object, context)?
+                } else if let Some(items) = object.get("@list") {
+                    self.list(as_array(items), context, definition)?
+                } else if let Some(items) = object.get("@set") {
+                    return self.objects(items, context, definition);
+                } else {
+                    self.node(object, context)?
+                }
+            }
+        };
+        Ok(vec![term])
+    }
+
+    fn value_object(
+        &mut self,
+        literal: &Value,
+        object: &Map<String, Value>,
+        context: &Context,
+    ) -> Result<Term, RdfError> {
+        let lexical = match literal {
+            Value::String(s) => s.clone(),
+            Value::Number(_) | Value::Bool(_) => literal.to_string(),
+            other => return Err(RdfError::JsonLd(format!("ongeldige @value: {}", other))),
+        };
+
+        if let Some(datatype) = object.get("@type").and_then(Value::as_str) {
+            let datatype = context.expand(datatype, true).unwrap_or_else(|| datatype.to_string());
+            return Ok(Term::typed_literal(lexical, datatype));
+        }
+        if let Some(language) = object.get("@language").and_then(Value::as_str) {
+            return Ok(Term::lang_literal(lexical, language));
+        }
+        Ok(match literal {
+            Value::Bool(_) => Term::typed_literal(lexical, XSD_BOOLEAN),
+            Value::Number(n) if n.is_i64() || n.is_u64() => Term::typed_literal(lexical, XSD_INTEGER),
+            Value::Number(_) => Term::typed_literal(lexical, XSD_DOUBLE),
+            _ => Term::literal(lexical),
+        })
+    }
+
+    fn list(
+        &mut self,
+        items: &[Value],
+        context: &Context,
+        definition: Option<&TermDefinition>,
+    ) -> Result<Term, RdfError> {
+        let mut terms = Vec::new();
+        for item in items {
+            terms.extend(self.objects(item, context, definition)?);
+        }
+
+        let mut list = Term::iri(RDF_NIL);
+        for term in terms.into_iter().rev() {
+            let node = self.fresh_blank_node();
+            self.push(node.clone(), Term::iri(RDF_FIRST), term);
+            self.push(node.clone(), Term::iri(RDF_REST), list);
+            list = node;
+        }
+        Ok(list)
+    }
+
+    fn resource(&mut self, context: &Context, value: &str, vocab: bool) -> Result<Term, RdfError> {
+        if let Some(label) = value.strip_prefix("_:") {
+            return Ok(Term::BlankNode(label.to_string()));
+        }
+        context
+            .expand(value, vocab)
+            .map(Term::Iri)
+            .ok_or_else(|| RdfError::JsonLd(format!("'{}' is geen IRI", value)))
+    }
+
+    fn fresh_blank_node(&mut self) -> Term {
+        self.blank_nodes += 1;
+        Term::BlankNode(format!("anon~{}", self.blank_nodes))
+    }
+
+    fn push(&mut self, subject: Term, predicate: Term, object: Term) {
+        self.triples.push(Triple::new(subject, predicate, object));
+    }
+}
+
+fn as_array(value: &Value) -> &[Value] {
+    match value {
+        Value::Array(items) => items,
+        other => std::slice::from_ref(other),
+    }
+}
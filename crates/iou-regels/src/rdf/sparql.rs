@@ -0,0 +1,1578 @@
+//! SPARQL 1.1-subset voor de lokale store
+//!
+//! Ondersteund: `PREFIX`/`BASE`, `SELECT [DISTINCT|REDUCED]` met variabelen,
+//! `*` of `(expressie AS ?var)`, `CONSTRUCT` (ook de `CONSTRUCT WHERE`-vorm),
+//! triple patterns met `;`, `,`, `a` en blank nodes, `OPTIONAL`, `UNION`,
+//! geneste groepen, `FILTER`, `BIND`, `ORDER BY`, `LIMIT` en `OFFSET`.
+//! Expressies kennen logische en rekenkundige operatoren, vergelijkingen,
+//! `IN`/`NOT IN`, de gebruikelijke string- en termfuncties, `REGEX` en
+//! casts naar `xsd:`-datatypes.
+//!
+//! Alles daarbuiten (property paths, aggregaten, subqueries, `GRAPH`,
+//! `SERVICE`, `VALUES`, `MINUS`, `ASK`, `DESCRIBE`, updates) geeft een
+//! [`RdfError::Query`] in plaats van een stilzwijgend afwijkend antwoord.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use regex::RegexBuilder;
+
+use super::turtle::resolve;
+use super::{
+    jsonld, RdfError, RdfStore, Term, Triple, RDF_TYPE, XSD, XSD_BOOLEAN, XSD_DECIMAL, XSD_DOUBLE,
+    XSD_INTEGER, XSD_STRING,
+};
+use crate::client::{Bindings, SparqlValue};
+
+const RDF_LANG_STRING: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString";
+
+/// Voer een SELECT-query uit
+pub(super) fn select(store: &RdfStore, query: &str) -> Result<Bindings, RdfError> {
+    let Query::Select { distinct, projection, pattern, modifiers } = parse(query)? else {
+        return Err(RdfError::Query("SELECT-query verwacht".into()));
+    };
+
+    let mut solutions = evaluate_group(store, &pattern, vec![Solution::new()]);
+    order(&mut solutions, &modifiers.order_by);
+
+    let projection = projection.unwrap_or_else(|| {
+        let mut vars = Vec::new();
+        collect_vars(&pattern, &mut vars);
+        vars.into_iter().map(Projection::Var).collect()
+    });
+
+    let mut rows: Vec<Vec<(String, Option<Term>)>> = solutions
+        .iter()
+        .map(|solution| {
+            projection
+                .iter()
+                .map(|p| match p {
+                    Projection::Var(var) => (var.clone(), solution.get(var).cloned()),
+                    Projection::Expression(expression, var) => {
+                        (var.clone(), evaluate(expression, solution))
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    if distinct {
+        let mut seen = HashSet::new();
+        rows.retain(|row| seen.insert(row.iter().map(|(_, t)| t.clone()).collect::<Vec<_>>()));
+    }
+
+    Ok(modifiers
+        .slice(rows)
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .filter_map(|(var, term)| term.map(|t| (var, t.to_sparql_value())))
+                .collect::<HashMap<String, SparqlValue>>()
+        })
+        .collect())
+}
+
+/// Voer een CONSTRUCT-query uit; geeft expanded JSON-LD terug
+pub(super) fn construct(store: &RdfStore, query: &str) -> Result<serde_json::Value, RdfError> {
+    let Query::Construct { template, pattern, modifiers } = parse(query)? else {
+        return Err(RdfError::Query("CONSTRUCT-query verwacht".into()));
+    };
+
+    let mut solutions = evaluate_group(store, &pattern, vec![Solution::new()]);
+    order(&mut solutions, &modifiers.order_by);
+
+    let mut triples = Vec::new();
+    let mut seen = HashSet::new();
+    for (row, solution) in modifiers.slice(solutions).iter().enumerate() {
+        for pattern in &template {
+            let instantiate = |term: &PatternTerm| match term {
+                PatternTerm::Var(var) => solution.get(var).cloned(),
+                // Template blank nodes are fresh for every solution
+                PatternTerm::Term(Term::BlankNode(label)) => {
+                    Some(Term::BlankNode(format!("c{}~{}", row, label)))
+                }
+                PatternTerm::Term(term) => Some(term.clone()),
+            };
+            let (Some(subject), Some(predicate), Some(object)) = (
+                instantiate(&pattern.subject),
+                instantiate(&pattern.predicate),
+                instantiate(&pattern.object),
+            ) else {
+                continue;
+            };
+            if subject.is_literal() || !matches!(predicate, Term::Iri(_)) {
+                continue;
+            }
+            let triple = Triple::new(subject, predicate, object);
+            if seen.insert(triple.clone()) {
+                triples.push(triple);
+            }
+        }
+    }
+
+    Ok(jsonld::serialize(&triples.iter().collect::<Vec<_>>()))
+}
+
+// ── Query model ──────────────────────────────────────────────────────────────
+
+enum Query {
+    Select {
+        distinct: bool,
+        /// `None` for `SELECT *`
+        projection: Option<Vec<Projection>>,
+        pattern: Group,
+        modifiers: Modifiers,
+    },
+    Construct {
+        template: Vec<TriplePattern>,
+        pattern: Group,
+        modifiers: Modifiers,
+    },
+}
+
+enum Projection {
+    Var(String),
+    Expression(Expression, String),
+}
+
+#[derive(Default)]
+struct Modifiers {
+    /// Expression and descending flag
+    order_by: Vec<(Expression, bool)>,
+    limit: Option<usize>,
+    offset: usize,
+}
+
+impl Modifiers {
+    fn slice<T>(&self, items: Vec<T>) -> Vec<T> {
+        items
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+enum PatternTerm {
+    Var(String),
+    Term(Term),
+}
+
+#[derive(Debug, Clone)]
+struct TriplePattern {
+    subject: PatternTerm,
+    predicate: PatternTerm,
+    object: PatternTerm,
+}
+
+#[derive(Default)]
+struct Group {
+    elements: Vec<Element>,
+}
+
+enum Element {
+    Triples(Vec<TriplePattern>),
+    Optional(Group),
+    Union(Vec<Group>),
+    Group(Group),
+    Filter(Expression),
+    Bind(Expression, String),
+}
+
+#[derive(Debug, Clone)]
+enum Expression {
+    Var(String),
+    Constant(Term),
+    Or(Box<Expression>, Box<Expression>),
+    And(Box<Expression>, Box<Expression>),
+    Not(Box<Expression>),
+    Compare(CompareOp, Box<Expression>, Box<Expression>),
+    In(Box<Expression>, Vec<Expression>, bool),
+    Arithmetic(ArithmeticOp, Box<Expression>, Box<Expression>),
+    Negate(Box<Expression>),
+    Call(Function, Vec<Expression>),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CompareOp {
+    Equal,
+    NotEqual,
+    Less,
+    Greater,
+    LessOrEqual,
+    GreaterOrEqual,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArithmeticOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Function {
+    Str,
+    Lang,
+    Datatype,
+    Bound,
+    Iri,
+    IsIri,
+    IsBlank,
+    IsLiteral,
+    IsNumeric,
+    StrLen,
+    UCase,
+    LCase,
+    Contains,
+    StrStarts,
+    StrEnds,
+    StrBefore,
+    StrAfter,
+    Concat,
+    Regex,
+    LangMatches,
+    SameTerm,
+    If,
+    Coalesce,
+    Cast(&'static str),
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Function> {
+        Some(match name.to_ascii_uppercase().as_str() {
+            "STR" => Function::Str,
+            "LANG" => Function::Lang,
+            "DATATYPE" => Function::Datatype,
+            "BOUND" => Function::Bound,
+            "IRI" | "URI" => Function::Iri,
+            "ISIRI" | "ISURI" => Function::IsIri,
+            "ISBLANK" => Function::IsBlank,
+            "ISLITERAL" => Function::IsLiteral,
+            "ISNUMERIC" => Function::IsNumeric,
+            "STRLEN" => Function::StrLen,
+            "UCASE" => Function::UCase,
+            "LCASE" => Function::LCase,
+            "CONTAINS" => Function::Contains,
+            "STRSTARTS" => Function::StrStarts,
+            "STRENDS" => Function::StrEnds,
+            "STRBEFORE" => Function::StrBefore,
+            "STRAFTER" => Function::StrAfter,
+            "CONCAT" => Function::Concat,
+            "REGEX" => Function::Regex,
+            "LANGMATCHES" => Function::LangMatches,
+            "SAMETERM" => Function::SameTerm,
+            "IF" => Function::If,
+            "COALESCE" => Function::Coalesce,
+            _ => return None,
+        })
+    }
+
+    fn cast(datatype: &str) -> Option<Function> {
+        Some(Function::Cast(match datatype.strip_prefix(XSD)? {
+            "string" => XSD_STRING,
+            "boolean" => XSD_BOOLEAN,
+            "integer" => XSD_INTEGER,
+            "decimal" => XSD_DECIMAL,
+            "double" => XSD_DOUBLE,
+            _ => return None,
+        }))
+    }
+}
+
+// ── Lexer ────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Var(String),
+    Iri(String),
+    PrefixedName(String, String),
+    BlankNode(String),
+    String(String),
+    LangTag(String),
+    Number(String),
+    Word(String),
+    Punct(&'static str),
+}
+
+const PUNCTUATION: [&str; 20] = [
+    "^^", "&&", "||", "!=", "<=", ">=", "{", "}", "(", ")", "[", "]", ".", ";", ",", "*", "/",
+    "|", "^", "=",
+];
+
+fn tokenize(query: &str) -> Result<Vec<Token>, RdfError> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    let name_char = |c: char| c.is_alphanumeric() || c == '_' || c == '-' || c == '.';
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '#' {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if (c == '?' || c == '$') && next.is_some_and(|n| n.is_alphanumeric() || n == '_') {
+            let start = i + 1;
+            i = start;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Var(chars[start..i].iter().collect()));
+        } else if c == '<' {
+            // IRI reference, or the less-than operator
+            let end = (i + 1..chars.len())
+                .find(|&j| chars[j].is_whitespace() || "<>\"{}|^`\\".contains(chars[j]));
+            match end {
+                Some(j) if chars[j] == '>' => {
+                    tokens.push(Token::Iri(chars[i + 1..j].iter().collect()));
+                    i = j + 1;
+                }
+                _ if next == Some('=') => {
+                    tokens.push(Token::Punct("<="));
+                    i += 2;
+                }
+                _ => {
+                    tokens.push(Token::Punct("<"));
+                    i += 1;
+                }
+            }
+        } else if c == '>' {
+            if next == Some('=') {
+                tokens.push(Token::Punct(">="));
+                i += 2;
+            } else {
+                tokens.push(Token::Punct(">"));
+                i += 1;
+            }
+        } else if c == '"' || c == '\'' {
+            let (value, end) = lex_string(&chars, i)?;
+            tokens.push(Token::String(value));
+            i = end;
+        } else if c == '@' && next.is_some_and(|n| n.is_ascii_alphabetic()) {
+            let start = i + 1;
+            i = start;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '-') {
+                i += 1;
+            }
+            tokens.push(Token::LangTag(chars[start..i].iter().collect::<String>().to_lowercase()));
+        } else if c.is_ascii_digit() || (c == '.' && next.is_some_and(|n| n.is_ascii_digit())) {
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            if i + 1 < chars.len() && chars[i] == '.' && chars[i + 1].is_ascii_digit() {
+                i += 1;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+            }
+            if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                i += 1;
+                if i < chars.len() && (chars[i] == '+' || chars[i] == '-') {
+                    i += 1;
+                }
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+            }
+            tokens.push(Token::Number(chars[start..i].iter().collect()));
+        } else if c == '_' && next == Some(':') {
+            let start = i + 2;
+            i = start;
+            while i < chars.len() && name_char(chars[i]) {
+                i += 1;
+            }
+            while i > start && chars[i - 1] == '.' {
+                i -= 1;
+            }
+            tokens.push(Token::BlankNode(chars[start..i].iter().collect()));
+        } else if c.is_alphabetic() || c == '_' || c == ':' {
+            let start = i;
+            while i < chars.len() && name_char(chars[i]) {
+                i += 1;
+            }
+            while i > start && chars[i - 1] == '.' {
+                i -= 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+
+            if chars.get(i) == Some(&':') {
+                let local_start = i + 1;
+                i = local_start;
+                while i < chars.len() && (name_char(chars[i]) || chars[i] == ':' || chars[i] == '%') {
+                    i += 1;
+                }
+                while i > local_start && chars[i - 1] == '.' {
+                    i -= 1;
+                }
+                tokens.push(Token::PrefixedName(word, chars[local_start..i].iter().collect()));
+            } else {
+                tokens.push(Token::Word(word));
+            }
+        } else if let Some(punct) = PUNCTUATION.iter().find(|p| {
+            p.chars().enumerate().all(|(k, pc)| chars.get(i + k) == Some(&pc))
+        }) {
+            tokens.push(Token::Punct(punct));
+            i += punct.len();
+        } else {
+            let single = match c {
+                '!' => "!",
+                '+' => "+",
+                '-' => "-",
+                '?' => "?",
+                _ => return Err(RdfError::Query(format!("onverwacht teken '{}'", c))),
+            };
+            tokens.push(Token::Punct(single));
+            i += 1;
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn lex_string(chars: &[char], start: usize) -> Result<(String, usize), RdfError> {
+    let quote = chars[start];
+    let long = chars.get(start + 1) == Some(&quote) && chars.get(start + 2) == Some(&quote);
+    let mut i = if long { start + 3 } else { start + 1 };
+    let mut value = String::new();
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == quote {
+            if !long {
+                return Ok((value, i + 1));
+            }
+            if chars.get(i + 1) == Some(&quote) && chars.get(i + 2) == Some(&quote) {
+                return Ok((value, i + 3));
+            }
+        }
+        if c == '\\' {
+            i += 1;
+            let escaped = match chars.get(i) {
+                Some('t') => '\t',
+                Some('n') => '\n',
+                Some('r') => '\r',
+                Some('b') => '\u{8}',
+                Some('f') => '\u{c}',
+                Some('"') => '"',
+                Some('\'') => '\'',
+                Some('\\') => '\\',
+                Some(u @ ('u' | 'U')) => {
+                    let digits = if *u == 'u' { 4 } else { 8 };
+                    let hex: String = chars.iter().skip(i + 1).take(digits).collect();
+                    i += digits;
+                    u32::from_str_radix(&hex, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| RdfError::Query(format!("ongeldige escape \\{}{}", u, hex)))?
+                }
+                _ => return Err(RdfError::Query("ongeldige escape in string".into())),
+            };
+            value.push(escaped);
+        } else {
+            value.push(c);
+        }
+        i += 1;
+    }
+
+    Err(RdfError::Query("string zonder afsluitend aanhalingsteken".into()))
+}
+
+// ── Parser ───────────────────────────────────────────────────────────────────
+
+fn parse(query: &str) -> Result<Query, RdfError> {
+    let mut parser = Parser {
+        tokens: tokenize(query)?,
+        pos: 0,
+        prefixes: HashMap::new(),
+        base: None,
+        blank_nodes: 0,
+        in_template: false,
+    };
+    let query = parser.query()?;
+    match parser.peek() {
+        None => Ok(query),
+        Some(token) => Err(RdfError::Query(format!("onverwacht {:?} na het einde van de query", token))),
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    prefixes: HashMap<String, String>,
+    base: Option<String>,
+    blank_nodes: usize,
+    /// Blank nodes in a CONSTRUCT template are terms, elsewhere variables
+    in_template: bool,
+}
+
+impl Parser {
+    fn query(&mut self) -> Result<Query, RdfError> {
+        loop {
+            if self.eat_word("PREFIX") {
+                let Some(Token::PrefixedName(prefix, local)) = self.next() else {
+                    return Err(self.error("prefix verwacht na PREFIX"));
+                };
+                if !local.is_empty() {
+                    return Err(self.error("prefixnaam verwacht na PREFIX"));
+                }
+                let Some(Token::Iri(iri)) = self.next() else {
+                    return Err(self.error("IRI verwacht na PREFIX"));
+                };
+                let iri = resolve(self.base.as_deref(), &iri);
+                self.prefixes.insert(prefix, iri);
+            } else if self.eat_word("BASE") {
+                let Some(Token::Iri(iri)) = self.next() else {
+                    return Err(self.error("IRI verwacht na BASE"));
+                };
+                self.base = Some(iri);
+            } else {
+                break;
+            }
+        }
+
+        if self.eat_word("SELECT") {
+            self.select_query()
+        } else if self.eat_word("CONSTRUCT") {
+            self.construct_query()
+        } else {
+            Err(self.error("alleen SELECT- en CONSTRUCT-queries worden ondersteund"))
+        }
+    }
+
+    fn select_query(&mut self) -> Result<Query, RdfError> {
+        let distinct = self.eat_word("DISTINCT");
+        // REDUCED permits, but does not require, removing duplicates
+        self.eat_word("REDUCED");
+
+        let projection = if self.eat_punct("*") {
+            None
+        } else {
+            let mut projection = Vec::new();
+            loop {
+                match self.peek() {
+                    Some(Token::Var(var)) => {
+                        projection.push(Projection::Var(var.clone()));
+                        self.pos += 1;
+                    }
+                    Some(Token::Punct("(")) => {
+                        self.pos += 1;
+                        let expression = self.expression()?;
+                        self.expect_word("AS")?;
+                        let var = self.var()?;
+                        self.expect_punct(")")?;
+                        projection.push(Projection::Expression(expression, var));
+                    }
+                    _ => break,
+                }
+            }
+            if projection.is_empty() {
+                return Err(self.error("variabelen of * verwacht na SELECT"));
+            }
+            Some(projection)
+        };
+
+        self.dataset_clause()?;
+        self.eat_word("WHERE");
+        let pattern = self.group()?;
+        let modifiers = self.modifiers()?;
+        Ok(Query::Select { distinct, projection, pattern, modifiers })
+    }
+
+    fn construct_query(&mut self) -> Result<Query, RdfError> {
+        if self.eat_word("WHERE") {
+            // CONSTRUCT WHERE { triples }: the pattern is the template
+            let pattern = self.group()?;
+            let template = match pattern.elements.as_slice() {
+                [Element::Triples(triples)] => triples.clone(),
+                _ => return Err(self.error("CONSTRUCT WHERE mag alleen triple patterns bevatten")),
+            };
+            let modifiers = self.modifiers()?;
+            return Ok(Query::Construct { template, pattern, modifiers });
+        }
+
+        self.expect_punct("{")?;
+        self.in_template = true;
+        let mut template = Vec::new();
+        while !self.eat_punct("}") {
+            if self.eat_punct(".") {
+                continue;
+            }
+            self.triples_same_subject(&mut template)?;
+        }
+        self.in_template = false;
+
+        self.dataset_clause()?;
+        self.eat_word("WHERE");
+        let pattern = self.group()?;
+        let modifiers = self.modifiers()?;
+        Ok(Query::Construct { template, pattern, modifiers })
+    }
+
+    fn dataset_clause(&self) -> Result<(), RdfError> {
+        if self.is_word("FROM") {
+            return Err(self.error("FROM (named graphs) wordt niet ondersteund"));
+        }
+        Ok(())
+    }
+
+    fn modifiers(&mut self) -> Result<Modifiers, RdfError> {
+        let mut modifiers = Modifiers::default();
+
+        if self.is_word("GROUP") || self.is_word("HAVING") {
+            return Err(self.error("aggregaten (GROUP BY/HAVING) worden niet ondersteund"));
+        }
+
+        if self.eat_word("ORDER") {
+            self.expect_word("BY")?;
+            loop {
+                if self.eat_word("ASC") {
+                    modifiers.order_by.push((self.bracketted_expression()?, false));
+                } else if self.eat_word("DESC") {
+                    modifiers.order_by.push((self.bracketted_expression()?, true));
+                } else if let Some(Token::Var(var)) = self.peek() {
+                    modifiers.order_by.push((Expression::Var(var.clone()), false));
+                    self.pos += 1;
+                } else if self.peek() == Some(&Token::Punct("(")) || self.is_function_call() {
+                    modifiers.order_by.push((self.primary()?, false));
+                } else {
+                    break;
+                }
+            }
+            if modifiers.order_by.is_empty() {
+                return Err(self.error("sorteervoorwaarde verwacht na ORDER BY"));
+            }
+        }
+
+        loop {
+            if self.eat_word("LIMIT") {
+                modifiers.limit = Some(self.count()?);
+            } else if self.eat_word("OFFSET") {
+                modifiers.offset = self.count()?;
+            } else {
+                break;
+            }
+        }
+
+        if self.is_word("VALUES") {
+            return Err(self.error("VALUES wordt niet ondersteund"));
+        }
+        Ok(modifiers)
+    }
+
+    fn count(&mut self) -> Result<usize, RdfError> {
+        match self.next() {
+            Some(Token::Number(n)) => n.parse().map_err(|_| self.error("geheel getal verwacht")),
+            _ => Err(self.error("geheel getal verwacht")),
+        }
+    }
+
+    fn group(&mut self) -> Result<Group, RdfError> {
+        self.expect_punct("{")?;
+        let mut group = Group::default();
+
+        loop {
+            if self.eat_punct("}") {
+                return Ok(group);
+            }
+            if self.eat_punct(".") {
+                continue;
+            }
+
+            if self.eat_word("OPTIONAL") {
+                group.elements.push(Element::Optional(self.group()?));
+            } else if self.eat_word("FILTER") {
+                let constraint = if self.peek() == Some(&Token::Punct("(")) {
+                    self.bracketted_expression()?
+                } else {
+                    self.primary()?
+                };
+                group.elements.push(Element::Filter(constraint));
+            } else if self.eat_word("BIND") {
+                self.expect_punct("(")?;
+                let expression = self.expression()?;
+                self.expect_word("AS")?;
+                let var = self.var()?;
+                self.expect_punct(")")?;
+                group.elements.push(Element::Bind(expression, var));
+            } else if self.peek() == Some(&Token::Punct("{")) {
+                let mut branches = vec![self.group()?];
+                while self.eat_word("UNION") {
+                    branches.push(self.group()?);
+                }
+                let element = if branches.len() == 1 {
+                    Element::Group(branches.remove(0))
+                } else {
+                    Element::Union(branches)
+                };
+                group.elements.push(element);
+            } else if let Some(keyword) = ["MINUS", "GRAPH", "SERVICE", "VALUES", "SELECT"]
+                .into_iter()
+                .find(|k| self.is_word(k))
+            {
+                return Err(self.error(&format!("{} wordt niet ondersteund", keyword)));
+            } else if self.peek().is_none() {
+                return Err(self.error("'}' verwacht"));
+            } else {
+                let mut triples = Vec::new();
+                self.triples_same_subject(&mut triples)?;
+                match group.elements.last_mut() {
+                    Some(Element::Triples(existing)) => existing.extend(triples),
+                    _ => group.elements.push(Element::Triples(triples)),
+                }
+            }
+        }
+    }
+
+    fn triples_same_subject(&mut self, triples: &mut Vec<TriplePattern>) -> Result<(), RdfError> {
+        let subject = if self.peek() == Some(&Token::Punct("[")) {
+            let subject = self.blank_node_property_list(triples)?;
+            if matches!(self.peek(), Some(Token::Punct(".")) | Some(Token::Punct("}"))) {
+                return Ok(());
+            }
+            subject
+        } else {
+            self.node()?
+        };
+        self.property_list(&subject, triples)
+    }
+
+    fn property_list(&mut self, subject: &PatternTerm, triples: &mut Vec<TriplePattern>) -> Result<(), RdfError> {
+        loop {
+            let predicate = self.verb()?;
+            loop {
+                let object = self.object(triples)?;
+                triples.push(TriplePattern {
+                    subject: subject.clone(),
+                    predicate: predicate.clone(),
+                    object,
+                });
+                if !self.eat_punct(",") {
+                    break;
+                }
+            }
+
+            if !self.eat_punct(";") {
+                return Ok(());
+            }
+            while self.eat_punct(";") {}
+            if matches!(
+                self.peek(),
+                Some(Token::Punct(".")) | Some(Token::Punct("}")) | Some(Token::Punct("]")) | None
+            ) {
+                return Ok(());
+            }
+        }
+    }
+
+    fn verb(&mut self) -> Result<PatternTerm, RdfError> {
+        let verb = match self.peek() {
+            Some(Token::Word(w)) if w == "a" => {
+                self.pos += 1;
+                PatternTerm::Term(Term::iri(RDF_TYPE))
+            }
+            Some(Token::Var(var)) => {
+                let var = var.clone();
+                self.pos += 1;
+                PatternTerm::Var(var)
+            }
+            Some(Token::Iri(_)) | Some(Token::PrefixedName(..)) => PatternTerm::Term(self.iri()?),
+            Some(Token::Punct("^")) | Some(Token::Punct("(")) | Some(Token::Punct("!")) => {
+                return Err(self.error("property paths worden niet ondersteund"));
+            }
+            _ => return Err(self.error("predicaat verwacht")),
+        };
+
+        if matches!(self.peek(), Some(Token::Punct("/" | "|" | "*" | "+" | "?"))) {
+            return Err(self.error("property paths worden niet ondersteund"));
+        }
+        Ok(verb)
+    }
+
+    fn object(&mut self, triples: &mut Vec<TriplePattern>) -> Result<PatternTerm, RdfError> {
+        match self.peek() {
+            Some(Token::Punct("[")) => self.blank_node_property_list(triples),
+            Some(Token::Punct("(")) => Err(self.error("collections in queries worden niet ondersteund")),
+            _ => self.node(),
+        }
+    }
+
+    /// Variable, IRI, blank node or literal
+    fn node(&mut self) -> Result<PatternTerm, RdfError> {
+        match self.peek().cloned() {
+            Some(Token::Var(var)) => {
+                self.pos += 1;
+                Ok(PatternTerm::Var(var))
+            }
+            Some(Token::BlankNode(label)) => {
+                self.pos += 1;
+                Ok(self.blank_node(Some(&label)))
+            }
+            Some(Token::Iri(_)) | Some(Token::PrefixedName(..)) => Ok(PatternTerm::Term(self.iri()?)),
+            _ => match self.literal()? {
+                Some(literal) => Ok(PatternTerm::Term(literal)),
+                None => Err(self.error("term verwacht")),
+            },
+        }
+    }
+
+    fn blank_node_property_list(&mut self, triples: &mut Vec<TriplePattern>) -> Result<PatternTerm, RdfError> {
+        self.expect_punct("[")?;
+        let node = self.blank_node(None);
+        if !self.eat_punct("]") {
+            self.property_list(&node, triples)?;
+            self.expect_punct("]")?;
+        }
+        Ok(node)
+    }
+
+    /// Blank nodes act as non-projected variables in patterns
+    fn blank_node(&mut self, label: Option<&str>) -> PatternTerm {
+        let label = match label {
+            Some(label) => label.to_string(),
+            None => {
+                self.blank_nodes += 1;
+                format!("anon~{}", self.blank_nodes)
+            }
+        };
+        if self.in_template {
+            PatternTerm::Term(Term::BlankNode(label))
+        } else {
+            // ':' cannot occur in a variable name, so these never clash
+            PatternTerm::Var(format!("_:{}", label))
+        }
+    }
+
+    fn iri(&mut self) -> Result<Term, RdfError> {
+        match self.next() {
+            Some(Token::Iri(iri)) => Ok(Term::Iri(resolve(self.base.as_deref(), &iri))),
+            Some(Token::PrefixedName(prefix, local)) => match self.prefixes.get(&prefix) {
+                Some(namespace) => Ok(Term::Iri(format!("{}{}", namespace, local))),
+                None => Err(RdfError::Query(format!("onbekend prefix '{}:'", prefix))),
+            },
+            _ => Err(self.error("IRI verwacht")),
+        }
+    }
+
+    /// String, number or boolean literal; `None` if the next token is none of these
+    fn literal(&mut self) -> Result<Option<Term>, RdfError> {
+        let literal = match self.peek().cloned() {
+            Some(Token::String(value)) => {
+                self.pos += 1;
+                match self.peek().cloned() {
+                    Some(Token::LangTag(language)) => {
+                        self.pos += 1;
+                        Term::lang_literal(value, language)
+                    }
+                    Some(Token::Punct("^^")) => {
+                        self.pos += 1;
+                        let datatype = self.iri()?;
+                        Term::typed_literal(value, datatype.value())
+                    }
+                    _ => Term::literal(value),
+                }
+            }
+            Some(Token::Number(number)) => {
+                self.pos += 1;
+                number_literal(number)
+            }
+            Some(Token::Punct(sign @ ("-" | "+"))) => match self.tokens.get(self.pos + 1).cloned() {
+                Some(Token::Number(number)) => {
+                    self.pos += 2;
+                    let number = if sign == "-" { format!("-{}", number) } else { number };
+                    number_literal(number)
+                }
+                _ => return Ok(None),
+            },
+            Some(Token::Word(w)) if w == "true" || w == "false" => {
+                self.pos += 1;
+                Term::typed_literal(w, XSD_BOOLEAN)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(literal))
+    }
+
+    fn var(&mut self) -> Result<String, RdfError> {
+        match self.next() {
+            Some(Token::Var(var)) => Ok(var),
+            _ => Err(self.error("variabele verwacht")),
+        }
+    }
+
+    // ── Expressions ──
+
+    fn bracketted_expression(&mut self) -> Result<Expression, RdfError> {
+        self.expect_punct("(")?;
+        let expression = self.expression()?;
+        self.expect_punct(")")?;
+        Ok(expression)
+    }
+
+    fn expression(&mut self) -> Result<Expression, RdfError> {
+        let mut left = self.and_expression()?;
+        while self.eat_punct("||") {
+            left = Expression::Or(Box::new(left), Box::new(self.and_expression()?));
+        }
+        Ok(left)
+    }
+
+    fn and_expression(&mut self) -> Result<Expression, RdfError> {
+        let mut left = self.relational_expression()?;
+        while self.eat_punct("&&") {
+            left = Expression::And(Box::new(left), Box::new(self.relational_expression()?));
+        }
+        Ok(left)
+    }
+
+    fn relational_expression(&mut self) -> Result<Expression, RdfError> {
+        let left = self.additive_expression()?;
+
+        let op = match self.peek() {
+            Some(Token::Punct("=")) => Some(CompareOp::Equal),
+            Some(Token::Punct("!=")) => Some(CompareOp::NotEqual),
+            Some(Token::Punct("<")) => Some(CompareOp::Less),
+            Some(Token::Punct(">")) => Some(CompareOp::Greater),
+            Some(Token::Punct("<=")) => Some(CompareOp::LessOrEqual),
+            Some(Token::Punct(">=")) => Some(CompareOp::GreaterOrEqual),
+            _ => None,
+        };
+        if let Some(op) = op {
+            self.pos += 1;
+            let right = self.additive_expression()?;
+            return Ok(Expression::Compare(op, Box::new(left), Box::new(right)));
+        }
+
+        let negated = self.is_word("NOT") && self.word_at(1).is_some_and(|w| w.eq_ignore_ascii_case("IN"));
+        if negated {
+            self.pos += 1;
+        }
+        if self.eat_word("IN") {
+            let list = self.argument_list()?;
+            return Ok(Expression::In(Box::new(left), list, negated));
+        }
+        Ok(left)
+    }
+
+    fn additive_expression(&mut self) -> Result<Expression, RdfError> {
+        let mut left = self.multiplicative_expression()?;
+        loop {
+            let op = if self.eat_punct("+") {
+                ArithmeticOp::Add
+            } else if self.eat_punct("-") {
+                ArithmeticOp::Subtract
+            } else {
+                return Ok(left);
+            };
+            let right = self.multiplicative_expression()?;
+            left = Expression::Arithmetic(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn multiplicative_expression(&mut self) -> Result<Expression, RdfError> {
+        let mut left = self.unary_expression()?;
+        loop {
+            let op = if self.eat_punct("*") {
+                ArithmeticOp::Multiply
+            } else if self.eat_punct("/") {
+                ArithmeticOp::Divide
+            } else {
+                return Ok(left);
+            };
+            let right = self.unary_expression()?;
+            left = Expression::Arithmetic(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn unary_expression(&mut self) -> Result<Expression, RdfError> {
+        if self.eat_punct("!") {
+            return Ok(Expression::Not(Box::new(self.unary_expression()?)));
+        }
+        if self.eat_punct("-") {
+            return Ok(Expression::Negate(Box::new(self.unary_expression()?)));
+        }
+        if self.eat_punct("+") {
+            return self.unary_expression();
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expression, RdfError> {
+        match self.peek().cloned() {
+            Some(Token::Punct("(")) => self.bracketted_expression(),
+            Some(Token::Var(var)) => {
+                self.pos += 1;
+                Ok(Expression::Var(var))
+            }
+            Some(Token::Word(name)) if name != "true" && name != "false" => {
+                self.pos += 1;
+                let function = Function::from_name(&name).ok_or_else(|| {
+                    RdfError::Query(format!("functie {} wordt niet ondersteund", name.to_uppercase()))
+                })?;
+                if function == Function::Bound {
+                    self.expect_punct("(")?;
+                    let var = self.var()?;
+                    self.expect_punct(")")?;
+                    return Ok(Expression::Call(Function::Bound, vec![Expression::Var(var)]));
+                }
+                Ok(Expression::Call(function, self.argument_list()?))
+            }
+            Some(Token::Iri(_)) | Some(Token::PrefixedName(..)) => {
+                let iri = self.iri()?;
+                if self.peek() != Some(&Token::Punct("(")) {
+                    return Ok(Expression::Constant(iri));
+                }
+                let function = Function::cast(iri.value()).ok_or_else(|| {
+                    RdfError::Query(format!("functie <{}> wordt niet ondersteund", iri.value()))
+                })?;
+                Ok(Expression::Call(function, self.argument_list()?))
+            }
+            _ => match self.literal()? {
+                Some(literal) => Ok(Expression::Constant(literal)),
+                None => Err(self.error("expressie verwacht")),
+            },
+        }
+    }
+
+    fn argument_list(&mut self) -> Result<Vec<Expression>, RdfError> {
+        self.expect_punct("(")?;
+        let mut arguments = Vec::new();
+        if self.eat_punct(")") {
+            return Ok(arguments);
+        }
+        if self.is_word("DISTINCT") {
+            return Err(self.error("aggregaten worden niet ondersteund"));
+        }
+        loop {
+            arguments.push(self.expression()?);
+            if self.eat_punct(")") {
+                return Ok(arguments);
+            }
+            self.expect_punct(",")?;
+        }
+    }
+
+    fn is_function_call(&self) -> bool {
+        matches!(self.peek(), Some(Token::Word(_)))
+            && self.tokens.get(self.pos + 1) == Some(&Token::Punct("("))
+    }
+
+    // ── Token helpers ──
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn word_at(&self, offset: usize) -> Option<&str> {
+        match self.tokens.get(self.pos + offset) {
+            Some(Token::Word(w)) => Some(w),
+            _ => None,
+        }
+    }
+
+    fn is_word(&self, keyword: &str) -> bool {
+        self.word_at(0).is_some_and(|w| w.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_word(&mut self, keyword: &str) -> bool {
+        let matches = self.is_word(keyword);
+        if matches {
+            self.pos += 1;
+        }
+        matches
+    }
+
+    fn expect_word(&mut self, keyword: &str) -> Result<(), RdfError> {
+        if self.eat_word(keyword) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("{} verwacht", keyword)))
+        }
+    }
+
+    fn eat_punct(&mut self, punct: &str) -> bool {
+        let matches = matches!(self.peek(), Some(Token::Punct(p)) if *p == punct);
+        if matches {
+            self.pos += 1;
+        }
+        matches
+    }
+
+    fn expect_punct(&mut self, punct: &str) -> Result<(), RdfError> {
+        if self.eat_punct(punct) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("'{}' verwacht", punct)))
+        }
+    }
+
+    fn error(&self, message: &str) -> RdfError {
+        match self.peek() {
+            Some(token) => RdfError::Query(format!("{}, gevonden {:?}", message, token)),
+            None => RdfError::Query(format!("{}, einde van de query", message)),
+        }
+    }
+}
+
+fn number_literal(number: String) -> Term {
+    let datatype = if number.contains(['e', 'E']) {
+        XSD_DOUBLE
+    } else if number.contains('.') {
+        XSD_DECIMAL
+    } else {
+        XSD_INTEGER
+    };
+    Term::typed_literal(number, datatype)
+}
+
+/// Variables of a pattern in order of first appearance, for `SELECT *`
+fn collect_vars(group: &Group, vars: &mut Vec<String>) {
+    let mut add = |var: &str| {
+        if !var.starts_with("_:") && !vars.iter().any(|v| v == var) {
+            vars.push(var.to_string());
+        }
+    };
+    let mut nested = Vec::new();
+    for element in &group.elements {
+        match element {
+            Element::Triples(triples) => {
+                for pattern in triples {
+                    for term in [&pattern.subject, &pattern.predicate, &pattern.object] {
+                        if let PatternTerm::Var(var) = term {
+                            add(var);
+                        }
+                    }
+                }
+            }
+            Element::Bind(_, var) => add(var),
+            Element::Optional(group) | Element::Group(group) => nested.push(group),
+            Element::Union(groups) => nested.extend(groups),
+            Element::Filter(_) => {}
+        }
+    }
+    for group in nested {
+        collect_vars(group, vars);
+    }
+}
+
+// ── Evaluation ───────────────────────────────────────────────────────────────
+
+type Solution = HashMap<String, Term>;
+
+/// Evaluate a group for each seed solution
+///
+/// Filters apply to the whole group, wherever they appear in it.
+fn evaluate_group(store: &RdfStore, group: &Group, seeds: Vec<Solution>) -> Vec<Solution> {
+    let mut solutions = seeds;
+    let mut filters = Vec::new();
+
+    for element in &group.elements {
+        solutions = match element {
+            Element::Triples(patterns) => solutions
+                .into_iter()
+                .flat_map(|s| match_patterns(store, patterns, s))
+                .collect(),
+            Element::Optional(optional) => solutions
+                .into_iter()
+                .flat_map(|s| {
+                    let extended = evaluate_group(store, optional, vec![s.clone()]);
+                    if extended.is_empty() { vec![s] } else { extended }
+                })
+                .collect(),
+            Element::Union(branches) => solutions
+                .into_iter()
+                .flat_map(|s| {
+                    branches
+                        .iter()
+                        .flat_map(|branch| evaluate_group(store, branch, vec![s.clone()]))
+                        .collect::<Vec<_>>()
+                })
+                .collect(),
+            Element::Group(nested) => evaluate_group(store, nested, solutions),
+            Element::Bind(expression, var) => solutions
+                .into_iter()
+                .map(|mut s| {
+                    if let Some(value) = evaluate(expression, &s) {
+                        s.entry(var.clone()).or_insert(value);
+                    }
+                    s
+                })
+                .collect(),
+            Element::Filter(expression) => {
+                filters.push(expression);
+                solutions
+            }
+        };
+    }
+
+    solutions.retain(|s| filters.iter().all(|f| effective_boolean(f, s) == Some(true)));
+    solutions
+}
+
+/// Basic graph pattern matching, one triple pattern at a time
+fn match_patterns(store: &RdfStore, patterns: &[TriplePattern], seed: Solution) -> Vec<Solution> {
+    let mut solutions = vec![seed];
+
+    for pattern in patterns {
+        let mut next = Vec::new();
+        for solution in &solutions {
+            let subject = resolve_term(&pattern.subject, solution);
+            let predicate = resolve_term(&pattern.predicate, solution);
+            let object = resolve_term(&pattern.object, solution);
+
+            for triple in store.matching(subject.as_ref(), predicate.as_ref(), object.as_ref()) {
+                let mut extended = solution.clone();
+                if bind(&mut extended, &pattern.subject, &triple.subject)
+                    && bind(&mut extended, &pattern.predicate, &triple.predicate)
+                    && bind(&mut extended, &pattern.object, &triple.object)
+                {
+                    next.push(extended);
+                }
+            }
+        }
+        solutions = next;
+        if solutions.is_empty() {
+            break;
+        }
+    }
+
+    solutions
+}
+
+fn resolve_term(term: &PatternTerm, solution: &Solution) -> Option<Term> {
+    match term {
+        PatternTerm::Var(var) => solution.get(var).cloned(),
+        PatternTerm::Term(term) => Some(term.clone()),
+    }
+}
+
+/// Bind a variable; `false` when it is already bound to another term
+fn bind(solution: &mut Solution, pattern: &PatternTerm, value: &Term) -> bool {
+    let PatternTerm::Var(var) = pattern else {
+        return true;
+    };
+    match solution.get(var) {
+        Some(bound) => bound == value,
+        None => {
+            solution.insert(var.clone(), value.clone());
+            true
+        }
+    }
+}
+
+fn order(solutions: &mut [Solution], order_by: &[(Expression, bool)]) {
+    if order_by.is_empty() {
+        return;
+    }
+    solutions.sort_by(|a, b| {
+        for (expression, descending) in order_by {
+            let ordering = order_terms(evaluate(expression, a).as_ref(), evaluate(expression, b).as_ref());
+            let ordering = if *descending { ordering.reverse() } else { ordering };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+        Ordering::Equal
+    });
+}
+
+/// SPARQL ordering: unbound, blank nodes, IRIs, literals
+fn order_terms(a: Option<&Term>, b: Option<&Term>) -> Ordering {
+    let rank = |t: Option<&Term>| match t {
+        None => 0,
+        Some(Term::BlankNode(_)) => 1,
+        Some(Term::Iri(_)) => 2,
+        Some(Term::Literal { .. }) => 3,
+    };
+    match (a, b) {
+        (Some(a), Some(b)) if rank(Some(a)) == rank(Some(b)) => match (numeric(a), numeric(b)) {
+            (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+            _ => a.value().cmp(b.value()),
+        },
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+fn effective_boolean(expression: &Expression, solution: &Solution) -> Option<bool> {
+    term_boolean(&evaluate(expression, solution)?)
+}
+
+fn term_boolean(term: &Term) -> Option<bool> {
+    let Term::Literal { value, datatype, .. } = term else {
+        return None;
+    };
+    match datatype.as_deref() {
+        Some(XSD_BOOLEAN) => Some(value == "true" || value == "1"),
+        Some(_) => numeric(term).map(|n| n != 0.0 && !n.is_nan()),
+        None => Some(!value.is_empty()),
+    }
+}
+
+/// Evaluate an expression; `None` is an error or unbound variable
+fn evaluate(expression: &Expression, solution: &Solution) -> Option<Term> {
+    match expression {
+        Expression::Var(var) => solution.get(var).cloned(),
+        Expression::Constant(term) => Some(term.clone()),
+        Expression::Or(a, b) => {
+            // An error on one side is ignored when the other side is true
+            match (effective_boolean(a, solution), effective_boolean(b, solution)) {
+                (Some(true), _) | (_, Some(true)) => Some(boolean(true)),
+                (Some(false), Some(false)) => Some(boolean(false)),
+                _ => None,
+            }
+        }
+        Expression::And(a, b) => {
+            match (effective_boolean(a, solution), effective_boolean(b, solution)) {
+                (Some(false), _) | (_, Some(false)) => Some(boolean(false)),
+                (Some(true), Some(true)) => Some(boolean(true)),
+                _ => None,
+            }
+        }
+        Expression::Not(a) => effective_boolean(a, solution).map(|b| boolean(!b)),
+        Expression::Compare(op, a, b) => {
+            let (a, b) = (evaluate(a, solution)?, evaluate(b, solution)?);
+            let result = match op {
+                CompareOp::Equal => terms_equal(&a, &b)?,
+                CompareOp::NotEqual => !terms_equal(&a, &b)?,
+                CompareOp::Less => compare_terms(&a, &b)? == Ordering::Less,
+                CompareOp::Greater => compare_terms(&a, &b)? == Ordering::Greater,
+                CompareOp::LessOrEqual => compare_terms(&a, &b)? != Ordering::Greater,
+                CompareOp::GreaterOrEqual => compare_terms(&a, &b)? != Ordering::Less,
+            };
+            Some(boolean(result))
+        }
+        Expression::In(value, list, negated) => {
+            let value = evaluate(value, solution)?;
+            let found = list
+                .iter()
+                .filter_map(|e| evaluate(e, solution))
+                .any(|candidate| terms_equal(&value, &candidate) == Some(true));
+            Some(boolean(found != *negated))
+        }
+        Expression::Arithmetic(op, a, b) => {
+            arithmetic(*op, &evaluate(a, solution)?, &evaluate(b, solution)?)
+        }
+        Expression::Negate(a) => {
+            let a = evaluate(a, solution)?;
+            arithmetic(ArithmeticOp::Subtract, &Term::typed_literal("0", XSD_INTEGER), &a)
+        }
+        Expression::Call(function, arguments) => call(*function, arguments, solution),
+    }
+}
+
+fn call(function: Function, arguments: &[Expression], solution: &Solution) -> Option<Term> {
+    // Functions that do not evaluate all of their arguments
+    match function {
+        Function::Bound => {
+            let Some(Expression::Var(var)) = arguments.first() else {
+                return None;
+            };
+            return Some(boolean(solution.contains_key(var)));
+        }
+        Function::If => {
+            let [condition, then, otherwise] = arguments else {
+                return None;
+            };
+            let branch = if effective_boolean(condition, solution)? { then } else { otherwise };
+            return evaluate(branch, solution);
+        }
+        Function::Coalesce => {
+            return arguments.iter().find_map(|a| evaluate(a, solution));
+        }
+        _ => {}
+    }
+
+    let values = arguments
+        .iter()
+        .map(|a| evaluate(a, solution))
+        .collect::<Option<Vec<Term>>>()?;
+    let arg = |i: usize| values.get(i);
+    let text = |i: usize| arg(i).filter(|t| t.is_literal()).map(Term::value);
+
+    Some(match function {
+        Function::Str => match arg(0)? {
+            Term::BlankNode(_) => return None,
+            term => Term::literal(term.value()),
+        },
+        Function::Lang => match arg(0)? {
+            Term::Literal { language, .. } => Term::literal(language.clone().unwrap_or_default()),
+            _ => return None,
+        },
+        Function::Datatype => match arg(0)? {
+            Term::Literal { datatype: Some(datatype), .. } => Term::iri(datatype.clone()),
+            Term::Literal { language: Some(_), .. } => Term::iri(RDF_LANG_STRING),
+            Term::Literal { .. } => Term::iri(XSD_STRING),
+            _ => return None,
+        },
+        Function::Iri => match arg(0)? {
+            Term::BlankNode(_) => return None,
+            term => Term::iri(term.value()),
+        },
+        Function::IsIri => boolean(matches!(arg(0)?, Term::Iri(_))),
+        Function::IsBlank => boolean(matches!(arg(0)?, Term::BlankNode(_))),
+        Function::IsLiteral => boolean(arg(0)?.is_literal()),
+        Function::IsNumeric => boolean(numeric(arg(0)?).is_some()),
+        Function::StrLen => Term::typed_literal(text(0)?.chars().count().to_string(), XSD_INTEGER),
+        Function::UCase => same_language(arg(0)?, text(0)?.to_uppercase()),
+        Function::LCase => same_language(arg(0)?, text(0)?.to_lowercase()),
+        Function::Contains => boolean(text(0)?.contains(text(1)?)),
+        Function::StrStarts => boolean(text(0)?.starts_with(text(1)?)),
+        Function::StrEnds => boolean(text(0)?.ends_with(text(1)?)),
+        Function::StrBefore => match text(0)?.split_once(text(1)?) {
+            Some((before, _)) => same_language(arg(0)?, before.to_string()),
+            None => Term::literal(""),
+        },
+        Function::StrAfter => match text(0)?.split_once(text(1)?) {
+            Some((_, after)) => same_language(arg(0)?, after.to_string()),
+            None => Term::literal(""),
+        },
+        Function::Concat => Term::literal(
+            (0..values.len()).map(text).collect::<Option<Vec<_>>>()?.concat(),
+        ),
+        Function::Regex => {
+            let flags = text(2).unwrap_or_default();
+            let regex = RegexBuilder::new(text(1)?)
+                .case_insensitive(flags.contains('i'))
+                .multi_line(flags.contains('m'))
+                .dot_matches_new_line(flags.contains('s'))
+                .ignore_whitespace(flags.contains('x'))
+                .build()
+                .ok()?;
+            boolean(regex.is_match(text(0)?))
+        }
+        Function::LangMatches => {
+            let (tag, range) = (text(0)?.to_lowercase(), text(1)?.to_lowercase());
+            boolean(if range == "*" {
+                !tag.is_empty()
+            } else {
+                tag == range || tag.starts_with(&format!("{}-", range))
+            })
+        }
+        Function::SameTerm => boolean(arg(0)? == arg(1)?),
+        Function::Cast(datatype) => cast(arg(0)?, datatype)?,
+        Function::Bound | Function::If | Function::Coalesce => unreachable!("handled above"),
+    })
+}
+
+fn cast(term: &Term, datatype: &str) -> Option<Term> {
+    if matches!(term, Term::BlankNode(_)) {
+        return None;
+    }
+    let value = term.value().trim();
+    match datatype {
+        XSD_STRING => Some(Term::literal(term.value())),
+        XSD_BOOLEAN => match (value, numeric(term)) {
+            ("true" | "1", _) => Some(boolean(true)),
+            ("false" | "0", _) => Some(boolean(false)),
+            (_, Some(n)) => Some(boolean(n != 0.0)),
+            _ => None,
+        },
+        XSD_INTEGER => {
+            let n = value.parse::<i64>().ok().or_else(|| numeric(term).map(|n| n.trunc() as i64))?;
+            Some(Term::typed_literal(n.to_string(), XSD_INTEGER))
+        }
+        _ => {
+            let n = numeric(term).or_else(|| value.parse::<f64>().ok())?;
+            Some(Term::typed_literal(n.to_string(), datatype))
+        }
+    }
+}
+
+fn boolean(value: bool) -> Term {
+    Term::typed_literal(value.to_string(), XSD_BOOLEAN)
+}
+
+/// String function results keep the language tag of their first argument
+fn same_language(source: &Term, value: String) -> Term {
+    match source {
+        Term::Literal { language: Some(language), .. } => Term::lang_literal(value, language.clone()),
+        _ => Term::literal(value),
+    }
+}
+
+fn is_numeric_type(datatype: &str) -> bool {
+    matches!(
+        datatype.strip_prefix(XSD),
+        Some(
+            "integer" | "decimal" | "double" | "float" | "int" | "long" | "short" | "byte"
+                | "nonNegativeInteger" | "positiveInteger" | "negativeInteger"
+                | "nonPositiveInteger" | "unsignedInt" | "unsignedLong"
+        )
+    )
+}
+
+fn is_integer_type(datatype: &str) -> bool {
+    is_numeric_type(datatype) && !matches!(datatype.strip_prefix(XSD), Some("decimal" | "double" | "float"))
+}
+
+fn numeric(term: &Term) -> Option<f64> {
+    match term {
+        Term::Literal { value, datatype: Some(datatype), .. } if is_numeric_type(datatype) => {
+            value.trim().parse().ok()
+        }
+        _ => None,
+    }
+}
+
+fn arithmetic(op: ArithmeticOp, a: &Term, b: &Term) -> Option<Term> {
+    let (x, y) = (numeric(a)?, numeric(b)?);
+    let datatype_of = |t: &Term| match t {
+        Term::Literal { datatype: Some(d), .. } => d.clone(),
+        _ => String::new(),
+    };
+    let (da, db) = (datatype_of(a), datatype_of(b));
+
+    let result = match op {
+        ArithmeticOp::Add => x + y,
+        ArithmeticOp::Subtract => x - y,
+        ArithmeticOp::Multiply => x * y,
+        ArithmeticOp::Divide if y == 0.0 => return None,
+        ArithmeticOp::Divide => x / y,
+    };
+
+    if is_integer_type(&da) && is_integer_type(&db) && op != ArithmeticOp::Divide {
+        return Some(Term::typed_literal((result as i64).to_string(), XSD_INTEGER));
+    }
+    let datatype = if [da.as_str(), db.as_str()].iter().any(|d| d.ends_with("double") || d.ends_with("float")) {
+        XSD_DOUBLE
+    } else {
+        XSD_DECIMAL
+    };
+    Some(Term::typed_literal(result.to_string(), datatype))
+}
+
+/// `=`: numbers by value, other literals by value, type and language
+fn terms_equal(a: &Term, b: &Term) -> Option<bool> {
+    if let (Some(x), Some(y)) = (numeric(a), numeric(b)) {
+        return Some(x == y);
+    }
+    Some(a == b)
+}
+
+/// `<` and friends: numbers, or literals of the same kind
+fn compare_terms(a: &Term, b: &Term) -> Option<Ordering> {
+    if let (Some(x), Some(y)) = (numeric(a), numeric(b)) {
+        return x.partial_cmp(&y);
+    }
+    match (a, b) {
+        (
+            Term::Literal { value: x, datatype: dx, .. },
+            Term::Literal { value: y, datatype: dy, .. },
+        ) if dx == dy => Some(x.cmp(y)),
+        _ => None,
+    }
+}
@@ -0,0 +1,532 @@
+//! Turtle- en N-Triples-parser
+//!
+//! Volgt de Turtle 1.1-grammatica: `@prefix`/`PREFIX`, `@base`/`BASE`,
+//! predicaat- en objectlijsten, blank node property lists (`[ ... ]`),
+//! collections (`( ... )`) en alle literalvormen. N-Triples is een
+//! deelverzameling en gaat daarom ook door deze parser.
+
+use std::collections::HashMap;
+
+use super::{
+    RdfError, Term, Triple, RDF_FIRST, RDF_NIL, RDF_REST, RDF_TYPE, XSD_BOOLEAN, XSD_DECIMAL,
+    XSD_DOUBLE, XSD_INTEGER,
+};
+
+/// Parse een Turtle-document; blank node labels zijn lokaal aan het document
+pub(super) fn parse(source: &str) -> Result<Vec<Triple>, RdfError> {
+    let mut parser = Parser {
+        chars: source.chars().collect(),
+        pos: 0,
+        prefixes: HashMap::new(),
+        base: None,
+        triples: Vec::new(),
+        blank_nodes: 0,
+    };
+    parser.document()?;
+    Ok(parser.triples)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    prefixes: HashMap<String, String>,
+    base: Option<String>,
+    triples: Vec<Triple>,
+    blank_nodes: usize,
+}
+
+impl Parser {
+    fn document(&mut self) -> Result<(), RdfError> {
+        loop {
+            self.skip_whitespace();
+            if self.at_end() {
+                return Ok(());
+            }
+
+            if self.eat_keyword("@prefix") {
+                self.prefix_directive()?;
+                self.expect('.')?;
+            } else if self.eat_keyword("@base") {
+                self.base_directive()?;
+                self.expect('.')?;
+            } else if self.eat_keyword_ci("PREFIX") {
+                self.prefix_directive()?;
+            } else if self.eat_keyword_ci("BASE") {
+                self.base_directive()?;
+            } else {
+                self.triples_statement()?;
+                self.expect('.')?;
+            }
+        }
+    }
+
+    fn prefix_directive(&mut self) -> Result<(), RdfError> {
+        self.skip_whitespace();
+        let prefix = self.name_prefix();
+        self.expect(':')?;
+        self.skip_whitespace();
+        let iri = self.iri_ref()?;
+        self.prefixes.insert(prefix, iri);
+        Ok(())
+    }
+
+    fn base_directive(&mut self) -> Result<(), RdfError> {
+        self.skip_whitespace();
+        self.base = Some(self.iri_ref()?);
+        Ok(())
+    }
+
+    fn triples_statement(&mut self) -> Result<(), RdfError> {
+        self.skip_whitespace();
+        if self.peek() == Some('[') {
+            let subject = self.blank_node_property_list()?;
+            self.skip_whitespace();
+            if self.peek() != Some('.') {
+                self.predicate_object_list(&subject)?;
+            }
+            return Ok(());
+        }
+
+        let subject = match self.peek() {
+            Some('(') => self.collection()?,
+            Some('_') => self.blank_node_label()?,
+            _ => self.iri()?,
+        };
+        self.predicate_object_list(&subject)
+    }
+
+    fn predicate_object_list(&mut self, subject: &Term) -> Result<(), RdfError> {
+        loop {
+            self.skip_whitespace();
+            let predicate = self.verb()?;
+            loop {
+                self.skip_whitespace();
+                let object = self.object()?;
+                self.triples.push(Triple::new(subject.clone(), predicate.clone(), object));
+                self.skip_whitespace();
+                if !self.eat(',') {
+                    break;
+                }
+            }
+
+            // One or more ';', optionally followed by another verb
+            if !self.eat(';') {
+                return Ok(());
+            }
+            loop {
+                self.skip_whitespace();
+                if !self.eat(';') {
+                    break;
+                }
+            }
+            if matches!(self.peek(), Some('.') | Some(']') | None) {
+                return Ok(());
+            }
+        }
+    }
+
+    fn verb(&mut self) -> Result<Term, RdfError> {
+        if self.peek() == Some('a') && self.peek_at(1).is_none_or(|c| c.is_whitespace() || "<[\"'(_".contains(c)) {
+            self.pos += 1;
+            return Ok(Term::iri(RDF_TYPE));
+        }
+        self.iri()
+    }
+
+    fn object(&mut self) -> Result<Term, RdfError> {
+        match self.peek() {
+            Some('[') => self.blank_node_property_list(),
+            Some('(') => self.collection(),
+            Some('_') => self.blank_node_label(),
+            Some('"') | Some('\'') => self.rdf_literal(),
+            Some(c) if c.is_ascii_digit() || c == '+' || c == '-' || c == '.' => self.numeric_literal(),
+            Some(_) if self.eat_word("true") => Ok(Term::typed_literal("true", XSD_BOOLEAN)),
+            Some(_) if self.eat_word("false") => Ok(Term::typed_literal("false", XSD_BOOLEAN)),
+            Some(_) => self.iri(),
+            None => Err(self.error("object verwacht")),
+        }
+    }
+
+    fn blank_node_property_list(&mut self) -> Result<Term, RdfError> {
+        self.expect('[')?;
+        let node = self.fresh_blank_node();
+        self.skip_whitespace();
+        if !self.eat(']') {
+            self.predicate_object_list(&node)?;
+            self.skip_whitespace();
+            self.expect(']')?;
+        }
+        Ok(node)
+    }
+
+    fn collection(&mut self) -> Result<Term, RdfError> {
+        self.expect('(')?;
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.eat(')') {
+                break;
+            }
+            if self.at_end() {
+                return Err(self.error("collection zonder ')'"));
+            }
+            items.push(self.object()?);
+        }
+
+        let mut list = Term::iri(RDF_NIL);
+        for item in items.into_iter().rev() {
+            let node = self.fresh_blank_node();
+            self.triples.push(Triple::new(node.clone(), Term::iri(RDF_FIRST), item));
+            self.triples.push(Triple::new(node.clone(), Term::iri(RDF_REST), list));
+            list = node;
+        }
+        Ok(list)
+    }
+
+    fn blank_node_label(&mut self) -> Result<Term, RdfError> {
+        self.expect('_')?;
+        self.expect(':')?;
+        let label = self.local_name();
+        if label.is_empty() {
+            return Err(self.error("blank node zonder label"));
+        }
+        Ok(Term::BlankNode(label))
+    }
+
+    fn fresh_blank_node(&mut self) -> Term {
+        self.blank_nodes += 1;
+        // '~' cannot occur in a blank node label from the document
+        Term::BlankNode(format!("anon~{}", self.blank_nodes))
+    }
+
+    fn iri(&mut self) -> Result<Term, RdfError> {
+        if self.peek() == Some('<') {
+            return Ok(Term::Iri(self.iri_ref()?));
+        }
+
+        let start = self.pos;
+        let prefix = self.name_prefix();
+        if !self.eat(':') {
+            self.pos = start;
+            return Err(self.error("IRI verwacht"));
+        }
+        let local = self.local_name();
+        match self.prefixes.get(&prefix) {
+            Some(namespace) => Ok(Term::Iri(format!("{}{}", namespace, local))),
+            None => {
+                self.pos = start;
+                Err(self.error(&format!("onbekend prefix '{}:'", prefix)))
+            }
+        }
+    }
+
+    fn iri_ref(&mut self) -> Result<String, RdfError> {
+        self.expect('<')?;
+        let mut iri = String::new();
+        loop {
+            match self.next() {
+                Some('>') => break,
+                Some('\\') => iri.push(self.unicode_escape()?),
+                Some(c) if c.is_whitespace() => return Err(self.error("spatie in IRI")),
+                Some(c) => iri.push(c),
+                None => return Err(self.error("IRI zonder '>'")),
+            }
+        }
+        Ok(resolve(self.base.as_deref(), &iri))
+    }
+
+    fn rdf_literal(&mut self) -> Result<Term, RdfError> {
+        let value = self.string()?;
+        if self.eat('@') {
+            let mut language = String::new();
+            while let Some(c) = self.peek().filter(|c| c.is_ascii_alphanumeric() || *c == '-') {
+                language.push(c);
+                self.pos += 1;
+            }
+            return Ok(Term::lang_literal(value, language));
+        }
+        if self.peek() == Some('^') && self.peek_at(1) == Some('^') {
+            self.pos += 2;
+            let datatype = self.iri()?;
+            return Ok(Term::typed_literal(value, datatype.value()));
+        }
+        Ok(Term::literal(value))
+    }
+
+    fn string(&mut self) -> Result<String, RdfError> {
+        let quote = self.next().ok_or_else(|| self.error("string verwacht"))?;
+        let long = self.peek() == Some(quote) && self.peek_at(1) == Some(quote);
+        if long {
+            self.pos += 2;
+        }
+
+        let mut value = String::new();
+        loop {
+            match self.next() {
+                Some(c) if c == quote && !long => return Ok(value),
+                Some(c) if c == quote && self.peek() == Some(quote) && self.peek_at(1) == Some(quote) => {
+                    self.pos += 2;
+                    return Ok(value);
+                }
+                Some('\\') => value.push(self.string_escape()?),
+                Some('\n') | Some('\r') if !long => return Err(self.error("regeleinde in string")),
+                Some(c) => value.push(c),
+                None => return Err(self.error("string zonder afsluitend aanhalingsteken")),
+            }
+        }
+    }
+
+    fn string_escape(&mut self) -> Result<char, RdfError> {
+        let escaped = match self.peek() {
+            Some('t') => '\t',
+            Some('b') => '\u{8}',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('f') => '\u{c}',
+            Some('"') => '"',
+            Some('\'') => '\'',
+            Some('\\') => '\\',
+            _ => return self.unicode_escape(),
+        };
+        self.pos += 1;
+        Ok(escaped)
+    }
+
+    fn unicode_escape(&mut self) -> Result<char, RdfError> {
+        let digits = match self.next() {
+            Some('u') => 4,
+            Some('U') => 8,
+            _ => return Err(self.error("ongeldige escape")),
+        };
+        let hex: String = (0..digits).filter_map(|_| self.next()).collect();
+        u32::from_str_radix(&hex, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| self.error("ongeldige unicode escape"))
+    }
+
+    fn numeric_literal(&mut self) -> Result<Term, RdfError> {
+        let mut number = String::new();
+        if let Some(sign) = self.peek().filter(|c| *c == '+' || *c == '-') {
+            number.push(sign);
+            self.pos += 1;
+        }
+        self.digits(&mut number);
+
+        let mut datatype = XSD_INTEGER;
+        if self.peek() == Some('.') && self.peek_at(1).is_some_and(|c| c.is_ascii_digit()) {
+            number.push('.');
+            self.pos += 1;
+            self.digits(&mut number);
+            datatype = XSD_DECIMAL;
+        }
+        if let Some(e) = self.peek().filter(|c| *c == 'e' || *c == 'E') {
+            number.push(e);
+            self.pos += 1;
+            if let Some(sign) = self.peek().filter(|c| *c == '+' || *c == '-') {
+                number.push(sign);
+                self.pos += 1;
+            }
+            self.digits(&mut number);
+            datatype = XSD_DOUBLE;
+        }
+
+        if !number.chars().any(|c| c.is_ascii_digit()) {
+            return Err(self.error("getal verwacht"));
+        }
+        Ok(Term::typed_literal(number, datatype))
+    }
+
+    fn digits(&mut self, out: &mut String) {
+        while let Some(c) = self.peek().filter(char::is_ascii_digit) {
+            out.push(c);
+            self.pos += 1;
+        }
+    }
+
+    /// `PN_PREFIX`; may be empty
+    fn name_prefix(&mut self) -> String {
+        let mut prefix = String::new();
+        while let Some(c) = self.peek().filter(|c| is_name_char(*c) || *c == '.') {
+            prefix.push(c);
+            self.pos += 1;
+        }
+        self.unread_trailing_dots(&mut prefix);
+        prefix
+    }
+
+    /// `PN_LOCAL`, including `\`-escapes and `%xx`; a trailing '.' ends the statement
+    fn local_name(&mut self) -> String {
+        let mut local = String::new();
+        while let Some(c) = self.peek() {
+            match (c, self.peek_at(1)) {
+                ('\\', Some(escaped)) => {
+                    local.push(escaped);
+                    self.pos += 2;
+                }
+                _ if is_name_char(c) || c == '.' || c == ':' || c == '%' => {
+                    local.push(c);
+                    self.pos += 1;
+                }
+                _ => break,
+            }
+        }
+        self.unread_trailing_dots(&mut local);
+        local
+    }
+
+    fn unread_trailing_dots(&mut self, name: &mut String) {
+        while name.ends_with('.') {
+            name.pop();
+            self.pos -= 1;
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == '#' {
+                while self.peek().is_some_and(|c| c != '\n') {
+                    self.pos += 1;
+                }
+            } else if c.is_whitespace() {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let matches = keyword.chars().enumerate().all(|(i, k)| self.peek_at(i) == Some(k));
+        self.finish_keyword(keyword, matches)
+    }
+
+    fn eat_keyword_ci(&mut self, keyword: &str) -> bool {
+        let matches = keyword
+            .chars()
+            .enumerate()
+            .all(|(i, k)| self.peek_at(i).is_some_and(|c| c.eq_ignore_ascii_case(&k)));
+        self.finish_keyword(keyword, matches)
+    }
+
+    /// `true`/`false` only when not the start of a prefixed name
+    fn eat_word(&mut self, word: &str) -> bool {
+        let matches = word.chars().enumerate().all(|(i, k)| self.peek_at(i) == Some(k));
+        let len = word.chars().count();
+        if matches && !self.peek_at(len).is_some_and(|c| is_name_char(c) || c == ':') {
+            self.pos += len;
+            return true;
+        }
+        false
+    }
+
+    fn finish_keyword(&mut self, keyword: &str, matches: bool) -> bool {
+        let len = keyword.chars().count();
+        if matches && self.peek_at(len).is_none_or(|c| c.is_whitespace() || c == '<') {
+            self.pos += len;
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), RdfError> {
+        self.skip_whitespace();
+        if self.eat(expected) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("'{}' verwacht", expected)))
+        }
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.chars.len()
+    }
+
+    fn error(&self, message: &str) -> RdfError {
+        let end = self.pos.min(self.chars.len());
+        let line = 1 + self.chars[..end].iter().filter(|c| **c == '\n').count();
+        let found = match self.peek() {
+            Some(c) => format!(", gevonden '{}'", c),
+            None => ", einde van document".to_string(),
+        };
+        RdfError::Turtle {
+            line,
+            message: format!("{}{}", message, found),
+        }
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-' || c == '\u{b7}'
+}
+
+/// Resolve a relative IRI against the document base
+pub(super) fn resolve(base: Option<&str>, iri: &str) -> String {
+    let Some(base) = base else {
+        return iri.to_string();
+    };
+    if has_scheme(iri) {
+        return iri.to_string();
+    }
+
+    let without_fragment = base.split('#').next().unwrap_or(base);
+    if iri.is_empty() {
+        return without_fragment.to_string();
+    }
+    if iri.starts_with('#') {
+        return format!("{}{}", without_fragment, iri);
+    }
+    if let Some(path) = iri.strip_prefix("//") {
+        let scheme = base.split(':').next().unwrap_or("http");
+        return format!("{}://{}", scheme, path);
+    }
+    if iri.starts_with('/') {
+        // scheme://authority
+        let authority_end = base
+            .find("://")
+            .and_then(|i| base[i + 3..].find('/').map(|j| i + 3 + j))
+            .unwrap_or(base.len());
+        return format!("{}{}", &base[..authority_end], iri);
+    }
+
+    let directory = match without_fragment.rfind('/') {
+        Some(i) => &without_fragment[..=i],
+        None => without_fragment,
+    };
+    format!("{}{}", directory, iri)
+}
+
+fn has_scheme(iri: &str) -> bool {
+    match iri.find(':') {
+        Some(i) => {
+            let scheme = &iri[..i];
+            !scheme.is_empty()
+                && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+        }
+        None => false,
+    }
+}
@@ -255,6 +255,88 @@ LIMIT 50
 #[cfg(all(test, not(target_arch = "wasm32")))]
 mod tests {
     use super::*;
+    use crate::rdf::RdfStore;
+
+    const REGISTER: &str = r#"
+@prefix dcterms: <http://purl.org/dc/terms/> .
+@prefix cpsv:    <http://purl.org/vocab/cpsv#> .
+
+<https://regels.overheid.nl/zorgtoeslag> a cpsv:PublicService ;
+    dcterms:title "Zorgtoeslag berekening"@nl ;
+    dcterms:description "Berekent de hoogte van de zorgtoeslag" ;
+    dcterms:publisher "Belastingdienst" ;
+    dcterms:subject "zorg" ;
+    dcterms:source <https://wetten.overheid.nl/BWBR0018451> .
+
+<https://regels.overheid.nl/bijstand> a cpsv:PublicService ;
+    dcterms:title "Bijstandsnorm" ;
+    dcterms:subject "participatie" ;
+    dcterms:source <https://wetten.overheid.nl/BWBR0015703> .
+"#;
+
+    fn lokale_tools() -> OpenRegelsTools {
+        let mut store = RdfStore::new();
+        store.load_turtle(REGISTER).unwrap();
+        OpenRegelsTools::with_client(OpenRegelsClient::lokaal(store))
+    }
+
+    #[tokio::test]
+    async fn test_zoek_regels_lokaal() {
+        let tools = lokale_tools();
+
+        let regels = tools.zoek_regels("ZORGTOESLAG").await.unwrap();
+        assert_eq!(regels.len(), 1);
+        assert_eq!(regels[0].uri, "https://regels.overheid.nl/zorgtoeslag");
+        assert_eq!(regels[0].eigenaar.as_deref(), Some("Belastingdienst"));
+
+        let domein = tools.regels_voor_domein("participatie").await.unwrap();
+        assert_eq!(domein.len(), 1);
+        assert_eq!(domein[0].label.as_deref(), Some("Bijstandsnorm"));
+
+        let wet = tools.regels_voor_wet("https://wetten.overheid.nl/BWBR0015703").await.unwrap();
+        assert_eq!(wet.len(), 1);
+        assert_eq!(
+            wet[0].juridische_bron.as_ref().map(|j| j.uri.as_str()),
+            Some("https://wetten.overheid.nl/BWBR0015703")
+        );
+
+        let sets = tools.beschikbare_regelsets().await.unwrap();
+        let labels: Vec<_> = sets.iter().filter_map(|r| r.label.as_deref()).collect();
+        assert_eq!(labels, ["Bijstandsnorm", "Zorgtoeslag berekening"]);
+    }
+
+    #[tokio::test]
+    async fn test_haal_regel_details_lokaal() {
+        let tools = lokale_tools();
+
+        let detail = tools.haal_regel_details("https://regels.overheid.nl/zorgtoeslag").await.unwrap();
+        assert_eq!(detail.regel.label.as_deref(), Some("Zorgtoeslag berekening"));
+        assert_eq!(detail.json_ld[0]["@id"], "https://regels.overheid.nl/zorgtoeslag");
+        assert_eq!(
+            detail.json_ld[0]["http://purl.org/dc/terms/title"][0]["@language"],
+            "nl"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lokale_client_met_cache() {
+        let mut store = RdfStore::new();
+        store.load_turtle(REGISTER).unwrap();
+        let client = OpenRegelsClient::lokaal(store).with_cache(std::time::Duration::from_secs(60));
+        assert!(client.is_lokaal());
+
+        let query = "SELECT ?regel WHERE { ?regel <http://purl.org/dc/terms/subject> \"zorg\" }";
+        let eerste = client.select(query).await.unwrap();
+        let tweede = client.select(query).await.unwrap();
+        assert_eq!(eerste.len(), 1);
+        assert_eq!(tweede[0]["regel"].value, eerste[0]["regel"].value);
+
+        let resource = client.fetch_resource("https://regels.overheid.nl/bijstand").await.unwrap();
+        assert_eq!(resource[0]["@id"], "https://regels.overheid.nl/bijstand");
+        client.clear_cache();
+
+        assert!(client.select("SELECT ?s WHERE { ?s ?p/?q ?o }").await.is_err());
+    }
 
     /// Smoke test — vereist netwerkverbinding
     #[cfg(feature = "tokio")]
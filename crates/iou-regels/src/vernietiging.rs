@@ -0,0 +1,1065 @@
+//! Vernietigingslijsten: periodieke vernietiging volgens de Archiefwet
+//!
+//! [`ProvisaBeoordeling`] beoordeelt één document; dit module past die
+//! beoordeling toe op een hele verzameling archiefstukken en begeleidt de
+//! vernietiging zoals archivarissen die volgens de Archiefwet uitvoeren:
+//!
+//! 1. **Samenstellen** — [`Vernietigingslijst::samenstellen`] selecteert alle
+//!    stukken waarvan de bewaartermijn per de peildatum is verstreken, met per
+//!    stuk een verantwoording (selectielijst, categorie, termijn, datum).
+//!    Stukken die (nog) niet vernietigd mogen worden komen met reden in
+//!    [`Vernietigingslijst::overgeslagen`].
+//! 2. **Beoordelen** — de lijst gaat [`ter_beoordeling`](Vernietigingslijst::ter_beoordeling)
+//!    naar één of meer beoordelaars (archivaris, proceseigenaar). Zij kunnen
+//!    stukken [`uitzonderen`](Vernietigingslijst::sluit_uit) en geven ieder
+//!    een [`ApprovalResponse`]. Alle beoordelaars moeten akkoord geven;
+//!    één afwijzing wijst de hele lijst af.
+//! 3. **Uitvoeren** — na goedkeuring verwijdert een [`VernietigingsUitvoerder`]
+//!    de stukken uit opslag en databases. Vóór de uitvoering wordt een
+//!    audit-regel geschreven (write-ahead); daarna volgt de ondertekende
+//!    [`VerklaringVanVernietiging`] als audit-regel met de eerste als parent.
+//!    Mislukt een stuk, dan is de lijst [`DeelsUitgevoerd`](VernietigingslijstStatus::DeelsUitgevoerd)
+//!    en vernietigt een volgende uitvoering alleen de resterende stukken.
+//!
+//! # Voorbeeld
+//!
+//! ```rust,ignore
+//! let mut lijst = Vernietigingslijst::samenstellen(
+//!     stukken, &ProvisaSelectielijst::provinciaal_2020(), Some(&hotspots), peildatum, archivaris,
+//! );
+//! lijst.ter_beoordeling(vec![archivaris, proceseigenaar])?;
+//! lijst.beoordeel(ApprovalResponse::new(archivaris, ApprovalDecision::Approved, None))?;
+//! lijst.beoordeel(ApprovalResponse::new(proceseigenaar, ApprovalDecision::Approved, None))?;
+//!
+//! let verklaring = lijst
+//!     .uitvoeren(&uitvoerder, &audit_backend, "provincie-flevoland", &did, &sleutel)
+//!     .await?;
+//! assert!(verklaring.verifieer(&sleutel));
+//! ```
+
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use hmac::{Hmac, Mac};
+use iou_core::audit::{AuditAction, AuditBackend, AuditEntry, AuditOutcome};
+use iou_core::audit::logger::AuditError;
+use iou_core::workflows::{ApprovalDecision, ApprovalResponse};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::provisa::{
+    Archiefwaarde, BesluitType, HotspotRegister, PetraCategorie, ProvincieOrgaan,
+    ProvisaBeoordeling, ProvisaSelectielijst, ProvisaVersion,
+};
+
+/// Audit-actie die vóór de uitvoering wordt vastgelegd
+pub const AUDIT_ACTIE_GESTART: &str = "vernietiging_gestart";
+
+/// Audit-actie waaronder de verklaring van vernietiging wordt vastgelegd
+pub const AUDIT_ACTIE_UITGEVOERD: &str = "vernietiging_uitgevoerd";
+
+/// Resource type van vernietigingslijsten in het audit log
+pub const AUDIT_RESOURCE: &str = "vernietigingslijst";
+
+/// Fouten bij het samenstellen, beoordelen en uitvoeren van een vernietigingslijst
+#[derive(Debug, Error)]
+pub enum VernietigingError {
+    #[error("Actie '{actie}' niet toegestaan in status {status:?}")]
+    OngeldigeStatus {
+        status: VernietigingslijstStatus,
+        actie: &'static str,
+    },
+
+    #[error("Vernietigingslijst bevat geen te vernietigen stukken")]
+    GeenItems,
+
+    #[error("Vernietigingslijst heeft geen beoordelaars")]
+    GeenBeoordelaars,
+
+    #[error("Stuk {0} staat niet op de vernietigingslijst")]
+    OnbekendStuk(Uuid),
+
+    #[error("{0} is geen beoordelaar van deze vernietigingslijst")]
+    GeenBeoordelaar(Uuid),
+
+    #[error("{0} heeft deze vernietigingslijst al beoordeeld")]
+    AlBeoordeeld(Uuid),
+
+    #[error("Vernietiging mislukt: {0}")]
+    Uitvoering(String),
+
+    #[error("Audit log niet beschikbaar: {0}")]
+    Audit(#[from] AuditError),
+
+    /// De stukken zijn vernietigd, maar de verklaring staat niet in het audit log
+    #[error("Verklaring van vernietiging niet vastgelegd: {fout}")]
+    VerklaringNietVastgelegd {
+        verklaring: Box<VerklaringVanVernietiging>,
+        #[source]
+        fout: AuditError,
+    },
+}
+
+impl VernietigingError {
+    /// De verklaring die ondanks de fout is opgesteld, om elders te bewaren
+    pub fn verklaring(&self) -> Option<&VerklaringVanVernietiging> {
+        match self {
+            Self::VerklaringNietVastgelegd { verklaring, .. } => Some(verklaring),
+            _ => None,
+        }
+    }
+}
+
+/// Een archiefstuk dat voor vernietiging in aanmerking kan komen
+///
+/// Minimale weergave van een informatieobject: genoeg om de PROVISA
+/// beoordeling uit te voeren en het stuk daarna terug te vinden in opslag.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Archiefstuk {
+    /// Id van het informatieobject
+    pub object_id: Uuid,
+    /// Titel zoals die op de vernietigingslijst verschijnt
+    pub titel: String,
+    /// PETRA categorie
+    pub categorie: PetraCategorie,
+    /// Type besluit
+    pub besluit_type: BesluitType,
+    /// Datum waarop de bewaartermijn ingaat (meestal afsluiting van het dossier)
+    pub creatie_datum: NaiveDate,
+    /// Locatie van de inhoud in object storage
+    pub opslag_locatie: Option<String>,
+    /// Reden om het stuk ongeacht de termijn te bewaren (bijv. lopende procedure)
+    pub bewaarplicht: Option<String>,
+}
+
+impl Archiefstuk {
+    pub fn new(
+        object_id: Uuid,
+        titel: impl Into<String>,
+        categorie: PetraCategorie,
+        besluit_type: BesluitType,
+        creatie_datum: NaiveDate,
+    ) -> Self {
+        Self {
+            object_id,
+            titel: titel.into(),
+            categorie,
+            besluit_type,
+            creatie_datum,
+            opslag_locatie: None,
+            bewaarplicht: None,
+        }
+    }
+
+    /// Locatie van de inhoud in object storage
+    pub fn met_opslag(mut self, locatie: impl Into<String>) -> Self {
+        self.opslag_locatie = Some(locatie.into());
+        self
+    }
+
+    /// Markeer het stuk als (tijdelijk) niet vernietigbaar
+    pub fn met_bewaarplicht(mut self, reden: impl Into<String>) -> Self {
+        self.bewaarplicht = Some(reden.into());
+        self
+    }
+}
+
+/// Een stuk op de vernietigingslijst, met verantwoording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VernietigingsItem {
+    pub object_id: Uuid,
+    pub titel: String,
+    pub categorie: PetraCategorie,
+    pub besluit_type: BesluitType,
+    pub creatie_datum: NaiveDate,
+    /// Datum waarop de bewaartermijn verstreek
+    pub vernietigingsdatum: NaiveDate,
+    /// Referentie naar de bepaling in de selectielijst
+    pub selectielijst_ref: String,
+    /// Verantwoording uit de PROVISA beoordeling
+    pub verantwoording: Vec<String>,
+    pub opslag_locatie: Option<String>,
+    /// Gezet wanneer een beoordelaar het stuk van vernietiging uitzondert
+    pub uitzondering: Option<Uitzondering>,
+    /// Moment waarop het stuk bij uitvoering is vernietigd
+    #[serde(default)]
+    pub vernietigd_op: Option<DateTime<Utc>>,
+}
+
+impl VernietigingsItem {
+    /// Wordt dit stuk bij uitvoering vernietigd?
+    pub fn wordt_vernietigd(&self) -> bool {
+        self.uitzondering.is_none()
+    }
+}
+
+/// Uitzondering van een stuk door een beoordelaar
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Uitzondering {
+    pub door: Uuid,
+    pub reden: String,
+    pub op: DateTime<Utc>,
+}
+
+/// Een stuk dat bij het samenstellen niet op de lijst is gekomen
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OvergeslagenStuk {
+    pub object_id: Uuid,
+    pub titel: String,
+    pub reden: OverslagReden,
+}
+
+/// Waarom een stuk niet op de vernietigingslijst staat
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "reden", content = "detail")]
+pub enum OverslagReden {
+    /// Bewaartermijn nog niet verstreken
+    TermijnLooptNog(NaiveDate),
+    /// Permanent te bewaren (selectielijst of hotspot): overbrengen, niet vernietigen
+    Permanent,
+    /// Geen bepaling in de selectielijst: handmatig waarderen
+    GeenBewaartermijn,
+    /// Bewaarplicht op het stuk zelf
+    Bewaarplicht(String),
+}
+
+/// Status van een vernietigingslijst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VernietigingslijstStatus {
+    Concept,
+    TerBeoordeling,
+    Goedgekeurd,
+    Afgewezen,
+    /// Wordt op dit moment uitgevoerd
+    InUitvoering,
+    /// Uitgevoerd, maar niet elk stuk kon worden vernietigd
+    DeelsUitgevoerd,
+    Uitgevoerd,
+}
+
+/// Een vernietigingslijst: selectie, beoordeling en uitvoering
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Vernietigingslijst {
+    pub id: Uuid,
+    /// Datum waarop de bewaartermijnen zijn getoetst
+    pub peildatum: NaiveDate,
+    /// Naam van de toegepaste selectielijst
+    pub selectielijst: String,
+    pub versie: ProvisaVersion,
+    pub orgaan: ProvincieOrgaan,
+    pub status: VernietigingslijstStatus,
+    pub items: Vec<VernietigingsItem>,
+    pub overgeslagen: Vec<OvergeslagenStuk>,
+    pub beoordelaars: Vec<Uuid>,
+    pub beoordelingen: Vec<ApprovalResponse>,
+    pub aangemaakt_door: Uuid,
+    pub aangemaakt_op: DateTime<Utc>,
+    pub uitgevoerd_op: Option<DateTime<Utc>>,
+}
+
+impl Vernietigingslijst {
+    /// Stel een vernietigingslijst samen uit de gegeven archiefstukken
+    ///
+    /// Elk stuk wordt beoordeeld met [`ProvisaBeoordeling::beoordeel`]; stukken
+    /// die per `peildatum` vernietigbaar zijn komen op de lijst, de rest in
+    /// [`overgeslagen`](Self::overgeslagen).
+    pub fn samenstellen(
+        stukken: impl IntoIterator<Item = Archiefstuk>,
+        selectielijst: &ProvisaSelectielijst,
+        hotspots: Option<&HotspotRegister>,
+        peildatum: NaiveDate,
+        aangemaakt_door: Uuid,
+    ) -> Self {
+        let mut items = Vec::new();
+        let mut overgeslagen = Vec::new();
+
+        for stuk in stukken {
+            let beoordeling = ProvisaBeoordeling::beoordeel(
+                selectielijst,
+                &stuk.categorie,
+                &stuk.besluit_type,
+                stuk.creatie_datum,
+                hotspots,
+            );
+
+            let reden = if let Some(reden) = &stuk.bewaarplicht {
+                Some(OverslagReden::Bewaarplicht(reden.clone()))
+            } else if beoordeling.uiteindelijke_waarde == Archiefwaarde::Permanent {
+                Some(OverslagReden::Permanent)
+            } else if beoordeling.bewaartermijn.is_none() {
+                Some(OverslagReden::GeenBewaartermijn)
+            } else if !beoordeling.is_vernietigbaar_per(peildatum) {
+                beoordeling.vernietigingsdatum.map(OverslagReden::TermijnLooptNog)
+            } else {
+                None
+            };
+
+            match (reden, beoordeling.vernietigingsdatum) {
+                (None, Some(vernietigingsdatum)) => {
+                    let mut verantwoording = beoordeling.toelichting;
+                    verantwoording.push(format!(
+                        "Categorie {} / {}: bewaartermijn verstreken op {}",
+                        stuk.categorie, stuk.besluit_type, vernietigingsdatum
+                    ));
+                    items.push(VernietigingsItem {
+                        object_id: stuk.object_id,
+                        titel: stuk.titel,
+                        categorie: stuk.categorie,
+                        besluit_type: stuk.besluit_type,
+                        creatie_datum: stuk.creatie_datum,
+                        vernietigingsdatum,
+                        selectielijst_ref: beoordeling
+                            .bewaartermijn
+                            .map(|b| b.selectielijst_ref)
+                            .unwrap_or_default(),
+                        verantwoording,
+                        opslag_locatie: stuk.opslag_locatie,
+                        uitzondering: None,
+                        vernietigd_op: None,
+                    });
+                }
+                (reden, _) => overgeslagen.push(OvergeslagenStuk {
+                    object_id: stuk.object_id,
+                    titel: stuk.titel,
+                    reden: reden.unwrap_or(OverslagReden::GeenBewaartermijn),
+                }),
+            }
+        }
+
+        Self {
+            id: Uuid::new_v4(),
+            peildatum,
+            selectielijst: selectielijst.naam.clone(),
+            versie: selectielijst.versie,
+            orgaan: selectielijst.orgaan,
+            status: VernietigingslijstStatus::Concept,
+            items,
+            overgeslagen,
+            beoordelaars: Vec::new(),
+            beoordelingen: Vec::new(),
+            aangemaakt_door,
+            aangemaakt_op: Utc::now(),
+            uitgevoerd_op: None,
+        }
+    }
+
+    /// Stukken die bij uitvoering vernietigd worden
+    pub fn te_vernietigen(&self) -> impl Iterator<Item = &VernietigingsItem> {
+        self.items.iter().filter(|i| i.wordt_vernietigd())
+    }
+
+    /// Te vernietigen stukken die nog niet zijn vernietigd
+    pub fn openstaand(&self) -> impl Iterator<Item = &VernietigingsItem> {
+        self.te_vernietigen().filter(|i| i.vernietigd_op.is_none())
+    }
+
+    /// Bied de lijst aan ter beoordeling
+    pub fn ter_beoordeling(&mut self, beoordelaars: Vec<Uuid>) -> Result<(), VernietigingError> {
+        self.vereis(VernietigingslijstStatus::Concept, "ter beoordeling aanbieden")?;
+        if self.te_vernietigen().next().is_none() {
+            return Err(VernietigingError::GeenItems);
+        }
+        if beoordelaars.is_empty() {
+            return Err(VernietigingError::GeenBeoordelaars);
+        }
+        self.beoordelaars = beoordelaars;
+        self.status = VernietigingslijstStatus::TerBeoordeling;
+        Ok(())
+    }
+
+    /// Zonder een stuk uit van vernietiging
+    ///
+    /// Kan zolang de lijst nog niet is goedgekeurd. Het stuk blijft op de
+    /// lijst staan, met de reden, zodat de uitzondering verantwoord is.
+    pub fn sluit_uit(
+        &mut self,
+        object_id: Uuid,
+        door: Uuid,
+        reden: impl Into<String>,
+    ) -> Result<(), VernietigingError> {
+        if !matches!(
+            self.status,
+            VernietigingslijstStatus::Concept | VernietigingslijstStatus::TerBeoordeling
+        ) {
+            return Err(VernietigingError::OngeldigeStatus {
+                status: self.status,
+                actie: "stuk uitzonderen",
+            });
+        }
+        let item = self
+            .items
+            .iter_mut()
+            .find(|i| i.object_id == object_id)
+            .ok_or(VernietigingError::OnbekendStuk(object_id))?;
+        item.uitzondering = Some(Uitzondering {
+            door,
+            reden: reden.into(),
+            op: Utc::now(),
+        });
+        Ok(())
+    }
+
+    /// Verwerk het oordeel van een beoordelaar
+    ///
+    /// Een afwijzing wijst de lijst direct af. Bij delegatie neemt de
+    /// gedelegeerde de plaats van de beoordelaar in. Zodra alle beoordelaars
+    /// akkoord zijn is de lijst goedgekeurd.
+    pub fn beoordeel(&mut self, response: ApprovalResponse) -> Result<(), VernietigingError> {
+        self.vereis(VernietigingslijstStatus::TerBeoordeling, "beoordelen")?;
+        let beoordelaar = response.approver_id;
+        let Some(positie) = self.beoordelaars.iter().position(|b| *b == beoordelaar) else {
+            return Err(VernietigingError::GeenBeoordelaar(beoordelaar));
+        };
+        if self.beoordelingen.iter().any(|b| {
+            b.approver_id == beoordelaar && !matches!(b.decision, ApprovalDecision::Delegated { .. })
+        }) {
+            return Err(VernietigingError::AlBeoordeeld(beoordelaar));
+        }
+
+        match &response.decision {
+            ApprovalDecision::Rejected => self.status = VernietigingslijstStatus::Afgewezen,
+            ApprovalDecision::Delegated { to } => self.beoordelaars[positie] = *to,
+            ApprovalDecision::Approved => {}
+        }
+        self.beoordelingen.push(response);
+
+        let akkoord: HashSet<Uuid> = self
+            .beoordelingen
+            .iter()
+            .filter(|b| b.decision == ApprovalDecision::Approved)
+            .map(|b| b.approver_id)
+            .collect();
+        if self.status == VernietigingslijstStatus::TerBeoordeling
+            && self.beoordelaars.iter().all(|b| akkoord.contains(b))
+        {
+            self.status = VernietigingslijstStatus::Goedgekeurd;
+        }
+        Ok(())
+    }
+
+    /// Voer de goedgekeurde vernietiging uit
+    ///
+    /// Schrijft eerst een audit-regel [`AUDIT_ACTIE_GESTART`]; lukt dat niet,
+    /// dan wordt er niets vernietigd. Daarna wordt elk openstaand stuk via de
+    /// `uitvoerder` verwijderd. De ondertekende verklaring van deze uitvoering
+    /// wordt als [`AUDIT_ACTIE_UITGEVOERD`] vastgelegd.
+    ///
+    /// Mislukte stukken worden in de verklaring vermeld; de lijst is dan
+    /// [`DeelsUitgevoerd`](VernietigingslijstStatus::DeelsUitgevoerd) en kan
+    /// opnieuw worden uitgevoerd voor alleen die stukken. Pas als elk stuk is
+    /// vernietigd is de lijst [`Uitgevoerd`](VernietigingslijstStatus::Uitgevoerd).
+    ///
+    /// Een lijst die al met [`neem_in_uitvoering`](Self::neem_in_uitvoering)
+    /// is geclaimd wordt direct uitgevoerd. Kan de verklaring niet in het
+    /// audit log worden vastgelegd, dan zit ze in de fout
+    /// ([`VernietigingError::verklaring`]).
+    pub async fn uitvoeren(
+        &mut self,
+        uitvoerder: &dyn VernietigingsUitvoerder,
+        audit: &dyn AuditBackend,
+        tenant_id: &str,
+        uitgevoerd_door: &str,
+        sleutel: &[u8],
+    ) -> Result<VerklaringVanVernietiging, VernietigingError> {
+        if self.status != VernietigingslijstStatus::InUitvoering {
+            self.neem_in_uitvoering()?;
+        }
+
+        let gestart = AuditEntry::new(
+            tenant_id.to_string(),
+            uitgevoerd_door.to_string(),
+            AuditAction::Custom(AUDIT_ACTIE_GESTART.to_string()),
+            AUDIT_RESOURCE.to_string(),
+            self.id.to_string(),
+        )
+        .with_context(serde_json::json!({
+            "peildatum": self.peildatum,
+            "selectielijst": self.selectielijst,
+            "aantal": self.openstaand().count(),
+        }));
+        if let Err(e) = audit.write(&gestart).await {
+            // Er is niets vernietigd: geef de lijst weer vrij
+            self.status = if self.items.iter().any(|i| i.vernietigd_op.is_some()) {
+                VernietigingslijstStatus::DeelsUitgevoerd
+            } else {
+                VernietigingslijstStatus::Goedgekeurd
+            };
+            return Err(e.into());
+        }
+
+        let mut vernietigd = Vec::new();
+        let mut mislukt = Vec::new();
+        for item in self.items.iter_mut().filter(|i| i.wordt_vernietigd() && i.vernietigd_op.is_none()) {
+            match uitvoerder.vernietig(item).await {
+                Ok(()) => {
+                    item.vernietigd_op = Some(Utc::now());
+                    vernietigd.push(VernietigdStuk::from(&*item));
+                }
+                Err(e) => {
+                    tracing::warn!("Vernietiging van {} mislukt: {}", item.object_id, e);
+                    mislukt.push(MisluktStuk {
+                        object_id: item.object_id,
+                        titel: item.titel.clone(),
+                        fout: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        let uitgevoerd_op = Utc::now();
+        let mut verklaring = VerklaringVanVernietiging {
+            id: Uuid::new_v4(),
+            vernietigingslijst_id: self.id,
+            orgaan: self.orgaan,
+            selectielijst: self.selectielijst.clone(),
+            versie: self.versie,
+            peildatum: self.peildatum,
+            goedgekeurd_door: self
+                .beoordelingen
+                .iter()
+                .filter(|b| b.decision == ApprovalDecision::Approved)
+                .map(|b| b.approver_id)
+                .collect(),
+            vernietigd,
+            uitgezonderd: self.items.len() - self.te_vernietigen().count(),
+            mislukt,
+            uitgevoerd_door: uitgevoerd_door.to_string(),
+            uitgevoerd_op,
+            handtekening: None,
+        };
+        verklaring.onderteken(sleutel);
+
+        if verklaring.mislukt.is_empty() {
+            self.status = VernietigingslijstStatus::Uitgevoerd;
+            self.uitgevoerd_op = Some(uitgevoerd_op);
+        } else {
+            self.status = VernietigingslijstStatus::DeelsUitgevoerd;
+        }
+
+        let outcome = if verklaring.mislukt.is_empty() {
+            AuditOutcome::Success
+        } else {
+            AuditOutcome::Failed
+        };
+        let entry = verklaring
+            .audit_entry(tenant_id)
+            .with_parent(gestart.id)
+            .with_outcome(outcome);
+        if let Err(fout) = audit.write(&entry).await {
+            return Err(VernietigingError::VerklaringNietVastgelegd {
+                verklaring: Box::new(verklaring),
+                fout,
+            });
+        }
+        Ok(verklaring)
+    }
+
+    /// Claim de lijst voor uitvoering
+    ///
+    /// Kan alleen vanuit [`Goedgekeurd`](VernietigingslijstStatus::Goedgekeurd)
+    /// of [`DeelsUitgevoerd`](VernietigingslijstStatus::DeelsUitgevoerd), zodat
+    /// een lijst nooit twee keer tegelijk wordt uitgevoerd.
+    pub fn neem_in_uitvoering(&mut self) -> Result<(), VernietigingError> {
+        if !matches!(
+            self.status,
+            VernietigingslijstStatus::Goedgekeurd | VernietigingslijstStatus::DeelsUitgevoerd
+        ) {
+            return Err(VernietigingError::OngeldigeStatus {
+                status: self.status,
+                actie: "uitvoeren",
+            });
+        }
+        self.status = VernietigingslijstStatus::InUitvoering;
+        Ok(())
+    }
+
+    fn vereis(
+        &self,
+        status: VernietigingslijstStatus,
+        actie: &'static str,
+    ) -> Result<(), VernietigingError> {
+        if self.status == status {
+            Ok(())
+        } else {
+            Err(VernietigingError::OngeldigeStatus {
+                status: self.status,
+                actie,
+            })
+        }
+    }
+}
+
+/// Verwijdert een archiefstuk uit opslag en databases
+///
+/// De server levert een implementatie die de inhoud uit object storage en
+/// de rijen uit de databases verwijdert. Een implementatie moet idempotent
+/// zijn: een al verwijderd stuk is geen fout.
+#[async_trait]
+pub trait VernietigingsUitvoerder: Send + Sync {
+    async fn vernietig(&self, item: &VernietigingsItem) -> Result<(), VernietigingError>;
+}
+
+/// Een vernietigd stuk zoals vermeld in de verklaring
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VernietigdStuk {
+    pub object_id: Uuid,
+    pub titel: String,
+    pub categorie: PetraCategorie,
+    pub besluit_type: BesluitType,
+    pub selectielijst_ref: String,
+    pub vernietigingsdatum: NaiveDate,
+}
+
+impl From<&VernietigingsItem> for VernietigdStuk {
+    fn from(item: &VernietigingsItem) -> Self {
+        Self {
+            object_id: item.object_id,
+            titel: item.titel.clone(),
+            categorie: item.categorie,
+            besluit_type: item.besluit_type,
+            selectielijst_ref: item.selectielijst_ref.clone(),
+            vernietigingsdatum: item.vernietigingsdatum,
+        }
+    }
+}
+
+/// Een stuk waarvan de vernietiging mislukte
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MisluktStuk {
+    pub object_id: Uuid,
+    pub titel: String,
+    pub fout: String,
+}
+
+/// Verklaring van vernietiging
+///
+/// Bewijs dat de stukken op een goedgekeurde vernietigingslijst zijn
+/// vernietigd. Elke uitvoering levert een eigen verklaring op, met de
+/// stukken die in die uitvoering zijn vernietigd. De handtekening is een HMAC-SHA256 over de JSON van alle
+/// overige velden, zodat latere wijzigingen aantoonbaar zijn.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerklaringVanVernietiging {
+    pub id: Uuid,
+    pub vernietigingslijst_id: Uuid,
+    pub orgaan: ProvincieOrgaan,
+    pub selectielijst: String,
+    pub versie: ProvisaVersion,
+    pub peildatum: NaiveDate,
+    pub goedgekeurd_door: Vec<Uuid>,
+    pub vernietigd: Vec<VernietigdStuk>,
+    /// Aantal stukken dat door beoordelaars is uitgezonderd
+    pub uitgezonderd: usize,
+    pub mislukt: Vec<MisluktStuk>,
+    pub uitgevoerd_door: String,
+    pub uitgevoerd_op: DateTime<Utc>,
+    /// Hex-gecodeerde HMAC-SHA256
+    pub handtekening: Option<String>,
+}
+
+impl VerklaringVanVernietiging {
+    /// Onderteken de verklaring met de gegeven sleutel
+    pub fn onderteken(&mut self, sleutel: &[u8]) {
+        self.handtekening = Some(hex::encode(self.mac(sleutel).finalize().into_bytes()));
+    }
+
+    /// Controleer de handtekening
+    pub fn verifieer(&self, sleutel: &[u8]) -> bool {
+        let Some(handtekening) = self.handtekening.as_deref().and_then(|h| hex::decode(h).ok())
+        else {
+            return false;
+        };
+        self.mac(sleutel).verify_slice(&handtekening).is_ok()
+    }
+
+    /// Audit-regel waarmee de verklaring wordt bewaard
+    pub fn audit_entry(&self, tenant_id: &str) -> AuditEntry {
+        AuditEntry::new(
+            tenant_id.to_string(),
+            self.uitgevoerd_door.clone(),
+            AuditAction::Custom(AUDIT_ACTIE_UITGEVOERD.to_string()),
+            AUDIT_RESOURCE.to_string(),
+            self.vernietigingslijst_id.to_string(),
+        )
+        .with_context(serde_json::to_value(self).unwrap_or_default())
+    }
+
+    fn mac(&self, sleutel: &[u8]) -> Hmac<Sha256> {
+        let mut inhoud = self.clone();
+        inhoud.handtekening = None;
+        let bytes = serde_json::to_vec(&inhoud).unwrap_or_default();
+        let mut mac = Hmac::<Sha256>::new_from_slice(sleutel)
+            .expect("HMAC accepteert sleutels van elke lengte");
+        mac.update(&bytes);
+        mac
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provisa::{Hotspot, HotspotRegister};
+    use iou_core::audit::AuditQuery;
+    use iou_core::audit::models::AuditQueryResult;
+    use std::sync::Mutex;
+
+    fn datum(jaar: i32, maand: u32, dag: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(jaar, maand, dag).unwrap()
+    }
+
+    #[derive(Default)]
+    struct GeheugenAudit(Mutex<Vec<AuditEntry>>);
+
+    #[async_trait]
+    impl AuditBackend for GeheugenAudit {
+        async fn write(&self, entry: &AuditEntry) -> Result<(), AuditError> {
+            self.0.lock().unwrap().push(entry.clone());
+            Ok(())
+        }
+
+        async fn query(&self, _: &str, _: &AuditQuery) -> Result<AuditQueryResult, AuditError> {
+            let entries = self.0.lock().unwrap().clone();
+            Ok(AuditQueryResult {
+                total_count: entries.len() as u64,
+                entries,
+                has_more: false,
+                next_cursor: None,
+            })
+        }
+
+        async fn get(&self, id: Uuid) -> Result<Option<AuditEntry>, AuditError> {
+            Ok(self.0.lock().unwrap().iter().find(|e| e.id == id).cloned())
+        }
+    }
+
+    /// Accepteert alleen de eerste `ruimte` audit-regels
+    struct BeperkteAudit(Mutex<usize>);
+
+    #[async_trait]
+    impl AuditBackend for BeperkteAudit {
+        async fn write(&self, _: &AuditEntry) -> Result<(), AuditError> {
+            let mut ruimte = self.0.lock().unwrap();
+            if *ruimte == 0 {
+                return Err(AuditError::Unavailable);
+            }
+            *ruimte -= 1;
+            Ok(())
+        }
+
+        async fn query(&self, _: &str, _: &AuditQuery) -> Result<AuditQueryResult, AuditError> {
+            Err(AuditError::Unavailable)
+        }
+
+        async fn get(&self, _: Uuid) -> Result<Option<AuditEntry>, AuditError> {
+            Ok(None)
+        }
+    }
+
+    #[derive(Default)]
+    struct TestUitvoerder {
+        vernietigd: Mutex<Vec<Uuid>>,
+        weiger: Option<Uuid>,
+    }
+
+    #[async_trait]
+    impl VernietigingsUitvoerder for TestUitvoerder {
+        async fn vernietig(&self, item: &VernietigingsItem) -> Result<(), VernietigingError> {
+            if self.weiger == Some(item.object_id) {
+                return Err(VernietigingError::Uitvoering("opslag niet bereikbaar".to_string()));
+            }
+            self.vernietigd.lock().unwrap().push(item.object_id);
+            Ok(())
+        }
+    }
+
+    /// Contract (10 jaar), subsidie (20 jaar), verordening (permanent) en een stuk met bewaarplicht
+    fn stukken() -> Vec<Archiefstuk> {
+        vec![
+            Archiefstuk::new(
+                Uuid::new_v4(),
+                "Contract schoonmaak 2010",
+                PetraCategorie::Financien,
+                BesluitType::Contract,
+                datum(2010, 3, 1),
+            )
+            .met_opslag("s3://archief/contract-2010.pdf"),
+            Archiefstuk::new(
+                Uuid::new_v4(),
+                "Subsidie sportvereniging 2020",
+                PetraCategorie::Financien,
+                BesluitType::Subsidie,
+                datum(2020, 6, 1),
+            ),
+            Archiefstuk::new(
+                Uuid::new_v4(),
+                "Provinciale verordening 2005",
+                PetraCategorie::Bestuur,
+                BesluitType::Verordening,
+                datum(2005, 1, 1),
+            ),
+            Archiefstuk::new(
+                Uuid::new_v4(),
+                "Contract met bezwaar 2009",
+                PetraCategorie::Financien,
+                BesluitType::Contract,
+                datum(2009, 1, 1),
+            )
+            .met_bewaarplicht("Lopende bezwaarprocedure"),
+        ]
+    }
+
+    fn samengesteld() -> Vernietigingslijst {
+        Vernietigingslijst::samenstellen(
+            stukken(),
+            &ProvisaSelectielijst::provinciaal_2020(),
+            None,
+            datum(2024, 1, 1),
+            Uuid::new_v4(),
+        )
+    }
+
+    #[test]
+    fn test_samenstellen_selecteert_verlopen_stukken() {
+        let lijst = samengesteld();
+        assert_eq!(lijst.status, VernietigingslijstStatus::Concept);
+        assert_eq!(lijst.items.len(), 1);
+        let item = &lijst.items[0];
+        assert_eq!(item.titel, "Contract schoonmaak 2010");
+        assert_eq!(item.vernietigingsdatum, datum(2020, 3, 1));
+        assert!(!item.selectielijst_ref.is_empty());
+        assert!(item.verantwoording.iter().any(|v| v.contains("10 jaar")));
+
+        let redenen: Vec<_> = lijst.overgeslagen.iter().map(|o| &o.reden).collect();
+        assert!(redenen.contains(&&OverslagReden::TermijnLooptNog(datum(2040, 6, 1))));
+        assert!(redenen.contains(&&OverslagReden::Permanent));
+        assert!(redenen.contains(&&OverslagReden::Bewaarplicht("Lopende bezwaarprocedure".to_string())));
+    }
+
+    #[test]
+    fn test_hotspot_houdt_stuk_buiten_de_lijst() {
+        let mut register = HotspotRegister::new("Flevoland");
+        register.voeg_toe(
+            Hotspot::new("HS-01", "Aanbesteding", "Parlementaire enquête", datum(2009, 1, 1))
+                .met_categorieen(vec![PetraCategorie::Financien]),
+        );
+        let lijst = Vernietigingslijst::samenstellen(
+            stukken(),
+            &ProvisaSelectielijst::provinciaal_2020(),
+            Some(&register),
+            datum(2024, 1, 1),
+            Uuid::new_v4(),
+        );
+        assert!(lijst.items.is_empty());
+        assert!(lijst
+            .overgeslagen
+            .iter()
+            .any(|o| o.titel == "Contract schoonmaak 2010" && o.reden == OverslagReden::Permanent));
+    }
+
+    #[test]
+    fn test_beoordeling_vereist_alle_beoordelaars() {
+        let mut lijst = samengesteld();
+        let (archivaris, eigenaar) = (Uuid::new_v4(), Uuid::new_v4());
+
+        assert!(matches!(
+            lijst.beoordeel(ApprovalResponse::new(archivaris, ApprovalDecision::Approved, None)),
+            Err(VernietigingError::OngeldigeStatus { .. })
+        ));
+        lijst.ter_beoordeling(vec![archivaris, eigenaar]).unwrap();
+
+        lijst
+            .beoordeel(ApprovalResponse::new(archivaris, ApprovalDecision::Approved, None))
+            .unwrap();
+        assert_eq!(lijst.status, VernietigingslijstStatus::TerBeoordeling);
+        assert!(matches!(
+            lijst.beoordeel(ApprovalResponse::new(archivaris, ApprovalDecision::Approved, None)),
+            Err(VernietigingError::AlBeoordeeld(_))
+        ));
+        assert!(matches!(
+            lijst.beoordeel(ApprovalResponse::new(Uuid::new_v4(), ApprovalDecision::Approved, None)),
+            Err(VernietigingError::GeenBeoordelaar(_))
+        ));
+
+        let plaatsvervanger = Uuid::new_v4();
+        lijst
+            .beoordeel(ApprovalResponse::new(
+                eigenaar,
+                ApprovalDecision::Delegated { to: plaatsvervanger },
+                None,
+            ))
+            .unwrap();
+        assert_eq!(lijst.status, VernietigingslijstStatus::TerBeoordeling);
+        lijst
+            .beoordeel(
+                ApprovalResponse::new(plaatsvervanger, ApprovalDecision::Approved, None)
+                    .with_delegation(eigenaar),
+            )
+            .unwrap();
+        assert_eq!(lijst.status, VernietigingslijstStatus::Goedgekeurd);
+    }
+
+    #[test]
+    fn test_afwijzing_en_uitzondering() {
+        let mut lijst = samengesteld();
+        let archivaris = Uuid::new_v4();
+        let object_id = lijst.items[0].object_id;
+
+        lijst.sluit_uit(object_id, archivaris, "Nodig voor onderzoek").unwrap();
+        assert!(matches!(
+            lijst.ter_beoordeling(vec![archivaris]),
+            Err(VernietigingError::GeenItems)
+        ));
+        assert!(matches!(
+            lijst.sluit_uit(Uuid::new_v4(), archivaris, "x"),
+            Err(VernietigingError::OnbekendStuk(_))
+        ));
+
+        let mut lijst = samengesteld();
+        lijst.ter_beoordeling(vec![archivaris]).unwrap();
+        lijst
+            .beoordeel(ApprovalResponse::new(
+                archivaris,
+                ApprovalDecision::Rejected,
+                Some("Selectie onvolledig".to_string()),
+            ))
+            .unwrap();
+        assert_eq!(lijst.status, VernietigingslijstStatus::Afgewezen);
+    }
+
+    #[tokio::test]
+    async fn test_uitvoeren_legt_ondertekende_verklaring_vast() {
+        let stukken: Vec<_> = (0..3)
+            .map(|i| {
+                Archiefstuk::new(
+                    Uuid::new_v4(),
+                    format!("Contract {}", i),
+                    PetraCategorie::Financien,
+                    BesluitType::Contract,
+                    datum(2010, 1, 1),
+                )
+            })
+            .collect();
+        let (uitgezonderd, weiger) = (stukken[1].object_id, stukken[2].object_id);
+        let archivaris = Uuid::new_v4();
+        let mut lijst = Vernietigingslijst::samenstellen(
+            stukken,
+            &ProvisaSelectielijst::provinciaal_2020(),
+            None,
+            datum(2024, 1, 1),
+            archivaris,
+        );
+        lijst.ter_beoordeling(vec![archivaris]).unwrap();
+        lijst.sluit_uit(uitgezonderd, archivaris, "Bewaren voor tentoonstelling").unwrap();
+
+        let audit = GeheugenAudit::default();
+        let uitvoerder = TestUitvoerder {
+            weiger: Some(weiger),
+            ..Default::default()
+        };
+        let sleutel = b"archief-sleutel";
+        assert!(matches!(
+            lijst.uitvoeren(&uitvoerder, &audit, "flevoland", "did:web:archivaris", sleutel).await,
+            Err(VernietigingError::OngeldigeStatus { .. })
+        ));
+        assert!(audit.0.lock().unwrap().is_empty());
+
+        lijst
+            .beoordeel(ApprovalResponse::new(archivaris, ApprovalDecision::Approved, None))
+            .unwrap();
+        let verklaring = lijst
+            .uitvoeren(
+                &uitvoerder,
+                &audit,
+                "flevoland",
+                "did:web:archivaris",
+                sleutel,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(lijst.status, VernietigingslijstStatus::DeelsUitgevoerd);
+        assert_eq!(lijst.uitgevoerd_op, None);
+        assert_eq!(uitvoerder.vernietigd.lock().unwrap().len(), 1);
+        assert_eq!(verklaring.vernietigd.len(), 1);
+        assert_eq!(verklaring.uitgezonderd, 1);
+        assert_eq!(verklaring.mislukt[0].object_id, weiger);
+        assert_eq!(verklaring.goedgekeurd_door, vec![archivaris]);
+
+        assert!(verklaring.verifieer(sleutel));
+        assert!(!verklaring.verifieer(b"andere-sleutel"));
+        let mut vervalst = verklaring.clone();
+        vervalst.vernietigd.clear();
+        assert!(!vervalst.verifieer(sleutel));
+
+        let entries = audit.0.lock().unwrap().clone();
+        assert_eq!(entries.len(), 2);
+        assert!(matches!(&entries[0].action, AuditAction::Custom(a) if a == AUDIT_ACTIE_GESTART));
+        assert!(matches!(&entries[1].action, AuditAction::Custom(a) if a == AUDIT_ACTIE_UITGEVOERD));
+        assert_eq!(entries[1].parent_id, Some(entries[0].id));
+        assert_eq!(entries[1].outcome, AuditOutcome::Failed);
+        let bewaard: VerklaringVanVernietiging =
+            serde_json::from_value(entries[1].context.clone().unwrap()).unwrap();
+        assert!(bewaard.verifieer(sleutel));
+
+        // Een volgende uitvoering vernietigt alleen het mislukte stuk
+        let uitvoerder = TestUitvoerder::default();
+        let verklaring = lijst
+            .uitvoeren(&uitvoerder, &audit, "flevoland", "did:web:archivaris", sleutel)
+            .await
+            .unwrap();
+        assert_eq!(*uitvoerder.vernietigd.lock().unwrap(), vec![weiger]);
+        assert_eq!(verklaring.vernietigd[0].object_id, weiger);
+        assert!(verklaring.mislukt.is_empty());
+        assert_eq!(lijst.status, VernietigingslijstStatus::Uitgevoerd);
+        assert!(lijst.uitgevoerd_op.is_some());
+        assert_eq!(lijst.openstaand().count(), 0);
+        assert!(matches!(
+            lijst.uitvoeren(&uitvoerder, &audit, "flevoland", "did:web:archivaris", sleutel).await,
+            Err(VernietigingError::OngeldigeStatus { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_uitvoering_wordt_geclaimd_en_bewaart_de_verklaring() {
+        let archivaris = Uuid::new_v4();
+        let mut lijst = samengesteld();
+        lijst.ter_beoordeling(vec![archivaris]).unwrap();
+        lijst
+            .beoordeel(ApprovalResponse::new(archivaris, ApprovalDecision::Approved, None))
+            .unwrap();
+
+        lijst.neem_in_uitvoering().unwrap();
+        assert_eq!(lijst.status, VernietigingslijstStatus::InUitvoering);
+        assert!(matches!(
+            lijst.neem_in_uitvoering(),
+            Err(VernietigingError::OngeldigeStatus { .. })
+        ));
+
+        // Zonder write-ahead audit-regel wordt niets vernietigd en komt de lijst vrij
+        let uitvoerder = TestUitvoerder::default();
+        let sleutel = b"archief-sleutel";
+        let geen_audit = BeperkteAudit(Mutex::new(0));
+        assert!(matches!(
+            lijst.uitvoeren(&uitvoerder, &geen_audit, "flevoland", "did:web:archivaris", sleutel).await,
+            Err(VernietigingError::Audit(_))
+        ));
+        assert_eq!(lijst.status, VernietigingslijstStatus::Goedgekeurd);
+        assert!(uitvoerder.vernietigd.lock().unwrap().is_empty());
+
+        // Mislukt alleen het vastleggen van de verklaring, dan zit die in de fout
+        let alleen_gestart = BeperkteAudit(Mutex::new(1));
+        let fout = lijst
+            .uitvoeren(&uitvoerder, &alleen_gestart, "flevoland", "did:web:archivaris", sleutel)
+            .await
+            .unwrap_err();
+        let verklaring = fout.verklaring().unwrap();
+        assert_eq!(verklaring.vernietigd.len(), 1);
+        assert!(verklaring.verifieer(sleutel));
+        assert_eq!(lijst.status, VernietigingslijstStatus::Uitgevoerd);
+    }
+}
@@ -0,0 +1,158 @@
+//! Minimal XML element tree for the DMN and BPMN parsers
+//!
+//! DMN and BPMN files are small enough to load completely, and both
+//! parsers need random access to children and attributes. This module
+//! turns a `quick-xml` event stream into a tree of [`XmlElement`]s keyed
+//! by local name, so callers don't have to care whether a file uses the
+//! `dmn:`/`bpmn:` prefix or a default namespace.
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+/// A parsed XML element
+#[derive(Debug, Clone, Default)]
+pub(crate) struct XmlElement {
+    /// Local element name (e.g. `decisionTable`)
+    pub name: String,
+    /// Attributes as `(qualified name, value)` pairs, in document order
+    pub attributes: Vec<(String, String)>,
+    /// Child elements, in document order
+    pub children: Vec<XmlElement>,
+    /// Concatenated text and CDATA content directly inside this element
+    pub text: String,
+}
+
+impl XmlElement {
+    /// Look up an attribute by its local name, ignoring any prefix
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| local(key) == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// First child with the given local name
+    pub fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    /// All children with the given local name
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> + 'a {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    /// Trimmed text of the first child with the given local name
+    pub fn child_text(&self, name: &str) -> Option<&str> {
+        self.child(name).map(|c| c.text.trim())
+    }
+}
+
+/// Strip the prefix from a qualified name
+fn local(qname: &str) -> &str {
+    qname.rsplit(':').next().unwrap_or(qname)
+}
+
+/// Parse an XML document and return its root element
+pub(crate) fn parse(xml: &str) -> Result<XmlElement, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut stack: Vec<XmlElement> = Vec::new();
+    let mut root: Option<XmlElement> = None;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("XML error at position {}: {}", reader.buffer_position(), e))?;
+
+        match event {
+            Event::Start(start) => stack.push(element_from(&start)?),
+            Event::Empty(start) => {
+                let element = element_from(&start)?;
+                attach(&mut stack, &mut root, element)?;
+            }
+            Event::End(_) => {
+                let element = stack.pop().ok_or("Unexpected closing tag")?;
+                attach(&mut stack, &mut root, element)?;
+            }
+            Event::Text(text) => {
+                if let Some(current) = stack.last_mut() {
+                    let text = text.unescape().map_err(|e| e.to_string())?;
+                    current.text.push_str(&text);
+                }
+            }
+            Event::CData(data) => {
+                if let Some(current) = stack.last_mut() {
+                    let data = data.decode().map_err(|e| e.to_string())?;
+                    current.text.push_str(&data);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !stack.is_empty() {
+        return Err(format!("Unclosed element <{}>", stack[stack.len() - 1].name));
+    }
+
+    root.ok_or_else(|| "Document has no root element".to_string())
+}
+
+fn element_from(start: &BytesStart<'_>) -> Result<XmlElement, String> {
+    let mut element = XmlElement {
+        name: String::from_utf8_lossy(start.local_name().as_ref()).into_owned(),
+        ..Default::default()
+    };
+
+    for attr in start.attributes() {
+        let attr = attr.map_err(|e| e.to_string())?;
+        let key = String::from_utf8_lossy(attr.key.as_ref()).into_owned();
+        let value = attr.unescape_value().map_err(|e| e.to_string())?.into_owned();
+        element.attributes.push((key, value));
+    }
+
+    Ok(element)
+}
+
+fn attach(
+    stack: &mut [XmlElement],
+    root: &mut Option<XmlElement>,
+    element: XmlElement,
+) -> Result<(), String> {
+    match stack.last_mut() {
+        Some(parent) => parent.children.push(element),
+        None if root.is_none() => *root = Some(element),
+        None => return Err("Document has more than one root element".to_string()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_prefixed_and_default_namespace() {
+        let xml = r#"<?xml version="1.0"?>
+            <dmn:definitions xmlns:dmn="urn:x" id="defs">
+                <dmn:decision id="d1" name="Een &amp; twee">
+                    <text><![CDATA[a < b]]></text>
+                    <empty/>
+                </dmn:decision>
+            </dmn:definitions>"#;
+
+        let root = parse(xml).unwrap();
+        assert_eq!(root.name, "definitions");
+        assert_eq!(root.attr("id"), Some("defs"));
+
+        let decision = root.child("decision").unwrap();
+        assert_eq!(decision.attr("name"), Some("Een & twee"));
+        assert_eq!(decision.child_text("text"), Some("a < b"));
+        assert!(decision.child("empty").is_some());
+    }
+
+    #[test]
+    fn test_parse_rejects_unbalanced_document() {
+        assert!(parse("<a><b></a>").is_err());
+        assert!(parse("").is_err());
+    }
+}
@@ -0,0 +1,28 @@
+-- BPMN process instances of the embedded iou-regels engine (DuckDB)
+
+CREATE TABLE IF NOT EXISTS bpmn_process_instances (
+    id UUID PRIMARY KEY,
+    definition_id VARCHAR NOT NULL,
+    state VARCHAR NOT NULL,
+    variables_json JSON NOT NULL,
+    tokens_json JSON NOT NULL,
+    current_activity VARCHAR,
+    started_at TIMESTAMPTZ NOT NULL,
+    completed_at TIMESTAMPTZ,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_bpmn_process_instances_state
+    ON bpmn_process_instances(state);
+
+-- Append-only: rows are never updated or deleted
+CREATE TABLE IF NOT EXISTS bpmn_activity_history (
+    instance_id UUID NOT NULL,
+    seq INTEGER NOT NULL,
+    element_id VARCHAR NOT NULL,
+    element_name VARCHAR NOT NULL,
+    element_type VARCHAR NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL,
+    completed_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (instance_id, seq)
+);
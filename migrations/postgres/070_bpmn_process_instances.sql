@@ -0,0 +1,57 @@
+-- ============================================================
+-- BPMN Process Instances Migration
+-- ============================================================
+-- Durable state for processes run by the embedded iou-regels
+-- BPMN engine: variables and waiting tokens per instance, plus
+-- an append-only history of completed activities for auditors.
+-- ============================================================
+
+-- ============================================================
+-- BPMN_PROCESS_INSTANCES table
+-- ============================================================
+CREATE TABLE IF NOT EXISTS bpmn_process_instances (
+    id UUID PRIMARY KEY,
+    definition_id VARCHAR NOT NULL,
+    state VARCHAR NOT NULL CHECK (state IN (
+        'running', 'suspended', 'completed', 'failed', 'terminated'
+    )),
+    variables JSONB NOT NULL DEFAULT '{}',
+    tokens JSONB NOT NULL DEFAULT '[]',  -- Waiting tokens (task, timer, join)
+    current_activity VARCHAR,
+    started_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    completed_at TIMESTAMP WITH TIME ZONE,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_bpmn_process_instances_state
+    ON bpmn_process_instances(state);
+CREATE INDEX IF NOT EXISTS idx_bpmn_process_instances_definition
+    ON bpmn_process_instances(definition_id);
+
+-- ============================================================
+-- BPMN_ACTIVITY_HISTORY table
+-- ============================================================
+-- Completed activities per instance, in completion order
+CREATE TABLE IF NOT EXISTS bpmn_activity_history (
+    instance_id UUID NOT NULL REFERENCES bpmn_process_instances(id),
+    seq INTEGER NOT NULL,
+    element_id VARCHAR NOT NULL,
+    element_name VARCHAR NOT NULL,
+    element_type VARCHAR NOT NULL,
+    started_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    completed_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (instance_id, seq)
+);
+
+-- History is an audit trail: forbid changes after insert
+CREATE OR REPLACE FUNCTION bpmn_activity_history_immutable()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'bpmn_activity_history is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_bpmn_activity_history_immutable ON bpmn_activity_history;
+CREATE TRIGGER trg_bpmn_activity_history_immutable
+    BEFORE UPDATE OR DELETE ON bpmn_activity_history
+    FOR EACH ROW EXECUTE FUNCTION bpmn_activity_history_immutable();
@@ -0,0 +1,157 @@
+-- ============================================================
+-- Vernietigingslijsten Migration
+-- ============================================================
+-- Disposal runs under the Archiefwet: the destruction list with
+-- per-item justification and reviews (as JSONB, see
+-- iou_regels::Vernietigingslijst), and the signed certificates
+-- of destruction produced when a list is executed.
+--
+-- Objects are selected from information_objects using
+-- metadata->>'petra_categorie' and metadata->>'besluit_type';
+-- metadata->>'bewaarplicht' keeps an object off every list.
+--
+-- Lists belong to one organization and only contain objects
+-- from that organization's information domains. They are
+-- composed with the selectielijst and hotspot register the
+-- organization configured in archiefinstellingen.
+-- ============================================================
+
+-- ============================================================
+-- ARCHIEFINSTELLINGEN table
+-- ============================================================
+-- Both documents are JSON exports as read by
+-- iou_regels::provisa::import (SelectielijstImport and
+-- HotspotRegister::lees); the API validates them on upload.
+CREATE TABLE IF NOT EXISTS archiefinstellingen (
+    organization_id UUID PRIMARY KEY,
+    selectielijst JSONB,
+    hotspotregister JSONB,
+    bijgewerkt_door UUID NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- ============================================================
+-- VERNIETIGINGSLIJSTEN table
+-- ============================================================
+CREATE TABLE IF NOT EXISTS vernietigingslijsten (
+    id UUID PRIMARY KEY,
+    organization_id UUID NOT NULL,
+    status VARCHAR NOT NULL CHECK (status IN (
+        'concept', 'ter_beoordeling', 'goedgekeurd', 'afgewezen',
+        'in_uitvoering', 'deels_uitgevoerd', 'uitgevoerd'
+    )),
+    peildatum DATE NOT NULL,
+    selectielijst VARCHAR NOT NULL,
+    lijst JSONB NOT NULL,
+    aangemaakt_door UUID NOT NULL,
+    aangemaakt_op TIMESTAMP WITH TIME ZONE NOT NULL,
+    uitgevoerd_op TIMESTAMP WITH TIME ZONE,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_vernietigingslijsten_org
+    ON vernietigingslijsten(organization_id);
+CREATE INDEX IF NOT EXISTS idx_vernietigingslijsten_status
+    ON vernietigingslijsten(status);
+CREATE INDEX IF NOT EXISTS idx_vernietigingslijsten_peildatum
+    ON vernietigingslijsten(peildatum DESC);
+
+-- ============================================================
+-- VERKLARINGEN_VAN_VERNIETIGING table
+-- ============================================================
+-- One signed certificate per execution; a partly executed list
+-- (deels_uitgevoerd) gets another one when it is executed again
+CREATE TABLE IF NOT EXISTS verklaringen_van_vernietiging (
+    id UUID PRIMARY KEY,
+    vernietigingslijst_id UUID NOT NULL REFERENCES vernietigingslijsten(id),
+    verklaring JSONB NOT NULL,
+    handtekening VARCHAR(64) NOT NULL,  -- Hex HMAC-SHA256
+    uitgevoerd_door VARCHAR NOT NULL,
+    uitgevoerd_op TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_verklaringen_van_vernietiging_lijst
+    ON verklaringen_van_vernietiging(vernietigingslijst_id, uitgevoerd_op DESC);
+
+-- Certificates are evidence: forbid changes after insert
+CREATE OR REPLACE FUNCTION verklaringen_van_vernietiging_immutable()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'verklaringen_van_vernietiging is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_verklaringen_van_vernietiging_immutable ON verklaringen_van_vernietiging;
+CREATE TRIGGER trg_verklaringen_van_vernietiging_immutable
+    BEFORE UPDATE OR DELETE ON verklaringen_van_vernietiging
+    FOR EACH ROW EXECUTE FUNCTION verklaringen_van_vernietiging_immutable();
+
+-- ============================================================
+-- Row-Level Security
+-- ============================================================
+ALTER TABLE archiefinstellingen ENABLE ROW LEVEL SECURITY;
+ALTER TABLE vernietigingslijsten ENABLE ROW LEVEL SECURITY;
+ALTER TABLE verklaringen_van_vernietiging ENABLE ROW LEVEL SECURITY;
+
+-- Archiefinstellingen: Organization isolation
+CREATE POLICY org_isolation_select ON archiefinstellingen
+  FOR SELECT
+  TO authenticated
+  USING (organization_id = auth.organization_id());
+
+CREATE POLICY org_isolation_insert ON archiefinstellingen
+  FOR INSERT
+  TO authenticated
+  WITH CHECK (organization_id = auth.organization_id());
+
+CREATE POLICY org_isolation_update ON archiefinstellingen
+  FOR UPDATE
+  TO authenticated
+  USING (organization_id = auth.organization_id())
+  WITH CHECK (organization_id = auth.organization_id());
+
+-- Vernietigingslijsten: Organization isolation
+CREATE POLICY org_isolation_select ON vernietigingslijsten
+  FOR SELECT
+  TO authenticated
+  USING (organization_id = auth.organization_id());
+
+CREATE POLICY org_isolation_insert ON vernietigingslijsten
+  FOR INSERT
+  TO authenticated
+  WITH CHECK (organization_id = auth.organization_id());
+
+CREATE POLICY org_isolation_update ON vernietigingslijsten
+  FOR UPDATE
+  TO authenticated
+  USING (organization_id = auth.organization_id())
+  WITH CHECK (organization_id = auth.organization_id());
+
+CREATE POLICY org_isolation_delete ON vernietigingslijsten
+  FOR DELETE
+  TO authenticated
+  USING (organization_id = auth.organization_id());
+
+-- Verklaringen: via the list they certify (append-only, so no
+-- update or delete policy)
+CREATE POLICY org_isolation_select ON verklaringen_van_vernietiging
+  FOR SELECT
+  TO authenticated
+  USING (
+    EXISTS (
+      SELECT 1 FROM vernietigingslijsten
+      WHERE vernietigingslijsten.id = verklaringen_van_vernietiging.vernietigingslijst_id
+      AND vernietigingslijsten.organization_id = auth.organization_id()
+    )
+  );
+
+CREATE POLICY org_isolation_insert ON verklaringen_van_vernietiging
+  FOR INSERT
+  TO authenticated
+  WITH CHECK (
+    EXISTS (
+      SELECT 1 FROM vernietigingslijsten
+      WHERE vernietigingslijsten.id = vernietigingslijst_id
+      AND vernietigingslijsten.organization_id = auth.organization_id()
+    )
+  );
@@ -0,0 +1,34 @@
+-- ============================================================
+-- Woo Deadline Events Migration
+-- ============================================================
+-- Events that move the legal decision term of a Woo request:
+-- verdaging, zienswijze of third parties, opschorting, uitstel
+-- and ingebrekestelling. Each event is stored as JSONB (see
+-- iou_core::sla::CaseEvent); the due date is recomputed from
+-- received_date and the full event list, and written back to
+-- woo_requests.decision_due_date.
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS woo_request_deadline_events (
+    id UUID PRIMARY KEY,
+    request_id UUID NOT NULL REFERENCES woo_requests(id) ON DELETE CASCADE,
+    event JSONB NOT NULL,
+    event_date DATE NOT NULL,
+    recorded_by UUID,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_woo_deadline_events_request
+    ON woo_request_deadline_events(request_id, event_date, created_at);
+
+-- The Woo decision term is 4 weeks (art. 4.4 Woo), not the 8 weeks
+-- of the former Wob default; the API computes the exact date.
+CREATE OR REPLACE FUNCTION set_woo_decision_due_date()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.decision_due_date IS NULL THEN
+        NEW.decision_due_date := NEW.received_date + INTERVAL '4 weeks';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
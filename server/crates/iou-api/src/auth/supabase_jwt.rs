@@ -9,6 +9,8 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use std::env;
 
+use iou_core::tenancy::TenantId;
+
 use crate::middleware::{AuthContext, Role};
 
 /// Errors that can occur during Supabase JWT verification
@@ -37,6 +39,9 @@ pub enum SupabaseAuthError {
 
     #[error("Invalid organization ID format")]
     InvalidOrganizationId,
+
+    #[error("Invalid municipality format")]
+    InvalidMunicipality,
 }
 
 /// Standard claims in a Supabase JWT token
@@ -51,6 +56,7 @@ pub enum SupabaseAuthError {
 ///
 /// Custom claims (via JWT hooks in Supabase):
 /// - `organization_id`: User's organization UUID
+/// - `municipality`: Municipality of the organization (e.g. `utrecht`)
 /// - `clearance`: Security clearance level
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SupabaseClaims {
@@ -70,6 +76,10 @@ pub struct SupabaseClaims {
     #[serde(default)]
     pub organization_id: Option<String>,
 
+    /// Municipality of the organization (custom claim)
+    #[serde(default)]
+    pub municipality: Option<String>,
+
     /// Security clearance level (custom claim)
     #[serde(default)]
     pub clearance: Option<String>,
@@ -198,6 +208,11 @@ impl SupabaseJwtVerifier {
             .parse::<Uuid>()
             .map_err(|_| SupabaseAuthError::InvalidOrganizationId)?;
 
+        let municipality = claims.municipality
+            .map(TenantId::new)
+            .transpose()
+            .map_err(|_| SupabaseAuthError::InvalidMunicipality)?;
+
         // Convert app_roles to our Role enum
         let roles: Vec<Role> = claims
             .app_roles
@@ -218,6 +233,10 @@ impl SupabaseJwtVerifier {
             email: claims.email,
             organization_id,
             roles,
+            municipality,
+            loa: None,
+            department: None,
+            delegations: Vec::new(),
         })
     }
 }
@@ -248,6 +267,7 @@ mod tests {
             role: "authenticated".to_string(),
             email: email.to_string(),
             organization_id: Some(organization_id.to_string()),
+            municipality: None,
             clearance: Some("intern".to_string()),
             app_roles: Some(vec!["domain_viewer".to_string()]),
             exp: (chrono::Utc::now() + chrono::Duration::hours(1)).timestamp(),
@@ -330,6 +350,7 @@ mod tests {
             role: "authenticated".to_string(),
             email: "test@example.com".to_string(),
             organization_id: Some(Uuid::new_v4().to_string()),
+            municipality: None,
             clearance: None,
             app_roles: None,
             exp: (chrono::Utc::now() - chrono::Duration::hours(1)).timestamp(), // Expired
@@ -17,6 +17,7 @@ use uuid::Uuid;
 use iou_core::domain::{DomainStatus, DomainType, InformationDomain};
 use iou_core::objects::{InformationObject, ObjectType};
 use iou_ai::PipelineCheckpoint;
+use iou_regels::{ActivityRecord, ProcessInstance, ProcessInstanceState};
 
 /// Persisted fields to rebuild a document generation request for Camunda workers.
 #[derive(Debug, Clone)]
@@ -55,6 +56,53 @@ fn parse_optional_datetime(s: Option<String>) -> Option<DateTime<Utc>> {
     s.map(|ds| parse_datetime(&ds))
 }
 
+const PROCESS_INSTANCE_SELECT: &str = r#"
+    SELECT id, definition_id, state, CAST(variables_json AS VARCHAR), CAST(tokens_json AS VARCHAR),
+           current_activity, CAST(started_at AS VARCHAR), CAST(completed_at AS VARCHAR)
+    FROM bpmn_process_instances"#;
+
+/// Raw columns of a `bpmn_process_instances` row
+type ProcessInstanceColumns = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    Option<String>,
+    String,
+    Option<String>,
+);
+
+fn process_instance_columns(row: &duckdb::Row<'_>) -> DuckResult<ProcessInstanceColumns> {
+    Ok((
+        row.get(0)?,
+        row.get(1)?,
+        row.get(2)?,
+        row.get(3)?,
+        row.get(4)?,
+        row.get(5)?,
+        row.get(6)?,
+        row.get(7)?,
+    ))
+}
+
+/// Build a process instance (without history) from its raw columns
+fn process_instance_from_columns(columns: ProcessInstanceColumns) -> anyhow::Result<ProcessInstance> {
+    let (id, definition_id, state, variables, tokens, current_activity, started_at, completed_at) =
+        columns;
+    Ok(ProcessInstance {
+        id: Uuid::parse_str(&id)?,
+        definition_id,
+        state: state.parse()?,
+        variables: serde_json::from_str(&variables)?,
+        current_activity,
+        started_at: parse_datetime(&started_at),
+        completed_at: parse_optional_datetime(completed_at),
+        tokens: serde_json::from_str(&tokens)?,
+        history: vec![],
+    })
+}
+
 /// Database wrapper met thread-safe connection
 #[derive(Clone)]
 pub struct Database {
@@ -144,6 +192,22 @@ impl Database {
             }
         }
 
+        let bpmn_schema = include_str!("../../../migrations/005_bpmn_process_instances.sql");
+        for statement in bpmn_schema.split(';') {
+            // Drop comment lines so a statement preceded by a comment still runs
+            let stmt = statement
+                .lines()
+                .filter(|line| !line.trim_start().starts_with("--"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let stmt = stmt.trim();
+            if !stmt.is_empty() {
+                // Every statement is idempotent, so any error is real
+                conn.execute(stmt, [])
+                    .map_err(|e| anyhow::anyhow!("BPMN schema statement failed: {}", e))?;
+            }
+        }
+
         tracing::info!("Database schema initialized");
         Ok(())
     }
@@ -380,6 +444,20 @@ impl Database {
         Ok(())
     }
 
+    /// Remove information objects and their AI suggestions
+    ///
+    /// Used when objects are destroyed; ids that are not present are skipped.
+    pub fn delete_objects(&self, ids: &[Uuid]) -> anyhow::Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        for id in ids {
+            conn.execute("DELETE FROM ai_metadata_suggestions WHERE object_id = ?", params![id.to_string()])?;
+            conn.execute("DELETE FROM information_objects WHERE id = ?", params![id.to_string()])?;
+        }
+
+        Ok(())
+    }
+
     // ============================================
     // SEARCH OPERATIONS
     // ============================================
@@ -1595,6 +1673,135 @@ impl Database {
         }
     }
 
+    // ============================================
+    // BPMN PROCESS INSTANCES
+    // ============================================
+
+    /// Insert or update a process instance and append its new history records
+    pub fn save_process_instance_row(&self, instance: &ProcessInstance) -> anyhow::Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let id = instance.id.to_string();
+
+        tx.execute(
+            r#"
+            INSERT INTO bpmn_process_instances
+                (id, definition_id, state, variables_json, tokens_json, current_activity,
+                 started_at, completed_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (id) DO UPDATE SET
+                state = excluded.state,
+                variables_json = excluded.variables_json,
+                tokens_json = excluded.tokens_json,
+                current_activity = excluded.current_activity,
+                completed_at = excluded.completed_at,
+                updated_at = excluded.updated_at
+            "#,
+            params![
+                id,
+                instance.definition_id,
+                instance.state.to_string(),
+                serde_json::to_string(&instance.variables)?,
+                serde_json::to_string(&instance.tokens)?,
+                instance.current_activity,
+                datetime_to_string(&instance.started_at),
+                instance.completed_at.as_ref().map(datetime_to_string),
+                datetime_to_string(&Utc::now()),
+            ],
+        )?;
+
+        // History is append-only: only insert records beyond those already stored
+        let stored: i64 = tx.query_row(
+            "SELECT COUNT(*) FROM bpmn_activity_history WHERE instance_id = ?",
+            [&id],
+            |row| row.get(0),
+        )?;
+        for (seq, record) in instance.history.iter().enumerate().skip(stored as usize) {
+            tx.execute(
+                r#"
+                INSERT INTO bpmn_activity_history
+                    (instance_id, seq, element_id, element_name, element_type, started_at, completed_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                "#,
+                params![
+                    id,
+                    seq as i64,
+                    record.element_id,
+                    record.element_name,
+                    record.element_type,
+                    datetime_to_string(&record.started_at),
+                    datetime_to_string(&record.completed_at),
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Load a process instance with its full history
+    pub fn load_process_instance_row(&self, id: Uuid) -> anyhow::Result<Option<ProcessInstance>> {
+        let mut instance = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare(&format!("{} WHERE id = ?", PROCESS_INSTANCE_SELECT))?;
+            match stmt.query_row([id.to_string()], process_instance_columns) {
+                Ok(columns) => process_instance_from_columns(columns)?,
+                Err(duckdb::Error::QueryReturnedNoRows) => return Ok(None),
+                Err(e) => return Err(e.into()),
+            }
+        };
+
+        instance.history = self.load_process_history_rows(id)?;
+        Ok(Some(instance))
+    }
+
+    /// All running process instances, with their history
+    pub fn list_running_process_instance_rows(&self) -> anyhow::Result<Vec<ProcessInstance>> {
+        let rows = {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare(&format!(
+                "{} WHERE state = ? ORDER BY started_at",
+                PROCESS_INSTANCE_SELECT
+            ))?;
+            stmt.query_map([ProcessInstanceState::Running.to_string()], process_instance_columns)?
+                .collect::<DuckResult<Vec<_>>>()?
+        };
+
+        let mut instances = Vec::with_capacity(rows.len());
+        for columns in rows {
+            let mut instance = process_instance_from_columns(columns)?;
+            instance.history = self.load_process_history_rows(instance.id)?;
+            instances.push(instance);
+        }
+        Ok(instances)
+    }
+
+    /// Activity history of a process instance, oldest first
+    pub fn load_process_history_rows(&self, id: Uuid) -> anyhow::Result<Vec<ActivityRecord>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT element_id, element_name, element_type,
+                   CAST(started_at AS VARCHAR), CAST(completed_at AS VARCHAR)
+            FROM bpmn_activity_history
+            WHERE instance_id = ?
+            ORDER BY seq
+            "#,
+        )?;
+        let records = stmt
+            .query_map([id.to_string()], |row| {
+                Ok(ActivityRecord {
+                    element_id: row.get(0)?,
+                    element_name: row.get(1)?,
+                    element_type: row.get(2)?,
+                    started_at: parse_datetime(&row.get::<_, String>(3)?),
+                    completed_at: parse_datetime(&row.get::<_, String>(4)?),
+                })
+            })?
+            .collect::<DuckResult<Vec<_>>>()?;
+        Ok(records)
+    }
+
     // ============================================
     // ASYNC WRAPPERS (for compatibility with async route handlers)
     // ============================================
@@ -1606,6 +1813,13 @@ impl Database {
             .await?
     }
 
+    /// Async wrapper for delete_objects (information_objects table)
+    pub async fn delete_objects_async(&self, ids: Vec<Uuid>) -> anyhow::Result<()> {
+        let db = self.clone();
+        tokio::task::spawn_blocking(move || db.delete_objects(&ids))
+            .await?
+    }
+
     /// Async wrapper for get_document (documents table)
     pub async fn get_document_async(&self, id: Uuid) -> anyhow::Result<Option<iou_core::document::DocumentMetadata>> {
         let db = self.clone();
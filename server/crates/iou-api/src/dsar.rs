@@ -5,6 +5,7 @@
 
 use anyhow::Result;
 use chrono::{DateTime, Utc};
+use iou_core::sla::CaseEvent;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sqlx::{PgPool, postgres::types::PgInterval, FromRow, Row};
@@ -919,6 +920,18 @@ impl WooRepository {
     // Woo Publication Operations
     // ============================================
 
+    /// Domain and classification of an information object, for access decisions
+    pub async fn object_access_attributes(&self, object_id: Uuid) -> Result<Option<(Uuid, Option<String>)>> {
+        let row = sqlx::query("SELECT domain_id, classification FROM information_objects WHERE id = $1")
+            .bind(object_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(match row {
+            Some(r) => Some((r.try_get(0)?, r.try_get(1)?)),
+            None => None,
+        })
+    }
+
     pub async fn create_publication(
         &self,
         id: Uuid,
@@ -1413,6 +1426,78 @@ impl WooRepository {
             .collect()
     }
 
+    /// Date the request was received, the start of the decision term
+    pub async fn get_received_date(&self, id: Uuid) -> Result<Option<chrono::NaiveDate>> {
+        let date = sqlx::query_scalar("SELECT received_date FROM woo_requests WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(date)
+    }
+
+    /// Deadline events of a request in date order
+    pub async fn list_deadline_events(&self, request_id: Uuid) -> Result<Vec<CaseEvent>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT event FROM woo_request_deadline_events
+            WHERE request_id = $1
+            ORDER BY event_date ASC, created_at ASC
+            "#,
+        )
+        .bind(request_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| Ok(serde_json::from_value(row.try_get("event")?)?))
+            .collect()
+    }
+
+    pub async fn add_deadline_event(
+        &self,
+        request_id: Uuid,
+        event: &CaseEvent,
+        recorded_by: Uuid,
+    ) -> Result<Uuid> {
+        let id = Uuid::new_v4();
+
+        sqlx::query(
+            r#"
+            INSERT INTO woo_request_deadline_events (id, request_id, event, event_date, recorded_by)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(id)
+        .bind(request_id)
+        .bind(serde_json::to_value(event)?)
+        .bind(event.date())
+        .bind(recorded_by)
+        .execute(&self.pool)
+        .await?;
+
+        tracing::info!("Woo request {} deadline event recorded: {:?}", request_id, event);
+        Ok(id)
+    }
+
+    /// Store a due date recomputed from the deadline events
+    pub async fn update_decision_due_date(&self, id: Uuid, due_date: chrono::NaiveDate) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE woo_requests
+            SET decision_due_date = $1, updated_at = $2
+            WHERE id = $3
+            "#,
+        )
+        .bind(due_date)
+        .bind(Utc::now())
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn get_published_documents(
         &self,
         limit: i32,
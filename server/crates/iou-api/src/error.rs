@@ -53,6 +53,7 @@ impl From<S3Error> for ApiError {
             S3Error::S3Error(msg) => ApiError::Internal(anyhow::anyhow!("S3 error: {}", msg)),
             S3Error::HttpError { code, message } => ApiError::Internal(anyhow::anyhow!("S3 HTTP {}: {}", code, message)),
             S3Error::MissingEnvVar(var) => ApiError::Internal(anyhow::anyhow!("Missing env var: {}", var)),
+            S3Error::InvalidKey(key) => ApiError::Validation(format!("Invalid storage key: {}", key)),
         }
     }
 }
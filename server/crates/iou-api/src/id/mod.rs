@@ -8,7 +8,12 @@
 //!    [`nl_wallet::start_disclosure_session`] en de upstream
 //!    [wallet_web README](https://github.com/MinBZK/nl-wallet/blob/main/wallet_web/README.md)).
 //!
-//! 2. **API-token** — [`crate::routes::auth::wallet_auth`] (`POST /api/auth/wallet`) valideert
+//! 2. **Eigen OpenID4VP-verifier** — [`openid4vp`]: IOU ondertekent zelf het request object
+//!    (JAR), ontvangt de `vp_token` via `direct_post` en verifieert SD-JWT VC's en mdocs.
+//!    `POST /api/id/openid4vp/sessions` met een `dcql_query` of `presentation_definition`
+//!    geeft de `openid4vp://`-link voor de QR-code; de frontend pollt de `status_url`.
+//!
+//! 3. **API-token** — [`crate::routes::auth::wallet_auth`] (`POST /api/auth/wallet`) valideert
 //!    een ingediende Verifiable Presentation en geeft een IOU JWT uit.
 //!
 //! # Omgeving
@@ -18,5 +23,11 @@
 //! | `NL_WALLET_VERIFICATION_SERVER_URL` | Basis-URL van `verification_server` (bijv. `http://127.0.0.1:3011` na `./scripts/nl-wallet-verification-up.sh`). Vereist voor `POST .../id/nl-wallet/sessions`. Zie repo **`docs/nl-wallet-e2e.md`**. |
 //! | `VC_TRUSTED_ISSUERS` | Komma-gescheiden trusted issuer DIDs voor wallet-auth. |
 //! | `VC_STRICT_MODE` | `true` / `1` weigert issuers buiten de trustlijst. |
+//! | `OPENID4VP_CLIENT_ID` | Client identifier met prefix, bijv. `x509_san_dns:iou.gemeente.nl`. Zonder deze en `OPENID4VP_PUBLIC_URL` geven de OpenID4VP-endpoints 503. |
+//! | `OPENID4VP_PUBLIC_URL` | Publieke basis-URL van de API, voor `request_uri` en `response_uri`. |
+//! | `OPENID4VP_SIGNING_KEY` | Base64 PKCS#8 (P-256 of Ed25519) voor het request object; zonder wordt een tijdelijke sleutel gegenereerd. |
+//! | `OPENID4VP_CERTIFICATE_CHAIN` | Komma-gescheiden base64 DER-certificaten van de verifier (`x5c`), leaf eerst. |
+//! | `OPENID4VP_TRUST_ANCHORS_FILE` | PEM met vertrouwde IACA's (mdoc) en X.509-issuers (SD-JWT VC met `x5c`). DID-issuers gaan via de trust registry. |
 
 pub mod nl_wallet;
+pub mod openid4vp;
@@ -0,0 +1,209 @@
+//! Eigen OpenID4VP-verifier: wallets presenteren direct aan IOU, zonder NL Wallet
+//! `verification_server`.
+//!
+//! Een sessie bewaart het authorization request (nonce, state, query) tot de wallet via
+//! `direct_post` antwoordt. Sessies staan in het geheugen en verlopen na
+//! [`SESSION_TTL_SECONDS`]; na een herstart moet de gebruiker opnieuw scannen.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use iou_core::ssi::{
+    AuthorizationRequest, AuthorizationResponse, Certificate, DisclosedCredential, Openid4vpVerifier, PresentationQuery,
+    SigningKey, TrustRegistry,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::vc::VcConfig;
+
+/// Levensduur van een sessie, van aanmaken tot uitlezen van het resultaat
+pub const SESSION_TTL_SECONDS: i64 = 600;
+
+/// Configuratie uit de omgeving
+pub struct Openid4vpConfig {
+    /// Client identifier met prefix, bijv. `x509_san_dns:iou.gemeente.nl`
+    pub client_id: String,
+    /// Publieke basis-URL van deze API, voor `request_uri` en `response_uri`
+    pub public_url: String,
+    pub signing_key: SigningKey,
+    /// Certificaatketen van de verifier (base64 DER, leaf eerst) voor `x5c`
+    pub certificate_chain: Vec<String>,
+    /// IACA's en X.509-issuers die de verifier vertrouwt
+    pub trust_anchors: Vec<Certificate>,
+}
+
+impl Openid4vpConfig {
+    /// `OPENID4VP_CLIENT_ID`, `OPENID4VP_PUBLIC_URL`, `OPENID4VP_SIGNING_KEY` (base64 PKCS#8),
+    /// `OPENID4VP_CERTIFICATE_CHAIN` (komma-gescheiden base64 DER) en
+    /// `OPENID4VP_TRUST_ANCHORS_FILE` (PEM).
+    ///
+    /// `None` zonder client id of publieke URL; zonder sleutel wordt er een tijdelijke
+    /// gegenereerd (alleen voor ontwikkeling: wallets vertrouwen die niet).
+    pub fn from_env() -> Result<Option<Self>, String> {
+        let var = |name: &str| std::env::var(name).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        let (Some(client_id), Some(public_url)) = (var("OPENID4VP_CLIENT_ID"), var("OPENID4VP_PUBLIC_URL")) else {
+            return Ok(None);
+        };
+
+        let signing_key = match var("OPENID4VP_SIGNING_KEY") {
+            Some(encoded) => iou_core::ssi::encoding::base64_decode(&encoded)
+                .map_err(|e| format!("OPENID4VP_SIGNING_KEY: {}", e))
+                .and_then(|der| SigningKey::from_pkcs8(&der).map_err(|e| format!("OPENID4VP_SIGNING_KEY: {}", e)))?,
+            None => {
+                tracing::warn!("OPENID4VP_SIGNING_KEY not set; signing request objects with a temporary key");
+                SigningKey::generate_p256().map_err(|e| e.to_string())?
+            }
+        };
+        let certificate_chain = var("OPENID4VP_CERTIFICATE_CHAIN")
+            .map(|chain| chain.split(',').map(|c| c.trim().to_string()).filter(|c| !c.is_empty()).collect())
+            .unwrap_or_default();
+        let trust_anchors = match var("OPENID4VP_TRUST_ANCHORS_FILE") {
+            Some(path) => {
+                let pem = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path, e))?;
+                Certificate::from_pem(&pem).map_err(|e| format!("{}: {}", path, e))?
+            }
+            None => Vec::new(),
+        };
+
+        Ok(Some(Self {
+            client_id,
+            public_url: public_url.trim_end_matches('/').to_string(),
+            signing_key,
+            certificate_chain,
+            trust_anchors,
+        }))
+    }
+}
+
+/// Stand van een sessie zoals de frontend die pollt
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum SessionStatus {
+    /// Wacht tot de wallet het request object ophaalt
+    Pending,
+    /// De wallet heeft het request; wacht op de gebruiker
+    RequestRetrieved,
+    Completed { credentials: Vec<DisclosedCredential> },
+    Failed { reason: String },
+}
+
+struct Session {
+    request: AuthorizationRequest,
+    created_at: DateTime<Utc>,
+    status: SessionStatus,
+}
+
+/// Nieuwe sessie: wat de frontend nodig heeft voor QR-code of same-device link
+#[derive(Debug, Serialize)]
+pub struct CreatedSession {
+    pub session_id: Uuid,
+    /// `openid4vp://` link met `client_id` en `request_uri`
+    pub authorization_uri: String,
+    pub request_uri: String,
+    pub status_url: String,
+}
+
+/// Verifier en lopende sessies
+pub struct Openid4vpService {
+    config: Openid4vpConfig,
+    verifier: Openid4vpVerifier,
+    sessions: DashMap<Uuid, Session>,
+}
+
+impl Openid4vpService {
+    pub fn new(config: Openid4vpConfig, vc: &VcConfig, trust_registry: Arc<TrustRegistry>) -> Self {
+        let verifier = Openid4vpVerifier::new(Arc::new(vc.did_resolver()))
+            .with_trust_anchors(config.trust_anchors.clone())
+            .with_trust_registry(trust_registry)
+            .with_status_lists(Arc::new(vc.status_list_client()));
+        Self { config, verifier, sessions: DashMap::new() }
+    }
+
+    /// Service uit de omgeving; `None` als OpenID4VP niet geconfigureerd is
+    pub fn from_env(trust_registry: Arc<TrustRegistry>) -> Option<Arc<Self>> {
+        match Openid4vpConfig::from_env() {
+            Ok(Some(config)) => Some(Arc::new(Self::new(config, &VcConfig::from_env(), trust_registry))),
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!("OpenID4VP verifier disabled: {}", e);
+                None
+            }
+        }
+    }
+
+    fn session_url(&self, id: Uuid) -> String {
+        format!("{}/api/id/openid4vp/sessions/{}", self.config.public_url, id)
+    }
+
+    /// Start een sessie voor `query`
+    pub fn create_session(&self, query: PresentationQuery) -> CreatedSession {
+        let id = Uuid::new_v4();
+        let request = AuthorizationRequest::new(
+            self.config.client_id.clone(),
+            format!("{}/response", self.session_url(id)),
+            query,
+        );
+        let request_uri = format!("{}/request", self.session_url(id));
+        let created = CreatedSession {
+            session_id: id,
+            authorization_uri: request.authorization_link(&request_uri),
+            request_uri,
+            status_url: self.session_url(id),
+        };
+
+        let now = Utc::now();
+        self.sessions.retain(|_, session| !expired(session, now));
+        self.sessions.insert(id, Session { request, created_at: now, status: SessionStatus::Pending });
+        created
+    }
+
+    /// Ondertekend request object voor de wallet; `None` voor onbekende of verlopen sessies
+    pub fn request_object(&self, id: Uuid) -> Option<Result<String, String>> {
+        let mut session = self.sessions.get_mut(&id).filter(|session| !expired(session, Utc::now()))?;
+        if matches!(session.status, SessionStatus::Pending) {
+            session.status = SessionStatus::RequestRetrieved;
+        }
+        Some(
+            session
+                .request
+                .sign(&self.config.signing_key, &self.config.certificate_chain, Utc::now())
+                .map_err(|e| e.to_string()),
+        )
+    }
+
+    /// Verwerk het antwoord van de wallet; een sessie wordt maar één keer afgerond
+    pub async fn complete(&self, id: Uuid, response: AuthorizationResponse) -> Option<SessionStatus> {
+        let request = {
+            let session = self.sessions.get(&id).filter(|session| !expired(session, Utc::now()))?;
+            if matches!(session.status, SessionStatus::Completed { .. } | SessionStatus::Failed { .. }) {
+                return Some(SessionStatus::Failed { reason: "session already completed".into() });
+            }
+            session.request.clone()
+        };
+
+        let status = match self.verifier.verify(&request, &response).await {
+            Ok(credentials) => SessionStatus::Completed { credentials },
+            Err(e) => {
+                tracing::info!("OpenID4VP session {} failed: {}", id, e);
+                SessionStatus::Failed { reason: e.to_string() }
+            }
+        };
+        if let Some(mut session) = self.sessions.get_mut(&id) {
+            session.status = status.clone();
+        }
+        Some(status)
+    }
+
+    pub fn status(&self, id: Uuid) -> Option<SessionStatus> {
+        self.sessions
+            .get(&id)
+            .filter(|session| !expired(session, Utc::now()))
+            .map(|session| session.status.clone())
+    }
+}
+
+fn expired(session: &Session, now: DateTime<Utc>) -> bool {
+    session.created_at + Duration::seconds(SESSION_TTL_SECONDS) < now
+}
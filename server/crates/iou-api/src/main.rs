@@ -36,6 +36,8 @@ mod supabase_utils;
 mod workflows;
 mod websockets;
 mod orchestrator;
+mod process_store;
+mod vernietiging;
 mod vc;
 mod id;
 mod realtime;
@@ -47,9 +49,12 @@ use workflows::WorkflowEngine;
 use orchestrator::types::StatusMessage;
 use websockets::types::DocumentStatus;
 use websockets::documents::WebSocketState;
-use iou_core::storage::S3Client;
+use iou_core::storage::{FilesystemStorage, MemoryStorage, S3Client, StorageBackend};
 use iou_ai::graphrag::KnowledgeGraph;
 use supabase::SupabasePool;
+use process_store::{DuckdbProcessInstanceStore, PostgresProcessInstanceStore};
+use iou_core::audit::PostgresAuditBackend;
+use iou_regels::{BpmnProcessEngine, DmnEvaluator, ProcessInstanceStore};
 use realtime::RealtimeService;
 use routes::realtime::realtime_router;
 
@@ -71,6 +76,9 @@ async fn main() -> anyhow::Result<()> {
     let s3_client: Arc<S3Client> = match S3Client::new_from_env() {
         Ok(client) => {
             tracing::info!("S3 client created. Bucket: {}", client.bucket_name());
+            if let Err(e) = client.validate().await {
+                tracing::warn!("S3 bucket {} is not usable: {}", client.bucket_name(), e);
+            }
             Arc::new(client)
         }
         Err(e) => {
@@ -88,6 +96,21 @@ async fn main() -> anyhow::Result<()> {
         }
     };
 
+    // Document storage: S3 by default, or content-addressed local storage
+    // for single-node deployments without MinIO
+    let storage: Arc<dyn StorageBackend> = match std::env::var("STORAGE_BACKEND").as_deref() {
+        Ok("filesystem") => {
+            let path = std::env::var("STORAGE_PATH").unwrap_or_else(|_| "./data/storage".to_string());
+            tracing::info!("Document storage: filesystem at {}", path);
+            Arc::new(FilesystemStorage::new(path)?)
+        }
+        Ok("memory") => {
+            tracing::warn!("Document storage: in memory, documents are lost on restart");
+            Arc::new(MemoryStorage::new())
+        }
+        _ => s3_client.clone(),
+    };
+
     // Initialize DuckDB database
     let db = Database::new(&config.database_path)?;
     db.initialize_schema()?;
@@ -182,6 +205,64 @@ async fn main() -> anyhow::Result<()> {
         camunda: camunda_gateway,
     });
 
+    // Trusted credential issuers per credential type and municipality
+    let trust_registry = routes::v1::trust_registry::trust_registry_from_env();
+
+    // Native OpenID4VP verifier, when OPENID4VP_CLIENT_ID and OPENID4VP_PUBLIC_URL are set
+    let openid4vp = id::openid4vp::Openid4vpService::from_env(trust_registry.clone());
+
+    // BPMN process engine; instances are stored after every step so they
+    // resume after a restart
+    let process_store: Arc<dyn ProcessInstanceStore> = match &supabase_pool {
+        Some(pool) => Arc::new(PostgresProcessInstanceStore::new(pool.inner().clone())),
+        None => Arc::new(DuckdbProcessInstanceStore(db_arc.clone())),
+    };
+    let process_engine =
+        Arc::new(BpmnProcessEngine::new(Arc::new(DmnEvaluator::new())).with_store(process_store));
+    {
+        let engine = process_engine.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+            loop {
+                interval.tick().await;
+                if let Err(e) = engine.trigger_stored_timers(chrono::Utc::now()).await {
+                    tracing::warn!("BPMN timers not triggered: {}", e);
+                }
+            }
+        });
+    }
+
+    // Process endpoints authenticate with a Verifiable Presentation and
+    // write to the PostgreSQL audit log
+    let processes = match &supabase_pool {
+        Some(pool) => {
+            let vc_state = middleware::vc::VcState::from_config(
+                &vc::VcConfig::from_env(),
+                trust_registry.clone(),
+                iou_core::audit::shared_logger(PostgresAuditBackend::new(pool.inner().clone())),
+            )?;
+            Router::new()
+                .route("/processes", post(routes::v1::start_process))
+                .route("/processes/{id}", get(routes::v1::get_process))
+                .route("/processes/{id}/history", get(routes::v1::get_process_history))
+                .with_state(process_engine)
+                .layer(axum_middleware::from_fn_with_state(vc_state.clone(), middleware::vc::vc_middleware))
+                .merge(
+                    Router::new()
+                        .route("/vc/challenge", post(middleware::vc::issue_challenge))
+                        .with_state(vc_state),
+                )
+        }
+        None => {
+            tracing::info!("No DATABASE_URL set. BPMN process endpoints will be disabled.");
+            Router::new()
+        }
+    };
+
+    // Access policies: the built-in policy plus tenant policies from IOU_POLICY_DIR
+    let policies = middleware::policy_engine();
+    tracing::info!("Access policies loaded for {} tenant(s)", policies.tenants().len());
+
     // Build API router
     let api = Router::new()
         // Health check (no auth required)
@@ -196,6 +277,11 @@ async fn main() -> anyhow::Result<()> {
             "/id/nl-wallet/sessions",
             post(routes::id::nl_wallet_create_session),
         )
+        // OpenID4VP verifier — request object (JAR) and direct_post response
+        .route("/id/openid4vp/sessions", post(routes::id::openid4vp_create_session))
+        .route("/id/openid4vp/sessions/{id}", get(routes::id::openid4vp_session_status))
+        .route("/id/openid4vp/sessions/{id}/request", get(routes::id::openid4vp_request_object))
+        .route("/id/openid4vp/sessions/{id}/response", post(routes::id::openid4vp_response))
         // Context endpoints
         .route("/context/{id}", get(routes::context::get_context))
         .route("/domains", get(routes::context::list_domains))
@@ -266,6 +352,40 @@ async fn main() -> anyhow::Result<()> {
         .route("/data-erasure/{id}", get(routes::v1::get_erasure))
         .route("/data-erasure/{id}/approve", put(routes::v1::approve_erasure))
         .route("/admin/dsar/pending", get(routes::v1::list_pending_dsar))
+        // Audit trail: paging, export, verification and retention
+        .route("/audit/entries", get(routes::v1::list_audit_entries))
+        .route("/audit/export", get(routes::v1::export_audit_entries))
+        .route("/audit/verify", get(routes::v1::verify_audit_chain))
+        .route("/admin/audit/retention", post(routes::v1::apply_audit_retention))
+        // Trusted credential issuers for wallet login
+        .route("/admin/trust-registry", get(routes::v1::list_trusted_issuers))
+        .route("/admin/trust-registry/issuers", post(routes::v1::upsert_trusted_issuer))
+        .route("/admin/trust-registry/issuers/{id}", delete(routes::v1::remove_trusted_issuer))
+        .route("/admin/trust-registry/load", post(routes::v1::load_trust_registry))
+        .route("/admin/trust-registry/reload", post(routes::v1::reload_trust_registry))
+        // Access policies (ABAC) per tenant
+        .route("/admin/policies", get(routes::v1::list_policies))
+        .route("/admin/policies/explain", post(routes::v1::explain_decision))
+        .route(
+            "/admin/policies/{tenant}",
+            get(routes::v1::get_tenant_policy)
+                .put(routes::v1::put_tenant_policy)
+                .delete(routes::v1::delete_tenant_policy),
+        )
+        .route("/admin/policies/{tenant}/test", post(routes::v1::test_tenant_policy))
+        // Disposal runs (Archiefwet vernietigingslijsten)
+        .route("/vernietigingslijsten", get(routes::v1::list_vernietigingslijsten))
+        .route("/vernietigingslijsten", post(routes::v1::create_vernietigingslijst))
+        .route("/vernietigingslijsten/{id}", get(routes::v1::get_vernietigingslijst))
+        .route("/vernietigingslijsten/{id}/ter-beoordeling", post(routes::v1::submit_vernietigingslijst))
+        .route("/vernietigingslijsten/{id}/uitzonderen", post(routes::v1::exclude_from_vernietigingslijst))
+        .route("/vernietigingslijsten/{id}/beoordeling", post(routes::v1::review_vernietigingslijst))
+        .route("/vernietigingslijsten/{id}/uitvoeren", post(routes::v1::execute_vernietigingslijst))
+        .route("/vernietigingslijsten/{id}/verklaring", get(routes::v1::get_verklaring_van_vernietiging))
+        .route(
+            "/archiefinstellingen/{instelling}",
+            get(routes::v1::get_archiefinstelling).put(routes::v1::put_archiefinstelling),
+        )
         // Woo Publication (Wet open overheid)
         .route("/documents/{id}/request-woo-publication", post(routes::v1::request_woo_publication))
         .route("/woo-publications", get(routes::v1::list_woo_publications))
@@ -277,6 +397,8 @@ async fn main() -> anyhow::Result<()> {
         .route("/woo-requests", post(routes::v1::create_woo_request))
         .route("/woo-requests", get(routes::v1::list_woo_requests))
         .route("/woo-requests/{id}", get(routes::v1::get_woo_request))
+        .route("/woo-requests/{id}/deadline", get(routes::v1::get_woo_request_deadline))
+        .route("/woo-requests/{id}/deadline-events", post(routes::v1::add_woo_deadline_event))
         .route("/woo/statistics", get(routes::v1::get_woo_statistics))
         .route("/woo/upcoming-deadlines", get(routes::v1::get_woo_deadlines))
         .route("/woo/published-documents", get(routes::v1::get_published_woo_documents))
@@ -309,6 +431,8 @@ async fn main() -> anyhow::Result<()> {
         .route("/tags/untag", post(routes::v1::tags::untag_object))
         .route("/domains/{id}/tags", get(routes::v1::tags::get_domain_tags))
         .route("/tags/merge", post(routes::v1::tags::merge_tags))
+        // BPMN processes
+        .merge(processes)
         // Realtime API endpoints
         .nest("/realtime", realtime_router())
         // 3D Buildings proxy
@@ -348,9 +472,12 @@ async fn main() -> anyhow::Result<()> {
         .layer(Extension(orchestrator_status_tx))
         .layer(Extension(doc_status_tx))
         .layer(Extension(s3_client))
+        .layer(Extension(storage))
         .layer(Extension(ws_state))
         .layer(Extension(document_workflow_rt))
         .layer(Extension(supabase_pool))
+        .layer(Extension(trust_registry))
+        .layer(Extension(openid4vp))
         .layer(Extension(realtime_service));
 
     // Start server
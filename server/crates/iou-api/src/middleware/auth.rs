@@ -17,7 +17,14 @@ use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation}
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use iou_core::delegation::Delegation;
+use iou_core::DelegationService;
+use iou_core::tenancy::{LoA, TenantId};
+use iou_regels::abac::{Resource, Subject};
+
+use super::policy::{authorize, policy_engine};
 use crate::error::ApiError;
+use crate::supabase::SupabasePool;
 
 /// JWT secret key (should come from environment in production)
 const JWT_SECRET: &[u8] = b"iou-secret-key-change-in-production";
@@ -40,6 +47,19 @@ pub struct Claims {
     /// User roles
     pub roles: Vec<String>,
 
+    /// Municipality of the organization, as credentials name it (e.g.
+    /// `utrecht`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub municipality: Option<String>,
+
+    /// Level of assurance of the login (`low`, `substantial`, `high`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loa: Option<String>,
+
+    /// Department of the user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub department: Option<String>,
+
     /// Token issuance time
     pub iat: i64,
 
@@ -57,6 +77,14 @@ pub struct AuthContext {
     pub email: String,
     pub organization_id: Uuid,
     pub roles: Vec<Role>,
+    /// Municipality of the organization; trust registry entries and
+    /// credentials are keyed by it
+    pub municipality: Option<TenantId>,
+    /// Level of assurance of the login; `None` if the token does not say
+    pub loa: Option<LoA>,
+    pub department: Option<String>,
+    /// Active delegations the user received
+    pub delegations: Vec<Delegation>,
 }
 
 /// Role definitions for RBAC
@@ -66,6 +94,8 @@ pub enum Role {
     // System roles
     Admin,
     Auditor,
+    /// Manages every organization on the platform
+    PlatformAdmin,
 
     // Domain roles
     DomainManager,
@@ -87,81 +117,11 @@ pub enum Role {
 }
 
 impl Role {
-    /// Check if this role has a specific permission
+    /// Check if this role has a specific permission under the built-in policy
     pub fn has_permission(&self, permission: Permission) -> bool {
-        match self {
-            Role::Admin => true, // Admin has all permissions
-
-            Role::Auditor => matches!(
-                permission,
-                Permission::DomainRead
-                    | Permission::ObjectRead
-                    | Permission::AuditView
-                    | Permission::ComplianceAssess
-            ),
-
-            Role::DomainManager => matches!(
-                permission,
-                Permission::DomainCreate
-                    | Permission::DomainRead
-                    | Permission::DomainUpdate
-                    | Permission::DomainArchive
-                    | Permission::ObjectRead
-            ),
-
-            Role::DomainEditor => matches!(
-                permission,
-                Permission::DomainRead | Permission::DomainUpdate | Permission::ObjectRead
-            ),
-
-            Role::DomainViewer => matches!(permission, Permission::DomainRead | Permission::ObjectRead),
-
-            Role::ObjectCreator => matches!(
-                permission,
-                Permission::ObjectCreate | Permission::ObjectRead
-            ),
-
-            Role::ObjectEditor => matches!(
-                permission,
-                Permission::ObjectCreate
-                    | Permission::ObjectRead
-                    | Permission::ObjectUpdate
-            ),
-
-            Role::ObjectApprover => matches!(
-                permission,
-                Permission::ObjectRead
-                    | Permission::ObjectUpdate
-                    | Permission::ComplianceAssess
-                    | Permission::ComplianceApprove
-            ),
-
-            Role::ComplianceOfficer => matches!(
-                permission,
-                Permission::ComplianceAssess
-                    | Permission::ComplianceApprove
-                    | Permission::ObjectRead
-                    | Permission::ObjectClassify
-            ),
-
-            Role::ComplianceReviewer => matches!(
-                permission,
-                Permission::ComplianceAssess | Permission::ObjectRead
-            ),
-
-            Role::WooOfficer => matches!(
-                permission,
-                Permission::ObjectRead
-                    | Permission::ObjectClassify
-                    | Permission::WooPublish
-                    | Permission::ComplianceAssess
-            ),
-
-            Role::WooPublisher => matches!(
-                permission,
-                Permission::WooPublish | Permission::ObjectRead
-            ),
-        }
+        policy_engine()
+            .decide(None, &Subject::with_roles([self.to_string()]), permission.as_str(), &Resource::default())
+            .is_permitted()
     }
 }
 
@@ -193,6 +153,34 @@ pub enum Permission {
     RoleManage,
     OrganizationManage,
     AuditView,
+    /// Act on organizations other than one's own
+    PlatformManage,
+}
+
+impl Permission {
+    /// Action name in access policies
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::DomainCreate => "domain_create",
+            Permission::DomainRead => "domain_read",
+            Permission::DomainUpdate => "domain_update",
+            Permission::DomainDelete => "domain_delete",
+            Permission::DomainArchive => "domain_archive",
+            Permission::ObjectCreate => "object_create",
+            Permission::ObjectRead => "object_read",
+            Permission::ObjectUpdate => "object_update",
+            Permission::ObjectDelete => "object_delete",
+            Permission::ObjectClassify => "object_classify",
+            Permission::ComplianceAssess => "compliance_assess",
+            Permission::ComplianceApprove => "compliance_approve",
+            Permission::WooPublish => "woo_publish",
+            Permission::UserManage => "user_manage",
+            Permission::RoleManage => "role_manage",
+            Permission::OrganizationManage => "organization_manage",
+            Permission::AuditView => "audit_view",
+            Permission::PlatformManage => "platform_manage",
+        }
+    }
 }
 
 /// Authentication error types
@@ -238,14 +226,31 @@ impl JwtService {
         organization_id: Uuid,
         roles: Vec<Role>,
     ) -> Result<String, AuthError> {
+        self.create_token_for(&AuthContext {
+            user_id,
+            email: email.to_string(),
+            organization_id,
+            roles,
+            municipality: None,
+            loa: None,
+            department: None,
+            delegations: Vec::new(),
+        })
+    }
+
+    /// Create a new JWT token carrying everything known about the user
+    pub fn create_token_for(&self, auth: &AuthContext) -> Result<String, AuthError> {
         let now = Utc::now();
         let expiration = now + Duration::hours(TOKEN_EXPIRATION_HOURS);
 
         let claims = Claims {
-            sub: user_id.to_string(),
-            email: email.to_string(),
-            org_id: organization_id.to_string(),
-            roles: roles.iter().map(|r| r.to_string()).collect(),
+            sub: auth.user_id.to_string(),
+            email: auth.email.clone(),
+            org_id: auth.organization_id.to_string(),
+            roles: auth.roles.iter().map(|r| r.to_string()).collect(),
+            municipality: auth.municipality.as_ref().map(|m| m.as_str().to_string()),
+            loa: auth.loa.map(|loa| loa.as_str().to_string()),
+            department: auth.department.clone(),
             iat: now.timestamp(),
             exp: expiration.timestamp(),
             iss: "iou-modern".to_string(),
@@ -280,6 +285,25 @@ impl Default for JwtService {
 /// Extension key for storing auth context in request state
 pub struct AuthExtension;
 
+/// Database of the request, if the API has one
+fn request_pool(req: &Request) -> Option<Arc<SupabasePool>> {
+    req.extensions().get::<Option<Arc<SupabasePool>>>().cloned().flatten()
+}
+
+/// Active delegations `user_id` received; none without a database
+async fn received_delegations(pool: Option<Arc<SupabasePool>>, user_id: Uuid) -> Vec<Delegation> {
+    let Some(pool) = pool else {
+        return Vec::new();
+    };
+    match DelegationService::new(pool.inner().clone()).list_user_delegations(user_id, false).await {
+        Ok(delegations) => delegations.into_iter().filter(|d| d.to_user_id == user_id).collect(),
+        Err(e) => {
+            tracing::warn!(user_id = %user_id, "Delegations not loaded: {}", e);
+            Vec::new()
+        }
+    }
+}
+
 /// Authentication middleware - validates JWT tokens and extracts user context
 pub async fn auth_middleware(
     req: Request,
@@ -310,6 +334,16 @@ pub async fn auth_middleware(
         .map_err(|_| ApiError::Unauthorized("Invalid user ID in token".to_string()))?;
     let organization_id = Uuid::parse_str(&claims.org_id)
         .map_err(|_| ApiError::Unauthorized("Invalid organization ID in token".to_string()))?;
+    let municipality = claims
+        .municipality
+        .map(TenantId::new)
+        .transpose()
+        .map_err(|_| ApiError::Unauthorized("Invalid municipality in token".to_string()))?;
+    let loa = claims
+        .loa
+        .map(|loa| LoA::from_str(&loa).ok_or(()))
+        .transpose()
+        .map_err(|_| ApiError::Unauthorized("Invalid LoA in token".to_string()))?;
 
     // Parse roles
     let roles: Vec<Role> = claims
@@ -318,12 +352,18 @@ pub async fn auth_middleware(
         .filter_map(|r| r.parse().ok())
         .collect();
 
+    let delegations = received_delegations(request_pool(&req), user_id).await;
+
     // Create auth context
     let auth_context = AuthContext {
         user_id,
         email: claims.email,
         organization_id,
         roles,
+        municipality,
+        loa,
+        department: claims.department,
+        delegations,
     };
 
     // Store auth context in request extensions
@@ -351,21 +391,28 @@ pub async fn optional_auth_middleware(
             let jwt_service = JwtService::new();
 
             if let Ok(claims) = jwt_service.validate_token(token) {
-                if let (Ok(user_id), Ok(org_id)) = (
+                if let (Ok(user_id), Ok(org_id), Ok(municipality), Ok(loa)) = (
                     Uuid::parse_str(&claims.sub),
                     Uuid::parse_str(&claims.org_id),
+                    claims.municipality.map(TenantId::new).transpose(),
+                    claims.loa.map(|loa| LoA::from_str(&loa).ok_or(())).transpose(),
                 ) {
                     let roles: Vec<Role> = claims
                         .roles
                         .iter()
                         .filter_map(|r| r.parse().ok())
                         .collect();
+                    let delegations = received_delegations(request_pool(&req), user_id).await;
 
                     let auth_context = AuthContext {
                         user_id,
                         email: claims.email,
                         organization_id: org_id,
                         roles,
+                        municipality,
+                        loa,
+                        department: claims.department,
+                        delegations,
                     };
 
                     let mut req = req;
@@ -380,20 +427,12 @@ pub async fn optional_auth_middleware(
     next.run(req).await
 }
 
-/// Require specific permission - returns 403 if user doesn't have permission
+/// Require specific permission - returns 403 if the organization's policy denies it
+///
+/// Decides without a resource, so rules that match resource attributes do
+/// not apply; routes that load the entity use [`authorize`] instead.
 pub fn require_permission(auth: &AuthContext, permission: Permission) -> Result<(), ApiError> {
-    if auth
-        .roles
-        .iter()
-        .any(|role| role.has_permission(permission))
-    {
-        Ok(())
-    } else {
-        Err(ApiError::Forbidden(format!(
-            "Permission {:?} required",
-            permission
-        )))
-    }
+    authorize(auth, permission, &Resource::default())
 }
 
 /// Check if user has any of the specified roles
@@ -489,12 +528,7 @@ pub async fn refresh_token(
 ) -> Result<Json<LoginResponse>, ApiError> {
     // Create new token with same user context
     let jwt_service = JwtService::new();
-    let access_token = jwt_service.create_token(
-        auth.user_id,
-        &auth.email,
-        auth.organization_id,
-        auth.roles.clone(),
-    )
+    let access_token = jwt_service.create_token_for(&auth)
     .map_err(|e| ApiError::Internal(anyhow::anyhow!("Failed to create token: {}", e)))?;
 
     Ok(Json(LoginResponse {
@@ -555,6 +589,7 @@ impl std::fmt::Display for Role {
         match self {
             Role::Admin => write!(f, "admin"),
             Role::Auditor => write!(f, "auditor"),
+            Role::PlatformAdmin => write!(f, "platform_admin"),
             Role::DomainManager => write!(f, "domain_manager"),
             Role::DomainEditor => write!(f, "domain_editor"),
             Role::DomainViewer => write!(f, "domain_viewer"),
@@ -577,6 +612,7 @@ impl std::str::FromStr for Role {
         match s.to_lowercase().as_str() {
             "admin" => Ok(Role::Admin),
             "auditor" => Ok(Role::Auditor),
+            "platform_admin" => Ok(Role::PlatformAdmin),
             "domain_manager" => Ok(Role::DomainManager),
             "domain_editor" => Ok(Role::DomainEditor),
             "domain_viewer" => Ok(Role::DomainViewer),
@@ -595,6 +631,7 @@ impl std::str::FromStr for Role {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::middleware::policy::require_own_tenant;
 
     #[test]
     fn test_jwt_token_creation_and_validation() {
@@ -612,10 +649,78 @@ mod tests {
         assert_eq!(claims.org_id, org_id.to_string());
     }
 
+    #[test]
+    fn test_token_carries_subject_attributes() {
+        let service = JwtService::new();
+        let auth = AuthContext {
+            user_id: Uuid::new_v4(),
+            email: "lezer@utrecht.nl".to_string(),
+            organization_id: Uuid::new_v4(),
+            roles: vec![Role::DomainViewer],
+            municipality: Some(TenantId::new("utrecht").unwrap()),
+            loa: Some(LoA::High),
+            department: Some("ruimte".to_string()),
+            delegations: Vec::new(),
+        };
+
+        let claims = service.validate_token(&service.create_token_for(&auth).unwrap()).unwrap();
+        assert_eq!(claims.municipality.as_deref(), Some("utrecht"));
+        assert_eq!(claims.loa.as_deref(), Some("high"));
+        assert_eq!(claims.department.as_deref(), Some("ruimte"));
+
+        let plain = service.create_token(auth.user_id, &auth.email, auth.organization_id, auth.roles).unwrap();
+        let claims = service.validate_token(&plain).unwrap();
+        assert_eq!((claims.municipality, claims.loa, claims.department), (None, None, None));
+    }
+
     #[test]
     fn test_role_permissions() {
         assert!(Role::Admin.has_permission(Permission::UserManage));
         assert!(Role::DomainViewer.has_permission(Permission::DomainRead));
         assert!(!Role::DomainViewer.has_permission(Permission::DomainUpdate));
+        assert!(!Role::Admin.has_permission(Permission::PlatformManage));
+        assert!(Role::PlatformAdmin.has_permission(Permission::PlatformManage));
+    }
+
+    #[test]
+    fn test_require_permission_hides_policy() {
+        let auth = AuthContext {
+            user_id: Uuid::new_v4(),
+            email: "lezer@iou.nl".to_string(),
+            organization_id: Uuid::new_v4(),
+            roles: vec![Role::DomainViewer],
+            municipality: None,
+            loa: None,
+            department: None,
+            delegations: Vec::new(),
+        };
+        assert!(require_permission(&auth, Permission::ObjectRead).is_ok());
+
+        match require_permission(&auth, Permission::DomainDelete) {
+            Err(ApiError::Forbidden(message)) => assert_eq!(message, "Permission DomainDelete required"),
+            other => panic!("expected 403, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_own_tenant_only() {
+        let admin = AuthContext {
+            user_id: Uuid::new_v4(),
+            email: "beheer@iou.nl".to_string(),
+            organization_id: Uuid::new_v4(),
+            roles: vec![Role::Admin],
+            municipality: None,
+            loa: None,
+            department: None,
+            delegations: Vec::new(),
+        };
+        assert!(require_own_tenant(&admin, &admin.organization_id.to_string()).is_ok());
+        assert!(matches!(
+            require_own_tenant(&admin, &Uuid::new_v4().to_string()),
+            Err(ApiError::Forbidden(_))
+        ));
+
+        let platform = AuthContext { roles: vec![Role::PlatformAdmin], ..admin };
+        assert!(require_own_tenant(&platform, &Uuid::new_v4().to_string()).is_ok());
     }
 }
@@ -1,9 +1,12 @@
 //! Middleware for the IOU-Modern API
 
 pub mod auth;
+pub mod policy;
 pub mod purpose;
+pub mod vc;
 
 pub use auth::{
     auth_middleware, optional_auth_middleware, AuthContext, require_permission, Role,
 };
+pub use policy::{authorize, is_platform_admin, policy_engine, require_own_tenant, require_platform_admin};
 pub use purpose::{purpose_middleware, PurposeContext, PurposeState, HEADER_PURPOSE};
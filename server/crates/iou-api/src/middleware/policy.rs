@@ -0,0 +1,226 @@
+//! Policy-based authorization
+//!
+//! Permissions are decided by the attribute-based policy engine of
+//! `iou_regels::abac` instead of a fixed role matrix. The built-in policy
+//! (`policies/defaults.yaml`) grants what each role has always granted;
+//! an organization adjusts it with its own policy file in
+//! `IOU_POLICY_DIR` (default `config/policies`), named after the
+//! organization id or carrying it as `tenant`.
+//!
+//! The subject carries what the token says about the user: id, roles,
+//! LoA, department and organization (`subject.organization`), and the
+//! delegations the user received. [`require_permission`] decides
+//! on the action alone, so rules that match resource attributes only take
+//! effect where a route passes the loaded entity to [`authorize`]; routes
+//! on one information object use [`authorize_object`].
+//!
+//! [`require_permission`]: super::auth::require_permission
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use iou_core::compliance::Classification;
+use iou_regels::abac::{Decision, Policy, PolicyEngine, Resource, Subject};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::auth::{AuthContext, Permission};
+use crate::dsar::WooRepository;
+use crate::error::ApiError;
+
+/// Built-in policy, compiled into the binary
+const DEFAULT_POLICY: &str = include_str!("../../policies/defaults.yaml");
+
+const DEFAULT_POLICY_DIR: &str = "config/policies";
+
+static ENGINE: OnceLock<Arc<PolicyEngine>> = OnceLock::new();
+
+/// Process-wide policy engine; tenant policies are read on first use
+pub fn policy_engine() -> Arc<PolicyEngine> {
+    ENGINE
+        .get_or_init(|| {
+            let engine = PolicyEngine::from_yaml(DEFAULT_POLICY).expect("built-in access policy is invalid");
+            load_tenant_policies(&engine, &policy_dir());
+            Arc::new(engine)
+        })
+        .clone()
+}
+
+/// Directory with tenant policy files
+pub fn policy_dir() -> PathBuf {
+    std::env::var("IOU_POLICY_DIR")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .unwrap_or_else(|| DEFAULT_POLICY_DIR.to_string())
+        .into()
+}
+
+/// Load every `*.yaml` in `dir`; invalid files are skipped with a warning
+fn load_tenant_policies(engine: &PolicyEngine, dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        tracing::debug!("No tenant access policies in {}", dir.display());
+        return;
+    };
+    let paths = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| matches!(path.extension().and_then(|e| e.to_str()), Some("yaml" | "yml")));
+
+    for path in paths {
+        let result = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|source| Policy::from_yaml(&source).map_err(|e| e.to_string()))
+            .and_then(|policy| {
+                let tenant = policy
+                    .tenant
+                    .clone()
+                    .or_else(|| path.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+                    .unwrap_or_default();
+                let report = engine.set_tenant_policy(&tenant, policy).map_err(|e| e.to_string())?;
+                Ok((tenant, report.passed))
+            });
+        match result {
+            Ok((tenant, passed)) => {
+                tracing::info!("Access policy for {} loaded from {} ({} tests passed)", tenant, path.display(), passed)
+            }
+            Err(e) => tracing::warn!("Access policy {} not loaded: {}", path.display(), e),
+        }
+    }
+}
+
+/// Policy subject for an authenticated user
+pub fn subject(auth: &AuthContext) -> Subject {
+    let mut subject = Subject::with_roles(auth.roles.iter().map(|role| role.to_string()))
+        .with_id(auth.user_id.to_string())
+        .with_delegations(auth.delegations.clone())
+        .with_attribute("organization", auth.organization_id.to_string());
+    if let Some(loa) = auth.loa {
+        subject = subject.with_loa(loa);
+    }
+    if let Some(department) = &auth.department {
+        subject = subject.with_department(department.clone());
+    }
+    subject
+}
+
+/// Decide on `permission` for `resource` under the organization's policy
+pub fn decide(auth: &AuthContext, permission: Permission, resource: &Resource) -> Decision {
+    let tenant = auth.organization_id.to_string();
+    policy_engine().decide(Some(&tenant), &subject(auth), permission.as_str(), resource)
+}
+
+/// Whether the user may act on organizations other than their own
+///
+/// Decided by the built-in policy alone, so a tenant policy cannot grant it.
+pub fn is_platform_admin(auth: &AuthContext) -> bool {
+    policy_engine()
+        .decide(None, &subject(auth), Permission::PlatformManage.as_str(), &Resource::default())
+        .is_permitted()
+}
+
+/// Require that the user is a platform administrator - returns 403 if not
+pub fn require_platform_admin(auth: &AuthContext) -> Result<(), ApiError> {
+    if is_platform_admin(auth) {
+        return Ok(());
+    }
+    tracing::debug!(user_id = %auth.user_id, "Platform administration denied");
+    Err(ApiError::Forbidden(format!("Permission {:?} required", Permission::PlatformManage)))
+}
+
+/// Require that `tenant` is the user's own organization, unless the user
+/// is a platform administrator
+pub fn require_own_tenant(auth: &AuthContext, tenant: &str) -> Result<(), ApiError> {
+    if tenant == auth.organization_id.to_string() || is_platform_admin(auth) {
+        return Ok(());
+    }
+    tracing::debug!(user_id = %auth.user_id, "Access to tenant {} denied", tenant);
+    Err(ApiError::Forbidden(format!("Permission {:?} required", Permission::PlatformManage)))
+}
+
+/// Require `permission` on `resource` - returns 403 if denied
+///
+/// The rules behind the decision are logged, not returned: they would
+/// disclose the policy. Administrators use the explain endpoint instead.
+pub fn authorize(auth: &AuthContext, permission: Permission, resource: &Resource) -> Result<(), ApiError> {
+    let decision = decide(auth, permission, resource);
+    if decision.is_permitted() {
+        return Ok(());
+    }
+    tracing::info!(user_id = %auth.user_id, "Access denied: {}", decision.reason());
+    Err(ApiError::Forbidden(format!("Permission {:?} required", permission)))
+}
+
+/// Policy resource for an information object of `domain_id`
+pub fn object_resource(object_id: Uuid, domain_id: Uuid, classification: Option<Classification>) -> Resource {
+    let resource = Resource::new("information_object").with_id(object_id).with_domain(domain_id);
+    match classification {
+        Some(classification) => resource.with_classification(classification),
+        None => resource,
+    }
+}
+
+/// Require `permission` on a stored information object, so rules on its
+/// domain and classification apply - returns 404 if it does not exist
+pub async fn authorize_object(
+    auth: &AuthContext,
+    permission: Permission,
+    pool: &PgPool,
+    object_id: Uuid,
+) -> Result<(), ApiError> {
+    let (domain_id, classification) = WooRepository::new(pool.clone())
+        .object_access_attributes(object_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Information object {}", object_id)))?;
+    let classification = classification.and_then(|c| c.parse::<Classification>().ok());
+    authorize(auth, permission, &object_resource(object_id, domain_id, classification))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::auth::Role;
+    use iou_core::tenancy::LoA;
+
+    /// The example policy of `config/policies/README.md`
+    fn readme_policy() -> Policy {
+        let readme = include_str!("../../../../../config/policies/README.md");
+        let yaml = readme
+            .split("```yaml")
+            .nth(1)
+            .and_then(|rest| rest.split("```").next())
+            .expect("README has a yaml example");
+        Policy::from_yaml(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_readme_policy_requires_high_loa_for_geheim() {
+        let policy = readme_policy();
+        let tenant = policy.tenant.clone().unwrap();
+        policy_engine().set_tenant_policy(&tenant, policy).unwrap();
+
+        let reader = AuthContext {
+            user_id: Uuid::new_v4(),
+            email: "lezer@iou.nl".to_string(),
+            organization_id: tenant.parse().unwrap(),
+            roles: vec![Role::DomainViewer],
+            municipality: None,
+            loa: Some(LoA::Substantial),
+            department: Some("ruimte".to_string()),
+            delegations: Vec::new(),
+        };
+        let domain_id = Uuid::new_v4();
+        let geheim = object_resource(Uuid::new_v4(), domain_id, Some(Classification::Geheim));
+        let intern = object_resource(Uuid::new_v4(), domain_id, Some(Classification::Intern));
+
+        assert!(authorize(&reader, Permission::ObjectRead, &intern).is_ok());
+        assert!(matches!(authorize(&reader, Permission::ObjectRead, &geheim), Err(ApiError::Forbidden(_))));
+        assert!(decide(&reader, Permission::ObjectRead, &geheim).reason().contains("geheim-alleen-hoog"));
+
+        let high = AuthContext { loa: Some(LoA::High), ..reader.clone() };
+        assert!(authorize(&high, Permission::ObjectRead, &geheim).is_ok());
+
+        // A token without LoA does not pass the condition
+        let unknown = AuthContext { loa: None, ..reader };
+        assert!(authorize(&unknown, Permission::ObjectRead, &geheim).is_err());
+    }
+}
@@ -1,21 +1,125 @@
 //! Verifiable Credential authentication middleware
+//!
+//! A client first fetches a challenge (`POST /api/v1/vc/challenge`), lets
+//! the wallet sign a presentation with it as nonce and audience
+//! `VC_AUDIENCE`, and sends it as `Authorization: VC <presentation>` with
+//! the challenge in the `VC-Challenge` header. A challenge is accepted
+//! once and for at most five minutes, so a captured presentation cannot
+//! be replayed.
 
 use crate::error::ApiError;
 use axum::{
     extract::{Request, State},
     middleware::Next,
     response::Response,
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+use iou_core::ssi::{
+    PresentationRequirements, PresentationValidator, StatusListClient, TrustRegistry, VerifiablePresentation,
 };
-use iou_core::ssi::{PresentationValidator, VerifiablePresentation};
 use iou_core::tenancy::TenantContext;
+use serde::Serialize;
 use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::vc::VcConfig;
+
+/// Oldest holder proof accepted, and lifetime of a challenge
+const MAX_PRESENTATION_AGE_MIN: i64 = 5;
+
+/// Header with the challenge a presentation answers
+pub const CHALLENGE_HEADER: &str = "vc-challenge";
+
+/// Nonce a presentation must be signed with
+#[derive(Debug, Clone, Serialize)]
+pub struct Challenge {
+    pub nonce: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Challenges handed out and not yet answered
+#[derive(Default)]
+pub struct Challenges {
+    issued: DashMap<String, DateTime<Utc>>,
+}
+
+impl Challenges {
+    /// New single-use challenge
+    pub fn issue(&self) -> Challenge {
+        let now = Utc::now();
+        self.issued.retain(|_, expires_at| now < *expires_at);
+        let challenge = Challenge {
+            nonce: Uuid::new_v4().simple().to_string(),
+            expires_at: now + Duration::minutes(MAX_PRESENTATION_AGE_MIN),
+        };
+        self.issued.insert(challenge.nonce.clone(), challenge.expires_at);
+        challenge
+    }
+
+    /// Whether `nonce` was issued and has not expired; true only once
+    pub fn redeem(&self, nonce: &str) -> bool {
+        self.issued.remove(nonce).is_some_and(|(_, expires_at)| Utc::now() < expires_at)
+    }
+}
 
 /// VC validation middleware state
+#[derive(Clone)]
 pub struct VcState {
     pub validator: Arc<PresentationValidator>,
+    pub challenges: Arc<Challenges>,
     pub audit_logger: iou_core::audit::SharedAuditLogger,
 }
 
+impl VcState {
+    /// State whose validator also rejects revoked and suspended credentials,
+    /// so a credential stops giving access once its issuer revokes it, and
+    /// credentials of issuers the registry does not trust for the claimed
+    /// municipality; while the registry is empty the configured
+    /// `trusted_issuers` apply, as in `VpVerifier`
+    pub fn new(
+        validator: PresentationValidator,
+        status_lists: Arc<StatusListClient>,
+        trust_registry: Arc<TrustRegistry>,
+        audit_logger: iou_core::audit::SharedAuditLogger,
+    ) -> Self {
+        let validator = validator
+            .with_status_lists(status_lists)
+            .with_trust_registry(trust_registry);
+        Self { validator: Arc::new(validator), challenges: Arc::default(), audit_logger }
+    }
+
+    /// State for a VC configuration: its DID resolver, trusted issuers,
+    /// audience and status list policy
+    ///
+    /// Fails without `VC_AUDIENCE`: a presentation addressed to another
+    /// verifier must not give access here.
+    pub fn from_config(
+        config: &VcConfig,
+        trust_registry: Arc<TrustRegistry>,
+        audit_logger: iou_core::audit::SharedAuditLogger,
+    ) -> anyhow::Result<Self> {
+        let audience = config
+            .audience
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("VC_AUDIENCE must be set for presentation authentication"))?;
+        let validator = PresentationValidator::new(Arc::new(config.did_resolver()), config.trusted_issuers.clone())
+            .with_requirements(PresentationRequirements {
+                audience: Some(audience),
+                max_age: Some(Duration::minutes(MAX_PRESENTATION_AGE_MIN)),
+                ..Default::default()
+            });
+        Ok(Self::new(validator, Arc::new(config.status_list_client()), trust_registry, audit_logger))
+    }
+}
+
+/// POST /api/v1/vc/challenge
+/// Challenge for the next presentation
+pub async fn issue_challenge(State(state): State<VcState>) -> Json<Challenge> {
+    Json(state.challenges.issue())
+}
+
 /// VC validation middleware
 pub async fn vc_middleware(
     State(state): State<VcState>,
@@ -37,14 +141,27 @@ pub async fn vc_middleware(
 
     let vp_data = &auth_header[3..];
 
-    // Parse and validate VP
-    let vp: VerifiablePresentation =
-        serde_json::from_str(vp_data)
-            .map_err(|e| ApiError::Unauthorized(format!("Invalid VP format: {}", e)))?;
+    // The presentation must answer a challenge of this verifier, once
+    let nonce = request
+        .headers()
+        .get(CHALLENGE_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .ok_or_else(|| ApiError::Unauthorized("Missing VC-Challenge header".to_string()))?;
+    if !state.challenges.redeem(nonce) {
+        return Err(ApiError::Unauthorized("Unknown or expired challenge".to_string()));
+    }
+    let requirements = PresentationRequirements {
+        nonce: Some(nonce.to_string()),
+        ..state.validator.requirements().clone()
+    };
+
+    // Parse and validate VP (JSON with Data Integrity proof, VP-JWT or SD-JWT)
+    let vp = VerifiablePresentation::parse(vp_data)
+        .map_err(|e| ApiError::Unauthorized(format!("Invalid VP format: {}", e)))?;
 
-    let validated = tokio::task::block_in_place(|| {
-        tokio::runtime::Handle::current().block_on(state.validator.validate(&vp))
-    })
+    // Signatures, nonce, audience and age of the holder proof, holder
+    // binding and revocation of every credential
+    let validated = state.validator.validate_with(&vp, &requirements).await
         .map_err(|e| ApiError::Unauthorized(format!("VP validation failed: {}", e)))?;
 
     // Convert VC claims to TenantContext
@@ -56,8 +173,8 @@ pub async fn vc_middleware(
         tenant_context.tenant_id.as_str().to_string(),
         tenant_context.holder_did.clone(),
         iou_core::audit::AuditAction::VCPresented,
-        "authentication",
-        "vc_presented",
+        "authentication".to_string(),
+        "vc_presented".to_string(),
     );
     let _ = iou_core::audit::log_shared(&state.audit_logger, &audit_entry).await;
 
@@ -68,3 +185,33 @@ pub async fn vc_middleware(
 
     Ok(next.run(request).await)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_challenges_are_single_use() {
+        let challenges = Challenges::default();
+        let challenge = challenges.issue();
+        assert!(challenge.expires_at <= Utc::now() + Duration::minutes(MAX_PRESENTATION_AGE_MIN));
+
+        assert!(challenges.redeem(&challenge.nonce));
+        assert!(!challenges.redeem(&challenge.nonce));
+        assert!(!challenges.redeem("made-up"));
+    }
+
+    #[tokio::test]
+    async fn test_audience_is_required() {
+        let registry = Arc::new(TrustRegistry::default());
+        let pool = sqlx::PgPool::connect_lazy("postgres://localhost/iou").unwrap();
+        let logger = iou_core::audit::shared_logger(iou_core::audit::PostgresAuditBackend::new(pool));
+        assert!(VcState::from_config(&VcConfig::default(), registry.clone(), logger.clone()).is_err());
+
+        let config = VcConfig { audience: Some("https://iou.utrecht.nl".to_string()), ..VcConfig::default() };
+        let state = VcState::from_config(&config, registry, logger).unwrap();
+        let requirements = state.validator.requirements();
+        assert_eq!(requirements.audience.as_deref(), Some("https://iou.utrecht.nl"));
+        assert_eq!(requirements.max_age, Some(Duration::minutes(MAX_PRESENTATION_AGE_MIN)));
+    }
+}
@@ -0,0 +1,213 @@
+//! [`iou_regels::ProcessInstanceStore`] implementations
+//!
+//! DuckDB (`bpmn_process_instances` / `bpmn_activity_history`, migration 005)
+//! for the embedded deployment and PostgreSQL (migration 070) for Supabase.
+//! Both keep the activity history append-only.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use iou_regels::{ActivityRecord, BpmnError, ProcessInstance, ProcessInstanceState, ProcessInstanceStore};
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::db::Database;
+
+fn store_error(e: impl std::fmt::Display) -> BpmnError {
+    BpmnError::StoreError(e.to_string())
+}
+
+#[derive(Clone)]
+pub struct DuckdbProcessInstanceStore(pub Arc<Database>);
+
+#[async_trait]
+impl ProcessInstanceStore for DuckdbProcessInstanceStore {
+    async fn save(&self, instance: &ProcessInstance) -> Result<(), BpmnError> {
+        let db = self.0.clone();
+        let instance = instance.clone();
+        tokio::task::spawn_blocking(move || db.save_process_instance_row(&instance))
+            .await
+            .map_err(store_error)?
+            .map_err(store_error)
+    }
+
+    async fn load(&self, id: Uuid) -> Result<Option<ProcessInstance>, BpmnError> {
+        let db = self.0.clone();
+        tokio::task::spawn_blocking(move || db.load_process_instance_row(id))
+            .await
+            .map_err(store_error)?
+            .map_err(store_error)
+    }
+
+    async fn list_running(&self) -> Result<Vec<ProcessInstance>, BpmnError> {
+        let db = self.0.clone();
+        tokio::task::spawn_blocking(move || db.list_running_process_instance_rows())
+            .await
+            .map_err(store_error)?
+            .map_err(store_error)
+    }
+
+    async fn history(&self, id: Uuid) -> Result<Vec<ActivityRecord>, BpmnError> {
+        let db = self.0.clone();
+        tokio::task::spawn_blocking(move || db.load_process_history_rows(id))
+            .await
+            .map_err(store_error)?
+            .map_err(store_error)
+    }
+}
+
+#[derive(Clone)]
+pub struct PostgresProcessInstanceStore {
+    pool: PgPool,
+}
+
+impl PostgresProcessInstanceStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    fn instance_from_row(row: &sqlx::postgres::PgRow) -> Result<ProcessInstance, BpmnError> {
+        let state: String = row.try_get("state").map_err(store_error)?;
+        let variables: serde_json::Value = row.try_get("variables").map_err(store_error)?;
+        let tokens: serde_json::Value = row.try_get("tokens").map_err(store_error)?;
+
+        Ok(ProcessInstance {
+            id: row.try_get("id").map_err(store_error)?,
+            definition_id: row.try_get("definition_id").map_err(store_error)?,
+            state: state.parse::<ProcessInstanceState>().map_err(store_error)?,
+            variables: serde_json::from_value(variables).map_err(store_error)?,
+            current_activity: row.try_get("current_activity").map_err(store_error)?,
+            started_at: row.try_get("started_at").map_err(store_error)?,
+            completed_at: row.try_get("completed_at").map_err(store_error)?,
+            tokens: serde_json::from_value(tokens).map_err(store_error)?,
+            history: vec![],
+        })
+    }
+}
+
+#[async_trait]
+impl ProcessInstanceStore for PostgresProcessInstanceStore {
+    async fn save(&self, instance: &ProcessInstance) -> Result<(), BpmnError> {
+        let mut tx = self.pool.begin().await.map_err(store_error)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO bpmn_process_instances
+                (id, definition_id, state, variables, tokens, current_activity,
+                 started_at, completed_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW())
+            ON CONFLICT (id) DO UPDATE SET
+                state = EXCLUDED.state,
+                variables = EXCLUDED.variables,
+                tokens = EXCLUDED.tokens,
+                current_activity = EXCLUDED.current_activity,
+                completed_at = EXCLUDED.completed_at,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(instance.id)
+        .bind(&instance.definition_id)
+        .bind(instance.state.to_string())
+        .bind(serde_json::to_value(&instance.variables).map_err(store_error)?)
+        .bind(serde_json::to_value(&instance.tokens).map_err(store_error)?)
+        .bind(&instance.current_activity)
+        .bind(instance.started_at)
+        .bind(instance.completed_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(store_error)?;
+
+        // History is append-only: only insert records beyond those already stored
+        let stored: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM bpmn_activity_history WHERE instance_id = $1",
+        )
+        .bind(instance.id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(store_error)?;
+
+        for (seq, record) in instance.history.iter().enumerate().skip(stored as usize) {
+            sqlx::query(
+                r#"
+                INSERT INTO bpmn_activity_history
+                    (instance_id, seq, element_id, element_name, element_type, started_at, completed_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                "#,
+            )
+            .bind(instance.id)
+            .bind(seq as i32)
+            .bind(&record.element_id)
+            .bind(&record.element_name)
+            .bind(&record.element_type)
+            .bind(record.started_at)
+            .bind(record.completed_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(store_error)?;
+        }
+
+        tx.commit().await.map_err(store_error)
+    }
+
+    async fn load(&self, id: Uuid) -> Result<Option<ProcessInstance>, BpmnError> {
+        let row = sqlx::query("SELECT * FROM bpmn_process_instances WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(store_error)?;
+
+        match row {
+            Some(row) => {
+                let mut instance = Self::instance_from_row(&row)?;
+                instance.history = self.history(id).await?;
+                Ok(Some(instance))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn list_running(&self) -> Result<Vec<ProcessInstance>, BpmnError> {
+        let rows = sqlx::query(
+            "SELECT * FROM bpmn_process_instances WHERE state = $1 ORDER BY started_at",
+        )
+        .bind(ProcessInstanceState::Running.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(store_error)?;
+
+        let mut instances = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let mut instance = Self::instance_from_row(row)?;
+            instance.history = self.history(instance.id).await?;
+            instances.push(instance);
+        }
+        Ok(instances)
+    }
+
+    async fn history(&self, id: Uuid) -> Result<Vec<ActivityRecord>, BpmnError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT element_id, element_name, element_type, started_at, completed_at
+            FROM bpmn_activity_history
+            WHERE instance_id = $1
+            ORDER BY seq
+            "#,
+        )
+        .bind(id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(store_error)?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(ActivityRecord {
+                    element_id: row.try_get("element_id").map_err(store_error)?,
+                    element_name: row.try_get("element_name").map_err(store_error)?,
+                    element_type: row.try_get("element_type").map_err(store_error)?,
+                    started_at: row.try_get::<DateTime<Utc>, _>("started_at").map_err(store_error)?,
+                    completed_at: row.try_get::<DateTime<Utc>, _>("completed_at").map_err(store_error)?,
+                })
+            })
+            .collect()
+    }
+}
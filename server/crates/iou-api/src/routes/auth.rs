@@ -4,13 +4,15 @@
 //! EBSI Verifiable Credential (wallet) authentication.
 
 use axum::{extract::Extension, response::Json};
+use iou_core::ssi::TrustRegistry;
+use iou_core::tenancy::{LoA, TenantId};
 use std::sync::Arc;
 use uuid::Uuid;
 use chrono::Duration;
 
 use crate::db::Database;
 use crate::error::ApiError;
-use crate::middleware::auth::{JwtService, Role};
+use crate::middleware::auth::{AuthContext, JwtService, Role};
 use crate::vc::{WalletAuthRequest, WalletAuthResponse, VpVerifier, VcConfig, VcUserContext};
 
 // Re-export traditional auth handlers
@@ -26,7 +28,7 @@ pub use crate::middleware::auth::{
 /// # Flow
 ///
 /// 1. Receive Verifiable Presentation (JWT format)
-/// 2. Verify VP signature and issuer trust
+/// 2. Verify VP signature and issuer trust (trust registry)
 /// 3. Extract claims from credentials
 /// 4. Map to local user/roles
 /// 5. Issue short-lived JWT for subsequent API calls
@@ -62,12 +64,13 @@ pub use crate::middleware::auth::{
 /// ```
 pub async fn wallet_auth(
     Extension(db): Extension<Arc<Database>>,
+    Extension(trust_registry): Extension<Arc<TrustRegistry>>,
     Json(req): Json<WalletAuthRequest>,
 ) -> Result<Json<WalletAuthResponse>, ApiError> {
     let config = VcConfig::from_env();
 
     // Verify the VP and extract user context
-    let verifier = VpVerifier::new(config.clone());
+    let verifier = VpVerifier::new(config.clone()).with_trust_registry(trust_registry);
     let user_context = verifier.verify_wallet_auth(req).await
         .map_err(|e| ApiError::Unauthorized(format!("VP verification failed: {}", e)))?;
 
@@ -77,14 +80,23 @@ pub async fn wallet_auth(
         .filter_map(|r| r.parse().ok())
         .collect();
 
+    // Municipality, LoA and department as the credential states them
+    let claim = |name: &str| user_context.additional_claims.get(name).and_then(|value| value.as_str());
+    let auth = AuthContext {
+        user_id: user_context.id,
+        email: format!("{}@wallet", user_context.id), // Placeholder email
+        organization_id: user_context.organization_id,
+        roles,
+        municipality: claim("municipality").and_then(|municipality| TenantId::new(municipality).ok()),
+        loa: claim("loa").and_then(LoA::from_str),
+        department: claim("department").map(str::to_string),
+        delegations: Vec::new(),
+    };
+
     // Issue short-lived JWT (1 hour)
     let jwt_service = JwtService::new();
-    let access_token = jwt_service.create_token(
-        user_context.id,
-        &format!("{}@wallet", user_context.id), // Placeholder email
-        user_context.organization_id,
-        roles.clone(),
-    ).map_err(|e| ApiError::Internal(anyhow::anyhow!("Token creation failed: {}", e)))?;
+    let access_token = jwt_service.create_token_for(&auth)
+        .map_err(|e| ApiError::Internal(anyhow::anyhow!("Token creation failed: {}", e)))?;
 
     // Log authentication event
     tracing::info!(
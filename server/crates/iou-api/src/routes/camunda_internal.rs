@@ -16,7 +16,7 @@ use iou_ai::{
 };
 use iou_core::document::{DocumentRequest, DomainConfig, Template};
 use iou_core::document::AuditEntry;
-use iou_core::storage::S3Client;
+use iou_core::storage::StorageBackend;
 use iou_core::workflows::WorkflowStatus;
 
 #[derive(Debug, Deserialize)]
@@ -86,7 +86,7 @@ pub async fn run_pipeline_job(
     Extension(kg): Extension<Arc<iou_ai::graphrag::KnowledgeGraph>>,
     Extension(status_tx): Extension<broadcast::Sender<StatusMessage>>,
     Extension(doc_status_tx): Extension<broadcast::Sender<DocumentStatus>>,
-    Extension(s3): Extension<Arc<S3Client>>,
+    Extension(storage): Extension<Arc<dyn StorageBackend>>,
     Json(req): Json<RunPipelineJobRequest>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
     verify_worker_token(&headers)?;
@@ -189,7 +189,7 @@ pub async fn run_pipeline_job(
 
     finalize_after_pipeline(
         &db,
-        &s3,
+        &storage,
         document_id,
         &pipeline_result.agent_results,
         pipeline_result.final_status,
@@ -256,7 +256,7 @@ pub async fn run_pipeline_job(
 
 async fn finalize_after_pipeline(
     db: &Arc<Database>,
-    s3: &Arc<S3Client>,
+    storage: &Arc<dyn StorageBackend>,
     document_id: Uuid,
     agent_results: &[iou_ai::AgentExecutionResult],
     final_status: WorkflowStatus,
@@ -269,7 +269,7 @@ async fn finalize_after_pipeline(
         "requires_human_approval": requires_human_approval,
     });
     let key = format!("documents/{}/v1.pipeline.json", document_id);
-    s3.upload(
+    storage.upload(
         &key,
         serde_json::to_vec(&summary).map_err(|e| ApiError::Internal(anyhow::anyhow!(e)))?,
         "application/json",
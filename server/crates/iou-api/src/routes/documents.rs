@@ -373,7 +373,7 @@ pub async fn get_audit_trail(
 /// GET /api/documents/{id}/download?format=odf|pdf|md
 pub async fn download_document(
     Extension(db): Extension<Arc<Database>>,
-    Extension(s3_client): Extension<Arc<iou_core::storage::S3Client>>,
+    Extension(storage): Extension<Arc<dyn iou_core::storage::StorageBackend>>,
     Path(id): Path<Uuid>,
     Query(params): Query<DownloadParams>,
 ) -> Result<impl IntoResponse, ApiError> {
@@ -396,8 +396,7 @@ pub async fn download_document(
         document.current_version_key.clone()
     };
 
-    // Download from S3
-    let data = s3_client.download(&storage_key).await?;
+    let data = storage.download(&storage_key).await?;
 
     // Determine content type
     let content_type = params.format
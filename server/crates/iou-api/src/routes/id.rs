@@ -1,9 +1,18 @@
 //! Routes voor de identity / NL Wallet-module.
 
+use std::sync::Arc;
+
+use axum::extract::{Extension, Form, Path};
+use axum::http::header;
+use axum::response::IntoResponse;
 use axum::Json;
+use iou_core::ssi::openid4vp::REQUEST_OBJECT_TYP;
+use iou_core::ssi::{AuthorizationResponse, PresentationQuery};
+use uuid::Uuid;
 
 use crate::error::ApiError;
 use crate::id::nl_wallet::WalletWebSessionResponse;
+use crate::id::openid4vp::{CreatedSession, Openid4vpService, SessionStatus};
 
 #[derive(Debug, serde::Deserialize)]
 pub struct NlWalletStartBody {
@@ -43,3 +52,65 @@ pub async fn nl_wallet_create_session(
 
     Ok(Json(out))
 }
+
+fn openid4vp(service: Option<Arc<Openid4vpService>>) -> Result<Arc<Openid4vpService>, ApiError> {
+    service.ok_or_else(|| {
+        ApiError::ServiceUnavailable(
+            "OPENID4VP_CLIENT_ID en OPENID4VP_PUBLIC_URL zijn niet gezet; zie crates/iou-api/src/id/mod.rs"
+                .into(),
+        )
+    })
+}
+
+fn unknown_session(id: Uuid) -> ApiError {
+    ApiError::NotFound(format!("Geen lopende OpenID4VP-sessie {}", id))
+}
+
+/// `POST /api/id/openid4vp/sessions`
+///
+/// Body `{"dcql_query": {...}}` of `{"presentation_definition": {...}}` → `authorization_uri`
+/// (`openid4vp://`, voor QR-code of same-device) en de `status_url` om te pollen.
+pub async fn openid4vp_create_session(
+    Extension(service): Extension<Option<Arc<Openid4vpService>>>,
+    Json(query): Json<PresentationQuery>,
+) -> Result<Json<CreatedSession>, ApiError> {
+    Ok(Json(openid4vp(service)?.create_session(query)))
+}
+
+/// `GET /api/id/openid4vp/sessions/{id}/request`
+///
+/// Het ondertekende request object (JAR) voor de wallet.
+pub async fn openid4vp_request_object(
+    Extension(service): Extension<Option<Arc<Openid4vpService>>>,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, ApiError> {
+    let jwt = openid4vp(service)?
+        .request_object(id)
+        .ok_or_else(|| unknown_session(id))?
+        .map_err(|e| ApiError::Internal(anyhow::anyhow!("request object: {}", e)))?;
+    Ok(([(header::CONTENT_TYPE, format!("application/{}", REQUEST_OBJECT_TYP))], jwt))
+}
+
+/// `POST /api/id/openid4vp/sessions/{id}/response`
+///
+/// `direct_post` van de wallet (form encoded `vp_token`, `presentation_submission`, `state`).
+pub async fn openid4vp_response(
+    Extension(service): Extension<Option<Arc<Openid4vpService>>>,
+    Path(id): Path<Uuid>,
+    Form(response): Form<AuthorizationResponse>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    match openid4vp(service)?.complete(id, response).await.ok_or_else(|| unknown_session(id))? {
+        SessionStatus::Failed { reason } => Err(ApiError::Validation(reason)),
+        _ => Ok(Json(serde_json::json!({}))),
+    }
+}
+
+/// `GET /api/id/openid4vp/sessions/{id}`
+///
+/// Stand van de sessie; na `completed` met de geverifieerde credentials en claims.
+pub async fn openid4vp_session_status(
+    Extension(service): Extension<Option<Arc<Openid4vpService>>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SessionStatus>, ApiError> {
+    openid4vp(service)?.status(id).map(Json).ok_or_else(|| unknown_session(id))
+}
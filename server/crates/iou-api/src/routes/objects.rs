@@ -10,17 +10,22 @@ use uuid::Uuid;
 
 use crate::db::Database;
 use crate::error::ApiError;
+use crate::middleware::auth::{AuthContext, Permission};
+use crate::middleware::policy::{authorize, object_resource};
 use iou_core::api_types::{CreateObjectRequest, CreateObjectResponse};
 use iou_core::objects::InformationObject;
 
 /// GET /objects/:id - Get an information object
 pub async fn get_object(
     Path(object_id): Path<Uuid>,
+    Extension(auth): Extension<AuthContext>,
     Extension(db): Extension<Arc<Database>>,
 ) -> Result<Json<InformationObject>, ApiError> {
     let object = db
         .get_object(object_id)?
         .ok_or_else(|| ApiError::NotFound(format!("Object {} not found", object_id)))?;
+    let resource = object_resource(object.id, object.domain_id, Some(object.classification));
+    authorize(&auth, Permission::ObjectRead, &resource)?;
 
     Ok(Json(object))
 }
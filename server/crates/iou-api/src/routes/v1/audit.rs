@@ -0,0 +1,255 @@
+//! Audit trail endpoints
+//!
+//! Auditors (Archiefwet, BIO) verify that the organisation's audit trail
+//! was not altered: the hash chain is walked from the first entry and
+//! checked against the signed checkpoints.
+//!
+//! Entries are listed page by page with a cursor, and whole periods can be
+//! exported as JSON Lines or CSV for auditors. Retention runs archive
+//! expired entries to object storage before purging them.
+
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Extension, Query},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use futures_util::{stream, StreamExt};
+use iou_core::audit::{
+    AuditAction, AuditBackend, AuditError, AuditExportFormat, AuditQuery, AuditQueryResult,
+    ChainVerification, CheckpointKey, PostgresAuditBackend, RetentionPolicy, RetentionReport,
+};
+use iou_core::storage::StorageBackend;
+use serde::Deserialize;
+
+use crate::{
+    error::ApiError,
+    middleware::auth::{require_permission, AuthContext, Permission},
+    supabase::SupabasePool,
+};
+
+impl From<AuditError> for ApiError {
+    fn from(err: AuditError) -> Self {
+        match err {
+            AuditError::NotFound(_) => ApiError::NotFound(err.to_string()),
+            AuditError::Unavailable => ApiError::ServiceUnavailable(err.to_string()),
+            AuditError::InvalidCursor(_) => ApiError::Validation(err.to_string()),
+            _ => ApiError::Internal(anyhow::anyhow!(err)),
+        }
+    }
+}
+
+/// Audit backend with the organisation's checkpoint keys
+///
+/// `IOU_AUDIT_CHECKPOINT_KEY` signs new checkpoints under the id in
+/// `IOU_AUDIT_CHECKPOINT_KEY_ID` (default "default");
+/// `IOU_AUDIT_PREVIOUS_CHECKPOINT_KEYS` holds rotated keys as
+/// comma-separated `id:secret` pairs. Without a key, entries are still
+/// chained but no checkpoints are written.
+pub fn audit_backend(pg: sqlx::PgPool) -> PostgresAuditBackend {
+    let env = |name: &str| std::env::var(name).ok().filter(|s| !s.is_empty());
+    let mut backend = PostgresAuditBackend::new(pg);
+
+    if let Some(secret) = env("IOU_AUDIT_CHECKPOINT_KEY") {
+        let key_id = env("IOU_AUDIT_CHECKPOINT_KEY_ID").unwrap_or_else(|| "default".to_string());
+        backend = backend.with_checkpoint_key(CheckpointKey::new(key_id, secret.into_bytes()));
+    }
+    for pair in env("IOU_AUDIT_PREVIOUS_CHECKPOINT_KEYS").unwrap_or_default().split(',') {
+        if let Some((key_id, secret)) = pair.trim().split_once(':') {
+            backend = backend.with_verification_key(CheckpointKey::new(key_id, secret.as_bytes().to_vec()));
+        }
+    }
+    backend
+}
+
+fn pg_pool(pool: &Option<Arc<SupabasePool>>) -> Result<sqlx::PgPool, ApiError> {
+    pool.as_ref()
+        .map(|p| p.inner().clone())
+        .ok_or_else(|| ApiError::ServiceUnavailable("Audit trail requires Supabase connection".to_string()))
+}
+
+/// Retention rules from `IOU_AUDIT_RETENTION_POLICY` (JSON), default BIO
+fn retention_policy() -> Result<RetentionPolicy, ApiError> {
+    match std::env::var("IOU_AUDIT_RETENTION_POLICY").ok().filter(|s| !s.is_empty()) {
+        Some(json) => serde_json::from_str(&json).map_err(|e| {
+            ApiError::Internal(anyhow::anyhow!("Invalid IOU_AUDIT_RETENTION_POLICY: {}", e))
+        }),
+        None => Ok(RetentionPolicy::bio()),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuditEntriesParams {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub action: Option<String>,
+    pub user_did: Option<String>,
+    pub resource_type: Option<String>,
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    pub limit: Option<u32>,
+    #[serde(default)]
+    pub oldest_first: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuditExportParams {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// `jsonl` (default) or `csv`
+    #[serde(default)]
+    pub format: AuditExportFormat,
+    pub action: Option<String>,
+    pub user_did: Option<String>,
+    pub resource_type: Option<String>,
+}
+
+/// GET /api/v1/audit/entries
+/// One page of the organisation's audit entries
+pub async fn list_audit_entries(
+    Extension(auth): Extension<AuthContext>,
+    Extension(pool): Extension<Option<Arc<SupabasePool>>>,
+    Query(params): Query<AuditEntriesParams>,
+) -> Result<Json<AuditQueryResult>, ApiError> {
+    require_permission(&auth, Permission::AuditView)?;
+
+    let defaults = AuditQuery::default();
+    let query = AuditQuery {
+        start_timestamp: params.from.or(defaults.start_timestamp),
+        end_timestamp: params.to.or(defaults.end_timestamp),
+        user_did: params.user_did,
+        action: params.action.map(AuditAction::from),
+        resource_type: params.resource_type,
+        limit: params.limit.unwrap_or(defaults.limit),
+        cursor: params.cursor,
+        oldest_first: params.oldest_first,
+    };
+
+    let result = audit_backend(pg_pool(&pool)?)
+        .query(&auth.organization_id.to_string(), &query)
+        .await?;
+    Ok(Json(result))
+}
+
+/// GET /api/v1/audit/export
+/// Stream all entries of `[from, to)` as one JSON Lines or CSV file
+///
+/// Pages are fetched while the response is written, so long periods do
+/// not have to fit in memory.
+pub async fn export_audit_entries(
+    Extension(auth): Extension<AuthContext>,
+    Extension(pool): Extension<Option<Arc<SupabasePool>>>,
+    Query(params): Query<AuditExportParams>,
+) -> Result<Response, ApiError> {
+    require_permission(&auth, Permission::AuditView)?;
+    if params.to <= params.from {
+        return Err(ApiError::Validation("'to' must be after 'from'".to_string()));
+    }
+
+    let backend = Arc::new(audit_backend(pg_pool(&pool)?));
+    let tenant_id = auth.organization_id.to_string();
+    let format = params.format;
+    let query = AuditQuery {
+        user_did: params.user_did,
+        action: params.action.map(AuditAction::from),
+        resource_type: params.resource_type,
+        ..AuditQuery::range(params.from, params.to)
+    };
+
+    tracing::info!(
+        "Audit export of tenant {} from {} to {} by {}",
+        tenant_id,
+        params.from,
+        params.to,
+        auth.user_id
+    );
+
+    let pages = stream::unfold(Some(query.clone()), move |page| {
+        let backend = backend.clone();
+        let tenant_id = tenant_id.clone();
+        let query = query.clone();
+        async move {
+            let page = page?;
+            match backend.query(&tenant_id, &page).await {
+                Ok(result) => {
+                    let chunk: String = result.entries.iter().map(|e| format.line(e)).collect();
+                    let next = result.next_cursor.map(|cursor| query.after(cursor));
+                    Some((Ok::<_, std::io::Error>(chunk), next))
+                }
+                Err(e) => {
+                    tracing::error!("Audit export failed: {}", e);
+                    Some((Err(std::io::Error::other(e.to_string())), None))
+                }
+            }
+        }
+    });
+    let body = Body::from_stream(stream::once(async move { Ok(format.header()) }).chain(pages));
+
+    let filename = format!(
+        "audit-{}-{}.{}",
+        params.from.format("%Y%m%d"),
+        params.to.format("%Y%m%d"),
+        format.extension()
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// POST /api/v1/admin/audit/retention
+/// Archive and purge the organisation's expired audit entries
+pub async fn apply_audit_retention(
+    Extension(auth): Extension<AuthContext>,
+    Extension(pool): Extension<Option<Arc<SupabasePool>>>,
+    Extension(storage): Extension<Arc<dyn StorageBackend>>,
+) -> Result<Json<RetentionReport>, ApiError> {
+    require_permission(&auth, Permission::OrganizationManage)?;
+
+    let policy = retention_policy()?;
+    let report = audit_backend(pg_pool(&pool)?)
+        .apply_retention(&auth.organization_id.to_string(), &policy, storage.as_ref(), Utc::now())
+        .await?;
+
+    tracing::info!(
+        "Audit retention for tenant {}: {} archived, {} purged, partitions dropped: {:?}",
+        report.tenant_id,
+        report.archived,
+        report.purged,
+        report.partitions_dropped
+    );
+
+    Ok(Json(report))
+}
+
+/// GET /api/v1/audit/verify
+/// Walk the organisation's audit chain and report the first broken link
+pub async fn verify_audit_chain(
+    Extension(auth): Extension<AuthContext>,
+    Extension(pool): Extension<Option<Arc<SupabasePool>>>,
+) -> Result<Json<ChainVerification>, ApiError> {
+    require_permission(&auth, Permission::AuditView)?;
+
+    let verification = audit_backend(pg_pool(&pool)?)
+        .verify_chain(&auth.organization_id.to_string())
+        .await?;
+
+    if let Some(broken) = &verification.first_break {
+        tracing::warn!(
+            "Audit chain of tenant {} is broken at entry {}: {}",
+            verification.tenant_id,
+            broken.sequence,
+            broken
+        );
+    }
+
+    Ok(Json(verification))
+}
@@ -19,6 +19,7 @@ use iou_core::{
 use crate::{
     error::ApiError,
     middleware::auth::{AuthContext, require_permission, Permission},
+    middleware::policy::authorize_object,
     supabase::SupabasePool,
 };
 
@@ -355,7 +356,7 @@ pub async fn categorize_object(
     Extension(auth): Extension<AuthContext>,
     Json(req): Json<CategorizeObjectRequest>,
 ) -> Result<Json<CategoryResponse>, ApiError> {
-    require_permission(&auth, Permission::ObjectUpdate)?;
+    authorize_object(&auth, Permission::ObjectUpdate, pool.inner(), req.object_id).await?;
 
     let repo = CategoryRepository::new(pool.into_inner());
 
@@ -380,7 +381,7 @@ pub async fn uncategorize_object(
     Extension(auth): Extension<AuthContext>,
     Json(req): Json<UncategorizeObjectRequest>,
 ) -> Result<axum::http::StatusCode, ApiError> {
-    require_permission(&auth, Permission::ObjectUpdate)?;
+    authorize_object(&auth, Permission::ObjectUpdate, pool.inner(), req.object_id).await?;
 
     let repo = CategoryRepository::new(pool.into_inner());
     repo.uncategorize_object(req.object_id, req.category_id).await?;
@@ -397,7 +398,7 @@ pub async fn get_object_categories(
     Extension(auth): Extension<AuthContext>,
     Path(object_id): Path<Uuid>,
 ) -> Result<Json<Vec<CategoryResponse>>, ApiError> {
-    require_permission(&auth, Permission::ObjectRead)?;
+    authorize_object(&auth, Permission::ObjectRead, pool.inner(), object_id).await?;
 
     let repo = CategoryRepository::new(pool.into_inner());
     let categories = repo.get_object_categories(object_id).await?;
@@ -8,10 +8,17 @@ pub mod woo;
 pub mod categories;
 pub mod tags;
 pub mod settings;
+pub mod vernietiging;
+pub mod audit;
+pub mod trust_registry;
+pub mod policies;
 
 pub use rules::{list_rules, evaluate_rule, get_open_regels_rule, RuleEvaluationRequest};
 pub use calculations::{start_calculation, CalculationRequest, CalculationResponse};
-pub use processes::{start_process, get_process, ProcessRequest, ProcessResponse, ProcessInstanceResponse};
+pub use processes::{
+    start_process, get_process, get_process_history, ProcessRequest, ProcessResponse,
+    ProcessInstanceResponse, ProcessHistoryResponse,
+};
 
 // Data Subject Rights exports
 pub use data_subject_rights::{
@@ -27,5 +34,28 @@ pub use woo::{
     approve_woo_publication, publish_woo_publication, withdraw_woo_publication,
     create_woo_request, list_woo_requests, get_woo_request,
     get_woo_statistics, get_woo_deadlines, get_published_woo_documents,
-    mark_consultation_complete,
+    mark_consultation_complete, get_woo_request_deadline, add_woo_deadline_event,
+};
+
+// Disposal run (Archiefwet) exports
+pub use vernietiging::{
+    create_vernietigingslijst, list_vernietigingslijsten, get_vernietigingslijst,
+    submit_vernietigingslijst, exclude_from_vernietigingslijst, review_vernietigingslijst,
+    execute_vernietigingslijst, get_verklaring_van_vernietiging, get_archiefinstelling,
+    put_archiefinstelling,
+};
+
+// Audit trail exports
+pub use audit::{apply_audit_retention, export_audit_entries, list_audit_entries, verify_audit_chain};
+
+// Trust registry exports
+pub use trust_registry::{
+    list_trusted_issuers, upsert_trusted_issuer, remove_trusted_issuer,
+    load_trust_registry, reload_trust_registry,
+};
+
+// Access policy exports
+pub use policies::{
+    list_policies, get_tenant_policy, put_tenant_policy, delete_tenant_policy,
+    test_tenant_policy, explain_decision,
 };
@@ -0,0 +1,169 @@
+//! Access policy endpoints
+//!
+//! Administrators view the built-in policy, upload a policy per tenant
+//! (organization id), dry-run its tests, and ask the engine why a
+//! request would be permitted or denied. Uploaded policies only become
+//! active when their tests pass, and are written to `IOU_POLICY_DIR` when
+//! that directory exists so they survive a restart. Administrators of an
+//! organization only see and change its own policy; platform
+//! administrators manage every tenant.
+
+use axum::{
+    extract::{Extension, Path},
+    Json,
+};
+use iou_core::tenancy::TenantId;
+use iou_regels::abac::{Decision, Policy, PolicyError, Resource, Subject, TestReport};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::ApiError,
+    middleware::auth::{require_permission, AuthContext, Permission},
+    middleware::policy::{is_platform_admin, policy_dir, policy_engine, require_own_tenant},
+};
+
+impl From<PolicyError> for ApiError {
+    fn from(err: PolicyError) -> Self {
+        ApiError::Validation(err.to_string())
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PoliciesResponse {
+    pub default: Policy,
+    /// Tenants with their own policy
+    pub tenants: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExplainRequest {
+    /// Tenant whose policy applies; the built-in policy when absent
+    pub tenant: Option<String>,
+    #[serde(default)]
+    pub subject: Subject,
+    pub action: String,
+    #[serde(default)]
+    pub resource: Resource,
+}
+
+/// Validated tenant the user may manage
+fn tenant_key(auth: &AuthContext, tenant: &str) -> Result<String, ApiError> {
+    let tenant = TenantId::new(tenant)
+        .map(|id| id.as_str().to_string())
+        .map_err(|e| ApiError::Validation(e.to_string()))?;
+    require_own_tenant(auth, &tenant)?;
+    Ok(tenant)
+}
+
+/// Write or remove the tenant's policy file, if the policy directory exists
+fn persist(tenant: &str, source: Option<&str>) {
+    let dir = policy_dir();
+    if !dir.is_dir() {
+        return;
+    }
+    let path = dir.join(format!("{}.yaml", tenant));
+    let result = match source {
+        Some(source) => std::fs::write(&path, source),
+        None if path.exists() => std::fs::remove_file(&path),
+        None => Ok(()),
+    };
+    if let Err(e) = result {
+        tracing::warn!("Access policy file {} not updated: {}", path.display(), e);
+    }
+}
+
+/// GET /api/v1/admin/policies
+/// Built-in policy and the tenants that adjust it
+pub async fn list_policies(
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<PoliciesResponse>, ApiError> {
+    require_permission(&auth, Permission::RoleManage)?;
+
+    let engine = policy_engine();
+    let mut tenants = engine.tenants();
+    if !is_platform_admin(&auth) {
+        let own = auth.organization_id.to_string();
+        tenants.retain(|tenant| *tenant == own);
+    }
+    Ok(Json(PoliciesResponse {
+        default: engine.default_policy().clone(),
+        tenants,
+    }))
+}
+
+/// GET /api/v1/admin/policies/{tenant}
+pub async fn get_tenant_policy(
+    Extension(auth): Extension<AuthContext>,
+    Path(tenant): Path<String>,
+) -> Result<Json<Policy>, ApiError> {
+    require_permission(&auth, Permission::RoleManage)?;
+    let tenant = tenant_key(&auth, &tenant)?;
+
+    policy_engine()
+        .tenant_policy(&tenant)
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("Access policy for {}", tenant)))
+}
+
+/// PUT /api/v1/admin/policies/{tenant}
+/// Activate a tenant policy (YAML body) once its tests pass
+pub async fn put_tenant_policy(
+    Extension(auth): Extension<AuthContext>,
+    Path(tenant): Path<String>,
+    body: String,
+) -> Result<Json<TestReport>, ApiError> {
+    require_permission(&auth, Permission::RoleManage)?;
+    let tenant = tenant_key(&auth, &tenant)?;
+
+    let policy = Policy::from_yaml(&body)?;
+    // The file is loaded for its `tenant` on restart, so it must match
+    if policy.tenant.as_ref().is_some_and(|own| *own != tenant) {
+        return Err(ApiError::Validation(format!("Policy is for another tenant than {}", tenant)));
+    }
+    let report = policy_engine().set_tenant_policy(&tenant, policy)?;
+    persist(&tenant, Some(&body));
+    tracing::info!("Access policy for {} replaced by {} ({} tests passed)", tenant, auth.user_id, report.passed);
+    Ok(Json(report))
+}
+
+/// DELETE /api/v1/admin/policies/{tenant}
+/// Fall back to the built-in policy
+pub async fn delete_tenant_policy(
+    Extension(auth): Extension<AuthContext>,
+    Path(tenant): Path<String>,
+) -> Result<Json<Policy>, ApiError> {
+    require_permission(&auth, Permission::RoleManage)?;
+    let tenant = tenant_key(&auth, &tenant)?;
+
+    let removed = policy_engine()
+        .remove_tenant_policy(&tenant)
+        .ok_or_else(|| ApiError::NotFound(format!("Access policy for {}", tenant)))?;
+    persist(&tenant, None);
+    tracing::info!("Access policy for {} removed by {}", tenant, auth.user_id);
+    Ok(Json(removed))
+}
+
+/// POST /api/v1/admin/policies/{tenant}/test
+/// Run the tests of a policy (YAML body) without activating it
+pub async fn test_tenant_policy(
+    Extension(auth): Extension<AuthContext>,
+    Path(tenant): Path<String>,
+    body: String,
+) -> Result<Json<TestReport>, ApiError> {
+    require_permission(&auth, Permission::RoleManage)?;
+    let tenant = tenant_key(&auth, &tenant)?;
+
+    Ok(Json(policy_engine().test_policy(&tenant, Policy::from_yaml(&body)?)?))
+}
+
+/// POST /api/v1/admin/policies/explain
+/// Decision with the rules that led to it, for any subject and resource
+pub async fn explain_decision(
+    Extension(auth): Extension<AuthContext>,
+    Json(req): Json<ExplainRequest>,
+) -> Result<Json<Decision>, ApiError> {
+    require_permission(&auth, Permission::RoleManage)?;
+    let tenant = req.tenant.as_deref().map(|tenant| tenant_key(&auth, tenant)).transpose()?;
+
+    Ok(Json(policy_engine().decide(tenant.as_deref(), &req.subject, &req.action, &req.resource)))
+}
@@ -2,7 +2,7 @@
 
 use iou_core::audit::{AuditAction, AuditEntry};
 use iou_core::tenancy::TenantContext;
-use iou_regels::BpmnProcessEngine;
+use iou_regels::{ActivityRecord, BpmnProcessEngine, ProcessInstance};
 use axum::{
     extract::{Extension, State, Path},
     Json,
@@ -34,10 +34,38 @@ pub struct ProcessInstanceResponse {
     pub process_definition_id: String,
     pub status: String,
     pub variables: HashMap<String, serde_json::Value>,
+    pub current_activity: Option<String>,
+    pub waiting_tasks: Vec<String>,
     pub started_at: chrono::DateTime<chrono::Utc>,
     pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+impl From<ProcessInstance> for ProcessInstanceResponse {
+    fn from(instance: ProcessInstance) -> Self {
+        Self {
+            waiting_tasks: instance.waiting_tasks().into_iter().map(str::to_string).collect(),
+            id: instance.id,
+            process_definition_id: instance.definition_id,
+            status: instance.state.to_string(),
+            variables: instance
+                .variables
+                .into_iter()
+                .map(|(k, v)| (k, serde_json::to_value(v).unwrap_or_default()))
+                .collect(),
+            current_activity: instance.current_activity,
+            started_at: instance.started_at,
+            completed_at: instance.completed_at,
+        }
+    }
+}
+
+/// Process instance history
+#[derive(Debug, Serialize)]
+pub struct ProcessHistoryResponse {
+    pub process_instance_id: Uuid,
+    pub activities: Vec<ActivityRecord>,
+}
+
 /// Start a BPMN process instance
 pub async fn start_process(
     Extension(tenant): Extension<TenantContext>,
@@ -91,7 +119,7 @@ pub async fn start_process(
 
     Ok(Json(ProcessResponse {
         process_instance_id: instance.id,
-        status: format!("{:?}", instance.state),
+        status: instance.state.to_string(),
         status_url: format!("/v1/processes/{}", instance.id),
     }))
 }
@@ -101,7 +129,7 @@ pub async fn get_process(
     Extension(tenant): Extension<TenantContext>,
     Extension(audit): Extension<iou_core::audit::SharedAuditLogger>,
     Path(id): Path<Uuid>,
-    State(_engine): State<Arc<BpmnProcessEngine>>,
+    State(engine): State<Arc<BpmnProcessEngine>>,
 ) -> Result<Json<ProcessInstanceResponse>, crate::error::ApiError> {
     // Write audit
     let audit_entry = AuditEntry::new(
@@ -113,14 +141,48 @@ pub async fn get_process(
     );
     let _ = iou_core::audit::log_shared(&audit, &audit_entry).await;
 
-    // TODO: Query process instance from engine
-    // For now, return a mock response
-    Ok(Json(ProcessInstanceResponse {
-        id,
-        process_definition_id: "unknown".to_string(),
-        status: "running".to_string(),
-        variables: HashMap::new(),
-        started_at: chrono::Utc::now(),
-        completed_at: None,
+    let instance = engine
+        .get_instance(id)
+        .await
+        .map_err(|e| crate::error::ApiError::Internal(anyhow::anyhow!("Failed to load process: {}", e)))?
+        .ok_or_else(|| crate::error::ApiError::NotFound(format!("Process instance {}", id)))?;
+
+    Ok(Json(instance.into()))
+}
+
+/// Get the completed activities of a process instance, oldest first
+pub async fn get_process_history(
+    Extension(tenant): Extension<TenantContext>,
+    Extension(audit): Extension<iou_core::audit::SharedAuditLogger>,
+    Path(id): Path<Uuid>,
+    State(engine): State<Arc<BpmnProcessEngine>>,
+) -> Result<Json<ProcessHistoryResponse>, crate::error::ApiError> {
+    // Write audit
+    let audit_entry = AuditEntry::new(
+        tenant.tenant_id.as_str().to_string(),
+        tenant.holder_did.clone(),
+        AuditAction::Custom("process_history".to_string()),
+        "process".to_string(),
+        id.to_string(),
+    );
+    let _ = iou_core::audit::log_shared(&audit, &audit_entry).await;
+
+    if engine
+        .get_instance(id)
+        .await
+        .map_err(|e| crate::error::ApiError::Internal(anyhow::anyhow!("Failed to load process: {}", e)))?
+        .is_none()
+    {
+        return Err(crate::error::ApiError::NotFound(format!("Process instance {}", id)));
+    }
+
+    let activities = engine
+        .instance_history(id)
+        .await
+        .map_err(|e| crate::error::ApiError::Internal(anyhow::anyhow!("Failed to load history: {}", e)))?;
+
+    Ok(Json(ProcessHistoryResponse {
+        process_instance_id: id,
+        activities,
     }))
 }
@@ -19,6 +19,7 @@ use iou_core::{
 use crate::{
     error::ApiError,
     middleware::auth::{AuthContext, require_permission, Permission},
+    middleware::policy::authorize_object,
     supabase::SupabasePool,
 };
 
@@ -282,7 +283,7 @@ pub async fn tag_object(
     Extension(auth): Extension<AuthContext>,
     Json(req): Json<TagObjectRequest>,
 ) -> Result<Json<TagResponse>, ApiError> {
-    require_permission(&auth, Permission::ObjectUpdate)?;
+    authorize_object(&auth, Permission::ObjectUpdate, pool.inner(), req.object_id).await?;
 
     let repo = TagRepository::new(pool.into_inner());
 
@@ -308,7 +309,7 @@ pub async fn untag_object(
     Extension(auth): Extension<AuthContext>,
     Json(req): Json<UntagObjectRequest>,
 ) -> Result<axum::http::StatusCode, ApiError> {
-    require_permission(&auth, Permission::ObjectUpdate)?;
+    authorize_object(&auth, Permission::ObjectUpdate, pool.inner(), req.object_id).await?;
 
     let repo = TagRepository::new(pool.into_inner());
     repo.untag_object(req.object_id, req.tag_id).await?;
@@ -325,7 +326,7 @@ pub async fn get_object_tags(
     Extension(auth): Extension<AuthContext>,
     Path(object_id): Path<Uuid>,
 ) -> Result<Json<Vec<TagResponse>>, ApiError> {
-    require_permission(&auth, Permission::ObjectRead)?;
+    authorize_object(&auth, Permission::ObjectRead, pool.inner(), object_id).await?;
 
     let repo = TagRepository::new(pool.into_inner());
     let tags = repo.get_object_tags(object_id).await?;
@@ -343,7 +344,7 @@ pub async fn get_tag_suggestions(
     Path(object_id): Path<Uuid>,
     Query(query): Query<std::collections::HashMap<String, String>>,
 ) -> Result<Json<Vec<TagSuggestion>>, ApiError> {
-    require_permission(&auth, Permission::ObjectRead)?;
+    authorize_object(&auth, Permission::ObjectRead, pool.inner(), object_id).await?;
 
     let repo = TagRepository::new(pool.into_inner());
     let limit = query.get("limit")
@@ -0,0 +1,304 @@
+//! Trust registry endpoints
+//!
+//! The registry of trusted credential issuers is published as a signed
+//! file (`VC_TRUST_REGISTRY_FILE`), signed by one of the anchor keys in
+//! `VC_TRUST_REGISTRY_ANCHORS`. Platform administrators load a newer
+//! signed file or reload the configured one, and manage entries of any
+//! municipality. Organization administrators see, add and remove the
+//! entries of their own municipality (the `municipality` claim of their
+//! token); such entries are marked unsigned, may not allow `roles` or
+//! `*`, and hold until the next signed file is loaded.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Extension, Path, Query},
+    Json,
+};
+use iou_core::ssi::{PublicKey, TrustAnchor, TrustRegistry, TrustRegistryError, TrustedIssuer};
+use iou_core::ssi::did::Jwk;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    error::ApiError,
+    middleware::auth::{require_permission, AuthContext, Permission},
+    middleware::policy::{is_platform_admin, require_platform_admin},
+};
+
+impl From<TrustRegistryError> for ApiError {
+    fn from(err: TrustRegistryError) -> Self {
+        match err {
+            TrustRegistryError::InvalidSignature(_) => ApiError::Forbidden(err.to_string()),
+            TrustRegistryError::InvalidDocument(_) | TrustRegistryError::Rollback { .. } => {
+                ApiError::Validation(err.to_string())
+            }
+            TrustRegistryError::Io(_) => ApiError::Internal(anyhow::anyhow!(err)),
+        }
+    }
+}
+
+/// Registry with the configured anchors and signed file
+///
+/// `VC_TRUST_REGISTRY_ANCHORS` is a JSON array of public JWKs with a
+/// `kid`. Without a readable file the registry starts empty and the flat
+/// `VC_TRUSTED_ISSUERS` list applies.
+pub fn trust_registry_from_env() -> Arc<TrustRegistry> {
+    let anchors: Vec<TrustAnchor> = std::env::var("VC_TRUST_REGISTRY_ANCHORS")
+        .ok()
+        .and_then(|json| match serde_json::from_str::<Vec<Jwk>>(&json) {
+            Ok(jwks) => Some(jwks),
+            Err(e) => {
+                tracing::warn!("Invalid VC_TRUST_REGISTRY_ANCHORS: {}", e);
+                None
+            }
+        })
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|jwk| match PublicKey::from_jwk(&jwk) {
+            Ok(key) => Some(TrustAnchor { kid: jwk.kid.unwrap_or_default(), key }),
+            Err(e) => {
+                tracing::warn!("Ignoring trust anchor {:?}: {}", jwk.kid, e);
+                None
+            }
+        })
+        .collect();
+
+    let registry = Arc::new(TrustRegistry::new(anchors));
+    if let Some(path) = registry_file()
+        && let Err(e) = registry.load_file(&path)
+    {
+        tracing::warn!("Trust registry {} not loaded: {}", path, e);
+    }
+    registry
+}
+
+fn registry_file() -> Option<String> {
+    std::env::var("VC_TRUST_REGISTRY_FILE").ok().filter(|s| !s.is_empty())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TrustRegistryParams {
+    /// Only entries of this municipality
+    pub tenant: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TrustRegistryResponse {
+    pub version: u64,
+    pub issuers: Vec<TrustedIssuer>,
+}
+
+/// GET /api/v1/admin/trust-registry
+/// Trusted issuers, optionally of one municipality
+///
+/// Organization administrators only see their own municipality.
+pub async fn list_trusted_issuers(
+    Extension(auth): Extension<AuthContext>,
+    Extension(registry): Extension<Arc<TrustRegistry>>,
+    Query(params): Query<TrustRegistryParams>,
+) -> Result<Json<TrustRegistryResponse>, ApiError> {
+    require_permission(&auth, Permission::OrganizationManage)?;
+    let tenant = match params.tenant {
+        Some(tenant) => Some(tenant),
+        None if is_platform_admin(&auth) => None,
+        None => Some(own_municipality(&auth)?.to_string()),
+    };
+    require_own_municipality(&auth, tenant.as_deref())?;
+
+    Ok(Json(TrustRegistryResponse {
+        version: registry.version(),
+        issuers: registry.issuers(tenant.as_deref()),
+    }))
+}
+
+/// Municipality of the user's organization
+fn own_municipality(auth: &AuthContext) -> Result<&str, ApiError> {
+    auth.municipality
+        .as_ref()
+        .map(|municipality| municipality.as_str())
+        .ok_or_else(|| ApiError::Forbidden("Organization has no municipality".to_string()))
+}
+
+/// Require that `tenant` is the user's own municipality, unless the user
+/// is a platform administrator
+fn require_own_municipality(auth: &AuthContext, tenant: Option<&str>) -> Result<(), ApiError> {
+    let own = auth.municipality.as_ref().map(|municipality| municipality.as_str());
+    if is_platform_admin(auth) || (tenant.is_some() && tenant == own) {
+        return Ok(());
+    }
+    tracing::debug!(user_id = %auth.user_id, "Access to trusted issuers of {:?} denied", tenant);
+    Err(ApiError::Forbidden(format!("Permission {:?} required", Permission::PlatformManage)))
+}
+
+/// Require that the entry belongs to the user's municipality
+fn require_own_entry(auth: &AuthContext, issuer: &TrustedIssuer) -> Result<(), ApiError> {
+    require_own_municipality(auth, issuer.tenant.as_deref())
+}
+
+/// POST /api/v1/admin/trust-registry/issuers
+/// Add an entry, or replace the entry with the same id
+///
+/// Entries of organization administrators always apply to their own
+/// municipality.
+pub async fn upsert_trusted_issuer(
+    Extension(auth): Extension<AuthContext>,
+    Extension(registry): Extension<Arc<TrustRegistry>>,
+    Json(mut issuer): Json<TrustedIssuer>,
+) -> Result<Json<TrustedIssuer>, ApiError> {
+    require_permission(&auth, Permission::OrganizationManage)?;
+    if !is_platform_admin(&auth) {
+        issuer.tenant = Some(own_municipality(&auth)?.to_string());
+    }
+    if let Some(existing) = registry.issuer(issuer.id) {
+        require_own_entry(&auth, &existing)?;
+    }
+
+    let issuer = registry.upsert(issuer)?;
+    tracing::info!(
+        "Trusted issuer {} for {} ({}) set by {}",
+        issuer.did,
+        issuer.credential_type,
+        issuer.tenant.as_deref().unwrap_or("no municipality"),
+        auth.user_id
+    );
+    Ok(Json(issuer))
+}
+
+/// DELETE /api/v1/admin/trust-registry/issuers/{id}
+pub async fn remove_trusted_issuer(
+    Extension(auth): Extension<AuthContext>,
+    Extension(registry): Extension<Arc<TrustRegistry>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<TrustedIssuer>, ApiError> {
+    require_permission(&auth, Permission::OrganizationManage)?;
+    let existing = registry
+        .issuer(id)
+        .ok_or_else(|| ApiError::NotFound(format!("Trusted issuer {}", id)))?;
+    require_own_entry(&auth, &existing)?;
+
+    let removed = registry
+        .remove(id)
+        .ok_or_else(|| ApiError::NotFound(format!("Trusted issuer {}", id)))?;
+    tracing::info!("Trusted issuer {} for {} removed by {}", removed.did, removed.credential_type, auth.user_id);
+    Ok(Json(removed))
+}
+
+/// POST /api/v1/admin/trust-registry/load
+/// Replace the registry with a signed registry file (compact JWS body)
+pub async fn load_trust_registry(
+    Extension(auth): Extension<AuthContext>,
+    Extension(registry): Extension<Arc<TrustRegistry>>,
+    body: String,
+) -> Result<Json<TrustRegistryResponse>, ApiError> {
+    require_platform_admin(&auth)?;
+
+    let version = registry.load_signed(&body)?;
+    Ok(Json(TrustRegistryResponse { version, issuers: registry.issuers(None) }))
+}
+
+/// POST /api/v1/admin/trust-registry/reload
+/// Read the configured signed file again
+pub async fn reload_trust_registry(
+    Extension(auth): Extension<AuthContext>,
+    Extension(registry): Extension<Arc<TrustRegistry>>,
+) -> Result<Json<TrustRegistryResponse>, ApiError> {
+    require_platform_admin(&auth)?;
+
+    let path = registry_file()
+        .ok_or_else(|| ApiError::ServiceUnavailable("VC_TRUST_REGISTRY_FILE is not set".to_string()))?;
+    let version = registry.load_file(&path)?;
+    Ok(Json(TrustRegistryResponse { version, issuers: registry.issuers(None) }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::auth::Role;
+    use chrono::Utc;
+    use iou_core::ssi::VerifiableCredential;
+    use iou_core::tenancy::TenantId;
+    use serde_json::json;
+
+    fn admin(municipality: &str) -> AuthContext {
+        AuthContext {
+            user_id: Uuid::new_v4(),
+            email: format!("beheer@{}.nl", municipality),
+            organization_id: Uuid::new_v4(),
+            roles: vec![Role::Admin],
+            municipality: Some(TenantId::new(municipality).unwrap()),
+            loa: None,
+            department: None,
+            delegations: Vec::new(),
+        }
+    }
+
+    fn issuer(claims: &[&str]) -> Json<TrustedIssuer> {
+        Json(
+            serde_json::from_value(json!({
+                "did": "did:web:utrecht.nl",
+                "credential_type": "MedewerkerCredential",
+                "allowed_claims": claims,
+            }))
+            .unwrap(),
+        )
+    }
+
+    fn credential(municipality: &str) -> VerifiableCredential {
+        serde_json::from_value(json!({
+            "type": ["VerifiableCredential", "MedewerkerCredential"],
+            "issuer": "did:web:utrecht.nl",
+            "credentialSubject": {"municipality": municipality, "displayName": "Jan"},
+            "proof": {"type": "DataIntegrityProof", "proofPurpose": "assertionMethod", "verificationMethod": ""}
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_entries_of_organization_administrators() {
+        let registry = Arc::new(TrustRegistry::default());
+        let Json(added) = upsert_trusted_issuer(
+            Extension(admin("utrecht")),
+            Extension(registry.clone()),
+            issuer(&["municipality", "displayName"]),
+        )
+        .await
+        .unwrap();
+
+        // The entry trusts credentials of the administrator's municipality
+        assert_eq!(added.tenant.as_deref(), Some("utrecht"));
+        registry.check(&credential("utrecht"), Utc::now()).unwrap();
+        assert!(registry.check(&credential("amsterdam"), Utc::now()).is_err());
+
+        // Only a signed file may let an issuer assert roles or anything
+        for claim in ["roles", "*"] {
+            let result =
+                upsert_trusted_issuer(Extension(admin("utrecht")), Extension(registry.clone()), issuer(&[claim])).await;
+            assert!(matches!(result, Err(ApiError::Validation(_))));
+        }
+
+        // Another municipality neither sees nor removes it
+        let Json(listed) = list_trusted_issuers(
+            Extension(admin("amsterdam")),
+            Extension(registry.clone()),
+            Query(TrustRegistryParams { tenant: None }),
+        )
+        .await
+        .unwrap();
+        assert!(listed.issuers.is_empty());
+        let other = list_trusted_issuers(
+            Extension(admin("amsterdam")),
+            Extension(registry.clone()),
+            Query(TrustRegistryParams { tenant: Some("utrecht".to_string()) }),
+        )
+        .await;
+        assert!(matches!(other, Err(ApiError::Forbidden(_))));
+        let removed = remove_trusted_issuer(Extension(admin("amsterdam")), Extension(registry.clone()), Path(added.id)).await;
+        assert!(matches!(removed, Err(ApiError::Forbidden(_))));
+
+        // Signed files are the platform's
+        let loaded = load_trust_registry(Extension(admin("utrecht")), Extension(registry.clone()), String::new()).await;
+        assert!(matches!(loaded, Err(ApiError::Forbidden(_))));
+        assert_eq!(registry.issuers(Some("utrecht")), vec![added]);
+    }
+}
@@ -0,0 +1,328 @@
+//! Disposal run endpoints (Archiefwet)
+//!
+//! Archivists compose a destruction list for a reference date, reviewers
+//! exclude items and approve or reject it, and once approved the list is
+//! executed. Execution writes a signed certificate of destruction to the
+//! audit log and to `verklaringen_van_vernietiging`. When items fail the
+//! list is `deels_uitgevoerd`; executing it again retries only those items.
+//! While it runs the list is `in_uitvoering`, so it is executed only once
+//! at a time.
+//!
+//! Lists are composed with the organization's own selectielijst and hotspot
+//! register, configured under `/api/v1/archiefinstellingen`.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Extension, Path},
+    Json,
+};
+use chrono::NaiveDate;
+use iou_core::storage::StorageBackend;
+use iou_core::workflows::{ApprovalDecision, ApprovalResponse};
+use iou_regels::{VerklaringVanVernietiging, VernietigingError, Vernietigingslijst};
+use serde::Deserialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    db::Database,
+    error::ApiError,
+    middleware::auth::{require_permission, AuthContext, Permission},
+    supabase::SupabasePool,
+    vernietiging::{
+        lees_hotspotregister, lees_selectielijst, Archiefinstelling, PostgresVernietigingsUitvoerder,
+        VernietigingRepository,
+    },
+};
+
+/// Compose request
+#[derive(Debug, Deserialize)]
+pub struct VernietigingslijstRequest {
+    /// Reference date for expired retention periods (default: today)
+    pub peildatum: Option<NaiveDate>,
+}
+
+/// Submit for review
+#[derive(Debug, Deserialize)]
+pub struct TerBeoordelingRequest {
+    pub beoordelaars: Vec<Uuid>,
+}
+
+/// Exclude an item from destruction
+#[derive(Debug, Deserialize)]
+pub struct UitzonderingRequest {
+    pub object_id: Uuid,
+    pub reden: String,
+}
+
+/// Review decision on a list
+#[derive(Debug, Deserialize)]
+pub struct BeoordelingRequest {
+    pub decision: ApprovalDecision,
+    pub comment: Option<String>,
+    pub delegated_from: Option<Uuid>,
+}
+
+fn pg_pool(pool: &Option<Arc<SupabasePool>>) -> Result<sqlx::PgPool, ApiError> {
+    pool.as_ref()
+        .map(|p| p.inner().clone())
+        .ok_or_else(|| ApiError::ServiceUnavailable("Disposal runs require Supabase connection".to_string()))
+}
+
+fn repository(pool: &Option<Arc<SupabasePool>>) -> Result<VernietigingRepository, ApiError> {
+    Ok(VernietigingRepository::new(pg_pool(pool)?))
+}
+
+async fn load(repo: &VernietigingRepository, auth: &AuthContext, id: Uuid) -> Result<Vernietigingslijst, ApiError> {
+    repo.get(auth.organization_id, id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Vernietigingslijst {}", id)))
+}
+
+impl From<VernietigingError> for ApiError {
+    fn from(err: VernietigingError) -> Self {
+        match err {
+            VernietigingError::OnbekendStuk(_) => ApiError::NotFound(err.to_string()),
+            VernietigingError::GeenBeoordelaar(_) => ApiError::Forbidden(err.to_string()),
+            VernietigingError::Uitvoering(_)
+            | VernietigingError::Audit(_)
+            | VernietigingError::VerklaringNietVastgelegd { .. } => ApiError::Internal(anyhow::anyhow!(err)),
+            _ => ApiError::Validation(err.to_string()),
+        }
+    }
+}
+
+/// POST /api/v1/vernietigingslijsten
+/// Compose a destruction list from the organization's classified objects
+pub async fn create_vernietigingslijst(
+    Extension(auth): Extension<AuthContext>,
+    Extension(pool): Extension<Option<Arc<SupabasePool>>>,
+    Json(req): Json<VernietigingslijstRequest>,
+) -> Result<Json<Vernietigingslijst>, ApiError> {
+    require_permission(&auth, Permission::ComplianceAssess)?;
+    let repo = repository(&pool)?;
+
+    let niet_ingesteld = |instelling: Archiefinstelling| {
+        ApiError::Validation(format!(
+            "No {} configured; upload it to /api/v1/archiefinstellingen/{}",
+            instelling.as_str(),
+            instelling.as_str()
+        ))
+    };
+    let selectielijst = repo
+        .selectielijst(auth.organization_id)
+        .await?
+        .ok_or_else(|| niet_ingesteld(Archiefinstelling::Selectielijst))?;
+    let hotspots = repo
+        .hotspotregister(auth.organization_id)
+        .await?
+        .ok_or_else(|| niet_ingesteld(Archiefinstelling::Hotspotregister))?;
+
+    let peildatum = req.peildatum.unwrap_or_else(|| chrono::Utc::now().date_naive());
+    let lijst = Vernietigingslijst::samenstellen(
+        repo.archiefstukken(auth.organization_id).await?,
+        &selectielijst,
+        Some(&hotspots),
+        peildatum,
+        auth.user_id,
+    );
+    repo.save(auth.organization_id, &lijst).await?;
+
+    tracing::info!(
+        "Vernietigingslijst {} samengesteld: {} te vernietigen, {} overgeslagen",
+        lijst.id,
+        lijst.items.len(),
+        lijst.overgeslagen.len()
+    );
+    Ok(Json(lijst))
+}
+
+/// GET /api/v1/vernietigingslijsten
+pub async fn list_vernietigingslijsten(
+    Extension(auth): Extension<AuthContext>,
+    Extension(pool): Extension<Option<Arc<SupabasePool>>>,
+) -> Result<Json<Vec<Vernietigingslijst>>, ApiError> {
+    require_permission(&auth, Permission::ComplianceAssess)?;
+    Ok(Json(repository(&pool)?.list(auth.organization_id).await?))
+}
+
+/// GET /api/v1/vernietigingslijsten/{id}
+pub async fn get_vernietigingslijst(
+    Extension(auth): Extension<AuthContext>,
+    Extension(pool): Extension<Option<Arc<SupabasePool>>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vernietigingslijst>, ApiError> {
+    require_permission(&auth, Permission::ComplianceAssess)?;
+    Ok(Json(load(&repository(&pool)?, &auth, id).await?))
+}
+
+/// POST /api/v1/vernietigingslijsten/{id}/ter-beoordeling
+pub async fn submit_vernietigingslijst(
+    Extension(auth): Extension<AuthContext>,
+    Extension(pool): Extension<Option<Arc<SupabasePool>>>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<TerBeoordelingRequest>,
+) -> Result<Json<Vernietigingslijst>, ApiError> {
+    require_permission(&auth, Permission::ComplianceAssess)?;
+    let repo = repository(&pool)?;
+    let mut lijst = load(&repo, &auth, id).await?;
+    lijst.ter_beoordeling(req.beoordelaars)?;
+    repo.save(auth.organization_id, &lijst).await?;
+    Ok(Json(lijst))
+}
+
+/// POST /api/v1/vernietigingslijsten/{id}/uitzonderen
+pub async fn exclude_from_vernietigingslijst(
+    Extension(auth): Extension<AuthContext>,
+    Extension(pool): Extension<Option<Arc<SupabasePool>>>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UitzonderingRequest>,
+) -> Result<Json<Vernietigingslijst>, ApiError> {
+    require_permission(&auth, Permission::ComplianceAssess)?;
+    let repo = repository(&pool)?;
+    let mut lijst = load(&repo, &auth, id).await?;
+    lijst.sluit_uit(req.object_id, auth.user_id, req.reden)?;
+    repo.save(auth.organization_id, &lijst).await?;
+    Ok(Json(lijst))
+}
+
+/// POST /api/v1/vernietigingslijsten/{id}/beoordeling
+pub async fn review_vernietigingslijst(
+    Extension(auth): Extension<AuthContext>,
+    Extension(pool): Extension<Option<Arc<SupabasePool>>>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<BeoordelingRequest>,
+) -> Result<Json<Vernietigingslijst>, ApiError> {
+    require_permission(&auth, Permission::ComplianceApprove)?;
+    let repo = repository(&pool)?;
+    let mut lijst = load(&repo, &auth, id).await?;
+
+    let mut response = ApprovalResponse::new(auth.user_id, req.decision, req.comment);
+    if let Some(original) = req.delegated_from {
+        response = response.with_delegation(original);
+    }
+    lijst.beoordeel(response)?;
+    repo.save(auth.organization_id, &lijst).await?;
+    Ok(Json(lijst))
+}
+
+/// POST /api/v1/vernietigingslijsten/{id}/uitvoeren
+/// Execute an approved or partly executed list and return the signed certificate
+///
+/// The signing key comes from `IOU_VERNIETIGING_SLEUTEL`; without it the
+/// endpoint refuses to destroy anything. The list is claimed before anything
+/// is destroyed, and the certificate is stored before any error is returned.
+/// A list stays `in_uitvoering` if the server stops during execution; the
+/// audit log then has a `vernietiging_gestart` entry without certificate.
+pub async fn execute_vernietigingslijst(
+    Extension(auth): Extension<AuthContext>,
+    Extension(pool): Extension<Option<Arc<SupabasePool>>>,
+    Extension(storage): Extension<Arc<dyn StorageBackend>>,
+    Extension(db): Extension<Arc<Database>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<VerklaringVanVernietiging>, ApiError> {
+    require_permission(&auth, Permission::ComplianceApprove)?;
+    let sleutel = std::env::var("IOU_VERNIETIGING_SLEUTEL")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| {
+            ApiError::ServiceUnavailable("IOU_VERNIETIGING_SLEUTEL is not configured".to_string())
+        })?;
+
+    let pg = pg_pool(&pool)?;
+    let repo = VernietigingRepository::new(pg.clone());
+    let Some(mut lijst) = repo.claim(auth.organization_id, id).await? else {
+        let lijst = load(&repo, &auth, id).await?;
+        return Err(VernietigingError::OngeldigeStatus { status: lijst.status, actie: "uitvoeren" }.into());
+    };
+
+    let uitvoerder = PostgresVernietigingsUitvoerder::new(pg.clone(), storage, db);
+    let audit = super::audit::audit_backend(pg);
+    let resultaat = lijst
+        .uitvoeren(
+            &uitvoerder,
+            &audit,
+            &auth.organization_id.to_string(),
+            &auth.user_id.to_string(),
+            sleutel.as_bytes(),
+        )
+        .await;
+
+    // The objects are gone whatever fails next: keep the certificate first
+    let verklaring = match &resultaat {
+        Ok(verklaring) => Some(verklaring),
+        Err(e) => e.verklaring(),
+    };
+    let opgeslagen = match verklaring {
+        Some(verklaring) => repo.save_verklaring(verklaring).await,
+        None => Ok(()),
+    };
+    if let (Err(e), Some(verklaring)) = (&opgeslagen, verklaring) {
+        tracing::error!(
+            "Verklaring {} van vernietigingslijst {} niet opgeslagen: {}",
+            verklaring.id,
+            id,
+            e
+        );
+    }
+    let bijgewerkt = repo.save(auth.organization_id, &lijst).await;
+    opgeslagen?;
+    bijgewerkt?;
+    Ok(Json(resultaat?))
+}
+
+/// GET /api/v1/vernietigingslijsten/{id}/verklaring
+/// The certificate of the latest execution
+pub async fn get_verklaring_van_vernietiging(
+    Extension(auth): Extension<AuthContext>,
+    Extension(pool): Extension<Option<Arc<SupabasePool>>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<VerklaringVanVernietiging>, ApiError> {
+    require_permission(&auth, Permission::AuditView)?;
+    repository(&pool)?
+        .get_verklaring(auth.organization_id, id)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("Verklaring voor vernietigingslijst {}", id)))
+}
+
+/// GET /api/v1/archiefinstellingen/{instelling}
+/// The organization's selectielijst or hotspot register, as uploaded
+pub async fn get_archiefinstelling(
+    Extension(auth): Extension<AuthContext>,
+    Extension(pool): Extension<Option<Arc<SupabasePool>>>,
+    Path(instelling): Path<Archiefinstelling>,
+) -> Result<Json<Value>, ApiError> {
+    require_permission(&auth, Permission::ComplianceAssess)?;
+    repository(&pool)?
+        .archiefinstelling(auth.organization_id, instelling)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("No {} configured", instelling.as_str())))
+}
+
+/// PUT /api/v1/archiefinstellingen/{instelling}
+/// Configure the selectielijst or hotspot register lists are composed with
+///
+/// The body is a JSON export as read by `iou_regels::provisa`'s importers;
+/// it is validated in full before it replaces the current one.
+pub async fn put_archiefinstelling(
+    Extension(auth): Extension<AuthContext>,
+    Extension(pool): Extension<Option<Arc<SupabasePool>>>,
+    Path(instelling): Path<Archiefinstelling>,
+    Json(export): Json<Value>,
+) -> Result<Json<Value>, ApiError> {
+    require_permission(&auth, Permission::OrganizationManage)?;
+    let gelezen = match instelling {
+        Archiefinstelling::Selectielijst => lees_selectielijst(&export).map(drop),
+        Archiefinstelling::Hotspotregister => lees_hotspotregister(&export).map(drop),
+    };
+    gelezen.map_err(|e| ApiError::Validation(e.to_string()))?;
+
+    repository(&pool)?
+        .save_archiefinstelling(auth.organization_id, instelling, &export, auth.user_id)
+        .await?;
+    tracing::info!("{} of organization {} replaced", instelling.as_str(), auth.organization_id);
+    Ok(Json(export))
+}
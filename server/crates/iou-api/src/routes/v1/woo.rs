@@ -22,8 +22,10 @@ use crate::{
     },
     error::ApiError,
     middleware::auth::{AuthContext, require_permission, Permission},
+    middleware::policy::authorize_object,
     supabase::SupabasePool,
 };
+use iou_core::sla::{CaseEvent, DeadlineStatus, LegalCase, LegalDeadlineEngine, Procedure};
 
 /// Woo Publication Request
 #[derive(Debug, Deserialize)]
@@ -172,12 +174,12 @@ pub async fn request_woo_publication(
     Path(object_id): Path<Uuid>,
     Json(req): Json<WooPublicationRequest>,
 ) -> Result<Json<WooPublicationResponse>, ApiError> {
-    require_permission(&auth, Permission::ObjectClassify)?;
-
     let pool = pool.as_ref()
         .ok_or_else(|| ApiError::ServiceUnavailable("Woo functionality requires Supabase connection".to_string()))?;
 
     let repo = crate::dsar::WooRepository::new(pool.inner().clone());
+
+    authorize_object(&auth, Permission::ObjectClassify, pool.inner(), object_id).await?;
     let publication_id = Uuid::new_v4();
     let now = Utc::now();
 
@@ -403,7 +405,8 @@ pub async fn create_woo_request(
     let simple_str = Uuid::new_v4().simple().to_string();
     let reference_number = format!("WOO-{}", &simple_str[..8].to_uppercase());
     let now = Utc::now();
-    let decision_due_date = now.date_naive() + chrono::Days::new(56); // 8 weeks
+    let decision_due_date = LegalDeadlineEngine::netherlands()
+        .initial_due_date(Procedure::WooVerzoek, now.date_naive());
 
     repo.create_request(
         request_id,
@@ -579,3 +582,65 @@ pub async fn mark_consultation_complete(
         "message": "Consultatie afgerond."
     })))
 }
+
+/// GET /api/v1/woo-requests/:id/deadline
+/// Legal due date, deadline history and penalty exposure of a Woo request
+pub async fn get_woo_request_deadline(
+    Extension(auth): Extension<AuthContext>,
+    Extension(pool): Extension<Option<Arc<SupabasePool>>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<DeadlineStatus>, ApiError> {
+    require_permission(&auth, Permission::ComplianceAssess)?;
+
+    let Some(pool) = pool.as_ref() else {
+        return Err(ApiError::ServiceUnavailable("Woo functionality requires Supabase connection".to_string()));
+    };
+
+    let repo = crate::dsar::WooRepository::new(pool.inner().clone());
+    let case = load_woo_case(&repo, id).await?;
+    let status = LegalDeadlineEngine::netherlands()
+        .evaluate(&case, Utc::now().date_naive())
+        .map_err(|e| ApiError::Validation(e.to_string()))?;
+
+    Ok(Json(status))
+}
+
+/// POST /api/v1/woo-requests/:id/deadline-events
+/// Record an event that moves the decision term (verdaging, zienswijze,
+/// opschorting, uitstel, ingebrekestelling, besluit)
+pub async fn add_woo_deadline_event(
+    Extension(auth): Extension<AuthContext>,
+    Extension(pool): Extension<Option<Arc<SupabasePool>>>,
+    Path(id): Path<Uuid>,
+    Json(event): Json<CaseEvent>,
+) -> Result<Json<DeadlineStatus>, ApiError> {
+    require_permission(&auth, Permission::ComplianceAssess)?;
+
+    let Some(pool) = pool.as_ref() else {
+        return Err(ApiError::ServiceUnavailable("Woo functionality requires Supabase connection".to_string()));
+    };
+
+    let repo = crate::dsar::WooRepository::new(pool.inner().clone());
+    let mut case = load_woo_case(&repo, id).await?;
+    case.events.push(event.clone());
+
+    // Validate the new event sequence before storing anything
+    let status = LegalDeadlineEngine::netherlands()
+        .evaluate(&case, Utc::now().date_naive())
+        .map_err(|e| ApiError::Validation(e.to_string()))?;
+
+    repo.add_deadline_event(id, &event, auth.user_id).await?;
+    if let Some(due_date) = status.due_date {
+        repo.update_decision_due_date(id, due_date).await?;
+    }
+
+    Ok(Json(status))
+}
+
+async fn load_woo_case(repo: &WooRepository, id: Uuid) -> Result<LegalCase, ApiError> {
+    let start = repo.get_received_date(id).await?
+        .ok_or_else(|| ApiError::NotFound("Woo request not found".to_string()))?;
+    let events = repo.list_deadline_events(id).await?;
+
+    Ok(LegalCase { procedure: Procedure::WooVerzoek, start, events })
+}
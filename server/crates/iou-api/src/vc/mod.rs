@@ -15,11 +15,14 @@
 //!
 //! # EBSI Compliance
 //!
-//! - Supports DID-based authentication (did:key, did:web)
+//! - Supports DID-based authentication (did:key, did:jwk, did:peer, did:web)
 //! - W3C Verifiable Credentials Data Model v1.1
 //! - JSON-LD JWT proof verification
 //! - SD-JWT for selective disclosure (future)
 
+use std::sync::Arc;
+
+use iou_core::ssi::{PinnedDocuments, StatusCachePolicy, StatusListClient, UniversalDidResolver};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use thiserror::Error;
@@ -49,6 +52,21 @@ pub struct VcConfig {
 
     /// Enable strict mode (reject unknown credential types)
     pub strict_mode: bool,
+
+    /// Verifier identifier presentations must be addressed to (`aud`/`domain`)
+    pub audience: Option<String>,
+
+    /// Caching of revocation status lists
+    pub status_cache: StatusCachePolicy,
+
+    /// Status list URL prefixes fetched from elsewhere, e.g. a fixture server
+    pub status_list_rewrites: Vec<(String, String)>,
+
+    /// Directory of pinned DID documents (`*.json`)
+    pub did_documents_dir: Option<String>,
+
+    /// Resolve only pinned and self-describing DIDs (did:key, did:jwk, did:peer)
+    pub did_offline: bool,
 }
 
 impl Default for VcConfig {
@@ -61,12 +79,20 @@ impl Default for VcConfig {
                 .unwrap_or_else(|_| "change-me-in-production".to_string()),
             vc_token_expiration_min: 60, // 1 hour
             strict_mode: false,
+            audience: None,
+            status_cache: StatusCachePolicy::default(),
+            status_list_rewrites: Vec::new(),
+            did_documents_dir: None,
+            did_offline: false,
         }
     }
 }
 
 impl VcConfig {
-    /// Laad trustlijst en strictheid uit de omgeving (`VC_TRUSTED_ISSUERS`, `VC_STRICT_MODE`, `JWT_SECRET`).
+    /// Laad trustlijst en strictheid uit de omgeving (`VC_TRUSTED_ISSUERS`, `VC_STRICT_MODE`,
+    /// `VC_AUDIENCE`, `JWT_SECRET`) en het statuslijstbeleid (`VC_STATUS_LIST_TTL_SECONDS`,
+    /// `VC_STATUS_LIST_FAIL_OPEN`, `VC_STATUS_LIST_REWRITES` als `prefix=vervanging,...`) en
+    /// DID-resolutie (`VC_DID_DOCUMENTS_DIR`, `VC_DID_OFFLINE`).
     pub fn from_env() -> Self {
         let mut c = Self::default();
         if let Ok(s) = std::env::var("VC_TRUSTED_ISSUERS") {
@@ -82,11 +108,59 @@ impl VcConfig {
         if let Ok(v) = std::env::var("VC_STRICT_MODE") {
             c.strict_mode = v == "1" || v.eq_ignore_ascii_case("true");
         }
+        if let Ok(audience) = std::env::var("VC_AUDIENCE") {
+            c.audience = Some(audience).filter(|a| !a.is_empty());
+        }
         if let Ok(secret) = std::env::var("JWT_SECRET") {
             c.jwt_secret = secret;
         }
+        if let Some(ttl) = std::env::var("VC_STATUS_LIST_TTL_SECONDS").ok().and_then(|v| v.parse().ok()) {
+            c.status_cache.default_ttl = chrono::Duration::seconds(ttl);
+        }
+        if let Ok(v) = std::env::var("VC_STATUS_LIST_FAIL_OPEN") {
+            c.status_cache.fail_closed = !(v == "1" || v.eq_ignore_ascii_case("true"));
+        }
+        if let Ok(s) = std::env::var("VC_STATUS_LIST_REWRITES") {
+            c.status_list_rewrites = s
+                .split(',')
+                .filter_map(|rule| rule.split_once('='))
+                .map(|(prefix, replacement)| (prefix.trim().to_string(), replacement.trim().to_string()))
+                .collect();
+        }
+        if let Ok(dir) = std::env::var("VC_DID_DOCUMENTS_DIR") {
+            c.did_documents_dir = Some(dir).filter(|d| !d.is_empty());
+        }
+        if let Ok(v) = std::env::var("VC_DID_OFFLINE") {
+            c.did_offline = v == "1" || v.eq_ignore_ascii_case("true");
+        }
         c
     }
+
+    /// Statuslijstclient volgens dit beleid
+    pub fn status_list_client(&self) -> StatusListClient {
+        self.status_list_rewrites.iter().fold(
+            StatusListClient::new(self.status_cache.clone()),
+            |client, (prefix, replacement)| client.with_rewrite(prefix.clone(), replacement.clone()),
+        )
+    }
+
+    /// DID-resolver met vastgepinde documenten uit `did_documents_dir`
+    ///
+    /// Een onleesbare map wordt gelogd; de resolver valt dan terug op
+    /// netwerkresolutie, tenzij `did_offline` aan staat.
+    pub fn did_resolver(&self) -> UniversalDidResolver {
+        let mut resolver = UniversalDidResolver::new();
+        if let Some(dir) = &self.did_documents_dir {
+            match PinnedDocuments::from_dir(dir) {
+                Ok(store) => resolver = resolver.with_store(Arc::new(store)),
+                Err(e) => tracing::warn!("Pinned DID documents not loaded: {}", e),
+            }
+        }
+        if self.did_offline {
+            resolver = resolver.offline();
+        }
+        resolver
+    }
 }
 
 /// VC authentication error types
@@ -107,6 +181,9 @@ pub enum VcError {
     #[error("Credential expired: {0}")]
     CredentialExpired(String),
 
+    #[error("Credential revoked or suspended: {0}")]
+    CredentialRevoked(String),
+
     #[error("Missing required claim: {0}")]
     MissingClaim(String),
 
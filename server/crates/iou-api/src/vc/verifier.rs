@@ -3,34 +3,52 @@
 //! Handles verification of VPs according to EBSI standards:
 //! - Signature verification using DID keys
 //! - Issuer trust validation
-//! - Expiration checking
+//! - Expiration and revocation checking
 //! - Required claim validation
 
 use crate::vc::{
     VcConfig, VcError, VerifiablePresentation, VerifiableCredential,
     WalletAuthRequest, VcUserContext, CredentialMapper,
 };
-use jsonwebtoken::{decode, decode_header, DecodingKey, Validation, Algorithm};
+use iou_core::ssi::{
+    PresentationRequirements, StatusListClient, TrustRegistry, UniversalDidResolver, VCValidationError,
+};
 use chrono::{DateTime, Utc, Duration};
+use std::sync::Arc;
 use uuid::Uuid;
 
+/// Oldest holder proof accepted from a wallet
+const MAX_PRESENTATION_AGE_MIN: i64 = 5;
+
 /// VP Verifier
 pub struct VpVerifier {
     config: VcConfig,
+    resolver: UniversalDidResolver,
+    status_lists: StatusListClient,
+    trust_registry: Option<Arc<TrustRegistry>>,
 }
 
 impl VpVerifier {
     /// Create a new VP verifier
     pub fn new(config: VcConfig) -> Self {
-        Self { config }
+        let status_lists = config.status_list_client();
+        let resolver = config.did_resolver();
+        Self { config, resolver, status_lists, trust_registry: None }
+    }
+
+    /// Check issuers against the trust registry instead of the flat
+    /// `trusted_issuers` list, once the registry has entries
+    pub fn with_trust_registry(mut self, registry: Arc<TrustRegistry>) -> Self {
+        self.trust_registry = Some(registry);
+        self
     }
 
     /// Verify a wallet authentication request
     ///
     /// # Flow
     ///
-    /// 1. Decode VP JWT
-    /// 2. Verify signature (issuer trust)
+    /// 1. Verify issuer and holder signatures
+    /// 2. Decode VP JWT
     /// 3. Check expiration
     /// 4. Extract and verify credentials
     /// 5. Map to user context
@@ -38,11 +56,11 @@ impl VpVerifier {
         &self,
         request: WalletAuthRequest,
     ) -> Result<VcUserContext, VcError> {
-        // Step 1: Decode VP JWT
-        let vp = self.decode_vp_jwt(&request.vp_token)?;
+        // Step 1: Verify issuer and holder signatures
+        self.verify_vp_signature(&request.vp_token).await?;
 
-        // Step 2: Verify holder signature
-        self.verify_vp_signature(&vp, &request.vp_token).await?;
+        // Step 2: Decode VP JWT
+        let vp = self.decode_vp_jwt(&request.vp_token)?;
 
         // Step 3: Verify all credentials in the VP
         let mut user_context = None;
@@ -86,29 +104,37 @@ impl VpVerifier {
     }
 
     /// Verify VP signature
-    async fn verify_vp_signature(
-        &self,
-        vp: &VerifiablePresentation,
-        vp_token: &str,
-    ) -> Result<(), VcError> {
-        // Extract holder DID
-        let holder = vp.holder.as_ref()
-            .ok_or_else(|| VcError::InvalidPresentation("Missing holder".to_string()))?;
-
-        // For now, we'll do basic JWT validation
-        // In production, you would:
-        // 1. Resolve the holder's DID to get the verification method
-        // 2. Fetch the public key from the DID document
-        // 3. Verify the JWT signature using that key
-
-        // Simplified: decode with default validation (checks structure and expiration)
-        let header = decode_header(vp_token)
-            .map_err(|e| VcError::SignatureVerification(format!("Invalid header: {}", e)))?;
-
-        // For demo purposes, skip signature verification
-        // In production, use the resolved public key
-        tracing::debug!("VP signature verification for holder: {}", holder);
+    ///
+    /// Resolves the issuer and holder DIDs and checks every credential
+    /// proof, the holder proof, the holder binding and the revocation
+    /// status of every credential.
+    async fn verify_vp_signature(&self, vp_token: &str) -> Result<(), VcError> {
+        let vp = iou_core::ssi::VerifiablePresentation::parse(vp_token)
+            .map_err(|e| VcError::InvalidPresentation(e.to_string()))?;
+
+        let requirements = PresentationRequirements {
+            audience: self.config.audience.clone(),
+            max_age: Some(Duration::minutes(MAX_PRESENTATION_AGE_MIN)),
+            ..Default::default()
+        };
+        vp.validate_with(&self.resolver, &requirements)
+            .await
+            .map_err(|e| VcError::SignatureVerification(e.to_string()))?;
+
+        let now = Utc::now();
+        for vc in &vp.verifiable_credential {
+            if let Some(registry) = self.registry() {
+                registry.check(vc, now).map_err(|e| VcError::UntrustedIssuer(e.to_string()))?;
+            }
+            vc.check_status(&self.status_lists, &self.resolver).await.map_err(|e| match e {
+                VCValidationError::Revoked | VCValidationError::Suspended => {
+                    VcError::CredentialRevoked(format!("{}: {}", vc.id, e))
+                }
+                other => VcError::InvalidCredential(other.to_string()),
+            })?;
+        }
 
+        tracing::debug!("VP signature verified for holder: {}", vp.holder);
         Ok(())
     }
 
@@ -146,9 +172,17 @@ impl VpVerifier {
         mapper.map_credential_to_user(vc, cred_type, &issuer)
     }
 
+    /// Trust registry, when it has entries
+    fn registry(&self) -> Option<&TrustRegistry> {
+        self.trust_registry.as_deref().filter(|registry| !registry.is_empty())
+    }
+
     /// Validate issuer is in trusted list
     fn validate_trusted_issuer(&self, issuer: &str) -> Result<(), VcError> {
-        if self.config.trusted_issuers.iter().any(|i| i == issuer) {
+        if self.registry().is_some() {
+            // Checked per credential in verify_vp_signature
+            Ok(())
+        } else if self.config.trusted_issuers.iter().any(|i| i == issuer) {
             Ok(())
         } else if !self.config.strict_mode {
             // In non-strict mode, allow unknown issuers but log a warning
@@ -180,6 +214,8 @@ mod tests {
             jwt_secret: "test-secret".to_string(),
             vc_token_expiration_min: 60,
             strict_mode: false,
+            audience: None,
+            ..VcConfig::default()
         }
     }
 
@@ -0,0 +1,446 @@
+//! Disposal runs (vernietigingslijsten) on PostgreSQL
+//!
+//! [`VernietigingRepository`] selects archive objects from `information_objects`
+//! and stores destruction lists and certificates (migration 071). Every query
+//! is scoped to one organization: objects through their information domain,
+//! lists through their `organization_id`. Lists are composed with the
+//! selectielijst and hotspot register the organization configured in
+//! `archiefinstellingen`, stored as the JSON exports read by
+//! [`iou_regels::provisa`]'s importers.
+//! [`PostgresVernietigingsUitvoerder`] performs the actual destruction: the
+//! content of every version is removed from document storage, then the
+//! analytics copy in DuckDB, and finally the rows in PostgreSQL.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use iou_core::storage::{S3Error, StorageBackend};
+use iou_regels::vernietiging::{Archiefstuk, VernietigingsItem, VernietigingError};
+use iou_regels::{
+    BesluitType, HotspotRegister, ImportFormaat, PetraCategorie, ProvincieOrgaan, ProvisaImportError,
+    ProvisaSelectielijst, ProvisaVersion, SelectielijstImport, VerklaringVanVernietiging, Vernietigingslijst,
+};
+use serde_json::Value;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::db::Database;
+
+/// Destroys archive objects in document storage, DuckDB and PostgreSQL
+pub struct PostgresVernietigingsUitvoerder {
+    pool: PgPool,
+    storage: Arc<dyn StorageBackend>,
+    analytics: Arc<Database>,
+}
+
+impl PostgresVernietigingsUitvoerder {
+    pub fn new(pool: PgPool, storage: Arc<dyn StorageBackend>, analytics: Arc<Database>) -> Self {
+        Self { pool, storage, analytics }
+    }
+
+    /// The object and all its earlier versions, with their storage keys
+    async fn versies(&self, object_id: Uuid) -> Result<Vec<(Uuid, Option<String>)>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            WITH RECURSIVE versies AS (
+                SELECT id, content_location, previous_version_id
+                FROM information_objects WHERE id = $1
+                UNION
+                SELECT io.id, io.content_location, io.previous_version_id
+                FROM information_objects io
+                JOIN versies v ON io.id = v.previous_version_id
+            )
+            SELECT id, content_location FROM versies
+            "#,
+        )
+        .bind(object_id)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter()
+            .map(|row| Ok((row.try_get("id")?, row.try_get("content_location")?)))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl iou_regels::VernietigingsUitvoerder for PostgresVernietigingsUitvoerder {
+    /// Every step must succeed before the next one starts, and the rows in
+    /// PostgreSQL go last: a failed item keeps its rows and can be retried.
+    /// Storage keys and rows that are already gone are not an error.
+    async fn vernietig(&self, item: &VernietigingsItem) -> Result<(), VernietigingError> {
+        let fout = |e: &dyn std::fmt::Display| VernietigingError::Uitvoering(format!("{}: {}", item.object_id, e));
+
+        let versies = self.versies(item.object_id).await.map_err(|e| fout(&e))?;
+        let mut keys: Vec<&str> = versies.iter().filter_map(|(_, key)| key.as_deref()).collect();
+        keys.extend(item.opslag_locatie.as_deref());
+        keys.sort_unstable();
+        keys.dedup();
+
+        // Older versions may be stored compressed, as keyframe or reverse
+        // delta, next to the full copy
+        for key in keys {
+            for key in [key.to_string(), format!("{}.gz", key)] {
+                match self.storage.delete(&key).await {
+                    Ok(()) | Err(S3Error::NotFound(_)) => {}
+                    Err(e) => return Err(fout(&format!("{}: {}", key, e))),
+                }
+            }
+        }
+
+        let ids: Vec<Uuid> = versies.iter().map(|(id, _)| *id).chain([item.object_id]).collect();
+        self.analytics
+            .delete_objects_async(ids.clone())
+            .await
+            .map_err(|e| fout(&format!("analytics copy: {}", e)))?;
+
+        // Tags and categories cascade on delete
+        sqlx::query("DELETE FROM information_objects WHERE id = ANY($1)")
+            .bind(&ids)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| fout(&e))?;
+
+        Ok(())
+    }
+}
+
+/// Configuration document in `archiefinstellingen`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Archiefinstelling {
+    Selectielijst,
+    Hotspotregister,
+}
+
+impl Archiefinstelling {
+    /// Column name, also used in the API path
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Archiefinstelling::Selectielijst => "selectielijst",
+            Archiefinstelling::Hotspotregister => "hotspotregister",
+        }
+    }
+}
+
+/// Selectielijst from its JSON export
+///
+/// Rows without `versie` or `orgaan` count as Provisa 2020 for the
+/// provincial organs; an export without provisions is rejected.
+pub fn lees_selectielijst(export: &Value) -> Result<ProvisaSelectielijst, ProvisaImportError> {
+    let import = SelectielijstImport::lees(
+        export.to_string().as_bytes(),
+        ImportFormaat::Json,
+        ProvisaVersion::V2020,
+        ProvincieOrgaan::ProvincialeOrganen,
+    )?;
+    let lijst = import.selectielijst();
+    if lijst.bepalingen.is_empty() {
+        return Err(ProvisaImportError::GeenBepalingen {
+            versie: import.versie,
+            orgaan: import.orgaan,
+        });
+    }
+    Ok(lijst)
+}
+
+/// Hotspot register from its JSON export
+pub fn lees_hotspotregister(export: &Value) -> Result<HotspotRegister, ProvisaImportError> {
+    HotspotRegister::lees(export.to_string().as_bytes(), ImportFormaat::Json, "")
+}
+
+pub struct VernietigingRepository {
+    pool: PgPool,
+}
+
+impl VernietigingRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Archive objects of an organization classified with a PETRA category
+    /// and decision type
+    ///
+    /// Objects without (valid) classification cannot be assessed and are
+    /// left out; they show up in the compliance overview instead.
+    pub async fn archiefstukken(&self, organization_id: Uuid) -> Result<Vec<Archiefstuk>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT io.id, io.title, io.content_location, io.created_at,
+                   io.metadata->>'petra_categorie' AS petra_categorie,
+                   io.metadata->>'besluit_type' AS besluit_type,
+                   io.metadata->>'bewaarplicht' AS bewaarplicht,
+                   (io.metadata->>'afgesloten_op')::date AS afgesloten_op
+            FROM information_objects io
+            JOIN information_domains d ON d.id = io.domain_id
+            WHERE d.organization_id = $1
+              AND io.metadata ? 'petra_categorie' AND io.metadata ? 'besluit_type'
+            "#,
+        )
+        .bind(organization_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut stukken = Vec::with_capacity(rows.len());
+        for row in rows {
+            let id: Uuid = row.try_get("id")?;
+            let categorie: String = row.try_get("petra_categorie")?;
+            let besluit_type: String = row.try_get("besluit_type")?;
+            let (Ok(categorie), Ok(besluit_type)) = (
+                PetraCategorie::from_str(&categorie),
+                BesluitType::from_str(&besluit_type),
+            ) else {
+                tracing::warn!("Object {} has an unknown PETRA classification, skipped", id);
+                continue;
+            };
+
+            // The retention period starts when the file is closed, falling
+            // back to the creation date
+            let created_at: chrono::DateTime<chrono::Utc> = row.try_get("created_at")?;
+            let afgesloten_op: Option<NaiveDate> = row.try_get("afgesloten_op")?;
+
+            let mut stuk = Archiefstuk::new(
+                id,
+                row.try_get::<String, _>("title")?,
+                categorie,
+                besluit_type,
+                afgesloten_op.unwrap_or_else(|| created_at.date_naive()),
+            );
+            stuk.opslag_locatie = row.try_get("content_location")?;
+            stuk.bewaarplicht = row.try_get("bewaarplicht")?;
+            stukken.push(stuk);
+        }
+        Ok(stukken)
+    }
+
+    /// Selectielijst configured for an organization
+    pub async fn selectielijst(&self, organization_id: Uuid) -> Result<Option<ProvisaSelectielijst>> {
+        match self.archiefinstelling(organization_id, Archiefinstelling::Selectielijst).await? {
+            Some(export) => Ok(Some(lees_selectielijst(&export)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Hotspot register configured for an organization
+    pub async fn hotspotregister(&self, organization_id: Uuid) -> Result<Option<HotspotRegister>> {
+        match self.archiefinstelling(organization_id, Archiefinstelling::Hotspotregister).await? {
+            Some(export) => Ok(Some(lees_hotspotregister(&export)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Stored export of the selectielijst or hotspot register
+    pub async fn archiefinstelling(&self, organization_id: Uuid, kolom: Archiefinstelling) -> Result<Option<Value>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} AS export FROM archiefinstellingen WHERE organization_id = $1",
+            kolom.as_str()
+        ))
+        .bind(organization_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        match row {
+            Some(row) => Ok(row.try_get("export")?),
+            None => Ok(None),
+        }
+    }
+
+    /// Replace the selectielijst or hotspot register of an organization
+    ///
+    /// The export must have been validated with [`lees_selectielijst`] or
+    /// [`lees_hotspotregister`].
+    pub async fn save_archiefinstelling(
+        &self,
+        organization_id: Uuid,
+        kolom: Archiefinstelling,
+        export: &Value,
+        bijgewerkt_door: Uuid,
+    ) -> Result<()> {
+        sqlx::query(&format!(
+            r#"
+            INSERT INTO archiefinstellingen (organization_id, {kolom}, bijgewerkt_door, updated_at)
+            VALUES ($1, $2, $3, NOW())
+            ON CONFLICT (organization_id) DO UPDATE SET
+                {kolom} = EXCLUDED.{kolom},
+                bijgewerkt_door = EXCLUDED.bijgewerkt_door,
+                updated_at = NOW()
+            "#,
+            kolom = kolom.as_str()
+        ))
+        .bind(organization_id)
+        .bind(export)
+        .bind(bijgewerkt_door)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Insert or update a list of `organization_id`
+    ///
+    /// A list of another organization with the same id is left untouched.
+    pub async fn save(&self, organization_id: Uuid, lijst: &Vernietigingslijst) -> Result<()> {
+        let status = serde_json::to_value(lijst.status)?;
+        let result = sqlx::query(
+            r#"
+            INSERT INTO vernietigingslijsten
+                (id, organization_id, status, peildatum, selectielijst, lijst, aangemaakt_door,
+                 aangemaakt_op, uitgevoerd_op, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, NOW())
+            ON CONFLICT (id) DO UPDATE SET
+                status = EXCLUDED.status,
+                lijst = EXCLUDED.lijst,
+                uitgevoerd_op = EXCLUDED.uitgevoerd_op,
+                updated_at = NOW()
+            WHERE vernietigingslijsten.organization_id = EXCLUDED.organization_id
+            "#,
+        )
+        .bind(lijst.id)
+        .bind(organization_id)
+        .bind(status.as_str().unwrap_or_default())
+        .bind(lijst.peildatum)
+        .bind(&lijst.selectielijst)
+        .bind(serde_json::to_value(lijst)?)
+        .bind(lijst.aangemaakt_door)
+        .bind(lijst.aangemaakt_op)
+        .bind(lijst.uitgevoerd_op)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            anyhow::bail!("Vernietigingslijst {} belongs to another organization", lijst.id);
+        }
+        Ok(())
+    }
+
+    /// Claim an approved or partly executed list of `organization_id` for execution
+    ///
+    /// The row is locked while its status moves to `in_uitvoering`, so
+    /// concurrent requests cannot both execute the list. Returns `None` if
+    /// there is no such list or it cannot be executed now.
+    pub async fn claim(&self, organization_id: Uuid, id: Uuid) -> Result<Option<Vernietigingslijst>> {
+        let mut tx = self.pool.begin().await?;
+        let row = sqlx::query(
+            r#"
+            SELECT lijst FROM vernietigingslijsten
+            WHERE id = $1 AND organization_id = $2 AND status IN ('goedgekeurd', 'deels_uitgevoerd')
+            FOR UPDATE
+            "#,
+        )
+        .bind(id)
+        .bind(organization_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let mut lijst: Vernietigingslijst = serde_json::from_value(row.try_get("lijst")?)?;
+        lijst.neem_in_uitvoering()?;
+        let status = serde_json::to_value(lijst.status)?;
+        sqlx::query(
+            "UPDATE vernietigingslijsten SET status = $2, lijst = $3, updated_at = NOW() WHERE id = $1",
+        )
+        .bind(id)
+        .bind(status.as_str().unwrap_or_default())
+        .bind(serde_json::to_value(&lijst)?)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(Some(lijst))
+    }
+
+    pub async fn get(&self, organization_id: Uuid, id: Uuid) -> Result<Option<Vernietigingslijst>> {
+        let row = sqlx::query("SELECT lijst FROM vernietigingslijsten WHERE id = $1 AND organization_id = $2")
+            .bind(id)
+            .bind(organization_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        match row {
+            Some(row) => Ok(Some(serde_json::from_value(row.try_get("lijst")?)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// All lists of an organization, newest first
+    pub async fn list(&self, organization_id: Uuid) -> Result<Vec<Vernietigingslijst>> {
+        let rows = sqlx::query(
+            "SELECT lijst FROM vernietigingslijsten WHERE organization_id = $1 ORDER BY aangemaakt_op DESC",
+        )
+        .bind(organization_id)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter()
+            .map(|row| Ok(serde_json::from_value(row.try_get("lijst")?)?))
+            .collect()
+    }
+
+    pub async fn save_verklaring(&self, verklaring: &VerklaringVanVernietiging) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO verklaringen_van_vernietiging
+                (id, vernietigingslijst_id, verklaring, handtekening, uitgevoerd_door, uitgevoerd_op)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(verklaring.id)
+        .bind(verklaring.vernietigingslijst_id)
+        .bind(serde_json::to_value(verklaring)?)
+        .bind(verklaring.handtekening.as_deref().unwrap_or_default())
+        .bind(&verklaring.uitgevoerd_door)
+        .bind(verklaring.uitgevoerd_op)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// The certificate of the latest execution of a list
+    pub async fn get_verklaring(
+        &self,
+        organization_id: Uuid,
+        lijst_id: Uuid,
+    ) -> Result<Option<VerklaringVanVernietiging>> {
+        let row = sqlx::query(
+            r#"
+            SELECT v.verklaring
+            FROM verklaringen_van_vernietiging v
+            JOIN vernietigingslijsten l ON l.id = v.vernietigingslijst_id
+            WHERE v.vernietigingslijst_id = $1 AND l.organization_id = $2
+            ORDER BY v.uitgevoerd_op DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(lijst_id)
+        .bind(organization_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        match row {
+            Some(row) => Ok(Some(serde_json::from_value(row.try_get("verklaring")?)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lees_archiefinstellingen() {
+        let selectielijst = serde_json::json!({
+            "naam": "Selectielijst provinciale organen 2020",
+            "bepalingen": [
+                { "categorie": "Milieu", "besluittype": "Vergunning", "waardering": "V", "termijn": 15, "referentie": "m-1" }
+            ]
+        });
+        let lijst = lees_selectielijst(&selectielijst).unwrap();
+        assert_eq!(lijst.versie, ProvisaVersion::V2020);
+        assert!(lijst.zoek_bewaartermijn(&PetraCategorie::Milieu, &BesluitType::Vergunning).is_some());
+        assert!(matches!(
+            lees_selectielijst(&serde_json::json!({ "bepalingen": [] })),
+            Err(ProvisaImportError::GeenBepalingen { .. })
+        ));
+
+        let register = lees_hotspotregister(&serde_json::json!({ "provincie": "Flevoland", "hotspots": [] })).unwrap();
+        assert!(register.hotspots.is_empty());
+    }
+}
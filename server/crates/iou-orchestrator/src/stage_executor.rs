@@ -135,6 +135,7 @@ impl StageExecutor {
             sla: iou_core::config::workflow::SlaConfig {
                 weekend_days: vec!["Saturday".to_string(), "Sunday".to_string()],
                 escalation_hours: vec![24, 8, 1],
+                calendar: Default::default(),
             },
         };
 